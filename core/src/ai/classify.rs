@@ -0,0 +1,307 @@
+//! Classify hunks with a Claude model against the trust taxonomy, and compare
+//! how different models label the same hunks — so a team can pick the
+//! cheapest model that labels their codebase well enough.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::redact::Redactor;
+use crate::ai::{ensure_claude_available, run_claude_streaming, ClaudeError};
+use crate::classify::{ClassificationResult, ClassifyResponse};
+use crate::diff::parser::DiffHunk;
+use crate::trust::patterns::get_trust_taxonomy;
+
+/// Build the classification prompt: the taxonomy's labels, then each hunk's
+/// ID and diff.
+fn build_prompt(hunks: &[DiffHunk]) -> String {
+    let mut prompt = String::from(
+        "Classify each diff hunk below with the labels from this taxonomy that \
+         fully describe it. A hunk gets no labels unless every changed line fits \
+         them.\n\nLabels:\n",
+    );
+    for pattern in get_trust_taxonomy().into_iter().flat_map(|c| c.patterns) {
+        prompt.push_str("- ");
+        prompt.push_str(&pattern.id);
+        prompt.push_str(": ");
+        prompt.push_str(&pattern.description);
+        prompt.push('\n');
+    }
+    prompt.push_str("\nHunks:\n");
+    for hunk in hunks {
+        prompt.push_str("\n--- hunk ");
+        prompt.push_str(&hunk.id);
+        prompt.push_str(" ---\n");
+        prompt.push_str(&hunk.content);
+        prompt.push('\n');
+    }
+    prompt.push_str(
+        "\nRespond with ONLY a JSON object mapping each hunk ID to \
+         {\"label\": [labels], \"reasoning\": \"one sentence\"}, with no \
+         markdown formatting or commentary.",
+    );
+    prompt
+}
+
+#[derive(Deserialize)]
+struct ModelLabels {
+    #[serde(default)]
+    label: Vec<String>,
+    #[serde(default)]
+    reasoning: String,
+}
+
+/// Parse a model's response into classifications, keeping only the given
+/// hunks and labels from the taxonomy. Tolerates a markdown fence or prose
+/// around the JSON object.
+pub fn parse_classifications(
+    output: &str,
+    hunks: &[DiffHunk],
+) -> Result<HashMap<String, ClassificationResult>, ClaudeError> {
+    let (Some(start), Some(end)) = (output.find('{'), output.rfind('}')) else {
+        return Err(ClaudeError::ParseError(
+            "no JSON object in response".to_owned(),
+        ));
+    };
+    let parsed: HashMap<String, ModelLabels> = serde_json::from_str(&output[start..=end])
+        .map_err(|e| ClaudeError::ParseError(e.to_string()))?;
+
+    let known: BTreeSet<String> = get_trust_taxonomy()
+        .into_iter()
+        .flat_map(|c| c.patterns.into_iter().map(|p| p.id))
+        .collect();
+    Ok(parsed
+        .into_iter()
+        .filter(|(id, _)| hunks.iter().any(|h| &h.id == id))
+        .map(|(id, labels)| {
+            let result = ClassificationResult {
+                label: labels
+                    .label
+                    .into_iter()
+                    .filter(|l| known.contains(l))
+                    .collect(),
+                reasoning: labels.reasoning,
+                evidence: Vec::new(),
+            };
+            (id, result)
+        })
+        .collect())
+}
+
+/// Classify `hunks` with `model` in one request. Hunks in files the repo
+/// excludes from AI prompts are left unclassified.
+pub fn classify_hunks_with_model(
+    hunks: &[DiffHunk],
+    model: &str,
+    cwd: &Path,
+) -> Result<ClassifyResponse, ClaudeError> {
+    ensure_claude_available()?;
+    let mut redactor = Redactor::load(cwd)?;
+    let shown: Vec<DiffHunk> = hunks
+        .iter()
+        .filter(|hunk| !redactor.exclude(&hunk.file_path))
+        .cloned()
+        .collect();
+    let classifications = if shown.is_empty() {
+        redactor.finish("classify")?;
+        HashMap::new()
+    } else {
+        let prompt = redactor.redact(&build_prompt(&shown))?;
+        redactor.finish("classify")?;
+        info!(
+            "[classify_hunks_with_model] {model}: {} hunks, prompt {} bytes",
+            shown.len(),
+            prompt.len()
+        );
+        let output = run_claude_streaming(&prompt, cwd, model, &["none"], &mut |_| {}, None)?;
+        parse_classifications(&output, &shown)?
+    };
+    Ok(ClassifyResponse {
+        classifications,
+        limits_exceeded: Vec::new(),
+        raw_labels: HashMap::new(),
+    })
+}
+
+/// How the compared models used one label.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelAgreement {
+    pub label: String,
+    /// Hunks every model gave this label.
+    pub agreed: usize,
+    /// Hunks some models gave this label and others didn't.
+    pub disagreed: usize,
+    /// Hunks each model gave this label, by model.
+    pub by_model: BTreeMap<String, usize>,
+}
+
+/// Agreement between models that classified the same hunks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelComparison {
+    pub models: Vec<String>,
+    /// Hunks compared.
+    pub hunks: usize,
+    /// Hunks every model labeled identically (including all leaving it
+    /// unlabeled).
+    pub identical: usize,
+    /// Per-label agreement, most disagreed first.
+    pub labels: Vec<LabelAgreement>,
+}
+
+/// Compare each model's classification of `hunk_ids`.
+pub fn compare_models(
+    results: &[(String, ClassifyResponse)],
+    hunk_ids: &[String],
+) -> ModelComparison {
+    let labels_of = |response: &ClassifyResponse, id: &str| -> BTreeSet<String> {
+        response
+            .classifications
+            .get(id)
+            .map(|c| c.label.iter().cloned().collect())
+            .unwrap_or_default()
+    };
+
+    let mut identical = 0;
+    let mut agreement: BTreeMap<String, LabelAgreement> = BTreeMap::new();
+    for id in hunk_ids {
+        let per_model: Vec<(&str, BTreeSet<String>)> = results
+            .iter()
+            .map(|(model, response)| (model.as_str(), labels_of(response, id)))
+            .collect();
+        if per_model.windows(2).all(|pair| pair[0].1 == pair[1].1) {
+            identical += 1;
+        }
+        let union: BTreeSet<&String> = per_model.iter().flat_map(|(_, l)| l).collect();
+        for label in union {
+            let entry = agreement
+                .entry(label.clone())
+                .or_insert_with(|| LabelAgreement {
+                    label: label.clone(),
+                    agreed: 0,
+                    disagreed: 0,
+                    by_model: results.iter().map(|(m, _)| (m.clone(), 0)).collect(),
+                });
+            let assigned: Vec<&str> = per_model
+                .iter()
+                .filter(|(_, l)| l.contains(label))
+                .map(|(m, _)| *m)
+                .collect();
+            if assigned.len() == per_model.len() {
+                entry.agreed += 1;
+            } else {
+                entry.disagreed += 1;
+            }
+            for model in assigned {
+                *entry.by_model.entry(model.to_owned()).or_default() += 1;
+            }
+        }
+    }
+
+    let mut labels: Vec<LabelAgreement> = agreement.into_values().collect();
+    labels.sort_by(|a, b| b.disagreed.cmp(&a.disagreed).then(b.agreed.cmp(&a.agreed)));
+    ModelComparison {
+        models: results.iter().map(|(m, _)| m.clone()).collect(),
+        hunks: hunk_ids.len(),
+        identical,
+        labels,
+    }
+}
+
+/// Classify `hunks` with each model in turn and compare the results.
+pub fn compare_classification_models(
+    hunks: &[DiffHunk],
+    models: &[String],
+    cwd: &Path,
+) -> Result<ModelComparison, ClaudeError> {
+    let mut results = Vec::new();
+    for model in models {
+        results.push((model.clone(), classify_hunks_with_model(hunks, model, cwd)?));
+    }
+    let hunk_ids: Vec<String> = hunks.iter().map(|h| h.id.clone()).collect();
+    Ok(compare_models(&results, &hunk_ids))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::create_untracked_hunk;
+
+    fn response(labels: &[(&str, &[&str])]) -> ClassifyResponse {
+        ClassifyResponse {
+            classifications: labels
+                .iter()
+                .map(|(id, labels)| {
+                    let result = ClassificationResult {
+                        label: labels.iter().map(|l| (*l).to_owned()).collect(),
+                        reasoning: String::new(),
+                        evidence: Vec::new(),
+                    };
+                    ((*id).to_owned(), result)
+                })
+                .collect(),
+            limits_exceeded: Vec::new(),
+            raw_labels: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn parses_fenced_response_and_drops_unknown_labels() {
+        let hunks = vec![create_untracked_hunk(
+            "src/a.rs",
+            "abc",
+            Some("use std::io;"),
+        )];
+        let id = &hunks[0].id;
+        let output = format!(
+            "```json\n{{\"{id}\": {{\"label\": [\"imports:added\", \"made:up\"], \
+             \"reasoning\": \"Adds an import\"}}, \"other:hunk\": {{\"label\": []}}}}\n```"
+        );
+        let parsed = parse_classifications(&output, &hunks).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[id].label, ["imports:added"]);
+        assert_eq!(parsed[id].reasoning, "Adds an import");
+
+        assert!(parse_classifications("I can't help with that", &hunks).is_err());
+    }
+
+    #[test]
+    fn compares_labels_across_models() {
+        let results = vec![
+            (
+                "sonnet".to_owned(),
+                response(&[("a", &["imports:added"]), ("b", &["comments:added"])]),
+            ),
+            (
+                "haiku".to_owned(),
+                response(&[("a", &["imports:added"]), ("b", &["formatting:style"])]),
+            ),
+        ];
+        let ids = ["a", "b", "c"].map(str::to_owned);
+        let comparison = compare_models(&results, &ids);
+
+        assert_eq!(comparison.models, ["sonnet", "haiku"]);
+        assert_eq!(comparison.hunks, 3);
+        // "a" matches, and "c" is unlabeled by both.
+        assert_eq!(comparison.identical, 2);
+        let rows: Vec<(&str, usize, usize)> = comparison
+            .labels
+            .iter()
+            .map(|l| (l.label.as_str(), l.agreed, l.disagreed))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("comments:added", 0, 1),
+                ("formatting:style", 0, 1),
+                ("imports:added", 1, 0),
+            ]
+        );
+        let comments = &comparison.labels[0];
+        assert_eq!(comments.by_model["sonnet"], 1);
+        assert_eq!(comments.by_model["haiku"], 0);
+    }
+}
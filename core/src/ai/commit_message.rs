@@ -1,3 +1,4 @@
+use crate::ai::redact::Redactor;
 use crate::ai::{ensure_claude_available, run_claude_streaming, ClaudeError};
 use log::info;
 use std::path::Path;
@@ -16,6 +17,7 @@ pub fn generate_commit_message_streaming(
     on_text: &mut dyn FnMut(&str),
 ) -> Result<String, ClaudeError> {
     ensure_claude_available()?;
+    let mut redactor = Redactor::load(cwd)?;
 
     let mut prompt = String::new();
 
@@ -29,7 +31,7 @@ pub fn generate_commit_message_streaming(
     }
 
     prompt.push_str("Here is the staged diff:\n\n");
-    prompt.push_str(staged_diff);
+    prompt.push_str(&redactor.exclude_from_diff(staged_diff));
     prompt.push_str("\n\n");
     prompt.push_str(
         "Write a commit message for this diff. \
@@ -40,6 +42,9 @@ pub fn generate_commit_message_streaming(
          no markdown formatting, and no surrounding quotes.",
     );
 
+    let prompt = redactor.redact(&prompt)?;
+    redactor.finish("commit-message")?;
+
     info!(
         "[generate_commit_message] prompt length: {} bytes",
         prompt.len()
@@ -0,0 +1,319 @@
+//! Drill into one hunk's labels: re-evaluate them with the full symbols
+//! around the hunk in view, and justify the verdict at length, citing the
+//! lines it rests on — for when a label's one-sentence stored reasoning
+//! doesn't convince.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::redact::Redactor;
+use crate::ai::{ensure_claude_available, run_claude_streaming, ClaudeError};
+use crate::diff::parser::{DiffHunk, LineType};
+use crate::trust::patterns::get_trust_taxonomy;
+
+/// Which version of the file a line is from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Old,
+    New,
+}
+
+impl Side {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Side::Old => "old",
+            Side::New => "new",
+        }
+    }
+}
+
+/// Source around a hunk on one side: its enclosing symbol, or a window of
+/// lines when no symbol encloses it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContextSnippet {
+    pub side: Side,
+    /// The enclosing symbol, e.g. `fn parse`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    /// 1-based line number of `lines[0]`.
+    pub start_line: u32,
+    pub lines: Vec<String>,
+}
+
+impl ContextSnippet {
+    fn line(&self, side: Side, number: u32) -> Option<&str> {
+        if side != self.side || number < self.start_line {
+            return None;
+        }
+        self.lines
+            .get((number - self.start_line) as usize)
+            .map(String::as_str)
+    }
+}
+
+/// A line the explanation rests on.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    pub side: Side,
+    pub line: u32,
+    pub text: String,
+    /// What the line shows.
+    pub note: String,
+}
+
+/// A re-evaluated label with its justification.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelExplanation {
+    pub hunk_id: String,
+    /// The labels being questioned.
+    pub labels: Vec<String>,
+    /// The labels the model assigns with the full context in view.
+    pub suggested_labels: Vec<String>,
+    /// Whether `suggested_labels` match `labels`.
+    pub agrees: bool,
+    pub justification: String,
+    pub citations: Vec<Citation>,
+}
+
+fn push_numbered(prompt: &mut String, side: Side, start: u32, lines: &[String]) {
+    for (number, line) in (start..).zip(lines) {
+        let _ = writeln!(prompt, "{} {number}: {line}", side.as_str());
+    }
+}
+
+/// Build the prompt: the taxonomy, the hunk, its labels and stored
+/// reasoning, then the surrounding symbols with every line numbered so the
+/// model can cite them.
+fn build_prompt(
+    hunk: &DiffHunk,
+    labels: &[String],
+    reasoning: Option<&str>,
+    context: &[ContextSnippet],
+) -> String {
+    let mut prompt = String::from(
+        "A code review tool labeled the diff hunk below using this taxonomy. A \
+         hunk gets a label only if every changed line fits it.\n\nLabels:\n",
+    );
+    for pattern in get_trust_taxonomy().into_iter().flat_map(|c| c.patterns) {
+        prompt.push_str("- ");
+        prompt.push_str(&pattern.id);
+        prompt.push_str(": ");
+        prompt.push_str(&pattern.description);
+        prompt.push('\n');
+    }
+
+    prompt.push_str("\nHunk ");
+    prompt.push_str(&hunk.id);
+    prompt.push_str(":\n");
+    for line in &hunk.lines {
+        let (side, number) = match (line.new_line_number, line.old_line_number) {
+            (Some(n), _) => (Side::New, n),
+            (None, Some(n)) => (Side::Old, n),
+            (None, None) => continue,
+        };
+        let marker = match line.line_type {
+            LineType::Added => '+',
+            LineType::Removed => '-',
+            LineType::Context => ' ',
+        };
+        let _ = writeln!(
+            prompt,
+            "{marker}{} {number}: {}",
+            side.as_str(),
+            line.content
+        );
+    }
+
+    prompt.push_str("\nAssigned labels: ");
+    if labels.is_empty() {
+        prompt.push_str("(none)");
+    } else {
+        prompt.push_str(&labels.join(", "));
+    }
+    prompt.push_str("\nStored reasoning: ");
+    prompt.push_str(reasoning.unwrap_or("(none)"));
+    prompt.push('\n');
+
+    for snippet in context {
+        prompt.push_str("\nSurrounding ");
+        prompt.push_str(snippet.side.as_str());
+        prompt.push_str(" code");
+        if let Some(symbol) = &snippet.symbol {
+            prompt.push_str(" (");
+            prompt.push_str(symbol);
+            prompt.push(')');
+        }
+        prompt.push_str(":\n");
+        push_numbered(
+            &mut prompt,
+            snippet.side,
+            snippet.start_line,
+            &snippet.lines,
+        );
+    }
+
+    prompt.push_str(
+        "\nRe-evaluate the labels with the surrounding code in view. Respond \
+         with ONLY a JSON object, with no markdown formatting or commentary: \
+         {\"label\": [the labels the hunk should have], \"justification\": \
+         \"a paragraph explaining why, referring to specific lines\", \
+         \"citations\": [{\"side\": \"old\" or \"new\", \"line\": number, \
+         \"note\": \"what this line shows\"}]}. Cite only numbered lines shown \
+         above.",
+    );
+    prompt
+}
+
+#[derive(Deserialize)]
+struct ModelCitation {
+    side: Side,
+    line: u32,
+    #[serde(default)]
+    note: String,
+}
+
+#[derive(Deserialize)]
+struct ModelExplanation {
+    #[serde(default)]
+    label: Vec<String>,
+    #[serde(default)]
+    justification: String,
+    #[serde(default)]
+    citations: Vec<ModelCitation>,
+}
+
+/// Parse the model's response. Labels outside the taxonomy are dropped, as
+/// are citations of lines that weren't shown; cited lines get their text
+/// from the hunk or the context.
+pub fn parse_explanation(
+    output: &str,
+    hunk: &DiffHunk,
+    labels: &[String],
+    context: &[ContextSnippet],
+) -> Result<LabelExplanation, ClaudeError> {
+    let (Some(start), Some(end)) = (output.find('{'), output.rfind('}')) else {
+        return Err(ClaudeError::ParseError(
+            "no JSON object in response".to_owned(),
+        ));
+    };
+    let parsed: ModelExplanation = serde_json::from_str(&output[start..=end])
+        .map_err(|e| ClaudeError::ParseError(e.to_string()))?;
+    if parsed.justification.trim().is_empty() {
+        return Err(ClaudeError::EmptyResponse);
+    }
+
+    let known: BTreeSet<String> = get_trust_taxonomy()
+        .into_iter()
+        .flat_map(|c| c.patterns.into_iter().map(|p| p.id))
+        .collect();
+    let suggested_labels: Vec<String> = parsed
+        .label
+        .into_iter()
+        .filter(|l| known.contains(l))
+        .collect();
+    let line_text = |side: Side, number: u32| {
+        hunk.lines
+            .iter()
+            .find(|line| match side {
+                Side::Old => line.old_line_number == Some(number),
+                Side::New => line.new_line_number == Some(number),
+            })
+            .map(|line| line.content.as_str())
+            .or_else(|| context.iter().find_map(|s| s.line(side, number)))
+    };
+    let citations = parsed
+        .citations
+        .into_iter()
+        .filter_map(|c| {
+            Some(Citation {
+                text: line_text(c.side, c.line)?.to_owned(),
+                side: c.side,
+                line: c.line,
+                note: c.note,
+            })
+        })
+        .collect();
+
+    let as_set = |labels: &[String]| labels.iter().cloned().collect::<BTreeSet<_>>();
+    Ok(LabelExplanation {
+        hunk_id: hunk.id.clone(),
+        labels: labels.to_vec(),
+        agrees: as_set(labels) == as_set(&suggested_labels),
+        suggested_labels,
+        justification: parsed.justification.trim().to_owned(),
+        citations,
+    })
+}
+
+/// Re-evaluate `hunk`'s `labels` with `model`, given the code around it.
+pub fn explain_label_with_model(
+    hunk: &DiffHunk,
+    labels: &[String],
+    reasoning: Option<&str>,
+    context: &[ContextSnippet],
+    model: &str,
+    cwd: &Path,
+) -> Result<LabelExplanation, ClaudeError> {
+    ensure_claude_available()?;
+    let mut redactor = Redactor::load(cwd)?;
+    if redactor.exclude(&hunk.file_path) {
+        redactor.finish("explain")?;
+        return Err(ClaudeError::Excluded(hunk.file_path.clone()));
+    }
+    let prompt = redactor.redact(&build_prompt(hunk, labels, reasoning, context))?;
+    redactor.finish("explain")?;
+    info!(
+        "[explain_label_with_model] {model}: hunk {}, {} context snippet(s), prompt {} bytes",
+        hunk.id,
+        context.len(),
+        prompt.len()
+    );
+    let output = run_claude_streaming(&prompt, cwd, model, &["none"], &mut |_| {}, None)?;
+    parse_explanation(&output, hunk, labels, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::create_untracked_hunk;
+
+    #[test]
+    fn parses_explanation_and_keeps_only_shown_lines() {
+        let hunk = create_untracked_hunk("src/a.rs", "abc", Some("use std::io;"));
+        let context = [ContextSnippet {
+            side: Side::New,
+            symbol: Some("mod a".to_owned()),
+            start_line: 1,
+            lines: vec!["use std::io;".to_owned(), "fn read() {}".to_owned()],
+        }];
+        let labels = vec!["imports:added".to_owned()];
+        let output = "```json\n{\"label\": [\"imports:added\", \"made:up\"], \
+             \"justification\": \"Only an import is added.\", \"citations\": [\
+             {\"side\": \"new\", \"line\": 1, \"note\": \"the import\"}, \
+             {\"side\": \"new\", \"line\": 2, \"note\": \"unchanged\"}, \
+             {\"side\": \"old\", \"line\": 9, \"note\": \"invented\"}]}\n```";
+
+        let explanation = parse_explanation(output, &hunk, &labels, &context).unwrap();
+        assert!(explanation.agrees);
+        assert_eq!(explanation.suggested_labels, ["imports:added"]);
+        let cited: Vec<(u32, &str)> = explanation
+            .citations
+            .iter()
+            .map(|c| (c.line, c.text.as_str()))
+            .collect();
+        assert_eq!(cited, [(1, "use std::io;"), (2, "fn read() {}")]);
+
+        let disagree = "{\"label\": [], \"justification\": \"It also adds code.\"}";
+        let explanation = parse_explanation(disagree, &hunk, &labels, &context).unwrap();
+        assert!(!explanation.agrees);
+        assert!(parse_explanation("{\"label\": []}", &hunk, &labels, &context).is_err());
+    }
+}
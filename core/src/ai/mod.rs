@@ -1,4 +1,8 @@
+pub mod classify;
 pub mod commit_message;
+pub mod explain;
+pub mod redact;
+pub mod split;
 
 use log::warn;
 use std::io::{BufRead, BufReader, Write};
@@ -20,17 +24,25 @@ pub enum ClaudeError {
     EmptyResponse,
     #[error("Cancelled")]
     Cancelled,
+    #[error("Redaction failed: {0}")]
+    Redaction(String),
+    #[error("{0} is excluded from AI prompts by .review.toml")]
+    Excluded(String),
+    #[error(transparent)]
+    Offline(#[from] crate::offline::OfflineError),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
 
-/// Check if the claude CLI is available
+/// Check if the claude CLI is available (never while offline)
 pub fn check_claude_available() -> bool {
-    find_claude_executable().is_some()
+    !crate::offline::is_offline() && find_claude_executable().is_some()
 }
 
-/// Verify Claude CLI is available, returning `ClaudeNotFound` if not.
+/// Verify Claude CLI is available, returning `Offline` in offline mode and
+/// `ClaudeNotFound` if it isn't installed.
 pub(crate) fn ensure_claude_available() -> Result<(), ClaudeError> {
+    crate::offline::ensure_online("Claude")?;
     find_claude_executable().ok_or(ClaudeError::ClaudeNotFound)?;
     Ok(())
 }
@@ -83,6 +95,7 @@ pub fn find_claude_executable() -> Option<String> {
 
 /// Build a base `Command` for the Claude CLI with common flags applied.
 fn build_claude_command(model: &str, allowed_tools: &[&str]) -> Result<Command, ClaudeError> {
+    crate::offline::ensure_online("Claude")?;
     let claude_path = find_claude_executable().ok_or(ClaudeError::ClaudeNotFound)?;
     let mut cmd = Command::new(claude_path);
     cmd.args([
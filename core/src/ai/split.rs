@@ -0,0 +1,175 @@
+//! Propose how to split a long commit range into several logical reviews,
+//! with a Claude model reading the commits' subjects and changed files.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::redact::Redactor;
+use crate::ai::{ensure_claude_available, run_claude_streaming, ClaudeError};
+use crate::sources::local_git::RangeCommit;
+
+/// Files listed per commit in the prompt; the rest are counted.
+const MAX_FILES_PER_COMMIT: usize = 20;
+
+/// A run of consecutive commits proposed as one review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitSegment {
+    /// Index of the segment's first commit, oldest first.
+    pub first: usize,
+    /// Index of its last commit (inclusive).
+    pub last: usize,
+    pub title: String,
+    /// Why these commits belong together.
+    pub reason: String,
+}
+
+fn build_prompt(commits: &[RangeCommit], max_reviews: usize) -> String {
+    let mut prompt = format!(
+        "The commits below (oldest first) are too many to review at once. Split \
+         them into at most {max_reviews} logical reviews of consecutive commits, \
+         each a coherent feature, refactor, or area of the codebase. Keep \
+         commits that depend on each other together.\n\nCommits:\n"
+    );
+    for (i, commit) in commits.iter().enumerate() {
+        let _ = writeln!(
+            prompt,
+            "\n[{i}] {} {}",
+            &commit.hash[..commit.hash.len().min(10)],
+            commit.subject
+        );
+        for file in commit.files.iter().take(MAX_FILES_PER_COMMIT) {
+            prompt.push_str("    ");
+            prompt.push_str(file);
+            prompt.push('\n');
+        }
+        if commit.files.len() > MAX_FILES_PER_COMMIT {
+            let _ = writeln!(
+                prompt,
+                "    … and {} more",
+                commit.files.len() - MAX_FILES_PER_COMMIT
+            );
+        }
+    }
+    prompt.push_str(
+        "\nRespond with ONLY a JSON object {\"reviews\": [{\"title\": \"short \
+         title\", \"last\": <index of the review's last commit>, \"reason\": \
+         \"one sentence\"}]}, in commit order, the last review ending at the \
+         final commit. No markdown formatting or commentary.",
+    );
+    prompt
+}
+
+#[derive(Deserialize)]
+struct ProposedParts {
+    reviews: Vec<ProposedPart>,
+}
+
+#[derive(Deserialize)]
+struct ProposedPart {
+    title: String,
+    last: usize,
+    #[serde(default)]
+    reason: String,
+}
+
+/// Parse a model's response into segments covering all `commit_count`
+/// commits. Tolerates a markdown fence or prose around the JSON object; a
+/// proposal that skips, repeats, or reorders commits is an error.
+pub fn parse_segments(
+    output: &str,
+    commit_count: usize,
+) -> Result<Vec<CommitSegment>, ClaudeError> {
+    let (Some(start), Some(end)) = (output.find('{'), output.rfind('}')) else {
+        return Err(ClaudeError::ParseError(
+            "no JSON object in response".to_owned(),
+        ));
+    };
+    let parsed: ProposedParts = serde_json::from_str(&output[start..=end])
+        .map_err(|e| ClaudeError::ParseError(e.to_string()))?;
+
+    let mut segments = Vec::with_capacity(parsed.reviews.len());
+    let mut first = 0;
+    for ProposedPart {
+        title,
+        last,
+        reason,
+    } in parsed.reviews
+    {
+        if last < first || last >= commit_count {
+            return Err(ClaudeError::ParseError(format!(
+                "review {title:?} ends at commit {last}, outside {first}..{commit_count}"
+            )));
+        }
+        segments.push(CommitSegment {
+            first,
+            last,
+            title,
+            reason,
+        });
+        first = last + 1;
+    }
+    if first != commit_count {
+        return Err(ClaudeError::ParseError(format!(
+            "reviews cover {first} of {commit_count} commits"
+        )));
+    }
+    Ok(segments)
+}
+
+/// Ask `model` to split `commits` into at most `max_reviews` segments.
+pub fn split_commits_with_model(
+    commits: &[RangeCommit],
+    max_reviews: usize,
+    model: &str,
+    cwd: &Path,
+) -> Result<Vec<CommitSegment>, ClaudeError> {
+    ensure_claude_available()?;
+    let mut redactor = Redactor::load(cwd)?;
+    let prompt = redactor.redact(&build_prompt(commits, max_reviews))?;
+    redactor.finish("split")?;
+    info!(
+        "[split_commits_with_model] {model}: {} commits, prompt {} bytes",
+        commits.len(),
+        prompt.len()
+    );
+    let output = run_claude_streaming(&prompt, cwd, model, &["none"], &mut |_| {}, None)?;
+    let segments = parse_segments(&output, commits.len())?;
+    if segments.len() > max_reviews {
+        return Err(ClaudeError::ParseError(format!(
+            "proposed {} reviews, more than the {max_reviews} asked for",
+            segments.len()
+        )));
+    }
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_contiguous_segments() {
+        let output = "```json\n{\"reviews\": [\
+            {\"title\": \"Schema\", \"last\": 1, \"reason\": \"migrations\"},\
+            {\"title\": \"API\", \"last\": 4}]}\n```";
+        let segments = parse_segments(output, 5).unwrap();
+        assert_eq!(
+            segments
+                .iter()
+                .map(|s| (s.first, s.last, s.title.as_str()))
+                .collect::<Vec<_>>(),
+            [(0, 1, "Schema"), (2, 4, "API")]
+        );
+
+        // Gaps, overlaps, and short coverage are rejected.
+        assert!(parse_segments("{\"reviews\": [{\"title\": \"a\", \"last\": 5}]}", 5).is_err());
+        let overlap =
+            "{\"reviews\": [{\"title\": \"a\", \"last\": 2}, {\"title\": \"b\", \"last\": 1}]}";
+        assert!(parse_segments(overlap, 3).is_err());
+        assert!(parse_segments("{\"reviews\": [{\"title\": \"a\", \"last\": 1}]}", 3).is_err());
+    }
+}
@@ -1,10 +1,18 @@
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use review::cli::{run, Cli};
 
 fn main() {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    review::logs::init_file_logger("cli.log", log::LevelFilter::Info);
 
-    if let Err(e) = run(cli) {
+    let result = run(cli);
+    // After `run`, so a `--home` override applies to where it's counted.
+    review::metrics::record(&format!(
+        "cli.{}",
+        matches.subcommand_name().unwrap_or("open")
+    ));
+    if let Err(e) = result {
         eprintln!("Error: {e}");
         std::process::exit(1);
     }
@@ -1,10 +1,62 @@
+use review::service::pairing;
+
 #[tokio::main]
 async fn main() {
     env_logger::init();
-    let port = std::env::var("REVIEW_PORT")
+    let ip = match review::server::bind_ip() {
+        Ok(ip) => ip,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let bound = match review::server::bind(ip).await {
+        Ok(bound) => bound,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let addr = bound.addr();
+    let port = addr.port();
+    if ip.is_loopback() {
+        println!("review-server listening on http://{addr} (this machine only)");
+    } else {
+        println!("review-server listening on http://{addr} (token required on every route)");
+    }
+    match review::server::auth::server_token() {
+        Ok(token) => {
+            println!("Token for remote job endpoints: {token}");
+            let payload =
+                pairing::pairing_payload(ip, port, token, pairing::link_local_requested());
+            for url in &payload.urls {
+                println!("  reachable at {url}");
+            }
+            if !payload.urls.is_empty() {
+                println!(
+                    "Pairing payload: {}",
+                    serde_json::to_string(&payload).unwrap_or_default()
+                );
+            }
+        }
+        Err(e) => eprintln!("Remote job endpoints unavailable: {e}"),
+    }
+    review::service::job_queue::resume();
+    #[cfg(feature = "viewer")]
+    if let Some(viewer_port) = std::env::var("REVIEW_VIEWER_PORT")
         .ok()
         .and_then(|p| p.parse().ok())
-        .unwrap_or(3421);
-    println!("review-server listening on http://127.0.0.1:{port}");
-    review::server::serve(port).await;
+    {
+        let viewer_addr = std::net::SocketAddr::new(ip, viewer_port);
+        println!("Read-only viewer on http://{viewer_addr}/?token=<token>");
+        tokio::spawn(async move {
+            if let Err(e) = review::server::viewer::serve_viewer(ip, viewer_port).await {
+                eprintln!("Viewer: {e}");
+            }
+        });
+    }
+    if let Err(e) = review::server::serve(bound).await {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
 }
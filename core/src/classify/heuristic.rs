@@ -0,0 +1,270 @@
+//! Heuristic coarse labels for machines with no AI provider.
+//!
+//! A linear (softmax) scorer over lexical features of a hunk — its path, how
+//! much of it is blank, comment, prose, or code, whether it only reflows
+//! lines — picks one coarse label: `coarse:formatting`, `coarse:tests`,
+//! `coarse:docs`, or `coarse:logic`. The weights in
+//! `resources/heuristic_weights.json` are set by hand, not trained on labeled
+//! hunks, so a score is how strongly the features point at a label rather
+//! than a calibrated probability. It only labels hunks the static rules leave
+//! unlabeled, and only when the top score clears the threshold. The coarse
+//! labels are opt-in in the taxonomy, so they're never trusted by default.
+//!
+//! Built with the `heuristic-labels` feature.
+
+use std::sync::OnceLock;
+
+use log::warn;
+use serde::Deserialize;
+
+use super::static_rules::{is_comment_text, is_test_path};
+use super::{ClassificationResult, ClassifyResponse};
+use crate::diff::parser::{DiffHunk, LineType};
+
+/// Feature names, in the order [`features`] computes them.
+const FEATURES: [&str; 10] = [
+    "test-path",
+    "doc-path",
+    "reformat",
+    "blank",
+    "comment",
+    "prose",
+    "code",
+    "control-flow",
+    "assertion",
+    "size",
+];
+
+const DOC_EXTENSIONS: &[&str] = &["md", "mdx", "rst", "txt", "adoc"];
+const CONTROL_FLOW: &[&str] = &[
+    "if", "else", "for", "while", "match", "switch", "case", "return", "break", "continue", "loop",
+    "try", "catch", "except", "raise", "throw",
+];
+const ASSERTIONS: &[&str] = &[
+    "assert",
+    "expect(",
+    "should",
+    "describe(",
+    "#[test]",
+    "@test",
+];
+
+/// Hand-set weights in the `heuristic_weights.json` format.
+#[derive(Debug, Deserialize)]
+pub struct HeuristicWeights {
+    pub version: u32,
+    /// Minimum score of the top label for it to be applied.
+    pub threshold: f64,
+    pub features: Vec<String>,
+    pub labels: Vec<String>,
+    /// One row of feature weights per label.
+    pub weights: Vec<Vec<f64>>,
+    pub bias: Vec<f64>,
+}
+
+impl HeuristicWeights {
+    /// Parse weights, checking their shape against the features computed here.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let model: Self = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if model.features != FEATURES {
+            return Err(format!("expected features {FEATURES:?}"));
+        }
+        if model.weights.len() != model.labels.len()
+            || model.bias.len() != model.labels.len()
+            || model.weights.iter().any(|row| row.len() != FEATURES.len())
+        {
+            return Err("weights and bias must have one entry per label and feature".to_owned());
+        }
+        Ok(model)
+    }
+
+    /// The best-scoring label for `hunk` and its softmax score, or `None` for
+    /// a hunk with no changed lines.
+    pub fn predict(&self, hunk: &DiffHunk) -> Option<(&str, f64)> {
+        let x = features(hunk)?;
+        let scores: Vec<f64> = self
+            .weights
+            .iter()
+            .zip(&self.bias)
+            .map(|(row, bias)| bias + row.iter().zip(&x).map(|(w, v)| w * v).sum::<f64>())
+            .collect();
+        let max = scores.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let exp: Vec<f64> = scores.iter().map(|s| (s - max).exp()).collect();
+        let total: f64 = exp.iter().sum();
+        let (best, p) = exp
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, e)| (i, e / total))?;
+        Some((self.labels[best].as_str(), p))
+    }
+}
+
+/// The built-in weights, parsed once.
+pub fn builtin() -> Option<&'static HeuristicWeights> {
+    static WEIGHTS: OnceLock<Option<HeuristicWeights>> = OnceLock::new();
+    WEIGHTS
+        .get_or_init(|| {
+            HeuristicWeights::parse(include_str!("../../resources/heuristic_weights.json"))
+                .inspect_err(|e| warn!("[heuristic] Invalid built-in weights: {e}"))
+                .ok()
+        })
+        .as_ref()
+}
+
+/// Label the hunks `response` has no classification for, where the top score
+/// clears the threshold.
+pub fn label_unlabeled(
+    weights: &HeuristicWeights,
+    hunks: &[DiffHunk],
+    response: &mut ClassifyResponse,
+) {
+    for hunk in hunks {
+        if response.classifications.contains_key(&hunk.id) {
+            continue;
+        }
+        let Some((label, score)) = weights.predict(hunk) else {
+            continue;
+        };
+        if score >= weights.threshold {
+            response.classifications.insert(
+                hunk.id.clone(),
+                ClassificationResult {
+                    label: vec![label.to_owned()],
+                    reasoning: format!("Heuristic: {label} (score {score:.2})"),
+                    evidence: Vec::new(),
+                },
+            );
+        }
+    }
+}
+
+/// Share of `lines` matching `pred`, in 0..=1.
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "hunk line counts are far below f64's exact range"
+)]
+fn share(lines: &[&str], pred: impl Fn(&str) -> bool) -> f64 {
+    lines.iter().filter(|l| pred(l)).count() as f64 / lines.len() as f64
+}
+
+fn has_word(line: &str, words: &[&str]) -> bool {
+    line.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|token| words.contains(&token))
+}
+
+/// The feature vector of `hunk`, in [`FEATURES`] order, each in 0..=1.
+#[allow(
+    clippy::cast_precision_loss,
+    reason = "hunk line counts are far below f64's exact range"
+)]
+fn features(hunk: &DiffHunk) -> Option<[f64; 10]> {
+    let side = |line_type: LineType| -> Vec<&str> {
+        hunk.lines
+            .iter()
+            .filter(|l| l.line_type == line_type)
+            .map(|l| l.content.as_str())
+            .collect()
+    };
+    let (added, removed) = (side(LineType::Added), side(LineType::Removed));
+    let changed: Vec<&str> = added.iter().chain(&removed).map(|l| l.trim()).collect();
+    if changed.is_empty() {
+        return None;
+    }
+
+    let path = hunk.file_path.to_ascii_lowercase();
+    let extension = path.rsplit_once('.').map_or("", |(_, ext)| ext);
+    let doc_path =
+        DOC_EXTENSIONS.contains(&extension) || path.starts_with("docs/") || path.contains("/docs/");
+
+    // The same text before and after, up to whitespace and line breaks: a
+    // reindent or reflow.
+    let squash =
+        |lines: &[&str]| -> String { lines.iter().flat_map(|l| l.split_whitespace()).collect() };
+    let reformat = !removed.is_empty() && squash(&added) == squash(&removed);
+
+    let is_code = |l: &str| l.contains([';', '{', '}', '(', ')', '=']);
+    let is_prose = |l: &str| !is_code(l) && l.split_whitespace().count() >= 4;
+
+    Some([
+        f64::from(u8::from(is_test_path(&hunk.file_path))),
+        f64::from(u8::from(doc_path)),
+        f64::from(u8::from(reformat)),
+        share(&changed, str::is_empty),
+        share(&changed, is_comment_text),
+        share(&changed, |l| !is_comment_text(l) && is_prose(l)),
+        share(&changed, |l| !is_comment_text(l) && is_code(l)),
+        share(&changed, |l| has_word(l, CONTROL_FLOW)),
+        share(&changed, |l| ASSERTIONS.iter().any(|a| l.contains(a))),
+        (changed.len() as f64 / 50.0).min(1.0),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn builtin_weights_assign_coarse_labels() {
+        use LineType::{Added, Removed};
+        let weights = builtin().expect("built-in weights parse");
+        let label = |h: &DiffHunk| weights.predict(h).map(|(label, _)| label.to_owned());
+
+        let reflow = DiffHunk::test(
+            "src/lib.rs",
+            [
+                (Removed, "let total = a +"),
+                (Removed, "    b;"),
+                (Added, "let total = a + b;"),
+            ],
+        );
+        assert_eq!(label(&reflow).as_deref(), Some("coarse:formatting"));
+
+        let test = DiffHunk::test(
+            "tests/parser_test.rs",
+            [(Added, "    assert_eq!(parse(\"1\"), Ok(1));")],
+        );
+        assert_eq!(label(&test).as_deref(), Some("coarse:tests"));
+
+        let docs = DiffHunk::test(
+            "docs/guide.md",
+            [(
+                Added,
+                "Run the installer and follow the prompts to finish setup.",
+            )],
+        );
+        assert_eq!(label(&docs).as_deref(), Some("coarse:docs"));
+
+        let logic = DiffHunk::test(
+            "src/cart.rs",
+            [
+                (Removed, "    return total;"),
+                (Added, "    if total > limit {"),
+                (Added, "        return limit;"),
+                (Added, "    }"),
+            ],
+        );
+        assert_eq!(label(&logic).as_deref(), Some("coarse:logic"));
+
+        // Static labels are kept; only unlabeled hunks get a coarse label.
+        let mut response = ClassifyResponse {
+            classifications: HashMap::from([(
+                reflow.id.clone(),
+                ClassificationResult {
+                    label: vec!["formatting:style".to_owned()],
+                    reasoning: String::new(),
+                    evidence: Vec::new(),
+                },
+            )]),
+            limits_exceeded: Vec::new(),
+            raw_labels: HashMap::new(),
+        };
+        label_unlabeled(weights, &[reflow.clone(), docs.clone()], &mut response);
+        assert_eq!(
+            response.classifications[&reflow.id].label,
+            ["formatting:style"]
+        );
+        assert_eq!(response.classifications[&docs.id].label, ["coarse:docs"]);
+    }
+}
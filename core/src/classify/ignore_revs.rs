@@ -0,0 +1,163 @@
+//! Hunks that only redo or undo an ignored formatting commit.
+//!
+//! Repos list their mass reformats in `.git-blame-ignore-revs` (or the file
+//! `blame.ignoreRevsFile` names) so blame looks through them. A hunk whose
+//! removed and added lines all come from one of those commits' own changes to
+//! the file — in either direction, so a branch that reverts or reapplies the
+//! reformat — gets [`IGNORED_REV_LABEL`] rather than reading as new code.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::{ClassificationResult, ClassifyResponse, Evidence};
+use crate::diff::parser::{DiffHunk, LineType};
+use crate::sources::local_git::LocalGitSource;
+
+/// Label added to hunks that only redo or undo an ignored commit.
+pub const IGNORED_REV_LABEL: &str = "formatting:ignored-rev";
+
+/// The lines an ignored commit removed and added in one file.
+#[derive(Debug, Default)]
+struct RevLines<'a> {
+    removed: HashSet<&'a str>,
+    added: HashSet<&'a str>,
+}
+
+/// How a hunk relates to an ignored commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Redoes,
+    Undoes,
+}
+
+fn changed<'a>(hunk: &'a DiffHunk, line_type: &LineType) -> Vec<&'a str> {
+    hunk.lines
+        .iter()
+        .filter(|line| line.line_type == *line_type)
+        .map(|line| line.content.as_str())
+        .collect()
+}
+
+/// Whether `hunk` is made only of `rev`'s changes, one way or the other. The
+/// hunk must both remove and add a non-blank line: lone additions or
+/// deletions of a common line like `}` prove nothing.
+fn match_rev(hunk: &DiffHunk, rev: &RevLines) -> Option<Direction> {
+    let removed = changed(hunk, &LineType::Removed);
+    let added = changed(hunk, &LineType::Added);
+    let blank = |lines: &[&str]| lines.iter().all(|l| l.trim().is_empty());
+    if blank(&removed) || blank(&added) {
+        return None;
+    }
+    let all_in = |lines: &[&str], set: &HashSet<&str>| lines.iter().all(|l| set.contains(l));
+    if all_in(&removed, &rev.removed) && all_in(&added, &rev.added) {
+        Some(Direction::Redoes)
+    } else if all_in(&removed, &rev.added) && all_in(&added, &rev.removed) {
+        Some(Direction::Undoes)
+    } else {
+        None
+    }
+}
+
+fn label(hunk: &DiffHunk, rev: &str, direction: Direction, response: &mut ClassifyResponse) {
+    let short = &rev[..rev.len().min(8)];
+    let verb = match direction {
+        Direction::Redoes => "redoes",
+        Direction::Undoes => "undoes",
+    };
+    let reasoning = format!("Only {verb} commit {short}, which blame ignores as formatting");
+    let result = response
+        .classifications
+        .entry(hunk.id.clone())
+        .or_insert_with(|| ClassificationResult {
+            label: Vec::new(),
+            reasoning: String::new(),
+            evidence: Vec::new(),
+        });
+    if result.label.iter().any(|l| l == IGNORED_REV_LABEL) {
+        return;
+    }
+    result.label.push(IGNORED_REV_LABEL.to_owned());
+    result.evidence.push(Evidence {
+        rule: "ignored-rev".to_owned(),
+        matched: Some(rev.to_owned()),
+        ..Evidence::default()
+    });
+    result.reasoning = if result.reasoning.is_empty() {
+        reasoning
+    } else {
+        format!("{}; {reasoning}", result.reasoning)
+    };
+}
+
+/// Label the hunks that only redo or undo one of the repo's ignored revs.
+pub fn apply(repo_path: &Path, hunks: &[DiffHunk], response: &mut ClassifyResponse) {
+    let Ok(source) = LocalGitSource::new(repo_path.to_path_buf()) else {
+        return;
+    };
+    let revs = source.blame_ignore_revs();
+    if revs.is_empty() || hunks.is_empty() {
+        return;
+    }
+    let mut paths: Vec<&str> = hunks.iter().map(|h| h.file_path.as_str()).collect();
+    paths.sort_unstable();
+    paths.dedup();
+
+    let mut unmatched: Vec<&DiffHunk> = hunks.iter().collect();
+    for rev in &revs {
+        let Ok(rev_hunks) = source.get_commit_hunks(rev, &paths) else {
+            continue;
+        };
+        let mut by_file: HashMap<&str, RevLines> = HashMap::new();
+        for rev_hunk in &rev_hunks {
+            let lines = by_file.entry(rev_hunk.file_path.as_str()).or_default();
+            lines.removed.extend(changed(rev_hunk, &LineType::Removed));
+            lines.added.extend(changed(rev_hunk, &LineType::Added));
+        }
+        unmatched.retain(|hunk| {
+            let direction = by_file
+                .get(hunk.file_path.as_str())
+                .and_then(|lines| match_rev(hunk, lines));
+            if let Some(direction) = direction {
+                label(hunk, rev, direction, response);
+            }
+            direction.is_none()
+        });
+        if unmatched.is_empty() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(removed: &[&str], added: &[&str]) -> DiffHunk {
+        let removed = removed.iter().map(|line| (LineType::Removed, *line));
+        let added = added.iter().map(|line| (LineType::Added, *line));
+        DiffHunk::test("src/a.rs", removed.chain(added))
+    }
+
+    #[test]
+    fn matches_changes_from_the_rev_either_way() {
+        let rev = RevLines {
+            removed: HashSet::from(["fn f(a:u8){", "  g(a)"]),
+            added: HashSet::from(["fn f(a: u8) {", "    g(a)"]),
+        };
+        assert_eq!(
+            match_rev(&hunk(&["  g(a)"], &["    g(a)"]), &rev),
+            Some(Direction::Redoes)
+        );
+        assert_eq!(
+            match_rev(&hunk(&["fn f(a: u8) {"], &["fn f(a:u8){"]), &rev),
+            Some(Direction::Undoes)
+        );
+        // A real edit mixed in.
+        assert_eq!(
+            match_rev(&hunk(&["  g(a)"], &["    g(a)", "    h(a)"]), &rev),
+            None
+        );
+        // Pure additions of lines the rev happened to add prove nothing.
+        assert_eq!(match_rev(&hunk(&[], &["    g(a)"]), &rev), None);
+    }
+}
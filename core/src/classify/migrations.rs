@@ -0,0 +1,503 @@
+//! Database migration checks.
+//!
+//! Migrations are recognized by path: `*.up.sql` / `*.down.sql` pairs
+//! (golang-migrate, sqlx), SQL files under a migrations directory (goose,
+//! dbmate and sql-migrate keep both directions in one file behind
+//! `-- +goose Up` / `-- migrate:up` markers; Flyway and Prisma migrations are
+//! forward-only), Alembic revisions, Rails migrations, and Django migrations.
+//!
+//! Two things are checked: that a migration can be rolled back (its down half
+//! exists), and that its forward half doesn't destroy data (`DROP COLUMN`,
+//! `TRUNCATE`, `remove_column`, `op.drop_table`, ...). Either makes the
+//! migration irreversible, and the hunks involved get [`IRREVERSIBLE_LABEL`].
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{ClassificationResult, ClassifyResponse, Evidence};
+use crate::diff::parser::{DiffHunk, LineType};
+
+/// Label for migration hunks that can't be rolled back.
+pub const IRREVERSIBLE_LABEL: &str = "sql:irreversible";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationFormat {
+    /// `*.up.sql`, paired with a `*.down.sql`.
+    SqlUp,
+    /// `*.down.sql`.
+    SqlDown,
+    /// A SQL file under a migrations directory, optionally with up/down markers.
+    Sql,
+    Alembic,
+    Rails,
+    Django,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationIssueKind {
+    /// The migration has no down half to roll it back.
+    MissingDown,
+    /// The forward half destroys data.
+    DestructiveOperation,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationIssue {
+    pub kind: MigrationIssueKind,
+    pub detail: String,
+    /// 1-based line in the new file, for destructive operations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    pub hunk_ids: Vec<String>,
+}
+
+/// The checks for one changed migration file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationCheck {
+    pub file_path: String,
+    pub format: MigrationFormat,
+    pub issues: Vec<MigrationIssue>,
+}
+
+impl MigrationCheck {
+    /// Add [`IRREVERSIBLE_LABEL`] to every hunk an issue of `kind` points at.
+    pub fn apply(&self, kind: MigrationIssueKind, response: &mut ClassifyResponse) {
+        for issue in self.issues.iter().filter(|i| i.kind == kind) {
+            for hunk_id in &issue.hunk_ids {
+                let result = response
+                    .classifications
+                    .entry(hunk_id.clone())
+                    .or_insert_with(|| ClassificationResult {
+                        label: Vec::new(),
+                        reasoning: String::new(),
+                        evidence: Vec::new(),
+                    });
+                if !result.label.iter().any(|l| l == IRREVERSIBLE_LABEL) {
+                    result.label.insert(0, IRREVERSIBLE_LABEL.to_owned());
+                }
+                let rule = match kind {
+                    MigrationIssueKind::MissingDown => "migration-missing-down",
+                    MigrationIssueKind::DestructiveOperation => "migration-irreversible",
+                };
+                result.evidence.insert(
+                    0,
+                    Evidence {
+                        rule: rule.to_owned(),
+                        matched: Some(issue.detail.clone()),
+                        ..Evidence::default()
+                    },
+                );
+                result.reasoning = if result.reasoning.is_empty() {
+                    issue.detail.clone()
+                } else {
+                    format!("{}; {}", issue.detail, result.reasoning)
+                };
+            }
+        }
+    }
+}
+
+/// The migration format of `path`, if it's a migration.
+pub fn migration_format(path: &str) -> Option<MigrationFormat> {
+    let lower = path.to_ascii_lowercase();
+    let mut segments = lower.rsplit('/');
+    let filename = segments.next()?;
+    let dirs: Vec<&str> = segments.collect();
+    let ext = filename.rsplit_once('.').map(|(_, ext)| ext)?;
+
+    if filename.ends_with(".up.sql") {
+        return Some(MigrationFormat::SqlUp);
+    }
+    if filename.ends_with(".down.sql") {
+        return Some(MigrationFormat::SqlDown);
+    }
+    let in_migrations_dir = dirs
+        .iter()
+        .any(|d| matches!(*d, "migrations" | "migration" | "migrate"));
+    match ext {
+        "sql" if in_migrations_dir => Some(MigrationFormat::Sql),
+        "py" if dirs.first() == Some(&"versions") => Some(MigrationFormat::Alembic),
+        "py" if dirs.first() == Some(&"migrations") && filename != "__init__.py" => {
+            Some(MigrationFormat::Django)
+        }
+        "rb" if lower.contains("db/migrate/") => Some(MigrationFormat::Rails),
+        _ => None,
+    }
+}
+
+/// The `*.down.sql` that rolls back a `*.up.sql`.
+pub fn down_counterpart(path: &str) -> Option<String> {
+    let stem_len = path.len().checked_sub(".up.sql".len())?;
+    path.get(stem_len..)
+        .filter(|suffix| suffix.eq_ignore_ascii_case(".up.sql"))
+        .map(|_| format!("{}.down.sql", &path[..stem_len]))
+}
+
+/// A line that switches a single-file migration between its halves.
+fn direction_marker(format: MigrationFormat, line: &str) -> Option<Direction> {
+    let lower = line.to_ascii_lowercase();
+    match format {
+        MigrationFormat::Sql => {
+            let marker = lower.strip_prefix("--")?.trim();
+            if ["+goose up", "migrate:up", "+migrate up"].contains(&marker) {
+                Some(Direction::Up)
+            } else if ["+goose down", "migrate:down", "+migrate down"].contains(&marker) {
+                Some(Direction::Down)
+            } else {
+                None
+            }
+        }
+        MigrationFormat::Alembic => {
+            if lower.starts_with("def upgrade(") {
+                Some(Direction::Up)
+            } else if lower.starts_with("def downgrade(") {
+                Some(Direction::Down)
+            } else {
+                None
+            }
+        }
+        MigrationFormat::Rails => match lower.as_str() {
+            "def up" | "def change" => Some(Direction::Up),
+            "def down" => Some(Direction::Down),
+            _ => None,
+        },
+        MigrationFormat::SqlUp | MigrationFormat::SqlDown | MigrationFormat::Django => None,
+    }
+}
+
+static SQL_DESTRUCTIVE: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    [
+        (r"(?i)\bdrop\s+table\b", "DROP TABLE"),
+        (r"(?i)\bdrop\s+column\b", "DROP COLUMN"),
+        (r"(?i)\bdrop\s+(schema|database)\b", "DROP SCHEMA"),
+        (r"(?i)\btruncate\b", "TRUNCATE"),
+        (r"(?i)\bdelete\s+from\b", "DELETE FROM"),
+        (
+            r"(?i)\balter\s+column\s+\S+\s+(set\s+data\s+)?type\b",
+            "ALTER COLUMN ... TYPE",
+        ),
+    ]
+    .into_iter()
+    .map(|(pattern, op)| (Regex::new(pattern).unwrap(), op))
+    .collect()
+});
+
+/// Framework calls that destroy data, per format.
+fn framework_destructive_calls(format: MigrationFormat) -> &'static [&'static str] {
+    match format {
+        MigrationFormat::Rails => &[
+            "remove_column",
+            "drop_table",
+            "remove_reference",
+            "remove_belongs_to",
+            // Not `change_column_null` / `change_column_default`, which reverse.
+            "change_column ",
+            "ActiveRecord::IrreversibleMigration",
+        ],
+        MigrationFormat::Alembic => &["op.drop_column", "op.drop_table"],
+        MigrationFormat::Django => &["migrations.RemoveField", "migrations.DeleteModel"],
+        MigrationFormat::SqlUp | MigrationFormat::SqlDown | MigrationFormat::Sql => &[],
+    }
+}
+
+/// The destructive operation on a trimmed migration line, if any.
+fn destructive_operation(format: MigrationFormat, line: &str) -> Option<&'static str> {
+    if line.starts_with("--") || line.starts_with('#') {
+        return None;
+    }
+    framework_destructive_calls(format)
+        .iter()
+        .find(|call| line.contains(*call))
+        .copied()
+        .or_else(|| {
+            SQL_DESTRUCTIVE
+                .iter()
+                .find(|(re, _)| re.is_match(line))
+                .map(|(_, op)| *op)
+        })
+}
+
+/// Indices of the lines that run a destructive operation in a migration's
+/// forward half, with the operation. `lines` may be a whole file or a hunk;
+/// direction markers among them are followed, and everything before the first
+/// marker is taken to be the forward half.
+pub fn destructive_lines<'a>(
+    format: MigrationFormat,
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Vec<(usize, &'static str)> {
+    let mut direction = if format == MigrationFormat::SqlDown {
+        Direction::Down
+    } else {
+        Direction::Up
+    };
+    let mut found = Vec::new();
+    for (i, line) in lines.into_iter().enumerate() {
+        let line = line.trim();
+        if let Some(marker) = direction_marker(format, line) {
+            direction = marker;
+        } else if direction == Direction::Up {
+            if let Some(op) = destructive_operation(format, line) {
+                found.push((i, op));
+            }
+        }
+    }
+    found
+}
+
+/// Why a single-file migration can't be rolled back, if it can't.
+fn missing_down(format: MigrationFormat, content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().map(str::trim).collect();
+    let has = |direction| {
+        lines
+            .iter()
+            .any(|l| direction_marker(format, l) == Some(direction))
+    };
+    match format {
+        // Forward-only files (no markers) aren't expected to have a down half.
+        MigrationFormat::Sql if has(Direction::Up) && !has(Direction::Down) => {
+            Some("Migration has an up section but no down section".to_owned())
+        }
+        MigrationFormat::Rails if lines.contains(&"def up") && !has(Direction::Down) => {
+            Some("Migration defines `up` without `down`".to_owned())
+        }
+        MigrationFormat::Alembic => {
+            let Some(start) = lines.iter().position(|l| l.starts_with("def downgrade(")) else {
+                return Some("Revision has no `downgrade()`".to_owned());
+            };
+            let empty = content
+                .lines()
+                .skip(start + 1)
+                .take_while(|l| l.trim().is_empty() || l.starts_with([' ', '\t']))
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .all(|l| l == "pass" || l.starts_with(['"', '\'']));
+            empty.then(|| "Revision's `downgrade()` is empty".to_owned())
+        }
+        _ => None,
+    }
+}
+
+/// Check one changed migration file. `content` is its new content, `hunks`
+/// its hunks, and `exists` says whether another repo file (a `*.down.sql`
+/// counterpart) is present. Destructive operations are only reported on
+/// added lines.
+pub fn check_migration(
+    file_path: &str,
+    content: &str,
+    hunks: &[DiffHunk],
+    exists: impl Fn(&str) -> bool,
+) -> Option<MigrationCheck> {
+    let format = migration_format(file_path)?;
+    let hunks: Vec<&DiffHunk> = hunks.iter().filter(|h| h.file_path == file_path).collect();
+    let all_hunk_ids = || hunks.iter().map(|h| h.id.clone()).collect::<Vec<_>>();
+    let mut issues = Vec::new();
+
+    let missing = match format {
+        MigrationFormat::SqlUp => down_counterpart(file_path)
+            .filter(|down| !exists(down))
+            .map(|down| format!("No down migration ({down})")),
+        _ => missing_down(format, content),
+    };
+    if let Some(detail) = missing {
+        issues.push(MigrationIssue {
+            kind: MigrationIssueKind::MissingDown,
+            detail,
+            line: None,
+            hunk_ids: all_hunk_ids(),
+        });
+    }
+
+    for (i, op) in destructive_lines(format, content.lines()) {
+        let line = i as u32 + 1;
+        let hunk_ids: Vec<String> = hunks
+            .iter()
+            .filter(|h| {
+                h.lines
+                    .iter()
+                    .any(|l| l.line_type == LineType::Added && l.new_line_number == Some(line))
+            })
+            .map(|h| h.id.clone())
+            .collect();
+        if !hunk_ids.is_empty() {
+            issues.push(MigrationIssue {
+                kind: MigrationIssueKind::DestructiveOperation,
+                detail: format!("Migration runs {op}, which can't be undone"),
+                line: Some(line),
+                hunk_ids,
+            });
+        }
+    }
+
+    Some(MigrationCheck {
+        file_path: file_path.to_owned(),
+        format,
+        issues,
+    })
+}
+
+/// Label the hunks of migrations that have no down half, reading each
+/// migration from the repo's working tree. Destructive operations are left to
+/// the static rules, which see them hunk by hunk.
+pub fn apply_missing_down(repo_path: &Path, hunks: &[DiffHunk], response: &mut ClassifyResponse) {
+    let changed: HashSet<&str> = hunks.iter().map(|h| h.file_path.as_str()).collect();
+    let exists = |path: &str| changed.contains(path) || repo_path.join(path).exists();
+    for file_path in &changed {
+        if migration_format(file_path).is_none() {
+            continue;
+        }
+        // Deleted migrations have nothing to roll back.
+        let Ok(content) = std::fs::read_to_string(repo_path.join(file_path)) else {
+            continue;
+        };
+        if let Some(check) = check_migration(file_path, &content, hunks, exists) {
+            check.apply(MigrationIssueKind::MissingDown, response);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::create_untracked_hunk;
+    use std::collections::HashMap;
+
+    #[test]
+    fn detects_migration_formats() {
+        let cases = [
+            ("migrations/0001_users.up.sql", Some(MigrationFormat::SqlUp)),
+            ("db/0001_users.DOWN.sql", Some(MigrationFormat::SqlDown)),
+            (
+                "db/migrations/20240101_users.sql",
+                Some(MigrationFormat::Sql),
+            ),
+            (
+                "alembic/versions/abc123_users.py",
+                Some(MigrationFormat::Alembic),
+            ),
+            (
+                "app/users/migrations/0002_email.py",
+                Some(MigrationFormat::Django),
+            ),
+            ("app/users/migrations/__init__.py", None),
+            (
+                "db/migrate/20240101_add_email.rb",
+                Some(MigrationFormat::Rails),
+            ),
+            ("sql/report.sql", None),
+        ];
+        for (path, format) in cases {
+            assert_eq!(migration_format(path), format, "{path}");
+        }
+        assert_eq!(
+            down_counterpart("migrations/0001_users.up.sql").as_deref(),
+            Some("migrations/0001_users.down.sql")
+        );
+    }
+
+    #[test]
+    fn destructive_operations_only_count_going_up() {
+        let goose = "\
+-- +goose Up
+ALTER TABLE users DROP COLUMN email;
+-- Drop the legacy table
+-- +goose Down
+ALTER TABLE users ADD COLUMN email text;
+DROP TABLE users_v2;
+";
+        assert_eq!(
+            destructive_lines(MigrationFormat::Sql, goose.lines()),
+            [(1, "DROP COLUMN")]
+        );
+        assert!(destructive_lines(MigrationFormat::SqlDown, ["DROP TABLE users;"]).is_empty());
+
+        let rails = "\
+class AddEmail < ActiveRecord::Migration[7.1]
+  def up
+    remove_column :users, :legacy
+  end
+
+  def down
+    drop_table :users_v2
+  end
+end
+";
+        assert_eq!(
+            destructive_lines(MigrationFormat::Rails, rails.lines()),
+            [(2, "remove_column")]
+        );
+    }
+
+    #[test]
+    fn checks_pairing_and_labels_hunks() {
+        let up = create_untracked_hunk(
+            "migrations/0002_drop.up.sql",
+            "h1",
+            Some("ALTER TABLE users DROP COLUMN email;\n"),
+        );
+        let hunks = [up];
+        let check = check_migration(
+            "migrations/0002_drop.up.sql",
+            "ALTER TABLE users DROP COLUMN email;\n",
+            &hunks,
+            |_| false,
+        )
+        .unwrap();
+        let kinds: Vec<_> = check.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                MigrationIssueKind::MissingDown,
+                MigrationIssueKind::DestructiveOperation
+            ]
+        );
+        assert_eq!(
+            check.issues[0].detail,
+            "No down migration (migrations/0002_drop.down.sql)"
+        );
+        assert_eq!(check.issues[1].line, Some(1));
+
+        let mut response = ClassifyResponse {
+            classifications: std::collections::HashMap::new(),
+            limits_exceeded: Vec::new(),
+            raw_labels: HashMap::new(),
+        };
+        check.apply(MigrationIssueKind::MissingDown, &mut response);
+        let result = &response.classifications[&hunks[0].id];
+        assert_eq!(result.label, [IRREVERSIBLE_LABEL]);
+
+        // A counterpart makes it reversible.
+        let check = check_migration("migrations/0002_drop.up.sql", "", &hunks, |_| true).unwrap();
+        assert!(check.issues.is_empty());
+
+        // Single-file formats need their down half.
+        assert!(missing_down(MigrationFormat::Sql, "-- migrate:up\nSELECT 1;\n").is_some());
+        assert!(missing_down(MigrationFormat::Sql, "CREATE TABLE t (id int);\n").is_none());
+        assert_eq!(
+            missing_down(
+                MigrationFormat::Alembic,
+                "def upgrade():\n    op.add_column('t', c)\n\n\ndef downgrade():\n    pass\n"
+            )
+            .as_deref(),
+            Some("Revision's `downgrade()` is empty")
+        );
+        assert!(missing_down(
+            MigrationFormat::Alembic,
+            "def downgrade():\n    \"\"\"Undo.\"\"\"\n    op.drop_column('t', 'c')\n"
+        )
+        .is_none());
+    }
+}
@@ -1,7 +1,16 @@
+#[cfg(feature = "heuristic-labels")]
+pub mod heuristic;
+pub mod ignore_revs;
+pub mod migrations;
+pub mod precedence;
+pub mod sensitive;
 pub mod static_rules;
 
+use crate::diff::parser::{DiffHunk, DiffLine};
+use crate::limits::{sample_indices, Degradation, LimitExceeded, LimitKind, ReviewLimits};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 
 pub use static_rules::classify_hunks_static;
 
@@ -9,9 +18,157 @@ pub use static_rules::classify_hunks_static;
 pub struct ClassificationResult {
     pub label: Vec<String>,
     pub reasoning: String,
+    /// What each rule behind the labels matched, so a label can be explained
+    /// (and a false positive reported) down to the line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<Evidence>,
+}
+
+/// Why a rule labeled a hunk.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Evidence {
+    /// The rule that matched, e.g. `whitespace`, `tests-removed`,
+    /// `sensitive-path`.
+    pub rule: String,
+    /// The hunk lines the rule matched. Empty for rules that look only at the
+    /// file path.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<EvidenceLine>,
+    /// What matched outside the hunk's lines, e.g. a sensitive path glob.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matched: Option<String>,
+}
+
+/// A hunk line a rule matched, with its line numbers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvidenceLine {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_line_number: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub new_line_number: Option<u32>,
+    pub text: String,
+}
+
+impl From<&DiffLine> for EvidenceLine {
+    fn from(line: &DiffLine) -> Self {
+        Self {
+            old_line_number: line.old_line_number,
+            new_line_number: line.new_line_number,
+            text: line.content.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassifyResponse {
     pub classifications: HashMap<String, ClassificationResult>,
+    /// Limits that left hunks unclassified (see [`classify_hunks_static_limited`]).
+    #[serde(
+        rename = "limitsExceeded",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub limits_exceeded: Vec<LimitExceeded>,
+    /// Labels as classified, for hunks whose labels precedence rules changed
+    /// (see [`apply_label_precedence`]).
+    #[serde(
+        rename = "rawLabels",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub raw_labels: HashMap<String, Vec<String>>,
+}
+
+/// Classify hunks statically within `limits`. Hunks whose content exceeds the
+/// per-file budget are left unclassified; past `max_hunks`, an evenly spread
+/// sample is classified rather than just the first files.
+pub fn classify_hunks_static_limited(
+    hunks: &[DiffHunk],
+    limits: &ReviewLimits,
+) -> ClassifyResponse {
+    let mut exceeded = Vec::new();
+    let (fitting, oversized): (Vec<&DiffHunk>, Vec<&DiffHunk>) = hunks
+        .iter()
+        .partition(|hunk| hunk.content.len() <= limits.max_file_bytes);
+    if !oversized.is_empty() {
+        let mut files: Vec<String> = oversized.iter().map(|h| h.file_path.clone()).collect();
+        files.dedup();
+        exceeded.push(LimitExceeded {
+            kind: LimitKind::FileBytes,
+            limit: limits.max_file_bytes,
+            actual: oversized.iter().map(|h| h.content.len()).max().unwrap_or(0),
+            degradation: Degradation::Skipped,
+            files,
+        });
+    }
+
+    let selected: Vec<DiffHunk> = if fitting.len() > limits.max_hunks {
+        exceeded.push(LimitExceeded {
+            kind: LimitKind::Hunks,
+            limit: limits.max_hunks,
+            actual: fitting.len(),
+            degradation: Degradation::Sampled,
+            files: Vec::new(),
+        });
+        sample_indices(fitting.len(), limits.max_hunks)
+            .into_iter()
+            .map(|i| fitting[i].clone())
+            .collect()
+    } else {
+        fitting.into_iter().cloned().collect()
+    };
+
+    let mut response = classify_hunks_static(&selected);
+    response.limits_exceeded = exceeded;
+    response
+}
+
+/// Classify hunks statically, flag migrations with no down half, label hunks
+/// that only redo or undo an ignored formatting commit, apply the repo's
+/// sensitive path policy, fall back to heuristic coarse labels where no AI
+/// provider is available, and resolve conflicting labels.
+pub fn classify_repo_hunks_static(repo_path: &Path, hunks: &[DiffHunk]) -> ClassifyResponse {
+    let mut response = classify_hunks_static(hunks);
+    migrations::apply_missing_down(repo_path, hunks, &mut response);
+    ignore_revs::apply(repo_path, hunks, &mut response);
+    apply_sensitive_paths(repo_path, hunks, &mut response);
+    apply_heuristic_labels(hunks, &mut response);
+    apply_label_precedence(repo_path, &mut response);
+    response
+}
+
+/// Drop labels that contradict a higher-precedence label on the same hunk,
+/// by the repo's rules ([`precedence`]).
+pub fn apply_label_precedence(repo_path: &Path, response: &mut ClassifyResponse) {
+    precedence::apply_rules(&precedence::load_rules(repo_path), response);
+}
+
+/// Give hunks still unlabeled a coarse label from the built-in heuristic
+/// weights ([`heuristic`]) when no AI provider is available. Without the
+/// `heuristic-labels` feature this does nothing.
+#[cfg_attr(
+    not(feature = "heuristic-labels"),
+    allow(unused_variables, reason = "only the heuristic reads them")
+)]
+pub fn apply_heuristic_labels(hunks: &[DiffHunk], response: &mut ClassifyResponse) {
+    #[cfg(feature = "heuristic-labels")]
+    if !crate::ai::check_claude_available() {
+        if let Some(weights) = heuristic::builtin() {
+            heuristic::label_unlabeled(weights, hunks, response);
+        }
+    }
+}
+
+/// Label hunks in the repo's sensitive areas ([`sensitive::SensitivePaths`]),
+/// if it configures any.
+pub fn apply_sensitive_paths(
+    repo_path: &Path,
+    hunks: &[DiffHunk],
+    response: &mut ClassifyResponse,
+) {
+    if let Some(policy) = sensitive::SensitivePaths::load(repo_path) {
+        policy.apply(hunks, response);
+    }
 }
@@ -0,0 +1,169 @@
+//! Resolve contradictory labels after classification.
+//!
+//! A hunk labeled both `formatting:whitespace` and `api:breaking-change`
+//! can't be both "only whitespace" and a breaking change, and since a hunk is
+//! trusted when any of its labels is, the cosmetic label would wave the
+//! breaking change through. Precedence rules settle it: when a hunk has a
+//! label matching a rule's `prefer` pattern, its labels matching the rule's
+//! `over` patterns are dropped. Rules apply in order.
+//!
+//! The repo can replace the built-in rules in `.review.toml`:
+//!
+//! ```toml
+//! [[precedence]]
+//! prefer = "api:*"
+//! over = ["formatting:*", "comments:*"]
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::review::repo_config::RepoConfig;
+use crate::trust::matching::matches_pattern;
+
+use super::ClassifyResponse;
+
+/// Labels that claim a hunk is risky.
+const RISK_LABELS: &[&str] = &[
+    "api:breaking-change",
+    "sql:irreversible",
+    "ci:pull-request-target",
+    "infra:privileged-container",
+];
+
+/// Labels that claim a hunk is only cosmetic or mechanical.
+const COSMETIC_LABELS: &[&str] = &[
+    "formatting:*",
+    "comments:*",
+    "imports:*",
+    "type-annotations:*",
+    "move:code",
+    "file:added-empty",
+];
+
+/// One precedence rule: `prefer` wins over every label matching `over`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecedenceRule {
+    /// Label pattern, e.g. `api:*`.
+    pub prefer: String,
+    /// Label patterns dropped from hunks that have a `prefer` label.
+    pub over: Vec<String>,
+}
+
+/// The built-in rules: each risk label wins over the cosmetic ones.
+pub fn default_rules() -> Vec<PrecedenceRule> {
+    RISK_LABELS
+        .iter()
+        .map(|prefer| PrecedenceRule {
+            prefer: (*prefer).to_owned(),
+            over: COSMETIC_LABELS.iter().map(|l| (*l).to_owned()).collect(),
+        })
+        .collect()
+}
+
+/// The repo's rules from `.review.toml`, else the built-in ones.
+pub fn load_rules(repo_path: &Path) -> Vec<PrecedenceRule> {
+    RepoConfig::load(repo_path)
+        .and_then(|config| config.precedence)
+        .unwrap_or_else(default_rules)
+}
+
+/// Apply `rules` to `labels`. A label matching both a rule's `prefer` and
+/// `over` patterns is kept.
+pub fn resolve_labels(labels: &[String], rules: &[PrecedenceRule]) -> Vec<String> {
+    let mut resolved = labels.to_vec();
+    for rule in rules {
+        let preferred = |label: &String| matches_pattern(label, &rule.prefer);
+        if !resolved.iter().any(preferred) {
+            continue;
+        }
+        resolved.retain(|label| {
+            preferred(label) || !rule.over.iter().any(|p| matches_pattern(label, p))
+        });
+    }
+    resolved
+}
+
+/// Resolve conflicts in every classification of `response`, recording the
+/// labels as classified in `response.raw_labels` for hunks that changed.
+pub fn apply_rules(rules: &[PrecedenceRule], response: &mut ClassifyResponse) {
+    let mut raw_labels = HashMap::new();
+    for (hunk_id, result) in &mut response.classifications {
+        let resolved = resolve_labels(&result.label, rules);
+        if resolved != result.label {
+            raw_labels.insert(
+                hunk_id.clone(),
+                std::mem::replace(&mut result.label, resolved),
+            );
+        }
+    }
+    response.raw_labels.extend(raw_labels);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classify::ClassificationResult;
+
+    fn labels(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|l| (*l).to_owned()).collect()
+    }
+
+    #[test]
+    fn risk_labels_win_over_cosmetic_ones() {
+        let rules = default_rules();
+        assert_eq!(
+            resolve_labels(
+                &labels(&[
+                    "formatting:whitespace",
+                    "api:breaking-change",
+                    "i18n:translations"
+                ]),
+                &rules
+            ),
+            labels(&["api:breaking-change", "i18n:translations"])
+        );
+        let cosmetic = labels(&["formatting:whitespace", "comments:added"]);
+        assert_eq!(resolve_labels(&cosmetic, &rules), cosmetic);
+
+        let custom = [PrecedenceRule {
+            prefer: "comments:*".to_owned(),
+            over: labels(&["formatting:*", "comments:*"]),
+        }];
+        assert_eq!(
+            resolve_labels(&labels(&["formatting:style", "comments:added"]), &custom),
+            labels(&["comments:added"])
+        );
+    }
+
+    #[test]
+    fn records_raw_labels_for_resolved_hunks() {
+        let result = |l: &[&str]| ClassificationResult {
+            label: labels(l),
+            reasoning: String::new(),
+            evidence: Vec::new(),
+        };
+        let mut response = ClassifyResponse {
+            classifications: [
+                (
+                    "a".to_owned(),
+                    result(&["imports:added", "sql:irreversible"]),
+                ),
+                ("b".to_owned(), result(&["imports:added"])),
+            ]
+            .into_iter()
+            .collect(),
+            limits_exceeded: Vec::new(),
+            raw_labels: HashMap::new(),
+        };
+        apply_rules(&default_rules(), &mut response);
+        assert_eq!(response.classifications["a"].label, ["sql:irreversible"]);
+        assert_eq!(
+            response.raw_labels["a"],
+            ["imports:added", "sql:irreversible"]
+        );
+        assert!(!response.raw_labels.contains_key("b"));
+    }
+}
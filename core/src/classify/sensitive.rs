@@ -0,0 +1,157 @@
+//! Security-sensitive path policy.
+//!
+//! A repo can list the areas where every change needs a human — `auth/`,
+//! `crypto/`, `payments/` — in `.review/sensitive-paths`, one gitignore-style
+//! glob per line (`#` comments and blank lines ignored). Hunks in matching
+//! files get the [`SENSITIVE_AREA_LABEL`] label on top of whatever the static
+//! rules found. The label is never trusted: a hunk carrying it is excluded from
+//! auto-approval even when its other labels are on the trust list, and it sorts
+//! ahead of everything else in hunk listings.
+
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use super::{ClassificationResult, ClassifyResponse, Evidence};
+use crate::diff::parser::DiffHunk;
+
+/// Repo-relative path of the sensitive path list.
+pub const SENSITIVE_PATHS_PATH: &str = ".review/sensitive-paths";
+
+/// Label added to hunks in sensitive areas.
+pub const SENSITIVE_AREA_LABEL: &str = "security:sensitive-area";
+
+/// The repo's sensitive path globs.
+#[derive(Debug)]
+pub struct SensitivePaths {
+    matcher: Gitignore,
+}
+
+impl SensitivePaths {
+    /// Parse a sensitive path list. Invalid globs are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut builder = GitignoreBuilder::new("");
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Err(e) = builder.add_line(None, line) {
+                log::warn!("[sensitive_paths] Skipping invalid glob {line:?}: {e}");
+            }
+        }
+        Self {
+            matcher: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+        }
+    }
+
+    /// Read the repo's list. `None` when the repo has none (or it can't be
+    /// read).
+    pub fn load(repo_path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(repo_path.join(SENSITIVE_PATHS_PATH)).ok()?;
+        Some(Self::parse(&content))
+    }
+
+    /// The glob that marks `file_path` sensitive, if any. A directory glob
+    /// covers everything beneath it; `!pattern` lines carve out exceptions.
+    pub fn matching_glob(&self, file_path: &str) -> Option<&str> {
+        let matched = self.matcher.matched_path_or_any_parents(file_path, false);
+        if matched.is_ignore() {
+            matched.inner().map(|glob| glob.original())
+        } else {
+            None
+        }
+    }
+
+    /// Add [`SENSITIVE_AREA_LABEL`] to the classification of every hunk in a
+    /// sensitive file, classifying hunks the static rules skipped.
+    pub fn apply(&self, hunks: &[DiffHunk], response: &mut ClassifyResponse) {
+        for hunk in hunks {
+            let Some(glob) = self.matching_glob(&hunk.file_path) else {
+                continue;
+            };
+            let reasoning = format!("File is in a sensitive area ({glob})");
+            let result = response
+                .classifications
+                .entry(hunk.id.clone())
+                .or_insert_with(|| ClassificationResult {
+                    label: Vec::new(),
+                    reasoning: String::new(),
+                    evidence: Vec::new(),
+                });
+            if !result.label.iter().any(|l| l == SENSITIVE_AREA_LABEL) {
+                result.label.insert(0, SENSITIVE_AREA_LABEL.to_owned());
+                result.evidence.insert(
+                    0,
+                    Evidence {
+                        rule: "sensitive-path".to_owned(),
+                        matched: Some(glob.to_owned()),
+                        ..Evidence::default()
+                    },
+                );
+            }
+            result.reasoning = if result.reasoning.is_empty() {
+                reasoning
+            } else {
+                format!("{reasoning}; {}", result.reasoning)
+            };
+        }
+    }
+}
+
+/// Whether `labels` mark a hunk as being in a sensitive area.
+pub fn is_sensitive(labels: &[String]) -> bool {
+    labels.iter().any(|l| l == SENSITIVE_AREA_LABEL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classify::classify_hunks_static;
+    use crate::diff::parser::LineType;
+
+    #[test]
+    fn matches_directory_and_file_globs() {
+        let policy = SensitivePaths::parse(
+            "# Areas that always need a human\nauth/\n/src/crypto/**\n*.pem\n!auth/README.md\n",
+        );
+        assert_eq!(policy.matching_glob("auth/session.rs"), Some("auth/"));
+        assert_eq!(
+            policy.matching_glob("services/auth/token.go"),
+            Some("auth/")
+        );
+        assert_eq!(
+            policy.matching_glob("src/crypto/aes.rs"),
+            Some("/src/crypto/**")
+        );
+        assert_eq!(policy.matching_glob("certs/ca.pem"), Some("*.pem"));
+        assert_eq!(policy.matching_glob("auth/README.md"), None);
+        assert_eq!(policy.matching_glob("lib/crypto/aes.rs"), None);
+        assert_eq!(policy.matching_glob("src/authz.rs"), None);
+    }
+
+    #[test]
+    fn apply_adds_label_alongside_static_labels() {
+        let hunks = [
+            DiffHunk::test("auth/login.py", [(LineType::Added, "import os")]).with_id("a"),
+            DiffHunk::test("auth/login.py", [(LineType::Added, "check_password(user)")])
+                .with_id("b"),
+            DiffHunk::test("app/main.py", [(LineType::Added, "import os")]).with_id("c"),
+        ];
+        let mut response = classify_hunks_static(&hunks);
+        SensitivePaths::parse("auth/").apply(&hunks, &mut response);
+
+        let imports = &response.classifications["a"];
+        assert_eq!(imports.label, [SENSITIVE_AREA_LABEL, "imports:added"]);
+        assert!(imports
+            .reasoning
+            .starts_with("File is in a sensitive area (auth/)"));
+        let rules: Vec<&str> = imports.evidence.iter().map(|e| e.rule.as_str()).collect();
+        assert_eq!(rules, ["sensitive-path", "imports"]);
+        assert_eq!(imports.evidence[0].matched.as_deref(), Some("auth/"));
+        // Hunks no static rule matched are classified too.
+        assert_eq!(response.classifications["b"].label, [SENSITIVE_AREA_LABEL]);
+        assert_eq!(response.classifications["c"].label, ["imports:added"]);
+        assert!(is_sensitive(&imports.label));
+    }
+}
@@ -3,9 +3,31 @@
 //! Detects easily-identifiable patterns (lockfiles, whitespace-only changes,
 //! comment additions, import additions, etc.) without any external calls.
 //! All rules are conservative: if uncertain, return `None`.
-
-use crate::classify::{ClassificationResult, ClassifyResponse};
+//!
+//! Infrastructure files (Dockerfiles, GitHub workflows, Kubernetes manifests,
+//! Terraform) get a file-type label when no finer rule applies, and obviously
+//! risky edits to them get a dedicated label that outranks every other rule.
+//!
+//! Hunks that delete or skip tests, or drop assertions without replacing them,
+//! get [`TESTS_REMOVED_LABEL`], and changes to license files, license headers,
+//! or manifest license fields get [`LICENSE_CHANGE_LABEL`]. Neither is ever
+//! trusted. Translation catalogs are labeled when no finer rule applies.
+//!
+//! Hunks that remove an API operation or protobuf declaration, or renumber or
+//! retype a protobuf field, get [`API_BREAKING_LABEL`]. Within a single hunk
+//! that's all we can see; [`crate::diff::api_schema`] compares whole files.
+//! Likewise, migrations whose forward half destroys data get
+//! [`IRREVERSIBLE_LABEL`]; missing down migrations are found per file by
+//! [`crate::classify::migrations`].
+
+use crate::classify::migrations::{destructive_lines, migration_format, IRREVERSIBLE_LABEL};
+use crate::classify::{ClassificationResult, ClassifyResponse, Evidence, EvidenceLine};
+use crate::diff::api_schema::{
+    is_api_schema_path, parse_proto_line, strip_proto_comments, ProtoDecl, API_BREAKING_LABEL,
+    HTTP_METHODS,
+};
 use crate::diff::parser::{DiffHunk, DiffLine, LineType};
+use crate::diff::translations::is_translation_file;
 use std::collections::HashMap;
 
 /// Classify hunks using static pattern matching (no I/O).
@@ -21,21 +43,72 @@ pub fn classify_hunks_static(hunks: &[DiffHunk]) -> ClassifyResponse {
         }
     }
 
-    ClassifyResponse { classifications }
+    ClassifyResponse {
+        classifications,
+        limits_exceeded: Vec::new(),
+        raw_labels: HashMap::new(),
+    }
 }
 
 /// Attempt to classify a single hunk. Returns `None` if no rule matches.
+/// A static rule: its name (reported as [`Evidence::rule`]), whether it only
+/// looks at the file path, and the rule itself.
+type Rule = (
+    &'static str,
+    bool,
+    fn(&DiffHunk) -> Option<ClassificationResult>,
+);
+
+/// Risky infrastructure edits first, so no trusted label can mask them; then
+/// cheapest checks first.
+const RULES: &[Rule] = &[
+    ("infra-risk", false, classify_infra_risk),
+    ("api-breaking", false, classify_api_breaking),
+    (
+        "migration-irreversible",
+        false,
+        classify_migration_irreversible,
+    ),
+    ("moved", false, classify_moved),
+    ("tests-removed", false, classify_tests_removed),
+    ("license-change", false, classify_license_change),
+    ("lockfile", true, classify_lockfile),
+    ("empty-file", true, classify_empty_file),
+    ("whitespace", false, classify_whitespace),
+    ("line-length", false, classify_line_length),
+    ("style", false, classify_style),
+    ("comments", false, classify_comments),
+    ("type-annotations", false, classify_type_annotations),
+    ("imports", false, classify_imports),
+    ("infra", true, classify_infra),
+    ("translations", true, classify_translations),
+];
+
+/// Runs the rules in order and returns the first match, with its evidence
+/// attributed to the rule. Rules that don't pinpoint lines themselves are
+/// taken to have matched every changed line (none, for path rules).
 fn classify_single_hunk(hunk: &DiffHunk) -> Option<ClassificationResult> {
-    // Priority order: cheapest checks first
-    classify_moved(hunk)
-        .or_else(|| classify_lockfile(hunk))
-        .or_else(|| classify_empty_file(hunk))
-        .or_else(|| classify_whitespace(hunk))
-        .or_else(|| classify_line_length(hunk))
-        .or_else(|| classify_style(hunk))
-        .or_else(|| classify_comments(hunk))
-        .or_else(|| classify_type_annotations(hunk))
-        .or_else(|| classify_imports(hunk))
+    RULES.iter().find_map(|&(name, path_only, rule)| {
+        let mut result = rule(hunk)?;
+        if result.evidence.is_empty() {
+            let lines = if path_only {
+                Vec::new()
+            } else {
+                get_changed_lines(&hunk.lines)
+                    .into_iter()
+                    .map(EvidenceLine::from)
+                    .collect()
+            };
+            result.evidence.push(Evidence {
+                lines,
+                ..Evidence::default()
+            });
+        }
+        for evidence in &mut result.evidence {
+            name.clone_into(&mut evidence.rule);
+        }
+        Some(result)
+    })
 }
 
 // --- Rule 0: Move pair detection (cheapest: single field check) ---
@@ -46,6 +119,7 @@ fn classify_moved(hunk: &DiffHunk) -> Option<ClassificationResult> {
             label: vec!["move:code".to_owned()],
             reasoning: "Hunk is part of a move pair (identical content moved between files)"
                 .to_owned(),
+            evidence: Vec::new(),
         })
     } else {
         None
@@ -54,7 +128,8 @@ fn classify_moved(hunk: &DiffHunk) -> Option<ClassificationResult> {
 
 // --- Rule 1: Lockfile detection (path-based) ---
 
-const LOCKFILE_NAMES: &[&str] = &[
+/// Package manager lockfiles, by file name.
+pub(crate) const LOCKFILE_NAMES: &[&str] = &[
     "package-lock.json",
     "yarn.lock",
     "pnpm-lock.yaml",
@@ -72,6 +147,7 @@ const LOCKFILE_NAMES: &[&str] = &[
     "paket.lock",
     "pdm.lock",
     "uv.lock",
+    ".terraform.lock.hcl",
 ];
 
 fn classify_lockfile(hunk: &DiffHunk) -> Option<ClassificationResult> {
@@ -80,6 +156,7 @@ fn classify_lockfile(hunk: &DiffHunk) -> Option<ClassificationResult> {
         Some(ClassificationResult {
             label: vec!["generated:lockfile".to_owned()],
             reasoning: "File is a package manager lockfile".to_owned(),
+            evidence: Vec::new(),
         })
     } else {
         None
@@ -103,6 +180,7 @@ fn classify_empty_file(hunk: &DiffHunk) -> Option<ClassificationResult> {
         Some(ClassificationResult {
             label: vec!["file:added-empty".to_owned()],
             reasoning: "New empty file (no content or whitespace only)".to_owned(),
+            evidence: Vec::new(),
         })
     } else {
         None
@@ -125,6 +203,7 @@ fn classify_whitespace(hunk: &DiffHunk) -> Option<ClassificationResult> {
         Some(ClassificationResult {
             label: vec!["formatting:whitespace".to_owned()],
             reasoning: "All changed lines are empty or whitespace-only".to_owned(),
+            evidence: Vec::new(),
         })
     } else {
         None
@@ -171,6 +250,7 @@ fn classify_line_length(hunk: &DiffHunk) -> Option<ClassificationResult> {
             label: vec!["formatting:line-length".to_owned()],
             reasoning: "Code wrapped or unwrapped across lines (identical content after joining)"
                 .to_owned(),
+            evidence: Vec::new(),
         })
     } else {
         None
@@ -228,6 +308,7 @@ fn classify_style(hunk: &DiffHunk) -> Option<ClassificationResult> {
             label: vec!["formatting:style".to_owned()],
             reasoning: "Only punctuation changed (semicolons, quote style, or trailing commas)"
                 .to_owned(),
+            evidence: Vec::new(),
         })
     } else {
         None
@@ -479,6 +560,7 @@ fn classify_comments(hunk: &DiffHunk) -> Option<ClassificationResult> {
                     return Some(ClassificationResult {
                         label: vec![label.to_owned()],
                         reasoning: "Only inline comments changed; code is identical".to_owned(),
+                        evidence: Vec::new(),
                     });
                 }
             }
@@ -502,6 +584,7 @@ fn classify_comments(hunk: &DiffHunk) -> Option<ClassificationResult> {
     Some(ClassificationResult {
         label: vec![label.to_owned()],
         reasoning: "All changed lines are comments".to_owned(),
+        evidence: Vec::new(),
     })
 }
 
@@ -786,6 +869,7 @@ fn classify_type_annotations(hunk: &DiffHunk) -> Option<ClassificationResult> {
                 Some(ClassificationResult {
                     label: vec!["type-annotations:modified".to_owned()],
                     reasoning: "Stripping type annotations leaves identical code".to_owned(),
+                    evidence: Vec::new(),
                 })
             } else {
                 None
@@ -942,10 +1026,12 @@ fn classify_imports(hunk: &DiffHunk) -> Option<ClassificationResult> {
         (true, false) => Some(ClassificationResult {
             label: vec!["imports:added".to_owned()],
             reasoning: "All changed lines are import statements (additions only)".to_owned(),
+            evidence: Vec::new(),
         }),
         (false, true) => Some(ClassificationResult {
             label: vec!["imports:removed".to_owned()],
             reasoning: "All changed lines are import statements (removals only)".to_owned(),
+            evidence: Vec::new(),
         }),
         (true, true) => {
             // Check if it's a reorder: same imports, different order
@@ -953,11 +1039,13 @@ fn classify_imports(hunk: &DiffHunk) -> Option<ClassificationResult> {
                 Some(ClassificationResult {
                     label: vec!["imports:reordered".to_owned()],
                     reasoning: "Import statements were reordered (same set of imports)".to_owned(),
+                    evidence: Vec::new(),
                 })
             } else {
                 Some(ClassificationResult {
                     label: vec!["imports:modified".to_owned()],
                     reasoning: "All changed lines are import statements (modified)".to_owned(),
+                    evidence: Vec::new(),
                 })
             }
         }
@@ -1002,8 +1090,490 @@ fn normalize_import(line: &str) -> String {
     line.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
+// --- Rule 9: Infrastructure file types ---
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InfraKind {
+    Dockerfile,
+    Workflow,
+    Kubernetes,
+    Terraform,
+}
+
+/// Directory names that mark YAML underneath as Kubernetes manifests.
+const K8S_DIRS: &[&str] = &["k8s", "kubernetes", "manifests", "kustomize"];
+
+fn infra_kind(hunk: &DiffHunk) -> Option<InfraKind> {
+    let path = hunk.file_path.as_str();
+    let filename = path.rsplit('/').next().unwrap_or(path);
+    let ext = filename.rsplit_once('.').map(|(_, ext)| ext);
+
+    if filename == "Dockerfile"
+        || filename == "Containerfile"
+        || filename.starts_with("Dockerfile.")
+        || ext == Some("dockerfile")
+    {
+        return Some(InfraKind::Dockerfile);
+    }
+    if matches!(ext, Some("tf" | "tfvars")) {
+        return Some(InfraKind::Terraform);
+    }
+    if !matches!(ext, Some("yml" | "yaml")) {
+        return None;
+    }
+    if path.starts_with(".github/workflows/") || path.contains("/.github/workflows/") {
+        return Some(InfraKind::Workflow);
+    }
+    is_k8s_manifest(hunk, filename).then_some(InfraKind::Kubernetes)
+}
+
+/// A YAML hunk is a Kubernetes manifest if it's a kustomization, lives under a
+/// conventional manifests directory, or shows both `apiVersion:` and `kind:`.
+fn is_k8s_manifest(hunk: &DiffHunk, filename: &str) -> bool {
+    if filename.starts_with("kustomization.") {
+        return true;
+    }
+    let mut dirs = hunk.file_path.split('/').rev().skip(1);
+    if dirs.any(|dir| K8S_DIRS.contains(&dir)) {
+        return true;
+    }
+    let has_key = |key: &str| {
+        hunk.lines
+            .iter()
+            .any(|l| l.content.trim_start().starts_with(key))
+    };
+    has_key("apiVersion:") && has_key("kind:")
+}
+
+fn classify_infra(hunk: &DiffHunk) -> Option<ClassificationResult> {
+    let (label, reasoning) = match infra_kind(hunk)? {
+        InfraKind::Dockerfile => ("infra:dockerfile", "File is a Dockerfile"),
+        InfraKind::Workflow => ("ci:workflow-change", "File is a GitHub Actions workflow"),
+        InfraKind::Kubernetes => ("infra:k8s-resource", "File is a Kubernetes manifest"),
+        InfraKind::Terraform => ("infra:terraform", "File is Terraform configuration"),
+    };
+    Some(ClassificationResult {
+        label: vec![label.to_owned()],
+        reasoning: reasoning.to_owned(),
+        evidence: Vec::new(),
+    })
+}
+
+// --- Rule 10: Risky infrastructure edits ---
+
+/// `privileged: true`, `allowPrivilegeEscalation: true`, or the Terraform
+/// `privileged = true` / `allow_privilege_escalation = true` forms.
+fn enables_privilege(line: &str) -> bool {
+    let Some((key, value)) = line.split_once([':', '=']) else {
+        return false;
+    };
+    let key = key.trim().trim_start_matches("- ").trim_matches('"');
+    let value = value.split('#').next().unwrap_or_default().trim();
+    matches!(
+        key,
+        "privileged" | "allowPrivilegeEscalation" | "allow_privilege_escalation"
+    ) && value.trim_matches('"') == "true"
+}
+
+/// Label for workflow changes that add a `pull_request_target` trigger.
+pub const PULL_REQUEST_TARGET_LABEL: &str = "ci:pull-request-target";
+
+/// Label for Kubernetes or Terraform changes that add a privileged container
+/// or allow privilege escalation.
+pub const PRIVILEGED_CONTAINER_LABEL: &str = "infra:privileged-container";
+
+fn classify_infra_risk(hunk: &DiffHunk) -> Option<ClassificationResult> {
+    let (label, reasoning, matches): (_, _, fn(&str) -> bool) = match infra_kind(hunk)? {
+        InfraKind::Workflow => (
+            PULL_REQUEST_TARGET_LABEL,
+            "Workflow adds a pull_request_target trigger, which runs fork PRs with secrets and a write token",
+            |l| l.contains("pull_request_target"),
+        ),
+        InfraKind::Kubernetes | InfraKind::Terraform => (
+            PRIVILEGED_CONTAINER_LABEL,
+            "Adds a privileged container or allows privilege escalation",
+            enables_privilege,
+        ),
+        InfraKind::Dockerfile => return None,
+    };
+    let evidence = matching_lines(hunk, &LineType::Added, &|l| {
+        !l.starts_with('#') && !l.starts_with("//") && matches(l)
+    })?;
+    Some(ClassificationResult {
+        label: vec![label.to_owned()],
+        reasoning: reasoning.to_owned(),
+        evidence: vec![evidence],
+    })
+}
+
+// --- Rule 11: Removed tests and weakened assertions ---
+
+/// Label for hunks that remove or skip tests, or drop assertions.
+pub const TESTS_REMOVED_LABEL: &str = "tests:removed";
+
+/// Whether the path looks like a test file (by directory or naming convention).
+pub(crate) fn is_test_path(path: &str) -> bool {
+    let filename = path.rsplit('/').next().unwrap_or(path);
+    let stem = filename.split('.').next().unwrap_or(filename);
+    path.split('/')
+        .rev()
+        .skip(1)
+        .any(|dir| matches!(dir, "test" | "tests" | "__tests__" | "spec" | "specs"))
+        || stem.starts_with("test_")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || stem.ends_with("Test")
+        || stem.ends_with("Tests")
+        || filename.contains(".test.")
+        || filename.contains(".spec.")
+}
+
+/// Trimmed, non-comment lines of one side of the hunk.
+fn code_lines(hunk: &DiffHunk, line_type: LineType) -> impl Iterator<Item = &str> {
+    hunk.lines
+        .iter()
+        .filter(move |l| l.line_type == line_type)
+        .map(|l| l.content.trim())
+        .filter(|l| (!l.starts_with("//") && !l.starts_with('#')) || l.starts_with("#["))
+}
+
+/// A line that declares a test case.
+fn is_test_declaration(line: &str) -> bool {
+    line == "#[test]"
+        || line.starts_with("#[tokio::test")
+        || line.starts_with("@Test")
+        || line.starts_with("def test")
+        || line.starts_with("async def test")
+        || line.starts_with("func Test")
+        || [
+            "it(",
+            "test(",
+            "it.each",
+            "test.each",
+            "it.skip(",
+            "test.skip(",
+            "xit(",
+        ]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+/// A line that disables a test case. (JS skips also count as declarations, so
+/// `it(` → `it.skip(` reads as a skip rather than a removal.)
+fn is_test_skip(line: &str) -> bool {
+    line.starts_with("#[ignore")
+        || line.starts_with("@pytest.mark.skip")
+        || line.starts_with("@unittest.skip")
+        || line.starts_with("@Disabled")
+        || line.starts_with("@Ignore")
+        || line.starts_with("t.Skip")
+        || [
+            "it.skip(",
+            "test.skip(",
+            "describe.skip(",
+            "xit(",
+            "xdescribe(",
+        ]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+/// A line that asserts something. Rust's `.expect(...)` unwraps rather than
+/// asserts, so `expect(` only counts outside Rust.
+fn is_assertion(line: &str, rust: bool) -> bool {
+    let line = line.strip_prefix("await ").unwrap_or(line);
+    line.starts_with("assert")
+        || line.starts_with("self.assert")
+        || line.starts_with("XCTAssert")
+        || line.starts_with("require.")
+        || line.starts_with("t.Error")
+        || line.starts_with("t.Fatal")
+        || (!rust && line.starts_with("expect("))
+}
+
+fn classify_tests_removed(hunk: &DiffHunk) -> Option<ClassificationResult> {
+    let count = |line_type: LineType, pred: &dyn Fn(&str) -> bool| {
+        code_lines(hunk, line_type).filter(|l| pred(l)).count()
+    };
+
+    let removed_tests = count(LineType::Removed, &is_test_declaration)
+        .saturating_sub(count(LineType::Added, &is_test_declaration));
+    if removed_tests > 0 {
+        return Some(tests_removed(
+            hunk,
+            &LineType::Removed,
+            &is_test_declaration,
+            format!("Removes {removed_tests} test case(s)"),
+        ));
+    }
+
+    let skipped = count(LineType::Added, &is_test_skip)
+        .saturating_sub(count(LineType::Removed, &is_test_skip));
+    if skipped > 0 {
+        return Some(tests_removed(
+            hunk,
+            &LineType::Added,
+            &is_test_skip,
+            format!("Skips {skipped} test case(s)"),
+        ));
+    }
+
+    // Assertions are only judged where tests live: test files, and Rust
+    // sources (whose tests are inline).
+    let rust = hunk.file_path.rsplit('.').next() == Some("rs");
+    if !rust && !is_test_path(&hunk.file_path) {
+        return None;
+    }
+    let is_assert = |line: &str| is_assertion(line, rust);
+    let dropped =
+        count(LineType::Removed, &is_assert).saturating_sub(count(LineType::Added, &is_assert));
+    if dropped > 0 {
+        return Some(tests_removed(
+            hunk,
+            &LineType::Removed,
+            &is_assert,
+            format!("Removes {dropped} assertion(s) without replacements"),
+        ));
+    }
+    None
+}
+
+fn tests_removed(
+    hunk: &DiffHunk,
+    line_type: &LineType,
+    pred: &dyn Fn(&str) -> bool,
+    reasoning: String,
+) -> ClassificationResult {
+    ClassificationResult {
+        label: vec![TESTS_REMOVED_LABEL.to_owned()],
+        reasoning,
+        evidence: matching_lines(hunk, line_type, pred).into_iter().collect(),
+    }
+}
+
+// --- Rule 12: License and copyright changes ---
+
+/// Label for hunks that change licensing: license files, license or copyright
+/// headers, and manifest `license` fields.
+pub const LICENSE_CHANGE_LABEL: &str = "legal:license-change";
+
+/// `LICENSE`, `LICENSE-MIT`, `COPYING.txt`, `NOTICE`, … (any case).
+fn is_license_file(path: &str) -> bool {
+    let filename = path.rsplit('/').next().unwrap_or(path).to_ascii_lowercase();
+    let (stem, ext) = filename.split_once('.').unwrap_or((&filename, ""));
+    matches!(ext, "" | "md" | "txt" | "rst" | "markdown")
+        && ["license", "licence", "unlicense", "copying", "notice"]
+            .iter()
+            .any(|name| stem == *name || stem.starts_with(&format!("{name}-")))
+}
+
+/// Package manifests whose `license` field declares the project's license.
+const LICENSE_MANIFESTS: &[&str] = &[
+    "package.json",
+    "Cargo.toml",
+    "pyproject.toml",
+    "composer.json",
+    "setup.cfg",
+];
+
+pub(crate) fn is_comment_text(line: &str) -> bool {
+    ["//", "#", "/*", "*", "<!--", "--", ";"]
+        .iter()
+        .any(|prefix| line.starts_with(prefix))
+}
+
+/// A comment line that's part of a license or copyright header.
+fn is_license_header_line(line: &str) -> bool {
+    if !is_comment_text(line) {
+        return false;
+    }
+    let lower = line.to_ascii_lowercase();
+    [
+        "spdx-license-identifier",
+        "copyright",
+        "licensed under",
+        "all rights reserved",
+        "permission is hereby granted",
+    ]
+    .iter()
+    .any(|marker| lower.contains(marker))
+}
+
+/// `"license": "MIT"` / `license = "MIT"` (also `license-file`).
+fn is_license_field(line: &str) -> bool {
+    let key = line.trim_start_matches('"');
+    ["license-file", "license_file", "license"]
+        .iter()
+        .find_map(|name| key.strip_prefix(name))
+        .is_some_and(|rest| {
+            rest.trim_start_matches('"')
+                .trim_start()
+                .starts_with([':', '='])
+        })
+}
+
+fn classify_license_change(hunk: &DiffHunk) -> Option<ClassificationResult> {
+    let reasoning = if is_license_file(&hunk.file_path) {
+        "File is a license file"
+    } else {
+        let filename = hunk.file_path.rsplit('/').next().unwrap_or(&hunk.file_path);
+        let manifest = LICENSE_MANIFESTS.contains(&filename);
+        let changed = get_changed_lines(&hunk.lines);
+        if changed
+            .iter()
+            .any(|l| is_license_header_line(l.content.trim()))
+        {
+            "Changes a license or copyright header"
+        } else if manifest && changed.iter().any(|l| is_license_field(l.content.trim())) {
+            "Changes the package's declared license"
+        } else {
+            return None;
+        }
+    };
+    Some(ClassificationResult {
+        label: vec![LICENSE_CHANGE_LABEL.to_owned()],
+        reasoning: reasoning.to_owned(),
+        evidence: Vec::new(),
+    })
+}
+
+// --- Rule 13: Translation catalogs ---
+
+fn classify_translations(hunk: &DiffHunk) -> Option<ClassificationResult> {
+    is_translation_file(&hunk.file_path).then(|| ClassificationResult {
+        label: vec!["i18n:translations".to_owned()],
+        reasoning: "File is a translation catalog".to_owned(),
+        evidence: Vec::new(),
+    })
+}
+
+// --- Rule 14: Breaking API definition changes ---
+
+/// A removed declaration's identity within the hunk, and how to describe it.
+fn proto_decl_key(decl: &ProtoDecl) -> (String, String) {
+    match decl {
+        ProtoDecl::Block { kind, name } => (format!("{kind} {name}"), format!("{kind} `{name}`")),
+        ProtoDecl::Field { name, .. } => (format!("field {name}"), format!("field `{name}`")),
+        ProtoDecl::EnumValue { name, .. } => {
+            (format!("value {name}"), format!("enum value `{name}`"))
+        }
+        ProtoDecl::Rpc { name, .. } => (format!("rpc {name}"), format!("rpc `{name}`")),
+    }
+}
+
+fn classify_proto_breaking(hunk: &DiffHunk) -> Option<String> {
+    let decls = |line_type: LineType| {
+        let mut in_block = false;
+        hunk.lines
+            .iter()
+            .filter(|l| l.line_type == line_type)
+            .filter_map(|l| {
+                parse_proto_line(strip_proto_comments(&l.content, &mut in_block).trim())
+            })
+            .filter(|decl| !matches!(decl, ProtoDecl::Block { kind, .. } if kind == "oneof"))
+            .collect::<Vec<_>>()
+    };
+    let added: HashMap<String, ProtoDecl> = decls(LineType::Added)
+        .into_iter()
+        .map(|decl| (proto_decl_key(&decl).0, decl))
+        .collect();
+
+    for before in decls(LineType::Removed) {
+        let (key, what) = proto_decl_key(&before);
+        let reason = match (&before, added.get(&key)) {
+            (_, None) => format!("Removes {what}"),
+            (
+                ProtoDecl::Field { number: old, .. } | ProtoDecl::EnumValue { number: old, .. },
+                Some(
+                    ProtoDecl::Field { number: new, .. } | ProtoDecl::EnumValue { number: new, .. },
+                ),
+            ) if old != new => format!("Renumbers {what} ({old} → {new})"),
+            (
+                ProtoDecl::Field { type_name: old, .. },
+                Some(ProtoDecl::Field { type_name: new, .. }),
+            ) if old != new => format!("Changes the type of {what} ({old} → {new})"),
+            (
+                ProtoDecl::Rpc { signature: old, .. },
+                Some(ProtoDecl::Rpc { signature: new, .. }),
+            ) if old != new => format!("Changes the signature of {what}"),
+            _ => continue,
+        };
+        return Some(reason);
+    }
+    None
+}
+
+/// The key of an OpenAPI path (`/users/{id}:`) or operation (`get:`, or
+/// `"get": {` in JSON) line.
+fn openapi_route_key(line: &str) -> Option<&str> {
+    let (key, rest) = line.split_once(':')?;
+    let key = key.trim().trim_matches(['"', '\'']);
+    let rest = rest.trim();
+    ((rest.is_empty() || rest == "{") && (key.starts_with('/') || HTTP_METHODS.contains(&key)))
+        .then_some(key)
+}
+
+fn classify_api_breaking(hunk: &DiffHunk) -> Option<ClassificationResult> {
+    if !is_api_schema_path(&hunk.file_path) {
+        return None;
+    }
+    let ext = hunk.file_path.rsplit('.').next().unwrap_or("");
+    let reasoning = if ext.eq_ignore_ascii_case("proto") {
+        classify_proto_breaking(hunk)?
+    } else {
+        let added: Vec<&str> = code_lines(hunk, LineType::Added).collect();
+        let removed = code_lines(hunk, LineType::Removed)
+            .filter(|line| !added.contains(line))
+            .find_map(openapi_route_key)?;
+        format!("Removes API route or operation `{removed}`")
+    };
+    Some(ClassificationResult {
+        label: vec![API_BREAKING_LABEL.to_owned()],
+        reasoning,
+        evidence: Vec::new(),
+    })
+}
+
+// --- Rule 15: Destructive migrations ---
+
+fn classify_migration_irreversible(hunk: &DiffHunk) -> Option<ClassificationResult> {
+    let format = migration_format(&hunk.file_path)?;
+    let lines = hunk.lines.iter().map(|l| l.content.as_str());
+    let (i, op) = destructive_lines(format, lines)
+        .into_iter()
+        .find(|(i, _)| hunk.lines[*i].line_type == LineType::Added)?;
+    Some(ClassificationResult {
+        label: vec![IRREVERSIBLE_LABEL.to_owned()],
+        reasoning: format!("Migration runs {op}, which can't be undone"),
+        evidence: vec![Evidence {
+            lines: vec![EvidenceLine::from(&hunk.lines[i])],
+            matched: Some(op.to_owned()),
+            ..Evidence::default()
+        }],
+    })
+}
+
 // --- Helpers ---
 
+/// Evidence pointing at the lines of `line_type` whose trimmed content
+/// satisfies `pred`, if there are any.
+fn matching_lines(
+    hunk: &DiffHunk,
+    line_type: &LineType,
+    pred: &dyn Fn(&str) -> bool,
+) -> Option<Evidence> {
+    let lines: Vec<EvidenceLine> = hunk
+        .lines
+        .iter()
+        .filter(|l| l.line_type == *line_type && pred(l.content.trim()))
+        .map(EvidenceLine::from)
+        .collect();
+    (!lines.is_empty()).then(|| Evidence {
+        lines,
+        ..Evidence::default()
+    })
+}
+
 fn get_changed_lines(lines: &[DiffLine]) -> Vec<&DiffLine> {
     lines
         .iter()
@@ -1017,16 +1587,8 @@ mod tests {
 
     fn make_hunk(file_path: &str, lines: Vec<DiffLine>) -> DiffHunk {
         DiffHunk {
-            id: format!("{}:testhash", file_path),
-            file_path: file_path.to_owned(),
-            old_start: 1,
-            old_count: 0,
-            new_start: 1,
-            new_count: 0,
-            content: String::new(),
             lines,
-            content_hash: "testhash".to_owned(),
-            move_pair_id: None,
+            ..DiffHunk::test(file_path, []).with_id(&format!("{file_path}:testhash"))
         }
     }
 
@@ -1862,4 +2424,473 @@ mod tests {
         assert!(result.is_some());
         assert_eq!(result.unwrap().label, vec!["generated:lockfile"]);
     }
+
+    // --- Infrastructure tests ---
+
+    #[test]
+    fn test_infra_file_types() {
+        let cases = [
+            ("Dockerfile", "RUN apt-get update", "infra:dockerfile"),
+            ("docker/api.dockerfile", "EXPOSE 8080", "infra:dockerfile"),
+            (
+                ".github/workflows/ci.yml",
+                "runs-on: ubuntu-latest",
+                "ci:workflow-change",
+            ),
+            ("deploy/k8s/api.yaml", "replicas: 3", "infra:k8s-resource"),
+            (
+                "infra/main.tf",
+                "instance_type = \"t3.large\"",
+                "infra:terraform",
+            ),
+        ];
+        for (path, line, label) in cases {
+            let hunk = make_hunk(path, vec![removed("x"), added(line)]);
+            let result = classify_single_hunk(&hunk);
+            assert_eq!(result.unwrap().label, vec![label], "{path}");
+        }
+    }
+
+    #[test]
+    fn test_k8s_manifest_detected_by_content() {
+        let hunk = make_hunk(
+            "deploy/api.yaml",
+            vec![
+                context("apiVersion: apps/v1"),
+                context("kind: Deployment"),
+                removed("  replicas: 2"),
+                added("  replicas: 3"),
+            ],
+        );
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["infra:k8s-resource"]);
+
+        // Plain YAML config is not infrastructure.
+        let hunk = make_hunk("config/app.yaml", vec![removed("a: 1"), added("a: 2")]);
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    #[test]
+    fn test_infra_finer_rules_still_apply() {
+        let hunk = make_hunk(".github/workflows/ci.yml", vec![added("# Build stage")]);
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["comments:added"]);
+    }
+
+    #[test]
+    fn test_proto_breaking_changes() {
+        let classify = |lines| classify_single_hunk(&make_hunk("api/user.proto", lines));
+
+        let result = classify(vec![
+            context("message User {"),
+            removed("  string email = 2;"),
+            added("  string email = 4;"),
+        ])
+        .unwrap();
+        assert_eq!(result.label, vec!["api:breaking-change"]);
+        assert_eq!(result.reasoning, "Renumbers field `email` (2 → 4)");
+
+        let result = classify(vec![removed(
+            "  rpc Delete(DeleteRequest) returns (Empty);",
+        )])
+        .unwrap();
+        assert_eq!(result.reasoning, "Removes rpc `Delete`");
+        let result =
+            classify(vec![removed("  int32 age = 3;"), added("  int64 age = 3;")]).unwrap();
+        assert_eq!(
+            result.reasoning,
+            "Changes the type of field `age` (int32 → int64)"
+        );
+
+        // Adding fields, renaming options, or moving a field within the hunk is fine.
+        assert!(classify(vec![added("  string phone = 5;")])
+            .is_none_or(|r| r.label != ["api:breaking-change"]));
+        assert!(classify(vec![
+            removed("  string name = 1; // display name"),
+            added("  string name = 1; // full name"),
+        ])
+        .is_none_or(|r| r.label != ["api:breaking-change"]));
+    }
+
+    #[test]
+    fn test_openapi_breaking_changes() {
+        let hunk = make_hunk(
+            "docs/openapi.yaml",
+            vec![
+                context("  /users:"),
+                context("    get:"),
+                context("      summary: List users"),
+                removed("    delete:"),
+                removed("      summary: Delete all users"),
+            ],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.label, vec!["api:breaking-change"]);
+        assert_eq!(result.reasoning, "Removes API route or operation `delete`");
+
+        // Edits inside an operation aren't flagged from the hunk alone.
+        let hunk = make_hunk(
+            "docs/openapi.yaml",
+            vec![
+                removed("      summary: List users"),
+                added("      summary: List all users"),
+            ],
+        );
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    #[test]
+    fn test_destructive_migrations() {
+        let hunk = make_hunk(
+            "db/migrations/20240101_users.sql",
+            vec![
+                context("-- +goose Up"),
+                added("ALTER TABLE users DROP COLUMN email;"),
+                context("-- +goose Down"),
+                context("ALTER TABLE users ADD COLUMN email text;"),
+            ],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.label, vec!["sql:irreversible"]);
+        assert_eq!(
+            result.reasoning,
+            "Migration runs DROP COLUMN, which can't be undone"
+        );
+
+        // Dropping in the down half, or outside a migration, is expected.
+        let hunk = make_hunk(
+            "db/migrations/20240101_users.sql",
+            vec![context("-- +goose Down"), added("DROP TABLE users;")],
+        );
+        assert!(classify_single_hunk(&hunk).is_none());
+        let hunk = make_hunk("scripts/reset.sql", vec![added("TRUNCATE events;")]);
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    #[test]
+    fn test_translation_catalogs() {
+        let hunk = make_hunk(
+            "web/locales/de.json",
+            vec![
+                removed(r#"  "save": "Save","#),
+                added(r#"  "save": "Speichern","#),
+            ],
+        );
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["i18n:translations"]);
+
+        // Finer rules still win, and plain JSON is not a catalog.
+        let hunk = make_hunk("config/locales/fr.yml", vec![added("# Translator note")]);
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["comments:added"]);
+        let hunk = make_hunk(
+            "config/app.json",
+            vec![removed("  \"a\": 1"), added("  \"a\": 2")],
+        );
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    #[test]
+    fn test_pull_request_target_is_risky() {
+        let hunk = make_hunk(
+            ".github/workflows/pr.yml",
+            vec![
+                context("on:"),
+                removed("  pull_request:"),
+                added("  pull_request_target:"),
+            ],
+        );
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["ci:pull-request-target"]);
+
+        // Removing the trigger, or mentioning it in a comment, is not.
+        let hunk = make_hunk(
+            ".github/workflows/pr.yml",
+            vec![
+                removed("  pull_request_target:"),
+                added("  # pull_request_target: unsafe"),
+            ],
+        );
+        assert_ne!(
+            classify_single_hunk(&hunk).unwrap().label,
+            vec!["ci:pull-request-target"]
+        );
+    }
+
+    #[test]
+    fn test_privileged_container_is_risky() {
+        let hunk = make_hunk(
+            "k8s/daemonset.yaml",
+            vec![context("securityContext:"), added("  privileged: true")],
+        );
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["infra:privileged-container"]);
+
+        let hunk = make_hunk(
+            "infra/pod.tf",
+            vec![added("      allow_privilege_escalation = true")],
+        );
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["infra:privileged-container"]);
+
+        let hunk = make_hunk("k8s/pod.yaml", vec![added("  privileged: false")]);
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["infra:k8s-resource"]);
+    }
+
+    #[test]
+    fn test_terraform_lockfile() {
+        let hunk = make_hunk(".terraform.lock.hcl", vec![added("  version = \"5.0.0\"")]);
+        let result = classify_single_hunk(&hunk);
+        assert_eq!(result.unwrap().label, vec!["generated:lockfile"]);
+    }
+
+    // --- Removed tests ---
+
+    #[test]
+    fn test_removed_test_functions() {
+        let hunk = make_hunk(
+            "src/parser.rs",
+            vec![
+                removed("#[test]"),
+                removed("fn parses_empty() {"),
+                removed("    assert!(parse(\"\").is_empty());"),
+                removed("}"),
+            ],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.label, vec![TESTS_REMOVED_LABEL]);
+        assert_eq!(result.reasoning, "Removes 1 test case(s)");
+
+        let hunk = make_hunk(
+            "tests/test_api.py",
+            vec![
+                removed("def test_login(client):"),
+                removed("    assert client.login()"),
+            ],
+        );
+        assert_eq!(
+            classify_single_hunk(&hunk).unwrap().label,
+            vec![TESTS_REMOVED_LABEL]
+        );
+    }
+
+    #[test]
+    fn test_renamed_test_is_not_removed() {
+        let hunk = make_hunk(
+            "src/app.test.ts",
+            vec![
+                removed("it('works', () => {"),
+                added("it('renders the header', () => {"),
+            ],
+        );
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    #[test]
+    fn test_skipped_test_is_removed() {
+        let hunk = make_hunk(
+            "src/app.test.ts",
+            vec![
+                removed("it('works', () => {"),
+                added("it.skip('works', () => {"),
+            ],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.label, vec![TESTS_REMOVED_LABEL]);
+        assert_eq!(result.reasoning, "Skips 1 test case(s)");
+    }
+
+    #[test]
+    fn test_weakened_assertions() {
+        let hunk = make_hunk(
+            "src/app.test.ts",
+            vec![
+                context("const user = await login();"),
+                removed("expect(user.id).toBe(42);"),
+                removed("expect(user.admin).toBe(false);"),
+                added("expect(user).toBeDefined();"),
+            ],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.label, vec![TESTS_REMOVED_LABEL]);
+        assert_eq!(
+            result.reasoning,
+            "Removes 1 assertion(s) without replacements"
+        );
+
+        // Commenting an assertion out drops it too.
+        let hunk = make_hunk(
+            "src/lib.rs",
+            vec![removed("assert_eq!(x, 1);"), added("// assert_eq!(x, 1);")],
+        );
+        assert_eq!(
+            classify_single_hunk(&hunk).unwrap().label,
+            vec![TESTS_REMOVED_LABEL]
+        );
+    }
+
+    #[test]
+    fn test_assertions_outside_tests_ignored() {
+        // Rust's `.expect()` isn't an assertion.
+        let hunk = make_hunk(
+            "src/main.rs",
+            vec![
+                removed("let cfg = load().expect(\"config\");"),
+                added("let cfg = load()?;"),
+            ],
+        );
+        assert!(classify_single_hunk(&hunk).is_none());
+
+        // Nor is a Python `assert` in application code.
+        let hunk = make_hunk("app/models.py", vec![removed("assert user is not None")]);
+        assert!(classify_single_hunk(&hunk).is_none());
+
+        // Changing an assertion keeps it.
+        let hunk = make_hunk(
+            "tests/api_test.go",
+            vec![
+                removed("t.Errorf(\"got %d\", n)"),
+                added("t.Fatalf(\"got %d\", n)"),
+            ],
+        );
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    #[test]
+    fn test_moved_tests_not_removed() {
+        let mut hunk = make_hunk("src/old.rs", vec![removed("#[test]"), removed("fn a() {}")]);
+        hunk.move_pair_id = Some("src/new.rs:somehash".to_owned());
+        assert_eq!(
+            classify_single_hunk(&hunk).unwrap().label,
+            vec!["move:code"]
+        );
+    }
+
+    // --- License changes ---
+
+    #[test]
+    fn test_license_files() {
+        for path in [
+            "LICENSE",
+            "LICENSE-MIT",
+            "docs/COPYING.txt",
+            "NOTICE",
+            "Licence.md",
+        ] {
+            let hunk = make_hunk(path, vec![removed("MIT License"), added("Apache License")]);
+            let result = classify_single_hunk(&hunk).unwrap();
+            assert_eq!(result.label, vec![LICENSE_CHANGE_LABEL], "{path}");
+        }
+        let hunk = make_hunk("src/license.rs", vec![added("fn check() {}")]);
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    #[test]
+    fn test_license_header_outranks_comment_rule() {
+        let hunk = make_hunk(
+            "src/main.rs",
+            vec![
+                removed("// Copyright (c) 2023 Acme Inc."),
+                added("// Copyright (c) 2024 Acme Inc."),
+            ],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.label, vec![LICENSE_CHANGE_LABEL]);
+
+        let hunk = make_hunk(
+            "lib/util.py",
+            vec![added("# SPDX-License-Identifier: GPL-3.0-only")],
+        );
+        assert_eq!(
+            classify_single_hunk(&hunk).unwrap().label,
+            vec![LICENSE_CHANGE_LABEL]
+        );
+
+        // "Copyright" in code rather than a comment isn't a header.
+        let hunk = make_hunk(
+            "src/footer.ts",
+            vec![added("const text = `Copyright ${year}`;")],
+        );
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    #[test]
+    fn test_manifest_license_field() {
+        let hunk = make_hunk(
+            "Cargo.toml",
+            vec![
+                removed("license = \"MIT\""),
+                added("license = \"BUSL-1.1\""),
+            ],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.label, vec![LICENSE_CHANGE_LABEL]);
+
+        let hunk = make_hunk("package.json", vec![added("\"license\": \"ISC\",")]);
+        assert_eq!(
+            classify_single_hunk(&hunk).unwrap().label,
+            vec![LICENSE_CHANGE_LABEL]
+        );
+
+        let hunk = make_hunk("package.json", vec![added("\"version\": \"2.0.0\",")]);
+        assert!(classify_single_hunk(&hunk).is_none());
+    }
+
+    // --- Evidence tests ---
+
+    #[test]
+    fn test_evidence_defaults_to_changed_lines() {
+        let hunk = make_hunk(
+            "src/main.rs",
+            vec![context("fn main() {"), added(""), added("   ")],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.evidence.len(), 1);
+        assert_eq!(result.evidence[0].rule, "whitespace");
+        let texts: Vec<&str> = result.evidence[0]
+            .lines
+            .iter()
+            .map(|l| l.text.as_str())
+            .collect();
+        assert_eq!(texts, ["", "   "]);
+    }
+
+    #[test]
+    fn test_evidence_for_path_rules_has_no_lines() {
+        let hunk = make_hunk("Cargo.lock", vec![added("version = \"1.0.1\"")]);
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(
+            result.evidence,
+            [Evidence {
+                rule: "lockfile".to_owned(),
+                ..Evidence::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_evidence_pinpoints_matched_lines() {
+        let mut assertion = removed("assert_eq!(total, 3);");
+        assertion.old_line_number = Some(42);
+        let hunk = make_hunk(
+            "src/lib.rs",
+            vec![
+                removed("let total = sum();"),
+                assertion,
+                added("let total = sum();"),
+            ],
+        );
+        let result = classify_single_hunk(&hunk).unwrap();
+        assert_eq!(result.label, vec![TESTS_REMOVED_LABEL]);
+        assert_eq!(result.evidence[0].rule, "tests-removed");
+        assert_eq!(
+            result.evidence[0].lines,
+            [EvidenceLine {
+                old_line_number: Some(42),
+                new_line_number: None,
+                text: "assert_eq!(total, 3);".to_owned(),
+            }]
+        );
+    }
 }
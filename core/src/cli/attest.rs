@@ -0,0 +1,120 @@
+//! `review attest [--key K] [--format ssh|gpg]` · `review attest verify` —
+//! sign a completed review, and check a signed one (see
+//! [`crate::service::attestation`]).
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand, ValueEnum};
+
+use crate::review::state::{Attestation, SignatureFormat};
+use crate::service::attestation::{self, SigningKey};
+
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct AttestArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Signing key: an SSH key path or a GPG key ID (default: git's user.signingkey)
+    #[arg(long)]
+    pub key: Option<String>,
+    /// Signature format (default: git's gpg.format)
+    #[arg(long, value_enum)]
+    pub format: Option<FormatArg>,
+    /// Output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub action: Option<AttestAction>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AttestAction {
+    /// Check the attestation's signature and whether the review changed since
+    Verify,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FormatArg {
+    Ssh,
+    Gpg,
+}
+
+impl From<FormatArg> for SignatureFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Ssh => SignatureFormat::Ssh,
+            FormatArg::Gpg => SignatureFormat::Gpg,
+        }
+    }
+}
+
+fn print_attestation(attestation: &Attestation) {
+    let review = &attestation.review;
+    println!(
+        "{} — {} hunk(s), signed by {} at {}",
+        review.comparison,
+        review.decisions.len(),
+        review.reviewer,
+        attestation.signed_at
+    );
+    println!("  diff    sha256:{}", review.diff_sha256);
+    println!("  digest  sha256:{}", attestation.digest);
+    let key = if attestation.key.is_empty() {
+        "default key"
+    } else {
+        &attestation.key
+    };
+    let format = match attestation.format {
+        SignatureFormat::Ssh => "ssh",
+        SignatureFormat::Gpg => "gpg",
+    };
+    println!("  key     {format} {key}");
+}
+
+/// `review attest [verify]`.
+pub fn run(args: &AttestArgs) -> Result<(), String> {
+    let repo = PathBuf::from(get_repo_path(&args.target.repo)?);
+    let review = resolve_review_arg(&repo, args.target.spec.as_deref())?;
+    let ref_name = review.ref_name.as_str();
+    match &args.action {
+        None => {
+            let key = SigningKey::resolve(&repo, args.key.as_deref(), args.format.map(Into::into))
+                .map_err(|e| format!("{e:#}"))?;
+            let attestation =
+                attestation::attest(&repo, ref_name, &key).map_err(|e| format!("{e:#}"))?;
+            if args.json {
+                print_json(&attestation);
+            } else {
+                print_attestation(&attestation);
+            }
+        }
+        Some(AttestAction::Verify) => {
+            let check = attestation::verify(&repo, ref_name).map_err(|e| format!("{e:#}"))?;
+            if args.json {
+                print_json(&check);
+            } else {
+                print_attestation(&check.attestation);
+                if check.signature_valid {
+                    println!("Signature: good");
+                } else {
+                    println!("Signature: BAD");
+                }
+                if check.changes.is_empty() {
+                    println!("The review matches what was signed.");
+                }
+                for change in &check.changes {
+                    println!("Changed since signing: {change}");
+                }
+            }
+            if !check.signature_valid {
+                return Err("The attestation's signature does not verify".to_owned());
+            }
+            if !check.changes.is_empty() {
+                return Err("The review has changed since it was attested".to_owned());
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! `review bench` — benchmark the review's base and head, record the timings
+//! on the review, and flag benchmarks that got slower than the threshold.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::bench::{bench_config, run_benchmarks, PERFORMANCE_REGRESSION_LABEL};
+
+use super::common::{load_for_mutation, mutate_review, print_json, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `1234.5` → `1.23 µs`.
+fn format_ns(ns: f64) -> String {
+    match ns {
+        ns if ns >= 1e9 => format!("{:.2} s", ns / 1e9),
+        ns if ns >= 1e6 => format!("{:.2} ms", ns / 1e6),
+        ns if ns >= 1e3 => format!("{:.2} µs", ns / 1e3),
+        ns => format!("{ns:.0} ns"),
+    }
+}
+
+pub fn run_bench(args: BenchArgs) -> Result<(), String> {
+    let BenchArgs { target, json } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let config = bench_config(&repo).map_err(|e| format!("{e:#}"))?;
+    let (review, hunks, _) = load_for_mutation(&repo, target.spec.as_deref())?;
+    let report =
+        run_benchmarks(&repo, &review.comparison, &config).map_err(|e| format!("{e:#}"))?;
+    mutate_review(&repo, &review.ref_name, &hunks, |state| {
+        state.bench = Some(report.clone());
+        true
+    })?;
+
+    if json {
+        print_json(&report);
+        return Ok(());
+    }
+    let time = |ns: Option<f64>| ns.map_or("—".to_owned(), format_ns);
+    for bench in &report.benchmarks {
+        println!(
+            "  {:>10}  {:>10}  {:>8}  {}{}",
+            time(bench.base_ns),
+            time(bench.head_ns),
+            bench
+                .change_pct
+                .map_or("—".to_owned(), |pct| format!("{pct:+.1}%")),
+            bench.name,
+            if bench.regression {
+                format!("  [{PERFORMANCE_REGRESSION_LABEL}]")
+            } else {
+                String::new()
+            }
+        );
+    }
+    println!(
+        "{} of {} benchmark(s) regressed by more than {}%",
+        report.regressions().count(),
+        report.benchmarks.len(),
+        report.threshold_pct
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_timings_in_the_largest_whole_unit() {
+        assert_eq!(format_ns(850.0), "850 ns");
+        assert_eq!(format_ns(1234.5), "1.23 µs");
+        assert_eq!(format_ns(2.5e6), "2.50 ms");
+        assert_eq!(format_ns(3e9), "3.00 s");
+    }
+}
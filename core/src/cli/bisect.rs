@@ -0,0 +1,56 @@
+//! `review bisect` — find the commit in a review's range where a test command
+//! started failing, and record it (with the hunks it introduced) on the review.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::bisect::bisect_review;
+
+use super::common::{load_for_mutation, mutate_review, print_json, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct BisectArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Shell command that exits 0 when the code is good (e.g. "cargo test -p core")
+    #[arg(long)]
+    pub command: String,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_bisect(args: BisectArgs) -> Result<(), String> {
+    let BisectArgs {
+        target,
+        command,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let (review, hunks, _) = load_for_mutation(&repo, target.spec.as_deref())?;
+    let result = bisect_review(&repo, &review, &hunks, &command).map_err(|e| format!("{e:#}"))?;
+    mutate_review(&repo, &review.ref_name, &hunks, |state| {
+        state.bisect = Some(result.clone());
+        true
+    })?;
+
+    if json {
+        print_json(&result);
+        return Ok(());
+    }
+    println!(
+        "First bad commit: {} {} ({})",
+        result.short_hash, result.subject, result.author
+    );
+    if result.hunks.is_empty() {
+        println!("None of the review's hunks carry its changes; later commits replaced them.");
+    } else {
+        println!("Hunks from that commit:");
+        for id in &result.hunks {
+            println!("  {id}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! `review breakdown` — the shape of a review's change before diving in:
+//! lines and hunks per language and per directory, with the share that's
+//! generated code or tests.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::breakdown::{change_breakdown, ChangeBucket};
+
+use super::common::{load_comparison_hunks, print_json, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct BreakdownArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Group directories this many levels deep
+    #[arg(long, default_value_t = 2)]
+    pub depth: usize,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_breakdown(args: BreakdownArgs) -> Result<(), String> {
+    let BreakdownArgs {
+        target,
+        depth,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let (review, hunks) = load_comparison_hunks(&repo, target.spec.as_deref())?;
+    let breakdown = change_breakdown(&hunks, depth);
+
+    if json {
+        print_json(&breakdown);
+        return Ok(());
+    }
+    let total = &breakdown.total;
+    println!(
+        "{}: {} file(s), {} hunk(s), +{} -{} ({}% generated, {}% tests)",
+        review.comparison.key,
+        total.files,
+        total.hunks,
+        total.lines_added,
+        total.lines_removed,
+        total.generated_pct,
+        total.test_pct
+    );
+    print_buckets("language", &breakdown.languages);
+    print_buckets("directory", &breakdown.directories);
+    Ok(())
+}
+
+fn print_buckets(heading: &str, buckets: &[ChangeBucket]) {
+    if buckets.is_empty() {
+        return;
+    }
+    println!(
+        "\n  {heading:<32}  {:>5}  {:>5}  {:>7}  {:>7}  {:>6}  {:>6}",
+        "files", "hunks", "+", "-", "gen%", "test%"
+    );
+    for bucket in buckets {
+        println!(
+            "  {:<32}  {:>5}  {:>5}  {:>7}  {:>7}  {:>6}  {:>6}",
+            bucket.name,
+            bucket.files,
+            bucket.hunks,
+            bucket.lines_added,
+            bucket.lines_removed,
+            bucket.generated_pct,
+            bucket.test_pct
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Checklist subcommands: `checklist show|tick|untick` and `check`.
+//!
+//! Items come from the repo's `.review/checklist.md` template; their check
+//! state is stored per review. `review check` is the optional gate — it exits
+//! non-zero while any item is unchecked, so it can guard a merge script or CI.
+//! In a repo with a `.review/ratchet`, it also fails while any hunk in a
+//! ratcheted file isn't approved or trusted, and on a two-person review until
+//! both reviewers have signed off.
+
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+use crate::review::checklist::{
+    find_item, load_checklist_template, set_item_checked, sync_checklist, CHECKLIST_TEMPLATE_PATH,
+};
+use crate::review::ratchet::{ReviewRatchet, RATCHET_PATH};
+use crate::review::state::ChecklistItem;
+use crate::review::storage;
+use crate::service::two_person::{self, TwoPersonProgress};
+
+use super::comments::SourceArg;
+use super::common::{
+    effective_status, hunk_labels, load_for_mutation, load_review_view, mutate_review, print_json,
+    resolve_review_arg, resolve_source, EffectiveStatus, ReviewTarget,
+};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct ChecklistArgs {
+    #[command(subcommand)]
+    pub action: ChecklistAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ChecklistAction {
+    /// Show the review's checklist and what's been checked off
+    Show(ShowArgs),
+    /// Check off items (by id or 1-based number)
+    Tick(TickArgs),
+    /// Uncheck items (by id or 1-based number)
+    Untick(TickArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct ShowArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TickArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Item ids (e.g. "docs-updated") or 1-based numbers from `checklist show`
+    #[arg(required = true)]
+    pub items: Vec<String>,
+    /// Who is making the change (ui|cli|agent|github|gitlab); defaults to cli
+    #[arg(long)]
+    pub source: Option<SourceArg>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChecklistJson<'a> {
+    comparison: String,
+    items: &'a [ChecklistItem],
+    complete: bool,
+}
+
+/// The review's checklist, synced in memory against the current template.
+fn load_checklist(repo: &Path, ref_name: &str) -> Result<Vec<ChecklistItem>, String> {
+    let mut state = storage::load_review_state(repo, ref_name).map_err(|e| e.to_string())?;
+    if let Some(template) = load_checklist_template(repo) {
+        sync_checklist(&mut state, &template);
+    }
+    Ok(state.checklist)
+}
+
+fn print_checklist(comparison: &str, items: &[ChecklistItem], json: bool) {
+    let complete = items.iter().all(|item| item.checked);
+    if json {
+        print_json(&ChecklistJson {
+            comparison: comparison.to_owned(),
+            items,
+            complete,
+        });
+        return;
+    }
+    println!("{comparison}");
+    if items.is_empty() {
+        println!("  (no checklist — add items to {CHECKLIST_TEMPLATE_PATH})");
+        return;
+    }
+    for (n, item) in items.iter().enumerate() {
+        let mark = if item.checked { "x" } else { " " };
+        println!("  {:>2}. [{mark}] {}  ({})", n + 1, item.text, item.id);
+    }
+}
+
+/// `review checklist show`.
+pub fn run_show(args: ShowArgs) -> Result<(), String> {
+    let ShowArgs { target, json } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let items = load_checklist(&repo, &review.ref_name)?;
+    print_checklist(&review.comparison.key, &items, json);
+    Ok(())
+}
+
+/// `review checklist tick|untick <item>...`.
+pub fn run_tick(args: TickArgs, checked: bool) -> Result<(), String> {
+    let TickArgs {
+        target,
+        items: keys,
+        source,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let (review, hunks, _) = load_for_mutation(&repo, target.spec.as_deref())?;
+    let template = load_checklist_template(&repo);
+
+    // Validate up front so a typo doesn't half-apply.
+    let mut items = load_checklist(&repo, &review.ref_name)?;
+    let unknown: Vec<&str> = keys
+        .iter()
+        .filter(|key| find_item(&mut items, key).is_none())
+        .map(String::as_str)
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("Unknown checklist item(s): {}", unknown.join(", ")));
+    }
+
+    let source = resolve_source(source)?;
+    let state = mutate_review(&repo, &review.ref_name, &hunks, |state| {
+        let mut changed = template
+            .as_ref()
+            .is_some_and(|template| sync_checklist(state, template));
+        for key in &keys {
+            if let Some(item) = find_item(&mut state.checklist, key) {
+                changed |= set_item_checked(item, checked, source);
+            }
+        }
+        changed
+    })?;
+    print_checklist(&review.comparison.key, &state.checklist, json);
+    Ok(())
+}
+
+/// A hunk the ratchet requires review of that isn't approved or trusted.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PendingHunk {
+    hunk_id: String,
+    file_path: String,
+    status: EffectiveStatus,
+}
+
+/// How the review fares against the repo's ratchet.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RatchetJson {
+    /// Hunks in ratcheted files.
+    enforced: usize,
+    /// Enforced hunks not yet approved or trusted; these fail the check.
+    pending: Vec<PendingHunk>,
+    /// Unreviewed hunks outside the ratchet, which don't fail the check.
+    unenforced_unreviewed: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckJson<'a> {
+    #[serde(flatten)]
+    checklist: ChecklistJson<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ratchet: Option<&'a RatchetJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    two_person: Option<TwoPersonProgress>,
+}
+
+/// Check every hunk against `ratchet`: enforced hunks must be approved or
+/// trusted.
+fn check_ratchet(
+    repo: &Path,
+    spec: Option<&str>,
+    ratchet: &ReviewRatchet,
+) -> Result<RatchetJson, String> {
+    let view = load_review_view(repo, spec)?;
+    let mut result = RatchetJson {
+        enforced: 0,
+        pending: Vec::new(),
+        unenforced_unreviewed: 0,
+    };
+    for hunk in &view.hunks {
+        let labels = hunk_labels(&hunk.id, &view.state, &view.classification);
+        let status = effective_status(&hunk.id, &labels, &view.state);
+        let reviewed = matches!(status, EffectiveStatus::Approved | EffectiveStatus::Trusted);
+        if !ratchet.covers(&hunk.file_path) {
+            result.unenforced_unreviewed += usize::from(status == EffectiveStatus::Unreviewed);
+            continue;
+        }
+        result.enforced += 1;
+        if !reviewed {
+            result.pending.push(PendingHunk {
+                hunk_id: hunk.id.clone(),
+                file_path: hunk.file_path.clone(),
+                status,
+            });
+        }
+    }
+    Ok(result)
+}
+
+/// `review check` — succeed only when every checklist item is checked off
+/// and, with a `.review/ratchet`, every hunk in a ratcheted file is approved
+/// or trusted. A repo with neither passes trivially.
+pub fn run_check(args: ShowArgs) -> Result<(), String> {
+    let ShowArgs { target, json } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let items = load_checklist(&repo, &review.ref_name)?;
+    let ratchet = ReviewRatchet::load(&repo)
+        .map(|ratchet| check_ratchet(&repo, target.spec.as_deref(), &ratchet))
+        .transpose()?;
+    let state = storage::load_review_state(&repo, &review.ref_name).map_err(|e| e.to_string())?;
+    let two_person_failure = two_person::gate_failure(&state);
+
+    if json {
+        print_json(&CheckJson {
+            checklist: ChecklistJson {
+                comparison: review.comparison.key.clone(),
+                items: &items,
+                complete: items.iter().all(|item| item.checked),
+            },
+            ratchet: ratchet.as_ref(),
+            two_person: two_person::progress(&state),
+        });
+    } else {
+        print_checklist(&review.comparison.key, &items, false);
+        if let Some(ratchet) = &ratchet {
+            println!(
+                "  ratchet ({RATCHET_PATH}): {}/{} enforced hunk(s) reviewed",
+                ratchet.enforced - ratchet.pending.len(),
+                ratchet.enforced
+            );
+            for hunk in &ratchet.pending {
+                println!("    {:<10}  {}", hunk.status.as_str(), hunk.hunk_id);
+            }
+            if ratchet.unenforced_unreviewed > 0 {
+                println!(
+                    "  {} unreviewed hunk(s) outside the ratchet (not enforced)",
+                    ratchet.unenforced_unreviewed
+                );
+            }
+        }
+        if let Some(progress) = two_person::progress(&state) {
+            print_two_person(&progress);
+        }
+    }
+
+    match gate_failure(&items, ratchet.as_ref(), two_person_failure.as_deref()) {
+        Some(failure) => Err(failure),
+        None => Ok(()),
+    }
+}
+
+/// Why `review check` fails, or `None` when it passes.
+fn gate_failure(
+    items: &[ChecklistItem],
+    ratchet: Option<&RatchetJson>,
+    two_person: Option<&str>,
+) -> Option<String> {
+    let remaining = items.iter().filter(|item| !item.checked).count();
+    let pending = ratchet.map_or(0, |r| r.pending.len());
+    let mut reasons = Vec::new();
+    if remaining > 0 {
+        reasons.push(format!("{remaining} checklist item(s) not checked off"));
+    }
+    if pending > 0 {
+        reasons.push(format!("{pending} enforced hunk(s) not reviewed"));
+    }
+    reasons.extend(two_person.map(ToOwned::to_owned));
+    (!reasons.is_empty()).then(|| format!("{}.", reasons.join("; ")))
+}
+
+/// The two-person lines of `review check`.
+pub(super) fn print_two_person(progress: &TwoPersonProgress) {
+    let handoff = progress.review.handoff.as_ref().map_or_else(
+        || "not handed off yet".to_owned(),
+        |handoff| format!("handed off by {}", handoff.by),
+    );
+    let signoff = progress.review.signoff.as_ref().map_or_else(
+        || "awaiting a second reviewer".to_owned(),
+        |signoff| format!("signed off by {}", signoff.by),
+    );
+    println!("  two-person: {handoff}; {signoff}");
+    if progress.recommended > 0 {
+        println!(
+            "    {} recommendation(s): {} confirmed, {} overridden, {} pending",
+            progress.recommended, progress.confirmed, progress.overridden, progress.pending
+        );
+    }
+}
+
+/// Run the `review check` gate on a review without printing: why it fails,
+/// or `None` when it passes.
+pub(super) fn check_gate(repo: &Path, spec: Option<&str>) -> Result<Option<String>, String> {
+    let review = resolve_review_arg(repo, spec)?;
+    let items = load_checklist(repo, &review.ref_name)?;
+    let ratchet = ReviewRatchet::load(repo)
+        .map(|ratchet| check_ratchet(repo, spec, &ratchet))
+        .transpose()?;
+    let state = storage::load_review_state(repo, &review.ref_name).map_err(|e| e.to_string())?;
+    Ok(gate_failure(
+        &items,
+        ratchet.as_ref(),
+        two_person::gate_failure(&state).as_deref(),
+    ))
+}
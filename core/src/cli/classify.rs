@@ -0,0 +1,174 @@
+//! `review classify` — re-run AI classification on just the hunks you doubt
+//! (`--hunk`, `--file`, `--only-label`, `--stale`), keeping every other
+//! hunk's labels; or, with `--compare a,b`, classify a sample of the
+//! comparison's hunks with each model and report where they agree, per label.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::ai::classify::compare_classification_models;
+use crate::limits::sample_indices;
+use crate::review::storage;
+use crate::service::prewarm::{reclassify_review, ClassifyScope};
+
+use super::common::{load_comparison_hunks, print_json, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct ClassifyArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Models to compare, comma-separated (e.g. "sonnet,haiku")
+    #[arg(long, value_delimiter = ',', num_args = 1.., conflicts_with_all = ["hunk", "file", "only_label", "stale"])]
+    pub compare: Vec<String>,
+    /// Number of hunks to classify with --compare, spread evenly over the comparison
+    #[arg(long, default_value_t = 20, requires = "compare")]
+    pub sample: usize,
+    /// Model to re-classify with
+    #[arg(long, default_value = "sonnet")]
+    pub model: String,
+    /// Re-classify only this hunk (repeatable)
+    #[arg(long)]
+    pub hunk: Vec<String>,
+    /// Re-classify only hunks in files matching this glob (repeatable)
+    #[arg(long)]
+    pub file: Vec<String>,
+    /// Re-classify only hunks currently labeled with this pattern, e.g. "imports:*" (repeatable)
+    #[arg(long)]
+    pub only_label: Vec<String>,
+    /// Re-classify only hunks labeled under an older taxonomy
+    #[arg(long)]
+    pub stale: bool,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `review classify` — re-classify the scoped hunks, or with `--compare`,
+/// run each model over the same sample.
+pub fn run_classify(args: ClassifyArgs) -> Result<(), String> {
+    let ClassifyArgs {
+        target,
+        compare,
+        sample,
+        model,
+        hunk,
+        file,
+        only_label,
+        stale,
+        json,
+    } = args;
+    if compare.is_empty() {
+        let scope = ClassifyScope {
+            hunk_ids: hunk,
+            files: file,
+            labels: only_label,
+            stale,
+        };
+        return run_reclassify(&target, &model, &scope, json);
+    }
+    if compare.len() < 2 {
+        return Err("--compare needs at least two models (e.g. sonnet,haiku)".to_owned());
+    }
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let (review, hunks) = load_comparison_hunks(&repo, target.spec.as_deref())?;
+    let sampled: Vec<_> = sample_indices(hunks.len(), sample)
+        .into_iter()
+        .map(|i| hunks[i].clone())
+        .collect();
+    if sampled.is_empty() {
+        return Err(format!(
+            "No hunks to classify in {}.",
+            review.comparison.key
+        ));
+    }
+    if !json {
+        eprintln!(
+            "Classifying {} of {} hunk(s) with {}…",
+            sampled.len(),
+            hunks.len(),
+            compare.join(", ")
+        );
+    }
+
+    let comparison =
+        compare_classification_models(&sampled, &compare, &repo).map_err(|e| e.to_string())?;
+
+    if json {
+        print_json(&comparison);
+        return Ok(());
+    }
+    println!(
+        "{} labeled {}/{} hunk(s) identically ({}%)\n",
+        comparison.models.join(" and "),
+        comparison.identical,
+        comparison.hunks,
+        comparison.identical * 100 / comparison.hunks
+    );
+    if comparison.labels.is_empty() {
+        println!("No model assigned any labels.");
+        return Ok(());
+    }
+    let models: String = comparison
+        .models
+        .iter()
+        .map(|m| format!("  {m:>8}"))
+        .collect::<Vec<_>>()
+        .concat();
+    println!(
+        "  {:<32}  {:>6}  {:>9}{models}",
+        "label", "agreed", "disagreed"
+    );
+    for row in &comparison.labels {
+        let counts: String = comparison
+            .models
+            .iter()
+            .map(|m| format!("  {:>8}", row.by_model.get(m).copied().unwrap_or(0)))
+            .collect::<Vec<_>>()
+            .concat();
+        println!(
+            "  {:<32}  {:>6}  {:>9}{counts}",
+            row.label, row.agreed, row.disagreed
+        );
+    }
+    Ok(())
+}
+
+/// Re-run `model` on the hunks in `scope` and save their new labels.
+fn run_reclassify(
+    target: &ReviewTarget,
+    model: &str,
+    scope: &ClassifyScope,
+    json: bool,
+) -> Result<(), String> {
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let (review, hunks) = load_comparison_hunks(&repo, target.spec.as_deref())?;
+    let state = storage::load_review_state(&repo, &review.ref_name)
+        .map_err(|e| format!("Failed to load review: {e}"))?;
+    let selected = scope.select(&hunks, &state).len();
+    if selected == 0 {
+        return Err(format!(
+            "No hunks in {} match the given --hunk/--file/--only-label/--stale scope.",
+            review.comparison.key
+        ));
+    }
+    if !json {
+        eprintln!(
+            "Re-classifying {selected} of {} hunk(s) with {model}…",
+            hunks.len()
+        );
+    }
+
+    let result =
+        reclassify_review(&repo, &review.ref_name, model, scope).map_err(|e| format!("{e:#}"))?;
+    if json {
+        print_json(&result);
+    } else {
+        println!(
+            "Re-classified {} hunk(s); {} got new labels. Other hunks were left as they were.",
+            result.hunks, result.relabeled
+        );
+    }
+    Ok(())
+}
@@ -8,11 +8,13 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use clap::Args;
 use serde::Serialize;
 
-use crate::classify::{classify_hunks_static, ClassifyResponse};
+use crate::classify::{classify_repo_hunks_static, ClassifyResponse};
 use crate::diff::parser::{DiffHunk, LineType};
+use crate::review::embeddings;
 use crate::review::state::{Attributed, HunkStatus, ReviewState, Source};
 use crate::review::storage::{self, StorageError};
 use crate::service::targets::{self, ResolvedReview};
+use crate::trust::patterns::is_never_trusted;
 
 /// The `--repo` / `--spec` flags shared by the review-state subcommands.
 ///
@@ -188,37 +190,57 @@ pub fn classified_labels(classification: &ClassifyResponse, hunk_id: &str) -> Ve
 }
 
 /// The labels for a hunk: stored review labels take precedence over a fresh
-/// static classification.
+/// static classification — except never-trusted labels (sensitive area,
+/// removed tests), which the static classification always contributes.
 pub fn hunk_labels(
     hunk_id: &str,
     state: &ReviewState,
     classification: &ClassifyResponse,
 ) -> Vec<String> {
+    let classified = classified_labels(classification, hunk_id);
     if let Some(hunk_state) = state.hunks.get(hunk_id) {
         let labels = hunk_state.labels();
         if !labels.is_empty() {
-            return labels.to_vec();
+            let mut labels = labels.to_vec();
+            merge_never_trusted(&mut labels, &classified);
+            return labels;
         }
     }
-    classified_labels(classification, hunk_id)
+    classified
+}
+
+/// Add `classified`'s never-trusted labels that `labels` lacks, in front.
+fn merge_never_trusted(labels: &mut Vec<String>, classified: &[String]) {
+    let missing: Vec<String> = classified
+        .iter()
+        .filter(|label| is_never_trusted(label) && !labels.contains(label))
+        .cloned()
+        .collect();
+    labels.splice(0..0, missing);
 }
 
 /// Persist static-classification labels into the review state so summaries
 /// — `review list` and the desktop app's sidebar — see every classified
 /// hunk, matching what the app stores. Existing labels (e.g. from the app's
-/// AI classification) are left untouched.
+/// AI classification) are left untouched, apart from gaining any
+/// never-trusted labels the static rules found.
 pub fn sync_classification(state: &mut ReviewState, classification: &ClassifyResponse) {
     for (hunk_id, result) in &classification.classifications {
         if result.label.is_empty() {
             continue;
         }
         let entry = state.hunks.entry(hunk_id.clone()).or_default();
-        if entry.classification.is_none() {
-            entry.classification = Some(Attributed {
-                value: result.label.clone(),
-                source: Source::Static,
-                reasoning: (!result.reasoning.is_empty()).then(|| result.reasoning.clone()),
-            });
+        match &mut entry.classification {
+            None => {
+                entry.classification = Some(Attributed {
+                    value: result.label.clone(),
+                    source: Source::Static,
+                    reasoning: (!result.reasoning.is_empty()).then(|| result.reasoning.clone()),
+                    at: None,
+                });
+                entry.raw_labels = classification.raw_labels.get(hunk_id).cloned();
+            }
+            Some(existing) => merge_never_trusted(&mut existing.value, &result.label),
         }
     }
 }
@@ -267,7 +289,7 @@ pub struct ReviewView {
 /// Enumerate a review's hunks, classify them, and load its saved state.
 pub fn load_review_view(repo: &Path, spec: Option<&str>) -> Result<ReviewView, String> {
     let (review, hunks) = load_comparison_hunks(repo, spec)?;
-    let classification = classify_hunks_static(&hunks);
+    let classification = classify_repo_hunks_static(repo, &hunks);
     let mut state = storage::load_review_state(repo, &review.ref_name)
         .map_err(|e| format!("Failed to load review: {e}"))?;
     // Carry decisions forward onto the current diff for display (not persisted
@@ -285,6 +307,28 @@ pub fn load_review_view(repo: &Path, spec: Option<&str>) -> Result<ReviewView, S
 
 const MAX_SAVE_RETRIES: usize = 5;
 
+/// Index the review's hunks for matching against other reviews
+/// ([`embeddings`]). Best-effort: a failure is only logged.
+fn index_review_hunks(repo: &Path, ref_name: &str, hunks: &[DiffHunk]) {
+    if let Err(e) = embeddings::index_review(repo, ref_name, hunks) {
+        log::warn!("[cli] Failed to index {ref_name}'s hunks: {e}");
+    }
+}
+
+/// Suggest approving the hunks other reviews approved unchanged
+/// ([`embeddings::carry_identical_approvals`]). Best-effort: a failure is
+/// only logged.
+fn carry_identical_approvals(
+    repo: &Path,
+    ref_name: &str,
+    hunks: &[DiffHunk],
+    state: &mut ReviewState,
+) {
+    if let Err(e) = embeddings::carry_identical_approvals(repo, ref_name, hunks, state) {
+        log::warn!("[cli] Failed to match {ref_name}'s hunks to other reviews: {e}");
+    }
+}
+
 /// The set of live hunk IDs from a parsed diff.
 pub fn live_hunk_ids(hunks: &[DiffHunk]) -> HashSet<String> {
     hunks.iter().map(|h| h.id.clone()).collect()
@@ -334,9 +378,13 @@ where
         // drop_orphans=true: `live_hunks` is the authoritative full diff loaded
         // by `load_for_mutation`.
         state.reconcile(live_hunks, true);
+        carry_identical_approvals(repo, ref_name, live_hunks, &mut state);
         state.prepare_for_save();
         match storage::save_review_state(repo, &state) {
-            Ok(()) => return Ok(state),
+            Ok(()) => {
+                index_review_hunks(repo, ref_name, live_hunks);
+                return Ok(state);
+            }
             Err(StorageError::VersionConflict { .. }) if attempt + 1 < MAX_SAVE_RETRIES => {}
             Err(e) => return Err(format!("Failed to save review: {e}")),
         }
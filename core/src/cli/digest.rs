@@ -0,0 +1,73 @@
+//! `review digest` — a summary of review work across all repos for a period,
+//! as Markdown or HTML for a team email, optionally sent through the SMTP
+//! relay in settings.
+
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+use crate::service::digest::{
+    build_digest, parse_period, render_html, render_markdown, send_digest,
+};
+
+use super::common::print_json;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Args)]
+pub struct DigestArgs {
+    /// Period to cover, e.g. `7d`, `2w`, `24h`
+    #[arg(long, default_value = "7d")]
+    pub since: String,
+    /// Output format
+    #[arg(long, value_enum, default_value_t = DigestFormat::Markdown)]
+    pub format: DigestFormat,
+    /// Write the digest to FILE instead of stdout
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+    /// Email it via the `digestSmtp` relay in settings.json
+    #[arg(long)]
+    pub send: bool,
+    /// Output the digest data as JSON
+    #[arg(long, conflicts_with = "format")]
+    pub json: bool,
+}
+
+pub fn run_digest(args: DigestArgs) -> Result<(), String> {
+    let period = parse_period(&args.since).ok_or_else(|| {
+        format!(
+            "Invalid period '{}': use a number of hours, days or weeks, e.g. 24h, 7d, 2w",
+            args.since
+        )
+    })?;
+    let digest = build_digest(period).map_err(|e| format!("{e:#}"))?;
+
+    if args.send {
+        let smtp = send_digest(&digest).map_err(|e| format!("{e:#}"))?;
+        eprintln!("Sent the digest to {}", smtp.to.join(", "));
+    }
+
+    if args.json {
+        print_json(&digest);
+        return Ok(());
+    }
+    let rendered = match args.format {
+        DigestFormat::Markdown => render_markdown(&digest),
+        DigestFormat::Html => render_html(&digest),
+    };
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, rendered)
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+            eprintln!("Wrote {}", path.display());
+        }
+        // Sending already delivered it; don't also dump it on the terminal.
+        None if args.send => {}
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
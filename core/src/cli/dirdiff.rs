@@ -0,0 +1,111 @@
+//! `review dirdiff <old> <new>` — diff two directories that aren't commits of
+//! a repo (exported trees, unpacked releases, build output) into hunks, and
+//! label them with the static classifier, as a repo review would.
+
+use std::collections::HashMap;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::classify::classify_hunks_static;
+use crate::sources::dir::{DirChange, DirSource};
+use crate::sources::traits::FileStatus;
+
+use super::common::{hunk_line_stats, print_json};
+
+#[derive(Debug, Args)]
+pub struct DirDiffArgs {
+    /// The base directory
+    pub old: String,
+    /// The directory compared against it
+    pub new: String,
+    /// Only list the changed files
+    #[arg(long)]
+    pub files: bool,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DirHunkJson {
+    id: String,
+    file: String,
+    old_start: u32,
+    old_count: u32,
+    new_start: u32,
+    new_count: u32,
+    additions: usize,
+    deletions: usize,
+    labels: Vec<String>,
+}
+
+fn status_char(status: &FileStatus) -> char {
+    match status {
+        FileStatus::Added => 'A',
+        FileStatus::Deleted => 'D',
+        _ => 'M',
+    }
+}
+
+pub fn run_dirdiff(args: &DirDiffArgs) -> Result<(), String> {
+    let source = DirSource::new(&args.old, &args.new).map_err(|e| e.to_string())?;
+    if args.files {
+        let changes = source.changed_files().map_err(|e| e.to_string())?;
+        if args.json {
+            print_json(&changes);
+        } else {
+            for DirChange { path, status } in &changes {
+                println!("{}  {path}", status_char(status));
+            }
+        }
+        return Ok(());
+    }
+
+    let hunks = source.all_hunks().map_err(|e| e.to_string())?;
+    let mut labels: HashMap<String, Vec<String>> = classify_hunks_static(&hunks)
+        .classifications
+        .into_iter()
+        .map(|(id, result)| (id, result.label))
+        .collect();
+    let rows: Vec<DirHunkJson> = hunks
+        .iter()
+        .map(|hunk| {
+            let (additions, deletions) = hunk_line_stats(hunk);
+            DirHunkJson {
+                id: hunk.id.clone(),
+                file: hunk.file_path.clone(),
+                old_start: hunk.old_start,
+                old_count: hunk.old_count,
+                new_start: hunk.new_start,
+                new_count: hunk.new_count,
+                additions,
+                deletions,
+                labels: labels.remove(&hunk.id).unwrap_or_default(),
+            }
+        })
+        .collect();
+    if args.json {
+        print_json(&rows);
+        return Ok(());
+    }
+    for row in &rows {
+        let labels = if row.labels.is_empty() {
+            String::new()
+        } else {
+            format!("  [{}]", row.labels.join(", "))
+        };
+        println!(
+            "{}:{}  +{} -{}  {}{labels}",
+            row.file, row.new_start, row.additions, row.deletions, row.id
+        );
+    }
+    let files = rows
+        .iter()
+        .map(|row| row.file.as_str())
+        .collect::<std::collections::HashSet<_>>()
+        .len();
+    println!("\n{} hunk(s) in {files} file(s)", rows.len());
+    Ok(())
+}
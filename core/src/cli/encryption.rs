@@ -0,0 +1,130 @@
+//! `review encryption` — encrypt a review at rest with a passphrase, and
+//! unlock or lock it for the session (see [`crate::review::encryption`]).
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use clap::{Args, Subcommand};
+
+use crate::review::encryption;
+
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct EncryptionArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    #[command(subcommand)]
+    pub action: EncryptionAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum EncryptionAction {
+    /// Encrypt the review with a new passphrase (it stays unlocked)
+    Enable,
+    /// Decrypt the review for good
+    Disable,
+    /// Unlock the review until `review encryption lock`
+    Unlock,
+    /// Forget the review's key until it's unlocked again
+    Lock,
+    /// List the repo's encrypted reviews and whether each is unlocked
+    Status {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Toggle terminal echo, so a typed passphrase isn't shown.
+fn set_echo(on: bool) {
+    if cfg!(unix) {
+        let _ = Command::new("stty")
+            .arg(if on { "echo" } else { "-echo" })
+            .stdin(Stdio::inherit())
+            .status();
+    }
+}
+
+/// The passphrase from `$REVIEW_PASSPHRASE`, or else read from stdin (without
+/// echo at a terminal).
+fn read_passphrase(prompt: &str) -> Result<String, String> {
+    if let Ok(passphrase) = std::env::var("REVIEW_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+    let interactive = io::stdin().is_terminal();
+    eprint!("{prompt}: ");
+    let _ = io::stderr().flush();
+    if interactive {
+        set_echo(false);
+    }
+    let mut line = String::new();
+    let read = io::stdin().lock().read_line(&mut line);
+    if interactive {
+        set_echo(true);
+        eprintln!();
+    }
+    read.map_err(|e| e.to_string())?;
+    let passphrase = line.trim_end_matches(['\r', '\n']).to_owned();
+    if passphrase.is_empty() {
+        return Err("No passphrase given".to_owned());
+    }
+    Ok(passphrase)
+}
+
+pub fn run_encryption(args: EncryptionArgs) -> Result<(), String> {
+    let EncryptionArgs { target, action } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+
+    let review_ref = || resolve_review_arg(&repo, target.spec.as_deref()).map(|r| r.ref_name);
+    match action {
+        EncryptionAction::Enable => {
+            let ref_name = review_ref()?;
+            let passphrase = read_passphrase("New passphrase")?;
+            if std::env::var_os("REVIEW_PASSPHRASE").is_none()
+                && read_passphrase("Repeat passphrase")? != passphrase
+            {
+                return Err("The passphrases don't match".to_owned());
+            }
+            encryption::encrypt_review(&repo, &ref_name, &passphrase).map_err(|e| e.to_string())?;
+            println!("Encrypted review {ref_name} (unlocked until `review encryption lock`)");
+        }
+        EncryptionAction::Disable => {
+            let ref_name = review_ref()?;
+            let passphrase = read_passphrase("Passphrase")?;
+            encryption::decrypt_review(&repo, &ref_name, &passphrase).map_err(|e| e.to_string())?;
+            println!("Decrypted review {ref_name}");
+        }
+        EncryptionAction::Unlock => {
+            let ref_name = review_ref()?;
+            let passphrase = read_passphrase("Passphrase")?;
+            encryption::unlock_review(&repo, &ref_name, &passphrase).map_err(|e| e.to_string())?;
+            println!("Unlocked review {ref_name}");
+        }
+        EncryptionAction::Lock => {
+            let ref_name = review_ref()?;
+            encryption::lock_review(&repo, &ref_name).map_err(|e| e.to_string())?;
+            println!("Locked review {ref_name}");
+        }
+        EncryptionAction::Status { json } => {
+            let reviews = encryption::encrypted_reviews(&repo).map_err(|e| e.to_string())?;
+            if json {
+                print_json(&reviews);
+            } else if reviews.is_empty() {
+                println!("No encrypted reviews.");
+            } else {
+                for review in &reviews {
+                    let state = if review.unlocked {
+                        "unlocked"
+                    } else {
+                        "locked"
+                    };
+                    println!("  {state:<8}  {}", review.ref_name);
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! `review explain <hunk>` — re-evaluate one hunk's labels with the full
+//! symbols around it in view, and print a longer justification citing the
+//! lines it rests on.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::explain::explain_hunk_label;
+
+use super::common::{hunk_labels, load_review_view, print_json, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct ExplainArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Hunk ID whose labels to explain
+    pub hunk: String,
+    /// Model to ask
+    #[arg(long, default_value = "sonnet")]
+    pub model: String,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_explain(args: ExplainArgs) -> Result<(), String> {
+    let ExplainArgs {
+        target,
+        hunk,
+        model,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let view = load_review_view(&repo, target.spec.as_deref())?;
+    let comparison = &view.review.comparison;
+    let Some(diff_hunk) = view.hunks.iter().find(|h| h.id == hunk) else {
+        return Err(format!("Hunk not found in {}: {hunk}", comparison.key));
+    };
+    let labels = hunk_labels(&hunk, &view.state, &view.classification);
+    let reasoning = view
+        .state
+        .hunks
+        .get(&hunk)
+        .and_then(|s| s.classification.as_ref())
+        .and_then(|c| c.reasoning.clone())
+        .or_else(|| {
+            view.classification
+                .classifications
+                .get(&hunk)
+                .map(|c| c.reasoning.clone())
+                .filter(|r| !r.is_empty())
+        });
+    if !json {
+        eprintln!("Asking {model} to re-evaluate {hunk}…");
+    }
+
+    let explanation = explain_hunk_label(
+        &repo,
+        comparison,
+        diff_hunk,
+        &labels,
+        reasoning.as_deref(),
+        &model,
+    )
+    .map_err(|e| format!("{e:#}"))?;
+
+    if json {
+        print_json(&explanation);
+        return Ok(());
+    }
+    let shown = |labels: &[String]| {
+        if labels.is_empty() {
+            "(none)".to_owned()
+        } else {
+            labels.join(", ")
+        }
+    };
+    println!("Labels:    {}", shown(&explanation.labels));
+    println!(
+        "Suggested: {}{}",
+        shown(&explanation.suggested_labels),
+        if explanation.agrees {
+            " (agrees)"
+        } else {
+            " (disagrees)"
+        }
+    );
+    println!("\n{}", explanation.justification);
+    if !explanation.citations.is_empty() {
+        println!();
+        for citation in &explanation.citations {
+            println!(
+                "  {} {:>5}: {}\n         {}",
+                citation.side.as_str(),
+                citation.line,
+                citation.text.trim(),
+                citation.note
+            );
+        }
+    }
+    Ok(())
+}
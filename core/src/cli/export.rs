@@ -0,0 +1,58 @@
+//! `review export` — hunk-level review records (labels, decisions, reviewer,
+//! timestamps, size) as CSV or Parquet, for analysis in other tools.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+
+use crate::service::export::{collect_hunk_records, write_csv, write_parquet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    /// Needs the `duckdb` CLI
+    Parquet,
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+    /// Write to FILE (required for Parquet; CSV defaults to stdout)
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+    /// Only this repository; defaults to every registered repo
+    #[arg(short, long)]
+    pub repo: Option<String>,
+}
+
+pub fn run_export(args: ExportArgs) -> Result<(), String> {
+    let repo = args.repo.as_deref().map(PathBuf::from);
+    let records = collect_hunk_records(repo.as_deref()).map_err(|e| format!("{e:#}"))?;
+
+    match (args.format, args.output) {
+        (ExportFormat::Csv, None) => {
+            let mut stdout = std::io::stdout().lock();
+            write_csv(&records, &mut stdout)
+                .and_then(|()| stdout.flush())
+                .map_err(|e| e.to_string())?;
+        }
+        (ExportFormat::Csv, Some(path)) => {
+            let mut out = Vec::new();
+            write_csv(&records, &mut out).map_err(|e| e.to_string())?;
+            std::fs::write(&path, out)
+                .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+            eprintln!("Exported {} hunk(s) to {}", records.len(), path.display());
+        }
+        (ExportFormat::Parquet, None) => {
+            return Err("Parquet export needs --output FILE".to_owned())
+        }
+        (ExportFormat::Parquet, Some(path)) => {
+            write_parquet(&records, &path).map_err(|e| format!("{e:#}"))?;
+            eprintln!("Exported {} hunk(s) to {}", records.len(), path.display());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! `review files` — a review's files, with what isn't plain content on disk
+//! flagged: symlinks (with their target), Git LFS pointers whose object
+//! wasn't pulled, and files outside the sparse-checkout cone.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::files::list_files;
+use crate::sources::traits::{FileEntry, FileStatus};
+
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct FilesArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// List every tracked file, not just the changed ones
+    #[arg(long)]
+    pub all: bool,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_files(args: FilesArgs) -> Result<(), String> {
+    let FilesArgs { target, all, json } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let tree = list_files(&repo, &review.comparison, None, None).map_err(|e| format!("{e:#}"))?;
+
+    let mut files = Vec::new();
+    flatten(tree, &mut files);
+    if !all {
+        files.retain(|file| file.status.is_some());
+    }
+
+    if json {
+        print_json(&files);
+        return Ok(());
+    }
+    println!("{}", review.comparison.key);
+    if files.is_empty() {
+        println!("  No changed files.");
+        return Ok(());
+    }
+    for file in &files {
+        let status = match &file.status {
+            Some(FileStatus::Added) => "A",
+            Some(FileStatus::Modified) => "M",
+            Some(FileStatus::Deleted) => "D",
+            Some(FileStatus::Renamed) => "R",
+            Some(FileStatus::Untracked) => "?",
+            Some(FileStatus::Gitignored) => "!",
+            None => " ",
+        };
+        println!("  {status} {}{}", file.path, notes(file));
+    }
+    Ok(())
+}
+
+/// The files of a tree, depth-first, without their directories.
+fn flatten(entries: Vec<FileEntry>, out: &mut Vec<FileEntry>) {
+    for mut entry in entries {
+        match entry.children.take() {
+            Some(children) if entry.is_directory && !entry.is_symlink => flatten(children, out),
+            _ => out.push(entry),
+        }
+    }
+}
+
+fn notes(file: &FileEntry) -> String {
+    let mut notes = Vec::new();
+    if file.is_symlink {
+        notes.push(match &file.symlink_target {
+            Some(target) => format!("symlink → {target}"),
+            None => "symlink".to_owned(),
+        });
+    }
+    if file.is_lfs_pointer {
+        notes.push(match file.size {
+            Some(size) => format!("LFS object not pulled, {size} bytes"),
+            None => "LFS object not pulled".to_owned(),
+        });
+    }
+    if file.not_materialized {
+        notes.push("outside the sparse checkout".to_owned());
+    }
+    if notes.is_empty() {
+        String::new()
+    } else {
+        format!("  ({})", notes.join("; "))
+    }
+}
@@ -0,0 +1,72 @@
+//! `review gerrit` — fetch a Gerrit change's patch sets (see
+//! [`crate::sources::gerrit`]) and start a review of one: against its parent,
+//! or against an earlier patch set to see what changed between them.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::review::storage;
+use crate::sources::gerrit::GerritSource;
+
+use super::common::print_json;
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct GerritArgs {
+    /// Repository path (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Change number, or Change-Id (needs `[gerrit] url` in .review.toml)
+    pub change: String,
+    /// Patch set to review (defaults to the latest)
+    #[arg(long)]
+    pub patch_set: Option<u32>,
+    /// Compare against this patch set instead of the patch set's parent
+    #[arg(long)]
+    pub against: Option<u32>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_gerrit(args: GerritArgs) -> Result<(), String> {
+    let GerritArgs {
+        repo,
+        change,
+        patch_set,
+        against,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&repo)?);
+    let source = GerritSource::from_repo_config(repo.clone());
+    let change = source.change(&change).map_err(|e| e.to_string())?;
+    let comparison = source
+        .fetch_comparison(&change, patch_set, against)
+        .map_err(|e| e.to_string())?;
+    storage::ensure_review_exists(&repo, &comparison.head, Some(comparison.base.clone()), None)
+        .map_err(|e| e.to_string())?;
+
+    if json {
+        print_json(&serde_json::json!({
+            "change": change,
+            "spec": comparison.key,
+            "ref": comparison.head,
+            "base": comparison.base,
+        }));
+        return Ok(());
+    }
+    match &change.subject {
+        Some(subject) => println!("Change {}: {subject}", change.number),
+        None => println!("Change {}", change.number),
+    }
+    if let (Some(branch), Some(status)) = (&change.branch, &change.status) {
+        println!("  {branch}, {status}");
+    }
+    for ps in &change.patch_sets {
+        println!("  patch set {:>2}  {:.12}", ps.number, ps.revision);
+    }
+    println!("Reviewing {}", comparison.key);
+    println!("  review hunks -s {}", comparison.key);
+    Ok(())
+}
@@ -0,0 +1,208 @@
+//! `review inbox` — the reviewer inbox: pull requests awaiting the user's
+//! review and the user's own, with their review status, synced from GitHub.
+//! New or updated review requests are pre-warmed into classified reviews.
+//! With `--watch` it keeps polling, announcing new requests and status changes
+//! with a desktop notification, so reviews are ready before they're opened.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::ai::check_claude_available;
+use crate::review::inbox::{Inbox, InboxDirection};
+use crate::service::pr_sync::{sync_inbox, sync_review_requests, InboxChange, InboxSync};
+use crate::service::prewarm::PrewarmResult;
+
+use super::common::print_json;
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct InboxArgs {
+    /// Repository path (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Keep polling for review requests until interrupted, every SECS seconds
+    #[arg(
+        long,
+        value_name = "SECS",
+        num_args = 0..=1,
+        default_missing_value = "300",
+        value_parser = clap::value_parser!(u64).range(30..)
+    )]
+    pub watch: Option<u64>,
+    /// Model for AI classification
+    #[arg(long, default_value = "sonnet")]
+    pub model: String,
+    /// Only run static classification
+    #[arg(long)]
+    pub no_ai: bool,
+    /// Don't show desktop notifications with --watch
+    #[arg(long)]
+    pub no_notify: bool,
+    /// Output each sync pass as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `review inbox` — one sync pass, or a polling loop with `--watch`.
+pub fn run_inbox(args: InboxArgs) -> Result<(), String> {
+    let InboxArgs {
+        repo,
+        watch,
+        model,
+        no_ai,
+        no_notify,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&repo)?);
+    let model = if no_ai {
+        None
+    } else if check_claude_available() {
+        Some(model.as_str())
+    } else {
+        eprintln!("Claude CLI not found; running static classification only.");
+        None
+    };
+
+    let mut first = true;
+    loop {
+        match sync_pass(&repo, model, json, watch.is_some() && !no_notify, first) {
+            Ok(()) => {}
+            // A failed poll (offline, gh logged out) is retried next interval.
+            Err(e) if watch.is_some() => eprintln!("{e}"),
+            Err(e) => return Err(e),
+        }
+        let Some(interval) = watch else {
+            return Ok(());
+        };
+        first = false;
+        std::thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InboxPassJson<'a> {
+    inbox: &'a Inbox,
+    changes: &'a [InboxChange],
+    prewarmed: &'a [PrewarmResult],
+    failed: &'a [(u32, String)],
+}
+
+/// Sync the inbox and pre-warm new requests. The full inbox is printed once;
+/// a `--watch` poll (`notify`) reports only what changed.
+fn sync_pass(
+    repo: &Path,
+    model: Option<&str>,
+    json: bool,
+    notify: bool,
+    first: bool,
+) -> Result<(), String> {
+    let InboxSync { inbox, changes } = sync_inbox(repo).map_err(|e| format!("{e:#}"))?;
+    let outcome = sync_review_requests(repo, model).map_err(|e| format!("{e:#}"))?;
+
+    if json {
+        print_json(&InboxPassJson {
+            inbox: &inbox,
+            changes: &changes,
+            prewarmed: &outcome.synced,
+            failed: &outcome.failed,
+        });
+    } else {
+        if first {
+            print_inbox(&inbox);
+        }
+        for change in &changes {
+            println!("{}", describe_change(change));
+        }
+        for result in &outcome.synced {
+            println!("{}", describe(result));
+        }
+    }
+    for (number, error) in &outcome.failed {
+        eprintln!("#{number}: {error}");
+    }
+    if notify {
+        for change in &changes {
+            notify_desktop("Review status changed", &describe_change(change));
+        }
+        for result in &outcome.synced {
+            notify_desktop("Review requested", &describe(result));
+        }
+    }
+    if outcome.failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to pre-warm {} review request(s).",
+            outcome.failed.len()
+        ))
+    }
+}
+
+fn print_inbox(inbox: &Inbox) {
+    for (direction, heading) in [
+        (InboxDirection::Incoming, "Awaiting your review"),
+        (InboxDirection::Outgoing, "Your pull requests"),
+    ] {
+        let entries: Vec<_> = inbox
+            .entries
+            .iter()
+            .filter(|e| e.direction == direction)
+            .collect();
+        println!("{heading} ({}):", entries.len());
+        for entry in entries {
+            println!(
+                "  #{:<6} {:<18} {} ({})",
+                entry.number,
+                entry.status.as_str(),
+                entry.title,
+                entry.author
+            );
+        }
+    }
+}
+
+fn describe_change(change: &InboxChange) -> String {
+    format!(
+        "#{} {}: {} → {}",
+        change.number,
+        change.title,
+        change.from.as_str(),
+        change.to.as_str()
+    )
+}
+
+fn describe(result: &PrewarmResult) -> String {
+    format!(
+        "#{} {} — {} hunk(s), {} pre-labeled",
+        result.number,
+        result.title,
+        result.hunks,
+        result.static_labeled + result.ai_labeled
+    )
+}
+
+/// Best-effort desktop notification; silently does nothing where no
+/// notifier is available.
+fn notify_desktop(title: &str, body: &str) {
+    let result = if cfg!(target_os = "macos") {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        Command::new("osascript")
+            .arg("-e")
+            .arg(format!(
+                "display notification \"{}\" with title \"{}\"",
+                escape(body),
+                escape(title)
+            ))
+            .output()
+    } else {
+        Command::new("notify-send").args([title, body]).output()
+    };
+    if let Err(e) = result {
+        log::debug!("[notify_desktop] {e}");
+    }
+}
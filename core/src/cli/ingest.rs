@@ -0,0 +1,138 @@
+//! `review ingest` — record a static analyzer's report (SARIF, clippy,
+//! ESLint, Semgrep) as findings on the review's hunks.
+
+use std::cell::Cell;
+use std::io::Read;
+use std::path::PathBuf;
+
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+
+use crate::review::state::{now_iso8601, Finding};
+use crate::service::ingest::{attach_findings, merge_findings, IngestOutcome, ReportFormat};
+use crate::service::semgrep::SEMGREP_TOOL;
+
+use super::common::{load_for_mutation, mutate_review, print_json, ReviewTarget};
+use super::get_repo_path;
+use super::semgrep::sync_semgrep_labels;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IngestFormat {
+    /// SARIF 2.1 (CodeQL, many linters' `--format sarif`)
+    Sarif,
+    /// `cargo clippy --message-format=json`
+    Clippy,
+    /// `eslint -f json`
+    Eslint,
+    /// `semgrep --json`
+    Semgrep,
+}
+
+impl From<IngestFormat> for ReportFormat {
+    fn from(format: IngestFormat) -> Self {
+        match format {
+            IngestFormat::Sarif => ReportFormat::Sarif,
+            IngestFormat::Clippy => ReportFormat::Clippy,
+            IngestFormat::Eslint => ReportFormat::Eslint,
+            IngestFormat::Semgrep => ReportFormat::Semgrep,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct IngestArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Report format
+    #[arg(long, value_enum)]
+    pub format: IngestFormat,
+    /// The analyzer's report; `-` reads stdin
+    pub file: PathBuf,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct IngestOutput<'a> {
+    tools: Vec<&'a str>,
+    added: usize,
+    updated: usize,
+    fixed: usize,
+    outside_diff: usize,
+    findings: &'a [Finding],
+}
+
+pub fn run_ingest(args: IngestArgs) -> Result<(), String> {
+    let IngestArgs {
+        target,
+        format,
+        file,
+        json,
+    } = args;
+    let input = if file.as_os_str() == "-" {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| format!("Failed to read stdin: {e}"))?;
+        input
+    } else {
+        std::fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read {}: {e}", file.display()))?
+    };
+    let format = ReportFormat::from(format);
+    let report = format.parse(&input).map_err(|e| format!("{e:#}"))?;
+
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let (review, hunks, _) = load_for_mutation(&repo, target.spec.as_deref())?;
+    let (findings, outside_diff) =
+        attach_findings(&report, format.as_str(), &hunks, &repo, &now_iso8601());
+    let outcome = Cell::new(IngestOutcome::default());
+    mutate_review(&repo, &review.ref_name, &hunks, |state| {
+        let merged = merge_findings(state, &report.tools, &findings);
+        outcome.set(merged);
+        if report.tools.contains(SEMGREP_TOOL) {
+            sync_semgrep_labels(state, &hunks);
+        }
+        merged != IngestOutcome::default()
+    })?;
+    let outcome = outcome.get();
+
+    if json {
+        print_json(&IngestOutput {
+            tools: report.tools.iter().map(String::as_str).collect(),
+            added: outcome.added,
+            updated: outcome.updated,
+            fixed: outcome.fixed,
+            outside_diff,
+            findings: &findings,
+        });
+        return Ok(());
+    }
+    let tools: Vec<&str> = report.tools.iter().map(String::as_str).collect();
+    println!(
+        "{}: {} finding(s) on changed lines ({} new, {} already recorded), {} fixed since the last run, {} outside the diff.",
+        tools.join(", "),
+        findings.len(),
+        outcome.added,
+        outcome.updated,
+        outcome.fixed,
+        outside_diff
+    );
+    for finding in &findings {
+        println!(
+            "  {:<7} {}:{} {}{}",
+            finding.severity.as_str(),
+            finding.file_path,
+            finding.line,
+            finding
+                .rule
+                .as_deref()
+                .map(|r| format!("[{r}] "))
+                .unwrap_or_default(),
+            finding.message
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! `review init` — scan a repo and write a starter `.review.toml` with
+//! suggested ignore globs and trust patterns, reporting which symbol grammars
+//! and language servers apply to it.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::review::repo_config::REPO_CONFIG_PATH;
+use crate::service::onboarding::{render_repo_config, scan_repo, write_repo_config, RepoScan};
+
+use super::common::print_json;
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    /// Repository path (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Replace an existing `.review.toml`
+    #[arg(long)]
+    pub force: bool,
+    /// Print the config instead of writing it
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Output the scan as JSON (implies --dry-run)
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_init(args: InitArgs) -> Result<(), String> {
+    let InitArgs {
+        repo,
+        force,
+        dry_run,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&repo)?);
+    let scan = scan_repo(&repo).map_err(|e| format!("{e:#}"))?;
+
+    if json {
+        print_json(&scan);
+        return Ok(());
+    }
+    print_scan(&scan);
+    if dry_run {
+        println!("\n{}", render_repo_config(&scan));
+        return Ok(());
+    }
+    let path = write_repo_config(&repo, &scan, force).map_err(|e| format!("{e:#}"))?;
+    println!(
+        "\nWrote {}. Commit it to share these settings.",
+        path.display()
+    );
+    Ok(())
+}
+
+fn print_scan(scan: &RepoScan) {
+    println!("Scanned {} tracked file(s).\n", scan.files);
+
+    if !scan.languages.is_empty() {
+        println!(
+            "  {:<12}  {:>6}  {:<28}  language server",
+            "language", "files", "symbols"
+        );
+        for lang in &scan.languages {
+            let symbols = match (&lang.symbols_feature, lang.symbols_enabled) {
+                (None, _) => "-".to_owned(),
+                (Some(_), true) => "yes".to_owned(),
+                (Some(feature), false) => format!("needs `{feature}`"),
+            };
+            let server = match (&lang.language_server, lang.language_server_installed) {
+                (None, _) => "-".to_owned(),
+                (Some(name), true) => name.clone(),
+                (Some(name), false) => format!("{name} (not on PATH)"),
+            };
+            println!(
+                "  {:<12}  {:>6}  {:<28}  {server}",
+                lang.language, lang.files, symbols
+            );
+        }
+        println!();
+    }
+
+    if !scan.lockfiles.is_empty() {
+        println!(
+            "Lockfiles (trusted via generated:lockfile): {}",
+            scan.lockfiles.join(", ")
+        );
+    }
+    for generated in &scan.generated {
+        println!(
+            "Generated or vendored: {} ({} file(s)) — suggested for `ignore`",
+            generated.glob, generated.files
+        );
+    }
+
+    let fits: Vec<String> = scan
+        .categories
+        .iter()
+        .filter(|fit| fit.applies)
+        .map(|fit| {
+            if fit.opt_in {
+                format!("{} (opt-in)", fit.category)
+            } else {
+                fit.category.clone()
+            }
+        })
+        .collect();
+    println!("Trust categories that fit: {}", fits.join(", "));
+    let misfits: Vec<&str> = scan
+        .categories
+        .iter()
+        .filter(|fit| !fit.applies)
+        .map(|fit| fit.category.as_str())
+        .collect();
+    if !misfits.is_empty() {
+        println!("Not relevant here: {}", misfits.join(", "));
+    }
+    if scan.suggested_ignore.is_empty() {
+        println!("No ignore globs to suggest for {REPO_CONFIG_PATH}.");
+    }
+}
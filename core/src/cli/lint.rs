@@ -0,0 +1,133 @@
+//! `review lint` — run the repo's configured linters and record what they
+//! flag on the review's changed lines as findings.
+
+use std::cell::Cell;
+use std::path::PathBuf;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::review::state::{now_iso8601, Finding};
+use crate::service::ingest::{attach_findings, merge_findings, IngestOutcome};
+use crate::service::linters::{configured_linters, lint_dir, run_linter};
+
+use super::common::{load_for_mutation, mutate_review, print_json, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct LintArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Only this linter, by its `.review.toml` name (repeatable)
+    #[arg(long = "linter", value_name = "NAME")]
+    pub linters: Vec<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LinterOutcome {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// Diagnostics the linter reported in all.
+    reported: usize,
+    /// Of those, diagnostics on lines the review didn't change.
+    pre_existing: usize,
+    findings: Vec<Finding>,
+}
+
+pub fn run_lint(args: LintArgs) -> Result<(), String> {
+    let LintArgs {
+        target,
+        linters,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let linters = configured_linters(&repo, &linters).map_err(|e| format!("{e:#}"))?;
+    let (review, hunks, _) = load_for_mutation(&repo, target.spec.as_deref())?;
+    let dir = lint_dir(&repo, &review.comparison).map_err(|e| format!("{e:#}"))?;
+
+    let now = now_iso8601();
+    let mut outcomes = Vec::new();
+    let mut reports = Vec::new();
+    for linter in &linters {
+        match run_linter(&dir, linter) {
+            Ok(report) => {
+                let (findings, pre_existing) =
+                    attach_findings(&report, linter.format.as_str(), &hunks, &dir, &now);
+                outcomes.push(LinterOutcome {
+                    name: linter.name.clone(),
+                    error: None,
+                    reported: report.findings.len(),
+                    pre_existing,
+                    findings: findings.clone(),
+                });
+                reports.push((report.tools, findings));
+            }
+            Err(e) => outcomes.push(LinterOutcome {
+                name: linter.name.clone(),
+                error: Some(format!("{e:#}")),
+                reported: 0,
+                pre_existing: 0,
+                findings: Vec::new(),
+            }),
+        }
+    }
+
+    let fixed = Cell::new(0);
+    if !reports.is_empty() {
+        mutate_review(&repo, &review.ref_name, &hunks, |state| {
+            let mut changed = false;
+            let mut total_fixed = 0;
+            for (tools, findings) in &reports {
+                let merged = merge_findings(state, tools, findings);
+                total_fixed += merged.fixed;
+                changed |= merged != IngestOutcome::default();
+            }
+            fixed.set(total_fixed);
+            changed
+        })?;
+    }
+
+    let failed = outcomes.iter().filter(|o| o.error.is_some()).count();
+    if json {
+        print_json(&outcomes);
+    } else {
+        for outcome in &outcomes {
+            if let Some(error) = &outcome.error {
+                println!("{}: failed: {error}", outcome.name);
+                continue;
+            }
+            println!(
+                "{}: {} on changed lines, {} pre-existing",
+                outcome.name,
+                outcome.findings.len(),
+                outcome.pre_existing
+            );
+            for finding in &outcome.findings {
+                println!(
+                    "  {:<7} {}:{} {}{}",
+                    finding.severity.as_str(),
+                    finding.file_path,
+                    finding.line,
+                    finding
+                        .rule
+                        .as_deref()
+                        .map(|r| format!("[{r}] "))
+                        .unwrap_or_default(),
+                    finding.message
+                );
+            }
+        }
+        if fixed.get() > 0 {
+            println!("{} finding(s) from earlier runs are fixed.", fixed.get());
+        }
+    }
+    if failed > 0 {
+        return Err(format!("{failed} linter(s) failed"));
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::review::permalink::{self, ReviewLink};
 use crate::review::state::HunkStatus;
 use crate::review::storage;
 use crate::service::targets::{self, BaseReason, ResolvedReview};
@@ -7,12 +8,47 @@ use clap::{Parser, Subcommand};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+mod attest;
+mod bench;
+mod bisect;
+mod breakdown;
+mod checklist;
+mod classify;
 mod comments;
 mod common;
+mod digest;
+mod dirdiff;
+mod encryption;
+mod explain;
+mod export;
+mod files;
+mod gerrit;
 mod guide;
+mod inbox;
+mod ingest;
+mod init;
+mod lint;
+mod patch;
+mod pr;
+mod prewarm;
+mod protection;
+mod redactions;
+mod relink;
+mod remote;
 mod review_state;
+mod semgrep;
+mod similar;
+mod size;
 mod skill;
+mod split;
 mod staging;
+mod stats;
+mod submodules;
+mod support_bundle;
+mod tailscale;
+mod taxonomy;
+mod timeline;
+mod two_person;
 mod url;
 
 #[derive(Debug, Parser)]
@@ -23,10 +59,16 @@ pub struct Cli {
     #[arg(long, global = true)]
     pub home: Option<String>,
 
+    /// Disable everything that uses the network: AI, GitHub, Tailscale,
+    /// digest mail, metrics (env: REVIEW_OFFLINE)
+    #[arg(long, global = true)]
+    pub offline: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 
-    /// Path to open (file or directory, defaults to current directory)
+    /// Path to open (file or directory, defaults to current directory), or a
+    /// `review://` link
     pub path: Option<String>,
 }
 
@@ -40,7 +82,8 @@ pub enum Commands {
 
         /// Review spec: a ref (a branch — vs the default branch; a tag or bare
         /// commit — reviewed on its own); "base..ref" to pin the base;
-        /// "<rev>^!" (a single commit); or "snapshot:<rev>". Defaults to the
+        /// "<rev>^!" (a single commit); or "snapshot:<rev>". ":index" and
+        /// ":worktree" name the staged and unstaged changes. Defaults to the
         /// current branch.
         spec: Option<String>,
 
@@ -77,6 +120,20 @@ pub enum Commands {
             conflicts_with_all = ["spec", "old", "new", "commit", "working", "stash"]
         )]
         patch: Option<String>,
+
+        /// Review staged changes — what the next commit would contain (HEAD..:index).
+        #[arg(
+            long,
+            conflicts_with_all = ["spec", "old", "new", "commit", "working", "stash", "patch"]
+        )]
+        staged: bool,
+
+        /// Review unstaged changes, untracked files included (:index..:worktree).
+        #[arg(
+            long,
+            conflicts_with_all = ["spec", "old", "new", "commit", "working", "stash", "patch", "staged"]
+        )]
+        unstaged: bool,
     },
 
     /// List uncommitted working-tree changes as individual hunks
@@ -106,18 +163,112 @@ pub enum Commands {
     /// Show review progress for a comparison
     Status(review_state::StatusArgs),
 
+    /// Show a review's activity — commits, classifications, decisions, comments
+    Timeline(timeline::TimelineArgs),
+
+    /// Propose splitting a long review into smaller ones by commit range (`--create` to save them)
+    Split(split::SplitArgs),
+
+    /// Re-run AI labels on chosen hunks (`--hunk`, `--file`, `--only-label`), or compare models (`--compare`)
+    Classify(classify::ClassifyArgs),
+
+    /// Re-evaluate one hunk's labels with its surrounding symbols, citing the lines behind the verdict
+    Explain(explain::ExplainArgs),
+
+    /// Sync PRs awaiting your review into pre-classified reviews (`--watch` to keep polling)
+    Inbox(inbox::InboxArgs),
+
+    /// Create and classify reviews for pull requests ahead of time (`--prs 12,13`)
+    Prewarm(prewarm::PrewarmArgs),
+
+    /// Scan the repo and write a starter `.review.toml` (ignore globs, trust patterns)
+    Init(init::InitArgs),
+
+    /// Summarize completed reviews across all repos for a team email (`--since 7d`, `--send`)
+    Digest(digest::DigestArgs),
+
+    /// Find the commit in the review that made a test command fail (`--command`)
+    Bisect(bisect::BisectArgs),
+
+    /// Record a static analyzer's report as findings on the review's hunks (`--format clippy`)
+    Ingest(ingest::IngestArgs),
+
+    /// Run Semgrep over the review's changed files and record its findings on their hunks
+    Semgrep(semgrep::SemgrepArgs),
+
+    /// Run the linters configured in `.review.toml`, keeping what they flag on changed lines
+    Lint(lint::LintArgs),
+
+    /// Build the base and head and report how the build artifacts' sizes changed
+    Size(size::SizeArgs),
+
+    /// Benchmark the base and head and flag benchmarks that got slower
+    Bench(bench::BenchArgs),
+
+    /// Show lines, hunks, generated and test share per language and directory
+    Breakdown(breakdown::BreakdownArgs),
+
+    /// Export hunk-level review records as CSV or Parquet for analysis
+    Export(export::ExportArgs),
+
+    /// Show time spent reviewing and how often AI labels were corrected, or export the corrections
+    Stats(stats::StatsArgs),
+
+    /// List changed submodules as nested review scopes, with their progress
+    Submodules(submodules::SubmodulesArgs),
+
+    /// List a review's files, flagging symlinks, LFS pointers and sparse-checkout gaps
+    Files(files::FilesArgs),
+
     /// List saved reviews
     List(review_state::ListArgs),
 
     /// Delete a saved review
     Delete(review_state::DeleteArgs),
 
+    /// Archive a review: hide it from listings, keep its decisions compressed
+    Archive(review_state::ArchiveArgs),
+
+    /// Restore an archived review
+    Unarchive(review_state::ArchiveArgs),
+
     /// Pin (or clear) a review's base override — a derived setting, not identity
     ChangeBase(review_state::ChangeBaseArgs),
 
     /// Inspect or edit the trust list
     Trust(review_state::TrustArgs),
 
+    /// Encrypt a review at rest with a passphrase, or unlock/lock it
+    Encryption(encryption::EncryptionArgs),
+
+    /// Show what was redacted from AI prompts
+    Redactions(redactions::RedactionsArgs),
+
+    /// Find hunks like the review's among those already reviewed: similar,
+    /// duplicated across reviews, or worth approving
+    Similar(similar::SimilarArgs),
+
+    /// Review a .patch/.diff file on its own, no refs needed (`--approve`, `--reject`, `--trust`)
+    Patch(patch::PatchArgs),
+
+    /// Diff two plain directories (no git repo needed) into labeled hunks
+    Dirdiff(dirdiff::DirDiffArgs),
+
+    /// Review a remote repo's refs through a cached partial clone (no local checkout needed)
+    Remote(remote::RemoteArgs),
+
+    /// Fetch a Gerrit change's patch sets and start a review of one
+    Gerrit(gerrit::GerritArgs),
+
+    /// GitHub pull request sync (`pr submit [--approve|--request-changes|--comment]`, `pr threads`, `pr status`)
+    Pr(pr::PrArgs),
+
+    /// Show how to require `review check` in the branch's GitHub protection, or `--post <PR>` its verdict
+    Protection(protection::ProtectionArgs),
+
+    /// Validate the label taxonomy and the review's trust list
+    Taxonomy(taxonomy::TaxonomyArgs),
+
     /// Read or edit review notes
     Note(review_state::NoteArgs),
 
@@ -130,6 +281,18 @@ pub enum Commands {
     /// Show, author, or clear the review guide (an agent-authored hunk grouping)
     Guide(guide::GuideArgs),
 
+    /// Show or tick off the review's checklist (from `.review/checklist.md`)
+    Checklist(checklist::ChecklistArgs),
+
+    /// Fail unless every checklist item is checked off
+    Check(checklist::ShowArgs),
+
+    /// Two-person review: require a second reviewer, hand off, sign off
+    TwoPerson(two_person::TwoPersonArgs),
+
+    /// Sign a completed review's diff and decisions with your SSH/GPG key, or verify the signature
+    Attest(attest::AttestArgs),
+
     /// Print a `review://` deep link for a file or hunk
     Url(url::UrlArgs),
 
@@ -138,6 +301,15 @@ pub enum Commands {
 
     /// Set (or show/clear) the default comparison so commands don't need `-s`
     Use(UseArgs),
+
+    /// Reattach the reviews of a repo that was moved or renamed
+    Relink(relink::RelinkArgs),
+
+    /// Zip recent logs, redacted settings, and diagnostics for a bug report
+    SupportBundle(support_bundle::SupportBundleArgs),
+
+    /// Expose the review server through Tailscale Serve or Funnel, or show its status
+    Tailscale(tailscale::TailscaleArgs),
 }
 
 /// `review use [spec]` — the repo's stored default comparison. With a spec,
@@ -216,7 +388,7 @@ fn run_use(args: UseArgs) -> Result<(), String> {
 }
 
 /// Resolve a potentially relative path to an absolute one.
-fn resolve_absolute(path: &Path) -> Result<PathBuf, String> {
+pub(crate) fn resolve_absolute(path: &Path) -> Result<PathBuf, String> {
     if path.is_absolute() {
         return Ok(path.to_path_buf());
     }
@@ -245,6 +417,9 @@ pub fn run(cli: Cli) -> Result<(), String> {
         let absolute = resolve_absolute(Path::new(home))?;
         std::env::set_var("REVIEW_HOME", &absolute);
     }
+    if cli.offline {
+        std::env::set_var(crate::offline::OFFLINE_ENV, "1");
+    }
 
     match cli.command {
         Some(Commands::Start {
@@ -256,9 +431,13 @@ pub fn run(cli: Cli) -> Result<(), String> {
             working,
             stash,
             patch,
+            staged,
+            unstaged,
         }) => run_start(
             repo,
-            StartTarget::from_args(spec, old, new, commit, working, stash, patch),
+            StartTarget::from_args(
+                spec, old, new, commit, working, stash, patch, staged, unstaged,
+            ),
             has_home_override,
         ),
         Some(Commands::Changes(args)) => staging::run_changes(args),
@@ -270,10 +449,48 @@ pub fn run(cli: Cli) -> Result<(), String> {
         Some(Commands::Save(args)) => review_state::run_mark(args, HunkStatus::SavedForLater),
         Some(Commands::Unmark(args)) => review_state::run_unmark(args),
         Some(Commands::Status(args)) => review_state::run_status(args),
+        Some(Commands::Timeline(args)) => timeline::run_timeline(args),
+        Some(Commands::Split(args)) => split::run_split(args),
+        Some(Commands::Classify(args)) => classify::run_classify(args),
+        Some(Commands::Explain(args)) => explain::run_explain(args),
+        Some(Commands::Inbox(args)) => inbox::run_inbox(args),
+        Some(Commands::Prewarm(args)) => prewarm::run_prewarm(args),
+        Some(Commands::Stats(args)) => stats::run_stats(args),
+        Some(Commands::Init(args)) => init::run_init(args),
+        Some(Commands::Digest(args)) => digest::run_digest(args),
+        Some(Commands::Bisect(args)) => bisect::run_bisect(args),
+        Some(Commands::Ingest(args)) => ingest::run_ingest(args),
+        Some(Commands::Semgrep(args)) => semgrep::run_semgrep_scan(args),
+        Some(Commands::Lint(args)) => lint::run_lint(args),
+        Some(Commands::Size(args)) => size::run_size(args),
+        Some(Commands::Bench(args)) => bench::run_bench(args),
+        Some(Commands::Breakdown(args)) => breakdown::run_breakdown(args),
+        Some(Commands::Export(args)) => export::run_export(args),
+        Some(Commands::Submodules(args)) => submodules::run_submodules(args),
+        Some(Commands::Files(args)) => files::run_files(args),
         Some(Commands::List(args)) => review_state::run_list(args),
         Some(Commands::Delete(args)) => review_state::run_delete(args),
+        Some(Commands::Archive(args)) => review_state::run_archive(args, true),
+        Some(Commands::Unarchive(args)) => review_state::run_archive(args, false),
         Some(Commands::ChangeBase(args)) => review_state::run_change_base(args),
         Some(Commands::Trust(args)) => review_state::run_trust(args),
+        Some(Commands::Encryption(args)) => encryption::run_encryption(args),
+        Some(Commands::Redactions(args)) => redactions::run_redactions(args),
+        Some(Commands::Similar(args)) => similar::run_similar(args),
+        Some(Commands::Patch(args)) => patch::run_patch(&args),
+        Some(Commands::Dirdiff(args)) => dirdiff::run_dirdiff(&args),
+        Some(Commands::Remote(args)) => remote::run_remote(&args),
+        Some(Commands::Gerrit(args)) => gerrit::run_gerrit(args),
+        Some(Commands::Pr(args)) => match args.action {
+            pr::PrAction::Submit(a) => pr::run_submit(&args.target, a),
+            pr::PrAction::Threads(a) => pr::run_threads(&args.target, &a),
+            pr::PrAction::Status(a) => pr::run_status(&args.target, &a),
+        },
+        Some(Commands::Protection(args)) => protection::run_protection(args),
+        Some(Commands::Taxonomy(args)) => match args.action {
+            taxonomy::TaxonomyAction::Lint(a) => taxonomy::run_lint(a),
+            taxonomy::TaxonomyAction::Stale(a) => taxonomy::run_stale(a),
+        },
         Some(Commands::Note(args)) => review_state::run_note(args),
         Some(Commands::Comments(mut args)) => match args.action.take() {
             Some(comments::CommentsAction::Submit(a)) => {
@@ -293,9 +510,24 @@ pub fn run(cli: Cli) -> Result<(), String> {
             guide::GuideAction::Add(a) => guide::run_add(a),
             guide::GuideAction::Clear(a) => guide::run_clear(a),
         },
+        Some(Commands::Checklist(args)) => match args.action {
+            checklist::ChecklistAction::Show(a) => checklist::run_show(a),
+            checklist::ChecklistAction::Tick(a) => checklist::run_tick(a, true),
+            checklist::ChecklistAction::Untick(a) => checklist::run_tick(a, false),
+        },
+        Some(Commands::Check(args)) => checklist::run_check(args),
+        Some(Commands::TwoPerson(args)) => two_person::run(args),
+        Some(Commands::Attest(args)) => attest::run(&args),
         Some(Commands::Url(args)) => url::run_url(args),
         Some(Commands::Skill(args)) => skill::run_skill(args),
         Some(Commands::Use(args)) => run_use(args),
+        Some(Commands::Relink(args)) => relink::run_relink(args),
+        Some(Commands::SupportBundle(args)) => support_bundle::run_support_bundle(args),
+        Some(Commands::Tailscale(args)) => match args.action {
+            tailscale::TailscaleAction::Status(a) => tailscale::run_status(a),
+            tailscale::TailscaleAction::Expose(a) => tailscale::run_expose(a),
+            tailscale::TailscaleAction::Unexpose(a) => tailscale::run_unexpose(a),
+        },
         None => run_open(cli.path, has_home_override),
     }
 }
@@ -317,6 +549,9 @@ fn warn_home_override(has_home_override: bool) {
 /// nothing to diff), so we fall back to browse mode, which still has the file
 /// tree to show. A specific file also opens in browse mode, focused on that file.
 fn run_open(path: Option<String>, has_home_override: bool) -> Result<(), String> {
+    if let Some(link) = path.as_deref().filter(|p| p.starts_with(permalink::SCHEME)) {
+        return open_link(link, has_home_override);
+    }
     let (repo_path, focused_file) = resolve_open_path(path)?;
 
     let review = if focused_file.is_none() {
@@ -341,6 +576,37 @@ fn run_open(path: Option<String>, has_home_override: bool) -> Result<(), String>
         &repo_path,
         review.as_ref().map(|r| r.ref_name.as_str()),
         focused_file.as_deref(),
+        None,
+    )?;
+    warn_home_override(has_home_override);
+    Ok(())
+}
+
+/// `review review://…` — open the app at the review and hunk a link names.
+/// The link's repo id is looked up among the repos registered on this machine.
+fn open_link(raw: &str, has_home_override: bool) -> Result<(), String> {
+    let link = ReviewLink::parse(raw).ok_or_else(|| format!("Not a review link: {raw}"))?;
+    let repo_path = link.repo_path().ok_or_else(|| {
+        format!(
+            "Repo {} isn't registered here; open it with `review <path>` first.",
+            link.repo_id
+        )
+    })?;
+    if let Some(review_ref) = &link.review_ref {
+        // Mirrors `review start`: a pinned base is persisted with the review.
+        storage::ensure_review_exists(
+            Path::new(&repo_path),
+            review_ref,
+            link.base_override.clone(),
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    open_app(
+        &repo_path,
+        link.review_ref.as_deref(),
+        link.file.as_deref(),
+        link.hunk_hash.as_deref(),
     )?;
     warn_home_override(has_home_override);
     Ok(())
@@ -383,6 +649,9 @@ enum StartTarget {
     Stash(u32),
     /// A unified-diff patch from a file or stdin (`--patch <file|->`).
     Patch(String),
+    /// The index vs HEAD (`--staged`) or the working tree vs the index
+    /// (`--unstaged`), by its endpoint token.
+    Uncommitted(&'static str),
 }
 
 impl StartTarget {
@@ -397,8 +666,14 @@ impl StartTarget {
         working: bool,
         stash: Option<u32>,
         patch: Option<String>,
+        staged: bool,
+        unstaged: bool,
     ) -> Self {
-        if working {
+        if staged {
+            StartTarget::Uncommitted(Comparison::INDEX)
+        } else if unstaged {
+            StartTarget::Uncommitted(Comparison::WORKTREE)
+        } else if working {
             StartTarget::Working
         } else if let Some(n) = stash {
             StartTarget::Stash(n)
@@ -423,6 +698,9 @@ impl StartTarget {
             }
             StartTarget::Stash(n) => resolve_ref_review(repo_path, &format!("stash@{{{n}}}"), None),
             StartTarget::Patch(src) => resolve_patch_review(repo_path, &src),
+            // The ladder maps `:index` to `HEAD..:index` and `:worktree` to
+            // `:index..:worktree`.
+            StartTarget::Uncommitted(endpoint) => resolve_ref_review(repo_path, endpoint, None),
             // Pin the commit's SHA as the ref so the review is stable even as
             // HEAD/branches move; the ladder reviews it as `sha^..sha`.
             StartTarget::Commit(rev) => {
@@ -454,7 +732,7 @@ fn run_start(
     let review = target.resolve(&path)?;
     storage::ensure_review_exists(&path, &review.ref_name, review.base_override.clone(), None)
         .map_err(|e| e.to_string())?;
-    open_app(&repo_path, Some(&review.ref_name), None)?;
+    open_app(&repo_path, Some(&review.ref_name), None, None)?;
     warn_home_override(has_home_override);
     Ok(())
 }
@@ -497,6 +775,8 @@ fn resolve_ref_sha(repo_path: &Path, rev: &str) -> Result<String, String> {
 ///   single-commit rule yields `rev^..rev`).
 /// - `snapshot:<rev>` → `(rev, Some(""))` — the full tree at a rev, diffed
 ///   against the empty tree (empty-string base is the empty-tree convention).
+///
+/// Either side may be an endpoint token: `:index` (staged) or `:worktree`.
 pub(crate) fn parse_review_spec(spec: &str) -> Result<(String, Option<String>), String> {
     // Explicit range — an empty side means HEAD, matching git's `a..` / `..b`.
     if let Some((base, head)) = spec.split_once("..") {
@@ -561,14 +841,17 @@ fn open_request_path() -> PathBuf {
     PathBuf::from(tmp).join("review-open-request")
 }
 
-/// Launch the Review desktop app for the given repo, optionally with a review ref and/or focused file.
+/// Launch the Review desktop app for the given repo, optionally with a review
+/// ref, focused file, and focused hunk.
 fn open_app(
     repo_path: &str,
     review_ref: Option<&str>,
     focused_file: Option<&str>,
+    focused_hunk_hash: Option<&str>,
 ) -> Result<(), String> {
-    // Write a signal file with a timestamp, repo path, optional review ref, and optional focused file.
-    // Always write all 4 lines, using empty strings for missing optional values.
+    // Write a signal file with a timestamp, repo path, optional review ref, optional focused file,
+    // and optional focused hunk hash.
+    // Always write all 5 lines, using empty strings for missing optional values.
     // This is the reliable channel for the already-running case where
     // `open -a` activates the app but drops `--args`.
     let now = std::time::SystemTime::now()
@@ -576,9 +859,10 @@ fn open_app(
         .unwrap_or_default()
         .as_secs();
     let signal_content = format!(
-        "{now}\n{repo_path}\n{}\n{}",
+        "{now}\n{repo_path}\n{}\n{}\n{}",
         review_ref.unwrap_or(""),
-        focused_file.unwrap_or("")
+        focused_file.unwrap_or(""),
+        focused_hunk_hash.unwrap_or("")
     );
     let _ = std::fs::write(open_request_path(), signal_content);
 
@@ -602,6 +886,9 @@ fn open_app(
             if let Ok(review_home) = std::env::var("REVIEW_HOME") {
                 cmd.env("REVIEW_HOME", review_home);
             }
+            if let Some(offline) = std::env::var_os(crate::offline::OFFLINE_ENV) {
+                cmd.env(crate::offline::OFFLINE_ENV, offline);
+            }
 
             cmd.arg("-a").arg(app_path).arg("--args").arg(repo_path);
 
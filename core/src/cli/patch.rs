@@ -0,0 +1,177 @@
+//! `review patch <file|->` — review a `.patch`/`.diff` file on its own, with
+//! neither side of the change needed locally (see
+//! [`crate::sources::patch`]). The review is stored in the repo under
+//! `patch:<name>`; rerunning with a revised patch of the same name keeps the
+//! decisions on hunks that didn't change.
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::classify::classify_repo_hunks_static;
+use crate::review::state::{Attributed, HunkStatus};
+use crate::review::storage;
+use crate::sources::patch::PatchSource;
+
+use super::common::{
+    effective_status, hunk_labels, hunk_line_stats, live_hunk_ids, mutate_review, print_json,
+    resolve_source, sync_classification, EffectiveStatus,
+};
+use super::{get_repo_path, read_patch_input};
+
+#[derive(Debug, Args)]
+pub struct PatchArgs {
+    /// The patch file ("-" reads stdin)
+    pub file: String,
+    /// Repository to store the review in (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Name to store the review under (default: the file name; required for stdin)
+    #[arg(long)]
+    pub name: Option<String>,
+    /// Approve these hunks (repeatable)
+    #[arg(long, value_name = "HUNK")]
+    pub approve: Vec<String>,
+    /// Reject these hunks (repeatable)
+    #[arg(long, value_name = "HUNK")]
+    pub reject: Vec<String>,
+    /// Add a pattern to this review's trust list, e.g. "imports:*" (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub trust: Vec<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PatchHunkJson {
+    id: String,
+    file: String,
+    new_start: u32,
+    additions: usize,
+    deletions: usize,
+    labels: Vec<String>,
+    status: EffectiveStatus,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PatchReviewJson {
+    #[serde(rename = "ref")]
+    ref_name: String,
+    version: u64,
+    hunks: Vec<PatchHunkJson>,
+}
+
+fn load_patch(args: &PatchArgs) -> Result<PatchSource, String> {
+    let diff = read_patch_input(&args.file)?;
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None if args.file == "-" => {
+            return Err("A patch read from stdin needs --name to store its review under".to_owned())
+        }
+        None => Path::new(&args.file)
+            .file_name()
+            .map_or_else(|| args.file.clone(), |n| n.to_string_lossy().into_owned()),
+    };
+    Ok(PatchSource::new(name, diff))
+}
+
+/// `review patch` — store the patch's review (classified, with any decisions
+/// and trust patterns given) and list its hunks.
+pub fn run_patch(args: &PatchArgs) -> Result<(), String> {
+    let repo = PathBuf::from(get_repo_path(&args.repo)?);
+    let patch = load_patch(args)?;
+    let hunks = patch.hunks();
+    if hunks.is_empty() {
+        return Err(format!("No hunks found in {}", patch.name()));
+    }
+    let ref_name = patch.ref_name();
+    let live_ids = live_hunk_ids(&hunks);
+    for id in args.approve.iter().chain(&args.reject) {
+        if !live_ids.contains(id) {
+            eprintln!("warning: hunk not found in {}: {id}", patch.name());
+        }
+    }
+    let classification = classify_repo_hunks_static(&repo, &hunks);
+    let source = resolve_source(None)?;
+
+    storage::ensure_review_exists(&repo, &ref_name, None, None).map_err(|e| e.to_string())?;
+    let state = mutate_review(&repo, &ref_name, &hunks, |state| {
+        state.set_diff_hunks(&hunks);
+        sync_classification(state, &classification);
+        for pattern in &args.trust {
+            if !state.trust_list.contains(pattern) {
+                state.trust_list.push(pattern.clone());
+            }
+        }
+        let decisions = [
+            (&args.approve, HunkStatus::Approved),
+            (&args.reject, HunkStatus::Rejected),
+        ];
+        for (ids, status) in decisions {
+            for id in ids.iter().filter(|id| live_ids.contains(*id)) {
+                state.hunks.entry(id.clone()).or_default().status =
+                    Some(Attributed::new(status.clone(), source));
+            }
+        }
+        true
+    })?;
+
+    let rows: Vec<PatchHunkJson> = hunks
+        .iter()
+        .map(|hunk| {
+            let labels = hunk_labels(&hunk.id, &state, &classification);
+            let status = effective_status(&hunk.id, &labels, &state);
+            let (additions, deletions) = hunk_line_stats(hunk);
+            PatchHunkJson {
+                id: hunk.id.clone(),
+                file: hunk.file_path.clone(),
+                new_start: hunk.new_start,
+                additions,
+                deletions,
+                labels,
+                status,
+            }
+        })
+        .collect();
+    if args.json {
+        print_json(&PatchReviewJson {
+            ref_name,
+            version: state.version,
+            hunks: rows,
+        });
+        return Ok(());
+    }
+    let reviewed = rows
+        .iter()
+        .filter(|row| {
+            matches!(
+                row.status,
+                EffectiveStatus::Approved | EffectiveStatus::Rejected | EffectiveStatus::Trusted
+            )
+        })
+        .count();
+    println!(
+        "{ref_name} — {reviewed}/{} hunks reviewed (review v{})",
+        rows.len(),
+        state.version
+    );
+    for row in &rows {
+        let labels = if row.labels.is_empty() {
+            String::new()
+        } else {
+            format!("  [{}]", row.labels.join(", "))
+        };
+        println!(
+            "  {:<10}  {}  +{} -{}{labels}",
+            row.status.as_str(),
+            row.id,
+            row.additions,
+            row.deletions
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,190 @@
+//! `review pr submit` — post the review to its GitHub pull request (see
+//! [`crate::service::pr_review`]); `review pr threads` — import the PR's
+//! review threads as comments (see [`sync_pr_threads`]); `review pr status` —
+//! post the review's progress on the PR head (see
+//! [`crate::service::pr_status`]).
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::review::storage::{self, sync_pr_threads};
+use crate::service::pr_review::{draft_review, submit_review};
+use crate::service::pr_status::publish_progress;
+use crate::sources::github::PrReviewEvent;
+
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct PrArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    #[command(subcommand)]
+    pub action: PrAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PrAction {
+    /// Submit the review to the PR, with a summary of how its hunks were reviewed
+    Submit(SubmitArgs),
+    /// Import the PR's review threads as comments on the hunks they were left on
+    Threads(ThreadsArgs),
+    /// Post the review's progress as a `review/progress` status on the PR head
+    Status(StatusArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct StatusArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ThreadsArgs {
+    /// Pull request number, for a review that wasn't started from one
+    #[arg(long)]
+    pub pr: Option<u32>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SubmitArgs {
+    #[command(flatten)]
+    pub event: EventArgs,
+    /// Text to put above the summary
+    #[arg(short, long)]
+    pub message: Option<String>,
+    /// Pull request number, for a review that wasn't started from one
+    #[arg(long)]
+    pub pr: Option<u32>,
+    /// Print the review without submitting it
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// The review event; left unset, the review's state picks it.
+#[derive(Debug, Args)]
+pub struct EventArgs {
+    /// Approve the PR (the default once every hunk is reviewed and none rejected)
+    #[arg(long, conflicts_with_all = ["request_changes", "comment"])]
+    pub approve: bool,
+    /// Request changes (the default once a hunk is rejected)
+    #[arg(long, conflicts_with = "comment")]
+    pub request_changes: bool,
+    /// Only comment (the default while hunks are unreviewed)
+    #[arg(long)]
+    pub comment: bool,
+}
+
+impl EventArgs {
+    fn event(&self) -> Option<PrReviewEvent> {
+        if self.approve {
+            Some(PrReviewEvent::Approve)
+        } else if self.request_changes {
+            Some(PrReviewEvent::RequestChanges)
+        } else if self.comment {
+            Some(PrReviewEvent::Comment)
+        } else {
+            None
+        }
+    }
+}
+
+/// `review pr submit`.
+pub fn run_submit(target: &ReviewTarget, args: SubmitArgs) -> Result<(), String> {
+    let SubmitArgs {
+        event,
+        message,
+        pr,
+        dry_run,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let event = event.event();
+
+    let draft = if dry_run {
+        let mut draft = draft_review(&repo, &review.ref_name, event, message.as_deref())
+            .map_err(|e| format!("{e:#}"))?;
+        draft.number = pr.or(draft.number);
+        draft
+    } else {
+        submit_review(&repo, &review.ref_name, pr, event, message.as_deref())
+            .map_err(|e| format!("{e:#}"))?
+    };
+
+    if json {
+        print_json(&draft);
+        return Ok(());
+    }
+    let number = draft
+        .number
+        .map_or_else(|| "(no PR)".to_owned(), |n| format!("#{n}"));
+    if dry_run {
+        println!("Would submit {} to {number}:\n", draft.event.as_str());
+    } else {
+        println!("Submitted {} to {number}:\n", draft.event.as_str());
+    }
+    print!("{}", draft.body);
+    Ok(())
+}
+
+/// `review pr threads`.
+pub fn run_threads(target: &ReviewTarget, args: &ThreadsArgs) -> Result<(), String> {
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let sync = sync_pr_threads(&repo, &review.ref_name, args.pr).map_err(|e| e.to_string())?;
+    if args.json {
+        print_json(&sync);
+        return Ok(());
+    }
+    println!(
+        "Imported review threads into {}: {} added, {} updated, {} removed",
+        review.ref_name, sync.added, sync.updated, sync.removed
+    );
+    if sync.unanchored > 0 {
+        println!(
+            "{} thread(s) left out: outdated, or on lines outside the diff",
+            sync.unanchored
+        );
+    }
+    Ok(())
+}
+
+/// `review pr status`. The desktop app publishes as the review is saved (with
+/// `publishReviewStatus` on); CLI mutations don't wait on GitHub, so this
+/// posts on demand.
+pub fn run_status(target: &ReviewTarget, args: &StatusArgs) -> Result<(), String> {
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    if !storage::review_exists(&repo, &review.ref_name).unwrap_or(false) {
+        return Err(format!("No review of {} yet.", review.ref_name));
+    }
+    let published = publish_progress(&repo, &review.ref_name).map_err(|e| format!("{e:#}"))?;
+    if args.json {
+        print_json(&published);
+        return Ok(());
+    }
+    match published {
+        Some(posted) => println!(
+            "Posted `{}`: {} on #{} ({:.12}) — {}",
+            posted.status.context,
+            posted.status.state,
+            posted.pr,
+            posted.sha,
+            posted.status.description
+        ),
+        None => println!(
+            "Nothing to post: {} isn't a pull request review, or its status is already current.",
+            review.ref_name
+        ),
+    }
+    Ok(())
+}
@@ -0,0 +1,110 @@
+//! `review prewarm --prs 123,124` — create reviews for a batch of pull
+//! requests and classify their hunks ahead of time, so each review opens
+//! already labeled. Grouping is left to `review guide`, which caches its
+//! result the first time the review is opened.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::ai::check_claude_available;
+use crate::service::prewarm::{prewarm_pull_request, PrewarmResult};
+use crate::sources::github::{GitHubClient, GitHubProvider};
+
+use super::common::print_json;
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct PrewarmArgs {
+    /// Repository path (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Pull request numbers, comma-separated
+    #[arg(long, value_delimiter = ',', num_args = 1.., required_unless_present = "all_open")]
+    pub prs: Vec<u32>,
+    /// Pre-warm every open pull request
+    #[arg(long, conflicts_with = "prs")]
+    pub all_open: bool,
+    /// Model for AI classification
+    #[arg(long, default_value = "sonnet")]
+    pub model: String,
+    /// Only run static classification
+    #[arg(long)]
+    pub no_ai: bool,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `review prewarm` — pre-warm each PR; keep going past failures, then fail
+/// if any PR couldn't be pre-warmed.
+pub fn run_prewarm(args: PrewarmArgs) -> Result<(), String> {
+    let PrewarmArgs {
+        repo,
+        prs,
+        all_open,
+        model,
+        no_ai,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&repo)?);
+    let provider = GitHubClient::for_repo(repo.clone());
+    let pulls = if all_open {
+        provider.list_pull_requests().map_err(|e| e.to_string())?
+    } else {
+        prs.iter()
+            .map(|&n| {
+                provider
+                    .get_pull_request(n)
+                    .map_err(|e| format!("#{n}: {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    if pulls.is_empty() {
+        if !json {
+            println!("No pull requests to pre-warm.");
+        }
+        return Ok(());
+    }
+
+    let model = if no_ai {
+        None
+    } else if check_claude_available() {
+        Some(model.as_str())
+    } else {
+        eprintln!("Claude CLI not found; running static classification only.");
+        None
+    };
+
+    let mut results: Vec<PrewarmResult> = Vec::new();
+    let mut failures = Vec::new();
+    for pr in &pulls {
+        if !json {
+            eprintln!("Pre-warming #{} {}…", pr.number, pr.title);
+        }
+        match prewarm_pull_request(&repo, pr, model) {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                eprintln!("  #{}: {e:#}", pr.number);
+                failures.push(pr.number);
+            }
+        }
+    }
+
+    if json {
+        print_json(&results);
+    } else {
+        for r in &results {
+            println!(
+                "#{:<6} {:<40} {} hunk(s): {} static, {} AI",
+                r.number, r.ref_name, r.hunks, r.static_labeled, r.ai_labeled
+            );
+        }
+    }
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        let numbers: Vec<String> = failures.iter().map(|n| format!("#{n}")).collect();
+        Err(format!("Failed to pre-warm {}.", numbers.join(", ")))
+    }
+}
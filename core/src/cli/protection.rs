@@ -0,0 +1,287 @@
+//! `review protection` — advise on gating merges with `review check`.
+//!
+//! Reads the branch's GitHub protection rule through `gh api` and says how to
+//! make a `review` status required. The review state lives on the reviewer's
+//! machine, not in CI, so the status is posted from here: `--post <PR>` runs
+//! the `review check` gate on the PR's review and posts the verdict as a
+//! commit status on the PR's head.
+
+use std::path::PathBuf;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::review::state::ReviewSummary;
+use crate::review::storage;
+use crate::service::review_io::get_review_progress;
+use crate::sources::github::{BranchProtection, CommitStatus, GhCliProvider};
+use crate::sources::local_git::LocalGitSource;
+
+use super::checklist::check_gate;
+use super::common::print_json;
+use super::get_repo_path;
+
+/// GitHub cuts status descriptions off past this many characters.
+const MAX_DESCRIPTION_CHARS: usize = 140;
+
+#[derive(Debug, Args)]
+pub struct ProtectionArgs {
+    /// Repository path (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Protected branch to inspect (defaults to the repo's default branch)
+    #[arg(long)]
+    pub branch: Option<String>,
+    /// Status name the review is reported under
+    #[arg(long, default_value = "review")]
+    pub context: String,
+    /// Post the review's `review check` verdict as a status on this PR's head
+    #[arg(long, value_name = "PR")]
+    pub post: Option<u32>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProtectionReport {
+    branch: String,
+    context: String,
+    /// `None` when the rule couldn't be read (it needs admin rights).
+    protected: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    required_checks: Vec<String>,
+    required_approvals: u32,
+    /// Whether `context` is already a required status.
+    required: bool,
+    /// `gh` command that makes `context` required, when it isn't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    posted: Option<PostedStatus>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PostedStatus {
+    pr: u32,
+    sha: String,
+    #[serde(flatten)]
+    status: CommitStatus,
+}
+
+/// The `gh` command that makes `context` a required status on `branch`:
+/// added to an existing rule, or a new rule requiring nothing else.
+fn require_command(branch: &str, context: &str, protected: bool) -> String {
+    if protected {
+        format!(
+            "gh api --method POST repos/{{owner}}/{{repo}}/branches/{branch}/protection/required_status_checks/contexts -f 'contexts[]={context}'"
+        )
+    } else {
+        let rule = serde_json::json!({
+            "required_status_checks": { "strict": false, "contexts": [context] },
+            "enforce_admins": null,
+            "required_pull_request_reviews": null,
+            "restrictions": null,
+        });
+        format!(
+            "echo '{rule}' | gh api --method PUT repos/{{owner}}/{{repo}}/branches/{branch}/protection --input -"
+        )
+    }
+}
+
+/// The status to post for a review: `failure` once a hunk is rejected,
+/// `pending` while the `review check` gate fails, else `success`.
+fn review_status(
+    summary: &ReviewSummary,
+    gate_failure: Option<&str>,
+    context: &str,
+) -> CommitStatus {
+    let progress = format!(
+        "{}/{} hunks reviewed",
+        summary.reviewed_hunks, summary.total_hunks
+    );
+    let (state, description) = if summary.rejected_hunks > 0 {
+        (
+            "failure",
+            format!("{progress}, {} rejected", summary.rejected_hunks),
+        )
+    } else if let Some(failure) = gate_failure {
+        ("pending", format!("{progress}; {failure}"))
+    } else {
+        ("success", progress)
+    };
+    CommitStatus {
+        state: state.to_owned(),
+        context: context.to_owned(),
+        description: description.chars().take(MAX_DESCRIPTION_CHARS).collect(),
+    }
+}
+
+/// Run the gate on PR `number`'s review and post the verdict on its head.
+fn post_status(
+    repo: &std::path::Path,
+    provider: &GhCliProvider,
+    number: u32,
+    context: &str,
+) -> Result<PostedStatus, String> {
+    let pr = provider
+        .get_pull_request(number)
+        .map_err(|e| e.to_string())?;
+    let ref_name = pr.head_ref_name;
+    if !storage::review_exists(repo, &ref_name).unwrap_or(false) {
+        return Err(format!(
+            "No review of #{number} ({ref_name}) yet; start one with `review prewarm --prs {number}`."
+        ));
+    }
+    let gate = check_gate(repo, Some(&ref_name))?;
+    let summary = get_review_progress(repo, &ref_name)
+        .map_err(|e| format!("{e:#}"))?
+        .summary;
+    let status = review_status(&summary, gate.as_deref(), context);
+    let sha = provider
+        .get_pr_status(number)
+        .map_err(|e| e.to_string())?
+        .head_ref_oid;
+    provider
+        .create_commit_status(&sha, &status)
+        .map_err(|e| e.to_string())?;
+    Ok(PostedStatus {
+        pr: number,
+        sha,
+        status,
+    })
+}
+
+/// `review protection [--post <PR>]`.
+pub fn run_protection(args: ProtectionArgs) -> Result<(), String> {
+    let ProtectionArgs {
+        repo,
+        branch,
+        context,
+        post,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&repo)?);
+    let branch = match branch {
+        Some(branch) => branch,
+        None => LocalGitSource::new(repo.clone())
+            .and_then(|source| source.get_default_branch())
+            .map_err(|e| e.to_string())?,
+    };
+    let provider = GhCliProvider::new(repo.clone());
+
+    let (protected, error, rule) = match provider.get_branch_protection(&branch) {
+        Ok(rule) => (Some(rule.is_some()), None, rule.unwrap_or_default()),
+        Err(e) => (None, Some(e.to_string()), BranchProtection::default()),
+    };
+    let required_checks = rule
+        .required_status_checks
+        .map(|checks| checks.contexts)
+        .unwrap_or_default();
+    let required = required_checks.contains(&context);
+    let posted = post
+        .map(|number| post_status(&repo, &provider, number, &context))
+        .transpose()?;
+    let report = ProtectionReport {
+        command: (!required).then(|| require_command(&branch, &context, protected != Some(false))),
+        branch,
+        context,
+        protected,
+        error,
+        required_checks,
+        required_approvals: rule
+            .required_pull_request_reviews
+            .map_or(0, |reviews| reviews.required_approving_review_count),
+        required,
+        posted,
+    };
+
+    if json {
+        print_json(&report);
+        return Ok(());
+    }
+    match (report.protected, &report.error) {
+        (Some(true), _) => {
+            println!("{} is protected", report.branch);
+            if report.required_checks.is_empty() {
+                println!("  required checks: none");
+            } else {
+                println!("  required checks: {}", report.required_checks.join(", "));
+            }
+            println!("  required approvals: {}", report.required_approvals);
+        }
+        (Some(false), _) => println!("{} has no protection rule", report.branch),
+        (None, error) => println!(
+            "Couldn't read {}'s protection (needs admin rights): {}",
+            report.branch,
+            error.as_deref().unwrap_or_default()
+        ),
+    }
+    if report.required {
+        println!("`{}` is already a required status.", report.context);
+    } else if let Some(command) = &report.command {
+        println!("\nTo require `{}` before merging:", report.context);
+        println!("  {command}");
+    }
+    match &report.posted {
+        Some(posted) => println!(
+            "\nPosted `{}`: {} on #{} ({:.12}) — {}",
+            posted.status.context,
+            posted.status.state,
+            posted.pr,
+            posted.sha,
+            posted.status.description
+        ),
+        None => println!(
+            "\nReview statuses come from your machine: after reviewing a PR, run\n  review protection --post <PR>"
+        ),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::state::ReviewState;
+
+    fn summary(reviewed: usize, rejected: usize) -> ReviewSummary {
+        let mut summary = ReviewState::new("feature", None).to_summary();
+        summary.total_hunks = 10;
+        summary.reviewed_hunks = reviewed;
+        summary.rejected_hunks = rejected;
+        summary
+    }
+
+    #[test]
+    fn status_follows_rejections_then_the_gate() {
+        let rejected = review_status(&summary(4, 1), None, "review");
+        assert_eq!(rejected.state, "failure");
+        assert_eq!(rejected.description, "4/10 hunks reviewed, 1 rejected");
+
+        let gated = review_status(
+            &summary(4, 0),
+            Some("2 checklist item(s) not checked off."),
+            "review",
+        );
+        assert_eq!(gated.state, "pending");
+
+        let passed = review_status(&summary(10, 0), None, "review");
+        assert_eq!(
+            (passed.state.as_str(), passed.context.as_str()),
+            ("success", "review")
+        );
+    }
+
+    #[test]
+    fn require_command_extends_or_creates_the_rule() {
+        assert!(require_command("main", "review", true).contains(
+            "branches/main/protection/required_status_checks/contexts -f 'contexts[]=review'"
+        ));
+        let create = require_command("main", "review", false);
+        assert!(create.contains(r#""contexts":["review"]"#));
+        assert!(create.contains("--method PUT"));
+    }
+}
@@ -0,0 +1,56 @@
+//! `review redactions` — the audit log of what was redacted from AI prompts
+//! (see [`crate::ai::redact`]).
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::ai::redact::load_redaction_log;
+
+use super::common::print_json;
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct RedactionsArgs {
+    /// Repository path (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Only entries at or after this ISO 8601 time
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_redactions(args: RedactionsArgs) -> Result<(), String> {
+    let RedactionsArgs { repo, since, json } = args;
+    let repo = PathBuf::from(get_repo_path(&repo)?);
+    let mut records = load_redaction_log(&repo).map_err(|e| e.to_string())?;
+    if let Some(since) = &since {
+        records.retain(|record| record.at >= *since);
+    }
+
+    if json {
+        print_json(&records);
+        return Ok(());
+    }
+    if records.is_empty() {
+        println!("Nothing has been redacted from AI prompts.");
+        return Ok(());
+    }
+    for record in &records {
+        println!(
+            "{}  {:<14}  {:>3}×  {}{}",
+            record.at,
+            record.purpose,
+            record.count,
+            record.rule,
+            record
+                .file_path
+                .as_deref()
+                .map_or(String::new(), |path| format!("  ({path})"))
+        );
+    }
+    Ok(())
+}
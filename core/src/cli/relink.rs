@@ -0,0 +1,51 @@
+//! `review relink <old-path> [<new-path>]` — reattach the reviews of a repo
+//! that was moved or renamed to where it lives now.
+
+use std::path::{Path, PathBuf};
+
+use clap::Args;
+
+use crate::review::central;
+
+use super::common::print_json;
+use super::{get_repo_path, resolve_absolute};
+
+#[derive(Debug, Args)]
+pub struct RelinkArgs {
+    /// Where the repo used to be
+    pub old_path: String,
+    /// Where it is now (defaults to the current repository)
+    pub new_path: Option<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_relink(args: RelinkArgs) -> Result<(), String> {
+    let RelinkArgs {
+        old_path,
+        new_path,
+        json,
+    } = args;
+    let old_path = resolve_absolute(Path::new(&old_path))?;
+    let new_path = match new_path {
+        Some(path) => resolve_absolute(Path::new(&path))?,
+        None => PathBuf::from(get_repo_path(&None)?),
+    };
+    let new_path = central::repo_root(&new_path);
+    let relinked = central::relink_repo(&old_path, &new_path).map_err(|e| e.to_string())?;
+
+    if json {
+        print_json(&relinked);
+    } else if relinked.old_id == relinked.new_id {
+        println!("{} already uses this repo's reviews.", old_path.display());
+    } else {
+        println!(
+            "Relinked {} to {} ({} file(s) moved).",
+            old_path.display(),
+            new_path.display(),
+            relinked.moved_files
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+//! `review remote <url> <base> <head>` — start a review of a repository that
+//! isn't checked out locally, through a cached partial clone (see
+//! [`crate::sources::remote`]). Later commands reach the review with
+//! `--repo <clone>`.
+
+use clap::Args;
+
+use crate::review::storage;
+use crate::sources::remote::RemoteGitSource;
+
+use super::common::print_json;
+
+#[derive(Debug, Args)]
+pub struct RemoteArgs {
+    /// Remote URL, anything `git clone` accepts
+    pub url: String,
+    /// Base branch, full ref, or full SHA
+    pub base: String,
+    /// Head branch, full ref, or full SHA
+    pub head: String,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_remote(args: &RemoteArgs) -> Result<(), String> {
+    let source = RemoteGitSource::new(&args.url).map_err(|e| e.to_string())?;
+    let comparison = source
+        .fetch_comparison(&args.base, &args.head)
+        .map_err(|e| e.to_string())?;
+    let repo = source.clone_dir();
+    storage::ensure_review_exists(repo, &comparison.head, Some(comparison.base.clone()), None)
+        .map_err(|e| e.to_string())?;
+
+    if args.json {
+        print_json(&serde_json::json!({
+            "url": source.url(),
+            "repo": repo,
+            "spec": comparison.key,
+            "ref": comparison.head,
+            "base": comparison.base,
+        }));
+        return Ok(());
+    }
+    println!("Reviewing {} of {}", comparison.key, source.url());
+    println!("  clone at {}", repo.display());
+    println!("  review hunks -r {} -s {}", repo.display(), comparison.key);
+    Ok(())
+}
@@ -1,17 +1,19 @@
 //! Review-state subcommands: `hunks`, `approve`/`reject`/`save`/`unmark`,
-//! `status`, `list`, `trust`, and `note`.
+//! `status`, `list`, `archive`/`unarchive`, `trust`, and `note`.
 //!
 //! These commands read and write the saved review JSON under `~/.review/`.
 
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::{Args, Subcommand};
 use serde::Serialize;
 
-use crate::classify::classify_hunks_static;
+use crate::classify::sensitive::is_sensitive;
+use crate::classify::{classify_repo_hunks_static, Evidence};
 use crate::review::state::{overall_review_state, Attributed, HunkStatus};
 use crate::review::storage;
+use crate::service::review_io::get_review_progress;
 use crate::trust::matches_pattern;
 
 use super::comments::SourceArg;
@@ -68,6 +70,9 @@ pub struct MarkArgs {
 pub struct StatusArgs {
     #[command(flatten)]
     pub target: ReviewTarget,
+    /// Show per-file progress as of the last save, without re-reading the diff
+    #[arg(long)]
+    pub files: bool,
     /// Output as JSON
     #[arg(long)]
     pub json: bool,
@@ -79,8 +84,11 @@ pub struct ListArgs {
     #[arg(short, long)]
     pub repo: Option<String>,
     /// List reviews across every registered repo
-    #[arg(long)]
+    #[arg(long, conflicts_with = "archived")]
     pub all: bool,
+    /// List archived reviews instead of live ones
+    #[arg(long)]
+    pub archived: bool,
     /// Output as JSON
     #[arg(long)]
     pub json: bool,
@@ -95,6 +103,15 @@ pub struct DeleteArgs {
     pub json: bool,
 }
 
+#[derive(Debug, Args)]
+pub struct ArchiveArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
 #[derive(Debug, Args)]
 pub struct ChangeBaseArgs {
     #[command(flatten)]
@@ -184,6 +201,9 @@ struct HunkJson {
     labels: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     reasoning: Option<String>,
+    /// What the static rules matched, so a label can be traced to its lines.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    evidence: Vec<Evidence>,
     #[serde(skip_serializing_if = "Option::is_none")]
     diff: Option<String>,
 }
@@ -222,6 +242,14 @@ struct DeleteResultJson {
     deleted: bool,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveResultJson {
+    #[serde(rename = "ref")]
+    reference: String,
+    archived: bool,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ChangeBaseResultJson {
@@ -300,6 +328,12 @@ pub fn run_hunks(args: HunksArgs) -> Result<(), String> {
             status,
             labels,
             reasoning,
+            evidence: view
+                .classification
+                .classifications
+                .get(&hunk.id)
+                .map(|c| c.evidence.clone())
+                .unwrap_or_default(),
             // A single-hunk query always includes the diff.
             diff: if args.diff || args.hunk.is_some() {
                 Some(render_hunk_diff(hunk))
@@ -309,6 +343,9 @@ pub fn run_hunks(args: HunksArgs) -> Result<(), String> {
         });
     }
 
+    // Sensitive-area hunks need a human no matter what; list them first.
+    rows.sort_by_key(|row| !is_sensitive(&row.labels));
+
     if args.json {
         print_json(&HunksJson {
             comparison: view.review.comparison.key.clone(),
@@ -359,6 +396,10 @@ fn print_hunks_human(comparison: &str, total: usize, counts: &Counts, rows: &[Hu
             println!("              reason: {reason}");
         }
         if let Some(diff) = &row.diff {
+            // Evidence is shown with the diff, where its lines can be found.
+            for evidence in &row.evidence {
+                println!("              matched: {}", format_evidence(evidence));
+            }
             for line in diff.lines() {
                 println!("      {line}");
             }
@@ -366,13 +407,36 @@ fn print_hunks_human(comparison: &str, total: usize, counts: &Counts, rows: &[Hu
     }
 }
 
+/// `rule (matched) at +12, -7` — new-side line numbers are `+`, old-side `-`.
+fn format_evidence(evidence: &Evidence) -> String {
+    let matched = evidence
+        .matched
+        .as_ref()
+        .map(|m| format!(" ({m})"))
+        .unwrap_or_default();
+    let lines: Vec<String> = evidence
+        .lines
+        .iter()
+        .filter_map(|l| match (l.new_line_number, l.old_line_number) {
+            (Some(n), _) => Some(format!("+{n}")),
+            (None, Some(n)) => Some(format!("-{n}")),
+            (None, None) => None,
+        })
+        .collect();
+    let at = if lines.is_empty() {
+        String::new()
+    } else {
+        format!(" at {}", lines.join(", "))
+    };
+    format!("{}{matched}{at}", evidence.rule)
+}
+
 /// `review approve` / `reject` / `save` — set a status on hunks.
 pub fn run_mark(args: MarkArgs, status: HunkStatus) -> Result<(), String> {
     let repo = PathBuf::from(get_repo_path(&args.target.repo)?);
     let (review, hunks, live_ids) = load_for_mutation(&repo, args.target.spec.as_deref())?;
     let comparison = &review.comparison;
-    let total_hunks = hunks.len();
-    let classification = classify_hunks_static(&hunks);
+    let classification = classify_repo_hunks_static(&repo, &hunks);
 
     let (known, unknown) = resolve_mark_targets(&live_ids, &args.hunks);
     for id in &unknown {
@@ -386,9 +450,9 @@ pub fn run_mark(args: MarkArgs, status: HunkStatus) -> Result<(), String> {
     let reason = args.reason.clone();
     let source = resolve_source(args.source)?;
     let result = mutate_review(&repo, &review.ref_name, &hunks, |state| {
-        // Keep the total and per-hunk labels fresh so `review list` and the
-        // desktop app show accurate progress.
-        state.total_diff_hunks = total_hunks;
+        // Keep the totals (overall and per file) and per-hunk labels fresh so
+        // `review list` and the desktop app show accurate progress.
+        state.set_diff_hunks(&hunks);
         sync_classification(state, &classification);
         for id in &known {
             let entry = state.hunks.entry(id.clone()).or_default();
@@ -396,6 +460,7 @@ pub fn run_mark(args: MarkArgs, status: HunkStatus) -> Result<(), String> {
                 value: status.clone(),
                 source,
                 reasoning: reason.clone(),
+                at: None,
             });
         }
         true
@@ -429,8 +494,7 @@ pub fn run_unmark(args: MarkArgs) -> Result<(), String> {
     let repo = PathBuf::from(get_repo_path(&args.target.repo)?);
     let (review, hunks, live_ids) = load_for_mutation(&repo, args.target.spec.as_deref())?;
     let comparison = &review.comparison;
-    let total_hunks = hunks.len();
-    let classification = classify_hunks_static(&hunks);
+    let classification = classify_repo_hunks_static(&repo, &hunks);
 
     if !storage::review_exists(&repo, &review.ref_name).unwrap_or(false) {
         return Err(format!("No review exists for {}.", comparison.key));
@@ -441,7 +505,7 @@ pub fn run_unmark(args: MarkArgs) -> Result<(), String> {
         eprintln!("warning: hunk not found in {}: {id}", comparison.key);
     }
     let result = mutate_review(&repo, &review.ref_name, &hunks, |state| {
-        state.total_diff_hunks = total_hunks;
+        state.set_diff_hunks(&hunks);
         sync_classification(state, &classification);
         for id in &ids {
             // Clear the status; drop the entry entirely if nothing else is
@@ -482,6 +546,9 @@ pub fn run_unmark(args: MarkArgs) -> Result<(), String> {
 /// `review status` — show review progress for a comparison.
 pub fn run_status(args: StatusArgs) -> Result<(), String> {
     let repo = PathBuf::from(get_repo_path(&args.target.repo)?);
+    if args.files {
+        return print_file_progress(&repo, args.target.spec.as_deref(), args.json);
+    }
     let view = load_review_view(&repo, args.target.spec.as_deref())?;
 
     let mut counts = Counts::default();
@@ -515,6 +582,42 @@ pub fn run_status(args: StatusArgs) -> Result<(), String> {
     Ok(())
 }
 
+/// `review status --files` — the stored per-file rollups.
+fn print_file_progress(repo: &Path, spec: Option<&str>, json: bool) -> Result<(), String> {
+    let review = resolve_review_arg(repo, spec)?;
+    if !storage::review_exists(repo, &review.ref_name).unwrap_or(false) {
+        return Err(format!("No review exists for {}.", review.comparison.key));
+    }
+    let progress = get_review_progress(repo, &review.ref_name).map_err(|e| format!("{e:#}"))?;
+    if json {
+        print_json(&progress);
+        return Ok(());
+    }
+    println!(
+        "{}  ({}/{} reviewed, as of {})",
+        review.comparison.key,
+        progress.summary.reviewed_hunks,
+        progress.summary.total_hunks,
+        progress.summary.updated_at
+    );
+    println!(
+        "  {:>8}  {:>8}  {:>8}  {:>8}  {:>5}  {:>12}  file",
+        "reviewed", "approved", "rejected", "trusted", "saved", "unclassified"
+    );
+    for (path, file) in &progress.files {
+        println!(
+            "  {:>8}  {:>8}  {:>8}  {:>8}  {:>5}  {:>12}  {path}",
+            format!("{}/{}", file.reviewed(), file.total),
+            file.approved,
+            file.rejected,
+            file.trusted,
+            file.saved_for_later,
+            file.unclassified
+        );
+    }
+    Ok(())
+}
+
 /// `review list` — list saved reviews.
 pub fn run_list(args: ListArgs) -> Result<(), String> {
     if args.all {
@@ -544,13 +647,19 @@ pub fn run_list(args: ListArgs) -> Result<(), String> {
     }
 
     let repo = PathBuf::from(get_repo_path(&args.repo)?);
-    let reviews = storage::list_saved_reviews(&repo).map_err(|e| e.to_string())?;
+    let reviews = if args.archived {
+        storage::list_archived_reviews(&repo)
+    } else {
+        storage::list_saved_reviews(&repo)
+    }
+    .map_err(|e| e.to_string())?;
+    let kind = if args.archived { "archived " } else { "" };
     if args.json {
         print_json(&reviews);
     } else if reviews.is_empty() {
-        println!("No saved reviews in this repo.");
+        println!("No {kind}reviews in this repo.");
     } else {
-        println!("{} review(s):\n", reviews.len());
+        println!("{} {kind}review(s):\n", reviews.len());
         for review in &reviews {
             println!(
                 "  {:<32}  {}/{} reviewed  {:<18}  {}",
@@ -580,7 +689,9 @@ fn review_label(ref_name: &str, base_override: Option<&str>) -> String {
 pub fn run_delete(args: DeleteArgs) -> Result<(), String> {
     let repo = PathBuf::from(get_repo_path(&args.target.repo)?);
     let review = resolve_review_arg(&repo, args.target.spec.as_deref())?;
-    if !storage::review_exists(&repo, &review.ref_name).unwrap_or(false) {
+    let exists = storage::review_exists(&repo, &review.ref_name).unwrap_or(false)
+        || storage::is_review_archived(&repo, &review.ref_name).unwrap_or(false);
+    if !exists {
         return Err(format!("No review exists for {}.", review.ref_name));
     }
     storage::delete_review(&repo, &review.ref_name).map_err(|e| e.to_string())?;
@@ -595,6 +706,32 @@ pub fn run_delete(args: DeleteArgs) -> Result<(), String> {
     Ok(())
 }
 
+/// `review archive` / `review unarchive` — park a finished review out of the
+/// way (compressed, hidden from `list` and freshness checks) without losing
+/// its decisions, or bring it back.
+pub fn run_archive(args: ArchiveArgs, archive: bool) -> Result<(), String> {
+    let ArchiveArgs { target, json } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    if archive {
+        storage::archive_review(&repo, &review.ref_name)
+    } else {
+        storage::unarchive_review(&repo, &review.ref_name)
+    }
+    .map_err(|e| e.to_string())?;
+    if json {
+        print_json(&ArchiveResultJson {
+            reference: review.ref_name.clone(),
+            archived: archive,
+        });
+    } else if archive {
+        println!("Archived review {}", review.ref_name);
+    } else {
+        println!("Restored review {}", review.ref_name);
+    }
+    Ok(())
+}
+
 /// `review change-base` — pin (or, with `--clear`, drop) a review's base
 /// override. The base is a derived setting, not identity, so this is a plain
 /// in-place edit: it sets the `base_override` field and re-resolves the diff.
@@ -0,0 +1,106 @@
+//! `review semgrep` — run Semgrep over the review's changed files and record
+//! its findings on the hunks they flag.
+
+use std::cell::Cell;
+use std::path::PathBuf;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::diff::parser::DiffHunk;
+use crate::review::state::{now_iso8601, Finding, ReviewState};
+use crate::service::ingest::{attach_findings, merge_findings, IngestOutcome};
+use crate::service::semgrep::{
+    clear_fixed_labels, finding_labels, resolve_configs, run_semgrep, ScanStats, SEMGREP_TOOL,
+};
+
+use super::common::{
+    load_for_mutation, mutate_review, print_json, sync_classification, ReviewTarget,
+};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct SemgrepArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Rule file, directory or registry pack (repeatable); defaults to
+    /// `.review.toml`'s `[semgrep] config`. Registry packs need the network
+    #[arg(long = "config", value_name = "CONFIG")]
+    pub configs: Vec<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SemgrepOutput<'a> {
+    configs: &'a [String],
+    files: usize,
+    cached: usize,
+    added: usize,
+    updated: usize,
+    fixed: usize,
+    findings: &'a [Finding],
+}
+
+/// Give hunks with a Semgrep finding the finding label, and take it off
+/// hunks whose findings are gone.
+pub(super) fn sync_semgrep_labels(state: &mut ReviewState, hunks: &[DiffHunk]) {
+    let labels = finding_labels(&state.findings, hunks);
+    sync_classification(state, &labels);
+    clear_fixed_labels(state);
+}
+
+pub fn run_semgrep_scan(args: SemgrepArgs) -> Result<(), String> {
+    let SemgrepArgs {
+        target,
+        configs,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let (review, hunks, _) = load_for_mutation(&repo, target.spec.as_deref())?;
+    let configs = resolve_configs(&repo, configs);
+    let (report, ScanStats { files, cached }) =
+        run_semgrep(&repo, &review.comparison, &hunks, &configs).map_err(|e| format!("{e:#}"))?;
+    let (findings, _) = attach_findings(&report, SEMGREP_TOOL, &hunks, &repo, &now_iso8601());
+    let outcome = Cell::new(IngestOutcome::default());
+    mutate_review(&repo, &review.ref_name, &hunks, |state| {
+        let merged = merge_findings(state, &report.tools, &findings);
+        outcome.set(merged);
+        sync_semgrep_labels(state, &hunks);
+        merged != IngestOutcome::default()
+    })?;
+    let outcome = outcome.get();
+
+    if json {
+        print_json(&SemgrepOutput {
+            configs: &configs,
+            files,
+            cached,
+            added: outcome.added,
+            updated: outcome.updated,
+            fixed: outcome.fixed,
+            findings: &findings,
+        });
+        return Ok(());
+    }
+    println!(
+        "Scanned {files} changed file(s) with {} ({cached} from cache): {} finding(s) on changed lines ({} new), {} fixed since the last run.",
+        configs.join(", "),
+        findings.len(),
+        outcome.added,
+        outcome.fixed
+    );
+    for finding in &findings {
+        println!(
+            "  {:<7} {}:{} [{}] {}",
+            finding.severity.as_str(),
+            finding.file_path,
+            finding.line,
+            finding.rule.as_deref().unwrap_or(""),
+            finding.message
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//! `review similar` — match the review's hunks against hunks already seen in
+//! this repo's reviews (see [`crate::review::embeddings`]): the ones like a
+//! given hunk, the review's hunks that other reviews contain too, and the
+//! undecided ones that closely match an approved hunk.
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::review::embeddings::{self, HunkMatches, SimilarHunk};
+use crate::review::state::HunkStatus;
+
+use super::common::{load_comparison_hunks, print_json, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct SimilarArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Output as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+    #[command(subcommand)]
+    pub action: SimilarAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SimilarAction {
+    /// List the indexed hunks most like one of the review's hunks
+    Hunk {
+        /// Hunk ID to match
+        hunk: String,
+        /// How many matches to show
+        #[arg(long, default_value_t = 5)]
+        limit: usize,
+        /// Include hunks with no decision yet
+        #[arg(long)]
+        all: bool,
+    },
+    /// List the review's hunks that other reviews contain too
+    Duplicates,
+    /// List undecided hunks that closely match an approved one
+    Suggest,
+    /// (Re)index the review's hunks
+    Index,
+}
+
+fn status_str(status: Option<&HunkStatus>) -> &'static str {
+    match status {
+        Some(HunkStatus::Approved) => "approved",
+        Some(HunkStatus::Rejected) => "rejected",
+        Some(HunkStatus::SavedForLater) => "saved",
+        None => "unreviewed",
+    }
+}
+
+fn print_match(m: &SimilarHunk) {
+    println!(
+        "    {:>3.0}%  {:<10}  {}  ({})",
+        m.similarity * 100.0,
+        status_str(m.status.as_ref()),
+        m.hunk_id,
+        m.ref_name
+    );
+}
+
+fn print_hunk_matches(found: &[HunkMatches], empty: &str) {
+    if found.is_empty() {
+        println!("{empty}");
+    }
+    for hunk in found {
+        println!("  {}", hunk.hunk_id);
+        for m in &hunk.matches {
+            print_match(m);
+        }
+    }
+}
+
+pub fn run_similar(args: SimilarArgs) -> Result<(), String> {
+    let SimilarArgs {
+        target,
+        json,
+        action,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let (review, hunks) = load_comparison_hunks(&repo, target.spec.as_deref())?;
+    let ref_name = review.ref_name;
+    // Queries see the review as it is now.
+    let indexed = embeddings::index_review(&repo, &ref_name, &hunks).map_err(|e| e.to_string())?;
+
+    match action {
+        SimilarAction::Hunk { hunk, limit, all } => {
+            let Some(diff_hunk) = hunks.iter().find(|h| h.id == hunk) else {
+                return Err(format!("Hunk not found in {ref_name}: {hunk}"));
+            };
+            let found = embeddings::find_similar(&repo, diff_hunk, limit, !all)
+                .map_err(|e| e.to_string())?;
+            if json {
+                print_json(&found);
+            } else if found.is_empty() {
+                println!("No similar reviewed hunks.");
+            } else {
+                found.iter().for_each(print_match);
+            }
+        }
+        SimilarAction::Duplicates => {
+            let found =
+                embeddings::find_duplicates(&repo, &ref_name, &hunks).map_err(|e| e.to_string())?;
+            if json {
+                print_json(&found);
+            } else {
+                print_hunk_matches(&found, "No hunks duplicated in other reviews.");
+            }
+        }
+        SimilarAction::Suggest => {
+            let found = embeddings::suggest_approvals(&repo, &ref_name, &hunks)
+                .map_err(|e| e.to_string())?;
+            if json {
+                print_json(&found);
+            } else {
+                print_hunk_matches(&found, "No undecided hunks match an approved one.");
+            }
+        }
+        SimilarAction::Index => {
+            if json {
+                print_json(&serde_json::json!({ "ref": ref_name, "indexed": indexed }));
+            } else {
+                println!(
+                    "Indexed {indexed} of {} hunk(s) in {ref_name}.",
+                    hunks.len()
+                );
+            }
+        }
+    }
+    Ok(())
+}
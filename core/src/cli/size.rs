@@ -0,0 +1,92 @@
+//! `review size` — build the review's base and head and report how much its
+//! build artifacts grew or shrank, recording the result on the review.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::build_size::{build_config, measure_build_size};
+
+use super::common::{load_for_mutation, mutate_review, print_json, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct SizeArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `1536` → `1.5 KiB`; `signed` adds a `+` to growth.
+fn format_bytes(bytes: i64, signed: bool) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let sign = match bytes {
+        b if b < 0 => "-",
+        b if b > 0 && signed => "+",
+        _ => "",
+    };
+    let mut value = bytes.unsigned_abs() as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{sign}{value} {}", UNITS[unit])
+    } else {
+        format!("{sign}{value:.1} {}", UNITS[unit])
+    }
+}
+
+pub fn run_size(args: SizeArgs) -> Result<(), String> {
+    let SizeArgs { target, json } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let config = build_config(&repo).map_err(|e| format!("{e:#}"))?;
+    let (review, hunks, _) = load_for_mutation(&repo, target.spec.as_deref())?;
+    let report =
+        measure_build_size(&repo, &review.comparison, &config).map_err(|e| format!("{e:#}"))?;
+    mutate_review(&repo, &review.ref_name, &hunks, |state| {
+        state.build_size = Some(report.clone());
+        true
+    })?;
+
+    if json {
+        print_json(&report);
+        return Ok(());
+    }
+    let size = |bytes: Option<u64>| {
+        bytes.map_or("—".to_owned(), |b| {
+            format_bytes(i64::try_from(b).unwrap_or(i64::MAX), false)
+        })
+    };
+    for artifact in &report.artifacts {
+        println!(
+            "  {:>10}  {:>10}  {:>11}  {}",
+            size(artifact.base_bytes),
+            size(artifact.head_bytes),
+            format_bytes(artifact.delta(), true),
+            artifact.path
+        );
+    }
+    println!(
+        "Total: {} across {} artifact(s)",
+        format_bytes(report.delta(), true),
+        report.artifacts.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_sizes_in_binary_units() {
+        assert_eq!(format_bytes(512, false), "512 B");
+        assert_eq!(format_bytes(1536, true), "+1.5 KiB");
+        assert_eq!(format_bytes(-3 * 1024 * 1024, true), "-3.0 MiB");
+        assert_eq!(format_bytes(0, true), "0 B");
+    }
+}
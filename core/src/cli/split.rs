@@ -0,0 +1,100 @@
+//! `review split` — propose cutting a long review into several smaller ones,
+//! each a run of consecutive commits, and with `--create` save them as reviews
+//! linked to the original.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::ai::check_claude_available;
+use crate::service::split::{create_split_reviews, propose_split, DEFAULT_MAX_REVIEWS};
+
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct SplitArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Split into at most this many reviews
+    #[arg(long, default_value_t = DEFAULT_MAX_REVIEWS)]
+    pub max_reviews: usize,
+    /// Model that proposes the split
+    #[arg(long, default_value = "sonnet")]
+    pub model: String,
+    /// Only cluster commits by directory
+    #[arg(long)]
+    pub no_ai: bool,
+    /// Save the proposed reviews, linked to this one
+    #[arg(long)]
+    pub create: bool,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_split(args: SplitArgs) -> Result<(), String> {
+    let SplitArgs {
+        target,
+        max_reviews,
+        model,
+        no_ai,
+        create,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let model = if no_ai {
+        None
+    } else if check_claude_available() {
+        Some(model.as_str())
+    } else {
+        eprintln!("Claude CLI not found; clustering commits by directory only.");
+        None
+    };
+
+    let plan = propose_split(
+        &repo,
+        &review.ref_name,
+        review.base_override.as_deref(),
+        model,
+        max_reviews,
+    )
+    .map_err(|e| format!("{e:#}"))?;
+    if let Some(e) = &plan.ai_error {
+        eprintln!("AI split failed ({e}); clustered commits by directory instead.");
+    }
+    if create {
+        create_split_reviews(&repo, &plan).map_err(|e| format!("{e:#}"))?;
+    }
+
+    if json {
+        print_json(&plan);
+        return Ok(());
+    }
+    println!(
+        "{} → {} reviews ({})",
+        plan.comparison.key,
+        plan.reviews.len(),
+        plan.source.as_str()
+    );
+    for (i, part) in plan.reviews.iter().enumerate() {
+        println!(
+            "\n{}. {}  ({} commit(s), {} file(s))",
+            i + 1,
+            part.title,
+            part.commits.len(),
+            part.files.len()
+        );
+        println!("   {}", part.reason);
+        println!(
+            "   review start --old {} --new {}",
+            &part.base[..part.base.len().min(12)],
+            &part.ref_name[..part.ref_name.len().min(12)]
+        );
+    }
+    if create {
+        println!("\nCreated {} linked reviews.", plan.reviews.len());
+    }
+    Ok(())
+}
@@ -4,12 +4,12 @@
 //! do not read or write review state, so they need no saved review.
 
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use clap::Args;
 use serde::Serialize;
 
-use crate::classify::classify_hunks_static;
+use crate::classify::classify_repo_hunks_static;
 use crate::diff::parser::{parse_diff, parse_multi_file_diff, DiffHunk};
 use crate::sources::local_git::LocalGitSource;
 
@@ -113,7 +113,7 @@ pub fn run_changes(args: ChangesArgs) -> Result<(), String> {
         }
     }
 
-    let classification = classify_hunks_static(&hunks);
+    let classification = classify_repo_hunks_static(Path::new(&repo_path), &hunks);
 
     let file_filter = match &args.file {
         Some(glob) => {
@@ -0,0 +1,179 @@
+//! `review stats` — for a repo: time actually spent reviewing, per review;
+//! classification quality, i.e. how often humans overrode the AI's labels,
+//! per label; and an export of every correction for tuning the classification
+//! prompt. With `--usage`, the opt-in usage counters instead (see
+//! [`crate::metrics`]).
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::metrics::{self, UsageMetrics};
+use crate::review::feedback::{
+    correction_stats, load_corrections, CorrectionStats, LabelCorrection,
+};
+use crate::review::sessions::{load_sessions, session_stats, SessionStats};
+
+use super::common::print_json;
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Repository path (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+    /// Write every recorded correction as JSON Lines to FILE ("-" for stdout)
+    #[arg(long, value_name = "FILE")]
+    pub export: Option<String>,
+    /// Show the locally aggregated feature usage counters instead
+    #[arg(long, conflicts_with = "export")]
+    pub usage: bool,
+    /// Submit the usage counters to `usageMetricsEndpoint` and reset them
+    #[arg(long, requires = "usage")]
+    pub submit: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StatsJson {
+    #[serde(flatten)]
+    corrections: CorrectionStats,
+    review_time: SessionStats,
+}
+
+/// `review stats` — show the repo's review time and AI label corrections (or
+/// export the corrections).
+pub fn run_stats(args: StatsArgs) -> Result<(), String> {
+    if args.usage {
+        return run_usage(args.submit, args.json);
+    }
+    let repo = PathBuf::from(get_repo_path(&args.repo)?);
+    let corrections = load_corrections(&repo).map_err(|e| e.to_string())?;
+
+    if let Some(dest) = args.export {
+        return export_corrections(&corrections, &dest);
+    }
+
+    let stats = correction_stats(&corrections);
+    let time = session_stats(&load_sessions(&repo).map_err(|e| e.to_string())?);
+    if args.json {
+        print_json(&StatsJson {
+            corrections: stats,
+            review_time: time,
+        });
+        return Ok(());
+    }
+
+    print_review_time(&time);
+    if stats.corrections == 0 {
+        println!("No AI label corrections recorded in this repo.");
+    } else {
+        println!(
+            "{} AI label correction(s) across {} review(s):\n",
+            stats.corrections, stats.reviews
+        );
+        println!("  {:<32}  {:>7}  {:>5}", "label", "removed", "added");
+        for count in &stats.labels {
+            println!(
+                "  {:<32}  {:>7}  {:>5}",
+                count.label, count.removed, count.added
+            );
+        }
+    }
+    Ok(())
+}
+
+/// `review stats --usage [--submit]`.
+fn run_usage(submit: bool, json: bool) -> Result<(), String> {
+    if submit {
+        let report = metrics::submit().map_err(|e| format!("{e:#}"))?;
+        if json {
+            print_json(&report);
+        } else {
+            let total: u64 = report.counters.values().sum();
+            println!(
+                "Submitted {total} use(s) of {} feature(s).",
+                report.counters.len()
+            );
+        }
+        return Ok(());
+    }
+
+    let usage = metrics::load().map_err(|e| format!("{e:#}"))?;
+    if json {
+        print_json(&usage);
+    } else {
+        print_usage(&usage, metrics::is_enabled());
+    }
+    Ok(())
+}
+
+fn print_usage(usage: &UsageMetrics, enabled: bool) {
+    if !enabled {
+        println!("Usage metrics are off (set `usageMetricsEnabled` in settings.json to opt in).");
+    }
+    if usage.counters.is_empty() {
+        println!("No feature usage recorded.");
+        return;
+    }
+    println!("Feature usage since {}:\n", usage.since);
+    let mut counters: Vec<_> = usage.counters.iter().collect();
+    counters.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    for (name, count) in counters {
+        println!("  {name:<32}  {count:>6}");
+    }
+}
+
+/// `1h 05m`, `12m 30s`, `45s`.
+fn format_duration(secs: u64) -> String {
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s:02}s"),
+        (h, m, _) => format!("{h}h {m:02}m"),
+    }
+}
+
+fn print_review_time(time: &SessionStats) {
+    if time.sessions == 0 {
+        println!("No review time recorded in this repo.\n");
+        return;
+    }
+    println!(
+        "{} spent reviewing across {} session(s):\n",
+        format_duration(time.active_secs),
+        time.sessions
+    );
+    println!("  {:<32}  {:>8}  {:>8}", "review", "time", "sessions");
+    for review in &time.reviews {
+        println!(
+            "  {:<32}  {:>8}  {:>8}",
+            review.ref_name,
+            format_duration(review.active_secs),
+            review.sessions
+        );
+    }
+    println!();
+}
+
+/// One correction per line, oldest first.
+fn export_corrections(corrections: &[LabelCorrection], dest: &str) -> Result<(), String> {
+    let mut out = String::new();
+    for correction in corrections {
+        out.push_str(&serde_json::to_string(correction).map_err(|e| e.to_string())?);
+        out.push('\n');
+    }
+    if dest == "-" {
+        std::io::stdout()
+            .write_all(out.as_bytes())
+            .map_err(|e| e.to_string())
+    } else {
+        std::fs::write(dest, out).map_err(|e| format!("Failed to write {dest}: {e}"))?;
+        eprintln!("Exported {} correction(s) to {dest}", corrections.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,60 @@
+//! `review submodules` — the nested review scopes of a superproject review:
+//! one per changed submodule, each with its own comparison and progress. A
+//! scope is reviewed like any other review, from inside the submodule
+//! (`review -r <path> -s <base>..<ref> ...`).
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::submodules::submodule_scopes;
+
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct SubmodulesArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Output as JSON (includes each scope's hunks and static classification)
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_submodules(args: SubmodulesArgs) -> Result<(), String> {
+    let SubmodulesArgs { target, json } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let scopes = submodule_scopes(&repo, &review.ref_name).map_err(|e| e.to_string())?;
+
+    if json {
+        print_json(&scopes);
+        return Ok(());
+    }
+    println!("{}", review.comparison.key);
+    if scopes.is_empty() {
+        println!("  No submodule changes.");
+        return Ok(());
+    }
+    for scope in &scopes {
+        if !scope.checked_out {
+            println!(
+                "  {}  (not checked out — run `git submodule update`)",
+                scope.path
+            );
+            continue;
+        }
+        println!(
+            "  {}  {}/{} reviewed  ({} hunks)",
+            scope.path,
+            scope.summary.reviewed_hunks,
+            scope.summary.total_hunks,
+            scope.hunks.len()
+        );
+        println!(
+            "      review -r {} -s {} hunks",
+            scope.path, scope.comparison.key
+        );
+    }
+    Ok(())
+}
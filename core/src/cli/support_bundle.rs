@@ -0,0 +1,43 @@
+//! `review support-bundle` — zip recent logs, the (redacted) settings file,
+//! and environment diagnostics for a bug report.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::support_bundle::{create_support_bundle, RECENT_LOG_DAYS};
+
+use super::common::print_json;
+
+#[derive(Debug, Args)]
+pub struct SupportBundleArgs {
+    /// Where to write the zip (defaults to `review-support-<time>.zip` in the
+    /// current directory)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_support_bundle(args: SupportBundleArgs) -> Result<(), String> {
+    let output = args.output.unwrap_or_else(|| {
+        let stamp = crate::review::state::now_iso8601().replace([':', '.'], "-");
+        PathBuf::from(format!("review-support-{stamp}.zip"))
+    });
+    let bundle = create_support_bundle(&output).map_err(|e| e.to_string())?;
+
+    if args.json {
+        print_json(&bundle);
+        return Ok(());
+    }
+    println!("Wrote {}:", bundle.path.display());
+    for file in &bundle.files {
+        println!("  {file}");
+    }
+    println!(
+        "Logs from the last {RECENT_LOG_DAYS} days are included. Home paths are shown as ~ and \
+         credentials in settings are removed; look it over before attaching it to a report."
+    );
+    Ok(())
+}
@@ -0,0 +1,124 @@
+//! `review tailscale status|expose|unexpose` — reach the review server from
+//! a phone off the LAN through Tailscale Serve (tailnet only) or Funnel
+//! (public internet).
+
+use clap::{Args, Subcommand};
+
+use crate::service::server_port::server_port;
+use crate::service::tailscale::{self, ExposeMode, TailscaleStatus};
+
+use super::common::print_json;
+
+#[derive(Debug, Args)]
+pub struct TailscaleArgs {
+    #[command(subcommand)]
+    pub action: TailscaleAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TailscaleAction {
+    /// Show this machine's tailnet address and whether the server is exposed
+    Status(StatusArgs),
+    /// Expose the server at https://<machine>.<tailnet>.ts.net
+    Expose(ExposeArgs),
+    /// Stop exposing the server
+    Unexpose(StatusArgs),
+}
+
+#[derive(Debug, Clone, Copy, Args)]
+pub struct StatusArgs {
+    /// Port the review server listens on [default: the port it last ran on]
+    #[arg(long)]
+    pub port: Option<u16>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, Args)]
+pub struct ExposeArgs {
+    #[command(flatten)]
+    pub status: StatusArgs,
+    /// Use Funnel, making the server reachable from the public internet
+    /// rather than only from your tailnet
+    #[arg(long)]
+    pub funnel: bool,
+}
+
+fn print_status(status: &TailscaleStatus) {
+    if !status.installed {
+        println!("Tailscale is not installed.");
+        return;
+    }
+    if !status.running {
+        println!("Tailscale is installed but not connected (`tailscale up`).");
+        return;
+    }
+    println!(
+        "Connected as {} ({})",
+        status.dns_name.as_deref().unwrap_or("?"),
+        status.ip.as_deref().unwrap_or("no IPv4")
+    );
+    let yes_no = |b: bool| if b { "available" } else { "not enabled" };
+    println!("  Serve:  {}", yes_no(status.serve_available));
+    println!("  Funnel: {}", yes_no(status.funnel_available));
+    match &status.exposure {
+        Some(exposure) => println!(
+            "Exposed at {} ({:?}) -> {}",
+            exposure.url, exposure.mode, exposure.target
+        ),
+        None => println!("Not exposed."),
+    }
+}
+
+pub fn run_status(args: StatusArgs) -> Result<(), String> {
+    let StatusArgs { port, json } = args;
+    let port = port.unwrap_or_else(server_port);
+    let status = tailscale::tailscale_status(port).map_err(|e| format!("{e:#}"))?;
+    if json {
+        print_json(&status);
+    } else {
+        print_status(&status);
+    }
+    Ok(())
+}
+
+pub fn run_expose(args: ExposeArgs) -> Result<(), String> {
+    let ExposeArgs {
+        status: StatusArgs { port, json },
+        funnel,
+    } = args;
+    let port = port.unwrap_or_else(server_port);
+    let mode = if funnel {
+        ExposeMode::Funnel
+    } else {
+        ExposeMode::Serve
+    };
+    let exposure = tailscale::expose(port, mode).map_err(|e| format!("{e:#}"))?;
+    if json {
+        print_json(&exposure);
+        return Ok(());
+    }
+    println!("Exposed {} at {}", exposure.target, exposure.url);
+    if mode == ExposeMode::Funnel {
+        println!(
+            "Funnel makes it reachable from the public internet; run `review tailscale unexpose` \
+             when you're done."
+        );
+    }
+    Ok(())
+}
+
+pub fn run_unexpose(args: StatusArgs) -> Result<(), String> {
+    let StatusArgs { port, json } = args;
+    let port = port.unwrap_or_else(server_port);
+    let removed = tailscale::unexpose(port).map_err(|e| format!("{e:#}"))?;
+    if json {
+        print_json(&serde_json::json!({ "removed": removed }));
+    } else if removed {
+        println!("Stopped exposing the server.");
+    } else {
+        println!("The server was not exposed.");
+    }
+    Ok(())
+}
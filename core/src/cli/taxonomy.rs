@@ -0,0 +1,196 @@
+//! Taxonomy subcommands: `taxonomy lint`, `taxonomy stale`.
+//!
+//! `review taxonomy lint [FILE]` validates a taxonomy (the bundled one, or a
+//! custom `taxonomy.json`) and, when the target review has been saved, its
+//! trust list against that taxonomy. It exits non-zero when any diagnostic is
+//! an error, so it can run in CI next to a custom taxonomy.
+//!
+//! `review taxonomy stale` lists the reviews with hunks labeled under an
+//! older taxonomy version, and how to re-classify just those hunks.
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+use crate::review::state::ReviewSummary;
+use crate::review::storage;
+use crate::trust::lint::{has_errors, lint_taxonomy, lint_trust_list, Diagnostic, Severity};
+use crate::trust::patterns::{current_taxonomy_version, get_trust_taxonomy, parse_taxonomy};
+
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct TaxonomyArgs {
+    #[command(subcommand)]
+    pub action: TaxonomyAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TaxonomyAction {
+    /// Check a taxonomy and the review's trust list for mistakes
+    Lint(LintArgs),
+    /// List reviews with hunks labeled under an older taxonomy version
+    Stale(StaleArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct StaleArgs {
+    /// Repository path (defaults to the current directory)
+    #[arg(short, long)]
+    pub repo: Option<String>,
+    /// Check the reviews of every registered repo
+    #[arg(long)]
+    pub all: bool,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StaleReview {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    stale_hunks: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StaleJson {
+    taxonomy_version: &'static str,
+    reviews: Vec<StaleReview>,
+}
+
+#[derive(Debug, Args)]
+pub struct LintArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Taxonomy JSON to lint (defaults to the bundled taxonomy)
+    pub file: Option<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LintJson<'a> {
+    /// The review whose trust list was linted, if one was saved.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comparison: Option<String>,
+    diagnostics: &'a [Diagnostic],
+}
+
+/// `review taxonomy lint` — print diagnostics; fail if any is an error.
+pub fn run_lint(args: LintArgs) -> Result<(), String> {
+    let LintArgs { target, file, json } = args;
+    let taxonomy = match file {
+        Some(file) => {
+            let json = std::fs::read_to_string(&file)
+                .map_err(|e| format!("Failed to read {file}: {e}"))?;
+            parse_taxonomy(&json).map_err(|e| format!("Invalid taxonomy {file}: {e}"))?
+        }
+        None => get_trust_taxonomy(),
+    };
+    let mut diagnostics = lint_taxonomy(&taxonomy);
+
+    // The trust list is only linted for a saved review: an unsaved one would
+    // just be the taxonomy's defaults.
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())
+        .ok()
+        .filter(|review| storage::review_exists(&repo, &review.ref_name).unwrap_or(false));
+    if let Some(review) = &review {
+        let state =
+            storage::load_review_state(&repo, &review.ref_name).map_err(|e| e.to_string())?;
+        diagnostics.extend(lint_trust_list(&taxonomy, &state.trust_list));
+    }
+
+    let comparison = review.map(|r| r.comparison.key);
+    if json {
+        print_json(&LintJson {
+            comparison,
+            diagnostics: &diagnostics,
+        });
+    } else if diagnostics.is_empty() {
+        match &comparison {
+            Some(key) => println!("Taxonomy and trust list for {key}: no problems found."),
+            None => println!("Taxonomy: no problems found."),
+        }
+    } else {
+        for diagnostic in &diagnostics {
+            let severity = match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!("{severity}: {}", diagnostic.message);
+        }
+    }
+
+    if has_errors(&diagnostics) {
+        let errors = diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .count();
+        return Err(format!("{errors} taxonomy error(s)."));
+    }
+    Ok(())
+}
+
+/// `review taxonomy stale` — reviews whose labels predate the current
+/// taxonomy, with the command that re-classifies only those hunks.
+pub fn run_stale(args: StaleArgs) -> Result<(), String> {
+    let StaleArgs { repo, all, json } = args;
+    let summaries: Vec<(String, ReviewSummary)> = if all {
+        storage::list_all_reviews_global()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|global| (global.repo_path, global.summary))
+            .collect()
+    } else {
+        let repo = get_repo_path(&repo)?;
+        storage::list_saved_reviews(&PathBuf::from(&repo))
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|summary| (repo.clone(), summary))
+            .collect()
+    };
+    let reviews: Vec<StaleReview> = summaries
+        .into_iter()
+        .filter(|(_, summary)| summary.stale_taxonomy_hunks > 0)
+        .map(|(repo_path, summary)| StaleReview {
+            repo_path,
+            ref_name: summary.ref_name,
+            stale_hunks: summary.stale_taxonomy_hunks,
+        })
+        .collect();
+
+    let version = current_taxonomy_version();
+    if json {
+        print_json(&StaleJson {
+            taxonomy_version: version,
+            reviews,
+        });
+        return Ok(());
+    }
+    if reviews.is_empty() {
+        println!("Every review's labels use the current taxonomy ({version}).");
+        return Ok(());
+    }
+    println!("Labeled under an older taxonomy than the current one ({version}):\n");
+    for review in &reviews {
+        let repo = if all {
+            format!(" --repo {}", review.repo_path)
+        } else {
+            String::new()
+        };
+        println!(
+            "  {:<40}  {:>5} hunk(s)   review classify{repo} --spec {} --stale",
+            review.ref_name, review.stale_hunks, review.ref_name
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,55 @@
+//! `review timeline` — a review's activity in chronological order: commits in
+//! the comparison, classification runs, review decisions, comments, and guide
+//! authoring. `--since` narrows it to "what happened while I was away";
+//! `--json` gives an exportable audit trail.
+
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::service::timeline::review_timeline;
+
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct TimelineArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    /// Only show events after this ISO 8601 timestamp
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+pub fn run_timeline(args: TimelineArgs) -> Result<(), String> {
+    let TimelineArgs {
+        target,
+        since,
+        json,
+    } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let timeline =
+        review_timeline(&repo, &review.ref_name, since.as_deref()).map_err(|e| e.to_string())?;
+
+    if json {
+        print_json(&timeline);
+        return Ok(());
+    }
+    println!("{}", timeline.comparison.key);
+    if timeline.events.is_empty() {
+        println!("  No activity.");
+    }
+    for event in &timeline.events {
+        let who = event
+            .actor
+            .as_deref()
+            .or_else(|| event.source.map(crate::review::state::Source::as_str))
+            .unwrap_or("");
+        println!("  {}  {:<10}  {}", event.at, who, event.summary);
+    }
+    Ok(())
+}
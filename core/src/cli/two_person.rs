@@ -0,0 +1,96 @@
+//! `review two-person require|handoff|signoff|show` — two-person review (see
+//! [`crate::service::two_person`]).
+
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand};
+
+use crate::review::storage;
+use crate::service::two_person::{self, hand_off, require_second_reviewer, sign_off};
+
+use super::checklist::print_two_person;
+use super::comments::default_git_user;
+use super::common::{print_json, resolve_review_arg, ReviewTarget};
+use super::get_repo_path;
+
+#[derive(Debug, Args)]
+pub struct TwoPersonArgs {
+    #[command(flatten)]
+    pub target: ReviewTarget,
+    #[command(subcommand)]
+    pub action: TwoPersonAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TwoPersonAction {
+    /// Require a second reviewer's sign-off before `review check` passes
+    Require,
+    /// Hand off as the first reviewer: your decisions become recommendations
+    Handoff(SignArgs),
+    /// Sign off as the second reviewer, once every recommendation is decided
+    Signoff(SignArgs),
+    /// Show where the two-person review stands
+    Show(ShowArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct SignArgs {
+    /// Who is signing (default: $REVIEW_AUTHOR, then git user.name)
+    #[arg(long = "as", value_name = "NAME")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ShowArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// `review two-person <action>`.
+pub fn run(args: TwoPersonArgs) -> Result<(), String> {
+    let TwoPersonArgs { target, action } = args;
+    let repo = PathBuf::from(get_repo_path(&target.repo)?);
+    let review = resolve_review_arg(&repo, target.spec.as_deref())?;
+    let ref_name = review.ref_name.as_str();
+    let signer = |name: Option<String>| {
+        name.or_else(|| std::env::var("REVIEW_AUTHOR").ok())
+            .or_else(|| default_git_user(&repo))
+            .ok_or_else(|| "Who is signing? Pass --as <name>.".to_owned())
+    };
+
+    match action {
+        TwoPersonAction::Require => {
+            require_second_reviewer(&repo, ref_name).map_err(|e| format!("{e:#}"))?;
+            println!("{ref_name} now needs a second reviewer's sign-off.");
+        }
+        TwoPersonAction::Handoff(SignArgs { name }) => {
+            let by = signer(name)?;
+            let recommended = hand_off(&repo, ref_name, &by).map_err(|e| format!("{e:#}"))?;
+            println!(
+                "Handed off {ref_name} as {by}: {recommended} decision(s) are now recommendations for the second reviewer."
+            );
+        }
+        TwoPersonAction::Signoff(SignArgs { name }) => {
+            let by = signer(name)?;
+            let progress = sign_off(&repo, ref_name, &by).map_err(|e| format!("{e:#}"))?;
+            println!(
+                "Signed off {ref_name} as {by}: {} recommendation(s) confirmed, {} overridden.",
+                progress.confirmed, progress.overridden
+            );
+        }
+        TwoPersonAction::Show(ShowArgs { json }) => {
+            let state = storage::load_review_state(&repo, ref_name).map_err(|e| e.to_string())?;
+            let progress = two_person::progress(&state);
+            if json {
+                print_json(&progress);
+            } else if let Some(progress) = progress {
+                println!("{ref_name}");
+                print_two_person(&progress);
+            } else {
+                println!("{ref_name} doesn't require a second reviewer.");
+            }
+        }
+    }
+    Ok(())
+}
@@ -2,13 +2,16 @@
 //! at a specific repo, comparison, file, and (optionally) hunk.
 //!
 //! Agents (and humans) call this to produce a clickable URL they can paste
-//! into chat, PR descriptions, or markdown notes.
+//! into chat, PR descriptions, or markdown notes. `--permalink` prints the
+//! path form (`review://<repo-id>/<ref>/<hunk-id>`, see
+//! [`crate::review::permalink`]) instead.
 
 use std::path::PathBuf;
 
 use clap::Args;
 
 use crate::review::central::compute_repo_id;
+use crate::review::permalink::{hunk_permalink, review_permalink};
 
 use super::common::{parse_hunk_target, resolve_review_arg, HunkTarget};
 use super::get_repo_path;
@@ -41,6 +44,11 @@ pub struct UrlArgs {
     /// Hunk content hash (the `<hash>` half of a `file:hash` hunk ID).
     #[arg(long, conflicts_with = "target")]
     pub hunk: Option<String>,
+
+    /// Print a `review://<repo-id>/<ref>/<hunk-id>` permalink to the review,
+    /// or to the hunk when one is given.
+    #[arg(long, conflicts_with = "no_comparison")]
+    pub permalink: bool,
 }
 
 pub fn run_url(args: UrlArgs) -> Result<(), String> {
@@ -63,6 +71,19 @@ pub fn run_url(args: UrlArgs) -> Result<(), String> {
         Some(resolve_review_arg(&repo, args.spec.as_deref())?.ref_name)
     };
 
+    if args.permalink {
+        let review_ref = review_ref.unwrap_or_default();
+        let url = match (file, hunk) {
+            (Some(file), Some(hash)) => {
+                hunk_permalink(&repo_id, &review_ref, &format!("{file}:{hash}"))
+            }
+            (None, None) => review_permalink(&repo_id, &review_ref),
+            _ => return Err("A permalink names a whole review or one hunk, not a file.".to_owned()),
+        };
+        println!("{url}");
+        return Ok(());
+    }
+
     println!(
         "{}",
         build_review_url(
@@ -0,0 +1,887 @@
+//! Semantic deltas for API definitions: OpenAPI documents and protobuf files.
+//!
+//! A line diff of a spec shows which text moved, not what the API lost. This
+//! module extracts each side's API surface — OpenAPI operations, schemas and
+//! schema properties; protobuf messages, enums, services, fields, enum values
+//! and rpcs — and compares them. Removals, renumbered fields and enum values,
+//! and retyped fields or rpcs are breaking changes for existing clients.
+//!
+//! Everything here is line-oriented and dependency-free: OpenAPI YAML is read
+//! by indentation, JSON through `serde_json`, and `.proto` files by
+//! declaration. Each change carries the IDs of the hunks whose changed lines
+//! touch it.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::parser::{DiffHunk, LineType};
+use crate::symbols::SymbolChangeType;
+
+/// Label for hunks that break an API definition's existing clients.
+pub const API_BREAKING_LABEL: &str = "api:breaking-change";
+
+/// OpenAPI operation keys under a path item.
+pub const HTTP_METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiSchemaKind {
+    Openapi,
+    Protobuf,
+}
+
+/// One change to an API surface.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaChange {
+    /// The element that changed: `GET /users/{id}`, `schema User`,
+    /// `property User.email`, `message User`, `field User.email`,
+    /// `enum value Status.ACTIVE`, `rpc Users.Get`.
+    pub element: String,
+    pub change_type: SymbolChangeType,
+    /// Whether existing clients break.
+    pub breaking: bool,
+    /// What changed about a modified element, e.g. `renumbered 3 → 4`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Hunks whose changed lines touch the element.
+    pub hunk_ids: Vec<String>,
+}
+
+/// Semantic delta for a single API definition file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSchemaDelta {
+    pub file_path: String,
+    pub kind: ApiSchemaKind,
+    pub changes: Vec<SchemaChange>,
+}
+
+impl FileSchemaDelta {
+    /// IDs of the hunks that carry at least one breaking change.
+    pub fn breaking_hunk_ids(&self) -> HashSet<&str> {
+        self.changes
+            .iter()
+            .filter(|c| c.breaking)
+            .flat_map(|c| c.hunk_ids.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+/// One element of an API surface.
+#[derive(Debug, Clone, PartialEq)]
+struct Element {
+    /// Compared to detect modifications: the element's full text.
+    signature: String,
+    /// A field's or property's type, or an rpc's request/response types.
+    type_name: Option<String>,
+    /// A protobuf field's or enum value's number.
+    number: Option<i64>,
+    /// Key of the enclosing element, whose addition or removal covers this one.
+    parent: Option<String>,
+    /// 1-based, inclusive line range.
+    start: u32,
+    end: u32,
+}
+
+type Surface = BTreeMap<String, Element>;
+
+fn extension(path: &str) -> Option<String> {
+    let filename = path.rsplit('/').next().unwrap_or(path);
+    filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+}
+
+/// The kind of API definition `content` is, if any: any `.proto` file, or a
+/// YAML/JSON document with a top-level `openapi` or `swagger` key.
+pub fn api_schema_kind(path: &str, content: &str) -> Option<ApiSchemaKind> {
+    match extension(path)?.as_str() {
+        "proto" => Some(ApiSchemaKind::Protobuf),
+        "yaml" | "yml" => content
+            .lines()
+            .any(|l| l.starts_with("openapi:") || l.starts_with("swagger:"))
+            .then_some(ApiSchemaKind::Openapi),
+        "json" => serde_json::from_str::<serde_json::Value>(content)
+            .ok()
+            .filter(|v| v.get("openapi").is_some() || v.get("swagger").is_some())
+            .map(|_| ApiSchemaKind::Openapi),
+        _ => None,
+    }
+}
+
+/// Whether `path` has an extension an API definition can have, before looking
+/// at its content.
+pub fn may_be_api_schema(path: &str) -> bool {
+    matches!(
+        extension(path).as_deref(),
+        Some("proto" | "yaml" | "yml" | "json")
+    )
+}
+
+/// Whether a path alone marks an API definition: a `.proto` file, or a
+/// YAML/JSON file named after OpenAPI or Swagger.
+pub fn is_api_schema_path(path: &str) -> bool {
+    let filename = path.rsplit('/').next().unwrap_or(path).to_ascii_lowercase();
+    match extension(path).as_deref() {
+        Some("proto") => true,
+        Some("yaml" | "yml" | "json") => {
+            filename.contains("openapi") || filename.contains("swagger")
+        }
+        _ => false,
+    }
+}
+
+/// Compute the semantic delta between two versions of an API definition.
+/// Either side may be empty (file added or deleted). `None` when neither side
+/// is an API definition.
+pub fn compute_schema_delta(
+    file_path: &str,
+    old_content: &str,
+    new_content: &str,
+    hunks: &[DiffHunk],
+) -> Option<FileSchemaDelta> {
+    let kind = api_schema_kind(file_path, new_content)
+        .or_else(|| api_schema_kind(file_path, old_content))?;
+    let surface = |content: &str| match kind {
+        ApiSchemaKind::Protobuf => proto_surface(content),
+        ApiSchemaKind::Openapi => openapi_surface(file_path, content),
+    };
+    let (old, new) = (surface(old_content), surface(new_content));
+
+    let mut changes = Vec::new();
+    for (key, before) in &old {
+        let Some(after) = new.get(key) else {
+            // Covered by the removal of its parent.
+            if before.parent.as_ref().is_some_and(|p| !new.contains_key(p)) {
+                continue;
+            }
+            changes.push(SchemaChange {
+                element: key.clone(),
+                change_type: SymbolChangeType::Removed,
+                breaking: true,
+                detail: None,
+                hunk_ids: touching_hunks(hunks, Some(before), None),
+            });
+            continue;
+        };
+        if before.signature == after.signature {
+            continue;
+        }
+        let (breaking, detail) = if before.number != after.number {
+            let show = |n: Option<i64>| n.map_or_else(|| "none".to_owned(), |n| n.to_string());
+            let detail = format!(
+                "renumbered {} → {}",
+                show(before.number),
+                show(after.number)
+            );
+            (true, Some(detail))
+        } else if before.type_name != after.type_name {
+            let show = |t: &Option<String>| t.clone().unwrap_or_else(|| "untyped".to_owned());
+            let detail = format!("{} → {}", show(&before.type_name), show(&after.type_name));
+            (true, Some(detail))
+        } else {
+            (false, None)
+        };
+        changes.push(SchemaChange {
+            element: key.clone(),
+            change_type: SymbolChangeType::Modified,
+            breaking,
+            detail,
+            hunk_ids: touching_hunks(hunks, Some(before), Some(after)),
+        });
+    }
+    for (key, after) in &new {
+        if old.contains_key(key) || after.parent.as_ref().is_some_and(|p| !old.contains_key(p)) {
+            continue;
+        }
+        changes.push(SchemaChange {
+            element: key.clone(),
+            change_type: SymbolChangeType::Added,
+            breaking: false,
+            detail: None,
+            hunk_ids: touching_hunks(hunks, None, Some(after)),
+        });
+    }
+
+    Some(FileSchemaDelta {
+        file_path: file_path.to_owned(),
+        kind,
+        changes,
+    })
+}
+
+/// Hunks with a removed line inside `old`'s range or an added line inside
+/// `new`'s.
+fn touching_hunks(hunks: &[DiffHunk], old: Option<&Element>, new: Option<&Element>) -> Vec<String> {
+    let within = |element: Option<&Element>, line: Option<u32>| {
+        element
+            .zip(line)
+            .is_some_and(|(e, n)| (e.start..=e.end).contains(&n))
+    };
+    hunks
+        .iter()
+        .filter(|hunk| {
+            hunk.lines.iter().any(|l| match l.line_type {
+                LineType::Removed => within(old, l.old_line_number),
+                LineType::Added => within(new, l.new_line_number),
+                LineType::Context => false,
+            })
+        })
+        .map(|hunk| hunk.id.clone())
+        .collect()
+}
+
+// --- Protobuf ---
+
+/// A protobuf declaration found on a single line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtoDecl {
+    /// `message`, `enum`, `service`, or `oneof` opening a block.
+    Block {
+        kind: String,
+        name: String,
+    },
+    Field {
+        type_name: String,
+        name: String,
+        number: i64,
+    },
+    EnumValue {
+        name: String,
+        number: i64,
+    },
+    Rpc {
+        name: String,
+        signature: String,
+    },
+}
+
+static PROTO_BLOCK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(message|enum|service|oneof)\s+(\w+)\s*\{").unwrap());
+static PROTO_FIELD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(?:repeated|optional|required)\s+)?(map\s*<[^>]+>|[\w.]+)\s+(\w+)\s*=\s*(\d+)")
+        .unwrap()
+});
+static PROTO_ENUM_VALUE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\w+)\s*=\s*(-?\d+)").unwrap());
+static PROTO_RPC: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^rpc\s+(\w+)\s*\(\s*((?:stream\s+)?[\w.]+)\s*\)\s*returns\s*\(\s*((?:stream\s+)?[\w.]+)\s*\)",
+    )
+    .unwrap()
+});
+
+/// Parse the declaration on a comment-free, trimmed line of a `.proto` file.
+pub fn parse_proto_line(line: &str) -> Option<ProtoDecl> {
+    if let Some(caps) = PROTO_BLOCK.captures(line) {
+        return Some(ProtoDecl::Block {
+            kind: caps[1].to_owned(),
+            name: caps[2].to_owned(),
+        });
+    }
+    if let Some(caps) = PROTO_RPC.captures(line) {
+        return Some(ProtoDecl::Rpc {
+            name: caps[1].to_owned(),
+            signature: format!("({}) returns ({})", &caps[2], &caps[3]),
+        });
+    }
+    if let Some(caps) = PROTO_FIELD.captures(line) {
+        let type_name = caps[1].split_whitespace().collect::<String>();
+        if matches!(type_name.as_str(), "option" | "reserved" | "extensions") {
+            return None;
+        }
+        return Some(ProtoDecl::Field {
+            type_name,
+            name: caps[2].to_owned(),
+            number: caps[3].parse().ok()?,
+        });
+    }
+    let caps = PROTO_ENUM_VALUE.captures(line)?;
+    Some(ProtoDecl::EnumValue {
+        name: caps[1].to_owned(),
+        number: caps[2].parse().ok()?,
+    })
+}
+
+/// Strip `//` and `/* */` comments from a line, tracking whether a block
+/// comment is still open at its end.
+pub fn strip_proto_comments(line: &str, in_block: &mut bool) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    loop {
+        if *in_block {
+            let Some(end) = rest.find("*/") else {
+                return out;
+            };
+            *in_block = false;
+            rest = &rest[end + 2..];
+        }
+        match (rest.find("//"), rest.find("/*")) {
+            (Some(line_start), block) if block.is_none_or(|b| line_start < b) => {
+                out.push_str(&rest[..line_start]);
+                return out;
+            }
+            (_, Some(block_start)) => {
+                out.push_str(&rest[..block_start]);
+                *in_block = true;
+                rest = &rest[block_start + 2..];
+            }
+            _ => {
+                out.push_str(rest);
+                return out;
+            }
+        }
+    }
+}
+
+struct ProtoScope {
+    kind: String,
+    /// Qualified name, `None` for anonymous braces (option literals).
+    name: Option<String>,
+    /// Key of the element this scope opened, to close its range.
+    key: Option<String>,
+}
+
+fn proto_surface(content: &str) -> Surface {
+    let mut surface = Surface::new();
+    let mut scopes: Vec<ProtoScope> = Vec::new();
+    let mut in_block = false;
+
+    for (i, raw) in content.lines().enumerate() {
+        let line_no = i as u32 + 1;
+        let line = strip_proto_comments(raw, &mut in_block);
+        let line = line.trim();
+        let mut opens = line.matches('{').count();
+
+        // The innermost named scope, and its element (oneofs are transparent).
+        let owner = scopes
+            .iter()
+            .rev()
+            .find(|s| s.name.is_some() && s.kind != "oneof");
+        let owner_kind = owner.map(|s| s.kind.clone());
+        let owner_name = owner.and_then(|s| s.name.clone());
+        let owner_key = owner.and_then(|s| s.key.clone());
+        let qualify = |name: &str| match &owner_name {
+            Some(parent) => format!("{parent}.{name}"),
+            None => name.to_owned(),
+        };
+        let element = |type_name, number| Element {
+            signature: line.to_owned(),
+            type_name,
+            number,
+            parent: owner_key.clone(),
+            start: line_no,
+            end: line_no,
+        };
+
+        match parse_proto_line(line) {
+            Some(ProtoDecl::Block { kind, name }) => {
+                opens = opens.saturating_sub(1);
+                if kind == "oneof" {
+                    scopes.push(ProtoScope {
+                        kind,
+                        name: Some(name),
+                        key: None,
+                    });
+                } else {
+                    let qualified = qualify(&name);
+                    let key = format!("{kind} {qualified}");
+                    surface.insert(key.clone(), element(None, None));
+                    scopes.push(ProtoScope {
+                        kind,
+                        name: Some(qualified),
+                        key: Some(key),
+                    });
+                }
+            }
+            Some(ProtoDecl::Field {
+                type_name,
+                name,
+                number,
+            }) if owner_kind.as_deref() == Some("message") => {
+                surface.insert(
+                    format!("field {}", qualify(&name)),
+                    element(Some(type_name), Some(number)),
+                );
+            }
+            Some(ProtoDecl::EnumValue { name, number })
+                if owner_kind.as_deref() == Some("enum") =>
+            {
+                surface.insert(
+                    format!("enum value {}", qualify(&name)),
+                    element(None, Some(number)),
+                );
+            }
+            Some(ProtoDecl::Rpc { name, signature })
+                if owner_kind.as_deref() == Some("service") =>
+            {
+                surface.insert(
+                    format!("rpc {}", qualify(&name)),
+                    element(Some(signature), None),
+                );
+            }
+            _ => {}
+        }
+
+        for _ in 0..opens {
+            scopes.push(ProtoScope {
+                kind: String::new(),
+                name: None,
+                key: None,
+            });
+        }
+        for _ in 0..line.matches('}').count() {
+            let Some(scope) = scopes.pop() else { break };
+            if let Some(element) = scope.key.and_then(|key| surface.get_mut(&key)) {
+                element.end = line_no;
+            }
+        }
+    }
+    surface
+}
+
+// --- OpenAPI ---
+
+/// A key in an OpenAPI document, with the line range of its value.
+struct Node {
+    path: Vec<String>,
+    start: u32,
+    end: u32,
+    /// The `type` of the value, when it declares one.
+    type_name: Option<String>,
+    signature: String,
+}
+
+static YAML_KEY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(\s*)(?:"([^"]+)"|'([^']+)'|([^\s#'"\-][^:#]*?))\s*:(?:\s+(.*))?$"#).unwrap()
+});
+
+fn yaml_nodes(content: &str) -> Vec<Node> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut nodes: Vec<Node> = Vec::new();
+    // (indent, key, node index)
+    let mut stack: Vec<(usize, String, usize)> = Vec::new();
+
+    let close = |nodes: &mut Vec<Node>, index: usize, before: usize| {
+        // The value ends at the last non-blank line before `before`.
+        let mut end = before;
+        while end > nodes[index].start as usize && lines[end - 1].trim().is_empty() {
+            end -= 1;
+        }
+        let node = &mut nodes[index];
+        node.end = end as u32;
+        node.signature = lines[node.start as usize - 1..end]
+            .iter()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect::<Vec<_>>()
+            .join("\n");
+    };
+
+    for (i, line) in lines.iter().enumerate() {
+        let Some(caps) = YAML_KEY.captures(line) else {
+            continue;
+        };
+        let indent = caps[1].len();
+        let key = caps
+            .get(2)
+            .or_else(|| caps.get(3))
+            .or_else(|| caps.get(4))
+            .map_or("", |m| m.as_str())
+            .to_owned();
+        while stack.last().is_some_and(|(n, _, _)| *n >= indent) {
+            let (_, _, index) = stack.pop().unwrap();
+            close(&mut nodes, index, i);
+        }
+        // A `type: x` line types its parent.
+        if key == "type" {
+            if let (Some((_, _, parent)), Some(value)) = (stack.last(), caps.get(5)) {
+                let value = value.as_str().trim().trim_matches(['"', '\'']);
+                nodes[*parent]
+                    .type_name
+                    .get_or_insert_with(|| value.to_owned());
+            }
+        }
+        let mut path: Vec<String> = stack.iter().map(|(_, k, _)| k.clone()).collect();
+        path.push(key.clone());
+        nodes.push(Node {
+            path,
+            start: i as u32 + 1,
+            end: i as u32 + 1,
+            type_name: None,
+            signature: String::new(),
+        });
+        stack.push((indent, key, nodes.len() - 1));
+    }
+    while let Some((_, _, index)) = stack.pop() {
+        close(&mut nodes, index, lines.len());
+    }
+    nodes
+}
+
+/// Walk a JSON document, locating each key's value by scanning forward from
+/// its parent's position.
+fn json_nodes(content: &str) -> Vec<Node> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let mut nodes = Vec::new();
+    walk_json(&value, &mut Vec::new(), 0, &lines, &mut nodes);
+    nodes
+}
+
+fn walk_json(
+    value: &serde_json::Value,
+    path: &mut Vec<String>,
+    from_line: usize,
+    lines: &[&str],
+    nodes: &mut Vec<Node>,
+) {
+    let serde_json::Value::Object(map) = value else {
+        return;
+    };
+    // Keys may not come back in document order, so search each from the
+    // parent's line.
+    for (key, child) in map {
+        let needle = format!("{}:", serde_json::Value::String(key.clone()));
+        let start = (from_line..lines.len())
+            .find(|&i| lines[i].replace("\": ", "\":").contains(&needle))
+            .unwrap_or(from_line);
+        let end = json_value_end(lines, start);
+        path.push(key.clone());
+        nodes.push(Node {
+            path: path.clone(),
+            start: start as u32 + 1,
+            end: end as u32 + 1,
+            type_name: child
+                .get("type")
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_owned),
+            signature: child.to_string(),
+        });
+        walk_json(child, path, start, lines, nodes);
+        path.pop();
+    }
+}
+
+/// Line index where the value starting on line `start` ends: the line that
+/// balances its brackets, ignoring brackets inside strings.
+fn json_value_end(lines: &[&str], start: usize) -> usize {
+    let mut depth = 0i32;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut seen_colon = false;
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        for c in line.chars() {
+            if in_string {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' => in_string = true,
+                ':' if depth == 0 => seen_colon = true,
+                '{' | '[' => depth += 1,
+                '}' | ']' => {
+                    depth -= 1;
+                    if depth <= 0 && seen_colon {
+                        return i;
+                    }
+                }
+                ',' if depth == 0 && seen_colon => return i,
+                _ => {}
+            }
+        }
+        if depth == 0 && seen_colon && i > start {
+            return i;
+        }
+    }
+    start
+}
+
+fn openapi_surface(file_path: &str, content: &str) -> Surface {
+    let nodes = if extension(file_path).as_deref() == Some("json") {
+        json_nodes(content)
+    } else {
+        yaml_nodes(content)
+    };
+    let mut surface = Surface::new();
+    for node in nodes {
+        let path: Vec<&str> = node.path.iter().map(String::as_str).collect();
+        let (key, parent) = match path.as_slice() {
+            ["paths", route, method] if HTTP_METHODS.contains(method) => {
+                (format!("{} {route}", method.to_ascii_uppercase()), None)
+            }
+            ["components", "schemas", schema] | ["definitions", schema] => {
+                (format!("schema {schema}"), None)
+            }
+            ["components", "schemas", schema, "properties", property]
+            | ["definitions", schema, "properties", property] => (
+                format!("property {schema}.{property}"),
+                Some(format!("schema {schema}")),
+            ),
+            _ => continue,
+        };
+        // Schemas are compared through their properties.
+        let signature = if key.starts_with("schema ") {
+            String::new()
+        } else {
+            node.signature
+        };
+        surface.insert(
+            key,
+            Element {
+                signature,
+                type_name: node.type_name,
+                number: None,
+                parent,
+                start: node.start,
+                end: node.end,
+            },
+        );
+    }
+    surface
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::parse_diff;
+
+    fn change<'a>(delta: &'a FileSchemaDelta, element: &str) -> &'a SchemaChange {
+        delta
+            .changes
+            .iter()
+            .find(|c| c.element == element)
+            .unwrap_or_else(|| panic!("no change to {element}: {:?}", delta.changes))
+    }
+
+    #[test]
+    fn detects_schema_kinds() {
+        assert_eq!(
+            api_schema_kind("api/user.proto", ""),
+            Some(ApiSchemaKind::Protobuf)
+        );
+        assert_eq!(
+            api_schema_kind("spec.yaml", "openapi: 3.0.0\npaths: {}\n"),
+            Some(ApiSchemaKind::Openapi)
+        );
+        assert_eq!(
+            api_schema_kind("spec.json", r#"{"swagger": "2.0"}"#),
+            Some(ApiSchemaKind::Openapi)
+        );
+        assert_eq!(api_schema_kind("config.yaml", "name: app\n"), None);
+        assert!(is_api_schema_path("docs/openapi.yml"));
+        assert!(!is_api_schema_path("config.yaml"));
+    }
+
+    #[test]
+    fn parses_proto_declarations() {
+        assert_eq!(
+            parse_proto_line("repeated string tags = 4 [deprecated = true];"),
+            Some(ProtoDecl::Field {
+                type_name: "string".to_owned(),
+                name: "tags".to_owned(),
+                number: 4,
+            })
+        );
+        assert_eq!(
+            parse_proto_line("map<string, int32> counts = 5;"),
+            Some(ProtoDecl::Field {
+                type_name: "map<string,int32>".to_owned(),
+                name: "counts".to_owned(),
+                number: 5,
+            })
+        );
+        assert_eq!(
+            parse_proto_line("rpc Get(GetRequest) returns (stream User);"),
+            Some(ProtoDecl::Rpc {
+                name: "Get".to_owned(),
+                signature: "(GetRequest) returns (stream User)".to_owned(),
+            })
+        );
+        assert_eq!(parse_proto_line("option java_package = \"x\";"), None);
+        assert_eq!(parse_proto_line("reserved 2, 15;"), None);
+
+        let mut in_block = false;
+        assert_eq!(
+            strip_proto_comments("int32 a = 1; // note", &mut in_block),
+            "int32 a = 1; "
+        );
+        assert_eq!(strip_proto_comments("/* start", &mut in_block), "");
+        assert!(in_block);
+        assert_eq!(
+            strip_proto_comments("end */ int32 b = 2;", &mut in_block),
+            " int32 b = 2;"
+        );
+    }
+
+    #[test]
+    fn proto_delta_flags_breaking_changes() {
+        let old = "\
+syntax = \"proto3\";
+
+message User {
+  string name = 1;
+  string email = 2;
+  int32 age = 3;
+}
+
+message Legacy {
+  string id = 1;
+}
+
+service Users {
+  rpc Get(GetRequest) returns (User);
+}
+";
+        let new = "\
+syntax = \"proto3\";
+
+message User {
+  string name = 1;
+  int64 age = 3;
+  string email = 4;
+  oneof contact {
+    string phone = 5;
+  }
+}
+
+service Users {
+  rpc Get(GetRequest) returns (User);
+  rpc List(ListRequest) returns (stream User);
+}
+";
+        let diff = "\
+--- a/api/user.proto
++++ b/api/user.proto
+@@ -3,13 +3,13 @@
+ message User {
+   string name = 1;
+-  string email = 2;
+-  int32 age = 3;
++  int64 age = 3;
++  string email = 4;
++  oneof contact {
++    string phone = 5;
++  }
+ }
+ \n\
+-message Legacy {
+-  string id = 1;
+-}
+-
+ service Users {
+   rpc Get(GetRequest) returns (User);
++  rpc List(ListRequest) returns (stream User);
+ }
+";
+        let hunks = parse_diff(diff, "api/user.proto");
+        let delta = compute_schema_delta("api/user.proto", old, new, &hunks).unwrap();
+        assert_eq!(delta.kind, ApiSchemaKind::Protobuf);
+
+        let email = change(&delta, "field User.email");
+        assert!(email.breaking);
+        assert_eq!(email.detail.as_deref(), Some("renumbered 2 → 4"));
+        assert_eq!(email.hunk_ids, [hunks[0].id.clone()]);
+        let age = change(&delta, "field User.age");
+        assert_eq!(age.detail.as_deref(), Some("int32 → int64"));
+        assert!(change(&delta, "message Legacy").breaking);
+        assert!(!change(&delta, "field User.phone").breaking);
+        assert!(!change(&delta, "rpc Users.List").breaking);
+        // Fields of a removed message are covered by the message's removal.
+        assert!(delta.changes.iter().all(|c| c.element != "field Legacy.id"));
+        assert_eq!(delta.changes.len(), 5);
+        assert_eq!(delta.breaking_hunk_ids().len(), 1);
+    }
+
+    #[test]
+    fn openapi_yaml_delta() {
+        let old = "\
+openapi: 3.0.0
+paths:
+  /users:
+    get:
+      summary: List users
+    post:
+      summary: Create a user
+  /users/{id}:
+    get:
+      summary: Get a user
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        id:
+          type: string
+        email:
+          type: string
+";
+        let new = "\
+openapi: 3.0.0
+paths:
+  /users:
+    get:
+      summary: List all users
+  /users/{id}:
+    get:
+      summary: Get a user
+components:
+  schemas:
+    User:
+      type: object
+      properties:
+        id:
+          type: integer
+";
+        let delta = compute_schema_delta("openapi.yaml", old, new, &[]).unwrap();
+        assert_eq!(delta.kind, ApiSchemaKind::Openapi);
+        assert!(change(&delta, "POST /users").breaking);
+        assert!(change(&delta, "property User.email").breaking);
+        let id = change(&delta, "property User.id");
+        assert_eq!(id.detail.as_deref(), Some("string → integer"));
+        let list = change(&delta, "GET /users");
+        assert_eq!(list.change_type, SymbolChangeType::Modified);
+        assert!(!list.breaking);
+        assert_eq!(delta.changes.len(), 4);
+    }
+
+    #[test]
+    fn openapi_json_delta() {
+        let old = r#"{
+  "openapi": "3.1.0",
+  "paths": {
+    "/pets": {
+      "get": {"summary": "List pets"},
+      "delete": {"summary": "Delete all pets"}
+    }
+  }
+}
+"#;
+        let new = r#"{
+  "openapi": "3.1.0",
+  "paths": {
+    "/pets": {
+      "get": {"summary": "List pets"}
+    }
+  }
+}
+"#;
+        let delta = compute_schema_delta("api.json", old, new, &[]).unwrap();
+        let removed = change(&delta, "DELETE /pets");
+        assert!(removed.breaking);
+        assert_eq!(delta.changes.len(), 1);
+    }
+}
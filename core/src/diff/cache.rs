@@ -3,21 +3,24 @@
 //! Caches `Vec<DiffHunk>` keyed by the SHA-256 hash of the full diff
 //! output. If the diff hasn't changed, the cached hunks are returned
 //! directly, skipping diff parsing entirely.
+//!
+//! The hunks of an encrypted review's comparisons (see
+//! [`crate::review::encryption`]) are cached sealed, and not at all while
+//! the review is locked.
 
 use super::parser::DiffHunk;
-use crate::review::central;
+use crate::review::{central, encryption};
 use crate::sources::traits::Comparison;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::fs;
-use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
 /// Bump this when the diff parsing algorithm changes to auto-invalidate
 /// stale caches.
-const CACHE_VERSION: u32 = 1;
+const CACHE_VERSION: u32 = 3;
 
 #[derive(Serialize, Deserialize)]
 struct HunkCache {
@@ -53,8 +56,8 @@ pub fn load(
     if !path.exists() {
         return Ok(None);
     }
-    let content = fs::read_to_string(&path)?;
-    let cached: HunkCache = serde_json::from_str(&content)?;
+    let content = encryption::open(fs::read(&path)?)?;
+    let cached: HunkCache = serde_json::from_slice(&content)?;
     if cached.version == CACHE_VERSION && cached.diff_hash == current_diff_hash {
         Ok(Some(cached.hunks))
     } else {
@@ -86,7 +89,27 @@ pub fn save(
         diff_hash,
         hunks,
     };
-    let file = fs::File::create(&path)?;
-    serde_json::to_writer(BufWriter::new(file), &cache)?;
+    let content = encryption::seal_for(repo_path, &comparison.head, serde_json::to_vec(&cache)?)?;
+    fs::write(&path, content)?;
+    Ok(())
+}
+
+/// Drop the cached hunks of every comparison whose head is `head`.
+pub fn clear_for_head(repo_path: &Path, head: &str) -> std::io::Result<()> {
+    let Ok(cache_dir) = central::get_repo_cache_dir(repo_path) else {
+        return Ok(());
+    };
+    let suffix = format!("..{}.json", central::sanitize_path_component(head));
+    let entries = match fs::read_dir(cache_dir.join("hunk-cache")) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().ends_with(&suffix) {
+            fs::remove_file(entry.path())?;
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,275 @@
+//! Diff two in-memory texts into hunks, without a repository.
+//!
+//! [`diff_contents`] lets callers preview a change that only exists in
+//! memory — "what would this suggestion look like applied" — as the same
+//! [`DiffHunk`]s a git comparison produces: it renders a unified diff with
+//! git's default three lines of context and runs it through
+//! [`parse_diff`], so hunk IDs and line numbers follow the usual rules.
+
+use std::fmt::Write as _;
+
+use super::parser::{parse_diff, DiffHunk};
+
+/// Unchanged lines shown around each change, as `git diff` does by default.
+const CONTEXT_LINES: usize = 3;
+
+/// Edit distance past which the middle of the texts is shown as replaced
+/// wholesale rather than diffed line by line, bounding time and memory.
+const MAX_EDIT_DISTANCE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// The shortest edit script turning `old` into `new` (Myers' algorithm), or
+/// `None` when it needs more than [`MAX_EDIT_DISTANCE`] edits.
+fn shortest_edit<T: PartialEq>(old: &[T], new: &[T]) -> Option<Vec<Op>> {
+    let (old_len, new_len) = (old.len(), new.len());
+    let max = (old_len + new_len).min(MAX_EDIT_DISTANCE);
+    // `furthest[max + k]` is the furthest x reached on diagonal k = x - y.
+    let mut furthest = vec![0usize; 2 * max + 2];
+    let mut trace = Vec::new();
+    let mut reached = false;
+    'search: for d in 0..=max {
+        trace.push(furthest.clone());
+        for idx in (max - d..=max + d).step_by(2) {
+            let down = idx == max - d || (idx != max + d && furthest[idx - 1] < furthest[idx + 1]);
+            let mut x = if down {
+                furthest[idx + 1]
+            } else {
+                furthest[idx - 1] + 1
+            };
+            let mut y = x + max - idx;
+            while x < old_len && y < new_len && old[x] == new[y] {
+                x += 1;
+                y += 1;
+            }
+            furthest[idx] = x;
+            if x >= old_len && y >= new_len {
+                reached = true;
+                break 'search;
+            }
+        }
+    }
+    if !reached {
+        return None;
+    }
+
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (old_len, new_len);
+    for (d, furthest) in trace.iter().enumerate().rev() {
+        if d == 0 {
+            ops.extend(std::iter::repeat_n(Op::Equal, x));
+            break;
+        }
+        let idx = x + max - y;
+        let down = idx == max - d || (idx != max + d && furthest[idx - 1] < furthest[idx + 1]);
+        let prev_idx = if down { idx + 1 } else { idx - 1 };
+        let prev_x = furthest[prev_idx];
+        let prev_y = prev_x + max - prev_idx;
+        while x > prev_x && y > prev_y {
+            ops.push(Op::Equal);
+            x -= 1;
+            y -= 1;
+        }
+        ops.push(if down { Op::Insert } else { Op::Delete });
+        (x, y) = (prev_x, prev_y);
+    }
+    ops.reverse();
+    Some(ops)
+}
+
+/// The edit script for the whole texts: common leading and trailing lines
+/// are matched up front, and only the middle is diffed. Works on any
+/// sequence — lines, tokens.
+pub(crate) fn edit_script<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Op> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_mid, new_mid) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    let middle = shortest_edit(old_mid, new_mid).unwrap_or_else(|| {
+        let mut replaced = vec![Op::Delete; old_mid.len()];
+        replaced.resize(old_mid.len() + new_mid.len(), Op::Insert);
+        replaced
+    });
+
+    let mut ops = vec![Op::Equal; prefix];
+    ops.extend(middle);
+    ops.resize(ops.len() + suffix, Op::Equal);
+    ops
+}
+
+/// `git diff`'s range for a hunk starting after `before` lines of a side
+/// and spanning `count`: an empty range names the line before it.
+fn hunk_range(before: usize, count: usize) -> String {
+    let start = if count == 0 { before } else { before + 1 };
+    format!("{start},{count}")
+}
+
+/// Diff `old_text` against `new_text` into hunks for the file `path_hint`
+/// (used for the hunks' path and IDs only; nothing is read from disk). A
+/// change to only the final newline produces no hunks.
+pub fn diff_contents(old_text: &str, new_text: &str, path_hint: &str) -> Vec<DiffHunk> {
+    let old: Vec<&str> = old_text.lines().collect();
+    let new: Vec<&str> = new_text.lines().collect();
+    let ops = edit_script(&old, &new);
+
+    // Each op with the number of old and new lines before it.
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for &op in &ops {
+        positions.push((op, old_line, new_line));
+        match op {
+            Op::Equal => {
+                old_line += 1;
+                new_line += 1;
+            }
+            Op::Delete => old_line += 1,
+            Op::Insert => new_line += 1,
+        }
+    }
+
+    let changes: Vec<usize> = (0..ops.len()).filter(|&i| ops[i] != Op::Equal).collect();
+    let mut unified = String::new();
+    let mut group_start = 0;
+    while group_start < changes.len() {
+        // Changes separated by no more unchanged lines than two hunks'
+        // worth of context share one hunk.
+        let mut group_end = group_start;
+        while group_end + 1 < changes.len()
+            && changes[group_end + 1] - changes[group_end] - 1 <= 2 * CONTEXT_LINES
+        {
+            group_end += 1;
+        }
+        let first = changes[group_start].saturating_sub(CONTEXT_LINES);
+        let last = (changes[group_end] + CONTEXT_LINES).min(ops.len() - 1);
+        let span = &positions[first..=last];
+        let old_count = span.iter().filter(|(op, ..)| *op != Op::Insert).count();
+        let new_count = span.iter().filter(|(op, ..)| *op != Op::Delete).count();
+        let (_, old_before, new_before) = span[0];
+        let _ = writeln!(
+            unified,
+            "@@ -{} +{} @@",
+            hunk_range(old_before, old_count),
+            hunk_range(new_before, new_count)
+        );
+        for &(op, old_index, new_index) in span {
+            let _ = match op {
+                Op::Equal => writeln!(unified, " {}", old[old_index]),
+                Op::Delete => writeln!(unified, "-{}", old[old_index]),
+                Op::Insert => writeln!(unified, "+{}", new[new_index]),
+            };
+        }
+        group_start = group_end + 1;
+    }
+    parse_diff(&unified, path_hint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::{split_threshold, LineType};
+
+    fn numbered(prefix: &str, lines: std::ops::RangeInclusive<usize>) -> String {
+        lines.fold(String::new(), |mut text, i| {
+            let _ = writeln!(text, "{prefix} {i}");
+            text
+        })
+    }
+
+    #[test]
+    fn matches_the_hunks_git_would_produce() {
+        let old = "a\nb\nc\nd\ne\n";
+        let new = "a\nb\nC\nd\ne\nf\n";
+        let git = "@@ -1,5 +1,6 @@\n a\n b\n-c\n+C\n d\n e\n+f\n";
+        let hunks = diff_contents(old, new, "src/x.txt");
+        let expected = parse_diff(git, "src/x.txt");
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].id, expected[0].id);
+        assert_eq!(
+            (
+                hunks[0].old_start,
+                hunks[0].old_count,
+                hunks[0].new_start,
+                hunks[0].new_count
+            ),
+            (1, 5, 1, 6)
+        );
+        assert_eq!(hunks[0].line_counts(), (2, 1));
+    }
+
+    #[test]
+    fn splits_distant_changes_into_separate_hunks() {
+        let old = numbered("line", 1..=20);
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "");
+        let hunks = diff_contents(&old, &new, "f");
+        assert_eq!(hunks.len(), 2);
+        assert_eq!((hunks[0].old_start, hunks[0].old_count), (1, 5));
+        assert_eq!(
+            (
+                hunks[1].old_start,
+                hunks[1].old_count,
+                hunks[1].new_start,
+                hunks[1].new_count
+            ),
+            (15, 6, 15, 5)
+        );
+        let removed = hunks[1]
+            .lines
+            .iter()
+            .find(|l| l.line_type == LineType::Removed)
+            .unwrap();
+        assert_eq!(removed.old_line_number, Some(18));
+
+        let nearby = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 8\n", "line eight\n");
+        assert_eq!(diff_contents(&old, &nearby, "f").len(), 1);
+    }
+
+    #[test]
+    fn handles_empty_and_identical_sides() {
+        assert!(diff_contents("same\n", "same\n", "f").is_empty());
+        assert!(diff_contents("same", "same\n", "f").is_empty());
+
+        let added = diff_contents("", "a\nb\n", "f");
+        assert_eq!(
+            (
+                added[0].old_start,
+                added[0].old_count,
+                added[0].new_start,
+                added[0].new_count
+            ),
+            (0, 0, 1, 2)
+        );
+        let deleted = diff_contents("a\nb\n", "", "f");
+        assert_eq!(deleted[0].line_counts(), (0, 2));
+    }
+
+    #[test]
+    fn replaces_wholesale_past_the_edit_distance_limit() {
+        let old = numbered("old", 0..=MAX_EDIT_DISTANCE);
+        let new = numbered("new", 0..=MAX_EDIT_DISTANCE);
+        let hunks = diff_contents(&old, &new, "f");
+        // One git hunk, which `parse_diff` splits when it's giant.
+        let count = MAX_EDIT_DISTANCE + 1;
+        let totals = hunks.iter().fold((0, 0), |(added, removed), hunk| {
+            let (a, r) = hunk.line_counts();
+            (added + a, removed + r)
+        });
+        assert_eq!(totals, (count, count));
+        assert_eq!(hunks.len(), (2 * count).div_ceil(split_threshold()));
+    }
+}
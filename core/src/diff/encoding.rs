@@ -0,0 +1,187 @@
+//! Decoding text that isn't UTF-8.
+//!
+//! Legacy codebases keep files in Latin-1, Shift-JIS and the like, which
+//! `String::from_utf8` rejects outright. [`decode`] keeps UTF-8 as is and
+//! otherwise guesses the encoding with `chardetng`, decoding with
+//! `encoding_rs` and replacing what doesn't fit with U+FFFD — the
+//! [`TextEncoding`] it returns says which encoding was used and whether
+//! anything was replaced, so the UI can flag the file. Binary content (a NUL
+//! in the first 8000 bytes, git's own test, without a UTF-16 BOM) isn't
+//! decoded at all.
+//!
+//! [`decode_diff`] does the same per file section of a `git diff`, so each
+//! file's hunks are decoded on their own and tagged with their encoding.
+
+use std::collections::HashMap;
+
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+use serde::{Deserialize, Serialize};
+
+use super::parser::{section_file_path, DiffHunk};
+
+/// How far into content to look for a NUL byte, as git does.
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+/// The encoding text that wasn't UTF-8 was decoded from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEncoding {
+    /// WHATWG name, e.g. `Shift_JIS` or `windows-1252` (which Latin-1 is
+    /// read as).
+    pub name: String,
+    /// Some bytes weren't valid in it and were replaced with U+FFFD.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lossy: bool,
+}
+
+/// Decode file content: UTF-8 as is (with no [`TextEncoding`]), anything
+/// else in its detected encoding. `None` for binary content.
+pub fn decode(bytes: Vec<u8>) -> Option<(String, Option<TextEncoding>)> {
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => return Some((text, None)),
+        Err(e) => e.into_bytes(),
+    };
+    if let Some((encoding, bom_len)) = Encoding::for_bom(&bytes) {
+        return Some(decode_as(encoding, &bytes[bom_len..]));
+    }
+    if bytes.iter().take(BINARY_SNIFF_BYTES).any(|&b| b == 0) {
+        return None;
+    }
+    Some(decode_as(detect(&bytes), &bytes))
+}
+
+fn detect(bytes: &[u8]) -> &'static Encoding {
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    // It isn't UTF-8 (that was tried first), so don't let a guess say it is.
+    detector.guess(None, false)
+}
+
+fn decode_as(encoding: &'static Encoding, bytes: &[u8]) -> (String, Option<TextEncoding>) {
+    let (text, lossy) = encoding.decode_without_bom_handling(bytes);
+    let encoding = TextEncoding {
+        name: encoding.name().to_owned(),
+        lossy,
+    };
+    (text.into_owned(), Some(encoding))
+}
+
+/// A `git diff` decoded file section by file section.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodedDiff {
+    pub text: String,
+    /// Encoding of each file whose section wasn't UTF-8, by path.
+    pub encodings: HashMap<String, TextEncoding>,
+}
+
+impl DecodedDiff {
+    /// Set the encoding of every hunk from a file decoded from one.
+    pub fn tag(&self, hunks: &mut [DiffHunk]) {
+        if self.encodings.is_empty() {
+            return;
+        }
+        for hunk in hunks {
+            hunk.encoding = self.encodings.get(&hunk.file_path).cloned();
+        }
+    }
+}
+
+/// Decode raw `git diff` output. Sections that are valid UTF-8 (almost
+/// always all of them) are kept as is; each other one is decoded in the
+/// encoding detected from its own bytes.
+pub fn decode_diff(bytes: Vec<u8>) -> DecodedDiff {
+    let bytes = match String::from_utf8(bytes) {
+        Ok(text) => {
+            return DecodedDiff {
+                text,
+                encodings: HashMap::new(),
+            }
+        }
+        Err(e) => e.into_bytes(),
+    };
+    let mut decoded = DecodedDiff {
+        text: String::with_capacity(bytes.len()),
+        encodings: HashMap::new(),
+    };
+    for section in split_byte_sections(&bytes) {
+        if let Ok(text) = std::str::from_utf8(section) {
+            decoded.text.push_str(text);
+            continue;
+        }
+        let (text, encoding) = decode_as(detect(section), section);
+        if let (Some(path), Some(encoding)) = (section_file_path(&text), encoding) {
+            decoded.encodings.insert(path, encoding);
+        }
+        decoded.text.push_str(&text);
+    }
+    decoded
+}
+
+/// Split diff bytes before each "diff --git " line; anything ahead of the
+/// first one is a section of its own.
+fn split_byte_sections(bytes: &[u8]) -> Vec<&[u8]> {
+    let mut sections = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    for line in bytes.split_inclusive(|&b| b == b'\n') {
+        if line.starts_with(b"diff --git ") && offset > start {
+            sections.push(&bytes[start..offset]);
+            start = offset;
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        sections.push(&bytes[start..offset]);
+    }
+    sections
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::parse_multi_file_diff;
+
+    #[test]
+    fn decodes_utf8_as_is_and_legacy_text_flagged() {
+        assert_eq!(
+            decode(b"caf\xc3\xa9\n".to_vec()),
+            Some(("café\n".to_owned(), None))
+        );
+
+        let (text, encoding) = decode(b"caf\xe9 cr\xe8me br\xfbl\xe9e\n".to_vec()).unwrap();
+        assert_eq!(text, "café crème brûlée\n");
+        let encoding = encoding.unwrap();
+        assert_eq!(encoding.name, "windows-1252");
+        assert!(!encoding.lossy);
+
+        let shift_jis = encoding_rs::SHIFT_JIS.encode("// 日本語のコメントです\n").0;
+        let (text, encoding) = decode(shift_jis.into_owned()).unwrap();
+        assert_eq!(text, "// 日本語のコメントです\n");
+        assert_eq!(encoding.unwrap().name, "Shift_JIS");
+
+        assert_eq!(decode(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\xff".to_vec()), None);
+    }
+
+    #[test]
+    fn diff_sections_are_decoded_and_their_hunks_tagged() {
+        let mut diff =
+            b"diff --git a/new.txt b/new.txt\n--- a/new.txt\n+++ b/new.txt\n@@ -1 +1 @@\n-a\n+b\n"
+                .to_vec();
+        diff.extend_from_slice(
+            b"diff --git a/old.txt b/old.txt\n--- a/old.txt\n+++ b/old.txt\n@@ -1 +1 @@\n-na\xefve\n+caf\xe9\n",
+        );
+        let decoded = decode_diff(diff);
+        assert!(decoded.text.contains("+café\n"));
+        assert_eq!(decoded.encodings.len(), 1);
+
+        let mut hunks = parse_multi_file_diff(&decoded.text);
+        decoded.tag(&mut hunks);
+        let encoding = |path: &str| {
+            let hunk = hunks.iter().find(|h| h.file_path == path).unwrap();
+            hunk.encoding.as_ref().map(|e| e.name.clone())
+        };
+        assert_eq!(encoding("new.txt"), None);
+        assert_eq!(encoding("old.txt").as_deref(), Some("windows-1252"));
+    }
+}
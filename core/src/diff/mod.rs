@@ -1,2 +1,6 @@
+pub mod api_schema;
 pub mod cache;
+pub mod contents;
+pub mod encoding;
 pub mod parser;
+pub mod translations;
@@ -1,6 +1,11 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+use super::contents::{edit_script, Op};
+use super::encoding::TextEncoding;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiffHunk {
     pub id: String,
@@ -22,6 +27,67 @@ pub struct DiffHunk {
     /// ID of the paired hunk if this is part of a move
     #[serde(rename = "movePairId", skip_serializing_if = "Option::is_none")]
     pub move_pair_id: Option<String>,
+    /// IDs of the hunks a plain `git diff` would have shown for this one's
+    /// changes, when it's wider than them: merging close hunks (see
+    /// [`merge_gap`]), or with function context (see [`parse_wide_diff`]).
+    /// Decisions on either carry over to the other.
+    #[serde(rename = "mergedIds", default, skip_serializing_if = "Vec::is_empty")]
+    pub merged_ids: Vec<String>,
+    /// Set when the hunk is a submodule's gitlink moving, rather than lines of
+    /// a file (see [`SubmoduleHunk`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub submodule: Option<SubmoduleHunk>,
+    /// The encoding the file's diff was decoded from, when it wasn't UTF-8
+    /// (see [`super::encoding`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<TextEncoding>,
+}
+
+/// A submodule pointer change: git shows it as a hunk whose only lines are
+/// `-Subproject commit <old>` and `+Subproject commit <new>`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleHunk {
+    /// `None` when the submodule was added.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_sha: Option<String>,
+    /// `None` when the submodule was removed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_sha: Option<String>,
+    /// The checkout has uncommitted changes (git's `-dirty` suffix, seen when
+    /// diffing the working tree).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dirty: bool,
+}
+
+impl SubmoduleHunk {
+    /// The pointer change a hunk's `lines` record, if that's all they are.
+    pub fn from_lines(lines: &[DiffLine]) -> Option<Self> {
+        let mut pointer = Self {
+            old_sha: None,
+            new_sha: None,
+            dirty: false,
+        };
+        for line in lines {
+            let commit = line.content.strip_prefix("Subproject commit ")?;
+            let (sha, dirty) = match commit.strip_suffix("-dirty") {
+                Some(sha) => (sha, true),
+                None => (commit, false),
+            };
+            if sha.len() < 40 || !sha.chars().all(|c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            match line.line_type {
+                LineType::Removed => pointer.old_sha = Some(sha.to_owned()),
+                LineType::Added => {
+                    pointer.new_sha = Some(sha.to_owned());
+                    pointer.dirty = dirty;
+                }
+                LineType::Context => return None,
+            }
+        }
+        (pointer.old_sha.is_some() || pointer.new_sha.is_some()).then_some(pointer)
+    }
 }
 
 impl DiffHunk {
@@ -47,6 +113,98 @@ impl DiffHunk {
         }
         hex::encode(&hasher.finalize()[..8])
     }
+
+    /// Whether this is a synthetic hunk standing in for content that wasn't
+    /// diffed: a binary or oversized file, or an untracked one read without
+    /// its content.
+    pub fn is_placeholder(&self) -> bool {
+        let untracked_without_content = self.content == UNTRACKED_HUNK_CONTENT
+            && matches!(self.lines.as_slice(), [line] if line.content == NEW_FILE_LINE);
+        untracked_without_content || self.lines.iter().all(|l| l.line_type == LineType::Context)
+    }
+
+    /// Number of added and removed lines.
+    pub fn line_counts(&self) -> (usize, usize) {
+        self.lines
+            .iter()
+            .fold((0, 0), |(added, removed), line| match line.line_type {
+                LineType::Added => (added + 1, removed),
+                LineType::Removed => (added, removed + 1),
+                LineType::Context => (added, removed),
+            })
+    }
+}
+
+/// Hunks for tests, built the way the parser builds them.
+#[cfg(test)]
+impl DiffHunk {
+    /// A hunk in `file_path` with `lines`, numbered from line 1 on both sides.
+    /// Its ID and content hash derive from the lines, as for a parsed hunk.
+    pub(crate) fn test<'a>(
+        file_path: &str,
+        lines: impl IntoIterator<Item = (LineType, &'a str)>,
+    ) -> Self {
+        let mut builder = HunkBuilder {
+            old_start: 1,
+            old_count: 0,
+            new_start: 1,
+            new_count: 0,
+            content: String::new(),
+            lines: Vec::new(),
+            old_line: 1,
+            new_line: 1,
+        };
+        for (line_type, content) in lines {
+            builder.add_line(line_type, content);
+        }
+        builder.old_count = builder.old_line - 1;
+        builder.new_count = builder.new_line - 1;
+        builder.build(file_path)
+    }
+
+    /// The same hunk under another ID.
+    pub(crate) fn with_id(self, id: &str) -> Self {
+        Self {
+            id: id.to_owned(),
+            ..self
+        }
+    }
+
+    /// The same hunk at another range, its lines renumbered from the starts.
+    pub(crate) fn with_range(
+        mut self,
+        old_start: u32,
+        old_count: u32,
+        new_start: u32,
+        new_count: u32,
+    ) -> Self {
+        let (mut old_line, mut new_line) = (old_start, new_start);
+        for line in &mut self.lines {
+            if line.line_type != LineType::Added {
+                line.old_line_number = Some(old_line);
+                old_line += 1;
+            }
+            if line.line_type != LineType::Removed {
+                line.new_line_number = Some(new_line);
+                new_line += 1;
+            }
+        }
+        Self {
+            old_start,
+            old_count,
+            new_start,
+            new_count,
+            ..self
+        }
+    }
+
+    /// The only hunk in `diff`, a `git diff` of one file.
+    pub(crate) fn test_from_diff(diff: &str) -> Self {
+        parse_multi_file_diff(diff)
+            .into_iter()
+            .next()
+            .expect("one hunk")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,8 +251,100 @@ pub fn extract_rename_map(diff_output: &str) -> std::collections::HashMap<String
     map
 }
 
-/// Parse a git diff output into hunks
+/// Changed lines above which [`parse_diff`] splits a hunk (see [`split_hunk`]).
+pub const DEFAULT_SPLIT_LINES: usize = 200;
+
+/// Overrides [`DEFAULT_SPLIT_LINES`]; `0` turns splitting off.
+pub const SPLIT_LINES_ENV: &str = "REVIEW_SPLIT_HUNK_LINES";
+
+/// The configured split threshold.
+pub fn split_threshold() -> usize {
+    std::env::var(SPLIT_LINES_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_SPLIT_LINES)
+}
+
+/// Merge hunks with fewer than this many unchanged lines between them; `0`
+/// (the default) keeps git's hunks. Applied by diffing with
+/// `--inter-hunk-context` (see [`inter_hunk_context_arg`]).
+pub const MERGE_GAP_ENV: &str = "REVIEW_MERGE_HUNK_GAP";
+
+/// The configured merge gap.
+pub fn merge_gap() -> usize {
+    std::env::var(MERGE_GAP_ENV)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// The `git diff` argument that merges hunks per [`merge_gap`], if any.
+pub fn inter_hunk_context_arg() -> Option<String> {
+    let gap = merge_gap();
+    (gap > 1).then(|| format!("--inter-hunk-context={}", gap - 1))
+}
+
+/// Context lines git puts around each change in a hunk.
+const GIT_CONTEXT_LINES: usize = 3;
+
+/// Parse a git diff output into hunks, splitting giant ones so they can be
+/// reviewed piece by piece. With merging on, each hunk lists the hunks it
+/// merges.
 pub fn parse_diff(diff_output: &str, file_path: &str) -> Vec<DiffHunk> {
+    parse_hunks(diff_output, file_path, merge_gap() > 1)
+}
+
+/// [`parse_diff`] for a diff with more context than git's default (e.g.
+/// `--function-context`): each hunk lists the hunks a plain diff would have
+/// shown, so decisions map between the two.
+pub fn parse_wide_diff(diff_output: &str, file_path: &str) -> Vec<DiffHunk> {
+    parse_hunks(diff_output, file_path, true)
+}
+
+fn parse_hunks(diff_output: &str, file_path: &str, with_plain_ids: bool) -> Vec<DiffHunk> {
+    let max_changed = split_threshold();
+    parse_raw_diff(diff_output, file_path)
+        .into_iter()
+        .flat_map(|mut hunk| {
+            if with_plain_ids {
+                hunk.merged_ids = plain_hunk_ids(&hunk);
+            }
+            split_hunk(hunk, max_changed)
+        })
+        .collect()
+}
+
+/// IDs of the hunks a plain `git diff` would have shown for the changes in
+/// `hunk`: each run of changes with its own context, runs more than twice the
+/// context apart being separate hunks. Empty when that's `hunk` itself.
+pub fn plain_hunk_ids(hunk: &DiffHunk) -> Vec<String> {
+    let changed: Vec<usize> = (0..hunk.lines.len())
+        .filter(|&i| hunk.lines[i].line_type != LineType::Context)
+        .collect();
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        match runs.last_mut() {
+            Some((_, last)) if i - *last <= 2 * GIT_CONTEXT_LINES + 1 => *last = i,
+            _ => runs.push((i, i)),
+        }
+    }
+    let ids: Vec<String> = runs
+        .into_iter()
+        .map(|(first, last)| {
+            let from = first.saturating_sub(GIT_CONTEXT_LINES);
+            let to = (last + GIT_CONTEXT_LINES + 1).min(hunk.lines.len());
+            hunk_piece(hunk, from, to).id
+        })
+        .collect();
+    if ids == [hunk.id.as_str()] {
+        return Vec::new();
+    }
+    ids
+}
+
+/// Parse a git diff output into git's own hunks, unsplit — what a patch built
+/// from the diff has to address.
+pub fn parse_raw_diff(diff_output: &str, file_path: &str) -> Vec<DiffHunk> {
     let mut hunks = Vec::new();
     let mut current_hunk: Option<HunkBuilder> = None;
 
@@ -144,6 +394,26 @@ pub fn parse_diff(diff_output: &str, file_path: &str) -> Vec<DiffHunk> {
 /// Splits on "diff --git" boundaries, extracts the file path from "+++ b/" lines,
 /// and delegates each section to `parse_diff`.
 pub fn parse_multi_file_diff(diff_output: &str) -> Vec<DiffHunk> {
+    parse_multi_file_diff_with(diff_output, parse_diff)
+}
+
+/// [`parse_multi_file_diff`] with git's own hunks, unsplit (see
+/// [`parse_raw_diff`]).
+pub fn parse_raw_multi_file_diff(diff_output: &str) -> Vec<DiffHunk> {
+    parse_multi_file_diff_with(diff_output, parse_raw_diff)
+}
+
+fn parse_multi_file_diff_with(
+    diff_output: &str,
+    parse_diff: fn(&str, &str) -> Vec<DiffHunk>,
+) -> Vec<DiffHunk> {
+    let parse_section = |section: &str, file_path: &str| {
+        let mut hunks = parse_diff(section, file_path);
+        for hunk in &mut hunks {
+            hunk.submodule = SubmoduleHunk::from_lines(&hunk.lines);
+        }
+        hunks
+    };
     let mut hunks = Vec::new();
     let mut current_section = String::new();
     let mut current_file: Option<String> = None;
@@ -155,7 +425,7 @@ pub fn parse_multi_file_diff(diff_output: &str) -> Vec<DiffHunk> {
             // Flush previous section
             if let Some(ref file_path) = current_file {
                 if !current_section.is_empty() {
-                    hunks.extend(parse_diff(&current_section, file_path));
+                    hunks.extend(parse_section(&current_section, file_path));
                 }
             }
             current_section.clear();
@@ -184,7 +454,7 @@ pub fn parse_multi_file_diff(diff_output: &str) -> Vec<DiffHunk> {
     // Flush last section
     if let Some(ref file_path) = current_file {
         if !current_section.is_empty() {
-            hunks.extend(parse_diff(&current_section, file_path));
+            hunks.extend(parse_section(&current_section, file_path));
         }
     }
 
@@ -255,8 +525,143 @@ impl HunkBuilder {
             lines: self.lines,
             content_hash,
             move_pair_id: None,
+            merged_ids: Vec::new(),
+            submodule: None,
+            encoding: None,
+        }
+    }
+}
+
+/// Split a hunk with more than `max_changed` added and removed lines into
+/// pieces of at most that many, so each can be reviewed and approved on its
+/// own. Cuts go before statements (per tree-sitter, on either side of the
+/// diff) or after blank lines — the least nested boundary that keeps the
+/// piece within the limit and at least a quarter full, the latest among
+/// equals — and never between a removed line and the line added in its
+/// place; a run with no such boundary is cut where it hits the limit. Each
+/// piece is an ordinary hunk whose ID hashes its own lines, so it stays put
+/// while other parts of the original hunk change. `0` disables splitting.
+pub fn split_hunk(hunk: DiffHunk, max_changed: usize) -> Vec<DiffHunk> {
+    let (added, removed) = hunk.line_counts();
+    if max_changed == 0 || added + removed <= max_changed {
+        return vec![hunk];
+    }
+    let statements = statement_starts(&hunk);
+    let lines = &hunk.lines;
+    let is_changed = |line: &DiffLine| line.line_type != LineType::Context;
+    // Lower is a better place to cut; `None` is no place to cut.
+    let cut_rank = |i: usize| {
+        let (prev, line) = (&lines[i - 1], &lines[i]);
+        if prev.line_type == LineType::Removed && line.line_type == LineType::Added {
+            return None;
+        }
+        let blank = prev.content.trim().is_empty();
+        match statements.get(&i) {
+            Some(&depth) => Some((depth, !blank)),
+            None if blank => Some((usize::MAX, false)),
+            None => None,
+        }
+    };
+    let min_changed = (max_changed / 4).max(1);
+
+    let mut cuts = Vec::new();
+    let mut start = 0;
+    let mut changed = 0;
+    let mut candidates = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > start && changed >= min_changed {
+            if let Some(rank) = cut_rank(i) {
+                candidates.push((std::cmp::Reverse(rank), i));
+            }
+        }
+        if is_changed(line) {
+            changed += 1;
+        }
+        if changed > max_changed {
+            let cut = candidates.iter().max().map_or(i, |&(_, cut)| cut);
+            candidates.clear();
+            changed = lines[cut..=i].iter().filter(|l| is_changed(l)).count();
+            cuts.push(cut);
+            start = cut;
+        }
+    }
+
+    let mut bounds = vec![0];
+    bounds.extend(cuts);
+    bounds.push(lines.len());
+    bounds
+        .windows(2)
+        .map(|w| hunk_piece(&hunk, w[0], w[1]))
+        .collect()
+}
+
+/// Indices into `hunk.lines` that start a statement on the old or new side,
+/// with their nesting (see
+/// [`statement_start_rows`](crate::symbols::extractor::statement_start_rows)).
+fn statement_starts(hunk: &DiffHunk) -> std::collections::HashMap<usize, usize> {
+    let mut starts = std::collections::HashMap::new();
+    for skip in [LineType::Added, LineType::Removed] {
+        let side: Vec<usize> = (0..hunk.lines.len())
+            .filter(|&i| hunk.lines[i].line_type != skip)
+            .collect();
+        let text = side.iter().fold(String::new(), |mut text, &i| {
+            text.push_str(&hunk.lines[i].content);
+            text.push('\n');
+            text
+        });
+        let rows = crate::symbols::extractor::statement_start_rows(&text, &hunk.file_path);
+        for (row, depth) in rows.into_iter().flatten() {
+            if let Some(&i) = side.get(row) {
+                let best: &mut usize = starts.entry(i).or_insert(depth);
+                *best = (*best).min(depth);
+            }
         }
     }
+    starts
+}
+
+/// `hunk.lines[from..to]` as a hunk of its own.
+fn hunk_piece(hunk: &DiffHunk, from: usize, to: usize) -> DiffHunk {
+    let lines = &hunk.lines[from..to];
+    // A side with no lines in the piece starts, as in git's headers, at the
+    // line before it.
+    let before = |number: fn(&DiffLine) -> Option<u32>, start: u32, count: u32| {
+        hunk.lines[..from]
+            .iter()
+            .rev()
+            .find_map(number)
+            .unwrap_or(if count > 0 {
+                start.saturating_sub(1)
+            } else {
+                start
+            })
+    };
+    let side = |number: fn(&DiffLine) -> Option<u32>, start: u32, count: u32| {
+        let count_in_piece = lines.iter().filter(|l| number(l).is_some()).count() as u32;
+        let first = lines
+            .iter()
+            .find_map(number)
+            .unwrap_or_else(|| before(number, start, count));
+        (first, count_in_piece)
+    };
+    let (old_start, old_count) = side(|l| l.old_line_number, hunk.old_start, hunk.old_count);
+    let (new_start, new_count) = side(|l| l.new_line_number, hunk.new_start, hunk.new_count);
+    let mut content = String::new();
+    for line in lines {
+        content.push_str(&line.content);
+        content.push('\n');
+    }
+    HunkBuilder {
+        old_start,
+        old_count,
+        new_start,
+        new_count,
+        content,
+        lines: lines.to_vec(),
+        old_line: 0,
+        new_line: 0,
+    }
+    .build(&hunk.file_path)
 }
 
 fn parse_hunk_header(line: &str) -> Option<(u32, u32, u32, u32)> {
@@ -332,6 +737,9 @@ fn create_synthetic_hunk(
         lines: vec![line],
         content_hash,
         move_pair_id: None,
+        merged_ids: Vec::new(),
+        submodule: None,
+        encoding: None,
     }
 }
 
@@ -343,6 +751,12 @@ pub fn compute_content_hash(bytes: &[u8]) -> String {
     hex::encode(&hasher.finalize()[..8])
 }
 
+/// `content` of an untracked file's hunk.
+const UNTRACKED_HUNK_CONTENT: &str = "(untracked file)";
+
+/// The line standing in for an untracked file's content when it isn't read.
+const NEW_FILE_LINE: &str = "(new file)";
+
 /// Create a hunk for an untracked (new) file.
 /// The `content_hash` should be a hash of the file's actual content so that
 /// modifications to the file produce different hunk IDs (invalidating approvals).
@@ -366,7 +780,7 @@ pub fn create_untracked_hunk(
             .collect(),
         None => vec![DiffLine {
             line_type: LineType::Added,
-            content: "(new file)".to_owned(),
+            content: NEW_FILE_LINE.to_owned(),
             old_line_number: None,
             new_line_number: Some(1),
         }],
@@ -380,10 +794,13 @@ pub fn create_untracked_hunk(
         old_count: 0,
         new_start: 1,
         new_count,
-        content: "(untracked file)".to_owned(),
+        content: UNTRACKED_HUNK_CONTENT.to_owned(),
         lines,
         content_hash: content_hash.to_owned(),
         move_pair_id: None,
+        merged_ids: Vec::new(),
+        submodule: None,
+        encoding: None,
     }
 }
 
@@ -405,6 +822,59 @@ pub fn create_binary_hunk(file_path: &str) -> DiffHunk {
     )
 }
 
+/// Create a placeholder hunk for a file whose diff was too large to load.
+pub fn create_oversized_hunk(file_path: &str, diff_bytes: usize) -> DiffHunk {
+    create_synthetic_hunk(
+        file_path,
+        &format!("(diff too large: {diff_bytes} bytes)"),
+        0,
+        0,
+        DiffLine {
+            line_type: LineType::Context,
+            content: format!("(diff too large to load: {diff_bytes} bytes)"),
+            old_line_number: None,
+            new_line_number: None,
+        },
+    )
+}
+
+/// Split a combined multi-file diff into its per-file sections, each starting
+/// at its "diff --git" header.
+pub fn split_diff_sections(diff_output: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = Vec::new();
+    let mut offset = 0;
+    for line in diff_output.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(diff_output.len());
+            &diff_output[start..end]
+        })
+        .collect()
+}
+
+/// File path of a diff section, from its "diff --git a/<path> b/<path>" header.
+/// Assumes `--no-renames` output, where both sides name the same path.
+pub fn section_file_path(section: &str) -> Option<String> {
+    let header = section.lines().next()?.strip_prefix("diff --git a/")?;
+    let path = if header.len() >= 3 && (header.len() - 3) % 2 == 0 {
+        let half = (header.len() - 3) / 2;
+        header
+            .get(..half)
+            .filter(|left| header.get(half..) == Some(&format!(" b/{left}")[..]))
+    } else {
+        None
+    };
+    path.or_else(|| header.rsplit_once(" b/").map(|(_, right)| right))
+        .map(ToOwned::to_owned)
+}
+
 /// Represents a detected move pair
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MovePair {
@@ -416,8 +886,39 @@ pub struct MovePair {
     pub source_file_path: String,
     #[serde(rename = "destFilePath")]
     pub dest_file_path: String,
+    /// What changed besides the move; empty for a pure move.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub residual: Vec<MoveEdit>,
+}
+
+/// A line of a moved block that differs between source and destination.
+/// Lines are indices into the hunks' `lines`; ranges are `[start, end)` in
+/// UTF-16 code units, as the UI indexes strings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MoveEdit {
+    /// `None` for a line only in the destination.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_line: Option<usize>,
+    /// `None` for a line only in the source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dest_line: Option<usize>,
+    pub source_ranges: Vec<(usize, usize)>,
+    pub dest_ranges: Vec<(usize, usize)>,
 }
 
+/// Share of lines a deletion and an addition must have in common (ignoring
+/// indentation) to pair as a modified move.
+const MIN_MODIFIED_MOVE_SIMILARITY: f64 = 0.6;
+
+/// Changed lines both sides of a modified move need, so that a couple of
+/// common lines (`}`, `return;`) don't pair unrelated hunks.
+const MIN_MODIFIED_MOVE_LINES: usize = 3;
+
+/// Deletion × addition candidates past which modified moves aren't looked
+/// for, bounding the time spent on huge diffs.
+const MAX_MODIFIED_MOVE_CANDIDATES: usize = 10_000;
+
 /// Check if a hunk consists only of removed lines (deletions-only)
 fn is_deletions_only(hunk: &DiffHunk) -> bool {
     hunk.lines
@@ -462,12 +963,180 @@ fn compute_changed_content_hash(hunk: &DiffHunk) -> String {
     hex::encode(&hasher.finalize()[..8])
 }
 
+/// The changed lines of `line_type` in `hunk` with their indices, leading and
+/// trailing blank lines left out as in [`extract_changed_content`].
+fn changed_lines<'a>(hunk: &'a DiffHunk, line_type: &LineType) -> Vec<(usize, &'a str)> {
+    let lines: Vec<(usize, &str)> = hunk
+        .lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| line.line_type == *line_type)
+        .map(|(idx, line)| (idx, line.content.as_str()))
+        .collect();
+    let first = lines.iter().position(|(_, l)| !l.trim().is_empty());
+    let last = lines.iter().rposition(|(_, l)| !l.trim().is_empty());
+    match (first, last) {
+        (Some(first), Some(last)) => lines[first..=last].to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+fn trimmed<'a>(lines: &[(usize, &'a str)]) -> Vec<&'a str> {
+    lines.iter().map(|(_, l)| l.trim()).collect()
+}
+
+/// Share of lines `source` and `dest` have in common, ignoring indentation.
+fn move_similarity(source: &[(usize, &str)], dest: &[(usize, &str)]) -> f64 {
+    let ops = edit_script(&trimmed(source), &trimmed(dest));
+    let equal = ops.iter().filter(|op| **op == Op::Equal).count();
+    #[expect(
+        clippy::cast_precision_loss,
+        reason = "line counts are far below f64's exact integer range"
+    )]
+    let similarity = 2.0 * equal as f64 / (source.len() + dest.len()) as f64;
+    similarity
+}
+
+/// What changed in a moved block besides the move: its lines aligned
+/// (ignoring indentation), and each differing pair diffed by token.
+pub fn move_residual(source: &DiffHunk, dest: &DiffHunk) -> Vec<MoveEdit> {
+    let src = changed_lines(source, &LineType::Removed);
+    let dst = changed_lines(dest, &LineType::Added);
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut deleted, mut inserted) = (Vec::new(), Vec::new());
+    for op in edit_script(&trimmed(&src), &trimmed(&dst)) {
+        match op {
+            Op::Equal => {
+                flush_line_edits(&mut edits, &mut deleted, &mut inserted);
+                if src[i].1 != dst[j].1 {
+                    edits.push(line_edit(Some(src[i]), Some(dst[j])));
+                }
+                i += 1;
+                j += 1;
+            }
+            Op::Delete => {
+                deleted.push(src[i]);
+                i += 1;
+            }
+            Op::Insert => {
+                inserted.push(dst[j]);
+                j += 1;
+            }
+        }
+    }
+    flush_line_edits(&mut edits, &mut deleted, &mut inserted);
+    edits
+}
+
+/// Pair up a run of deleted and inserted lines as modified lines, the rest
+/// being whole-line edits.
+fn flush_line_edits(
+    edits: &mut Vec<MoveEdit>,
+    deleted: &mut Vec<(usize, &str)>,
+    inserted: &mut Vec<(usize, &str)>,
+) {
+    let pairs = deleted.len().max(inserted.len());
+    for k in 0..pairs {
+        edits.push(line_edit(deleted.get(k).copied(), inserted.get(k).copied()));
+    }
+    deleted.clear();
+    inserted.clear();
+}
+
+fn line_edit(source: Option<(usize, &str)>, dest: Option<(usize, &str)>) -> MoveEdit {
+    let whole = |line: &str| vec![(0, line.encode_utf16().count())];
+    let (source_ranges, dest_ranges) = match (source, dest) {
+        (Some((_, old)), Some((_, new))) => changed_ranges(old, new),
+        (Some((_, old)), None) => (whole(old), Vec::new()),
+        (None, Some((_, new))) => (Vec::new(), whole(new)),
+        (None, None) => (Vec::new(), Vec::new()),
+    };
+    MoveEdit {
+        source_line: source.map(|(idx, _)| idx),
+        dest_line: dest.map(|(idx, _)| idx),
+        source_ranges,
+        dest_ranges,
+    }
+}
+
+/// Split a line into words, runs of whitespace, and single other characters.
+fn tokens(line: &str) -> Vec<&str> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<u8> = None;
+    for (idx, c) in line.char_indices() {
+        let cls = class(c);
+        if prev.is_some_and(|p| p != cls || cls == 2) {
+            tokens.push(&line[start..idx]);
+            start = idx;
+        }
+        prev = Some(cls);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+type Ranges = Vec<(usize, usize)>;
+
+/// The ranges of `old` and `new` that differ, diffed token by token.
+fn changed_ranges(old: &str, new: &str) -> (Ranges, Ranges) {
+    fn push(ranges: &mut Ranges, start: usize, end: usize) {
+        match ranges.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+    let (old_tokens, new_tokens) = (tokens(old), tokens(new));
+    let (mut old_ranges, mut new_ranges) = (Vec::new(), Vec::new());
+    let (mut i, mut j, mut old_at, mut new_at) = (0, 0, 0, 0);
+    for op in edit_script(&old_tokens, &new_tokens) {
+        match op {
+            Op::Equal => {
+                old_at += old_tokens[i].encode_utf16().count();
+                new_at += new_tokens[j].encode_utf16().count();
+                i += 1;
+                j += 1;
+            }
+            Op::Delete => {
+                let len = old_tokens[i].encode_utf16().count();
+                push(&mut old_ranges, old_at, old_at + len);
+                old_at += len;
+                i += 1;
+            }
+            Op::Insert => {
+                let len = new_tokens[j].encode_utf16().count();
+                push(&mut new_ranges, new_at, new_at + len);
+                new_at += len;
+                j += 1;
+            }
+        }
+    }
+    (old_ranges, new_ranges)
+}
+
 /// Detect move pairs in a list of hunks.
 /// A move is detected when:
 /// - Two hunks have the same changed content hash
 /// - One hunk is deletions-only (source)
 /// - One hunk is additions-only (destination)
 /// - They are in different files
+///
+/// Such pure moves set the hunks' `move_pair_id`. A deletion and addition
+/// that are mostly the same lines pair too, as a modified move, but only in
+/// the returned pairs: their `residual` holds what changed besides the move,
+/// which a `move:code` label would hide.
 pub fn detect_move_pairs(hunks: &mut [DiffHunk]) -> Vec<MovePair> {
     use std::collections::HashMap;
 
@@ -488,6 +1157,7 @@ pub fn detect_move_pairs(hunks: &mut [DiffHunk]) -> Vec<MovePair> {
     }
 
     // Find matching pairs
+    let mut paired = HashSet::new();
     for (hash, deletion_indices) in &deletions_by_hash {
         if let Some(addition_indices) = additions_by_hash.get(hash) {
             // Match deletions with additions and set move_pair_id directly by index
@@ -498,11 +1168,13 @@ pub fn detect_move_pairs(hunks: &mut [DiffHunk]) -> Vec<MovePair> {
                         let dest_id = hunks[add_idx].id.clone();
                         hunks[del_idx].move_pair_id = Some(dest_id.clone());
                         hunks[add_idx].move_pair_id = Some(source_id.clone());
+                        paired.extend([del_idx, add_idx]);
                         move_pairs.push(MovePair {
                             source_hunk_id: source_id,
                             dest_hunk_id: dest_id,
                             source_file_path: hunks[del_idx].file_path.clone(),
                             dest_file_path: hunks[add_idx].file_path.clone(),
+                            residual: move_residual(&hunks[del_idx], &hunks[add_idx]),
                         });
                     }
                 }
@@ -510,9 +1182,82 @@ pub fn detect_move_pairs(hunks: &mut [DiffHunk]) -> Vec<MovePair> {
         }
     }
 
+    move_pairs.extend(detect_modified_moves(
+        hunks,
+        deletions_by_hash.values().flatten().copied(),
+        additions_by_hash.values().flatten().copied(),
+        &paired,
+    ));
     move_pairs
 }
 
+/// Pair the unpaired deletions and additions that are mostly the same lines,
+/// most similar first, each hunk at most once.
+fn detect_modified_moves(
+    hunks: &[DiffHunk],
+    deletions: impl Iterator<Item = usize>,
+    additions: impl Iterator<Item = usize>,
+    paired: &HashSet<usize>,
+) -> Vec<MovePair> {
+    let candidates = |indices: Vec<usize>, line_type: LineType| {
+        indices
+            .into_iter()
+            .filter(|idx| !paired.contains(idx))
+            .map(|idx| (idx, changed_lines(&hunks[idx], &line_type)))
+            .filter(|(_, lines)| lines.len() >= MIN_MODIFIED_MOVE_LINES)
+            .collect::<Vec<_>>()
+    };
+    let deletions = candidates(deletions.collect(), LineType::Removed);
+    let additions = candidates(additions.collect(), LineType::Added);
+    if deletions.len() * additions.len() > MAX_MODIFIED_MOVE_CANDIDATES {
+        return Vec::new();
+    }
+
+    let mut scored = Vec::new();
+    for (del_idx, del_lines) in &deletions {
+        for (add_idx, add_lines) in &additions {
+            let (short, long) = if del_lines.len() < add_lines.len() {
+                (del_lines.len(), add_lines.len())
+            } else {
+                (add_lines.len(), del_lines.len())
+            };
+            #[expect(
+                clippy::cast_precision_loss,
+                reason = "line counts are far below f64's exact integer range"
+            )]
+            let best_possible = 2.0 * short as f64 / (short + long) as f64;
+            if hunks[*del_idx].file_path == hunks[*add_idx].file_path
+                || best_possible < MIN_MODIFIED_MOVE_SIMILARITY
+            {
+                continue;
+            }
+            let similarity = move_similarity(del_lines, add_lines);
+            if similarity >= MIN_MODIFIED_MOVE_SIMILARITY {
+                scored.push((similarity, *del_idx, *add_idx));
+            }
+        }
+    }
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0).then((a.1, a.2).cmp(&(b.1, b.2))));
+
+    let mut used = HashSet::new();
+    let mut pairs = Vec::new();
+    for (_, del_idx, add_idx) in scored {
+        if used.contains(&del_idx) || used.contains(&add_idx) {
+            continue;
+        }
+        used.extend([del_idx, add_idx]);
+        let (source, dest) = (&hunks[del_idx], &hunks[add_idx]);
+        pairs.push(MovePair {
+            source_hunk_id: source.id.clone(),
+            dest_hunk_id: dest.id.clone(),
+            source_file_path: source.file_path.clone(),
+            dest_file_path: dest.file_path.clone(),
+            residual: move_residual(source, dest),
+        });
+    }
+    pairs
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -554,6 +1299,7 @@ mod tests {
         assert_eq!(hunk.lines.len(), 1);
         assert_eq!(hunk.lines[0].content, "(new file)");
         assert!(hunk.move_pair_id.is_none());
+        assert!(hunk.is_placeholder());
     }
 
     #[test]
@@ -569,6 +1315,7 @@ mod tests {
         assert_eq!(hunk.lines[0].new_line_number, Some(1));
         assert_eq!(hunk.lines[2].content, "line three");
         assert_eq!(hunk.lines[2].new_line_number, Some(3));
+        assert!(!hunk.is_placeholder());
         for line in &hunk.lines {
             assert!(matches!(line.line_type, LineType::Added));
             assert!(line.old_line_number.is_none());
@@ -684,73 +1431,129 @@ mod tests {
         assert_eq!(hunks1[0].content_hash, hunks2[0].content_hash);
     }
 
+    use std::fmt::Write as _;
+
+    /// `count` added lines in paragraphs of `paragraph`, blank-line separated.
+    fn added_paragraphs(count: usize, paragraph: usize, tag: &str) -> String {
+        let mut diff = String::new();
+        for i in 0..count {
+            if i > 0 && i % paragraph == 0 {
+                diff.push_str("+\n");
+            }
+            let _ = writeln!(diff, "+{tag} {i}");
+        }
+        let added = diff.lines().count();
+        format!("@@ -1,2 +1,{} @@\n ctx\n{diff} end\n", added + 2)
+    }
+
+    #[test]
+    fn plain_hunk_ids_match_git_hunks() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let old: Vec<String> = (0..60).map(|i| format!("line {i}")).collect();
+        let mut new = old.clone();
+        // 8 and 20 unchanged lines between the changes.
+        for i in [10, 19, 40] {
+            new[i] = format!("changed {i}");
+        }
+        let (a, b) = (dir.path().join("a.txt"), dir.path().join("b.txt"));
+        std::fs::write(&a, old.join("\n") + "\n").unwrap();
+        std::fs::write(&b, new.join("\n") + "\n").unwrap();
+        let diff = |extra: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(["diff", "--no-index", "--histogram"])
+                .args(extra)
+                .arg(&a)
+                .arg(&b)
+                .output()
+                .unwrap();
+            parse_raw_diff(&String::from_utf8(output.stdout).unwrap(), "f.txt")
+        };
+        let plain = diff(&[]);
+        let merged = diff(&["--inter-hunk-context=9"]);
+        assert_eq!((plain.len(), merged.len()), (3, 2));
+        assert_eq!(
+            plain_hunk_ids(&merged[0]),
+            [plain[0].id.clone(), plain[1].id.clone()]
+        );
+        assert!(plain_hunk_ids(&merged[1]).is_empty());
+        assert_eq!(merged[1].id, plain[2].id);
+    }
+
+    #[test]
+    fn split_hunk_cuts_after_blank_lines() {
+        let hunk = parse_raw_diff(&added_paragraphs(30, 10, "x"), "notes.txt").remove(0);
+        assert!(split_hunk(hunk.clone(), 0).len() == 1);
+        let pieces = split_hunk(hunk.clone(), 15);
+        assert_eq!(pieces.len(), 3);
+        // Each piece ends with the blank line that closed its paragraph.
+        assert_eq!(pieces[0].lines.last().unwrap().content, "");
+        assert_eq!(pieces[1].lines[0].content, "x 10");
+        assert_eq!(
+            (pieces[1].old_start, pieces[1].old_count),
+            (1, 0),
+            "no old lines: starts after the line before"
+        );
+        assert_eq!((pieces[1].new_start, pieces[1].new_count), (13, 11));
+        assert_eq!(
+            pieces.iter().map(|p| p.lines.len()).sum::<usize>(),
+            hunk.lines.len()
+        );
+
+        // Editing one paragraph leaves the other pieces' IDs alone.
+        let edited = parse_raw_diff(
+            &added_paragraphs(30, 10, "x").replace("+x 25\n", "+changed\n"),
+            "notes.txt",
+        )
+        .remove(0);
+        let edited = split_hunk(edited, 15);
+        assert_eq!(edited[0].id, pieces[0].id);
+        assert_eq!(edited[1].id, pieces[1].id);
+        assert_ne!(edited[2].id, pieces[2].id);
+
+        // With no boundary in reach, the cut is where the limit is hit.
+        let dense = parse_raw_diff(&added_paragraphs(30, 100, "x"), "notes.txt").remove(0);
+        let pieces = split_hunk(dense, 15);
+        assert_eq!(
+            pieces.iter().map(|p| p.line_counts().0).collect::<Vec<_>>(),
+            [15, 15]
+        );
+    }
+
+    #[cfg(feature = "symbols-rust-lang")]
+    #[test]
+    fn split_hunk_cuts_before_statements() {
+        let mut diff = String::new();
+        for f in 0..4 {
+            let _ = write!(diff, "+fn f{f}() {{\n+    let a = 1;\n+    a + {f}\n+}}\n");
+        }
+        let added = diff.lines().count();
+        let diff = format!("@@ -0,0 +1,{added} @@\n{diff}");
+        let pieces = split_hunk(parse_raw_diff(&diff, "lib.rs").remove(0), 10);
+        assert_eq!(pieces.len(), 2);
+        assert_eq!(pieces[1].lines[0].content, "fn f2() {");
+    }
+
     #[test]
     fn test_detect_move_pairs() {
         // Create a deletion hunk (code removed from file_a.rs)
-        let del_hunk = DiffHunk {
-            id: "file_a.rs:abc123".to_string(),
-            file_path: "file_a.rs".to_string(),
-            old_start: 1,
-            old_count: 3,
-            new_start: 1,
-            new_count: 0,
-            content: "fn hello() {\n    println!(\"Hello\");\n}\n".to_string(),
-            lines: vec![
-                DiffLine {
-                    line_type: LineType::Removed,
-                    content: "fn hello() {".to_string(),
-                    old_line_number: Some(1),
-                    new_line_number: None,
-                },
-                DiffLine {
-                    line_type: LineType::Removed,
-                    content: "    println!(\"Hello\");".to_string(),
-                    old_line_number: Some(2),
-                    new_line_number: None,
-                },
-                DiffLine {
-                    line_type: LineType::Removed,
-                    content: "}".to_string(),
-                    old_line_number: Some(3),
-                    new_line_number: None,
-                },
+        let del_hunk = DiffHunk::test(
+            "file_a.rs",
+            [
+                (LineType::Removed, "fn hello() {"),
+                (LineType::Removed, "    println!(\"Hello\");"),
+                (LineType::Removed, "}"),
             ],
-            content_hash: "abc123".to_string(),
-            move_pair_id: None,
-        };
+        );
 
         // Create an addition hunk (same code added to file_b.rs)
-        let add_hunk = DiffHunk {
-            id: "file_b.rs:def456".to_string(),
-            file_path: "file_b.rs".to_string(),
-            old_start: 1,
-            old_count: 0,
-            new_start: 1,
-            new_count: 3,
-            content: "fn hello() {\n    println!(\"Hello\");\n}\n".to_string(),
-            lines: vec![
-                DiffLine {
-                    line_type: LineType::Added,
-                    content: "fn hello() {".to_string(),
-                    old_line_number: None,
-                    new_line_number: Some(1),
-                },
-                DiffLine {
-                    line_type: LineType::Added,
-                    content: "    println!(\"Hello\");".to_string(),
-                    old_line_number: None,
-                    new_line_number: Some(2),
-                },
-                DiffLine {
-                    line_type: LineType::Added,
-                    content: "}".to_string(),
-                    old_line_number: None,
-                    new_line_number: Some(3),
-                },
+        let add_hunk = DiffHunk::test(
+            "file_b.rs",
+            [
+                (LineType::Added, "fn hello() {"),
+                (LineType::Added, "    println!(\"Hello\");"),
+                (LineType::Added, "}"),
             ],
-            content_hash: "def456".to_string(),
-            move_pair_id: None,
-        };
+        );
 
         let mut hunks = vec![del_hunk.clone(), add_hunk.clone()];
         let pairs = detect_move_pairs(&mut hunks);
@@ -766,6 +1569,53 @@ mod tests {
         assert_eq!(hunks[1].move_pair_id, Some(del_hunk.id.clone()));
     }
 
+    #[test]
+    fn modified_move_pairs_with_its_residual() {
+        let removed = "@@ -1,5 +0,0 @@\n-fn total(items: &[u32]) -> u32 {\n-    let mut sum = 0;\n-    for i in items {\n-        sum += i;\n-    }\n";
+        let added = "@@ -0,0 +1,6 @@\n+fn total(items: &[u32]) -> u32 {\n+    let mut acc = 0;\n+    for i in items {\n+      sum += i;\n+    }\n+    acc\n";
+        let mut hunks = parse_diff(removed, "a.rs");
+        hunks.extend(parse_diff(added, "b.rs"));
+        let pairs = detect_move_pairs(&mut hunks);
+
+        assert_eq!(pairs.len(), 1);
+        assert_eq!(pairs[0].source_file_path, "a.rs");
+        assert!(
+            hunks.iter().all(|h| h.move_pair_id.is_none()),
+            "only pure moves are labelled as moves"
+        );
+        let edit = |line| {
+            pairs[0]
+                .residual
+                .iter()
+                .find(|e| e.dest_line == Some(line))
+                .cloned()
+                .unwrap()
+        };
+        // A renamed variable: just the name differs.
+        assert_eq!(edit(1).source_line, Some(1));
+        assert_eq!(
+            (edit(1).source_ranges, edit(1).dest_ranges),
+            (vec![(12, 15)], vec![(12, 15)])
+        );
+        // Re-indented.
+        assert_eq!(
+            (edit(3).source_ranges, edit(3).dest_ranges),
+            (vec![(0, 8)], vec![(0, 6)])
+        );
+        // A line only in the destination.
+        assert_eq!(edit(5).source_line, None);
+        assert_eq!(edit(5).dest_ranges, vec![(0, 7)]);
+        assert_eq!(pairs[0].residual.len(), 3);
+
+        // Too different to be the same code.
+        let mut hunks = parse_diff(removed, "a.rs");
+        hunks.extend(parse_diff(
+            "@@ -0,0 +1,3 @@\n+struct Other;\n+impl Other {}\n+const X: u8 = 1;\n",
+            "b.rs",
+        ));
+        assert!(detect_move_pairs(&mut hunks).is_empty());
+    }
+
     #[test]
     fn test_parse_multi_file_diff_empty() {
         let hunks = parse_multi_file_diff("");
@@ -827,12 +1677,36 @@ diff --git a/kept.rs b/kept.rs
         assert_eq!(hunks[1].file_path, "kept.rs");
     }
 
+    #[test]
+    fn test_submodule_pointer_hunks() {
+        let old = "a".repeat(40);
+        let new = "b".repeat(40);
+        let diff = format!(
+            "diff --git a/lib b/lib\nindex {old}..{new} 160000\n--- a/lib\n+++ b/lib\n\
+             @@ -1 +1 @@\n-Subproject commit {old}\n+Subproject commit {new}-dirty\n\
+             diff --git a/notes.txt b/notes.txt\n--- a/notes.txt\n+++ b/notes.txt\n\
+             @@ -1 +1 @@\n-Subproject commit {old}\n+just prose\n"
+        );
+        let hunks = parse_multi_file_diff(&diff);
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(
+            hunks[0].submodule,
+            Some(SubmoduleHunk {
+                old_sha: Some(old),
+                new_sha: Some(new),
+                dirty: true,
+            })
+        );
+        assert_eq!(hunks[1].submodule, None);
+    }
+
     #[test]
     fn test_create_binary_hunk() {
         let hunk = create_binary_hunk("icons/128x128.png");
         assert_eq!(hunk.file_path, "icons/128x128.png");
         assert!(hunk.id.starts_with("icons/128x128.png:"));
         assert_eq!(hunk.content, "(binary file)");
+        assert!(hunk.is_placeholder());
         assert_eq!(hunk.lines.len(), 1);
         assert_eq!(hunk.lines[0].content, "(binary file changed)");
         assert!(hunk.move_pair_id.is_none());
@@ -953,4 +1827,34 @@ Binary files a/old.png and /dev/null differ";
         assert_eq!(hunks.len(), 1);
         assert_eq!(hunks[0].file_path, "old.png");
     }
+
+    #[test]
+    fn test_split_diff_sections_and_paths() {
+        let diff = "\
+diff --git a/a.rs b/a.rs
+--- a/a.rs
++++ b/a.rs
+@@ -1 +1 @@
+-x
++y
+diff --git a/dir with b/c.txt b/dir with b/c.txt
+--- a/dir with b/c.txt
++++ b/dir with b/c.txt
+@@ -1 +1 @@
+-x
++y
+";
+        let sections = split_diff_sections(diff);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections.concat(), diff);
+        assert_eq!(section_file_path(sections[0]).as_deref(), Some("a.rs"));
+        assert_eq!(
+            section_file_path(sections[1]).as_deref(),
+            Some("dir with b/c.txt")
+        );
+        assert_eq!(
+            parse_multi_file_diff(sections[1])[0].file_path,
+            "dir with b/c.txt"
+        );
+    }
 }
@@ -0,0 +1,462 @@
+//! Translation catalog detection and key-level rollups.
+//!
+//! Locale files (`locales/en.json`, `po/de.po`, ...) change in bulk: a
+//! re-export reorders keys, a new string lands in every locale at once, and the
+//! line diff drowns the few keys that actually changed. For the formats we can
+//! parse (JSON/ARB catalogs and gettext PO/POT files) a file's hunks can be
+//! replaced by one synthetic rollup hunk listing the keys added, removed, and
+//! changed, one `key = value` line each.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::parser::{compute_content_hash, DiffHunk, DiffLine, LineType};
+
+/// Directory names that mark the files beneath them as translation catalogs.
+const LOCALE_DIRS: &[&str] = &[
+    "locales",
+    "locale",
+    "i18n",
+    "l10n",
+    "lang",
+    "langs",
+    "translations",
+];
+
+/// Catalog extensions recognized under a [`LOCALE_DIRS`] directory.
+const LOCALE_DIR_EXTENSIONS: &[&str] = &[
+    "json",
+    "yml",
+    "yaml",
+    "po",
+    "pot",
+    "arb",
+    "properties",
+    "strings",
+    "xliff",
+    "xlf",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CatalogFormat {
+    Json,
+    Po,
+}
+
+fn extension(path: &str) -> Option<String> {
+    let filename = path.rsplit('/').next().unwrap_or(path);
+    filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+}
+
+/// Whether `path` is a translation catalog: a gettext or ARB file anywhere, or
+/// a catalog-format file under a locale directory.
+pub fn is_translation_file(path: &str) -> bool {
+    let Some(ext) = extension(path) else {
+        return false;
+    };
+    if matches!(ext.as_str(), "po" | "pot" | "arb") {
+        return true;
+    }
+    LOCALE_DIR_EXTENSIONS.contains(&ext.as_str())
+        && path
+            .split('/')
+            .rev()
+            .skip(1)
+            .any(|dir| LOCALE_DIRS.contains(&dir.to_ascii_lowercase().as_str()))
+}
+
+/// The format a rollup can be computed for, if any.
+fn catalog_format(path: &str) -> Option<CatalogFormat> {
+    if !is_translation_file(path) {
+        return None;
+    }
+    match extension(path)?.as_str() {
+        "json" | "arb" => Some(CatalogFormat::Json),
+        "po" | "pot" => Some(CatalogFormat::Po),
+        _ => None,
+    }
+}
+
+/// Whether `path` is a translation catalog in a format we can roll up.
+pub fn supports_rollup(path: &str) -> bool {
+    catalog_format(path).is_some()
+}
+
+/// Whether `s` looks like a locale tag: `en`, `pt-BR`, `zh_Hant`, `es-419`.
+fn is_locale_tag(s: &str) -> bool {
+    let mut parts = s.split(['-', '_']);
+    let Some(lang) = parts.next() else {
+        return false;
+    };
+    (2..=3).contains(&lang.len())
+        && lang.chars().all(|c| c.is_ascii_lowercase())
+        && parts.all(|part| {
+            let mut chars = part.chars();
+            match part.len() {
+                2 => part.chars().all(|c| c.is_ascii_uppercase()),
+                3 => part.chars().all(|c| c.is_ascii_digit()),
+                4 => {
+                    chars.next().is_some_and(|c| c.is_ascii_uppercase())
+                        && chars.all(|c| c.is_ascii_lowercase())
+                }
+                _ => false,
+            }
+        })
+}
+
+/// The locale a catalog belongs to: the directory above `LC_MESSAGES` in a
+/// gettext tree (`de/LC_MESSAGES/app.po`), else the file name (`en.json`,
+/// `messages.de.po`, `app_en.arb`), else the parent directory
+/// (`locales/fr/common.json`).
+pub fn locale_of(path: &str) -> Option<String> {
+    let mut segments = path.rsplit('/');
+    let filename = segments.next()?;
+    let parent = segments.next();
+    if parent == Some("LC_MESSAGES") {
+        return segments
+            .next()
+            .filter(|dir| is_locale_tag(dir))
+            .map(str::to_owned);
+    }
+    let stem = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+    [
+        stem.rsplit('.').next(),
+        stem.split_once('_').map(|(_, tag)| tag),
+        // `po/` holds every locale's catalog.
+        parent.filter(|dir| *dir != "po"),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|tag| is_locale_tag(tag))
+    .map(str::to_owned)
+}
+
+/// Parse a catalog into a flat `key -> value` map. Empty content (a file that
+/// doesn't exist on one side) parses as an empty catalog; `None` means the
+/// format isn't supported or the content is malformed.
+pub fn parse_translation_keys(path: &str, content: &str) -> Option<BTreeMap<String, String>> {
+    let format = catalog_format(path)?;
+    if content.trim().is_empty() {
+        return Some(BTreeMap::new());
+    }
+    match format {
+        CatalogFormat::Json => parse_json(content),
+        CatalogFormat::Po => Some(parse_po(content)),
+    }
+}
+
+fn parse_json(content: &str) -> Option<BTreeMap<String, String>> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    if !value.is_object() {
+        return None;
+    }
+    let mut keys = BTreeMap::new();
+    flatten_json("", &value, &mut keys);
+    Some(keys)
+}
+
+/// Flatten nested objects to dotted keys and arrays to `key[i]`.
+fn flatten_json(prefix: &str, value: &serde_json::Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, child) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{prefix}.{key}")
+                };
+                flatten_json(&path, child, out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                flatten_json(&format!("{prefix}[{i}]"), child, out);
+            }
+        }
+        serde_json::Value::String(s) => {
+            out.insert(prefix.to_owned(), s.clone());
+        }
+        other => {
+            out.insert(prefix.to_owned(), other.to_string());
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PoField {
+    Context,
+    Id,
+    Plural,
+    Str,
+}
+
+#[derive(Default)]
+struct PoEntry {
+    context: Option<String>,
+    id: Option<String>,
+    value: String,
+    has_str: bool,
+}
+
+impl PoEntry {
+    /// Insert a finished entry, keyed `id` or `[context] id`. The header entry
+    /// (empty msgid) isn't a translation.
+    fn finish(self, out: &mut BTreeMap<String, String>) {
+        let Some(id) = self.id.filter(|id| !id.is_empty()) else {
+            return;
+        };
+        let key = match self.context {
+            Some(context) => format!("[{context}] {id}"),
+            None => id,
+        };
+        out.insert(key, self.value);
+    }
+}
+
+/// The body of a PO string literal, escapes left as written.
+fn po_string(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or("")
+}
+
+fn parse_po(content: &str) -> BTreeMap<String, String> {
+    let mut keys = BTreeMap::new();
+    let mut entry = PoEntry::default();
+    let mut field = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        // Comments, including obsolete `#~` entries, carry no translations.
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('"') {
+            let text = po_string(line);
+            match field {
+                Some(PoField::Context) => {
+                    entry.context.get_or_insert_with(String::new).push_str(text);
+                }
+                Some(PoField::Id) => entry.id.get_or_insert_with(String::new).push_str(text),
+                Some(PoField::Str) => entry.value.push_str(text),
+                Some(PoField::Plural) | None => {}
+            }
+            continue;
+        }
+        let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match keyword {
+            "msgctxt" | "msgid" => {
+                // A new entry starts once the previous one has its msgstr.
+                if entry.has_str {
+                    std::mem::take(&mut entry).finish(&mut keys);
+                }
+                if keyword == "msgctxt" {
+                    entry.context = Some(po_string(rest).to_owned());
+                    field = Some(PoField::Context);
+                } else {
+                    entry.id = Some(po_string(rest).to_owned());
+                    field = Some(PoField::Id);
+                }
+            }
+            "msgid_plural" => field = Some(PoField::Plural),
+            _ if keyword.starts_with("msgstr") => {
+                // Plural forms are joined into one value.
+                if entry.has_str {
+                    entry.value.push_str(" | ");
+                }
+                entry.has_str = true;
+                entry.value.push_str(po_string(rest));
+                field = Some(PoField::Str);
+            }
+            _ => field = None,
+        }
+    }
+    if entry.has_str {
+        entry.finish(&mut keys);
+    }
+    keys
+}
+
+/// Build the rollup hunk for a catalog from its old and new content: a summary
+/// line, then a removed `key = old` line and/or an added `key = new` line per
+/// key that changed, in key order. `None` when either side can't be parsed.
+pub fn create_translation_rollup_hunk(
+    file_path: &str,
+    old_content: &str,
+    new_content: &str,
+) -> Option<DiffHunk> {
+    let old = parse_translation_keys(file_path, old_content)?;
+    let new = parse_translation_keys(file_path, new_content)?;
+
+    let (mut added, mut removed, mut changed) = (0, 0, 0);
+    let mut lines = Vec::new();
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    for key in keys {
+        let line = |line_type, value: &str| DiffLine {
+            line_type,
+            content: format!("{key} = {value}"),
+            old_line_number: None,
+            new_line_number: None,
+        };
+        match (old.get(key), new.get(key)) {
+            (Some(before), Some(after)) if before == after => {}
+            (Some(before), Some(after)) => {
+                changed += 1;
+                lines.push(line(LineType::Removed, before));
+                lines.push(line(LineType::Added, after));
+            }
+            (Some(before), None) => {
+                removed += 1;
+                lines.push(line(LineType::Removed, before));
+            }
+            (None, Some(after)) => {
+                added += 1;
+                lines.push(line(LineType::Added, after));
+            }
+            (None, None) => {}
+        }
+    }
+
+    let locale = locale_of(file_path).map_or_else(String::new, |l| format!(" ({l})"));
+    let summary = if lines.is_empty() {
+        format!("(translations{locale}: no key changes)")
+    } else {
+        format!("(translations{locale}: {added} added, {removed} removed, {changed} changed)")
+    };
+
+    let mut content = summary.clone();
+    for line in &lines {
+        let marker = if line.line_type == LineType::Added {
+            '+'
+        } else {
+            '-'
+        };
+        content.push('\n');
+        content.push(marker);
+        content.push_str(&line.content);
+    }
+    let content_hash = compute_content_hash(content.as_bytes());
+    lines.insert(
+        0,
+        DiffLine {
+            line_type: LineType::Context,
+            content: summary,
+            old_line_number: None,
+            new_line_number: None,
+        },
+    );
+
+    Some(DiffHunk {
+        id: format!("{file_path}:{content_hash}"),
+        file_path: file_path.to_owned(),
+        old_start: 0,
+        old_count: 0,
+        new_start: 0,
+        new_count: 0,
+        content,
+        lines,
+        content_hash,
+        move_pair_id: None,
+        merged_ids: Vec::new(),
+        submodule: None,
+        encoding: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_translation_files() {
+        assert!(is_translation_file("locales/en.json"));
+        assert!(is_translation_file("web/src/i18n/fr/common.json"));
+        assert!(is_translation_file("po/de.po"));
+        assert!(is_translation_file("messages.pot"));
+        assert!(is_translation_file("lib/l10n/app_en.arb"));
+        assert!(is_translation_file("config/locales/en.yml"));
+        assert!(!is_translation_file("package.json"));
+        assert!(!is_translation_file("src/locales.ts"));
+        assert!(!supports_rollup("config/locales/en.yml"));
+
+        assert_eq!(locale_of("locales/pt-BR.json").as_deref(), Some("pt-BR"));
+        assert_eq!(locale_of("locales/fr/common.json").as_deref(), Some("fr"));
+        assert_eq!(locale_of("de/LC_MESSAGES/app.po").as_deref(), Some("de"));
+        assert_eq!(
+            locale_of("lib/l10n/app_pt_BR.arb").as_deref(),
+            Some("pt_BR")
+        );
+        assert_eq!(locale_of("po/messages.pot"), None);
+        assert_eq!(locale_of("locales/common.json"), None);
+    }
+
+    #[test]
+    fn parses_nested_json_and_po() {
+        let json = parse_translation_keys(
+            "locales/en.json",
+            r#"{"nav": {"home": "Home", "items": ["One"]}, "count": 3}"#,
+        )
+        .unwrap();
+        assert_eq!(json["nav.home"], "Home");
+        assert_eq!(json["nav.items[0]"], "One");
+        assert_eq!(json["count"], "3");
+        assert!(parse_translation_keys("locales/en.json", "[1, 2]").is_none());
+
+        let po = parse_translation_keys(
+            "po/de.po",
+            "msgid \"\"\nmsgstr \"Content-Type: text/plain\\n\"\n\n\
+             # A comment\nmsgid \"Hello\"\nmsgstr \"Hallo\"\n\n\
+             msgctxt \"menu\"\nmsgid \"Open\"\nmsgstr \"\"\n\"Öffnen\"\n\n\
+             msgid \"file\"\nmsgid_plural \"files\"\nmsgstr[0] \"Datei\"\nmsgstr[1] \"Dateien\"\n",
+        )
+        .unwrap();
+        assert_eq!(po.len(), 3);
+        assert_eq!(po["Hello"], "Hallo");
+        assert_eq!(po["[menu] Open"], "Öffnen");
+        assert_eq!(po["file"], "Datei | Dateien");
+    }
+
+    #[test]
+    fn rollup_lists_key_changes() {
+        let old = r#"{"a": "A", "b": "B", "c": "C"}"#;
+        let new = r#"{"c": "C2", "a": "A", "d": "D"}"#;
+        let hunk = create_translation_rollup_hunk("locales/en.json", old, new).unwrap();
+
+        let lines: Vec<(LineType, &str)> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.line_type.clone(), l.content.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (
+                    LineType::Context,
+                    "(translations (en): 1 added, 1 removed, 1 changed)"
+                ),
+                (LineType::Removed, "b = B"),
+                (LineType::Removed, "c = C"),
+                (LineType::Added, "c = C2"),
+                (LineType::Added, "d = D"),
+            ]
+        );
+        assert_eq!(hunk.id, format!("locales/en.json:{}", hunk.content_hash));
+
+        // Reordering and reformatting alone leave no key changes.
+        let reordered = create_translation_rollup_hunk(
+            "locales/en.json",
+            old,
+            "{\n  \"c\": \"C\",\n  \"b\": \"B\",\n  \"a\": \"A\"\n}\n",
+        )
+        .unwrap();
+        assert_eq!(reordered.lines.len(), 1);
+        assert_eq!(
+            reordered.lines[0].content,
+            "(translations (en): no key changes)"
+        );
+
+        assert!(create_translation_rollup_hunk("locales/en.json", old, "{oops").is_none());
+    }
+}
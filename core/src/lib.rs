@@ -7,6 +7,11 @@
 //! - Claude-based hunk classification (`classify`)
 //! - Git source abstraction (`sources`)
 //! - File path filtering utilities (`filters`)
+//! - Budget guards for giant reviews (`limits`)
+//! - Keychain-backed secret storage (`secrets`)
+//! - Rotating log files (`logs`)
+//! - Opt-in, locally aggregated usage metrics (`metrics`)
+//! - An offline switch for every network path (`offline`)
 //!
 //! Feature flags:
 //! - `cli`: Command-line interface
@@ -16,7 +21,12 @@ pub mod ai;
 pub mod classify;
 pub mod diff;
 pub mod filters;
+pub mod limits;
+pub mod logs;
+pub mod metrics;
+pub mod offline;
 pub mod review;
+pub mod secrets;
 pub mod sources;
 pub mod symbols;
 pub mod trust;
@@ -0,0 +1,147 @@
+//! Budget guards for giant reviews.
+//!
+//! A vendored dependency bump or a regenerated fixture can put hundreds of
+//! megabytes of diff in one comparison. Rather than parse, classify, and
+//! symbol-diff all of it (and hang the UI), the hunk pipeline works within a
+//! [`ReviewLimits`] budget and degrades gracefully when it's exceeded:
+//! oversized files become placeholder hunks, diff past the overall byte budget
+//! is skipped, and hunk lists are truncated. Every degradation is reported as
+//! a [`LimitExceeded`] so callers can tell the user what was left out and offer
+//! the explicit opt-in ([`ReviewLimits::unlimited`]).
+//!
+//! Defaults can be overridden with `REVIEW_MAX_HUNKS`, `REVIEW_MAX_DIFF_BYTES`
+//! and `REVIEW_MAX_FILE_BYTES`.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewLimits {
+    /// Most hunks a review loads.
+    pub max_hunks: usize,
+    /// Most bytes of raw diff parsed for a comparison.
+    pub max_diff_bytes: usize,
+    /// Most bytes of diff (or content) processed for a single file.
+    pub max_file_bytes: usize,
+}
+
+impl Default for ReviewLimits {
+    fn default() -> Self {
+        Self {
+            max_hunks: 20_000,
+            max_diff_bytes: 64 * 1024 * 1024,
+            max_file_bytes: 4 * 1024 * 1024,
+        }
+    }
+}
+
+impl ReviewLimits {
+    /// No limits — the explicit opt-in for loading everything.
+    pub const fn unlimited() -> Self {
+        Self {
+            max_hunks: usize::MAX,
+            max_diff_bytes: usize::MAX,
+            max_file_bytes: usize::MAX,
+        }
+    }
+
+    /// Defaults, overridden by any `REVIEW_MAX_*` environment variables.
+    pub fn from_env() -> Self {
+        fn var(name: &str) -> Option<usize> {
+            std::env::var(name).ok()?.trim().parse().ok()
+        }
+        let defaults = Self::default();
+        Self {
+            max_hunks: var("REVIEW_MAX_HUNKS").unwrap_or(defaults.max_hunks),
+            max_diff_bytes: var("REVIEW_MAX_DIFF_BYTES").unwrap_or(defaults.max_diff_bytes),
+            max_file_bytes: var("REVIEW_MAX_FILE_BYTES").unwrap_or(defaults.max_file_bytes),
+        }
+    }
+
+    /// The budget to use: everything when the caller opted in, otherwise the
+    /// configured defaults.
+    pub fn resolve(unlimited: bool) -> Self {
+        if unlimited {
+            Self::unlimited()
+        } else {
+            Self::from_env()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitKind {
+    Hunks,
+    DiffBytes,
+    FileBytes,
+}
+
+/// How the pipeline degraded when a limit was hit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Degradation {
+    /// Kept the first `limit` items, dropped the rest.
+    Truncated,
+    /// Processed an evenly spread subset.
+    Sampled,
+    /// Left the affected files out; they appear as placeholder hunks.
+    Skipped,
+}
+
+/// One limit that was exceeded, and what was done about it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitExceeded {
+    pub kind: LimitKind,
+    pub limit: usize,
+    /// The observed size (hunk count or bytes; for per-file limits, the
+    /// largest offending file).
+    pub actual: usize,
+    pub degradation: Degradation,
+    /// Files affected, when the degradation is per file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+}
+
+impl LimitExceeded {
+    /// One-line description for logs and CLI warnings.
+    pub fn describe(&self) -> String {
+        let what = match self.kind {
+            LimitKind::Hunks => format!("{} hunks (limit {})", self.actual, self.limit),
+            LimitKind::DiffBytes => format!("{} bytes of diff (limit {})", self.actual, self.limit),
+            LimitKind::FileBytes => format!(
+                "{} file(s) over {} bytes (largest {})",
+                self.files.len(),
+                self.limit,
+                self.actual
+            ),
+        };
+        let action = match self.degradation {
+            Degradation::Truncated => "truncated",
+            Degradation::Sampled => "sampled",
+            Degradation::Skipped => "skipped",
+        };
+        format!("{what}: {action}")
+    }
+}
+
+/// Evenly spread sample of `keep` indices out of `len`, in order.
+pub fn sample_indices(len: usize, keep: usize) -> Vec<usize> {
+    if keep >= len {
+        return (0..len).collect();
+    }
+    (0..keep).map(|i| i * len / keep).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_indices_spreads_evenly() {
+        assert_eq!(sample_indices(10, 5), [0, 2, 4, 6, 8]);
+        assert_eq!(sample_indices(3, 5), [0, 1, 2]);
+        assert!(sample_indices(5, 0).is_empty());
+    }
+}
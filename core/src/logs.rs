@@ -0,0 +1,143 @@
+//! Log files under `~/.review/logs/`, rotated by size.
+//!
+//! ```text
+//! ~/.review/logs/
+//!   app.log                # desktop app (tauri-plugin-log; frontend too in dev)
+//!   cli.log                # `review` CLI invocations
+//!   repos/<repo-id>.log    # per-repo events, e.g. file watcher activity
+//! ```
+//!
+//! A file that would grow past [`MAX_LOG_BYTES`] is rotated to `<name>.1.log`
+//! (shifting older ones up), keeping [`KEEP_ROTATED`] old files.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::review::central::{compute_repo_id, get_central_root, CentralError};
+use crate::review::state::now_iso8601;
+
+/// Size at which a log file is rotated.
+pub const MAX_LOG_BYTES: u64 = 5_000_000;
+/// Rotated files kept per log.
+pub const KEEP_ROTATED: usize = 3;
+
+/// `~/.review/logs/` (respects `$REVIEW_HOME`).
+pub fn logs_dir() -> Result<PathBuf, CentralError> {
+    Ok(get_central_root()?.join("logs"))
+}
+
+/// The log file for one repository (shared by its worktrees).
+pub fn repo_log_path(repo_path: &Path) -> Result<PathBuf, CentralError> {
+    Ok(logs_dir()?
+        .join("repos")
+        .join(format!("{}.log", compute_repo_id(repo_path)?)))
+}
+
+/// `app.log` -> `app.<n>.log`.
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!("{stem}.{n}.log"))
+}
+
+/// Shift `<name>.log` to `<name>.1.log`, `.1` to `.2`, and so on, dropping
+/// the oldest.
+fn rotate(path: &Path) -> io::Result<()> {
+    for n in (1..KEEP_ROTATED).rev() {
+        let from = rotated_path(path, n);
+        if from.exists() {
+            fs::rename(&from, rotated_path(path, n + 1))?;
+        }
+    }
+    fs::rename(path, rotated_path(path, 1))
+}
+
+/// Append a line to a log file, rotating it first if the line would push it
+/// past [`MAX_LOG_BYTES`].
+pub fn append(path: &Path, line: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let len = fs::metadata(path).map_or(0, |m| m.len());
+    if len > 0 && len + line.len() as u64 > MAX_LOG_BYTES {
+        rotate(path)?;
+    }
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line.trim_end_matches('\n'))
+}
+
+/// Append a timestamped message to a repository's log. Failures are ignored;
+/// logging must never break the caller.
+pub fn append_repo_log(repo_path: &Path, message: &str) {
+    if let Ok(path) = repo_log_path(repo_path) {
+        let _ = append(&path, &format!("[{}] {message}", now_iso8601()));
+    }
+}
+
+/// A [`log::Log`] that appends to a file in [`logs_dir`]. The path is
+/// resolved per record, so a `--home` override applied after install is
+/// honored.
+struct FileLogger {
+    file_name: &'static str,
+    level: log::LevelFilter,
+}
+
+impl log::Log for FileLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Ok(dir) = logs_dir() {
+            let line = format!(
+                "[{}] [{}] [{}] {}",
+                now_iso8601(),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+            let _ = append(&dir.join(self.file_name), &line);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Send this process's `log` records at `level` and above to
+/// `~/.review/logs/<file_name>`. Does nothing if a logger is already set.
+pub fn init_file_logger(file_name: &'static str, level: log::LevelFilter) {
+    let logger = Box::new(FileLogger { file_name, level });
+    if log::set_boxed_logger(logger).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+
+    #[test]
+    fn append_rotates_past_the_size_limit() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, _repo) = setup_test();
+        let path = logs_dir().unwrap().join("cli.log");
+        let big = "x".repeat(usize::try_from(MAX_LOG_BYTES).unwrap() - 10);
+
+        for round in 0..=KEEP_ROTATED + 1 {
+            append(&path, &format!("{round}{big}")).unwrap();
+        }
+
+        let first_char = |p: &Path| fs::read_to_string(p).unwrap().chars().next();
+        assert_eq!(first_char(&path), Some('4'));
+        assert_eq!(first_char(&rotated_path(&path, 1)), Some('3'));
+        assert_eq!(first_char(&rotated_path(&path, KEEP_ROTATED)), Some('1'));
+        assert!(!rotated_path(&path, KEEP_ROTATED + 1).exists());
+    }
+}
@@ -79,6 +79,11 @@ pub fn language_for_extension(ext: &str) -> Option<&'static str> {
         .map(|s| s.language)
 }
 
+/// The known language server for a file extension, if any.
+pub fn server_for_extension(ext: &str) -> Option<&'static ServerConfig> {
+    KNOWN_SERVERS.iter().find(|s| s.extensions.contains(&ext))
+}
+
 /// Map a file extension to the LSP language ID (for didOpen).
 /// Returns `"plaintext"` if unknown.
 pub fn language_id_for_extension(ext: &str) -> &'static str {
@@ -148,7 +153,7 @@ pub fn discover_servers(repo_path: &Path) -> Vec<DiscoveredServer> {
 }
 
 /// Check if a command exists on PATH.
-fn command_exists(command: &str) -> bool {
+pub(crate) fn command_exists(command: &str) -> bool {
     let which_cmd = if cfg!(target_os = "windows") {
         "where"
     } else {
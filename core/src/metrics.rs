@@ -0,0 +1,219 @@
+//! Opt-in, anonymous usage metrics: counts of which features get used, kept
+//! on this machine, so maintainers can see what matters without receiving
+//! raw events.
+//!
+//! Nothing is recorded unless the `usageMetricsEnabled` setting is on (it's
+//! separate from Sentry's `sentryEnabled`). [`record`] bumps a named counter
+//! in `usage-metrics.json` under the central root; `review stats --usage`
+//! shows the totals. If `usageMetricsEndpoint` is set, [`submit`] posts the
+//! aggregated counters — with the app version, OS, and the period they
+//! cover, nothing else — and starts a new period.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::review::central::get_central_root;
+use crate::review::state::{iso8601_from_system_time, now_iso8601};
+use crate::service::settings::read_settings;
+
+/// Filename of the counters under the central root.
+pub const METRICS_FILE: &str = "usage-metrics.json";
+
+/// How often [`submit_if_due`] sends a batch.
+pub const SUBMIT_INTERVAL: Duration = Duration::from_hours(7 * 24);
+
+/// Serializes read-modify-write of the counters within the process.
+static METRICS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Counters since the last submit (or since recording started).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageMetrics {
+    /// When this period started (ISO 8601).
+    pub since: String,
+    pub counters: BTreeMap<String, u64>,
+}
+
+/// What [`submit`] sends.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageReport {
+    pub version: &'static str,
+    pub os: &'static str,
+    pub since: String,
+    pub until: String,
+    pub counters: BTreeMap<String, u64>,
+}
+
+fn metrics_path() -> anyhow::Result<PathBuf> {
+    Ok(get_central_root()?.join(METRICS_FILE))
+}
+
+/// Whether the user opted in.
+pub fn is_enabled() -> bool {
+    read_settings().is_ok_and(|file| file.settings.usage_metrics_enabled)
+}
+
+/// Counter names are feature names like `cli.digest` or `menu.refresh`;
+/// anything else (a path, a ref) is squashed so it can't leak through.
+fn counter_name(feature: &str) -> String {
+    feature
+        .chars()
+        .take(64)
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '.' | '_' | '-' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '-',
+        })
+        .collect()
+}
+
+/// The recorded counters.
+pub fn load() -> anyhow::Result<UsageMetrics> {
+    let path = metrics_path()?;
+    if !path.exists() {
+        return Ok(UsageMetrics::default());
+    }
+    let content = std::fs::read_to_string(&path)?;
+    serde_json::from_str(&content).with_context(|| format!("{} is not valid", path.display()))
+}
+
+fn save(metrics: &UsageMetrics) -> anyhow::Result<()> {
+    let path = metrics_path()?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(metrics)?)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+fn increment(metrics: &mut UsageMetrics, feature: &str) {
+    if metrics.since.is_empty() {
+        metrics.since = now_iso8601();
+    }
+    *metrics.counters.entry(counter_name(feature)).or_default() += 1;
+}
+
+/// Count one use of `feature`, if the user opted in. Never fails the caller.
+pub fn record(feature: &str) {
+    if !is_enabled() {
+        return;
+    }
+    let _guard = METRICS_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let result = load().and_then(|mut metrics| {
+        increment(&mut metrics, feature);
+        save(&metrics)
+    });
+    if let Err(e) = result {
+        log::debug!("[metrics] Failed to record {feature}: {e:#}");
+    }
+}
+
+/// Post the counters to `usageMetricsEndpoint` and start a new period.
+pub fn submit() -> anyhow::Result<UsageReport> {
+    let settings = read_settings()?.settings;
+    if !settings.usage_metrics_enabled {
+        anyhow::bail!("Usage metrics are off; set `usageMetricsEnabled` in settings.json");
+    }
+    let endpoint = settings.usage_metrics_endpoint.trim();
+    if endpoint.is_empty() {
+        anyhow::bail!("No endpoint configured; set `usageMetricsEndpoint` in settings.json");
+    }
+    crate::offline::ensure_online("Submitting usage metrics")?;
+
+    let _guard = METRICS_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let metrics = load()?;
+    let report = UsageReport {
+        version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        since: metrics.since,
+        until: now_iso8601(),
+        counters: metrics.counters,
+    };
+
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", "30"])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-", endpoint])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        stdin.write_all(serde_json::to_string(&report)?.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Submitting to {endpoint} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    save(&UsageMetrics {
+        since: report.until.clone(),
+        counters: BTreeMap::new(),
+    })?;
+    log::info!("[metrics] Submitted usage counters to {endpoint}");
+    Ok(report)
+}
+
+/// [`submit`] when an endpoint is configured and the current period is at
+/// least [`SUBMIT_INTERVAL`] old. For calling from a background thread.
+pub fn submit_if_due() {
+    let Ok(file) = read_settings() else { return };
+    let settings = file.settings;
+    if !settings.usage_metrics_enabled
+        || settings.usage_metrics_endpoint.trim().is_empty()
+        || crate::offline::is_offline()
+    {
+        return;
+    }
+    let due = load().is_ok_and(|metrics| {
+        !metrics.counters.is_empty() && period_elapsed(&metrics.since, SystemTime::now())
+    });
+    if due {
+        if let Err(e) = submit() {
+            log::warn!("[metrics] {e:#}");
+        }
+    }
+}
+
+/// Whether a period that started at `since` (ISO 8601, as written by
+/// [`now_iso8601`]) has run for [`SUBMIT_INTERVAL`].
+fn period_elapsed(since: &str, now: SystemTime) -> bool {
+    now.checked_sub(SUBMIT_INTERVAL).is_some_and(|cutoff| {
+        !since.is_empty() && since <= iso8601_from_system_time(cutoff).as_str()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_sanitized_feature_names() {
+        let mut metrics = UsageMetrics::default();
+        increment(&mut metrics, "cli.digest");
+        increment(&mut metrics, "cli.digest");
+        increment(&mut metrics, "menu./Users/ana/secret repo");
+        assert!(!metrics.since.is_empty());
+        assert_eq!(metrics.counters["cli.digest"], 2);
+        assert_eq!(metrics.counters["menu.-users-ana-secret-repo"], 1);
+
+        let now = SystemTime::now();
+        assert!(!period_elapsed(&metrics.since, now));
+        assert!(period_elapsed(&metrics.since, now + SUBMIT_INTERVAL));
+        assert!(!period_elapsed("", now));
+    }
+}
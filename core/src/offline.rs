@@ -0,0 +1,65 @@
+//! Offline mode: one switch that turns off everything that reaches the
+//! network, for air-gapped machines.
+//!
+//! Offline mode is on when `$REVIEW_OFFLINE` is set (what `review --offline`
+//! does for its own process) or the `offlineMode` setting is. While it's on,
+//! Claude, `gh`, Tailscale, the digest mailer, and usage-metrics submission
+//! fail with [`OfflineError`] before starting anything, as do git fetches
+//! from a remote, and the desktop app drops crash reports and skips update
+//! checks. Local review, local git, and the static classifier are unaffected.
+
+use thiserror::Error;
+
+use crate::service::settings::read_settings;
+
+/// Environment variable that forces offline mode (any value but `0`).
+pub const OFFLINE_ENV: &str = "REVIEW_OFFLINE";
+
+/// A network-bound operation refused because offline mode is on.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "Offline mode is on: {0} needs the network (unset {OFFLINE_ENV} or turn off `offlineMode` in settings.json)"
+)]
+pub struct OfflineError(pub String);
+
+/// Whether offline mode is on.
+pub fn is_offline() -> bool {
+    if std::env::var_os(OFFLINE_ENV).is_some_and(|value| value != "0") {
+        return true;
+    }
+    read_settings().is_ok_and(|file| file.settings.offline_mode)
+}
+
+/// Refuse `what` — a short description like "GitHub (`gh`)" — while offline.
+pub fn ensure_online(what: &str) -> Result<(), OfflineError> {
+    if is_offline() {
+        Err(OfflineError(what.to_owned()))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::service::settings::write_settings;
+
+    #[test]
+    fn env_or_setting_turns_offline_mode_on() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, _repo) = setup_test();
+        std::env::remove_var(OFFLINE_ENV);
+        assert!(ensure_online("Claude").is_ok());
+
+        std::env::set_var(OFFLINE_ENV, "1");
+        let err = ensure_online("Claude").unwrap_err();
+        assert!(err.to_string().starts_with("Offline mode is on: Claude"));
+        std::env::set_var(OFFLINE_ENV, "0");
+        assert!(!is_offline());
+        std::env::remove_var(OFFLINE_ENV);
+
+        write_settings(&serde_json::json!({ "offlineMode": true })).unwrap();
+        assert!(is_offline());
+    }
+}
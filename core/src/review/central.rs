@@ -7,10 +7,12 @@
 //! risking durable state:
 //! ```text
 //! ~/.review/
-//!   index.json                        # repo_id -> { path, name, last_accessed }
+//!   index.json                        # repo_id -> { path, name, last_accessed, origin_url },
+//!                                     # plus aliases: old repo_id -> current
 //!   repos/                            # DURABLE — never delete to reclaim space
 //!     <repo-id>/
 //!       repo.json                     # { canonical_path, display_name }
+//!       encrypted.json                # encrypted reviews: ref -> { salt, check }
 //!       reviews/
 //!         <comparison-key>.json       # ReviewState (carries schemaVersion)
 //!   cache/                            # DISPOSABLE — safe to `rm -rf` anytime
@@ -18,19 +20,26 @@
 //!       hunk-cache/<comparison-key>.json
 //!       symbol-cache/<comparison-key>.json
 //!   worktrees/<repo-id>/              # Review-managed git worktrees
+//!   remotes/<url-id>/                 # partial clones of remote-only repos
+//!                                     # (see `crate::sources::remote`)
 //!   settings.json                     # desktop UI preferences
+//!   secrets.json                      # secrets, when there's no keychain (0600)
+//!   logs/                             # rotating logs (see `crate::logs`)
 //! ```
 //!
 //! `repo-id` is a 16-hex hash of the git **common dir**, so a repository and
-//! all of its worktrees share one id (see [`compute_repo_id`]).
+//! all of its worktrees share one id (see [`compute_repo_id`]). Stores left
+//! under the older per-worktree-path ids are folded into it the first time a
+//! process touches the repo.
 
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::{LazyLock, RwLock};
+use std::process::Command;
+use std::sync::{LazyLock, Mutex, PoisonError, RwLock};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -41,6 +50,8 @@ pub enum CentralError {
     Json(#[from] serde_json::Error),
     #[error("Could not determine home directory")]
     Home,
+    #[error("No reviews are stored for {0}")]
+    UnknownRepo(String),
 }
 
 /// A single entry in the repo index.
@@ -50,12 +61,20 @@ pub struct RepoIndexEntry {
     pub path: String,
     pub name: String,
     pub last_accessed: String,
+    /// `remote.origin.url` when registered, to recognize the repo after it
+    /// moves.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_url: Option<String>,
 }
 
 /// The full repo index stored at `~/.review/index.json`.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct RepoIndex {
     pub repos: HashMap<String, RepoIndexEntry>,
+    /// IDs repos had before they moved, mapped to their current ID, so links
+    /// carrying an old ID still resolve (see [`relink_repo`]).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub aliases: HashMap<String, String>,
 }
 
 /// Sanitize a string for use as a filename or directory name.
@@ -155,20 +174,134 @@ pub fn repo_root(repo_path: &Path) -> PathBuf {
 /// one review store). Non-git paths fall back to hashing `<path>/.git`.
 pub fn compute_repo_id(repo_path: &Path) -> Result<String, CentralError> {
     let (_git_dir, common_dir) = resolve_git_dirs(repo_path);
-    let canonical = canonical_path(&common_dir);
+    Ok(path_id(&canonical_path(&common_dir)))
+}
+
+/// 16-hex hash of a canonical path.
+fn path_id(canonical: &Path) -> String {
     let mut hasher = Sha256::new();
     hasher.update(canonical.to_string_lossy().as_bytes());
     let result = hasher.finalize();
-    Ok(hex::encode(&result[..8])) // 8 bytes = 16 hex chars
+    hex::encode(&result[..8]) // 8 bytes = 16 hex chars
 }
 
+/// Storage dirs already checked for legacy data by this process.
+static MIGRATED_STORES: LazyLock<Mutex<HashSet<PathBuf>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
 /// Get the **durable** storage directory for a specific repo
 /// (`~/.review/repos/<repo-id>/`): review state and `repo.json`. This is the
 /// precious tier — never delete it to reclaim space.
 pub fn get_repo_storage_dir(repo_path: &Path) -> Result<PathBuf, CentralError> {
     let root = get_central_root()?;
     let repo_id = compute_repo_id(repo_path)?;
-    Ok(root.join("repos").join(repo_id))
+    let dir = root.join("repos").join(&repo_id);
+    let first_visit = MIGRATED_STORES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(dir.clone());
+    if first_visit {
+        if let Err(e) = migrate_legacy_storage(repo_path, &root, &repo_id) {
+            log::warn!("[central] failed to migrate legacy review storage: {e}");
+        }
+    }
+    Ok(dir)
+}
+
+/// Repo IDs that earlier versions stored `repo_path`'s reviews under: hashes
+/// of a working-tree path rather than the common git dir, so the main
+/// checkout and every linked worktree each had a store of its own. Also any
+/// indexed repo whose path belongs to this repository under another ID.
+fn legacy_repo_ids(repo_path: &Path, current_id: &str) -> Vec<String> {
+    let root = repo_root(repo_path);
+    let (_git_dir, common_dir) = resolve_git_dirs(repo_path);
+    let mut paths = vec![root.clone(), canonical_path(repo_path)];
+    if let Ok(entries) = fs::read_dir(common_dir.join("worktrees")) {
+        for entry in entries.flatten() {
+            // Each linked worktree's admin dir records the path of its `.git` file.
+            let Ok(gitdir) = fs::read_to_string(entry.path().join("gitdir")) else {
+                continue;
+            };
+            if let Some(worktree) = Path::new(gitdir.trim()).parent() {
+                paths.push(canonical_path(worktree));
+            }
+        }
+    }
+    let mut ids: Vec<String> = paths.iter().map(|p| path_id(p)).collect();
+    if let Ok(index) = load_index() {
+        ids.extend(
+            index
+                .repos
+                .values()
+                .filter(|e| Path::new(&e.path).exists() && repo_root(Path::new(&e.path)) == root)
+                .map(|e| e.repo_id.clone()),
+        );
+    }
+    ids.sort();
+    ids.dedup();
+    ids.retain(|id| id != current_id);
+    ids
+}
+
+/// Move every file under `from` to the same relative path under `to`, unless
+/// `to` already has one (the current store wins). Emptied directories are
+/// removed. Returns how many files moved.
+fn merge_dir(from: &Path, to: &Path) -> io::Result<usize> {
+    let mut moved = 0;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            moved += merge_dir(&entry.path(), &target)?;
+        } else if !target.exists() {
+            fs::create_dir_all(to)?;
+            fs::rename(entry.path(), &target)?;
+            moved += 1;
+        } else {
+            log::warn!(
+                "[central] keeping {} over legacy {}",
+                target.display(),
+                entry.path().display()
+            );
+        }
+    }
+    // Fails harmlessly when something was left behind.
+    let _ = fs::remove_dir(from);
+    Ok(moved)
+}
+
+/// Fold durable stores left under [`legacy_repo_ids`] into `repo_id`'s, so
+/// reviews made from any worktree before storage was shared show up in all
+/// of them, and drop the legacy entries from the index. Where both stores
+/// hold the same file, the current one is kept and the legacy copy left in
+/// place. Returns how many files moved.
+fn migrate_legacy_storage(
+    repo_path: &Path,
+    root: &Path,
+    repo_id: &str,
+) -> Result<usize, CentralError> {
+    let legacy_ids = legacy_repo_ids(repo_path, repo_id);
+    let target = root.join("repos").join(repo_id);
+    let mut moved = 0;
+    for legacy_id in &legacy_ids {
+        let legacy_dir = root.join("repos").join(legacy_id);
+        if legacy_dir.is_dir() {
+            moved += merge_dir(&legacy_dir, &target)?;
+        }
+    }
+    let mut index = load_index()?;
+    let before = index.repos.len();
+    index.repos.retain(|id, _| !legacy_ids.contains(id));
+    if index.repos.len() != before {
+        save_index(&index)?;
+    }
+    if moved > 0 {
+        log::info!(
+            "[central] moved {moved} file(s) from legacy storage into {}",
+            target.display()
+        );
+    }
+    Ok(moved)
 }
 
 /// Get the **disposable** cache directory for a specific repo
@@ -278,10 +411,46 @@ fn save_index(index: &RepoIndex) -> Result<(), CentralError> {
     Ok(())
 }
 
+/// The repo's `remote.origin.url`, if it has one.
+fn origin_url(repo_path: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", "remote.origin.url"])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+    let url = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    (output.status.success() && !url.is_empty()).then_some(url)
+}
+
 /// Register (upsert) a repo in the index and create its storage directory.
+///
+/// A repo registering for the first time whose origin URL matches exactly
+/// one registered repo that's no longer on disk is taken to be that repo,
+/// moved: its reviews are relinked to the new location.
 pub fn register_repo(repo_path: &Path) -> Result<(), CentralError> {
     let repo_id = compute_repo_id(repo_path)?;
     let repo_dir = get_repo_storage_dir(repo_path)?;
+    let mut index = load_index()?;
+    let known_origin = index.repos.get(&repo_id).and_then(|e| e.origin_url.clone());
+    let origin = known_origin.or_else(|| origin_url(repo_path));
+    if let (false, Some(url)) = (index.repos.contains_key(&repo_id), &origin) {
+        let moved: Vec<&RepoIndexEntry> = index
+            .repos
+            .values()
+            .filter(|e| e.origin_url.as_ref() == Some(url) && !Path::new(&e.path).exists())
+            .collect();
+        if let [old] = moved.as_slice() {
+            log::info!(
+                "[central] {} looks like {} moved; relinking its reviews",
+                repo_path.display(),
+                old.path
+            );
+            let old_id = old.repo_id.clone();
+            relink_ids(&get_central_root()?, &old_id, &repo_id)?;
+            index = load_index()?;
+        }
+    }
+
     fs::create_dir_all(repo_dir.join("reviews"))?;
 
     // Register under the repo's main working tree, not the (possibly worktree)
@@ -304,7 +473,6 @@ pub fn register_repo(repo_path: &Path) -> Result<(), CentralError> {
     )?;
 
     // Update the index
-    let mut index = load_index()?;
     index.repos.insert(
         repo_id.clone(),
         RepoIndexEntry {
@@ -312,12 +480,79 @@ pub fn register_repo(repo_path: &Path) -> Result<(), CentralError> {
             path: canonical_str,
             name: display_name,
             last_accessed: now_iso8601(),
+            origin_url: origin,
         },
     );
     save_index(&index)?;
     Ok(())
 }
 
+/// Move `old_id`'s durable store into `new_id`'s, drop its disposable cache
+/// and index entry, and alias it to `new_id`. Returns how many files moved.
+fn relink_ids(root: &Path, old_id: &str, new_id: &str) -> Result<usize, CentralError> {
+    let old_dir = root.join("repos").join(old_id);
+    let moved = if old_dir.is_dir() {
+        merge_dir(&old_dir, &root.join("repos").join(new_id))?
+    } else {
+        0
+    };
+    let _ = fs::remove_dir_all(root.join("cache").join(old_id));
+
+    let mut index = load_index()?;
+    index.repos.remove(old_id);
+    for target in index.aliases.values_mut() {
+        if target == old_id {
+            new_id.clone_into(target);
+        }
+    }
+    index.aliases.insert(old_id.to_owned(), new_id.to_owned());
+    save_index(&index)?;
+    Ok(moved)
+}
+
+/// What [`relink_repo`] did.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Relinked {
+    pub old_id: String,
+    pub new_id: String,
+    /// Files moved into the new location's store.
+    pub moved_files: usize,
+}
+
+/// Reattach the reviews of a repo that used to be at `old_path` (since moved
+/// or renamed) to the repo at `new_path`, and alias its old ID to the new.
+pub fn relink_repo(old_path: &Path, new_path: &Path) -> Result<Relinked, CentralError> {
+    let root = get_central_root()?;
+    let index = load_index()?;
+    let old_id = match index
+        .repos
+        .values()
+        .find(|e| Path::new(&e.path) == old_path)
+    {
+        Some(entry) => entry.repo_id.clone(),
+        None => compute_repo_id(old_path)?,
+    };
+    let new_id = compute_repo_id(new_path)?;
+    if old_id != new_id
+        && !index.repos.contains_key(&old_id)
+        && !root.join("repos").join(&old_id).is_dir()
+    {
+        return Err(CentralError::UnknownRepo(old_path.display().to_string()));
+    }
+    let moved_files = if old_id == new_id {
+        0
+    } else {
+        relink_ids(&root, &old_id, &new_id)?
+    };
+    register_repo(new_path)?;
+    Ok(Relinked {
+        old_id,
+        new_id,
+        moved_files,
+    })
+}
+
 /// List all registered repos from the index.
 pub fn list_registered_repos() -> Result<Vec<RepoIndexEntry>, CentralError> {
     let index = load_index()?;
@@ -327,8 +562,10 @@ pub fn list_registered_repos() -> Result<Vec<RepoIndexEntry>, CentralError> {
 }
 
 /// Look up a single registered repo by id without sorting the full list.
+/// Follows the alias of a repo that has since moved.
 pub fn get_registered_repo(repo_id: &str) -> Result<Option<RepoIndexEntry>, CentralError> {
     let index = load_index()?;
+    let repo_id = index.aliases.get(repo_id).map_or(repo_id, String::as_str);
     Ok(index.repos.get(repo_id).cloned())
 }
 
@@ -347,10 +584,10 @@ pub fn unregister_repo(repo_path: &Path) -> Result<(), CentralError> {
     Ok(())
 }
 
-/// Register a repo only if the given path is a valid git repository.
+/// Register a repo only if the given path is a valid git (or jj) repository.
 /// Returns Ok(true) if registered, Ok(false) if not a git repo.
 pub fn register_repo_if_valid(repo_path: &Path) -> Result<bool, CentralError> {
-    if !repo_path.join(".git").exists() {
+    if !crate::sources::jj::is_repo_root(repo_path) {
         return Ok(false);
     }
     register_repo(repo_path)?;
@@ -426,6 +663,101 @@ pub(crate) mod tests {
         assert_eq!(repo_root(main.path()), main.path().canonicalize().unwrap());
     }
 
+    #[test]
+    fn test_legacy_per_worktree_stores_are_merged() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, review_home, main) = setup_test();
+        let wt_gitdir = main.path().join(".git").join("worktrees").join("wt");
+        fs::create_dir_all(&wt_gitdir).unwrap();
+        fs::write(wt_gitdir.join("commondir"), "../..\n").unwrap();
+        let worktree = TempDir::new().unwrap();
+        fs::write(
+            worktree.path().join(".git"),
+            format!("gitdir: {}\n", wt_gitdir.display()),
+        )
+        .unwrap();
+        fs::write(
+            wt_gitdir.join("gitdir"),
+            format!("{}\n", worktree.path().join(".git").display()),
+        )
+        .unwrap();
+
+        // Earlier versions keyed each checkout's store by its own path.
+        let repos = review_home.path().join("repos");
+        let main_legacy = repos.join(path_id(&main.path().canonicalize().unwrap()));
+        let wt_legacy = repos.join(path_id(&worktree.path().canonicalize().unwrap()));
+        fs::create_dir_all(main_legacy.join("reviews")).unwrap();
+        fs::create_dir_all(wt_legacy.join("reviews")).unwrap();
+        fs::write(main_legacy.join("reviews/main.json"), "{}").unwrap();
+        fs::write(wt_legacy.join("reviews/feat.json"), "{}").unwrap();
+
+        let dir = get_repo_storage_dir(worktree.path()).unwrap();
+        assert_eq!(dir, get_repo_storage_dir(main.path()).unwrap());
+        assert!(dir.join("reviews/main.json").exists());
+        assert!(dir.join("reviews/feat.json").exists());
+        assert!(!main_legacy.exists());
+        assert!(!wt_legacy.exists());
+    }
+
+    #[test]
+    fn test_moved_repos_are_relinked() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _review_home, parent) = setup_test();
+        let git = |dir: &Path, args: &[&str]| {
+            assert!(Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .status()
+                .unwrap()
+                .success());
+        };
+        let create = |name: &str, origin: Option<&str>| {
+            let dir = parent.path().join(name);
+            fs::create_dir(&dir).unwrap();
+            git(&dir, &["init", "-q"]);
+            if let Some(url) = origin {
+                git(&dir, &["remote", "add", "origin", url]);
+            }
+            register_repo(&dir).unwrap();
+            let reviews = get_repo_storage_dir(&dir).unwrap().join("reviews");
+            fs::write(reviews.join("feat.json"), "{}").unwrap();
+            (dir.canonicalize().unwrap(), compute_repo_id(&dir).unwrap())
+        };
+
+        // Same origin, old path gone: relinked on registration.
+        let (old, old_id) = create("a", Some("git@example.com:org/a.git"));
+        let new = parent.path().join("b");
+        fs::rename(&old, &new).unwrap();
+        register_repo(&new).unwrap();
+        let new_dir = get_repo_storage_dir(&new).unwrap();
+        assert!(new_dir.join("reviews/feat.json").exists());
+        assert!(!get_central_root()
+            .unwrap()
+            .join("repos")
+            .join(&old_id)
+            .exists());
+        let entry = get_registered_repo(&old_id).unwrap().unwrap();
+        assert_eq!(Path::new(&entry.path), new.canonicalize().unwrap());
+        assert_eq!(list_registered_repos().unwrap().len(), 1);
+
+        // No origin: relinked by hand.
+        let (old, old_id) = create("c", None);
+        let new = parent.path().join("d");
+        fs::rename(&old, &new).unwrap();
+        register_repo(&new).unwrap();
+        let relinked = relink_repo(&old, &new).unwrap();
+        assert_eq!(relinked.old_id, old_id);
+        assert_eq!(relinked.moved_files, 1);
+        assert!(get_repo_storage_dir(&new)
+            .unwrap()
+            .join("reviews/feat.json")
+            .exists());
+        assert!(matches!(
+            relink_repo(&parent.path().join("never"), &new),
+            Err(CentralError::UnknownRepo(_))
+        ));
+    }
+
     #[test]
     fn test_prune_duplicate_paths_keeps_latest_accessed() {
         let mut index = RepoIndex::default();
@@ -441,6 +773,7 @@ pub(crate) mod tests {
                     path: path.to_string(),
                     name: "x".to_string(),
                     last_accessed: accessed.to_string(),
+                    origin_url: None,
                 },
             );
         }
@@ -0,0 +1,172 @@
+//! Review checklists — repo-defined items ("migrations reviewed", "docs
+//! updated") that every review of the repo carries, each with its own check
+//! state stored on the [`ReviewState`].
+//!
+//! The template lives in the repo at `.review/checklist.md`: every markdown
+//! list item (`- item`, `* item`, `- [ ] item`) becomes a checklist item;
+//! headings and prose are ignored. The template is authoritative for *which*
+//! items exist — [`sync_checklist`] re-seeds a review's list from it, carrying
+//! check state across by item id.
+
+use std::path::Path;
+
+use super::state::{now_iso8601, ChecklistItem, ReviewState, Source};
+
+/// Repo-relative path of the checklist template.
+pub const CHECKLIST_TEMPLATE_PATH: &str = ".review/checklist.md";
+
+/// Parse a checklist template into unchecked items, in file order. Items whose
+/// id collides with an earlier one are skipped.
+pub fn parse_checklist_template(content: &str) -> Vec<ChecklistItem> {
+    let mut items: Vec<ChecklistItem> = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        let Some(rest) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        else {
+            continue;
+        };
+        let text = rest
+            .strip_prefix("[ ] ")
+            .or_else(|| rest.strip_prefix("[x] "))
+            .or_else(|| rest.strip_prefix("[X] "))
+            .unwrap_or(rest)
+            .trim();
+        let id = slugify(text);
+        if id.is_empty() || items.iter().any(|item| item.id == id) {
+            continue;
+        }
+        items.push(ChecklistItem {
+            id,
+            text: text.to_owned(),
+            checked: false,
+            checked_at: None,
+            checked_by: None,
+        });
+    }
+    items
+}
+
+/// Read and parse the repo's checklist template. `None` when the repo has no
+/// template (or it can't be read).
+pub fn load_checklist_template(repo_path: &Path) -> Option<Vec<ChecklistItem>> {
+    let content = std::fs::read_to_string(repo_path.join(CHECKLIST_TEMPLATE_PATH)).ok()?;
+    Some(parse_checklist_template(&content))
+}
+
+/// Re-seed `state.checklist` from `template`: template order and text, with
+/// check state carried over from existing items of the same id. Items dropped
+/// from the template are dropped from the review. Returns whether the list
+/// changed.
+pub fn sync_checklist(state: &mut ReviewState, template: &[ChecklistItem]) -> bool {
+    let synced: Vec<ChecklistItem> = template
+        .iter()
+        .map(|item| {
+            state
+                .checklist
+                .iter()
+                .find(|existing| existing.id == item.id)
+                .map_or_else(
+                    || item.clone(),
+                    |existing| ChecklistItem {
+                        text: item.text.clone(),
+                        ..existing.clone()
+                    },
+                )
+        })
+        .collect();
+    if synced == state.checklist {
+        return false;
+    }
+    state.checklist = synced;
+    true
+}
+
+/// Find a checklist item by id or by 1-based position.
+pub fn find_item<'a>(items: &'a mut [ChecklistItem], key: &str) -> Option<&'a mut ChecklistItem> {
+    if let Some(index) = key.parse::<usize>().ok().filter(|&n| n >= 1) {
+        return items.get_mut(index - 1);
+    }
+    items.iter_mut().find(|item| item.id == key)
+}
+
+/// Check or uncheck `item`. Returns whether anything changed.
+pub fn set_item_checked(item: &mut ChecklistItem, checked: bool, source: Source) -> bool {
+    if item.checked == checked {
+        return false;
+    }
+    item.checked = checked;
+    if checked {
+        item.checked_at = Some(now_iso8601());
+        item.checked_by = Some(source);
+    } else {
+        item.checked_at = None;
+        item.checked_by = None;
+    }
+    true
+}
+
+/// Lowercase ASCII alphanumerics joined by single dashes.
+fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE: &str = "# Release checklist\n\nSome prose.\n\n- [ ] Migrations reviewed\n* Docs updated\n  - [x] CHANGELOG entry\n- Docs updated!\n";
+
+    #[test]
+    fn parse_template_reads_list_items_only() {
+        let items = parse_checklist_template(TEMPLATE);
+        let ids: Vec<_> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["migrations-reviewed", "docs-updated", "changelog-entry"]
+        );
+        assert_eq!(items[0].text, "Migrations reviewed");
+        assert!(items.iter().all(|i| !i.checked));
+    }
+
+    #[test]
+    fn sync_carries_check_state_and_follows_template() {
+        let mut state = ReviewState::new("feature", None);
+        let template = parse_checklist_template(TEMPLATE);
+        assert!(sync_checklist(&mut state, &template));
+        let item = find_item(&mut state.checklist, "2").unwrap();
+        assert!(set_item_checked(item, true, Source::Cli));
+
+        let reordered = parse_checklist_template("- Docs updated\n- New item\n");
+        assert!(sync_checklist(&mut state, &reordered));
+        assert_eq!(state.checklist.len(), 2);
+        assert!(state.checklist[0].checked);
+        assert_eq!(state.checklist[0].checked_by, Some(Source::Cli));
+        assert!(!state.checklist[1].checked);
+        assert!(!sync_checklist(&mut state, &reordered));
+    }
+
+    #[test]
+    fn find_item_by_id_or_position() {
+        let mut items = parse_checklist_template(TEMPLATE);
+        assert_eq!(
+            find_item(&mut items, "docs-updated").unwrap().id,
+            "docs-updated"
+        );
+        assert_eq!(
+            find_item(&mut items, "1").unwrap().id,
+            "migrations-reviewed"
+        );
+        assert!(find_item(&mut items, "0").is_none());
+        assert!(find_item(&mut items, "nope").is_none());
+    }
+}
@@ -0,0 +1,660 @@
+//! Hunk embeddings: a vector per hunk, so a hunk can be matched against the
+//! ones already reviewed — elsewhere in this review or in others.
+//!
+//! The embedding is local and needs no model download or network: a hashed
+//! bag of the changed lines' tokens and token pairs (feature hashing), kept
+//! apart by side (added vs removed) and L2-normalized, so cosine similarity
+//! compares what a change does rather than where it sits. Stored quantized to
+//! `i8` in `embeddings.json` in the repo's durable storage dir, one entry per
+//! indexed hunk. Decisions aren't copied into the index; they're read from the
+//! reviews when queried, so the index never disagrees with them.
+//!
+//! An exact match — the same lines added and removed in the same file — is a
+//! change already reviewed, e.g. rebased or cherry-picked onto another branch,
+//! and its approval is suggested on the hunk ([`carry_identical_approvals`]).
+//! Near matches are only listed ([`suggest_approvals`]).
+//!
+//! Encrypted reviews aren't indexed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::central;
+use super::encryption;
+use super::state::{Attributed, HunkState, HunkStatus, ReviewState, Source};
+use super::storage::{self, StorageError};
+use crate::classify::classify_repo_hunks_static;
+use crate::diff::parser::{DiffHunk, LineType};
+use crate::service::settings::read_settings;
+use crate::trust::patterns::is_never_trusted;
+
+/// Filename of the index in a repo's durable storage dir.
+pub const EMBEDDINGS_FILE: &str = "embeddings.json";
+
+/// Bumped when [`embed`] or the entry format changes; an index of another
+/// version is rebuilt.
+pub const EMBEDDING_VERSION: u32 = 3;
+
+/// Length of an embedding.
+pub const DIMENSIONS: usize = 256;
+
+/// Similarity at or above which two hunks count as the same change.
+pub const DUPLICATE_SIMILARITY: f32 = 0.97;
+
+/// Similarity at or above which an approved hunk is grounds to suggest
+/// approving another.
+pub const SUGGEST_SIMILARITY: f32 = 0.9;
+
+/// Fewest word tokens a hunk's changed lines need for an approval to carry
+/// over: a lone `}` or `return;` is identical to far too much.
+pub const MIN_CARRY_TOKENS: usize = 3;
+
+/// One indexed hunk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedHunk {
+    /// The review the hunk belongs to.
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub hunk_id: String,
+    /// [`DiffHunk::stable_hash`], to find the hunk's decision after its ID
+    /// drifts, and the same change in another review.
+    pub stable_key: String,
+    pub file_path: String,
+    pub vector: Vec<i8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingIndex {
+    pub version: u32,
+    pub entries: Vec<IndexedHunk>,
+}
+
+impl Default for EmbeddingIndex {
+    fn default() -> Self {
+        Self {
+            version: EMBEDDING_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+/// An indexed hunk matched against a query, with its current decision.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarHunk {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub hunk_id: String,
+    pub file_path: String,
+    pub similarity: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<HunkStatus>,
+}
+
+/// A hunk and the hunks that match it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HunkMatches {
+    pub hunk_id: String,
+    pub matches: Vec<SimilarHunk>,
+}
+
+/// 64-bit FNV-1a: stable across builds, unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// The key an approval of `hunk` carries over by: its
+/// [`DiffHunk::stable_hash`] (path and changed lines). `None` for a
+/// placeholder, whose lines don't say what changed, and for a change of fewer
+/// than [`MIN_CARRY_TOKENS`] words.
+pub fn change_key(hunk: &DiffHunk) -> Option<String> {
+    if hunk.is_placeholder() {
+        return None;
+    }
+    let words = hunk
+        .lines
+        .iter()
+        .filter(|line| line.line_type != LineType::Context)
+        .flat_map(|line| tokens(&line.content))
+        .filter(|token| token.chars().any(char::is_alphanumeric))
+        .count();
+    (words >= MIN_CARRY_TOKENS).then(|| hunk.stable_hash())
+}
+
+/// Identifier, number, and punctuation-run tokens of `line`, lowercased.
+fn tokens(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut word = false;
+    for c in line.chars() {
+        if c.is_whitespace() {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let is_word = c.is_alphanumeric() || c == '_';
+        if !current.is_empty() && is_word != word {
+            tokens.push(std::mem::take(&mut current));
+        }
+        word = is_word;
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// The embedding of `hunk`'s changed lines, or `None` when it has none.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    reason = "hash buckets are below DIMENSIONS; counts and quantized values are small"
+)]
+pub fn embed(hunk: &DiffHunk) -> Option<Vec<i8>> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for line in &hunk.lines {
+        let side = match line.line_type {
+            LineType::Added => '+',
+            LineType::Removed => '-',
+            LineType::Context => continue,
+        };
+        let line_tokens = tokens(&line.content);
+        for token in &line_tokens {
+            *counts.entry(format!("{side}{token}")).or_default() += 1;
+        }
+        for pair in line_tokens.windows(2) {
+            *counts
+                .entry(format!("{side}{} {}", pair[0], pair[1]))
+                .or_default() += 1;
+        }
+    }
+    if counts.is_empty() {
+        return None;
+    }
+    if let Some((_, ext)) = hunk.file_path.rsplit_once('.') {
+        counts.insert(format!("ext:{ext}"), 1);
+    }
+
+    let mut vector = [0f32; DIMENSIONS];
+    for (feature, count) in counts {
+        let hash = fnv1a(feature.as_bytes());
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[(hash % DIMENSIONS as u64) as usize] += sign * (1.0 + (count as f32).ln());
+    }
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return None;
+    }
+    Some(
+        vector
+            .iter()
+            .map(|v| (v / norm * 127.0).round() as i8)
+            .collect(),
+    )
+}
+
+/// Cosine similarity of two quantized embeddings.
+pub fn similarity(a: &[i8], b: &[i8]) -> f32 {
+    let (mut dot, mut norm_a, mut norm_b) = (0i32, 0i32, 0i32);
+    for (&x, &y) in a.iter().zip(b) {
+        let (x, y) = (i32::from(x), i32::from(y));
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0 || norm_b == 0 {
+        return 0.0;
+    }
+    #[allow(
+        clippy::cast_precision_loss,
+        reason = "sums of DIMENSIONS products of i8s are exact in f64"
+    )]
+    let cosine = f64::from(dot) / (f64::from(norm_a).sqrt() * f64::from(norm_b).sqrt());
+    #[allow(clippy::cast_possible_truncation, reason = "a cosine fits in f32")]
+    let cosine = cosine as f32;
+    cosine
+}
+
+fn index_path(repo_path: &Path) -> Result<PathBuf, StorageError> {
+    Ok(central::get_repo_storage_dir(repo_path)?.join(EMBEDDINGS_FILE))
+}
+
+/// Load the repo's index; empty if nothing has been indexed, or if it was
+/// built by another [`EMBEDDING_VERSION`].
+pub fn load_index(repo_path: &Path) -> Result<EmbeddingIndex, StorageError> {
+    let path = index_path(repo_path)?;
+    if !path.exists() {
+        return Ok(EmbeddingIndex::default());
+    }
+    #[derive(Deserialize)]
+    struct Version {
+        version: u32,
+    }
+    let json = fs::read_to_string(&path)?;
+    // Checked first: an index of another version may not parse as this one.
+    if serde_json::from_str::<Version>(&json)?.version != EMBEDDING_VERSION {
+        return Ok(EmbeddingIndex::default());
+    }
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Save the repo's index (atomic: write tmp + rename).
+pub fn save_index(repo_path: &Path, index: &EmbeddingIndex) -> Result<(), StorageError> {
+    let path = index_path(repo_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_string(index)?)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+/// Replace review `ref_name`'s entries in the index with `hunks`. Returns how
+/// many hunks were indexed; none for an encrypted review.
+pub fn index_review(
+    repo_path: &Path,
+    ref_name: &str,
+    hunks: &[DiffHunk],
+) -> Result<usize, StorageError> {
+    if encryption::is_encrypted(repo_path, ref_name).unwrap_or(true) {
+        return Ok(0);
+    }
+    let mut index = load_index(repo_path)?;
+    index.entries.retain(|e| e.ref_name != ref_name);
+    let before = index.entries.len();
+    index.entries.extend(hunks.iter().filter_map(|hunk| {
+        Some(IndexedHunk {
+            ref_name: ref_name.to_owned(),
+            hunk_id: hunk.id.clone(),
+            stable_key: hunk.stable_hash(),
+            file_path: hunk.file_path.clone(),
+            vector: embed(hunk)?,
+        })
+    }));
+    let indexed = index.entries.len() - before;
+    save_index(repo_path, &index)?;
+    Ok(indexed)
+}
+
+/// Reviews' states, loaded once each as queries need them. A review that
+/// can't be loaded (deleted, locked) is `None`, and its entries are skipped.
+struct Decisions<'a> {
+    repo_path: &'a Path,
+    states: HashMap<String, Option<ReviewState>>,
+}
+
+impl<'a> Decisions<'a> {
+    fn new(repo_path: &'a Path) -> Self {
+        Self {
+            repo_path,
+            states: HashMap::new(),
+        }
+    }
+
+    /// The state of the review `entry` belongs to, if it can be loaded.
+    fn state_of(&mut self, entry: &IndexedHunk) -> Option<&ReviewState> {
+        self.states
+            .entry(entry.ref_name.clone())
+            .or_insert_with(|| storage::load_review_state(self.repo_path, &entry.ref_name).ok())
+            .as_ref()
+    }
+}
+
+/// `entry`'s decision in its review's `state`.
+fn decision_in<'s>(
+    state: &'s ReviewState,
+    entry: &IndexedHunk,
+) -> Option<&'s Attributed<HunkStatus>> {
+    let hunk_state = state.hunks.get(&entry.hunk_id).or_else(|| {
+        state
+            .hunks
+            .values()
+            .find(|h| h.stable_key.as_deref() == Some(entry.stable_key.as_str()))
+    });
+    hunk_state.and_then(|h| h.status.as_ref())
+}
+
+fn status_in(state: &ReviewState, entry: &IndexedHunk) -> Option<HunkStatus> {
+    decision_in(state, entry).map(|d| d.value.clone())
+}
+
+/// The indexed hunks most like `hunk` (itself excluded), best first, at
+/// least `min_similarity` alike. With `other_reviews_of` set, only hunks from
+/// reviews other than that one.
+fn matches(
+    index: &EmbeddingIndex,
+    decisions: &mut Decisions<'_>,
+    hunk: &DiffHunk,
+    other_reviews_of: Option<&str>,
+    min_similarity: f32,
+) -> Vec<SimilarHunk> {
+    let Some(vector) = embed(hunk) else {
+        return Vec::new();
+    };
+    let mut found: Vec<SimilarHunk> = index
+        .entries
+        .iter()
+        .filter(|e| e.hunk_id != hunk.id && other_reviews_of != Some(e.ref_name.as_str()))
+        .filter_map(|entry| {
+            let similarity = similarity(&vector, &entry.vector);
+            if similarity < min_similarity {
+                return None;
+            }
+            let status = status_in(decisions.state_of(entry)?, entry);
+            Some(SimilarHunk {
+                ref_name: entry.ref_name.clone(),
+                hunk_id: entry.hunk_id.clone(),
+                file_path: entry.file_path.clone(),
+                similarity,
+                status,
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    found
+}
+
+/// The indexed hunks most like `hunk`, best first. With `reviewed_only`, only
+/// ones with a decision.
+pub fn find_similar(
+    repo_path: &Path,
+    hunk: &DiffHunk,
+    limit: usize,
+    reviewed_only: bool,
+) -> Result<Vec<SimilarHunk>, StorageError> {
+    let index = load_index(repo_path)?;
+    let mut decisions = Decisions::new(repo_path);
+    let mut found = matches(&index, &mut decisions, hunk, None, 0.0);
+    if reviewed_only {
+        found.retain(|m| m.status.is_some());
+    }
+    found.truncate(limit);
+    Ok(found)
+}
+
+/// Hunks of review `ref_name` that other reviews contain too (at least
+/// [`DUPLICATE_SIMILARITY`] alike).
+pub fn find_duplicates(
+    repo_path: &Path,
+    ref_name: &str,
+    hunks: &[DiffHunk],
+) -> Result<Vec<HunkMatches>, StorageError> {
+    let index = load_index(repo_path)?;
+    let mut decisions = Decisions::new(repo_path);
+    Ok(hunks
+        .iter()
+        .map(|hunk| HunkMatches {
+            hunk_id: hunk.id.clone(),
+            matches: matches(
+                &index,
+                &mut decisions,
+                hunk,
+                Some(ref_name),
+                DUPLICATE_SIMILARITY,
+            ),
+        })
+        .filter(|m| !m.matches.is_empty())
+        .collect())
+}
+
+/// Undecided hunks of review `ref_name` that closely match an approved hunk
+/// (at least [`SUGGEST_SIMILARITY`] alike) and no rejected one — candidates
+/// to approve. Each lists the approved matches.
+pub fn suggest_approvals(
+    repo_path: &Path,
+    ref_name: &str,
+    hunks: &[DiffHunk],
+) -> Result<Vec<HunkMatches>, StorageError> {
+    let index = load_index(repo_path)?;
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let mut decisions = Decisions::new(repo_path);
+    Ok(hunks
+        .iter()
+        .filter(|hunk| state.hunks.get(&hunk.id).is_none_or(|h| h.status.is_none()))
+        .filter_map(|hunk| {
+            let found = matches(&index, &mut decisions, hunk, None, SUGGEST_SIMILARITY);
+            if found.iter().any(|m| m.status == Some(HunkStatus::Rejected)) {
+                return None;
+            }
+            let approved: Vec<SimilarHunk> = found
+                .into_iter()
+                .filter(|m| m.status == Some(HunkStatus::Approved))
+                .collect();
+            (!approved.is_empty()).then(|| HunkMatches {
+                hunk_id: hunk.id.clone(),
+                matches: approved,
+            })
+        })
+        .collect())
+}
+
+/// Suggest approving the undecided hunks of review `ref_name` (in `state`)
+/// whose exact change — same file, same lines — another review approved and
+/// none rejected: a rebase or cherry-pick of work already reviewed. The
+/// suggestion is the hunk's `recommendation`, with [`Source::Carried`] and
+/// reasoning naming the hunk and review it came from; the hunk stays
+/// undecided. Hunks without a [`change_key`], and ones carrying a label that
+/// always needs a human ([`is_never_trusted`]), get none. Only when the
+/// `carryIdenticalApprovals` setting is on. Returns how many hunks got one.
+pub fn carry_identical_approvals(
+    repo_path: &Path,
+    ref_name: &str,
+    hunks: &[DiffHunk],
+    state: &mut ReviewState,
+) -> Result<usize, StorageError> {
+    if !read_settings().is_ok_and(|file| file.settings.carry_identical_approvals) {
+        return Ok(0);
+    }
+    let index = load_index(repo_path)?;
+    let mut by_change: HashMap<&str, Vec<&IndexedHunk>> = HashMap::new();
+    for entry in index.entries.iter().filter(|e| e.ref_name != ref_name) {
+        by_change.entry(&entry.stable_key).or_default().push(entry);
+    }
+    if by_change.is_empty() {
+        return Ok(0);
+    }
+
+    let classified = classify_repo_hunks_static(repo_path, hunks);
+    let mut decisions = Decisions::new(repo_path);
+    let mut carried = 0;
+    for hunk in hunks {
+        let current = state.hunks.get(&hunk.id);
+        if current.is_some_and(|h| h.status.is_some() || h.recommendation.is_some()) {
+            continue;
+        }
+        let never_trusted = current
+            .map(HunkState::labels)
+            .unwrap_or_default()
+            .iter()
+            .chain(
+                classified
+                    .classifications
+                    .get(&hunk.id)
+                    .map_or(&[][..], |c| &c.label[..]),
+            )
+            .any(|label| is_never_trusted(label));
+        if never_trusted {
+            continue;
+        }
+        let Some(entries) = change_key(hunk).and_then(|key| by_change.get(key.as_str())) else {
+            continue;
+        };
+        let mut found: Vec<(&IndexedHunk, Attributed<HunkStatus>)> = Vec::new();
+        for &entry in entries {
+            if let Some(decision) = decisions
+                .state_of(entry)
+                .and_then(|s| decision_in(s, entry))
+            {
+                found.push((entry, decision.clone()));
+            }
+        }
+        if found.iter().any(|(_, d)| d.value == HunkStatus::Rejected) {
+            continue;
+        }
+        let Some((origin, approval)) = found
+            .into_iter()
+            .filter(|(_, d)| d.value == HunkStatus::Approved)
+            .max_by(|a, b| a.1.at.cmp(&b.1.at))
+        else {
+            continue;
+        };
+        let when = approval
+            .at
+            .as_deref()
+            .and_then(|at| at.get(..10))
+            .map(|day| format!(" on {day}"))
+            .unwrap_or_default();
+        let hunk_state = state.hunks.entry(hunk.id.clone()).or_default();
+        hunk_state.recommendation = Some(Attributed {
+            value: HunkStatus::Approved,
+            source: Source::Carried,
+            reasoning: Some(format!(
+                "Identical to {} approved in {}{when}",
+                origin.hunk_id, origin.ref_name
+            )),
+            at: None,
+        });
+        hunk_state.stable_key = Some(hunk.stable_hash());
+        carried += 1;
+    }
+    Ok(carried)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::create_untracked_hunk;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::review::state::HunkState;
+    use crate::service::settings::write_settings;
+
+    fn hunk(id: &str, path: &str, added: &[&str]) -> DiffHunk {
+        DiffHunk::test(path, added.iter().map(|line| (LineType::Added, *line))).with_id(id)
+    }
+
+    #[test]
+    fn similar_hunks_are_found_across_reviews() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let repo = repo.path();
+
+        let bump = hunk("a/Cargo.toml:1", "a/Cargo.toml", &["serde = \"1.0.200\""]);
+        let same_bump = hunk("b/Cargo.toml:1", "b/Cargo.toml", &["serde = \"1.0.200\""]);
+        let logic = hunk(
+            "src/cart.rs:1",
+            "src/cart.rs",
+            &["if total > limit { return Err(Error::OverLimit); }"],
+        );
+        assert!(similarity(&embed(&bump).unwrap(), &embed(&same_bump).unwrap()) > 0.99);
+        assert!(similarity(&embed(&bump).unwrap(), &embed(&logic).unwrap()) < 0.5);
+
+        let mut first = ReviewState::new("first", None);
+        first.hunks.insert(
+            bump.id.clone(),
+            HunkState {
+                status: Some(Attributed::new(HunkStatus::Approved, Source::Cli)),
+                ..HunkState::default()
+            },
+        );
+        storage::save_review_state(repo, &first).unwrap();
+        storage::save_review_state(repo, &ReviewState::new("second", None)).unwrap();
+        assert_eq!(
+            index_review(repo, "first", std::slice::from_ref(&bump)).unwrap(),
+            1
+        );
+        assert_eq!(
+            index_review(repo, "second", &[same_bump.clone(), logic.clone()]).unwrap(),
+            2
+        );
+
+        let similar = find_similar(repo, &same_bump, 5, true).unwrap();
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].hunk_id, bump.id);
+        assert_eq!(similar[0].status, Some(HunkStatus::Approved));
+
+        let duplicates =
+            find_duplicates(repo, "second", &[same_bump.clone(), logic.clone()]).unwrap();
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0].hunk_id, same_bump.id);
+
+        let suggested =
+            suggest_approvals(repo, "second", &[same_bump.clone(), logic.clone()]).unwrap();
+        assert_eq!(suggested.len(), 1);
+        assert_eq!(suggested[0].hunk_id, same_bump.id);
+
+        // Re-indexing a review replaces its entries.
+        assert_eq!(index_review(repo, "second", &[same_bump]).unwrap(), 1);
+        assert_eq!(load_index(repo).unwrap().entries.len(), 2);
+    }
+
+    #[test]
+    fn identical_approvals_are_suggested_only_when_safe() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let repo = repo.path();
+        std::fs::create_dir_all(repo.join(".review")).unwrap();
+        std::fs::write(repo.join(".review/sensitive-paths"), "auth/\n").unwrap();
+
+        let check = ["if total > limit { return Err(Error::OverLimit); }"];
+        let approved = [
+            hunk("src/cart.rs:1", "src/cart.rs", &check),
+            hunk("auth/session.rs:1", "auth/session.rs", &check),
+            hunk("src/lib.rs:1", "src/lib.rs", &["}"]),
+        ];
+        let mut first = ReviewState::new("first", None);
+        for h in &approved {
+            first.hunks.insert(
+                h.id.clone(),
+                HunkState {
+                    status: Some(Attributed::new(HunkStatus::Approved, Source::Cli)),
+                    ..HunkState::default()
+                },
+            );
+        }
+        storage::save_review_state(repo, &first).unwrap();
+        index_review(repo, "first", &approved).unwrap();
+
+        // Rebased: the same changes, at other lines.
+        let rebased = [
+            hunk("src/cart.rs:9", "src/cart.rs", &check),
+            hunk("src/order.rs:9", "src/order.rs", &check),
+            hunk("auth/session.rs:9", "auth/session.rs", &check),
+            hunk("src/lib.rs:9", "src/lib.rs", &["}"]),
+        ];
+        let mut second = ReviewState::new("second", None);
+        assert_eq!(
+            carry_identical_approvals(repo, "second", &rebased, &mut second).unwrap(),
+            0,
+            "off by default"
+        );
+
+        write_settings(&serde_json::json!({ "carryIdenticalApprovals": true })).unwrap();
+        let carried = carry_identical_approvals(repo, "second", &rebased, &mut second).unwrap();
+        assert_eq!(carried, 1);
+        let suggested = &second.hunks[&rebased[0].id];
+        assert!(suggested.status.is_none());
+        let recommendation = suggested.recommendation.as_ref().unwrap();
+        assert_eq!(recommendation.value, HunkStatus::Approved);
+        assert_eq!(recommendation.source, Source::Carried);
+        assert_eq!(
+            recommendation.reasoning.as_deref(),
+            Some("Identical to src/cart.rs:1 approved in first")
+        );
+        // Another file, a sensitive area, and a trivial change get nothing.
+        for h in &rebased[1..] {
+            assert!(!second.hunks.contains_key(&h.id), "{}", h.id);
+        }
+        assert_eq!(change_key(&rebased[3]), None);
+        assert_eq!(change_key(&create_untracked_hunk("a.txt", "1", None)), None);
+    }
+}
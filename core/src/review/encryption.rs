@@ -0,0 +1,333 @@
+//! Optional at-rest encryption of individual reviews.
+//!
+//! Encrypting a review seals its state file (live and archived) and the
+//! cached diffs of comparisons whose head is its ref with ChaCha20-Poly1305,
+//! under a key derived from a passphrase with Argon2id and a per-review salt.
+//! A sealed file is [`MAGIC`], the salt, a fresh nonce, then the ciphertext;
+//! [`super::storage`] and [`crate::diff::cache`] open it transparently while
+//! the review is unlocked and refuse (or skip caching) while it is locked.
+//!
+//! Which reviews are sealed, with each one's salt and a passphrase check, is
+//! kept in the repo's `encrypted.json`. Unlocking keeps the derived key in the
+//! OS keychain (see [`crate::secrets`]) under a name derived from the salt, so
+//! the CLI, desktop app, and server share one session until
+//! [`lock_review`]. Where there is no keychain the key falls back to
+//! `secrets.json`, which protects much less: lock reviews when done.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::central;
+use super::storage;
+use crate::secrets::{self, SecretsError};
+
+/// Leading bytes of every sealed file.
+pub const MAGIC: &[u8] = b"REVIEWENC1";
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+#[derive(Error, Debug)]
+pub enum EncryptionError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Central storage error: {0}")]
+    Central(#[from] central::CentralError),
+    #[error("Keychain error: {0}")]
+    Secrets(#[from] SecretsError),
+    #[error("Storage error: {0}")]
+    Storage(Box<storage::StorageError>),
+    #[error("This review is encrypted and locked; run `review encryption unlock`")]
+    Locked,
+    #[error("Wrong passphrase for '{0}'")]
+    WrongPassphrase(String),
+    #[error("Review '{0}' is already encrypted")]
+    AlreadyEncrypted(String),
+    #[error("Review '{0}' is not encrypted")]
+    NotEncrypted(String),
+    #[error("Encrypted file is corrupt or was tampered with")]
+    Corrupt,
+    #[error("Key derivation failed: {0}")]
+    Kdf(String),
+}
+
+impl From<storage::StorageError> for EncryptionError {
+    fn from(e: storage::StorageError) -> Self {
+        Self::Storage(Box::new(e))
+    }
+}
+
+type Result<T> = std::result::Result<T, EncryptionError>;
+
+/// A sealed review's entry in `encrypted.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedReview {
+    /// Hex Argon2 salt, also written into each of its sealed files.
+    salt: String,
+    /// Hex sealing of an empty message, to check a passphrase against.
+    check: String,
+}
+
+fn manifest_path(repo_path: &Path) -> Result<PathBuf> {
+    Ok(central::get_repo_storage_dir(repo_path)?.join("encrypted.json"))
+}
+
+fn read_manifest(repo_path: &Path) -> Result<BTreeMap<String, SealedReview>> {
+    match fs::read_to_string(manifest_path(repo_path)?) {
+        Ok(json) => Ok(serde_json::from_str(&json)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn write_manifest(repo_path: &Path, manifest: &BTreeMap<String, SealedReview>) -> Result<()> {
+    let path = manifest_path(repo_path)?;
+    if manifest.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        return Ok(());
+    }
+    fs::write(path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| EncryptionError::Kdf(e.to_string()))?;
+    Ok(key)
+}
+
+/// Keychain entry holding the unlocked key for `salt`.
+fn secret_name(salt: &[u8]) -> String {
+    format!("review-key:{}", hex::encode(salt))
+}
+
+fn seal_with(key: &[u8; 32], salt: &[u8], plaintext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = ChaCha20Poly1305::new(Key::from_slice(key))
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| EncryptionError::Corrupt)?;
+    let mut sealed = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(MAGIC);
+    sealed.extend_from_slice(salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Split a sealed file into its salt, nonce, and ciphertext.
+fn split_sealed(sealed: &[u8]) -> Result<(&[u8], &[u8], &[u8])> {
+    let body = sealed
+        .strip_prefix(MAGIC)
+        .filter(|body| body.len() >= SALT_LEN + NONCE_LEN)
+        .ok_or(EncryptionError::Corrupt)?;
+    let (salt, rest) = body.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+    Ok((salt, nonce, ciphertext))
+}
+
+fn open_with(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>> {
+    let (_, nonce, ciphertext) = split_sealed(sealed)?;
+    ChaCha20Poly1305::new(Key::from_slice(key))
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| EncryptionError::Corrupt)
+}
+
+/// The unlocked key for `salt`, if this session has one.
+fn session_key(salt: &[u8]) -> Result<Option<[u8; 32]>> {
+    Ok(secrets::get_secret(&secret_name(salt))?
+        .and_then(|key| hex::decode(key.trim()).ok())
+        .and_then(|key| key.try_into().ok()))
+}
+
+/// Whether `bytes` is a sealed file.
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// Decrypt `bytes` if it's sealed, or return it as is. Fails with
+/// [`EncryptionError::Locked`] when its review isn't unlocked.
+pub fn open(bytes: Vec<u8>) -> Result<Vec<u8>> {
+    if !is_sealed(&bytes) {
+        return Ok(bytes);
+    }
+    let (salt, ..) = split_sealed(&bytes)?;
+    let key = session_key(salt)?.ok_or(EncryptionError::Locked)?;
+    open_with(&key, &bytes)
+}
+
+/// Seal `plaintext` for review `ref_name` if it's encrypted, or return it as
+/// is. Fails with [`EncryptionError::Locked`] rather than writing plaintext
+/// when the review is encrypted but locked.
+pub fn seal_for(repo_path: &Path, ref_name: &str, plaintext: Vec<u8>) -> Result<Vec<u8>> {
+    let Some(entry) = read_manifest(repo_path)?.remove(ref_name) else {
+        return Ok(plaintext);
+    };
+    let salt = hex::decode(&entry.salt).map_err(|_| EncryptionError::Corrupt)?;
+    let key = session_key(&salt)?.ok_or(EncryptionError::Locked)?;
+    seal_with(&key, &salt, &plaintext)
+}
+
+/// Whether review `ref_name` is encrypted.
+pub fn is_encrypted(repo_path: &Path, ref_name: &str) -> Result<bool> {
+    Ok(read_manifest(repo_path)?.contains_key(ref_name))
+}
+
+/// A review's encryption status, for `review encryption status`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedReview {
+    pub ref_name: String,
+    pub unlocked: bool,
+}
+
+/// Every encrypted review in the repo and whether it's unlocked.
+pub fn encrypted_reviews(repo_path: &Path) -> Result<Vec<EncryptedReview>> {
+    read_manifest(repo_path)?
+        .into_iter()
+        .map(|(ref_name, entry)| {
+            let salt = hex::decode(&entry.salt).map_err(|_| EncryptionError::Corrupt)?;
+            Ok(EncryptedReview {
+                ref_name,
+                unlocked: session_key(&salt)?.is_some(),
+            })
+        })
+        .collect()
+}
+
+/// Check `passphrase` against review `ref_name` and return its salt and key.
+fn verify(
+    manifest: &BTreeMap<String, SealedReview>,
+    ref_name: &str,
+    passphrase: &str,
+) -> Result<(Vec<u8>, [u8; 32])> {
+    let entry = manifest
+        .get(ref_name)
+        .ok_or_else(|| EncryptionError::NotEncrypted(ref_name.to_owned()))?;
+    let salt = hex::decode(&entry.salt).map_err(|_| EncryptionError::Corrupt)?;
+    let check = hex::decode(&entry.check).map_err(|_| EncryptionError::Corrupt)?;
+    let key = derive_key(passphrase, &salt)?;
+    if open_with(&key, &check).is_err() {
+        return Err(EncryptionError::WrongPassphrase(ref_name.to_owned()));
+    }
+    Ok((salt, key))
+}
+
+/// Encrypt review `ref_name` with `passphrase`, leaving it unlocked. Its
+/// files are rewritten sealed and its plaintext cached diffs dropped.
+pub fn encrypt_review(repo_path: &Path, ref_name: &str, passphrase: &str) -> Result<()> {
+    let mut manifest = read_manifest(repo_path)?;
+    if manifest.contains_key(ref_name) {
+        return Err(EncryptionError::AlreadyEncrypted(ref_name.to_owned()));
+    }
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+    secrets::set_secret(&secret_name(&salt), &hex::encode(key))?;
+    manifest.insert(
+        ref_name.to_owned(),
+        SealedReview {
+            salt: hex::encode(salt),
+            check: hex::encode(seal_with(&key, &salt, &[])?),
+        },
+    );
+    write_manifest(repo_path, &manifest)?;
+
+    storage::rewrite_review_files(repo_path, ref_name)?;
+    crate::diff::cache::clear_for_head(repo_path, ref_name)?;
+    Ok(())
+}
+
+/// Decrypt review `ref_name` for good, after checking `passphrase`.
+pub fn decrypt_review(repo_path: &Path, ref_name: &str, passphrase: &str) -> Result<()> {
+    let mut manifest = read_manifest(repo_path)?;
+    let (salt, key) = verify(&manifest, ref_name, passphrase)?;
+    // The key has to be reachable to read the sealed files back.
+    secrets::set_secret(&secret_name(&salt), &hex::encode(key))?;
+    manifest.remove(ref_name);
+    write_manifest(repo_path, &manifest)?;
+
+    storage::rewrite_review_files(repo_path, ref_name)?;
+    crate::diff::cache::clear_for_head(repo_path, ref_name)?;
+    secrets::delete_secret(&secret_name(&salt))?;
+    Ok(())
+}
+
+/// Unlock review `ref_name` for every process until [`lock_review`].
+pub fn unlock_review(repo_path: &Path, ref_name: &str, passphrase: &str) -> Result<()> {
+    let (salt, key) = verify(&read_manifest(repo_path)?, ref_name, passphrase)?;
+    secrets::set_secret(&secret_name(&salt), &hex::encode(key))?;
+    Ok(())
+}
+
+/// Forget review `ref_name`'s key, locking it again.
+pub fn lock_review(repo_path: &Path, ref_name: &str) -> Result<()> {
+    let entry = read_manifest(repo_path)?
+        .remove(ref_name)
+        .ok_or_else(|| EncryptionError::NotEncrypted(ref_name.to_owned()))?;
+    let salt = hex::decode(&entry.salt).map_err(|_| EncryptionError::Corrupt)?;
+    secrets::delete_secret(&secret_name(&salt))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::review::state::ReviewState;
+
+    #[test]
+    fn encrypted_reviews_are_unreadable_while_locked() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        std::env::set_var("REVIEW_SECRETS_BACKEND", "file");
+        let repo = repo.path();
+
+        let mut state = ReviewState::new("secret-feature", None);
+        state.notes = "confidential".to_owned();
+        storage::save_review_state(repo, &state).unwrap();
+        let file = central::get_repo_storage_dir(repo)
+            .unwrap()
+            .join("reviews/secret-feature.json");
+
+        encrypt_review(repo, "secret-feature", "hunter2").unwrap();
+        let on_disk = fs::read(&file).unwrap();
+        assert!(is_sealed(&on_disk));
+        assert!(!String::from_utf8_lossy(&on_disk).contains("confidential"));
+        assert_eq!(
+            storage::load_review_state(repo, "secret-feature")
+                .unwrap()
+                .notes,
+            "confidential"
+        );
+
+        lock_review(repo, "secret-feature").unwrap();
+        assert!(storage::load_review_state(repo, "secret-feature").is_err());
+        assert!(storage::save_review_state(repo, &state).is_err());
+        assert!(matches!(
+            unlock_review(repo, "secret-feature", "wrong"),
+            Err(EncryptionError::WrongPassphrase(_))
+        ));
+        unlock_review(repo, "secret-feature", "hunter2").unwrap();
+        assert!(encrypted_reviews(repo).unwrap()[0].unlocked);
+
+        decrypt_review(repo, "secret-feature", "hunter2").unwrap();
+        assert!(!is_sealed(&fs::read(&file).unwrap()));
+        assert!(encrypted_reviews(repo).unwrap().is_empty());
+        std::env::remove_var("REVIEW_SECRETS_BACKEND");
+    }
+}
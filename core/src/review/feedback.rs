@@ -0,0 +1,321 @@
+//! Label feedback — a log of humans overriding AI-assigned labels, the signal
+//! for how good the AI classification pass is.
+//!
+//! [`super::storage::save_review_state`] diffs every save against the file it
+//! replaces. Each hunk whose [`Source::Ai`] classification was replaced by a
+//! human-set one with different labels becomes a [`LabelCorrection`], appended
+//! to `label-feedback.jsonl` in the repo's durable storage dir. Clearing a
+//! classification outright (as reclassifying does) is not a correction, and
+//! neither is the AI relabeling its own hunks.
+//!
+//! `review stats` aggregates the log ([`correction_stats`]) and exports it as
+//! JSON Lines for prompt tuning.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::central;
+use super::state::{now_iso8601, ReviewState, Source};
+use super::storage::StorageError;
+
+/// Filename of the correction log in a repo's durable storage dir.
+pub const LABEL_FEEDBACK_FILE: &str = "label-feedback.jsonl";
+
+/// A human overriding the labels the AI gave a hunk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelCorrection {
+    /// When the correction was saved (ISO 8601).
+    pub at: String,
+    /// The review it was made in.
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub hunk_id: String,
+    /// The labels the AI assigned.
+    pub ai_labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ai_reasoning: Option<String>,
+    /// The labels the human replaced them with (empty when all were removed).
+    pub corrected_labels: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Who made the correction.
+    pub source: Source,
+}
+
+impl LabelCorrection {
+    /// AI labels the human dropped.
+    pub fn removed_labels(&self) -> impl Iterator<Item = &str> {
+        self.ai_labels
+            .iter()
+            .filter(|l| !self.corrected_labels.contains(l))
+            .map(String::as_str)
+    }
+
+    /// Labels the human added over the AI's.
+    pub fn added_labels(&self) -> impl Iterator<Item = &str> {
+        self.corrected_labels
+            .iter()
+            .filter(|l| !self.ai_labels.contains(l))
+            .map(String::as_str)
+    }
+}
+
+/// Sources that are a person making a decision, as opposed to a classifier or
+/// an import from a forge.
+fn is_human(source: Source) -> bool {
+    matches!(source, Source::Ui | Source::Cli | Source::Agent)
+}
+
+/// The AI label corrections made between two saves of a review, ordered by
+/// hunk ID.
+pub fn label_corrections(before: &ReviewState, after: &ReviewState) -> Vec<LabelCorrection> {
+    let mut corrections: Vec<LabelCorrection> = after
+        .hunks
+        .iter()
+        .filter_map(|(hunk_id, hunk)| {
+            let new = hunk.classification.as_ref()?;
+            let old = before.hunks.get(hunk_id)?.classification.as_ref()?;
+            if old.source != Source::Ai || !is_human(new.source) || old.value == new.value {
+                return None;
+            }
+            Some(LabelCorrection {
+                at: new.at.clone().unwrap_or_else(now_iso8601),
+                ref_name: after.ref_name.clone(),
+                hunk_id: hunk_id.clone(),
+                ai_labels: old.value.clone(),
+                ai_reasoning: old.reasoning.clone(),
+                corrected_labels: new.value.clone(),
+                reasoning: new.reasoning.clone(),
+                source: new.source,
+            })
+        })
+        .collect();
+    corrections.sort_by(|a, b| a.hunk_id.cmp(&b.hunk_id));
+    corrections
+}
+
+fn feedback_path(repo_path: &Path) -> Result<PathBuf, StorageError> {
+    Ok(central::get_repo_storage_dir(repo_path)?.join(LABEL_FEEDBACK_FILE))
+}
+
+/// Append corrections to the repo's correction log.
+pub fn record_corrections(
+    repo_path: &Path,
+    corrections: &[LabelCorrection],
+) -> Result<(), StorageError> {
+    if corrections.is_empty() {
+        return Ok(());
+    }
+    let path = feedback_path(repo_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut lines = String::new();
+    for correction in corrections {
+        lines.push_str(&serde_json::to_string(correction)?);
+        lines.push('\n');
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?
+        .write_all(lines.as_bytes())?;
+    Ok(())
+}
+
+/// Every correction recorded for the repo, oldest first. Lines that don't
+/// parse are skipped.
+pub fn load_corrections(repo_path: &Path) -> Result<Vec<LabelCorrection>, StorageError> {
+    let path = feedback_path(repo_path)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)?;
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(correction) => Some(correction),
+            Err(e) => {
+                log::debug!("[load_corrections] Skipping unreadable line: {e}");
+                None
+            }
+        })
+        .collect())
+}
+
+/// How often one label was corrected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelCorrectionCount {
+    pub label: String,
+    /// Times the AI assigned it and a human removed it.
+    pub removed: usize,
+    /// Times a human added it where the AI hadn't.
+    pub added: usize,
+}
+
+/// Aggregated correction stats for a repo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CorrectionStats {
+    pub corrections: usize,
+    /// Distinct reviews corrections were made in.
+    pub reviews: usize,
+    /// Per-label counts, most corrected first.
+    pub labels: Vec<LabelCorrectionCount>,
+}
+
+/// Aggregate corrections by label.
+pub fn correction_stats(corrections: &[LabelCorrection]) -> CorrectionStats {
+    let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for correction in corrections {
+        for label in correction.removed_labels() {
+            counts.entry(label).or_default().0 += 1;
+        }
+        for label in correction.added_labels() {
+            counts.entry(label).or_default().1 += 1;
+        }
+    }
+    let mut labels: Vec<LabelCorrectionCount> = counts
+        .into_iter()
+        .map(|(label, (removed, added))| LabelCorrectionCount {
+            label: label.to_owned(),
+            removed,
+            added,
+        })
+        .collect();
+    labels.sort_by_key(|c| Reverse(c.removed + c.added));
+    CorrectionStats {
+        corrections: corrections.len(),
+        reviews: corrections
+            .iter()
+            .map(|c| c.ref_name.as_str())
+            .collect::<HashSet<_>>()
+            .len(),
+        labels,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::ENV_LOCK;
+    use crate::review::state::{Attributed, HunkState};
+    use crate::review::storage::save_review_state;
+    use tempfile::TempDir;
+
+    fn classified(labels: &[&str], source: Source) -> HunkState {
+        HunkState {
+            classification: Some(Attributed::new(
+                labels.iter().map(|l| (*l).to_owned()).collect(),
+                source,
+            )),
+            ..Default::default()
+        }
+    }
+
+    fn state_with(hunks: &[(&str, HunkState)]) -> ReviewState {
+        let mut state = ReviewState::new("feature", None);
+        for (id, hunk) in hunks {
+            state.hunks.insert((*id).to_owned(), hunk.clone());
+        }
+        state
+    }
+
+    #[test]
+    fn only_human_overrides_of_ai_labels_are_corrections() {
+        let before = state_with(&[
+            ("a", classified(&["imports:added"], Source::Ai)),
+            ("b", classified(&["formatting:style"], Source::Ai)),
+            ("c", classified(&["comments:added"], Source::Ai)),
+            ("d", classified(&["imports:added"], Source::Static)),
+            ("e", classified(&["types:added"], Source::Ai)),
+        ]);
+        let after = state_with(&[
+            // Changed by a human: a correction.
+            ("a", classified(&["imports:removed"], Source::Ui)),
+            // The AI relabeling its own hunk isn't.
+            ("b", classified(&["formatting:whitespace"], Source::Ai)),
+            // Neither is clearing the classification to reclassify.
+            ("c", HunkState::default()),
+            // Static labels aren't the AI's.
+            ("d", classified(&[], Source::Cli)),
+            // All labels removed: a correction.
+            ("e", classified(&[], Source::Agent)),
+        ]);
+
+        let corrections = label_corrections(&before, &after);
+        let ids: Vec<&str> = corrections.iter().map(|c| c.hunk_id.as_str()).collect();
+        assert_eq!(ids, ["a", "e"]);
+        assert_eq!(corrections[0].ai_labels, ["imports:added"]);
+        assert_eq!(corrections[0].corrected_labels, ["imports:removed"]);
+        assert_eq!(corrections[0].source, Source::Ui);
+        assert!(corrections[1].corrected_labels.is_empty());
+    }
+
+    #[test]
+    fn stats_count_removed_and_added_labels() {
+        let correction = |ref_name: &str, ai: &[&str], corrected: &[&str]| LabelCorrection {
+            at: String::new(),
+            ref_name: ref_name.to_owned(),
+            hunk_id: "a".to_owned(),
+            ai_labels: ai.iter().map(|l| (*l).to_owned()).collect(),
+            ai_reasoning: None,
+            corrected_labels: corrected.iter().map(|l| (*l).to_owned()).collect(),
+            reasoning: None,
+            source: Source::Ui,
+        };
+        let stats = correction_stats(&[
+            correction("one", &["imports:added"], &[]),
+            correction("one", &["imports:added", "x:y"], &["x:y", "types:added"]),
+            correction("two", &["comments:added"], &["types:added"]),
+        ]);
+        assert_eq!(stats.corrections, 3);
+        assert_eq!(stats.reviews, 2);
+        let counts: Vec<(&str, usize, usize)> = stats
+            .labels
+            .iter()
+            .map(|c| (c.label.as_str(), c.removed, c.added))
+            .collect();
+        assert_eq!(
+            counts,
+            [
+                ("imports:added", 2, 0),
+                ("types:added", 0, 2),
+                ("comments:added", 1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn saving_a_review_records_corrections() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let repo = TempDir::new().unwrap();
+        fs::create_dir(repo.path().join(".git")).unwrap();
+        let review_home = TempDir::new().unwrap();
+        std::env::set_var("REVIEW_HOME", review_home.path());
+
+        let mut state = state_with(&[("a", classified(&["imports:added"], Source::Ai))]);
+        save_review_state(repo.path(), &state).unwrap();
+        assert!(load_corrections(repo.path()).unwrap().is_empty());
+
+        state.hunks.insert(
+            "a".to_owned(),
+            classified(&["imports:removed"], Source::Cli),
+        );
+        state.prepare_for_save();
+        save_review_state(repo.path(), &state).unwrap();
+
+        let recorded = load_corrections(repo.path()).unwrap();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].ref_name, "feature");
+        assert_eq!(recorded[0].corrected_labels, ["imports:removed"]);
+    }
+}
@@ -0,0 +1,282 @@
+//! The reviewer inbox — pull requests awaiting the user's review (incoming)
+//! and the user's own pull requests awaiting others (outgoing), each with its
+//! review status and the transitions it has gone through.
+//!
+//! Stored as `inbox.json` in the repo's durable storage dir and refreshed from
+//! GitHub by [`crate::service::pr_sync::sync_inbox`]. A PR that reaches
+//! `merged` or `closed` is kept for the sync that saw it happen, so the change
+//! can be reported, and dropped on the next one.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::central;
+use super::storage::StorageError;
+use crate::sources::github::PrReviewStatus;
+
+/// Filename of the inbox in a repo's durable storage dir.
+pub const INBOX_FILE: &str = "inbox.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InboxDirection {
+    /// Someone requested the user's review.
+    Incoming,
+    /// The user's own pull request.
+    Outgoing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum InboxStatus {
+    Pending,
+    ChangesRequested,
+    Approved,
+    Merged,
+    Closed,
+}
+
+impl InboxStatus {
+    /// Status from a PR's GitHub state and review decision.
+    pub fn from_github(state: &str, review_decision: &str) -> Self {
+        match (state, review_decision) {
+            ("MERGED", _) => Self::Merged,
+            ("CLOSED", _) => Self::Closed,
+            (_, "CHANGES_REQUESTED") => Self::ChangesRequested,
+            (_, "APPROVED") => Self::Approved,
+            _ => Self::Pending,
+        }
+    }
+
+    /// Merged or closed: no further transitions are expected.
+    pub fn is_settled(self) -> bool {
+        matches!(self, Self::Merged | Self::Closed)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::ChangesRequested => "changes requested",
+            Self::Approved => "approved",
+            Self::Merged => "merged",
+            Self::Closed => "closed",
+        }
+    }
+}
+
+/// One status transition of an inbox entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub from: InboxStatus,
+    pub to: InboxStatus,
+    /// When the sync saw the change (ISO 8601).
+    pub at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxEntry {
+    pub number: u32,
+    pub direction: InboxDirection,
+    pub title: String,
+    pub url: String,
+    pub author: String,
+    pub head_ref_name: String,
+    pub base_ref_name: String,
+    pub status: InboxStatus,
+    /// The PR's `updatedAt` as of the last sync.
+    pub updated_at: String,
+    /// When the PR first entered the inbox (ISO 8601).
+    pub added_at: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<StatusChange>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Inbox {
+    /// Ordered by PR number.
+    pub entries: Vec<InboxEntry>,
+}
+
+impl Inbox {
+    pub fn get(&self, number: u32) -> Option<&InboxEntry> {
+        self.entries.iter().find(|e| e.number == number)
+    }
+
+    /// Record `pr`'s current standing, adding it if it's new. Returns the
+    /// status change, if an existing entry's status moved.
+    pub fn apply(
+        &mut self,
+        direction: InboxDirection,
+        pr: &PrReviewStatus,
+        at: &str,
+    ) -> Option<StatusChange> {
+        let status = InboxStatus::from_github(&pr.state, &pr.review_decision);
+        let index = match self.entries.binary_search_by_key(&pr.number, |e| e.number) {
+            Ok(index) => index,
+            Err(index) => {
+                self.entries.insert(
+                    index,
+                    InboxEntry {
+                        number: pr.number,
+                        direction,
+                        title: pr.title.clone(),
+                        url: pr.url.clone(),
+                        author: pr.author.login.clone(),
+                        head_ref_name: pr.head_ref_name.clone(),
+                        base_ref_name: pr.base_ref_name.clone(),
+                        status,
+                        updated_at: pr.updated_at.clone(),
+                        added_at: at.to_owned(),
+                        history: Vec::new(),
+                    },
+                );
+                return None;
+            }
+        };
+
+        let entry = &mut self.entries[index];
+        pr.title.clone_into(&mut entry.title);
+        pr.updated_at.clone_into(&mut entry.updated_at);
+        if entry.status == status {
+            return None;
+        }
+        let change = StatusChange {
+            from: entry.status,
+            to: status,
+            at: at.to_owned(),
+        };
+        entry.status = status;
+        entry.history.push(change.clone());
+        Some(change)
+    }
+
+    /// Drop entries already reported as merged or closed.
+    pub fn drop_settled(&mut self) {
+        self.entries.retain(|e| !e.status.is_settled());
+    }
+}
+
+fn inbox_path(repo_path: &Path) -> Result<PathBuf, StorageError> {
+    Ok(central::get_repo_storage_dir(repo_path)?.join(INBOX_FILE))
+}
+
+/// Load the repo's inbox; empty if it has never been synced.
+pub fn load_inbox(repo_path: &Path) -> Result<Inbox, StorageError> {
+    let path = inbox_path(repo_path)?;
+    if !path.exists() {
+        return Ok(Inbox::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+/// Save the repo's inbox (atomic: write tmp + rename).
+pub fn save_inbox(repo_path: &Path, inbox: &Inbox) -> Result<(), StorageError> {
+    let path = inbox_path(repo_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_string_pretty(inbox)?)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::sources::github::PrAuthor;
+
+    fn pr(number: u32, state: &str, review_decision: &str) -> PrReviewStatus {
+        PrReviewStatus {
+            number,
+            title: format!("PR {number}"),
+            url: format!("https://github.com/o/r/pull/{number}"),
+            author: PrAuthor {
+                login: "octocat".to_owned(),
+            },
+            head_ref_name: format!("feature-{number}"),
+            base_ref_name: "main".to_owned(),
+            state: state.to_owned(),
+            review_decision: review_decision.to_owned(),
+            updated_at: "2026-01-01T00:00:00Z".to_owned(),
+        }
+    }
+
+    #[test]
+    fn status_from_github() {
+        use InboxStatus::*;
+        assert_eq!(InboxStatus::from_github("OPEN", ""), Pending);
+        assert_eq!(InboxStatus::from_github("OPEN", "REVIEW_REQUIRED"), Pending);
+        assert_eq!(
+            InboxStatus::from_github("OPEN", "CHANGES_REQUESTED"),
+            ChangesRequested
+        );
+        assert_eq!(InboxStatus::from_github("OPEN", "APPROVED"), Approved);
+        assert_eq!(InboxStatus::from_github("MERGED", "APPROVED"), Merged);
+        assert_eq!(InboxStatus::from_github("CLOSED", ""), Closed);
+    }
+
+    #[test]
+    fn apply_tracks_transitions() {
+        let mut inbox = Inbox::default();
+        assert_eq!(
+            inbox.apply(InboxDirection::Incoming, &pr(7, "OPEN", ""), "t1"),
+            None
+        );
+        assert_eq!(
+            inbox.apply(InboxDirection::Outgoing, &pr(3, "OPEN", ""), "t1"),
+            None
+        );
+        assert_eq!(
+            inbox.entries.iter().map(|e| e.number).collect::<Vec<_>>(),
+            [3, 7]
+        );
+
+        let change = inbox.apply(
+            InboxDirection::Incoming,
+            &pr(7, "OPEN", "CHANGES_REQUESTED"),
+            "t2",
+        );
+        assert_eq!(
+            change,
+            Some(StatusChange {
+                from: InboxStatus::Pending,
+                to: InboxStatus::ChangesRequested,
+                at: "t2".to_owned(),
+            })
+        );
+        assert_eq!(
+            inbox.apply(
+                InboxDirection::Incoming,
+                &pr(7, "OPEN", "CHANGES_REQUESTED"),
+                "t3"
+            ),
+            None
+        );
+        inbox.apply(InboxDirection::Incoming, &pr(7, "MERGED", "APPROVED"), "t4");
+        let entry = inbox.get(7).unwrap();
+        assert_eq!(entry.status, InboxStatus::Merged);
+        assert_eq!(entry.history.len(), 2);
+        assert_eq!(entry.added_at, "t1");
+
+        inbox.drop_settled();
+        assert!(inbox.get(7).is_none());
+        assert!(inbox.get(3).is_some());
+    }
+
+    #[test]
+    fn inbox_round_trips() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        assert_eq!(load_inbox(repo.path()).unwrap(), Inbox::default());
+
+        let mut inbox = Inbox::default();
+        inbox.apply(InboxDirection::Incoming, &pr(1, "OPEN", ""), "t1");
+        save_inbox(repo.path(), &inbox).unwrap();
+        assert_eq!(load_inbox(repo.path()).unwrap(), inbox);
+    }
+}
@@ -1,4 +1,13 @@
 pub mod central;
+pub mod checklist;
+pub mod embeddings;
+pub mod encryption;
+pub mod feedback;
+pub mod inbox;
 pub mod migrate;
+pub mod permalink;
+pub mod ratchet;
+pub mod repo_config;
+pub mod sessions;
 pub mod state;
 pub mod storage;
@@ -0,0 +1,203 @@
+//! `review://` links to a repo, a review, and optionally one hunk.
+//!
+//! Two forms are understood:
+//! - `review://<repo-id>/<ref>/<hunk-id>` — the permalink form, for notes,
+//!   exported reports, and webhook payloads. Each segment is percent-encoded,
+//!   so a ref like `feature/x` or a hunk ID like `src/lib.rs:ab12cd34` stays a
+//!   single segment. The hunk (and then the ref) may be left off.
+//! - `review://open?repo=&ref=&file=&hunk=` — the query form `review url`
+//!   prints, where `hunk` is the bare content hash. `comparison=` may stand in
+//!   for `ref=`, for links written by hand or by other tools.
+//!
+//! A ref (in either form) or comparison may be spelled `base..ref`, which pins
+//! the review's base; git refnames can't contain `..`, so this is unambiguous.
+//!
+//! Repos are named by [`compute_repo_id`], so a link resolves on any machine
+//! where the repo is registered, wherever it is checked out. The `review` CLI
+//! and the desktop app's deep-link handler both open links through
+//! [`ReviewLink::parse`] and [`ReviewLink::repo_path`].
+
+use std::path::Path;
+
+use super::central::{self, compute_repo_id, CentralError};
+
+/// The URI scheme, with its separator.
+pub const SCHEME: &str = "review://";
+
+/// What a `review://` link points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewLink {
+    pub repo_id: String,
+    pub review_ref: Option<String>,
+    /// Base pinned by a `base..ref` spelling of the ref.
+    pub base_override: Option<String>,
+    pub file: Option<String>,
+    /// The hunk's content hash (the `<hash>` half of its `file:hash` ID).
+    pub hunk_hash: Option<String>,
+}
+
+/// `review://<repo-id>/<ref>` — a permalink to a review.
+pub fn review_permalink(repo_id: &str, review_ref: &str) -> String {
+    format!(
+        "{SCHEME}{}/{}",
+        urlencoding::encode(repo_id),
+        urlencoding::encode(review_ref)
+    )
+}
+
+/// `review://<repo-id>/<ref>/<hunk-id>` — a permalink to one hunk of a review.
+pub fn hunk_permalink(repo_id: &str, review_ref: &str, hunk_id: &str) -> String {
+    format!(
+        "{}/{}",
+        review_permalink(repo_id, review_ref),
+        urlencoding::encode(hunk_id)
+    )
+}
+
+/// [`hunk_permalink`] for the repo at `repo_path`.
+pub fn hunk_permalink_in(
+    repo_path: &Path,
+    review_ref: &str,
+    hunk_id: &str,
+) -> Result<String, CentralError> {
+    Ok(hunk_permalink(
+        &compute_repo_id(repo_path)?,
+        review_ref,
+        hunk_id,
+    ))
+}
+
+fn decode(segment: &str) -> Option<String> {
+    let decoded = urlencoding::decode(segment).ok()?.into_owned();
+    (!decoded.is_empty()).then_some(decoded)
+}
+
+/// Split a link's `base..ref` into `(ref, base)`; an empty side means `HEAD`,
+/// as in `review start`.
+fn split_comparison(spec: String) -> (String, Option<String>) {
+    match spec.split_once("..") {
+        Some((base, head)) => {
+            let or_head = |side: &str| if side.is_empty() { "HEAD" } else { side }.to_owned();
+            (or_head(head), Some(or_head(base)))
+        }
+        None => (spec, None),
+    }
+}
+
+impl ReviewLink {
+    /// Parse either link form. `None` for another scheme, a missing repo id,
+    /// or a hunk ID without its `file:hash` separator.
+    pub fn parse(raw: &str) -> Option<Self> {
+        let rest = raw.trim().strip_prefix(SCHEME)?;
+        if let Some(query) = rest.strip_prefix("open?") {
+            return Self::parse_query(query);
+        }
+
+        let mut segments = rest.trim_end_matches('/').splitn(3, '/');
+        let repo_id = decode(segments.next()?)?;
+        let (review_ref, base_override) = segments
+            .next()
+            .and_then(decode)
+            .map(split_comparison)
+            .unzip();
+        let (file, hunk_hash) = match segments.next().and_then(decode) {
+            Some(hunk_id) => {
+                let (file, hash) = hunk_id.rsplit_once(':')?;
+                (Some(file.to_owned()), Some(hash.to_owned()))
+            }
+            None => (None, None),
+        };
+        Some(Self {
+            repo_id,
+            review_ref,
+            base_override: base_override.flatten(),
+            file,
+            hunk_hash,
+        })
+    }
+
+    fn parse_query(query: &str) -> Option<Self> {
+        let mut link = Self {
+            repo_id: String::new(),
+            review_ref: None,
+            base_override: None,
+            file: None,
+            hunk_hash: None,
+        };
+        for pair in query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = decode(&value.replace('+', " "));
+            match key {
+                "repo" => link.repo_id = value.unwrap_or_default(),
+                "ref" | "comparison" => {
+                    if let Some((review_ref, base)) = value.map(split_comparison) {
+                        link.review_ref = Some(review_ref);
+                        link.base_override = base;
+                    }
+                }
+                "file" => link.file = value,
+                "hunk" => link.hunk_hash = value,
+                _ => {}
+            }
+        }
+        (!link.repo_id.is_empty()).then_some(link)
+    }
+
+    /// Where the link's repo is checked out on this machine, if it's
+    /// registered here.
+    pub fn repo_path(&self) -> Option<String> {
+        central::get_registered_repo(&self.repo_id)
+            .ok()
+            .flatten()
+            .map(|entry| entry.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hunk_permalink_round_trips() {
+        let url = hunk_permalink("abc123", "feature/x", "src/lib.rs:deadbeef");
+        assert_eq!(url, "review://abc123/feature%2Fx/src%2Flib.rs%3Adeadbeef");
+        assert_eq!(
+            ReviewLink::parse(&url),
+            Some(ReviewLink {
+                repo_id: "abc123".to_owned(),
+                review_ref: Some("feature/x".to_owned()),
+                base_override: None,
+                file: Some("src/lib.rs".to_owned()),
+                hunk_hash: Some("deadbeef".to_owned()),
+            })
+        );
+
+        let review = ReviewLink::parse(&review_permalink("abc123", "feature")).unwrap();
+        assert_eq!(review.review_ref.as_deref(), Some("feature"));
+        assert_eq!(review.hunk_hash, None);
+    }
+
+    #[test]
+    fn parses_query_links() {
+        let link = ReviewLink::parse("review://open?repo=abc&ref=main..f%2Fx&file=a.rs&hunk=beef")
+            .unwrap();
+        assert_eq!(link.repo_id, "abc");
+        assert_eq!(link.review_ref.as_deref(), Some("f/x"));
+        assert_eq!(link.base_override.as_deref(), Some("main"));
+        assert_eq!(link.file.as_deref(), Some("a.rs"));
+        assert_eq!(link.hunk_hash.as_deref(), Some("beef"));
+
+        let link = ReviewLink::parse("review://open?repo=abc&comparison=develop..f%2Fx").unwrap();
+        assert_eq!(link.review_ref.as_deref(), Some("f/x"));
+        assert_eq!(link.base_override.as_deref(), Some("develop"));
+        let link = ReviewLink::parse(&review_permalink("abc", "main..")).unwrap();
+        assert_eq!(
+            (link.review_ref.as_deref(), link.base_override.as_deref()),
+            (Some("HEAD"), Some("main"))
+        );
+
+        assert_eq!(ReviewLink::parse("review://open?ref=main"), None);
+        assert_eq!(ReviewLink::parse("https://abc/main"), None);
+        assert_eq!(ReviewLink::parse("review://abc/main/no-hash"), None);
+    }
+}
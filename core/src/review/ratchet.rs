@@ -0,0 +1,74 @@
+//! Review ratchet — incremental adoption of enforced review.
+//!
+//! A repo lists the paths where review is enforced in `.review/ratchet`, one
+//! gitignore-style glob per line (`#` comments and blank lines ignored). When
+//! the file exists, `review check` requires every hunk in a matching file to be
+//! reviewed; hunks elsewhere are reported but don't fail the check. Teams grow
+//! the list a directory at a time until a lone `*` enforces review everywhere.
+//! Without the file, `review check` enforces only the checklist.
+
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+/// Repo-relative path of the ratchet file.
+pub const RATCHET_PATH: &str = ".review/ratchet";
+
+/// The repo's enforced-review globs.
+#[derive(Debug)]
+pub struct ReviewRatchet {
+    matcher: Gitignore,
+}
+
+impl ReviewRatchet {
+    /// Parse a ratchet file. Invalid globs are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut builder = GitignoreBuilder::new("");
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Err(e) = builder.add_line(None, line) {
+                log::warn!("[ratchet] Skipping invalid glob {line:?}: {e}");
+            }
+        }
+        Self {
+            matcher: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+        }
+    }
+
+    /// Read the repo's ratchet. `None` when the repo has none (or it can't be
+    /// read).
+    pub fn load(repo_path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(repo_path.join(RATCHET_PATH)).ok()?;
+        Some(Self::parse(&content))
+    }
+
+    /// Whether review is enforced for `file_path`. A directory glob covers
+    /// everything beneath it; `!pattern` lines carve out exceptions.
+    pub fn covers(&self, file_path: &str) -> bool {
+        self.matcher
+            .matched_path_or_any_parents(file_path, false)
+            .is_ignore()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn covers_listed_paths_only() {
+        let ratchet = ReviewRatchet::parse(
+            "# enforced so far\nsrc/billing/\n*.sql\n\n!src/billing/fixtures/\n",
+        );
+        assert!(ratchet.covers("src/billing/invoice.rs"));
+        assert!(ratchet.covers("db/migrations/001.sql"));
+        assert!(!ratchet.covers("src/billing/fixtures/sample.json"));
+        assert!(!ratchet.covers("src/ui/app.tsx"));
+
+        assert!(ReviewRatchet::parse("*").covers("anything/at/all.rs"));
+        assert!(!ReviewRatchet::parse("# nothing yet\n").covers("src/lib.rs"));
+    }
+}
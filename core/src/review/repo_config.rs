@@ -0,0 +1,253 @@
+//! Per-repo review settings, checked into the repo as `.review.toml`:
+//!
+//! ```toml
+//! # Changed files matching these gitignore-style globs are left out of reviews.
+//! ignore = ["vendor/", "*.pb.go"]
+//!
+//! # Trust patterns new reviews start with, instead of the built-in defaults.
+//! trust = ["imports:*", "formatting:*"]
+//!
+//! # New reviews need a second reviewer's sign-off to pass `review check`
+//! # (see `crate::service::two_person`).
+//! two_person = true
+//!
+//! # Which label wins when a hunk gets contradictory ones, replacing the
+//! # built-in rules (see `crate::classify::precedence`).
+//! [[precedence]]
+//! prefer = "api:*"
+//! over = ["formatting:*", "comments:*"]
+//!
+//! # Rules `review semgrep` runs (none by default). Local paths are
+//! # relative to the repo; registry packs (`p/...`) need the network.
+//! [semgrep]
+//! config = ["p/security-audit", ".semgrep/"]
+//!
+//! # Linters `review lint` runs in the checkout, reporting only what they
+//! # flag on the review's changed lines.
+//! [[lint]]
+//! name = "clippy"
+//! command = "cargo clippy --message-format=json"
+//! format = "clippy"
+//!
+//! # How `review size` builds the base and head, and what it measures.
+//! [build]
+//! command = "npm ci && npm run build"
+//! artifacts = ["dist/**/*.js"]
+//!
+//! # How `review bench` benchmarks the base and head.
+//! [bench]
+//! command = "cargo bench -- --output-format bencher"
+//! format = "bencher"
+//! threshold = 5.0
+//!
+//! # What's kept out of AI prompts (see `crate::ai::redact`). The built-in
+//! # secret detectors run unless `builtin = false`.
+//! [redaction]
+//! patterns = ['internal-(?P<secret>[a-z]+)\.corp']
+//! exclude = ["secrets/", "*.pem"]
+//! scanner = "gitleaks stdin --no-banner --report-format json --report-path -"
+//!
+//! # Where `review gerrit` fetches changes from. `url` (REST API root; end it
+//! # in `/a` for authenticated access via ~/.netrc) is only needed to look
+//! # changes up by Change-Id.
+//! [gerrit]
+//! remote = "origin"
+//! url = "https://gerrit.example.com"
+//! ```
+//!
+//! `review init` writes a starter one from a scan of the repo.
+
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Deserialize;
+
+use crate::classify::precedence::PrecedenceRule;
+use crate::service::bench::BenchFormat;
+use crate::service::ingest::ReportFormat;
+
+/// Repo-relative path of the config file.
+pub const REPO_CONFIG_PATH: &str = ".review.toml";
+
+/// The parsed `.review.toml`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RepoConfig {
+    pub ignore: Vec<String>,
+    /// `None` keeps the built-in default trust list.
+    pub trust: Option<Vec<String>>,
+    /// Every new review requires a second reviewer.
+    pub two_person: bool,
+    /// `None` keeps the built-in label precedence rules.
+    pub precedence: Option<Vec<PrecedenceRule>>,
+    pub semgrep: Option<SemgrepConfig>,
+    pub lint: Vec<LinterConfig>,
+    pub build: Option<BuildConfig>,
+    pub bench: Option<BenchConfig>,
+    pub redaction: Option<RedactionConfig>,
+    pub gerrit: Option<GerritConfig>,
+}
+
+/// The `[gerrit]` table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GerritConfig {
+    /// Git remote the server's `refs/changes/*` are fetched from.
+    pub remote: String,
+    /// REST API root.
+    pub url: Option<String>,
+}
+
+impl Default for GerritConfig {
+    fn default() -> Self {
+        Self {
+            remote: "origin".to_owned(),
+            url: None,
+        }
+    }
+}
+
+/// The `[redaction]` table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Regexes whose matches are redacted — only the `secret` group, if the
+    /// pattern has one.
+    pub patterns: Vec<String>,
+    /// Gitignore-style globs of files never put in a prompt.
+    pub exclude: Vec<String>,
+    /// Whether the built-in secret detectors run.
+    pub builtin: bool,
+    /// Shell command that reads text on stdin and reports the secrets in it,
+    /// as a JSON array of findings with a `Secret` field or one per line.
+    pub scanner: Option<String>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            exclude: Vec::new(),
+            builtin: true,
+            scanner: None,
+        }
+    }
+}
+
+/// The `[bench]` table.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchConfig {
+    /// Shell command, run from the checkout root, that prints benchmark
+    /// results to stdout.
+    pub command: String,
+    pub format: BenchFormat,
+    /// Percent slowdown above which a benchmark is a regression.
+    #[serde(default = "default_bench_threshold")]
+    pub threshold: f64,
+}
+
+fn default_bench_threshold() -> f64 {
+    5.0
+}
+
+/// The `[build]` table.
+#[derive(Debug, Clone, Deserialize)]
+pub struct BuildConfig {
+    /// Shell command, run from the checkout root, that builds the artifacts.
+    pub command: String,
+    /// Globs, relative to the checkout root, of the artifacts to measure.
+    pub artifacts: Vec<String>,
+}
+
+/// One `[[lint]]` entry.
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinterConfig {
+    /// What its findings are recorded under, e.g. `clippy`.
+    pub name: String,
+    /// Shell command, run from the root of the review head's checkout, that
+    /// prints a report to stdout.
+    pub command: String,
+    pub format: ReportFormat,
+}
+
+/// The `[semgrep]` table.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SemgrepConfig {
+    /// `--config` values: registry packs, rule files or directories.
+    pub config: Vec<String>,
+}
+
+impl RepoConfig {
+    pub fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Read the repo's config. `None` when it has none; a file that doesn't
+    /// parse is logged and ignored.
+    pub fn load(repo_path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(repo_path.join(REPO_CONFIG_PATH)).ok()?;
+        Self::parse(&content)
+            .map_err(|e| log::warn!("[repo_config] Ignoring invalid {REPO_CONFIG_PATH}: {e}"))
+            .ok()
+    }
+
+    /// Matcher for the `ignore` globs. Invalid globs are skipped.
+    pub fn ignore_matcher(&self) -> Gitignore {
+        let mut builder = GitignoreBuilder::new("");
+        for glob in &self.ignore {
+            if let Err(e) = builder.add_line(None, glob) {
+                log::warn!("[repo_config] Skipping invalid ignore glob {glob:?}: {e}");
+            }
+        }
+        builder.build().unwrap_or_else(|_| Gitignore::empty())
+    }
+}
+
+/// Whether `file_path` is covered by one of the repo's `ignore` globs.
+pub fn is_ignored(matcher: &Gitignore, file_path: &str) -> bool {
+    matcher
+        .matched_path_or_any_parents(file_path, false)
+        .is_ignore()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ignore_globs_and_trust_list() {
+        let config = RepoConfig::parse(
+            "ignore = [\"vendor/\", \"*.pb.go\", \"!vendor/keep.go\"]\ntrust = [\"imports:*\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.trust, Some(vec!["imports:*".to_owned()]));
+        let matcher = config.ignore_matcher();
+        assert!(is_ignored(&matcher, "vendor/lib/a.go"));
+        assert!(is_ignored(&matcher, "api/v1/api.pb.go"));
+        assert!(!is_ignored(&matcher, "vendor/keep.go"));
+        assert!(!is_ignored(&matcher, "src/main.go"));
+
+        assert!(RepoConfig::parse("").unwrap().trust.is_none());
+        let config =
+            RepoConfig::parse("[[precedence]]\nprefer = \"api:*\"\nover = [\"comments:*\"]\n")
+                .unwrap();
+        assert_eq!(config.precedence.unwrap()[0].over, ["comments:*"]);
+        let config = RepoConfig::parse("[semgrep]\nconfig = [\"p/ci\"]\n").unwrap();
+        assert_eq!(config.semgrep.unwrap().config, ["p/ci"]);
+        let config = RepoConfig::parse(
+            "[[lint]]\nname = \"eslint\"\ncommand = \"npx eslint -f json .\"\nformat = \"eslint\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.lint[0].format, ReportFormat::Eslint);
+        assert!(
+            RepoConfig::parse("[[lint]]\nname = \"x\"\ncommand = \"x\"\nformat = \"xml\"\n")
+                .is_err()
+        );
+        let config = RepoConfig::parse("[redaction]\nexclude = [\"secrets/\"]\n").unwrap();
+        let redaction = config.redaction.unwrap();
+        assert!(redaction.builtin);
+        assert_eq!(redaction.exclude, ["secrets/"]);
+        assert!(RepoConfig::parse("ignore = 3").is_err());
+    }
+}
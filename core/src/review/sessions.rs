@@ -0,0 +1,255 @@
+//! Review sessions — how long someone actually spent reviewing each
+//! comparison, for honest numbers on what review costs.
+//!
+//! Clients report activity while a review is on screen and in use (see
+//! [`record_activity`]) and pause when the window loses focus. Reports close
+//! together extend the review's open [`ReviewSession`]; a gap longer than
+//! [`IDLE_TIMEOUT_MS`] counts as idle and starts a new one, so time away from
+//! the keyboard isn't counted. Sessions are kept per repo in
+//! `review-sessions.json` in its durable storage dir.
+//!
+//! `review stats` aggregates them per review ([`session_stats`]).
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::central;
+use super::storage::StorageError;
+
+/// Filename of the session log in a repo's durable storage dir.
+pub const SESSIONS_FILE: &str = "review-sessions.json";
+
+/// Longest gap between activity reports still counted as reviewing (reading
+/// a long hunk without touching anything, say).
+pub const IDLE_TIMEOUT_MS: u64 = 5 * 60 * 1000;
+
+/// Serializes read-modify-write of session files within the process.
+static SESSIONS_LOCK: Mutex<()> = Mutex::new(());
+
+/// A stretch of continuous activity on one review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewSession {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// First activity, in milliseconds since the Unix epoch.
+    pub start: u64,
+    /// Last activity, in milliseconds since the Unix epoch.
+    pub end: u64,
+    /// Closed by a pause; the next activity starts a new session.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub closed: bool,
+}
+
+impl ReviewSession {
+    pub fn active_ms(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// What a client reports about a review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionEvent {
+    /// The reviewer is working on it.
+    Active,
+    /// The review went out of view (window blurred, tab switched).
+    Pause,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// Apply an event at `now` (epoch ms) to a repo's sessions.
+pub fn apply_event(
+    sessions: &mut Vec<ReviewSession>,
+    ref_name: &str,
+    event: SessionEvent,
+    now: u64,
+) {
+    let open = sessions
+        .iter_mut()
+        .rev()
+        .find(|s| s.ref_name == ref_name && !s.closed);
+    let resumable = open.filter(|s| now >= s.end && now - s.end <= IDLE_TIMEOUT_MS);
+    match (event, resumable) {
+        (SessionEvent::Active, Some(session)) => session.end = now,
+        (SessionEvent::Active, None) => {
+            // Anything still open for this review went idle.
+            for session in sessions.iter_mut().filter(|s| s.ref_name == ref_name) {
+                session.closed = true;
+            }
+            sessions.push(ReviewSession {
+                ref_name: ref_name.to_owned(),
+                start: now,
+                end: now,
+                closed: false,
+            });
+        }
+        (SessionEvent::Pause, Some(session)) => {
+            session.end = now;
+            session.closed = true;
+        }
+        (SessionEvent::Pause, None) => {
+            for session in sessions.iter_mut().filter(|s| s.ref_name == ref_name) {
+                session.closed = true;
+            }
+        }
+    }
+}
+
+fn sessions_path(repo_path: &Path) -> Result<PathBuf, StorageError> {
+    Ok(central::get_repo_storage_dir(repo_path)?.join(SESSIONS_FILE))
+}
+
+/// Every session recorded for the repo, oldest first.
+pub fn load_sessions(repo_path: &Path) -> Result<Vec<ReviewSession>, StorageError> {
+    let path = sessions_path(repo_path)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(&path)?)?)
+}
+
+/// Record `event` for `ref_name`'s review now.
+pub fn record_activity(
+    repo_path: &Path,
+    ref_name: &str,
+    event: SessionEvent,
+) -> Result<(), StorageError> {
+    let _guard = SESSIONS_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut sessions = load_sessions(repo_path)?;
+    apply_event(&mut sessions, ref_name, event, now_ms());
+
+    let path = sessions_path(repo_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Write to a temp file and rename, so a crash mid-write never leaves a
+    // truncated log.
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_string(&sessions)?)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+/// Time spent on one review.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewTime {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub sessions: usize,
+    pub active_secs: u64,
+    /// Last activity, in milliseconds since the Unix epoch.
+    pub last_active: u64,
+}
+
+/// Aggregated review time for a repo.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStats {
+    pub sessions: usize,
+    pub active_secs: u64,
+    /// Per-review time, longest first.
+    pub reviews: Vec<ReviewTime>,
+}
+
+/// Aggregate sessions by review.
+pub fn session_stats(sessions: &[ReviewSession]) -> SessionStats {
+    let mut by_ref: BTreeMap<&str, (usize, u64, u64)> = BTreeMap::new();
+    for session in sessions {
+        let entry = by_ref.entry(&session.ref_name).or_default();
+        entry.0 += 1;
+        entry.1 += session.active_ms();
+        entry.2 = entry.2.max(session.end);
+    }
+    let mut reviews: Vec<ReviewTime> = by_ref
+        .into_iter()
+        .map(|(ref_name, (count, ms, last))| ReviewTime {
+            ref_name: ref_name.to_owned(),
+            sessions: count,
+            active_secs: ms / 1000,
+            last_active: last,
+        })
+        .collect();
+    reviews.sort_by_key(|r| Reverse(r.active_secs));
+    SessionStats {
+        sessions: sessions.len(),
+        active_secs: sessions.iter().map(ReviewSession::active_ms).sum::<u64>() / 1000,
+        reviews,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+
+    const MIN: u64 = 60 * 1000;
+
+    #[test]
+    fn idle_gaps_and_pauses_split_sessions() {
+        let mut sessions = Vec::new();
+        let t0 = 1_700_000_000_000;
+        apply_event(&mut sessions, "feature", SessionEvent::Active, t0);
+        apply_event(&mut sessions, "feature", SessionEvent::Active, t0 + 2 * MIN);
+        // Another review's activity doesn't touch this one's session.
+        apply_event(&mut sessions, "other", SessionEvent::Active, t0 + 3 * MIN);
+        apply_event(&mut sessions, "feature", SessionEvent::Active, t0 + 4 * MIN);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].active_ms(), 4 * MIN);
+
+        // Idle longer than the timeout: the gap isn't counted.
+        apply_event(
+            &mut sessions,
+            "feature",
+            SessionEvent::Active,
+            t0 + 20 * MIN,
+        );
+        apply_event(&mut sessions, "feature", SessionEvent::Pause, t0 + 21 * MIN);
+        assert_eq!(sessions.len(), 3);
+        assert!(sessions[0].closed && sessions[2].closed);
+        assert_eq!(sessions[2].active_ms(), MIN);
+
+        // After a pause, activity starts over even within the timeout.
+        apply_event(
+            &mut sessions,
+            "feature",
+            SessionEvent::Active,
+            t0 + 22 * MIN,
+        );
+        assert_eq!(sessions.len(), 4);
+
+        let stats = session_stats(&sessions);
+        assert_eq!(stats.sessions, 4);
+        assert_eq!(stats.active_secs, 5 * 60);
+        assert_eq!(stats.reviews[0].ref_name, "feature");
+        assert_eq!(stats.reviews[0].sessions, 3);
+        assert_eq!(stats.reviews[0].last_active, t0 + 22 * MIN);
+        assert_eq!(stats.reviews[1].active_secs, 0);
+    }
+
+    #[test]
+    fn sessions_persist_per_repo() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+
+        assert!(load_sessions(repo.path()).unwrap().is_empty());
+        record_activity(repo.path(), "feature", SessionEvent::Active).unwrap();
+        record_activity(repo.path(), "feature", SessionEvent::Pause).unwrap();
+        let sessions = load_sessions(repo.path()).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].ref_name, "feature");
+        assert!(sessions[0].closed);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::diff::parser::DiffHunk;
 use crate::trust::matches_pattern;
-use crate::trust::patterns::get_all_pattern_ids;
+use crate::trust::patterns::{current_taxonomy_version, get_default_pattern_ids, is_never_trusted};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// The on-disk format version for a serialized [`ReviewState`].
 ///
@@ -117,6 +117,24 @@ pub struct LineAnnotation {
     pub resolved_by: Option<String>,
 }
 
+/// One item of a review's checklist (e.g. "migrations reviewed"), seeded from
+/// the repo's checklist template — see [`super::checklist`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistItem {
+    /// Stable slug derived from the item text; the key check state is carried by.
+    pub id: String,
+    pub text: String,
+    #[serde(default)]
+    pub checked: bool,
+    /// When the item was last checked off; absent while unchecked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checked_at: Option<String>,
+    /// Who checked it off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checked_by: Option<Source>,
+}
+
 #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum AnnotationSide {
@@ -153,6 +171,9 @@ pub enum Source {
     Agent,
     Github,
     Gitlab,
+    /// Suggested because another review approved the identical change (see
+    /// [`crate::review::embeddings::carry_identical_approvals`]).
+    Carried,
 }
 
 impl Source {
@@ -165,6 +186,7 @@ impl Source {
             Source::Agent => "agent",
             Source::Github => "github",
             Source::Gitlab => "gitlab",
+            Source::Carried => "carried",
         }
     }
 }
@@ -226,6 +248,301 @@ pub struct ReviewState {
         skip_serializing_if = "Option::is_none"
     )]
     pub worktree_path: Option<String>,
+    /// When the review was archived. Archived reviews live compressed under the
+    /// repo's `archive/` area and are hidden from default listings and
+    /// freshness checks; see [`super::storage::archive_review`].
+    #[serde(
+        rename = "archivedAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub archived_at: Option<String>,
+    /// Checklist items and their check state, seeded from the repo's
+    /// `.review/checklist.md` template.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checklist: Vec<ChecklistItem>,
+    /// Per-file progress, keyed by file path. Totals come from the diff (see
+    /// [`ReviewState::set_diff_hunks`]); the counts are recomputed on every
+    /// save, so readers get progress without walking every hunk.
+    #[serde(
+        rename = "fileProgress",
+        default,
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub file_progress: BTreeMap<String, FileProgress>,
+    /// Set on a review created by splitting another's commit range: where it
+    /// sits in that split. See [`crate::service::split`].
+    #[serde(rename = "splitFrom", default, skip_serializing_if = "Option::is_none")]
+    pub split_from: Option<SplitLink>,
+    /// The refs of the reviews this one's commit range was split into, in
+    /// commit order.
+    #[serde(rename = "splitInto", default, skip_serializing_if = "Vec::is_empty")]
+    pub split_into: Vec<String>,
+    /// The last `review bisect` run: the commit in the range where a test
+    /// command started failing, and the hunks it introduced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bisect: Option<BisectResult>,
+    /// Static analyzer findings on the review's changed lines, ingested with
+    /// `review ingest`. See [`crate::service::ingest`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,
+    /// The last `review size` run: build artifact sizes at the base and head.
+    #[serde(rename = "buildSize", default, skip_serializing_if = "Option::is_none")]
+    pub build_size: Option<BuildSizeReport>,
+    /// The last `review bench` run: benchmark timings at the base and head.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bench: Option<BenchReport>,
+    /// Set on a review that needs a second reviewer's sign-off. See
+    /// [`crate::service::two_person`].
+    #[serde(rename = "twoPerson", default, skip_serializing_if = "Option::is_none")]
+    pub two_person: Option<TwoPersonReview>,
+    /// A signed record of exactly which diff was reviewed and how, made with
+    /// `review attest`. See [`crate::service::attestation`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attestation: Option<Attestation>,
+}
+
+/// A reviewer's signature over a completed review: which diff, and the
+/// decision on each of its hunks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Attestation {
+    pub review: AttestedReview,
+    /// SHA-256 of the signed bytes, [`AttestedReview::canonical_bytes`].
+    pub digest: String,
+    pub format: SignatureFormat,
+    /// The signing key: an SSH key path or literal key as configured, or the
+    /// fingerprint of the GPG key that signed.
+    pub key: String,
+    /// Armored detached signature.
+    pub signature: String,
+    pub signed_at: String,
+}
+
+/// What an [`Attestation`] signs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestedReview {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// The comparison key, `base..head`.
+    pub comparison: String,
+    /// Resolved commits (or endpoint tokens) of each side.
+    pub base: String,
+    pub head: String,
+    /// SHA-256 over every hunk's ID and lines, in ID order.
+    pub diff_sha256: String,
+    /// Hunk ID → `approved`, `rejected`, or `trusted`.
+    pub decisions: BTreeMap<String, String>,
+    /// The signer, as `Name <email>` from git config.
+    pub reviewer: String,
+}
+
+impl AttestedReview {
+    /// The exact bytes signed: compact JSON, which is stable because every
+    /// map is ordered.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+}
+
+/// Which tool made an [`Attestation`]'s signature, following git's
+/// `gpg.format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignatureFormat {
+    Ssh,
+    Gpg,
+}
+
+/// Where a two-person review stands: the first reviewer hands off, their
+/// decisions becoming each hunk's `recommendation`, and the second reviewer
+/// confirms or overrides them and signs off.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoPersonReview {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub handoff: Option<SignOff>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signoff: Option<SignOff>,
+}
+
+/// Who signed off on their part of a two-person review, and when.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignOff {
+    pub by: String,
+    pub at: String,
+}
+
+/// A split review's link back to the review it was split from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitLink {
+    /// The ref of the review that was split.
+    pub parent: String,
+    /// 1-based position among the parts, in commit order.
+    pub part: usize,
+    pub parts: usize,
+    pub title: String,
+}
+
+/// The outcome of bisecting a review's commit range with a test command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectResult {
+    /// The shell command that decided good (exit 0) or bad.
+    pub command: String,
+    /// The last commit where the command passed (the merge base).
+    pub good: String,
+    /// The review's head commit, where the command fails.
+    pub bad: String,
+    /// The first commit where the command fails.
+    pub culprit: String,
+    pub short_hash: String,
+    pub subject: String,
+    pub author: String,
+    /// When the bisect ran.
+    pub at: String,
+    /// Ids of the review's hunks that carry the culprit commit's changes.
+    pub hunks: Vec<String>,
+}
+
+/// One build artifact's size on each side of a review. `None` when the build
+/// didn't produce it on that side.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactSize {
+    /// Path relative to the checkout root.
+    pub path: String,
+    pub base_bytes: Option<u64>,
+    pub head_bytes: Option<u64>,
+}
+
+impl ArtifactSize {
+    /// Head minus base, counting a missing side as zero.
+    pub fn delta(&self) -> i64 {
+        let bytes = |b: Option<u64>| i64::try_from(b.unwrap_or(0)).unwrap_or(i64::MAX);
+        bytes(self.head_bytes) - bytes(self.base_bytes)
+    }
+}
+
+/// Build artifact sizes at a review's base and head commits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildSizeReport {
+    /// The build command both sides were built with.
+    pub command: String,
+    pub base: String,
+    pub head: String,
+    pub artifacts: Vec<ArtifactSize>,
+    pub measured_at: String,
+}
+
+impl BuildSizeReport {
+    /// Total size change across all artifacts, in bytes.
+    pub fn delta(&self) -> i64 {
+        self.artifacts.iter().map(ArtifactSize::delta).sum()
+    }
+}
+
+/// One benchmark's timing on each side of a review, in nanoseconds per
+/// iteration. `None` when the benchmark didn't run on that side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchDelta {
+    pub name: String,
+    pub base_ns: Option<f64>,
+    pub head_ns: Option<f64>,
+    /// Percent change from base to head; positive is slower.
+    pub change_pct: Option<f64>,
+    /// Slower than the run's threshold allows: flagged
+    /// `performance:regression`.
+    #[serde(default)]
+    pub regression: bool,
+}
+
+/// Benchmark timings at a review's base and head commits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    pub command: String,
+    pub base: String,
+    pub head: String,
+    /// Percent slowdown above which a benchmark counts as a regression.
+    pub threshold_pct: f64,
+    pub benchmarks: Vec<BenchDelta>,
+    pub measured_at: String,
+}
+
+impl BenchReport {
+    pub fn regressions(&self) -> impl Iterator<Item = &BenchDelta> {
+        self.benchmarks.iter().filter(|b| b.regression)
+    }
+}
+
+/// How serious an analyzer considers a finding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FindingSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl FindingSeverity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FindingSeverity::Info => "info",
+            FindingSeverity::Warning => "warning",
+            FindingSeverity::Error => "error",
+        }
+    }
+}
+
+/// A static analyzer's finding on a changed line of a hunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Finding {
+    /// Fingerprint of the tool, rule, file, message and flagged line's text;
+    /// the same finding from a later run has the same ID.
+    pub id: String,
+    /// The analyzer that reported it (e.g. `clippy`, a SARIF driver name).
+    pub tool: String,
+    /// The report format it was ingested from (`sarif`, `clippy`, …).
+    pub format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    pub severity: FindingSeverity,
+    pub message: String,
+    pub file_path: String,
+    /// First flagged line, on the new side.
+    pub line: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<u32>,
+    pub hunk_id: String,
+    /// When the run that last reported it was ingested.
+    pub ingested_at: String,
+}
+
+/// Review progress for one file of a review.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileProgress {
+    /// Hunks in the file's diff.
+    pub total: usize,
+    pub approved: usize,
+    pub rejected: usize,
+    /// Hunks with no status whose labels are on the trust list.
+    pub trusted: usize,
+    pub saved_for_later: usize,
+    /// Hunks with no classification.
+    pub unclassified: usize,
+}
+
+impl FileProgress {
+    pub fn reviewed(&self) -> usize {
+        self.approved + self.rejected + self.trusted
+    }
 }
 
 /// A value paired with its provenance and an optional rationale. Every axis of
@@ -237,6 +554,12 @@ pub struct Attributed<T> {
     pub source: Source,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reasoning: Option<String>,
+    /// When the value was set (ISO 8601). Left unset by writers and stamped by
+    /// [`ReviewState::prepare_for_save`], so an entry records the save that
+    /// introduced it. Empty for entries stored before this field existed,
+    /// whose time isn't known (see [`ReviewState::mark_undated`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub at: Option<String>,
 }
 
 impl<T> Attributed<T> {
@@ -246,6 +569,7 @@ impl<T> Attributed<T> {
             value,
             source,
             reasoning: None,
+            at: None,
         }
     }
 }
@@ -259,12 +583,32 @@ pub struct HunkState {
     pub classification: Option<Attributed<Vec<String>>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub status: Option<Attributed<HunkStatus>>,
+    /// In a two-person review, the first reviewer's decision, moved here from
+    /// `status` at hand-off for the second reviewer to confirm or override.
+    /// Also a suggestion carried over from an identical hunk another review
+    /// approved, with [`Source::Carried`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recommendation: Option<Attributed<HunkStatus>>,
     /// The hunk's stable identity (changed lines only — see
     /// [`crate::diff::parser::DiffHunk::stable_hash`]) at the time a decision was
     /// recorded. Lets [`ReviewState::reconcile`] carry this decision forward onto
     /// the same change after surrounding context drifts and the hunk ID changes.
     #[serde(rename = "stableKey", default, skip_serializing_if = "Option::is_none")]
     pub stable_key: Option<String>,
+    /// The labels as classified, when precedence rules dropped some of them
+    /// to give `classification` (see [`crate::classify::precedence`]).
+    /// Absent when nothing was dropped.
+    #[serde(rename = "rawLabels", default, skip_serializing_if = "Option::is_none")]
+    pub raw_labels: Option<Vec<String>>,
+    /// The taxonomy version (see [`crate::trust::patterns::taxonomy_version`])
+    /// current when `classification` was set. Absent for labels set before
+    /// versions were recorded.
+    #[serde(
+        rename = "taxonomyVersion",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub taxonomy_version: Option<String>,
 }
 
 impl HunkState {
@@ -276,14 +620,24 @@ impl HunkState {
             .unwrap_or(&[])
     }
 
+    /// True when a classifier (static rules or AI) labeled this hunk under a
+    /// taxonomy other than `current`, so its labels may use stale vocabulary.
+    /// Labels a person set are their call and never count.
+    pub fn has_stale_labels(&self, current: &str) -> bool {
+        self.classification.as_ref().is_some_and(|c| {
+            matches!(c.source, Source::Static | Source::Ai)
+                && self.taxonomy_version.as_deref() != Some(current)
+        })
+    }
+
     /// True when no axis is set. Used to prune entries that have nothing left
     /// on them after a status is cleared.
     pub fn is_empty(&self) -> bool {
-        self.classification.is_none() && self.status.is_none()
+        self.classification.is_none() && self.status.is_none() && self.recommendation.is_none()
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum HunkStatus {
     Approved,
@@ -309,7 +663,7 @@ impl ReviewState {
             ref_name: ref_name.into(),
             base_override,
             hunks: HashMap::new(),
-            trust_list: get_all_pattern_ids(),
+            trust_list: get_default_pattern_ids(),
             notes: String::new(),
             annotations: Vec::new(),
             created_at: now.clone(),
@@ -319,13 +673,116 @@ impl ReviewState {
             total_diff_hunks: 0,
             github_pr: None,
             worktree_path: None,
+            archived_at: None,
+            checklist: Vec::new(),
+            file_progress: BTreeMap::new(),
+            split_from: None,
+            split_into: Vec::new(),
+            bisect: None,
+            findings: Vec::new(),
+            build_size: None,
+            bench: None,
+            two_person: None,
+            attestation: None,
+        }
+    }
+
+    /// Record the diff's hunk count, overall and per file. `hunks` must be
+    /// the complete diff: files missing from it drop out of the rollups.
+    pub fn set_diff_hunks(&mut self, hunks: &[DiffHunk]) {
+        self.total_diff_hunks = hunks.len();
+        let mut totals: BTreeMap<String, FileProgress> = BTreeMap::new();
+        for hunk in hunks {
+            totals.entry(hunk.file_path.clone()).or_default().total += 1;
+        }
+        self.file_progress = totals;
+        self.roll_up_files();
+    }
+
+    /// Recompute each file's counts from the hunk entries. A file's total is
+    /// raised to the hunks known for it when no diff total was recorded.
+    pub fn roll_up_files(&mut self) {
+        let mut rollups: BTreeMap<String, FileProgress> = self
+            .file_progress
+            .iter()
+            .map(|(path, progress)| {
+                let total = FileProgress {
+                    total: progress.total,
+                    ..FileProgress::default()
+                };
+                (path.clone(), total)
+            })
+            .collect();
+        // Per file: (hunk entries, entries with a classification).
+        let mut known: HashMap<&str, (usize, usize)> = HashMap::new();
+        for (id, entry) in &self.hunks {
+            let path = id.rsplit_once(':').map_or(id.as_str(), |(path, _)| path);
+            let rollup = rollups.entry(path.to_owned()).or_default();
+            match entry.status.as_ref().map(|s| &s.value) {
+                Some(HunkStatus::Approved) => rollup.approved += 1,
+                Some(HunkStatus::Rejected) => rollup.rejected += 1,
+                Some(HunkStatus::SavedForLater) => rollup.saved_for_later += 1,
+                None if self.labels_trusted(entry.labels()) => rollup.trusted += 1,
+                None => {}
+            }
+            let counts = known.entry(path).or_default();
+            counts.0 += 1;
+            counts.1 += usize::from(entry.classification.is_some());
+        }
+        for (path, rollup) in &mut rollups {
+            let (entries, classified) = known.get(path.as_str()).copied().unwrap_or_default();
+            rollup.total = rollup.total.max(entries);
+            rollup.unclassified = rollup.total.saturating_sub(classified);
+        }
+        self.file_progress = rollups;
+    }
+
+    /// Give stored decisions with no time an empty one, so
+    /// [`Self::prepare_for_save`] tells them from decisions set since they
+    /// were read and leaves them undated.
+    pub(crate) fn mark_undated(&mut self) {
+        for entry in self.hunks.values_mut() {
+            if let Some(classification) = entry.classification.as_mut() {
+                classification.at.get_or_insert_with(String::new);
+            }
+            if let Some(status) = entry.status.as_mut() {
+                status.at.get_or_insert_with(String::new);
+            }
         }
     }
 
     /// Increment version and update timestamp for a save operation
     pub fn prepare_for_save(&mut self) {
+        let now = now_iso8601();
         self.version += 1;
-        self.updated_at = now_iso8601();
+        self.updated_at.clone_from(&now);
+        // Stamp newly-set decisions with this save's time; entries carried
+        // over unchanged keep the time they were first recorded, or none.
+        for entry in self.hunks.values_mut() {
+            if let Some(classification) = entry.classification.as_mut() {
+                // Unstamped labels were set since the last save: record the
+                // taxonomy they were made with.
+                if classification.at.is_none() {
+                    entry.taxonomy_version = Some(current_taxonomy_version().to_owned());
+                }
+                classification.at.get_or_insert_with(|| now.clone());
+            } else {
+                entry.taxonomy_version = None;
+            }
+            // Raw labels describe a classifier's output; labels set (or
+            // cleared) by anyone else supersede them.
+            if entry
+                .classification
+                .as_ref()
+                .is_none_or(|c| !matches!(c.source, Source::Static | Source::Ai))
+            {
+                entry.raw_labels = None;
+            }
+            if let Some(status) = entry.status.as_mut() {
+                status.at.get_or_insert_with(|| now.clone());
+            }
+        }
+        self.roll_up_files();
         // Always persist the current format version, so a state constructed
         // without one (e.g. a frontend fallback) doesn't write a stale
         // schemaVersion that the next read has to migrate back up.
@@ -378,10 +835,21 @@ impl ReviewState {
                 .or_insert_with(|| Some(hunk.id.clone()));
         }
 
+        // IDs a merged hunk stands in for are live too: their decisions map
+        // onto it, and survive merging being turned off again.
+        let children: HashSet<&str> = live_hunks
+            .iter()
+            .flat_map(|hunk| hunk.merged_ids.iter().map(String::as_str))
+            .collect();
+
         let mut result = Reconciliation::default();
         let mut next: HashMap<String, HunkState> = HashMap::with_capacity(self.hunks.len());
 
         for (id, mut hunk_state) in std::mem::take(&mut self.hunks) {
+            if children.contains(id.as_str()) {
+                next.insert(id, hunk_state);
+                continue;
+            }
             if let Some(stable) = stable_by_id.get(id.as_str()) {
                 // Still present: refresh the stable key and keep as-is.
                 hunk_state.stable_key = Some(stable.clone());
@@ -412,11 +880,46 @@ impl ReviewState {
         }
 
         self.hunks = next;
+        for hunk in live_hunks.iter().filter(|h| !h.merged_ids.is_empty()) {
+            self.map_merged_status(hunk);
+        }
         result
     }
 
-    /// Whether any of `labels` matches a pattern in the trust list.
+    /// Keep a merged hunk's status and its children's in step: the merged
+    /// hunk's status applies to children without one, and children that
+    /// agree give the merged hunk theirs.
+    fn map_merged_status(&mut self, hunk: &DiffHunk) {
+        let merged_status = self.hunks.get(&hunk.id).and_then(|s| s.status.clone());
+        if let Some(status) = merged_status {
+            for child in &hunk.merged_ids {
+                let entry = self.hunks.entry(child.clone()).or_default();
+                if entry.status.is_none() {
+                    entry.status = Some(status.clone());
+                }
+            }
+            return;
+        }
+        let mut statuses = hunk
+            .merged_ids
+            .iter()
+            .map(|child| self.hunks.get(child).and_then(|s| s.status.as_ref()));
+        let Some(Some(first)) = statuses.next() else {
+            return;
+        };
+        if statuses.all(|s| s.is_some_and(|s| s.value == first.value)) {
+            let status = first.clone();
+            self.hunks.entry(hunk.id.clone()).or_default().status = Some(status);
+        }
+    }
+
+    /// Whether any of `labels` matches a pattern in the trust list. Hunks with
+    /// a never-trusted label (sensitive area, removed tests) are never trusted,
+    /// whatever their other labels.
     pub fn labels_trusted(&self, labels: &[String]) -> bool {
+        if labels.iter().any(|label| is_never_trusted(label)) {
+            return false;
+        }
         labels.iter().any(|label| {
             self.trust_list
                 .iter()
@@ -433,6 +936,12 @@ impl ReviewState {
         let mut rejected_hunks = 0usize;
         let mut saved_for_later_hunks = 0usize;
         let mut trusted_hunks = 0usize;
+        let current_taxonomy = current_taxonomy_version();
+        let stale_taxonomy_hunks = self
+            .hunks
+            .values()
+            .filter(|h| h.has_stale_labels(current_taxonomy))
+            .count();
 
         for h in self.hunks.values() {
             match h.status.as_ref().map(|s| &s.value) {
@@ -467,6 +976,13 @@ impl ReviewState {
             updated_at: self.updated_at.clone(),
             github_pr: self.github_pr.clone(),
             worktree_path: self.worktree_path.clone(),
+            archived_at: self.archived_at.clone(),
+            stale_taxonomy_hunks,
+            build_size_delta: self.build_size.as_ref().map(BuildSizeReport::delta),
+            bench_regressions: self
+                .bench
+                .as_ref()
+                .map_or(0, |bench| bench.regressions().count()),
         }
     }
 }
@@ -581,6 +1097,28 @@ pub struct ReviewSummary {
         skip_serializing_if = "Option::is_none"
     )]
     pub worktree_path: Option<String>,
+    /// Set when the review is archived.
+    #[serde(
+        rename = "archivedAt",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub archived_at: Option<String>,
+    /// Hunks labeled by a classifier under an older taxonomy; see
+    /// [`HunkState::has_stale_labels`].
+    #[serde(rename = "staleTaxonomyHunks", default)]
+    pub stale_taxonomy_hunks: usize,
+    /// Bytes the build artifacts grew (or shrank) by, from the last
+    /// `review size` run.
+    #[serde(
+        rename = "buildSizeDelta",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub build_size_delta: Option<i64>,
+    /// Benchmarks flagged `performance:regression` by the last `review bench`.
+    #[serde(rename = "benchRegressions", default)]
+    pub bench_regressions: usize,
 }
 
 #[cfg(test)]
@@ -679,6 +1217,35 @@ mod tests {
         assert_eq!(summary.reviewed_hunks, 1);
     }
 
+    #[test]
+    fn test_never_trusted_labels() {
+        let mut state = new_state();
+        state.trust_list = vec!["*".to_owned()];
+
+        assert!(state.labels_trusted(&["imports:added".to_owned()]));
+        assert!(!state.labels_trusted(&[
+            "security:sensitive-area".to_owned(),
+            "imports:added".to_owned(),
+        ]));
+        assert!(!state.labels_trusted(&["tests:removed".to_owned()]));
+        assert!(!state.labels_trusted(&["legal:license-change".to_owned()]));
+
+        state.trust_list = vec!["infra:*".to_owned()];
+        assert!(state.labels_trusted(&["infra:dockerfile".to_owned()]));
+        assert!(!state.labels_trusted(&[
+            "infra:k8s-resource".to_owned(),
+            "infra:privileged-container".to_owned(),
+        ]));
+
+        state.trust_list = vec!["api:breaking-change".to_owned()];
+        assert!(!state.labels_trusted(&["api:breaking-change".to_owned()]));
+
+        state.trust_list = vec!["sql:*".to_owned(), "ci:*".to_owned()];
+        assert!(!state.labels_trusted(&["sql:irreversible".to_owned()]));
+        assert!(!state.labels_trusted(&["ci:pull-request-target".to_owned()]));
+        assert!(state.labels_trusted(&["ci:workflow-change".to_owned()]));
+    }
+
     #[test]
     fn test_review_state_to_summary_uses_total_diff_hunks() {
         let mut state = new_state();
@@ -718,6 +1285,67 @@ mod tests {
         assert!(summary.state.is_none());
     }
 
+    #[test]
+    fn test_file_progress_rolls_up_on_save() {
+        use crate::diff::parser::create_untracked_hunk;
+
+        let mut state = new_state();
+        state.trust_list = vec!["imports:*".to_owned()];
+        let hunks = [
+            create_untracked_hunk("src/a.rs", "h1", Some("x")),
+            create_untracked_hunk("src/a.rs", "h2", Some("y")),
+            create_untracked_hunk("src/a.rs", "h3", Some("z")),
+            create_untracked_hunk("src/b.rs", "h4", Some("w")),
+        ];
+        state.set_diff_hunks(&hunks);
+        assert_eq!(state.total_diff_hunks, 4);
+        assert_eq!(state.file_progress["src/a.rs"].unclassified, 3);
+
+        state.hunks.insert(
+            "src/a.rs:h1".to_owned(),
+            HunkState {
+                classification: Some(Attributed::new(
+                    vec!["imports:added".to_owned()],
+                    Source::Static,
+                )),
+                ..Default::default()
+            },
+        );
+        state.hunks.insert(
+            "src/a.rs:h2".to_owned(),
+            HunkState {
+                status: Some(Attributed::new(HunkStatus::Rejected, Source::Ui)),
+                ..Default::default()
+            },
+        );
+        // A decision on a file the recorded diff didn't include still counts.
+        state.hunks.insert(
+            "src/c.rs:h5".to_owned(),
+            HunkState {
+                status: Some(Attributed::new(HunkStatus::Approved, Source::Ui)),
+                ..Default::default()
+            },
+        );
+        state.prepare_for_save();
+
+        let a = &state.file_progress["src/a.rs"];
+        assert_eq!(
+            *a,
+            FileProgress {
+                total: 3,
+                approved: 0,
+                rejected: 1,
+                trusted: 1,
+                saved_for_later: 0,
+                unclassified: 2,
+            }
+        );
+        assert_eq!(a.reviewed(), 2);
+        assert_eq!(state.file_progress["src/b.rs"].total, 1);
+        assert_eq!(state.file_progress["src/c.rs"].approved, 1);
+        assert_eq!(state.file_progress["src/c.rs"].total, 1);
+    }
+
     #[test]
     fn test_review_state_to_summary_without_total_diff_hunks_defaults_to_zero() {
         let mut state = new_state();
@@ -749,6 +1377,65 @@ mod tests {
         assert!(timestamp.len() >= 24); // "2024-01-01T00:00:00.000Z"
     }
 
+    #[test]
+    fn prepare_for_save_stamps_only_unstamped_decisions() {
+        let mut state = new_state();
+        let mut earlier = Attributed::new(HunkStatus::Approved, Source::Ui);
+        earlier.at = Some("2024-01-01T00:00:00.000Z".to_owned());
+        state.hunks.insert(
+            "a.rs:1".to_owned(),
+            HunkState {
+                status: Some(earlier),
+                ..Default::default()
+            },
+        );
+        state.hunks.insert(
+            "b.rs:2".to_owned(),
+            HunkState {
+                classification: Some(Attributed::new(vec!["x:y".to_owned()], Source::Ai)),
+                ..Default::default()
+            },
+        );
+
+        state.prepare_for_save();
+
+        let kept = state.hunks["a.rs:1"].status.as_ref().unwrap();
+        assert_eq!(kept.at.as_deref(), Some("2024-01-01T00:00:00.000Z"));
+        let stamped = state.hunks["b.rs:2"].classification.as_ref().unwrap();
+        assert_eq!(stamped.at.as_deref(), Some(state.updated_at.as_str()));
+    }
+
+    #[test]
+    fn new_labels_are_stamped_with_the_taxonomy_version() {
+        let mut state = new_state();
+        let mut legacy = Attributed::new(vec!["imports:added".to_owned()], Source::Static);
+        legacy.at = Some("2024-01-01T00:00:00.000Z".to_owned());
+        state.hunks.insert(
+            "old.rs:1".to_owned(),
+            HunkState {
+                classification: Some(legacy),
+                ..Default::default()
+            },
+        );
+        state.hunks.insert(
+            "new.rs:1".to_owned(),
+            HunkState {
+                classification: Some(Attributed::new(
+                    vec!["imports:added".to_owned()],
+                    Source::Static,
+                )),
+                ..Default::default()
+            },
+        );
+
+        state.prepare_for_save();
+
+        let current = current_taxonomy_version();
+        assert!(state.hunks["old.rs:1"].has_stale_labels(current));
+        assert!(!state.hunks["new.rs:1"].has_stale_labels(current));
+        assert_eq!(state.to_summary().stale_taxonomy_hunks, 1);
+    }
+
     // --- stable identity + carry-forward (reconcile) ---
 
     // Both diffs add the same line `NEW` to `f.txt`, but with different
@@ -757,13 +1444,6 @@ mod tests {
     const DIFF_A: &str = "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n@@ -1,3 +1,4 @@\n alpha\n beta\n+NEW\n gamma\n";
     const DIFF_B: &str = "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n@@ -10,3 +10,4 @@\n delta\n epsilon\n+NEW\n zeta\n";
 
-    fn hunk_from(diff: &str) -> DiffHunk {
-        crate::diff::parser::parse_multi_file_diff(diff)
-            .into_iter()
-            .next()
-            .expect("expected one hunk")
-    }
-
     fn approved_entry(stable_key: Option<String>) -> HunkState {
         HunkState {
             status: Some(Attributed::new(HunkStatus::Approved, Source::Cli)),
@@ -774,8 +1454,8 @@ mod tests {
 
     #[test]
     fn stable_hash_ignores_context() {
-        let a = hunk_from(DIFF_A);
-        let b = hunk_from(DIFF_B);
+        let a = DiffHunk::test_from_diff(DIFF_A);
+        let b = DiffHunk::test_from_diff(DIFF_B);
         assert_ne!(a.id, b.id, "different context → different content-hash ID");
         assert_eq!(
             a.stable_hash(),
@@ -784,10 +1464,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reconcile_maps_status_between_merged_hunk_and_children() {
+        let mut merged = DiffHunk::test_from_diff(DIFF_A);
+        merged.merged_ids = vec!["child-1".to_owned(), "child-2".to_owned()];
+        let status_of = |state: &ReviewState, id: &str| {
+            state
+                .hunks
+                .get(id)
+                .and_then(|s| s.status.as_ref())
+                .map(|s| s.value.clone())
+        };
+
+        // Children that agree give the merged hunk their status, and are kept
+        // although no live hunk has their ID.
+        let mut state = new_state();
+        state
+            .hunks
+            .insert("child-1".to_owned(), approved_entry(None));
+        state
+            .hunks
+            .insert("child-2".to_owned(), approved_entry(None));
+        let recon = state.reconcile(&[merged.clone()], true);
+        assert_eq!(recon.dropped, 0);
+        assert_eq!(status_of(&state, &merged.id), Some(HunkStatus::Approved));
+        assert!(state.hunks.contains_key("child-2"));
+
+        // Disagreeing children leave it undecided.
+        let mut state = new_state();
+        state
+            .hunks
+            .insert("child-1".to_owned(), approved_entry(None));
+        state.reconcile(&[merged.clone()], true);
+        assert_eq!(status_of(&state, &merged.id), None);
+
+        // The merged hunk's status applies to its children.
+        let mut state = new_state();
+        state.hunks.insert(merged.id.clone(), approved_entry(None));
+        state.reconcile(&[merged.clone()], true);
+        assert_eq!(status_of(&state, "child-1"), Some(HunkStatus::Approved));
+        assert_eq!(status_of(&state, "child-2"), Some(HunkStatus::Approved));
+    }
+
     #[test]
     fn reconcile_carries_decision_forward_on_context_drift() {
-        let a = hunk_from(DIFF_A);
-        let b = hunk_from(DIFF_B);
+        let a = DiffHunk::test_from_diff(DIFF_A);
+        let b = DiffHunk::test_from_diff(DIFF_B);
         let mut state = new_state();
         state
             .hunks
@@ -815,7 +1537,7 @@ mod tests {
 
     #[test]
     fn reconcile_drops_orphan_without_stable_match() {
-        let a = hunk_from(DIFF_A);
+        let a = DiffHunk::test_from_diff(DIFF_A);
         let mut state = new_state();
         // An old-style entry (no stable key), now orphaned with nothing live.
         state.hunks.insert(a.id.clone(), approved_entry(None));
@@ -829,7 +1551,7 @@ mod tests {
 
     #[test]
     fn reconcile_retains_orphan_when_not_dropping() {
-        let a = hunk_from(DIFF_A);
+        let a = DiffHunk::test_from_diff(DIFF_A);
         let mut state = new_state();
         state.hunks.insert(a.id.clone(), approved_entry(None));
 
@@ -847,7 +1569,7 @@ mod tests {
 
     #[test]
     fn reconcile_keeps_exact_match_and_stamps_stable_key() {
-        let a = hunk_from(DIFF_A);
+        let a = DiffHunk::test_from_diff(DIFF_A);
         let mut state = new_state();
         state.hunks.insert(a.id.clone(), approved_entry(None));
 
@@ -864,8 +1586,8 @@ mod tests {
 
     #[test]
     fn reconcile_skips_ambiguous_stable_key() {
-        let a = hunk_from(DIFF_A);
-        let b = hunk_from(DIFF_B);
+        let a = DiffHunk::test_from_diff(DIFF_A);
+        let b = DiffHunk::test_from_diff(DIFF_B);
         assert_eq!(a.stable_hash(), b.stable_hash());
         let mut state = new_state();
         // An orphan whose stable key matches *two* live hunks — can't safely pick.
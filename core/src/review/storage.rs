@@ -1,11 +1,23 @@
 use super::central;
+use super::encryption;
+use super::feedback;
 use super::migrate;
-use super::state::{ReviewState, ReviewSummary};
-use crate::sources::github::GitHubPrRef;
+use super::repo_config::RepoConfig;
+use super::state::{
+    now_iso8601, AnnotationSide, LineAnnotation, ReviewState, ReviewSummary, Source,
+    TwoPersonReview,
+};
+use crate::diff::parser::{parse_multi_file_diff, DiffHunk};
+use crate::sources::github::{
+    GhError, GitHubClient, GitHubPrRef, GitHubProvider, PrReviewComment, PrReviewThread,
+};
 use crate::sources::local_git::DiffShortStat;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::Serialize;
 use std::fs;
-use std::io;
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -21,6 +33,18 @@ pub enum StorageError {
     VersionConflict { expected: u64, found: u64 },
     #[error("Central storage error: {0}")]
     Central(#[from] central::CentralError),
+    #[error("No saved review for '{0}'")]
+    NotFound(String),
+    #[error("A live review for '{0}' already exists; delete it before unarchiving")]
+    AlreadyExists(String),
+    #[error("'{0}' already has an archived review; unarchive or delete it before archiving again")]
+    AlreadyArchived(String),
+    #[error("{0}")]
+    Encryption(#[from] encryption::EncryptionError),
+    #[error("'{0}' isn't a pull request review")]
+    NotPullRequest(String),
+    #[error("GitHub error: {0}")]
+    GitHub(#[from] GhError),
 }
 
 /// Parse review JSON, migrating it forward to the current schema first.
@@ -31,7 +55,15 @@ pub enum StorageError {
 fn deserialize_review(content: &str) -> Result<ReviewState, StorageError> {
     let raw: serde_json::Value = serde_json::from_str(content)?;
     let migrated = migrate::migrate(raw)?;
-    Ok(serde_json::from_value(migrated)?)
+    let mut state: ReviewState = serde_json::from_value(migrated)?;
+    state.mark_undated();
+    Ok(state)
+}
+
+/// Read a review file, decrypting it if the review is encrypted.
+fn read_review_file(path: &Path) -> Result<String, StorageError> {
+    let bytes = encryption::open(fs::read(path)?)?;
+    String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
 }
 
 /// Get the storage directory for review state (centralized).
@@ -39,6 +71,11 @@ fn get_storage_dir(repo_path: &Path) -> Result<PathBuf, StorageError> {
     Ok(central::get_repo_storage_dir(repo_path)?.join("reviews"))
 }
 
+/// Get the directory archived reviews are moved into (centralized).
+fn get_archive_dir(repo_path: &Path) -> Result<PathBuf, StorageError> {
+    Ok(central::get_repo_storage_dir(repo_path)?.join("archive"))
+}
+
 /// Path to the repo's stored default-comparison marker (`review use`).
 fn default_spec_path(repo_path: &Path) -> Result<PathBuf, StorageError> {
     Ok(central::get_repo_storage_dir(repo_path)?.join("default-spec"))
@@ -141,6 +178,25 @@ fn review_filename(ref_name: &str) -> String {
     format!("{}.json", central::sanitize_path_component(ref_name))
 }
 
+/// A fresh review, trusting what the repo's `.review.toml` says new reviews
+/// should (the built-in defaults when it doesn't say), and needing a second
+/// reviewer when it says so.
+fn new_review_state(
+    repo_path: &Path,
+    ref_name: &str,
+    base_override: Option<String>,
+) -> ReviewState {
+    let mut state = ReviewState::new(ref_name, base_override);
+    let config = RepoConfig::load(repo_path).unwrap_or_default();
+    if let Some(trust) = config.trust {
+        state.trust_list = trust;
+    }
+    if config.two_person {
+        state.two_person = Some(TwoPersonReview::default());
+    }
+    state
+}
+
 /// Load review state for a ref.
 pub fn load_review_state(repo_path: &Path, ref_name: &str) -> Result<ReviewState, StorageError> {
     let storage_dir = get_storage_dir(repo_path)?;
@@ -148,12 +204,11 @@ pub fn load_review_state(repo_path: &Path, ref_name: &str) -> Result<ReviewState
     let path = storage_dir.join(&filename);
 
     if path.exists() {
-        let content = fs::read_to_string(&path)?;
-        let state = deserialize_review(&content)?;
+        let state = deserialize_review(&read_review_file(&path)?)?;
         Ok(state)
     } else {
         // Return a new empty state (not persisted — call ensure_review_exists for that)
-        Ok(ReviewState::new(ref_name, None))
+        Ok(new_review_state(repo_path, ref_name, None))
     }
 }
 
@@ -175,8 +230,9 @@ pub fn save_review_state(repo_path: &Path, state: &ReviewState) -> Result<(), St
     let path = storage_dir.join(&filename);
 
     // Check for version conflict if the file exists.
+    let mut corrections = Vec::new();
     if path.exists() {
-        let existing_content = fs::read_to_string(&path)?;
+        let existing_content = read_review_file(&path)?;
         // An existing file we can't read is a hard conflict, never silently
         // overwritten: it may be a newer schema or genuinely corrupt, and
         // clobbering it would be the data loss the loud-load path prevents.
@@ -192,10 +248,20 @@ pub fn save_review_state(repo_path: &Path, state: &ReviewState) -> Result<(), St
                 });
             }
         }
+        corrections = feedback::label_corrections(&existing_state, state);
     }
 
     let content = serde_json::to_string_pretty(state)?;
-    fs::write(&path, content)?;
+    fs::write(
+        &path,
+        encryption::seal_for(repo_path, &state.ref_name, content.into_bytes())?,
+    )?;
+
+    // The correction log is a side channel: failing to append to it must not
+    // fail the save that has already landed.
+    if let Err(e) = feedback::record_corrections(repo_path, &corrections) {
+        log::warn!("[save_review_state] Failed to record label corrections: {e}");
+    }
 
     Ok(())
 }
@@ -216,7 +282,7 @@ pub fn list_saved_reviews(repo_path: &Path) -> Result<Vec<ReviewSummary>, Storag
 
         // Only process .json files
         if path.extension().is_some_and(|ext| ext == "json") {
-            match fs::read_to_string(&path) {
+            match read_review_file(&path) {
                 Ok(content) => match deserialize_review(&content) {
                     Ok(state) => {
                         summaries.push(state.to_summary());
@@ -261,7 +327,7 @@ pub fn ensure_review_exists(
     let path = storage_dir.join(&filename);
 
     if !path.exists() {
-        let mut state = ReviewState::new(ref_name, base_override);
+        let mut state = new_review_state(repo_path, ref_name, base_override);
         state.github_pr = github_pr;
         save_review_state(repo_path, &state)?;
     }
@@ -289,10 +355,9 @@ pub fn set_base_override(
     let path = storage_dir.join(&filename);
 
     let mut state = if path.exists() {
-        let content = fs::read_to_string(&path)?;
-        deserialize_review(&content)?
+        deserialize_review(&read_review_file(&path)?)?
     } else {
-        ReviewState::new(ref_name, None)
+        new_review_state(repo_path, ref_name, None)
     };
 
     state.base_override = base_override;
@@ -302,7 +367,7 @@ pub fn set_base_override(
     Ok(())
 }
 
-/// Delete a saved review
+/// Delete a saved review, including any archived copy.
 pub fn delete_review(repo_path: &Path, ref_name: &str) -> Result<(), StorageError> {
     let storage_dir = get_storage_dir(repo_path)?;
     let filename = review_filename(ref_name);
@@ -312,16 +377,348 @@ pub fn delete_review(repo_path: &Path, ref_name: &str) -> Result<(), StorageErro
         fs::remove_file(&path)?;
     }
 
+    let archived = get_archive_dir(repo_path)?.join(archive_filename(ref_name));
+    if archived.exists() {
+        fs::remove_file(&archived)?;
+    }
+
     Ok(())
 }
 
+/// Generate a filename for an archived (gzip-compressed) review.
+fn archive_filename(ref_name: &str) -> String {
+    format!("{}.gz", review_filename(ref_name))
+}
+
+fn read_archived(path: &Path) -> Result<ReviewState, StorageError> {
+    let mut bytes = Vec::new();
+    GzDecoder::new(fs::File::open(path)?).read_to_end(&mut bytes)?;
+    let content = String::from_utf8(encryption::open(bytes)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    deserialize_review(&content)
+}
+
+/// Write `state` gzip-compressed to `archive_path`, via a temp file and
+/// rename so a crash mid-write never leaves a truncated archive.
+fn write_archived(
+    repo_path: &Path,
+    archive_path: &Path,
+    state: &ReviewState,
+) -> Result<(), StorageError> {
+    let content = encryption::seal_for(
+        repo_path,
+        &state.ref_name,
+        serde_json::to_string(state)?.into_bytes(),
+    )?;
+    let tmp_path = archive_path.with_extension("gz.tmp");
+    let mut encoder = GzEncoder::new(fs::File::create(&tmp_path)?, Compression::default());
+    encoder.write_all(&content)?;
+    encoder.finish()?.sync_all()?;
+    fs::rename(&tmp_path, archive_path)?;
+    Ok(())
+}
+
+/// Archive a saved review: stamp `archived_at`, write it gzip-compressed into
+/// the repo's `archive/` area, and remove the live file. Unlike delete, the
+/// review's decisions survive and [`unarchive_review`] restores them. Archived
+/// reviews drop out of [`list_saved_reviews`] (and so the sidebar and freshness
+/// checks) because their live file is gone. Fails if the ref already has an
+/// archived review (one restarted since it was archived), rather than
+/// overwriting it.
+pub fn archive_review(repo_path: &Path, ref_name: &str) -> Result<ReviewState, StorageError> {
+    let path = get_storage_dir(repo_path)?.join(review_filename(ref_name));
+    if !path.exists() {
+        return Err(StorageError::NotFound(ref_name.to_owned()));
+    }
+    let archive_dir = get_archive_dir(repo_path)?;
+    let archive_path = archive_dir.join(archive_filename(ref_name));
+    if archive_path.exists() {
+        return Err(StorageError::AlreadyArchived(ref_name.to_owned()));
+    }
+    let mut state = deserialize_review(&read_review_file(&path)?)?;
+    state.archived_at = Some(super::state::now_iso8601());
+
+    fs::create_dir_all(&archive_dir)?;
+    // Written in full before the live file is removed.
+    write_archived(repo_path, &archive_path, &state)?;
+
+    fs::remove_file(&path)?;
+    Ok(state)
+}
+
+/// Restore an archived review to live storage, clearing `archived_at`. Fails
+/// if a live review for the same ref has been started since it was archived,
+/// rather than silently overwriting either.
+pub fn unarchive_review(repo_path: &Path, ref_name: &str) -> Result<ReviewState, StorageError> {
+    let archive_path = get_archive_dir(repo_path)?.join(archive_filename(ref_name));
+    if !archive_path.exists() {
+        return Err(StorageError::NotFound(ref_name.to_owned()));
+    }
+    if review_exists(repo_path, ref_name)? {
+        return Err(StorageError::AlreadyExists(ref_name.to_owned()));
+    }
+    let mut state = read_archived(&archive_path)?;
+    state.archived_at = None;
+    // A fresh save (version 0) — there's no live file to conflict with.
+    state.version = 0;
+    save_review_state(repo_path, &state)?;
+    fs::remove_file(&archive_path)?;
+    Ok(state)
+}
+
+/// Whether the review for `ref_name` is archived (and not since restarted).
+pub fn is_review_archived(repo_path: &Path, ref_name: &str) -> Result<bool, StorageError> {
+    if review_exists(repo_path, ref_name)? {
+        return Ok(false);
+    }
+    Ok(get_archive_dir(repo_path)?
+        .join(archive_filename(ref_name))
+        .exists())
+}
+
+/// Load the archived review for `ref_name`.
+pub fn load_archived_review(repo_path: &Path, ref_name: &str) -> Result<ReviewState, StorageError> {
+    let archive_path = get_archive_dir(repo_path)?.join(archive_filename(ref_name));
+    if !archive_path.exists() {
+        return Err(StorageError::NotFound(ref_name.to_owned()));
+    }
+    read_archived(&archive_path)
+}
+
+/// Rewrite `ref_name`'s live and archived files after its encryption changes
+/// (see [`encryption`]): each is read back as it was and written out sealed
+/// or in plaintext, per the review's current setting.
+pub(crate) fn rewrite_review_files(repo_path: &Path, ref_name: &str) -> Result<(), StorageError> {
+    let path = get_storage_dir(repo_path)?.join(review_filename(ref_name));
+    if path.exists() {
+        let content = read_review_file(&path)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(
+            &tmp_path,
+            encryption::seal_for(repo_path, ref_name, content.into_bytes())?,
+        )?;
+        fs::rename(&tmp_path, &path)?;
+    }
+    let archive_path = get_archive_dir(repo_path)?.join(archive_filename(ref_name));
+    if archive_path.exists() {
+        let state = read_archived(&archive_path)?;
+        write_archived(repo_path, &archive_path, &state)?;
+    }
+    Ok(())
+}
+
+/// List archived reviews in the repository, most recently updated first.
+pub fn list_archived_reviews(repo_path: &Path) -> Result<Vec<ReviewSummary>, StorageError> {
+    let archive_dir = get_archive_dir(repo_path)?;
+    if !archive_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut summaries = Vec::new();
+    for entry in fs::read_dir(&archive_dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "gz") {
+            continue;
+        }
+        match read_archived(&path) {
+            Ok(state) => summaries.push(state.to_summary()),
+            Err(e) => log::debug!(
+                "[list_archived_reviews] Skipping unreadable archive {}: {e}",
+                path.display()
+            ),
+        }
+    }
+    summaries.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    Ok(summaries)
+}
+
+/// Prefix of the ids of annotations imported from GitHub review threads; the
+/// rest is the comment's node id, so a re-sync updates rather than duplicates.
+pub const GITHUB_ANNOTATION_PREFIX: &str = "github:";
+
+/// Saves [`import_pr_threads`] retries after a version conflict.
+const MAX_IMPORT_RETRIES: usize = 3;
+
+/// What importing a PR's review threads did.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadSync {
+    /// Comments added to the review.
+    pub added: usize,
+    /// Imported comments whose text or resolution changed on GitHub.
+    pub updated: usize,
+    /// Imported comments since deleted on GitHub.
+    pub removed: usize,
+    /// Threads not imported: outdated, or on a line no hunk covers.
+    pub unanchored: usize,
+}
+
+/// Where `thread` sits in `hunks`: its side and first and last lines, when a
+/// hunk of its file covers its line on that side.
+fn thread_anchor(
+    thread: &PrReviewThread,
+    hunks: &[DiffHunk],
+) -> Option<(AnnotationSide, u32, u32)> {
+    let line = thread.line.filter(|_| !thread.is_outdated)?;
+    let side = if thread.diff_side == "LEFT" {
+        AnnotationSide::Old
+    } else {
+        AnnotationSide::New
+    };
+    let covered = hunks.iter().any(|hunk| {
+        let (start, count) = match side {
+            AnnotationSide::Old => (hunk.old_start, hunk.old_count),
+            _ => (hunk.new_start, hunk.new_count),
+        };
+        hunk.file_path == thread.path && (start..start + count).contains(&line)
+    });
+    let start = thread
+        .start_line
+        .filter(|start| *start < line)
+        .unwrap_or(line);
+    covered.then_some((side, start, line))
+}
+
+/// The annotation mirroring one of `thread`'s comments.
+fn thread_annotation(
+    thread: &PrReviewThread,
+    comment: &PrReviewComment,
+    (side, start, end): (AnnotationSide, u32, u32),
+) -> LineAnnotation {
+    LineAnnotation {
+        id: format!("{GITHUB_ANNOTATION_PREFIX}{}", comment.id),
+        file_path: thread.path.clone(),
+        line_number: start,
+        end_line_number: (end != start).then_some(end),
+        side,
+        content: comment.body.clone(),
+        created_at: comment.created_at.clone(),
+        author: Some(comment.author.login.clone()),
+        source: Some(Source::Github),
+        updated_at: (comment.updated_at != comment.created_at).then(|| comment.updated_at.clone()),
+        resolved_at: None,
+        resolved_by: None,
+    }
+}
+
+/// Merge a PR's review threads into `state`'s annotations, each comment
+/// anchored on the line of `hunks` (the PR's diff) its thread is on.
+///
+/// Comments already imported keep their anchor when their thread has since
+/// gone outdated — the feedback still reads next to the hunk it was left on —
+/// and follow GitHub's text and resolution. Imported comments deleted on
+/// GitHub are dropped; annotations left locally are never touched.
+pub fn merge_pr_threads(
+    state: &mut ReviewState,
+    threads: &[PrReviewThread],
+    hunks: &[DiffHunk],
+) -> ThreadSync {
+    let mut sync = ThreadSync::default();
+    let mut live = std::collections::HashSet::new();
+    for thread in threads {
+        let anchor = thread_anchor(thread, hunks);
+        let resolved_by = thread
+            .resolved_by
+            .as_ref()
+            .map(|author| author.login.clone());
+        let mut imported = false;
+        for comment in &thread.comments {
+            let id = format!("{GITHUB_ANNOTATION_PREFIX}{}", comment.id);
+            live.insert(id.clone());
+            if let Some(existing) = state.annotations.iter_mut().find(|a| a.id == id) {
+                let mut next = match anchor {
+                    Some(anchor) => thread_annotation(thread, comment, anchor),
+                    None => LineAnnotation {
+                        content: comment.body.clone(),
+                        ..existing.clone()
+                    },
+                };
+                next.resolved_at = thread
+                    .is_resolved
+                    .then(|| existing.resolved_at.clone().unwrap_or_else(now_iso8601));
+                next.resolved_by = if thread.is_resolved {
+                    resolved_by.clone()
+                } else {
+                    None
+                };
+                let changed = next.content != existing.content
+                    || next.resolved_at.is_some() != existing.resolved_at.is_some();
+                *existing = next;
+                sync.updated += usize::from(changed);
+                imported = true;
+            } else if let Some(anchor) = anchor {
+                let mut annotation = thread_annotation(thread, comment, anchor);
+                if thread.is_resolved {
+                    annotation.resolved_at = Some(now_iso8601());
+                    annotation.resolved_by.clone_from(&resolved_by);
+                }
+                state.annotations.push(annotation);
+                sync.added += 1;
+                imported = true;
+            }
+        }
+        sync.unanchored += usize::from(!imported);
+    }
+    let before = state.annotations.len();
+    state
+        .annotations
+        .retain(|a| !a.id.starts_with(GITHUB_ANNOTATION_PREFIX) || live.contains(&a.id));
+    sync.removed = before - state.annotations.len();
+    sync
+}
+
+/// Merge `threads` into `ref_name`'s review (see [`merge_pr_threads`]) and
+/// save it, reapplying when another writer saved in between.
+pub fn import_pr_threads(
+    repo_path: &Path,
+    ref_name: &str,
+    threads: &[PrReviewThread],
+    hunks: &[DiffHunk],
+) -> Result<ThreadSync, StorageError> {
+    let mut attempt = 0;
+    loop {
+        let mut state = load_review_state(repo_path, ref_name)?;
+        let sync = merge_pr_threads(&mut state, threads, hunks);
+        if sync.added + sync.updated + sync.removed == 0 {
+            return Ok(sync);
+        }
+        state.prepare_for_save();
+        match save_review_state(repo_path, &state) {
+            Err(StorageError::VersionConflict { .. }) if attempt + 1 < MAX_IMPORT_RETRIES => {
+                attempt += 1;
+            }
+            result => return result.map(|()| sync),
+        }
+    }
+}
+
+/// Pull the review threads of `ref_name`'s pull request (or PR `number`, for
+/// a review that doesn't know it) from GitHub and import them as annotations
+/// on its hunks, so earlier reviewers' feedback shows up on re-review.
+pub fn sync_pr_threads(
+    repo_path: &Path,
+    ref_name: &str,
+    number: Option<u32>,
+) -> Result<ThreadSync, StorageError> {
+    let number = match number {
+        Some(number) => number,
+        None => load_review_state(repo_path, ref_name)?
+            .github_pr
+            .map(|pr| pr.number)
+            .ok_or_else(|| StorageError::NotPullRequest(ref_name.to_owned()))?,
+    };
+    let provider = GitHubClient::for_repo(repo_path.to_path_buf());
+    let hunks = parse_multi_file_diff(&provider.get_pull_request_diff(number)?);
+    let threads = provider.list_review_threads(number)?;
+    import_pr_threads(repo_path, ref_name, &threads, &hunks)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::review::central::tests::ENV_LOCK;
-    use crate::review::state::{
-        AnnotationSide, Attributed, HunkState, LineAnnotation, Source, REVIEW_SCHEMA_VERSION,
-    };
+    use crate::review::state::{Attributed, HunkState, HunkStatus, Source, REVIEW_SCHEMA_VERSION};
+    use crate::sources::github::PrAuthor;
     use tempfile::TempDir;
 
     /// The ref a test review is keyed by.
@@ -376,6 +773,7 @@ mod tests {
                     value: vec!["imports:added".to_string()],
                     source: Source::Static,
                     reasoning: Some("Added import".to_string()),
+                    at: None,
                 }),
                 ..Default::default()
             },
@@ -459,6 +857,75 @@ mod tests {
         assert_eq!(legacy.resolved_by, None);
     }
 
+    fn thread(path: &str, line: u32, outdated: bool, comment_id: &str) -> PrReviewThread {
+        PrReviewThread {
+            path: path.to_owned(),
+            line: (!outdated).then_some(line),
+            start_line: None,
+            diff_side: "RIGHT".to_owned(),
+            is_resolved: false,
+            is_outdated: outdated,
+            resolved_by: None,
+            comments: vec![PrReviewComment {
+                id: comment_id.to_owned(),
+                author: PrAuthor {
+                    login: "lee".to_owned(),
+                },
+                body: "Why retry here?".to_owned(),
+                created_at: "2026-01-01T00:00:00Z".to_owned(),
+                updated_at: "2026-01-01T00:00:00Z".to_owned(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_merge_pr_threads_anchors_on_hunks() {
+        let hunks = parse_multi_file_diff(
+            "diff --git a/src/a.rs b/src/a.rs\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+             @@ -1,2 +1,3 @@\n fn a() {\n+    retry();\n }\n",
+        );
+        let mut state = ReviewState::new(TEST_REF, None);
+        let local = thread_annotation(
+            &thread("src/a.rs", 1, false, "local"),
+            &thread("src/a.rs", 1, false, "local").comments[0],
+            (AnnotationSide::New, 1, 1),
+        );
+        state.annotations.push(LineAnnotation {
+            id: "src/a.rs:1:new:t1-0".to_owned(),
+            ..local.clone()
+        });
+        state.annotations.push(LineAnnotation {
+            id: format!("{GITHUB_ANNOTATION_PREFIX}C_gone"),
+            ..local
+        });
+
+        let mut threads = vec![
+            thread("src/a.rs", 2, false, "C_1"),
+            thread("src/a.rs", 2, true, "C_2"),
+            thread("src/b.rs", 2, false, "C_3"),
+        ];
+        let sync = merge_pr_threads(&mut state, &threads, &hunks);
+        assert_eq!((sync.added, sync.removed, sync.unanchored), (1, 1, 2));
+        let ids: Vec<&str> = state.annotations.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["src/a.rs:1:new:t1-0", "github:C_1"]);
+        assert_eq!(state.annotations[1].line_number, 2);
+        assert!(matches!(state.annotations[1].source, Some(Source::Github)));
+
+        // Resolved on GitHub, and since outdated: the anchor stays put.
+        threads[0].is_resolved = true;
+        threads[0].is_outdated = true;
+        threads[0].line = None;
+        threads[0].resolved_by = Some(PrAuthor {
+            login: "kim".to_owned(),
+        });
+        let sync = merge_pr_threads(&mut state, &threads, &hunks);
+        assert_eq!((sync.added, sync.updated, sync.unanchored), (0, 1, 2));
+        let imported = &state.annotations[1];
+        assert_eq!(imported.line_number, 2);
+        assert!(imported.resolved_at.is_some());
+        assert_eq!(imported.resolved_by.as_deref(), Some("kim"));
+    }
+
     #[test]
     fn test_list_saved_reviews_empty() {
         let _lock = ENV_LOCK.lock().unwrap();
@@ -561,6 +1028,32 @@ mod tests {
         assert_eq!(loaded.schema_version, REVIEW_SCHEMA_VERSION);
     }
 
+    #[test]
+    fn stored_decisions_without_a_time_stay_undated() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (temp_dir, _review_home) = create_test_repo();
+        let repo_path = temp_dir.path().to_path_buf();
+        let approved = || HunkState {
+            status: Some(Attributed::new(HunkStatus::Approved, Source::Cli)),
+            ..Default::default()
+        };
+
+        // As written before decisions recorded a time.
+        let mut state = ReviewState::new(TEST_REF, None);
+        state.hunks.insert("a.rs:1".to_owned(), approved());
+        save_review_state(&repo_path, &state).unwrap();
+
+        let mut state = load_review_state(&repo_path, TEST_REF).unwrap();
+        state.hunks.insert("b.rs:2".to_owned(), approved());
+        state.prepare_for_save();
+        save_review_state(&repo_path, &state).unwrap();
+
+        let loaded = load_review_state(&repo_path, TEST_REF).unwrap();
+        let at = |id: &str| loaded.hunks[id].status.as_ref().unwrap().at.clone();
+        assert_eq!(at("a.rs:1").as_deref(), Some(""));
+        assert_eq!(at("b.rs:2"), Some(loaded.updated_at.clone()));
+    }
+
     #[test]
     fn test_load_rejects_newer_schema() {
         let _lock = ENV_LOCK.lock().unwrap();
@@ -638,4 +1131,58 @@ mod tests {
         let result = delete_review(&repo_path, TEST_REF);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_archive_and_unarchive_roundtrip() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (temp_dir, _review_home) = create_test_repo();
+        let repo_path = temp_dir.path().to_path_buf();
+
+        let mut state = ReviewState::new(TEST_REF, None);
+        state.notes = "keep me".to_owned();
+        save_review_state(&repo_path, &state).unwrap();
+
+        let archived = archive_review(&repo_path, TEST_REF).unwrap();
+        assert!(archived.archived_at.is_some());
+        assert!(list_saved_reviews(&repo_path).unwrap().is_empty());
+        assert!(is_review_archived(&repo_path, TEST_REF).unwrap());
+        let listed = list_archived_reviews(&repo_path).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].archived_at.is_some());
+
+        let restored = unarchive_review(&repo_path, TEST_REF).unwrap();
+        assert!(restored.archived_at.is_none());
+        assert_eq!(
+            load_review_state(&repo_path, TEST_REF).unwrap().notes,
+            "keep me"
+        );
+        assert!(!is_review_archived(&repo_path, TEST_REF).unwrap());
+        assert!(list_archived_reviews(&repo_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_archive_and_unarchive_refuse_to_clobber() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (temp_dir, _review_home) = create_test_repo();
+        let repo_path = temp_dir.path().to_path_buf();
+
+        save_review_state(&repo_path, &ReviewState::new(TEST_REF, None)).unwrap();
+        archive_review(&repo_path, TEST_REF).unwrap();
+        save_review_state(&repo_path, &ReviewState::new(TEST_REF, None)).unwrap();
+
+        let result = unarchive_review(&repo_path, TEST_REF);
+        assert!(matches!(result, Err(StorageError::AlreadyExists(_))));
+        // Archiving the restarted review would overwrite the earlier archive.
+        let result = archive_review(&repo_path, TEST_REF);
+        assert!(matches!(result, Err(StorageError::AlreadyArchived(_))));
+        assert!(review_exists(&repo_path, TEST_REF).unwrap());
+        assert!(matches!(
+            archive_review(&repo_path, "missing"),
+            Err(StorageError::NotFound(_))
+        ));
+
+        // Delete removes the archived copy along with the live one.
+        delete_review(&repo_path, TEST_REF).unwrap();
+        assert!(list_archived_reviews(&repo_path).unwrap().is_empty());
+    }
 }
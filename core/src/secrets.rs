@@ -0,0 +1,445 @@
+//! Secret storage (API keys, server tokens) in the OS keychain.
+//!
+//! Each platform's keychain is driven through its standard command-line
+//! tool, so no native bindings are needed:
+//! - macOS: the login keychain, via `security`.
+//! - Linux: the Secret Service (GNOME Keyring, KWallet), via `secret-tool`.
+//! - Windows: DPAPI, via PowerShell. DPAPI encrypts rather than stores, so
+//!   the encrypted blob is kept in the fallback file.
+//!
+//! Where no keychain is available (headless Linux, CI), or with
+//! `REVIEW_SECRETS_BACKEND=file`, secrets fall back to `~/.review/secrets.json`,
+//! readable only by the owner. [`migrate_plaintext_secrets`] moves plaintext
+//! entries from that file into the keychain once one is available.
+//!
+//! Secret values are never passed on a command line, where other users could
+//! read them from the process list.
+
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use thiserror::Error;
+
+use crate::review::central::{self, get_central_root};
+
+/// Keychain service name every secret is stored under.
+const SERVICE: &str = "review";
+
+/// Prefix of a DPAPI-encrypted value in the fallback file (Windows).
+const DPAPI_PREFIX: &str = "dpapi:";
+
+#[derive(Error, Debug)]
+pub enum SecretsError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Central storage error: {0}")]
+    Central(#[from] central::CentralError),
+    /// The keychain tool is missing or has no keychain to talk to.
+    #[error("No keychain available: {0}")]
+    Unavailable(String),
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+}
+
+/// Where secrets are stored on this machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Keychain,
+    File,
+}
+
+fn backend() -> Backend {
+    match std::env::var("REVIEW_SECRETS_BACKEND").as_deref() {
+        Ok("file") => Backend::File,
+        _ => Backend::Keychain,
+    }
+}
+
+// --- Fallback file ---
+
+fn secrets_path() -> Result<PathBuf, SecretsError> {
+    Ok(get_central_root()?.join("secrets.json"))
+}
+
+fn read_file() -> Result<BTreeMap<String, String>, SecretsError> {
+    let path = secrets_path()?;
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Atomically write the fallback file, readable only by the owner.
+fn write_file(secrets: &BTreeMap<String, String>) -> Result<(), SecretsError> {
+    let path = secrets_path()?;
+    if secrets.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path)?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(serde_json::to_string_pretty(secrets)?.as_bytes())?;
+    drop(file);
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+// --- Keychain ---
+
+/// Run a keychain tool, feeding `stdin` to it. A tool that can't be run at
+/// all means there's no keychain.
+fn run_tool(
+    command: &mut Command,
+    stdin: Option<&str>,
+) -> Result<std::process::Output, SecretsError> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| SecretsError::Unavailable(format!("{program}: {e}")))?;
+    if let Some(mut pipe) = child.stdin.take() {
+        if let Some(input) = stdin {
+            pipe.write_all(input.as_bytes())?;
+        }
+    }
+    Ok(child.wait_with_output()?)
+}
+
+fn tool_error(output: &std::process::Output) -> String {
+    String::from_utf8_lossy(&output.stderr).trim().to_owned()
+}
+
+#[cfg(target_os = "macos")]
+mod keychain {
+    use super::{run_tool, tool_error, Command, SecretsError, SERVICE};
+
+    /// Quote an argument for `security -i`, which reads commands from stdin.
+    fn quote(arg: &str) -> String {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>, SecretsError> {
+        let output = run_tool(
+            Command::new("security").args([
+                "find-generic-password",
+                "-s",
+                SERVICE,
+                "-a",
+                name,
+                "-w",
+            ]),
+            None,
+        )?;
+        // 44: errSecItemNotFound
+        match output.status.code() {
+            Some(0) => Ok(Some(
+                String::from_utf8_lossy(&output.stdout)
+                    .trim_end_matches('\n')
+                    .to_owned(),
+            )),
+            Some(44) => Ok(None),
+            _ => Err(SecretsError::Keychain(tool_error(&output))),
+        }
+    }
+
+    pub fn set(name: &str, value: &str) -> Result<(), SecretsError> {
+        let script = format!(
+            "add-generic-password -U -s {} -a {} -w {}\n",
+            quote(SERVICE),
+            quote(name),
+            quote(value)
+        );
+        let output = run_tool(Command::new("security").arg("-i"), Some(&script))?;
+        if output.status.success() && output.stderr.is_empty() {
+            Ok(())
+        } else {
+            Err(SecretsError::Keychain(tool_error(&output)))
+        }
+    }
+
+    pub fn delete(name: &str) -> Result<(), SecretsError> {
+        let output = run_tool(
+            Command::new("security").args(["delete-generic-password", "-s", SERVICE, "-a", name]),
+            None,
+        )?;
+        match output.status.code() {
+            Some(0 | 44) => Ok(()),
+            _ => Err(SecretsError::Keychain(tool_error(&output))),
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod keychain {
+    use super::{run_tool, tool_error, Command, SecretsError, SERVICE};
+
+    fn attributes(name: &str) -> [&str; 4] {
+        ["service", SERVICE, "account", name]
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>, SecretsError> {
+        let output = run_tool(
+            Command::new("secret-tool")
+                .arg("lookup")
+                .args(attributes(name)),
+            None,
+        )?;
+        if output.status.success() {
+            return Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()));
+        }
+        // A missing item exits 1 with no message; anything else (no D-Bus
+        // session, no Secret Service provider) means there's no keychain.
+        let stderr = tool_error(&output);
+        if stderr.is_empty() {
+            Ok(None)
+        } else {
+            Err(SecretsError::Unavailable(stderr))
+        }
+    }
+
+    pub fn set(name: &str, value: &str) -> Result<(), SecretsError> {
+        let label = format!("Review: {name}");
+        let output = run_tool(
+            Command::new("secret-tool")
+                .args(["store", "--label", &label])
+                .args(attributes(name)),
+            Some(value),
+        )?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(SecretsError::Unavailable(tool_error(&output)))
+        }
+    }
+
+    pub fn delete(name: &str) -> Result<(), SecretsError> {
+        let output = run_tool(
+            Command::new("secret-tool")
+                .arg("clear")
+                .args(attributes(name)),
+            None,
+        )?;
+        let stderr = tool_error(&output);
+        if output.status.success() || stderr.is_empty() {
+            Ok(())
+        } else {
+            Err(SecretsError::Unavailable(stderr))
+        }
+    }
+}
+
+#[cfg(windows)]
+mod keychain {
+    use super::{read_file, run_tool, tool_error, write_file, Command, SecretsError, DPAPI_PREFIX};
+
+    /// Run a PowerShell snippet with `value` in `$env:REVIEW_SECRET`.
+    fn powershell(script: &str, value: &str) -> Result<String, SecretsError> {
+        let output = run_tool(
+            Command::new("powershell")
+                .args(["-NoProfile", "-NonInteractive", "-Command", script])
+                .env("REVIEW_SECRET", value),
+            None,
+        )?;
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        } else {
+            Err(SecretsError::Keychain(tool_error(&output)))
+        }
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>, SecretsError> {
+        let Some(blob) = read_file()?
+            .remove(name)
+            .and_then(|v| v.strip_prefix(DPAPI_PREFIX).map(str::to_owned))
+        else {
+            return Ok(None);
+        };
+        powershell(
+            "$s = ConvertTo-SecureString $env:REVIEW_SECRET; \
+             [Runtime.InteropServices.Marshal]::PtrToStringBSTR(\
+             [Runtime.InteropServices.Marshal]::SecureStringToBSTR($s))",
+            &blob,
+        )
+        .map(Some)
+    }
+
+    pub fn set(name: &str, value: &str) -> Result<(), SecretsError> {
+        let blob = powershell(
+            "ConvertTo-SecureString $env:REVIEW_SECRET -AsPlainText -Force | ConvertFrom-SecureString",
+            value,
+        )?;
+        let mut secrets = read_file()?;
+        secrets.insert(name.to_owned(), format!("{DPAPI_PREFIX}{blob}"));
+        write_file(&secrets)
+    }
+
+    pub fn delete(name: &str) -> Result<(), SecretsError> {
+        let mut secrets = read_file()?;
+        if secrets
+            .get(name)
+            .is_some_and(|v| v.starts_with(DPAPI_PREFIX))
+        {
+            secrets.remove(name);
+            write_file(&secrets)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plaintext value of `name` in the fallback file, skipping encrypted blobs.
+fn file_get(name: &str) -> Result<Option<String>, SecretsError> {
+    Ok(read_file()?
+        .remove(name)
+        .filter(|v| !v.starts_with(DPAPI_PREFIX)))
+}
+
+fn file_set(name: &str, value: &str) -> Result<(), SecretsError> {
+    let mut secrets = read_file()?;
+    secrets.insert(name.to_owned(), value.to_owned());
+    write_file(&secrets)
+}
+
+fn file_delete(name: &str) -> Result<(), SecretsError> {
+    let mut secrets = read_file()?;
+    if secrets.remove(name).is_some() {
+        write_file(&secrets)?;
+    }
+    Ok(())
+}
+
+/// Remove a plaintext copy of `name`, once it's safely in the keychain.
+fn drop_plaintext(name: &str) -> Result<(), SecretsError> {
+    if file_get(name)?.is_some() {
+        file_delete(name)?;
+    }
+    Ok(())
+}
+
+// --- Public API ---
+
+/// Read a secret, from the keychain or else the fallback file.
+pub fn get_secret(name: &str) -> Result<Option<String>, SecretsError> {
+    if backend() == Backend::Keychain {
+        match keychain::get(name) {
+            Ok(Some(value)) => return Ok(Some(value)),
+            Ok(None) | Err(SecretsError::Unavailable(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    file_get(name)
+}
+
+/// Store a secret in the keychain, falling back to the file when there's no
+/// keychain. Returns where it was stored.
+pub fn set_secret(name: &str, value: &str) -> Result<Backend, SecretsError> {
+    if backend() == Backend::Keychain {
+        match keychain::set(name, value) {
+            Ok(()) => {
+                // Don't leave a stale plaintext copy behind
+                drop_plaintext(name)?;
+                return Ok(Backend::Keychain);
+            }
+            Err(SecretsError::Unavailable(reason)) => {
+                warn!("[secrets] No keychain ({reason}); storing '{name}' in secrets.json");
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    file_set(name, value)?;
+    Ok(Backend::File)
+}
+
+/// Remove a secret from the keychain and the fallback file.
+pub fn delete_secret(name: &str) -> Result<(), SecretsError> {
+    if backend() == Backend::Keychain {
+        match keychain::delete(name) {
+            Ok(()) | Err(SecretsError::Unavailable(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    file_delete(name)
+}
+
+/// Move plaintext secrets from the fallback file into the keychain. Entries
+/// stay in the file if the keychain is unavailable. Returns how many moved.
+pub fn migrate_plaintext_secrets() -> Result<usize, SecretsError> {
+    if backend() != Backend::Keychain {
+        return Ok(0);
+    }
+    let plaintext: Vec<(String, String)> = read_file()?
+        .into_iter()
+        .filter(|(_, v)| !v.starts_with(DPAPI_PREFIX))
+        .collect();
+    let mut migrated = 0;
+    for (name, value) in plaintext {
+        match keychain::set(&name, &value) {
+            Ok(()) => {
+                drop_plaintext(&name)?;
+                migrated += 1;
+            }
+            Err(SecretsError::Unavailable(_)) => break,
+            Err(e) => warn!("[secrets] Failed to move '{name}' to the keychain: {e}"),
+        }
+    }
+    if migrated > 0 {
+        info!("[secrets] Moved {migrated} secret(s) from secrets.json to the keychain");
+    }
+    Ok(migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+
+    #[test]
+    fn file_backend_round_trips_privately() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, _repo) = setup_test();
+        std::env::set_var("REVIEW_SECRETS_BACKEND", "file");
+
+        assert_eq!(get_secret("github-token").unwrap(), None);
+        assert_eq!(
+            set_secret("github-token", "ghp_\"x\"").unwrap(),
+            Backend::File
+        );
+        assert_eq!(
+            get_secret("github-token").unwrap().as_deref(),
+            Some("ghp_\"x\"")
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(secrets_path().unwrap())
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Nothing to migrate to while the file backend is forced
+        assert_eq!(migrate_plaintext_secrets().unwrap(), 0);
+
+        delete_secret("github-token").unwrap();
+        assert_eq!(get_secret("github-token").unwrap(), None);
+        assert!(!secrets_path().unwrap().exists());
+        std::env::remove_var("REVIEW_SECRETS_BACKEND");
+    }
+}
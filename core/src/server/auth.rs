@@ -0,0 +1,200 @@
+//! Bearer-token auth for the endpoints that start work remotely (e.g. from a
+//! phone on the same network).
+//!
+//! The token is generated on first use and kept with the other secrets
+//! (see [`crate::secrets`]). Clients send it as `Authorization: Bearer
+//! <token>`; `EventSource` can't set headers, so a `token` query parameter is
+//! accepted too.
+//!
+//! Whether a request is local is decided per request (see [`is_local`]), not
+//! from the address the server is bound to: Tailscale Serve and Funnel, or any
+//! other reverse proxy, connect from loopback on behalf of remote clients.
+
+use axum::extract::{ConnectInfo, Request};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use std::net::SocketAddr;
+use std::sync::OnceLock;
+
+use crate::secrets::{self, SecretsError};
+
+/// Name of the token in secret storage.
+const TOKEN_SECRET: &str = "server-token";
+
+static TOKEN: OnceLock<String> = OnceLock::new();
+
+/// 256 bits from the OS random number generator, hex-encoded.
+fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// The server's token, created and stored on first use.
+pub fn server_token() -> Result<String, SecretsError> {
+    if let Some(token) = TOKEN.get() {
+        return Ok(token.clone());
+    }
+    let token = match secrets::get_secret(TOKEN_SECRET)? {
+        Some(token) if !token.is_empty() => token,
+        _ => {
+            let token = generate_token();
+            secrets::set_secret(TOKEN_SECRET, &token)?;
+            token
+        }
+    };
+    Ok(TOKEN.get_or_init(|| token).clone())
+}
+
+/// The token a request presents, from its `Authorization` header or `token`
+/// query parameter.
+fn presented_token(request: &Request) -> Option<String> {
+    let from_header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_owned());
+    from_header.or_else(|| {
+        request.uri().query()?.split('&').find_map(|pair| {
+            let value = pair.strip_prefix("token=")?;
+            urlencoding::decode(value)
+                .ok()
+                .map(std::borrow::Cow::into_owned)
+        })
+    })
+}
+
+/// Compare without short-circuiting on the first differing byte.
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Headers a reverse proxy adds to the requests it forwards. Tailscale Serve
+/// and Funnel set `X-Forwarded-For` and, for tailnet clients, the
+/// `Tailscale-User-*` identity headers.
+const PROXY_HEADERS: &[&str] = &[
+    "forwarded",
+    "x-forwarded-for",
+    "x-forwarded-host",
+    "x-forwarded-proto",
+    "x-real-ip",
+    "tailscale-user-login",
+    "tailscale-funnel-request",
+];
+
+/// Whether `request` came through a reverse proxy.
+fn is_proxied(request: &Request) -> bool {
+    PROXY_HEADERS
+        .iter()
+        .any(|name| request.headers().contains_key(*name))
+}
+
+/// Whether `request` comes straight from this machine: a loopback peer, with
+/// no sign of a proxy in between. Without the peer address (a router served
+/// without connect info), nothing is local.
+fn is_local(request: &Request) -> bool {
+    !is_proxied(request)
+        && request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .is_some_and(|ConnectInfo(peer)| peer.ip().is_loopback())
+}
+
+/// Middleware requiring the server token from every request that isn't
+/// [local](is_local).
+pub async fn require_token_unless_local(request: Request, next: Next) -> Response {
+    if is_local(&request) {
+        next.run(request).await
+    } else {
+        require_token(request, next).await
+    }
+}
+
+/// Middleware rejecting requests without the server token.
+pub async fn require_token(request: Request, next: Next) -> Response {
+    let expected = match server_token() {
+        Ok(token) => token,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Server token unavailable: {e}"),
+            )
+                .into_response()
+        }
+    };
+    match presented_token(&request) {
+        Some(token) if tokens_match(&token, &expected) => next.run(request).await,
+        _ => (StatusCode::UNAUTHORIZED, "Missing or invalid token").into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_token_from_header_or_query() {
+        let request = |uri: &str, auth: Option<&str>| {
+            let mut builder = Request::builder().uri(uri);
+            if let Some(auth) = auth {
+                builder = builder.header(header::AUTHORIZATION, auth);
+            }
+            builder.body(axum::body::Body::empty()).unwrap()
+        };
+        assert_eq!(
+            presented_token(&request("/api/jobs/trigger", Some("Bearer abc"))).as_deref(),
+            Some("abc")
+        );
+        assert_eq!(
+            presented_token(&request("/api/jobs/events?jobId=3&token=a%2Bb", None)).as_deref(),
+            Some("a+b")
+        );
+        assert_eq!(
+            presented_token(&request("/api/jobs/trigger", Some("Basic abc"))),
+            None
+        );
+
+        assert!(tokens_match("abc", "abc"));
+        assert!(!tokens_match("abd", "abc"));
+        assert!(!tokens_match("ab", "abc"));
+        assert_eq!(generate_token().len(), 64);
+        assert_ne!(generate_token(), generate_token());
+    }
+
+    #[test]
+    fn only_direct_loopback_requests_are_local() {
+        let request = |peer: Option<&str>, header: Option<(&str, &str)>| {
+            let mut builder = Request::builder().uri("/api/review/get");
+            if let Some((name, value)) = header {
+                builder = builder.header(name, value);
+            }
+            let mut request = builder.body(axum::body::Body::empty()).unwrap();
+            if let Some(peer) = peer {
+                let peer: SocketAddr = peer.parse().unwrap();
+                request.extensions_mut().insert(ConnectInfo(peer));
+            }
+            request
+        };
+        assert!(is_local(&request(Some("127.0.0.1:50000"), None)));
+        assert!(is_local(&request(Some("[::1]:50000"), None)));
+        assert!(!is_local(&request(Some("192.168.1.20:50000"), None)));
+        assert!(!is_local(&request(None, None)));
+        // Tailscale Serve connects from loopback but says who it's forwarding.
+        for header in [
+            ("X-Forwarded-For", "100.101.102.103"),
+            ("Tailscale-User-Login", "someone@example.com"),
+            ("Forwarded", "for=100.101.102.103"),
+        ] {
+            assert!(!is_local(&request(Some("127.0.0.1:50000"), Some(header))));
+        }
+    }
+}
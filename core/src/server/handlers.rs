@@ -1,24 +1,40 @@
 //! HTTP handlers for the Axum server.
 
 use axum::extract::{Json, Query};
-use axum::http::StatusCode;
+use axum::http::{header, HeaderMap, StatusCode};
 use axum::response::sse::{Event, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
 use axum::Router;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::convert::Infallible;
 use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, PoisonError};
 use std::time::Duration;
 
+use crate::classify::migrations::MigrationCheck;
 use crate::classify::{self, ClassifyResponse};
+use crate::diff::api_schema::FileSchemaDelta;
 use crate::diff::parser::{detect_move_pairs, DiffHunk};
-use crate::review::state::{ReviewState, ReviewSummary};
+use crate::limits::ReviewLimits;
+use crate::review::state::{ChecklistItem, ReviewState, ReviewSummary, Source};
 use crate::review::storage::{self, GlobalReviewSummary};
-use crate::service::watcher_events::{categorize_change, ChangeKind, GitChangedPayload};
+use crate::service::job_queue::{self, JobRequest};
+use crate::service::jobs::{JobKind, JobPriority};
+use crate::service::pr_review::PrReviewDraft;
+use crate::service::shortstats::{self, ShortStatRequest, ShortStatResult};
+use crate::service::watcher_events::{
+    categorize_change, is_worktree_state_path, linked_worktree_dirs, ChangeKind, GitChangedPayload,
+};
 use crate::service::*;
-use crate::sources::github::{GhCliProvider, GitHubPrRef, GitHubProvider, PullRequest};
+use crate::sources::azure::{AzureDevOpsProvider, AzurePrRef, AzurePullRequest};
+use crate::sources::github::{
+    GitHubClient, GitHubPrRef, GitHubProvider, PrReviewEvent, PullRequest,
+};
 use crate::sources::local_git::{
-    DiffShortStat, LocalGitSource, RemoteInfo, SearchMatch, WorktreeInfo,
+    DiffShortStat, GitRemote, LocalGitSource, RemoteInfo, SearchMatch, WorktreeInfo, DEEPEN_STEP,
 };
 use crate::sources::traits::{
     BranchList, CommitDetail, CommitEntry, Comparison, DiffSource, FileEntry, GitStatusSummary,
@@ -52,6 +68,9 @@ pub fn build_api_router() -> Router {
         .route("/api/git/user", post(git_user))
         .route("/api/git/remote-info", post(git_remote_info))
         .route("/api/git/fetch-origin", post(git_fetch_origin))
+        .route("/api/git/deepen", post(git_deepen))
+        .route("/api/git/remotes", post(git_remotes))
+        .route("/api/git/review-remote", post(git_set_review_remote))
         .route("/api/git/default-branch", post(git_default_branch))
         .route("/api/git/branches", post(git_branches))
         .route("/api/git/status", post(git_status))
@@ -60,12 +79,14 @@ pub fn build_api_router() -> Router {
         .route("/api/git/unstage-file", post(git_unstage_file))
         .route("/api/git/unstage-all", post(git_unstage_all))
         .route("/api/git/stage-hunks", post(git_stage_hunks))
+        .route("/api/git/stage-hunk-group", post(git_stage_hunk_group))
         .route("/api/git/unstage-hunks", post(git_unstage_hunks))
         .route("/api/git/commits", post(git_commits))
         .route("/api/git/commit-detail", post(git_commit_detail))
         .route("/api/git/hunk-attribution", post(git_hunk_attribution))
         .route("/api/git/diff", post(git_diff))
         .route("/api/git/diff-shortstat", post(git_diff_shortstat))
+        .route("/api/git/diff-shortstats", post(git_diff_shortstats))
         .route(
             "/api/git/working-tree-file-content",
             post(git_working_tree_file_content),
@@ -80,6 +101,11 @@ pub fn build_api_router() -> Router {
         // GitHub
         .route("/api/github/available", post(github_available))
         .route("/api/github/pull-requests", post(github_pull_requests))
+        .route("/api/github/review/draft", post(github_review_draft))
+        .route("/api/github/review/submit", post(github_review_submit))
+        .route("/api/github/review/threads", post(github_review_threads))
+        .route("/api/azure/available", post(azure_available))
+        .route("/api/azure/pull-requests", post(azure_pull_requests))
         // Files
         .route("/api/files/list", post(files_list))
         .route("/api/files/list-all", post(files_list_all))
@@ -90,7 +116,13 @@ pub fn build_api_router() -> Router {
         )
         .route("/api/files/content", post(files_content))
         .route("/api/files/all-hunks", post(files_all_hunks))
+        .route(
+            "/api/files/all-hunks-limited",
+            post(files_all_hunks_limited),
+        )
         .route("/api/files/expanded-context", post(files_expanded_context))
+        .route("/api/files/content-window", post(files_content_window))
+        .route("/api/files/diff-contents", post(files_diff_contents))
         .route("/api/files/search", post(files_search))
         .route("/api/files/read-raw", post(files_read_raw))
         .route("/api/files/raw-content", post(files_raw_content))
@@ -98,6 +130,7 @@ pub fn build_api_router() -> Router {
         // Review
         .route("/api/review/resolve", post(review_resolve))
         .route("/api/review/load", post(review_load))
+        .route("/api/review/delta", post(review_delta))
         .route("/api/review/reconcile", post(review_reconcile))
         .route("/api/review/save", post(review_save))
         .route("/api/review/list", post(review_list))
@@ -106,12 +139,37 @@ pub fn build_api_router() -> Router {
             post(review_set_base_override),
         )
         .route("/api/review/delete", post(review_delete))
+        .route("/api/review/archive", post(review_archive))
+        .route("/api/review/unarchive", post(review_unarchive))
+        .route("/api/review/list-archived", post(review_list_archived))
         .route("/api/review/exists", post(review_exists))
         .route("/api/review/ensure-exists", post(review_ensure_exists))
         .route("/api/review/list-global", post(review_list_global))
         .route("/api/review/root", post(review_root))
         .route("/api/review/storage-path", post(review_storage_path))
         .route("/api/review/freshness", post(review_freshness))
+        .route("/api/review/progress", post(review_progress))
+        .route("/api/review/timeline", post(review_timeline))
+        .route("/api/review/split", post(review_split))
+        .route("/api/review/submodules", post(review_submodules))
+        .route("/api/review/submodule-hunk", post(review_submodule_hunk))
+        .route("/api/review/checklist", post(review_checklist))
+        .route("/api/review/checklist/set", post(review_checklist_set))
+        .route("/api/review/two-person", post(review_two_person))
+        .route(
+            "/api/review/two-person/require",
+            post(review_two_person_require),
+        )
+        .route(
+            "/api/review/two-person/handoff",
+            post(review_two_person_handoff),
+        )
+        .route(
+            "/api/review/two-person/signoff",
+            post(review_two_person_signoff),
+        )
+        .route("/api/review/inbox", post(review_inbox))
+        .route("/api/review/activity", post(review_activity))
         // Classification
         .route("/api/classify/static", post(classify_static))
         .route("/api/classify/move-pairs", post(classify_move_pairs))
@@ -124,14 +182,20 @@ pub fn build_api_router() -> Router {
         .route("/api/symbols/definitions", post(symbols_definitions))
         .route("/api/symbols/file", post(symbols_file))
         .route("/api/symbols/repo", post(symbols_repo))
+        .route("/api/schemas/deltas", post(schemas_deltas))
+        .route("/api/migrations/checks", post(migrations_checks))
         // Activity
         .route("/api/activity/list", post(activity_list))
         .route("/api/activity/register", post(activity_register))
         .route("/api/activity/unregister", post(activity_unregister))
+        // Background jobs
+        .route("/api/jobs/queue", post(jobs_queue))
+        .route("/api/jobs/parallelism", post(jobs_set_parallelism))
         // Misc
         .route("/api/misc/is-git-repo", post(misc_is_git_repo))
         .route("/api/misc/path-is-file", post(misc_path_is_file))
         .route("/api/misc/vscode-theme", post(misc_vscode_theme))
+        .route("/api/misc/settings-schema", post(misc_settings_schema))
         .route("/api/misc/resolve-repo-path", post(misc_resolve_repo_path))
         // Streaming
         .route("/api/streaming/git-commit", post(streaming_git_commit))
@@ -141,6 +205,21 @@ pub fn build_api_router() -> Router {
         )
         // File watcher SSE
         .route("/api/events", get(events_sse))
+        // Remotely triggered jobs and companion reads (token required)
+        .merge(
+            Router::new()
+                .route("/api/jobs/trigger", post(jobs_trigger))
+                .route("/api/jobs/status", post(jobs_status))
+                .route("/api/jobs/events", get(jobs_events))
+                .route("/api/companion/symbols", post(companion_symbols))
+                .route("/api/companion/groups", post(companion_groups))
+                .route("/api/companion/graph", post(companion_graph))
+                .route("/api/companion/taxonomy", post(companion_taxonomy))
+                .route("/api/companion/trust", post(companion_trust))
+                .route("/api/companion/trust/add", post(companion_trust_add))
+                .route("/api/companion/trust/remove", post(companion_trust_remove))
+                .route_layer(axum::middleware::from_fn(super::auth::require_token)),
+        )
 }
 
 // ============================================================
@@ -173,6 +252,10 @@ struct GetFileContentRequest {
     file_path: String,
     comparison: Comparison,
     github_pr: Option<GitHubPrRef>,
+    azure_pr: Option<AzurePrRef>,
+    /// Regenerate the diff so each hunk carries its whole enclosing function.
+    #[serde(default)]
+    function_context: bool,
 }
 
 #[derive(Deserialize)]
@@ -181,6 +264,15 @@ struct GetAllHunksRequest {
     repo_path: String,
     comparison: Comparison,
     file_paths: Vec<String>,
+    /// Explicit opt-in to load everything, ignoring the review limits.
+    #[serde(default)]
+    unlimited: bool,
+    /// Replace each translation catalog's hunks with one key-level rollup hunk.
+    #[serde(default)]
+    collapse_translations: bool,
+    /// Files whose hunks each carry their whole enclosing function.
+    #[serde(default)]
+    function_context_files: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -194,6 +286,25 @@ struct ExpandedContextRequest {
     github_pr: Option<GitHubPrRef>,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentWindowRequest {
+    repo_path: String,
+    file_path: String,
+    comparison: Comparison,
+    side: ContentSide,
+    window: ContentWindow,
+    github_pr: Option<GitHubPrRef>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DiffContentsRequest {
+    old_text: String,
+    new_text: String,
+    path_hint: String,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SearchRequest {
@@ -209,6 +320,7 @@ struct ListFilesRequest {
     repo_path: String,
     comparison: Comparison,
     github_pr: Option<GitHubPrRef>,
+    azure_pr: Option<AzurePrRef>,
 }
 
 #[derive(Deserialize)]
@@ -248,6 +360,9 @@ struct ResolveReviewRequest {
 struct ReviewReconcileRequest {
     state: ReviewState,
     hunks: Vec<DiffHunk>,
+    /// Also approve hunks other reviews in this repo approved unchanged.
+    #[serde(default)]
+    repo_path: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -339,6 +454,7 @@ struct DiffRequest {
     repo_path: String,
     comparison: Comparison,
     github_pr: Option<GitHubPrRef>,
+    azure_pr: Option<AzurePrRef>,
 }
 
 #[derive(Deserialize)]
@@ -348,6 +464,11 @@ struct DiffShortStatRequest {
     comparison: Comparison,
 }
 
+#[derive(Deserialize)]
+struct DiffShortStatsRequest {
+    comparisons: Vec<ShortStatRequest>,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct StageFileRequest {
@@ -363,6 +484,15 @@ struct StageHunksRequest {
     content_hashes: Vec<String>,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StageHunkGroupRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    group_index: usize,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct WorkingTreeFileContentRequest {
@@ -419,6 +549,13 @@ struct WorktreeUpdateHeadRequest {
     commit_sha: String,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetReviewRemoteRequest {
+    repo_path: String,
+    remote: Option<String>,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ResolveRefRequest {
@@ -426,17 +563,29 @@ struct ResolveRefRequest {
     git_ref: String,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PrReviewRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    /// PR to submit to, for a review that wasn't started from one.
+    pr: Option<u32>,
+    event: Option<PrReviewEvent>,
+    message: Option<String>,
+}
+
 // ============================================================
 // Git handlers
 // ============================================================
 
 async fn git_current_repo() -> ApiResult<String> {
     blocking(|| {
-        // Walk up from cwd to find .git
+        // Walk up from cwd to find .git (or .jj)
         let cwd = std::env::current_dir()?;
         let mut current = cwd.as_path();
         loop {
-            if current.join(".git").exists() {
+            if crate::sources::jj::is_repo_root(current) {
                 return Ok(current.to_string_lossy().to_string());
             }
             match current.parent() {
@@ -480,6 +629,32 @@ async fn git_fetch_origin(Json(req): Json<RepoPathRequest>) -> ApiResult<()> {
     .await
 }
 
+async fn git_deepen(Json(req): Json<RepoPathRequest>) -> ApiResult<()> {
+    blocking(move || {
+        let source = LocalGitSource::new(PathBuf::from(&req.repo_path))?;
+        source.deepen(DEEPEN_STEP).map_err(Into::into)
+    })
+    .await
+}
+
+async fn git_remotes(Json(req): Json<RepoPathRequest>) -> ApiResult<Vec<GitRemote>> {
+    blocking(move || {
+        let source = LocalGitSource::new(PathBuf::from(&req.repo_path))?;
+        source.list_remotes().map_err(Into::into)
+    })
+    .await
+}
+
+async fn git_set_review_remote(Json(req): Json<SetReviewRemoteRequest>) -> ApiResult<()> {
+    blocking(move || {
+        let source = LocalGitSource::new(PathBuf::from(&req.repo_path))?;
+        source
+            .set_review_remote(req.remote.as_deref())
+            .map_err(Into::into)
+    })
+    .await
+}
+
 async fn git_default_branch(Json(req): Json<RepoPathRequest>) -> ApiResult<String> {
     blocking(move || {
         let source = LocalGitSource::new(PathBuf::from(&req.repo_path))?;
@@ -546,6 +721,17 @@ async fn git_stage_hunks(Json(req): Json<StageHunksRequest>) -> ApiResult<()> {
     .await
 }
 
+async fn git_stage_hunk_group(Json(req): Json<StageHunkGroupRequest>) -> ApiResult<Vec<String>> {
+    blocking(move || {
+        crate::service::staging::stage_hunk_group(
+            &PathBuf::from(&req.repo_path),
+            &req.ref_name,
+            req.group_index,
+        )
+    })
+    .await
+}
+
 async fn git_unstage_hunks(Json(req): Json<StageHunksRequest>) -> ApiResult<()> {
     blocking(move || {
         let source = LocalGitSource::new(PathBuf::from(&req.repo_path))?;
@@ -590,12 +776,17 @@ async fn git_commit_detail(Json(req): Json<CommitDetailRequest>) -> ApiResult<Co
 async fn git_diff(Json(req): Json<DiffRequest>) -> ApiResult<String> {
     blocking(move || {
         if let Some(ref pr) = req.github_pr {
-            let provider = GhCliProvider::new(PathBuf::from(&req.repo_path));
+            let provider = GitHubClient::for_repo(PathBuf::from(&req.repo_path));
             return provider
                 .get_pull_request_diff(pr.number)
                 .map_err(Into::into);
         }
         let source = LocalGitSource::new(PathBuf::from(&req.repo_path))?;
+        if let Some(ref pr) = req.azure_pr {
+            let provider = AzureDevOpsProvider::new(PathBuf::from(&req.repo_path));
+            let comparison = provider.comparison(pr)?;
+            return source.get_diff(&comparison, None).map_err(Into::into);
+        }
         source.get_diff(&req.comparison, None).map_err(Into::into)
     })
     .await
@@ -611,6 +802,12 @@ async fn git_diff_shortstat(Json(req): Json<DiffShortStatRequest>) -> ApiResult<
     .await
 }
 
+async fn git_diff_shortstats(
+    Json(req): Json<DiffShortStatsRequest>,
+) -> ApiResult<Vec<ShortStatResult>> {
+    blocking(move || Ok(shortstats::get_diff_shortstats(&req.comparisons))).await
+}
+
 async fn git_working_tree_file_content(
     Json(req): Json<WorkingTreeFileContentRequest>,
 ) -> ApiResult<FileContent> {
@@ -681,13 +878,67 @@ async fn git_resolve_ref(Json(req): Json<ResolveRefRequest>) -> ApiResult<String
 // ============================================================
 
 async fn github_available(Json(req): Json<RepoPathRequest>) -> Json<bool> {
-    let provider = GhCliProvider::new(PathBuf::from(&req.repo_path));
+    let provider = GitHubClient::for_repo(PathBuf::from(&req.repo_path));
     Json(provider.is_available())
 }
 
 async fn github_pull_requests(Json(req): Json<RepoPathRequest>) -> ApiResult<Vec<PullRequest>> {
     blocking(move || {
-        let provider = GhCliProvider::new(PathBuf::from(&req.repo_path));
+        let provider = GitHubClient::for_repo(PathBuf::from(&req.repo_path));
+        provider.list_pull_requests().map_err(Into::into)
+    })
+    .await
+}
+
+async fn github_review_draft(Json(req): Json<PrReviewRequest>) -> ApiResult<PrReviewDraft> {
+    blocking(move || {
+        crate::service::pr_review::draft_review(
+            &PathBuf::from(&req.repo_path),
+            &req.ref_name,
+            req.event,
+            req.message.as_deref(),
+        )
+        .map(|draft| PrReviewDraft {
+            number: req.pr.or(draft.number),
+            ..draft
+        })
+    })
+    .await
+}
+
+async fn github_review_submit(Json(req): Json<PrReviewRequest>) -> ApiResult<PrReviewDraft> {
+    blocking(move || {
+        crate::service::pr_review::submit_review(
+            &PathBuf::from(&req.repo_path),
+            &req.ref_name,
+            req.pr,
+            req.event,
+            req.message.as_deref(),
+        )
+    })
+    .await
+}
+
+async fn github_review_threads(Json(req): Json<PrReviewRequest>) -> ApiResult<storage::ThreadSync> {
+    blocking(move || {
+        storage::sync_pr_threads(&PathBuf::from(&req.repo_path), &req.ref_name, req.pr)
+            .map_err(Into::into)
+    })
+    .await
+}
+
+// ============================================================
+// Azure DevOps handlers
+// ============================================================
+
+async fn azure_available(Json(req): Json<RepoPathRequest>) -> Json<bool> {
+    let provider = AzureDevOpsProvider::new(PathBuf::from(&req.repo_path));
+    Json(provider.is_available())
+}
+
+async fn azure_pull_requests(Json(req): Json<RepoPathRequest>) -> ApiResult<Vec<AzurePullRequest>> {
+    blocking(move || {
+        let provider = AzureDevOpsProvider::new(PathBuf::from(&req.repo_path));
         provider.list_pull_requests().map_err(Into::into)
     })
     .await
@@ -703,6 +954,7 @@ async fn files_list(Json(req): Json<ListFilesRequest>) -> ApiResult<Vec<FileEntr
             &PathBuf::from(&req.repo_path),
             &req.comparison,
             req.github_pr.as_ref(),
+            req.azure_pr.as_ref(),
         )
     })
     .await
@@ -738,6 +990,8 @@ async fn files_content(Json(req): Json<GetFileContentRequest>) -> ApiResult<File
             &req.file_path,
             &req.comparison,
             req.github_pr.as_ref(),
+            req.azure_pr.as_ref(),
+            req.function_context,
         )
     })
     .await
@@ -745,11 +999,47 @@ async fn files_content(Json(req): Json<GetFileContentRequest>) -> ApiResult<File
 
 async fn files_all_hunks(Json(req): Json<GetAllHunksRequest>) -> ApiResult<Vec<DiffHunk>> {
     blocking(move || {
-        crate::service::files::get_all_hunks(
-            &PathBuf::from(&req.repo_path),
+        let repo_path = PathBuf::from(&req.repo_path);
+        let hunks =
+            crate::service::files::get_all_hunks(&repo_path, &req.comparison, &req.file_paths)?;
+        let hunks = crate::service::files::widen_to_functions(
+            &repo_path,
+            &req.comparison,
+            hunks,
+            &req.function_context_files,
+        );
+        Ok(if req.collapse_translations {
+            crate::service::files::collapse_translation_hunks(&repo_path, &req.comparison, hunks)
+        } else {
+            hunks
+        })
+    })
+    .await
+}
+
+async fn files_all_hunks_limited(Json(req): Json<GetAllHunksRequest>) -> ApiResult<LimitedHunks> {
+    blocking(move || {
+        let repo_path = PathBuf::from(&req.repo_path);
+        let mut limited = crate::service::files::get_all_hunks_limited(
+            &repo_path,
             &req.comparison,
             &req.file_paths,
-        )
+            &ReviewLimits::resolve(req.unlimited),
+        )?;
+        limited.hunks = crate::service::files::widen_to_functions(
+            &repo_path,
+            &req.comparison,
+            limited.hunks,
+            &req.function_context_files,
+        );
+        if req.collapse_translations {
+            limited.hunks = crate::service::files::collapse_translation_hunks(
+                &repo_path,
+                &req.comparison,
+                limited.hunks,
+            );
+        }
+        Ok(limited)
     })
     .await
 }
@@ -770,6 +1060,33 @@ async fn files_expanded_context(
     .await
 }
 
+async fn files_content_window(
+    Json(req): Json<ContentWindowRequest>,
+) -> ApiResult<FileContentWindow> {
+    blocking(move || {
+        crate::service::files::get_file_content_window(
+            &PathBuf::from(&req.repo_path),
+            &req.file_path,
+            &req.comparison,
+            req.side,
+            req.window,
+            req.github_pr.as_ref(),
+        )
+    })
+    .await
+}
+
+async fn files_diff_contents(Json(req): Json<DiffContentsRequest>) -> ApiResult<Vec<DiffHunk>> {
+    blocking(move || {
+        Ok(crate::diff::contents::diff_contents(
+            &req.old_text,
+            &req.new_text,
+            &req.path_hint,
+        ))
+    })
+    .await
+}
+
 async fn files_search(Json(req): Json<SearchRequest>) -> ApiResult<Vec<SearchMatch>> {
     blocking(move || {
         crate::service::files::search_file_contents(
@@ -815,21 +1132,84 @@ async fn review_resolve(
     .await
 }
 
-async fn review_load(Json(req): Json<RepoRefRequest>) -> ApiResult<ReviewState> {
-    blocking(move || {
+async fn review_load(
+    headers: HeaderMap,
+    Json(req): Json<RepoRefRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    let repo_path = req.repo_path.clone();
+    let Json(state) = blocking(move || {
         storage::load_review_state(&PathBuf::from(&req.repo_path), &req.ref_name)
             .map_err(Into::into)
     })
-    .await
+    .await?;
+    remember_snapshot(&repo_path, &state);
+    Ok(json_with_etag(&headers, &state))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewDeltaRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    since_version: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewDeltaResponse {
+    #[serde(flatten)]
+    delta: review_io::ReviewDelta,
+    /// The server no longer has `sinceVersion`, so `state` is the whole
+    /// review, hunks included.
+    full: bool,
+}
+
+/// The changes to a review since a version this server sent earlier.
+async fn review_delta(
+    headers: HeaderMap,
+    Json(req): Json<ReviewDeltaRequest>,
+) -> Result<Response, (StatusCode, String)> {
+    let ReviewDeltaRequest {
+        repo_path,
+        ref_name,
+        since_version,
+    } = req;
+    let path = PathBuf::from(&repo_path);
+    let Json(state) =
+        blocking(move || storage::load_review_state(&path, &ref_name).map_err(Into::into)).await?;
+    let response = match snapshot_at(&repo_path, &state, since_version) {
+        Some(previous) => ReviewDeltaResponse {
+            delta: review_io::review_delta(&previous, &state),
+            full: false,
+        },
+        None => ReviewDeltaResponse {
+            delta: review_io::ReviewDelta {
+                since_version,
+                version: state.version,
+                hunks: BTreeMap::new(),
+                removed_hunks: Vec::new(),
+                state: Some(state.clone()),
+            },
+            full: true,
+        },
+    };
+    remember_snapshot(&repo_path, &state);
+    Ok(json_with_etag(&headers, &response))
 }
 
 async fn review_reconcile(
     Json(req): Json<ReviewReconcileRequest>,
 ) -> ApiResult<crate::service::review_io::ReviewLoadResult> {
     blocking(move || {
-        Ok(crate::service::review_io::reconcile_review(
-            req.state, &req.hunks,
-        ))
+        Ok(match &req.repo_path {
+            Some(repo_path) => crate::service::review_io::reconcile_review_in_repo(
+                std::path::Path::new(repo_path),
+                req.state,
+                &req.hunks,
+            ),
+            None => crate::service::review_io::reconcile_review(req.state, &req.hunks),
+        })
     })
     .await
 }
@@ -874,6 +1254,27 @@ async fn review_delete(Json(req): Json<RepoRefRequest>) -> ApiResult<()> {
     .await
 }
 
+async fn review_archive(Json(req): Json<RepoRefRequest>) -> ApiResult<ReviewState> {
+    blocking(move || {
+        storage::archive_review(&PathBuf::from(&req.repo_path), &req.ref_name).map_err(Into::into)
+    })
+    .await
+}
+
+async fn review_unarchive(Json(req): Json<RepoRefRequest>) -> ApiResult<ReviewState> {
+    blocking(move || {
+        storage::unarchive_review(&PathBuf::from(&req.repo_path), &req.ref_name).map_err(Into::into)
+    })
+    .await
+}
+
+async fn review_list_archived(Json(req): Json<RepoPathRequest>) -> ApiResult<Vec<ReviewSummary>> {
+    blocking(move || {
+        storage::list_archived_reviews(&PathBuf::from(&req.repo_path)).map_err(Into::into)
+    })
+    .await
+}
+
 async fn review_exists(Json(req): Json<RepoRefRequest>) -> ApiResult<bool> {
     blocking(move || {
         storage::review_exists(&PathBuf::from(&req.repo_path), &req.ref_name).map_err(Into::into)
@@ -927,17 +1328,317 @@ async fn review_freshness(
     Json(crate::service::freshness::check_reviews_freshness(req.reviews).await)
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewTimelineRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    /// Only return events after this ISO 8601 timestamp.
+    since: Option<String>,
+}
+
+async fn review_timeline(
+    Json(req): Json<ReviewTimelineRequest>,
+) -> ApiResult<crate::service::timeline::ReviewTimeline> {
+    blocking(move || {
+        crate::service::timeline::review_timeline(
+            &PathBuf::from(&req.repo_path),
+            &req.ref_name,
+            req.since.as_deref(),
+        )
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewSplitRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    base_override: Option<String>,
+    /// Model that proposes the split; directory clustering only when absent.
+    model: Option<String>,
+    max_reviews: Option<usize>,
+    /// Save the proposed reviews, linked to this one.
+    #[serde(default)]
+    create: bool,
+}
+
+async fn review_split(
+    Json(req): Json<ReviewSplitRequest>,
+) -> ApiResult<crate::service::split::SplitPlan> {
+    blocking(move || {
+        let repo = PathBuf::from(&req.repo_path);
+        let plan = crate::service::split::propose_split(
+            &repo,
+            &req.ref_name,
+            req.base_override.as_deref(),
+            req.model.as_deref(),
+            req.max_reviews
+                .unwrap_or(crate::service::split::DEFAULT_MAX_REVIEWS),
+        )?;
+        if req.create {
+            crate::service::split::create_split_reviews(&repo, &plan)?;
+        }
+        Ok(plan)
+    })
+    .await
+}
+
+async fn review_progress(
+    Json(req): Json<RepoRefRequest>,
+) -> ApiResult<crate::service::review_io::ReviewProgress> {
+    blocking(move || {
+        crate::service::review_io::get_review_progress(
+            &PathBuf::from(&req.repo_path),
+            &req.ref_name,
+        )
+    })
+    .await
+}
+
+async fn review_submodules(
+    Json(req): Json<RepoRefRequest>,
+) -> ApiResult<Vec<crate::service::submodules::SubmoduleScope>> {
+    blocking(move || {
+        crate::service::submodules::submodule_scopes(&PathBuf::from(&req.repo_path), &req.ref_name)
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmoduleHunkRequest {
+    repo_path: String,
+    hunk: DiffHunk,
+}
+
+async fn review_submodule_hunk(
+    Json(req): Json<SubmoduleHunkRequest>,
+) -> ApiResult<Option<Vec<DiffHunk>>> {
+    blocking(move || {
+        crate::service::submodules::expand_submodule_hunk(&PathBuf::from(&req.repo_path), &req.hunk)
+    })
+    .await
+}
+
+async fn review_checklist(Json(req): Json<RepoRefRequest>) -> ApiResult<Vec<ChecklistItem>> {
+    blocking(move || {
+        crate::service::checklist::get_checklist(&PathBuf::from(&req.repo_path), &req.ref_name)
+    })
+    .await
+}
+
+async fn review_inbox(
+    Json(req): Json<RepoPathRequest>,
+) -> ApiResult<crate::service::pr_sync::InboxSync> {
+    blocking(move || crate::service::pr_sync::sync_inbox(&PathBuf::from(&req.repo_path))).await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChecklistSetRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    /// Item id, or its 1-based position.
+    item: String,
+    checked: bool,
+}
+
+async fn review_checklist_set(
+    Json(req): Json<ChecklistSetRequest>,
+) -> ApiResult<Vec<ChecklistItem>> {
+    blocking(move || {
+        crate::service::checklist::set_checklist_item(
+            &PathBuf::from(&req.repo_path),
+            &req.ref_name,
+            &req.item,
+            req.checked,
+            Source::Ui,
+        )
+    })
+    .await
+}
+
+async fn review_two_person(
+    Json(req): Json<RepoRefRequest>,
+) -> ApiResult<Option<crate::service::two_person::TwoPersonProgress>> {
+    blocking(move || {
+        let state = storage::load_review_state(&PathBuf::from(&req.repo_path), &req.ref_name)?;
+        Ok(crate::service::two_person::progress(&state))
+    })
+    .await
+}
+
+async fn review_two_person_require(Json(req): Json<RepoRefRequest>) -> ApiResult<()> {
+    blocking(move || {
+        crate::service::two_person::require_second_reviewer(
+            &PathBuf::from(&req.repo_path),
+            &req.ref_name,
+        )
+        .map(|_| ())
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TwoPersonSignRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    /// Who is handing off or signing off.
+    by: String,
+}
+
+async fn review_two_person_handoff(Json(req): Json<TwoPersonSignRequest>) -> ApiResult<usize> {
+    blocking(move || {
+        crate::service::two_person::hand_off(&PathBuf::from(&req.repo_path), &req.ref_name, &req.by)
+    })
+    .await
+}
+
+async fn review_two_person_signoff(
+    Json(req): Json<TwoPersonSignRequest>,
+) -> ApiResult<crate::service::two_person::TwoPersonProgress> {
+    blocking(move || {
+        crate::service::two_person::sign_off(&PathBuf::from(&req.repo_path), &req.ref_name, &req.by)
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewActivityRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    event: crate::review::sessions::SessionEvent,
+}
+
+async fn review_activity(Json(req): Json<ReviewActivityRequest>) -> ApiResult<()> {
+    blocking(move || {
+        crate::review::sessions::record_activity(
+            &PathBuf::from(&req.repo_path),
+            &req.ref_name,
+            req.event,
+        )
+        .map_err(Into::into)
+    })
+    .await
+}
+
+// ============================================================
+// Review sync (ETags and deltas)
+// ============================================================
+
+/// Versions of each review kept for [`review_delta`].
+const SNAPSHOTS_PER_REVIEW: usize = 8;
+
+/// Recent versions of each review this server has sent, keyed by repo path
+/// and ref, oldest first.
+type ReviewSnapshots = HashMap<(String, String), VecDeque<ReviewState>>;
+
+static REVIEW_SNAPSHOTS: LazyLock<Mutex<ReviewSnapshots>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn remember_snapshot(repo_path: &str, state: &ReviewState) {
+    let mut snapshots = REVIEW_SNAPSHOTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let versions = snapshots
+        .entry((repo_path.to_owned(), state.ref_name.clone()))
+        .or_default();
+    // A review deleted and recreated restarts its version numbers
+    versions.retain(|s| s.created_at == state.created_at);
+    if versions.iter().any(|s| s.version == state.version) {
+        return;
+    }
+    versions.push_back(state.clone());
+    while versions.len() > SNAPSHOTS_PER_REVIEW {
+        versions.pop_front();
+    }
+}
+
+/// The remembered `version` of the review `current` is the latest of.
+fn snapshot_at(repo_path: &str, current: &ReviewState, version: u64) -> Option<ReviewState> {
+    REVIEW_SNAPSHOTS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&(repo_path.to_owned(), current.ref_name.clone()))?
+        .iter()
+        .find(|s| s.version == version && s.created_at == current.created_at)
+        .cloned()
+}
+
+/// Respond with `value` as JSON tagged with an ETag of its content, or with
+/// 304 Not Modified when the request's `If-None-Match` already has it.
+fn json_with_etag<T: Serialize>(headers: &HeaderMap, value: &T) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(e) => return internal_err(e).into_response(),
+    };
+    let etag = format!("\"{}\"", &hex::encode(Sha256::digest(&body))[..32]);
+    let cached = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|tag| matches!(tag.trim(), "*") || tag.trim() == etag)
+        });
+    if cached {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+    (
+        [
+            (header::CONTENT_TYPE, "application/json".to_owned()),
+            (header::ETAG, etag),
+        ],
+        body,
+    )
+        .into_response()
+}
+
 // ============================================================
 // Classification handlers
 // ============================================================
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct ClassifyStaticRequest {
     hunks: Vec<DiffHunk>,
-}
-
-async fn classify_static(Json(req): Json<ClassifyStaticRequest>) -> Json<ClassifyResponse> {
-    Json(classify::classify_hunks_static(&req.hunks))
+    /// Applies the repo's sensitive path policy when given.
+    #[serde(default)]
+    repo_path: Option<String>,
+}
+
+async fn classify_static(Json(req): Json<ClassifyStaticRequest>) -> ApiResult<ClassifyResponse> {
+    let label = format!("Classify {} hunks", req.hunks.len());
+    jobs::run_blocking(
+        JobKind::Classification,
+        JobPriority::Interactive,
+        label,
+        move || {
+            let mut response =
+                classify::classify_hunks_static_limited(&req.hunks, &ReviewLimits::from_env());
+            if let Some(repo_path) = &req.repo_path {
+                classify::apply_sensitive_paths(
+                    std::path::Path::new(repo_path),
+                    &req.hunks,
+                    &mut response,
+                );
+                classify::apply_label_precedence(std::path::Path::new(repo_path), &mut response);
+            }
+            response
+        },
+    )
+    .await
+    .map_err(internal_err)
+    .map(Json)
 }
 
 #[derive(Deserialize)]
@@ -984,6 +1685,28 @@ async fn symbols_diffs(Json(req): Json<SymbolDiffsRequest>) -> ApiResult<Vec<Fil
     .await
 }
 
+async fn schemas_deltas(Json(req): Json<SymbolDiffsRequest>) -> ApiResult<Vec<FileSchemaDelta>> {
+    blocking(move || {
+        crate::service::schemas::get_api_schema_deltas(
+            &PathBuf::from(&req.repo_path),
+            &req.file_paths,
+            &req.comparison,
+        )
+    })
+    .await
+}
+
+async fn migrations_checks(Json(req): Json<SymbolDiffsRequest>) -> ApiResult<Vec<MigrationCheck>> {
+    blocking(move || {
+        crate::service::migrations::get_migration_checks(
+            &PathBuf::from(&req.repo_path),
+            &req.file_paths,
+            &req.comparison,
+        )
+    })
+    .await
+}
+
 async fn symbols_definitions(
     Json(req): Json<SymbolDefinitionsRequest>,
 ) -> ApiResult<Vec<SymbolDefinition>> {
@@ -1013,6 +1736,144 @@ async fn symbols_repo(Json(req): Json<RepoPathRequest>) -> ApiResult<Vec<RepoFil
         .await
 }
 
+// ============================================================
+// Background job handlers
+// ============================================================
+
+async fn jobs_queue() -> Json<jobs::JobQueueSnapshot> {
+    Json(jobs::snapshot())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SetParallelismRequest {
+    max_parallel: usize,
+}
+
+async fn jobs_set_parallelism(
+    Json(req): Json<SetParallelismRequest>,
+) -> Json<jobs::JobQueueSnapshot> {
+    jobs::set_max_parallel(req.max_parallel);
+    Json(jobs::snapshot())
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TriggerJobResponse {
+    job_id: u64,
+}
+
+/// Queue a job, recorded so it resumes if the server restarts first.
+async fn jobs_trigger(Json(req): Json<JobRequest>) -> Json<TriggerJobResponse> {
+    let job_id = job_queue::submit(req);
+    Json(TriggerJobResponse { job_id })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JobIdRequest {
+    job_id: u64,
+}
+
+async fn companion_symbols(
+    Json(req): Json<RepoRefRequest>,
+) -> ApiResult<Vec<companion::CompanionFileSymbols>> {
+    blocking(move || companion::symbols(&PathBuf::from(&req.repo_path), &req.ref_name)).await
+}
+
+async fn companion_groups(
+    Json(req): Json<RepoRefRequest>,
+) -> ApiResult<Option<companion::CompanionGroups>> {
+    blocking(move || companion::groups(&PathBuf::from(&req.repo_path), &req.ref_name)).await
+}
+
+async fn companion_graph(
+    Json(req): Json<RepoRefRequest>,
+) -> ApiResult<Vec<companion::CompanionCluster>> {
+    blocking(move || companion::graph(&PathBuf::from(&req.repo_path), &req.ref_name)).await
+}
+
+async fn companion_taxonomy() -> Json<companion::CompanionTaxonomy> {
+    Json(companion::taxonomy())
+}
+
+async fn companion_trust(Json(req): Json<RepoRefRequest>) -> ApiResult<companion::CompanionTrust> {
+    blocking(move || companion::trust(&PathBuf::from(&req.repo_path), &req.ref_name)).await
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TrustPatternRequest {
+    repo_path: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+    pattern: String,
+}
+
+/// Add a trust pattern, refusing with 422 one that can't match anything.
+async fn companion_trust_add(
+    Json(req): Json<TrustPatternRequest>,
+) -> ApiResult<companion::CompanionTrust> {
+    let problems = companion::pattern_problems(&req.pattern);
+    if !problems.is_empty() {
+        let messages: Vec<_> = problems.into_iter().map(|d| d.message).collect();
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, messages.join("; ")));
+    }
+    blocking(move || {
+        companion::add_trust_pattern(&PathBuf::from(&req.repo_path), &req.ref_name, &req.pattern)
+    })
+    .await
+}
+
+async fn companion_trust_remove(
+    Json(req): Json<TrustPatternRequest>,
+) -> ApiResult<companion::CompanionTrust> {
+    blocking(move || {
+        companion::remove_trust_pattern(&PathBuf::from(&req.repo_path), &req.ref_name, &req.pattern)
+    })
+    .await
+}
+
+async fn jobs_status(Json(req): Json<JobIdRequest>) -> ApiResult<jobs::TriggeredJob> {
+    jobs::triggered_job(req.job_id)
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("No job {}", req.job_id)))
+}
+
+/// Stream a triggered job's status each time it changes, ending once it
+/// finishes (or is no longer known).
+async fn jobs_events(
+    Query(req): Query<JobIdRequest>,
+) -> Sse<impl futures::Stream<Item = Result<Event, Infallible>>> {
+    let stream = futures::stream::unfold(
+        (Some(req.job_id), None::<jobs::JobStatus>),
+        |(job_id, last)| async move {
+            let job_id = job_id?;
+            loop {
+                let job = jobs::triggered_job(job_id);
+                let status = job.as_ref().map(|job| job.status);
+                if status.is_some() && status == last {
+                    tokio::time::sleep(Duration::from_millis(250)).await;
+                    continue;
+                }
+                let next = status
+                    .filter(|status| !status.is_finished())
+                    .map(|_| job_id);
+                let event = Event::default()
+                    .json_data(&job)
+                    .unwrap_or_else(|_| Event::default().data("null"));
+                return Some((Ok(event), (next, status)));
+            }
+        },
+    );
+
+    Sse::new(stream).keep_alive(
+        axum::response::sse::KeepAlive::new()
+            .interval(Duration::from_secs(15))
+            .text("keep-alive"),
+    )
+}
+
 // ============================================================
 // Activity handlers
 // ============================================================
@@ -1064,6 +1925,10 @@ async fn misc_vscode_theme() -> ApiResult<VscodeThemeDetection> {
     blocking(crate::service::vscode::detect_vscode_theme).await
 }
 
+async fn misc_settings_schema() -> Json<Vec<crate::service::settings::SettingSchema>> {
+    Json(crate::service::settings::settings_schema())
+}
+
 async fn misc_resolve_repo_path(
     Json(req): Json<ResolveRepoPathRequest>,
 ) -> ApiResult<Option<String>> {
@@ -1228,6 +2093,8 @@ async fn events_sse(
         let repo_for_closure = repo_path_str.clone();
         let repo_root = repo_path.clone();
         let tx_clone = tx.clone();
+        let worktree = linked_worktree_dirs(&repo_path);
+        let worktree_git_dir = worktree.as_ref().map(|w| w.git_dir.clone());
 
         let debouncer_result = new_debouncer(
             Duration::from_millis(200),
@@ -1248,6 +2115,13 @@ async fn events_sse(
                         if path_str.ends_with("/app.log") || path_str.ends_with("\\app.log") {
                             continue;
                         }
+                        if worktree_git_dir
+                            .as_ref()
+                            .is_some_and(|dir| is_worktree_state_path(&event.path, dir))
+                        {
+                            git_state_changed = true;
+                            continue;
+                        }
 
                         let category = categorize_change(&path_str);
                         match category {
@@ -1274,6 +2148,10 @@ async fn events_sse(
                                 .data(&repo_for_closure),
                         );
                     }
+                    if !changed_paths.is_empty() {
+                        let paths: Vec<String> = changed_paths.iter().cloned().collect();
+                        crate::service::symbols::update_repo_symbol_index(&repo_root, &paths);
+                    }
                     if working_tree_changed || git_state_changed {
                         let payload = GitChangedPayload {
                             repo_path: repo_for_closure.clone(),
@@ -1329,6 +2207,14 @@ async fn events_sse(
         let _ = debouncer
             .watcher()
             .watch(&repo_path, RecursiveMode::Recursive);
+        if let Some(worktree) = &worktree {
+            let _ = debouncer
+                .watcher()
+                .watch(&worktree.git_dir, RecursiveMode::NonRecursive);
+            let _ = debouncer
+                .watcher()
+                .watch(&worktree.refs_heads, RecursiveMode::Recursive);
+        }
 
         // Also watch central storage for review state changes
         if let Ok(central_dir) = crate::review::central::get_repo_storage_dir(&repo_path) {
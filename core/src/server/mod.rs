@@ -2,27 +2,277 @@
 //!
 //! Feature-gated behind `server`. Serves the same business logic as the
 //! Tauri desktop shell, but over HTTP + SSE instead of IPC.
+//!
+//! The `/api/jobs/trigger`, `/api/jobs/status` and `/api/jobs/events` routes
+//! start and follow work remotely, and the `/api/companion/*` routes serve the
+//! mobile client trimmed reads and trust-list edits (see
+//! [`crate::service::companion`]), so they require the token from
+//! [`auth::server_token`].
+//!
+//! Servers bind to loopback unless `REVIEW_BIND` names another address (see
+//! [`bind_ip`]) — listening on the LAN is an explicit opt-in. Every route
+//! requires the token from requests that don't come straight from this
+//! machine, including ones a proxy like Tailscale Serve forwards over
+//! loopback (see [`auth::require_token_unless_local`]). Bound beyond
+//! loopback, a server whose token can't be loaded refuses to start rather
+//! than serve the API unauthenticated.
+//!
+//! The API server starts on `REVIEW_PORT`, else the port it last ran on, and
+//! when that's taken moves to the next free one in `REVIEW_PORT_RANGE` (see
+//! [`bind`]). The port it gets is kept in `~/.review/server.json` for the
+//! next start and for clients like `scripts/dev-web` to find it.
 
+pub mod auth;
 mod handlers;
+#[cfg(feature = "viewer")]
+pub mod viewer;
 
-use axum::Router;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::ops::RangeInclusive;
+use std::sync::OnceLock;
+
+use axum::routing::post;
+use axum::{Json, Router};
+use serde::Serialize;
+use thiserror::Error;
 use tower_http::cors::{Any, CorsLayer};
 
-/// Build the full router with all API routes.
+use crate::secrets::SecretsError;
+use crate::service::pairing;
+use crate::service::server_port::persist_port;
+pub use crate::service::server_port::{persisted_port, DEFAULT_PORT};
+
+/// Environment variable naming the address servers bind to.
+pub const BIND_ENV: &str = "REVIEW_BIND";
+
+/// Environment variable naming the port the API server tries first.
+pub const PORT_ENV: &str = "REVIEW_PORT";
+
+/// Environment variable bounding the ports the API server falls back to, as
+/// `<first>-<last>`.
+pub const PORT_RANGE_ENV: &str = "REVIEW_PORT_RANGE";
+
+/// Ports past the preferred one tried when no range is configured.
+const DEFAULT_FALLBACK_PORTS: u16 = 9;
+
+#[derive(Error, Debug)]
+pub enum ServeError {
+    #[error("Invalid {BIND_ENV} address '{0}': expected an IP address or 'localhost'")]
+    InvalidAddress(String),
+    #[error("Refusing to listen on {0} without a server token: {1}")]
+    TokenUnavailable(SocketAddr, SecretsError),
+    #[error("Invalid {PORT_ENV} '{0}': expected a port number")]
+    InvalidPort(String),
+    #[error("Invalid {PORT_RANGE_ENV} '{0}': expected <first>-<last>, e.g. 3421-3430")]
+    InvalidPortRange(String),
+    #[error("Failed to listen on {0}: {1}")]
+    Bind(SocketAddr, std::io::Error),
+    #[error("No free port on {0} in {first}-{last}", first = .1.start(), last = .1.end())]
+    NoFreePort(IpAddr, RangeInclusive<u16>),
+    #[error("Server error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Parse a bind address: an IP, or `localhost`. Empty means loopback.
+fn parse_bind(value: &str) -> Result<IpAddr, ServeError> {
+    match value.trim() {
+        "" | "localhost" => Ok(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+        ip => ip
+            .parse()
+            .map_err(|_| ServeError::InvalidAddress(ip.to_owned())),
+    }
+}
+
+/// The address to bind to: `REVIEW_BIND` when set, else loopback.
+pub fn bind_ip() -> Result<IpAddr, ServeError> {
+    parse_bind(&std::env::var(BIND_ENV).unwrap_or_default())
+}
+
+/// Parse a port range: `<first>-<last>`, or a single port.
+fn parse_port_range(value: &str) -> Result<RangeInclusive<u16>, ServeError> {
+    let invalid = || ServeError::InvalidPortRange(value.to_owned());
+    let (first, last) = value.trim().split_once('-').unwrap_or((value, value));
+    let first: u16 = first.trim().parse().map_err(|_| invalid())?;
+    let last: u16 = last.trim().parse().map_err(|_| invalid())?;
+    if first == 0 || first > last {
+        return Err(invalid());
+    }
+    Ok(first..=last)
+}
+
+/// The ports to try, in order: `preferred`, then the rest of `range`. A
+/// preferred port outside the range is still tried first.
+fn port_candidates(preferred: u16, range: &RangeInclusive<u16>) -> Vec<u16> {
+    let mut ports = vec![preferred];
+    ports.extend(range.clone().filter(|&p| p != preferred));
+    ports
+}
+
+/// The port to try first and the fallback range, from `REVIEW_PORT`,
+/// `REVIEW_PORT_RANGE` and the persisted port. Without a range, the nine
+/// ports after the preferred one are the fallbacks; the persisted port is
+/// only preferred while it's inside the range.
+fn port_plan() -> Result<(u16, RangeInclusive<u16>), ServeError> {
+    let explicit = match std::env::var(PORT_ENV) {
+        Ok(value) if !value.trim().is_empty() => Some(
+            value
+                .trim()
+                .parse::<u16>()
+                .map_err(|_| ServeError::InvalidPort(value.clone()))?,
+        ),
+        _ => None,
+    };
+    let range = match std::env::var(PORT_RANGE_ENV) {
+        Ok(value) if !value.trim().is_empty() => parse_port_range(&value)?,
+        _ => {
+            let first = explicit.unwrap_or(DEFAULT_PORT);
+            first..=first.saturating_add(DEFAULT_FALLBACK_PORTS)
+        }
+    };
+    let preferred = explicit
+        .or_else(|| persisted_port().filter(|port| range.contains(port)))
+        .unwrap_or(*range.start());
+    Ok((preferred, range))
+}
+
+/// Where the API server is listening, as the status route reports it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatus {
+    pub address: String,
+    /// Reachable from this machine only.
+    pub loopback_only: bool,
+    /// Clients on other devices can connect directly, and need the token on
+    /// every route.
+    pub token_required: bool,
+    /// Where other devices can reach it, best first (see
+    /// [`crate::service::pairing`]). Empty on loopback.
+    pub urls: Vec<String>,
+}
+
+impl ServerStatus {
+    fn new(addr: SocketAddr) -> Self {
+        Self {
+            address: addr.to_string(),
+            loopback_only: addr.ip().is_loopback(),
+            token_required: !addr.ip().is_loopback(),
+            urls: pairing::server_urls(addr.ip(), addr.port(), pairing::link_local_requested()),
+        }
+    }
+}
+
+static STATUS: OnceLock<ServerStatus> = OnceLock::new();
+
+/// The running API server's status, once it is listening.
+pub fn status() -> Option<ServerStatus> {
+    STATUS.get().cloned()
+}
+
+async fn server_status() -> Json<Option<ServerStatus>> {
+    Json(status())
+}
+
+/// Bind `addr`, first making sure a server reachable beyond loopback has a
+/// token to require.
+async fn listen(addr: SocketAddr) -> Result<tokio::net::TcpListener, ServeError> {
+    if !addr.ip().is_loopback() {
+        auth::server_token().map_err(|e| ServeError::TokenUnavailable(addr, e))?;
+    }
+    tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| ServeError::Bind(addr, e))
+}
+
+/// Build the full router with all API routes. Every route requires the token
+/// from requests that aren't local (see [`auth::require_token_unless_local`]),
+/// whether they reach a LAN address or arrive through a proxy on loopback.
 fn build_router() -> Router {
     let cors = CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
         .allow_headers(Any);
 
-    handlers::build_api_router().layer(cors)
+    handlers::build_api_router()
+        .route("/api/server/status", post(server_status))
+        .route_layer(axum::middleware::from_fn(auth::require_token_unless_local))
+        .layer(cors)
 }
 
-/// Start the HTTP server on the given port.
-pub async fn serve(port: u16) {
-    let app = build_router();
-    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{port}"))
-        .await
-        .expect("Failed to bind to address");
-    axum::serve(listener, app).await.expect("Server error");
+/// A listening socket for the API server, from [`bind`].
+pub struct Bound {
+    listener: tokio::net::TcpListener,
+    addr: SocketAddr,
+}
+
+impl Bound {
+    /// The address actually bound, after any port fallback.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+}
+
+/// Bind the API server on `ip`. Tries the preferred port, then each port of
+/// the fallback range in turn while they're in use, and records the one it
+/// gets as the next start's preferred port.
+pub async fn bind(ip: IpAddr) -> Result<Bound, ServeError> {
+    let (preferred, range) = port_plan()?;
+    for port in port_candidates(preferred, &range) {
+        let addr = SocketAddr::new(ip, port);
+        match listen(addr).await {
+            Ok(listener) => {
+                if port != preferred {
+                    log::info!("[server] port {preferred} is in use, listening on {port}");
+                }
+                persist_port(port);
+                return Ok(Bound { listener, addr });
+            }
+            Err(ServeError::Bind(_, e)) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                log::debug!("[server] port {port} is in use");
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Err(ServeError::NoFreePort(ip, range))
+}
+
+/// Serve the API on a socket from [`bind`].
+pub async fn serve(bound: Bound) -> Result<(), ServeError> {
+    let Bound { listener, addr } = bound;
+    let _ = STATUS.set(ServerStatus::new(addr));
+    let app = build_router().into_make_service_with_connect_info::<SocketAddr>();
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_loopback_unless_told_otherwise() {
+        assert_eq!(parse_bind("").unwrap(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+        assert!(parse_bind("localhost").unwrap().is_loopback());
+        assert!(parse_bind("::1").unwrap().is_loopback());
+        let lan = parse_bind("0.0.0.0").unwrap();
+        assert!(!lan.is_loopback());
+        assert!(ServerStatus::new(SocketAddr::new(lan, 3421)).token_required);
+        assert!(matches!(
+            parse_bind("my-laptop"),
+            Err(ServeError::InvalidAddress(_))
+        ));
+    }
+
+    #[test]
+    fn falls_back_through_the_port_range() {
+        assert_eq!(parse_port_range("3421-3423").unwrap(), 3421..=3423);
+        assert_eq!(parse_port_range(" 4000 ").unwrap(), 4000..=4000);
+        for bad in ["", "3430-3421", "0-10", "3421-x", "70000-70001"] {
+            assert!(matches!(
+                parse_port_range(bad),
+                Err(ServeError::InvalidPortRange(_))
+            ));
+        }
+        assert_eq!(port_candidates(3422, &(3421..=3423)), [3422, 3421, 3423]);
+        assert_eq!(port_candidates(8080, &(3421..=3422)), [8080, 3421, 3422]);
+    }
 }
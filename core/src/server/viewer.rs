@@ -0,0 +1,220 @@
+//! Read-only web viewer: one static page that shows a review's progress,
+//! guide groups, and hunks with their labels, for a teammate on the LAN who
+//! doesn't have the app.
+//!
+//! Feature-gated behind `viewer`. It runs on its own listener (see
+//! [`serve_viewer`]) with only these routes, so exposing it to the network
+//! doesn't expose the rest of the API. Like the API server it binds to
+//! loopback unless `REVIEW_BIND` says otherwise (see [`super::bind_ip`]).
+//! The data routes require the server token (see [`super::auth`]), passed as
+//! `?token=` in the page URL.
+
+use axum::extract::{Json, Query};
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::get;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+
+use crate::classify::classify_repo_hunks_static;
+use crate::diff::parser::{DiffHunk, LineType};
+use crate::review::state::{HunkGroup, HunkState, HunkStatus, ReviewSummary};
+use crate::review::storage::{self, GlobalReviewSummary};
+use crate::service::files::comparison_hunks;
+use crate::service::targets;
+
+const VIEWER_HTML: &str = include_str!("viewer.html");
+
+type ViewerResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+/// A hunk as the viewer shows it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerHunk {
+    pub id: String,
+    pub file_path: String,
+    /// The hunk's lines in unified diff form (`+`, `-` or ` ` prefixed).
+    pub diff: String,
+    /// Stored labels, else the static classification's.
+    pub labels: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<HunkStatus>,
+    /// Whether a label is on the review's trust list.
+    pub trusted: bool,
+}
+
+/// Everything the viewer renders for one review.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerReview {
+    pub comparison: String,
+    pub summary: ReviewSummary,
+    pub groups: Vec<HunkGroup>,
+    pub hunks: Vec<ViewerHunk>,
+}
+
+/// The hunk's lines with their `+`/`-`/` ` prefixes, without the `@@`
+/// header.
+fn unified_lines(hunk: &DiffHunk) -> String {
+    hunk.lines
+        .iter()
+        .map(|line| {
+            let prefix = match line.line_type {
+                LineType::Context => ' ',
+                LineType::Added => '+',
+                LineType::Removed => '-',
+            };
+            format!("{prefix}{}", line.content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Load `ref_name`'s review with its current hunks.
+pub fn load_viewer_review(
+    repo_path: &std::path::Path,
+    ref_name: &str,
+) -> anyhow::Result<ViewerReview> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let review = targets::resolve(repo_path, ref_name, None)?;
+    let hunks = comparison_hunks(repo_path, &review.comparison, None)?;
+    let classification = classify_repo_hunks_static(repo_path, &hunks);
+
+    let hunks = hunks
+        .into_iter()
+        .map(|hunk| {
+            let hunk_state = state.hunks.get(&hunk.id);
+            let labels = match hunk_state.map(HunkState::labels) {
+                Some(labels) if !labels.is_empty() => labels.to_vec(),
+                _ => classification
+                    .classifications
+                    .get(&hunk.id)
+                    .map(|c| c.label.clone())
+                    .unwrap_or_default(),
+            };
+            ViewerHunk {
+                diff: unified_lines(&hunk),
+                trusted: state.labels_trusted(&labels),
+                status: hunk_state
+                    .and_then(|s| s.status.as_ref())
+                    .map(|s| s.value.clone()),
+                labels,
+                id: hunk.id,
+                file_path: hunk.file_path,
+            }
+        })
+        .collect();
+
+    Ok(ViewerReview {
+        comparison: review.comparison.key.clone(),
+        summary: state.to_summary(),
+        groups: state
+            .guide
+            .as_ref()
+            .and_then(|guide| guide.state.as_ref())
+            .map(|generated| generated.groups.clone())
+            .unwrap_or_default(),
+        hunks,
+    })
+}
+
+async fn viewer_page() -> Html<&'static str> {
+    Html(VIEWER_HTML)
+}
+
+async fn viewer_reviews() -> ViewerResult<Vec<GlobalReviewSummary>> {
+    tokio::task::spawn_blocking(storage::list_all_reviews_global)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+        .map(Json)
+}
+
+#[derive(Deserialize)]
+struct ViewerReviewQuery {
+    repo: String,
+    #[serde(rename = "ref")]
+    ref_name: String,
+}
+
+async fn viewer_review(Query(query): Query<ViewerReviewQuery>) -> ViewerResult<ViewerReview> {
+    tokio::task::spawn_blocking(move || {
+        load_viewer_review(&PathBuf::from(&query.repo), &query.ref_name)
+    })
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))
+    .map(Json)
+}
+
+/// The viewer's routes: the page at `/`, its data under `/api/`.
+pub fn viewer_router() -> Router {
+    Router::new()
+        .route("/api/reviews", get(viewer_reviews))
+        .route("/api/review", get(viewer_review))
+        .route_layer(axum::middleware::from_fn(super::auth::require_token))
+        .route("/", get(viewer_page))
+}
+
+/// Serve the viewer on `ip` and `port`.
+pub async fn serve_viewer(ip: IpAddr, port: u16) -> Result<(), super::ServeError> {
+    let listener = super::listen(SocketAddr::new(ip, port)).await?;
+    axum::serve(listener, viewer_router()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::review::state::{Attributed, HunkState, Source};
+    use std::process::Command;
+
+    #[test]
+    fn viewer_review_joins_hunks_with_state() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let git = |args: &[&str]| {
+            let status = Command::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(repo.path())
+                .status()
+                .unwrap();
+            assert!(status.success());
+        };
+        git(&["init", "-q", "-b", "main"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+        git(&["checkout", "-q", "-b", "feature"]);
+        std::fs::write(repo.path().join("a.txt"), "hello\n").unwrap();
+        git(&["add", "a.txt"]);
+        git(&["commit", "-q", "-m", "add a"]);
+
+        let hunk_id = load_viewer_review(repo.path(), "feature").unwrap().hunks[0]
+            .id
+            .clone();
+        let mut state = storage::load_review_state(repo.path(), "feature").unwrap();
+        state.hunks.insert(
+            hunk_id.clone(),
+            HunkState {
+                classification: Some(Attributed::new(vec!["docs:added".to_owned()], Source::Ui)),
+                status: Some(Attributed::new(HunkStatus::Approved, Source::Ui)),
+                ..Default::default()
+            },
+        );
+        state.prepare_for_save();
+        storage::save_review_state(repo.path(), &state).unwrap();
+
+        let view = load_viewer_review(repo.path(), "feature").unwrap();
+        assert_eq!(view.hunks.len(), 1);
+        let hunk = &view.hunks[0];
+        assert_eq!(hunk.id, hunk_id);
+        assert_eq!(hunk.file_path, "a.txt");
+        assert_eq!(hunk.diff, "+hello");
+        assert_eq!(hunk.labels, ["docs:added"]);
+        assert!(matches!(hunk.status, Some(HunkStatus::Approved)));
+        assert!(view.groups.is_empty());
+    }
+}
@@ -0,0 +1,536 @@
+//! Signed review attestations: provenance that a person reviewed exactly this
+//! diff and decided each of its hunks as recorded.
+//!
+//! `review attest` takes a completed review (every hunk in the current diff
+//! approved, rejected, or trusted), hashes the diff, and signs the diff hash
+//! and decisions with the user's git signing key: `ssh-keygen -Y sign` when
+//! `gpg.format` is `ssh`, else `gpg --detach-sign`, with `user.signingkey`.
+//! The attestation is kept on the review, so it travels with archives and
+//! exports; `review attest verify` checks the signature — made by the
+//! recorded key — and whether the diff or decisions have changed since.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::Context;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::diff::parser::{DiffHunk, LineType};
+use crate::review::state::{
+    now_iso8601, Attestation, AttestedReview, HunkStatus, ReviewState, SignatureFormat,
+};
+use crate::review::storage::{self, StorageError};
+use crate::service::files::comparison_hunks;
+use crate::service::targets;
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::Endpoint;
+
+/// The `ssh-keygen -Y` namespace attestations are signed in, so a signature
+/// made for something else (a commit, a file) can't pass as one.
+pub const SIGNATURE_NAMESPACE: &str = "review-attestation";
+
+/// The principal the recorded key is allowed to sign as, in the
+/// `allowed_signers` file built to verify an SSH attestation.
+const SSH_PRINCIPAL: &str = "attestation-signer";
+
+/// Saves retried after a version conflict.
+const MAX_SAVE_RETRIES: usize = 3;
+
+/// Which key to sign with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningKey {
+    pub format: SignatureFormat,
+    /// An SSH key path or literal public key, or a GPG key ID; empty for
+    /// GPG's default key.
+    pub key: String,
+}
+
+impl SigningKey {
+    /// `key` and `format` where given, else the repo's git signing config
+    /// (`user.signingkey`, `gpg.format`).
+    pub fn resolve(
+        repo_path: &Path,
+        key: Option<&str>,
+        format: Option<SignatureFormat>,
+    ) -> anyhow::Result<Self> {
+        let source = LocalGitSource::new(repo_path.to_path_buf())?;
+        let format = format.unwrap_or_else(|| match source.get_config("gpg.format").as_deref() {
+            Some("ssh") => SignatureFormat::Ssh,
+            _ => SignatureFormat::Gpg,
+        });
+        let key = key
+            .map(str::to_owned)
+            .or_else(|| source.get_config("user.signingkey"))
+            .unwrap_or_default();
+        if format == SignatureFormat::Ssh && key.is_empty() {
+            anyhow::bail!("No SSH signing key: set git's user.signingkey or pass --key");
+        }
+        Ok(Self { format, key })
+    }
+}
+
+/// What a signature check found.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationCheck {
+    pub attestation: Attestation,
+    /// The signature verifies over the recorded review.
+    pub signature_valid: bool,
+    /// Why the review no longer matches what was signed; empty when it does.
+    pub changes: Vec<String>,
+}
+
+/// SHA-256 over each hunk's ID and lines, in ID order, so the digest doesn't
+/// depend on the order hunks were listed in.
+pub fn diff_digest(hunks: &[DiffHunk]) -> String {
+    let mut sorted: Vec<&DiffHunk> = hunks.iter().collect();
+    sorted.sort_by(|a, b| a.id.cmp(&b.id));
+    let mut hasher = Sha256::new();
+    for hunk in sorted {
+        hasher.update(hunk.id.as_bytes());
+        hasher.update(b"\n");
+        for line in &hunk.lines {
+            let prefix: &[u8] = match line.line_type {
+                LineType::Added => b"+",
+                LineType::Removed => b"-",
+                LineType::Context => b" ",
+            };
+            hasher.update(prefix);
+            hasher.update(line.content.as_bytes());
+            hasher.update(b"\n");
+        }
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// The decision on every hunk in `hunks`. Fails while any is undecided or
+/// saved for later — only a completed review can be attested.
+pub fn hunk_decisions(
+    state: &ReviewState,
+    hunks: &[DiffHunk],
+) -> anyhow::Result<BTreeMap<String, String>> {
+    anyhow::ensure!(!hunks.is_empty(), "The review has no hunks to attest");
+    let mut decisions = BTreeMap::new();
+    let mut undecided = 0;
+    for hunk in hunks {
+        let hunk_state = state.hunks.get(&hunk.id);
+        let decision = match hunk_state.and_then(|h| h.status.as_ref()).map(|s| &s.value) {
+            Some(HunkStatus::Approved) => "approved",
+            Some(HunkStatus::Rejected) => "rejected",
+            None if hunk_state.is_some_and(|h| state.labels_trusted(h.labels())) => "trusted",
+            Some(HunkStatus::SavedForLater) | None => {
+                undecided += 1;
+                continue;
+            }
+        };
+        decisions.insert(hunk.id.clone(), decision.to_owned());
+    }
+    anyhow::ensure!(
+        undecided == 0,
+        "{undecided} hunk(s) still undecided; attest once the review is complete"
+    );
+    Ok(decisions)
+}
+
+/// `ref_name`'s review as it stands, in the form an attestation signs.
+pub fn attested_review(repo_path: &Path, ref_name: &str) -> anyhow::Result<AttestedReview> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let review = targets::resolve(repo_path, ref_name, state.base_override.as_deref())?;
+    let hunks = comparison_hunks(repo_path, &review.comparison, state.github_pr.as_ref())?;
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let resolve = |side: &str| match Endpoint::parse(side) {
+        Endpoint::Ref(git_ref) => source.resolve_ref_or_empty_tree(git_ref),
+        Endpoint::Index | Endpoint::Worktree => side.to_owned(),
+    };
+    let name = source.get_user_name().unwrap_or_default();
+    let reviewer = match source.get_user_email() {
+        Some(email) => format!("{name} <{email}>").trim().to_owned(),
+        None => name,
+    };
+    Ok(AttestedReview {
+        ref_name: ref_name.to_owned(),
+        base: resolve(&review.comparison.base),
+        head: resolve(&review.comparison.head),
+        comparison: review.comparison.key,
+        diff_sha256: diff_digest(&hunks),
+        decisions: hunk_decisions(&state, &hunks)?,
+        reviewer,
+    })
+}
+
+/// Run `program` with `data` on stdin, returning stdout.
+fn run_with_stdin(command: &mut Command, data: &[u8]) -> anyhow::Result<String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}"))?;
+    child
+        .stdin
+        .take()
+        .context("stdin not captured")?
+        .write_all(data)?;
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// An SSH key file for `ssh-keygen -f`: `key` itself when it's a path, or a
+/// temp file holding it when it's a literal public key (as git allows, with
+/// or without a `key::` prefix). The private half then comes from the agent.
+fn ssh_key_file(key: &str) -> anyhow::Result<(PathBuf, Option<tempfile::NamedTempFile>)> {
+    let literal = key.strip_prefix("key::").unwrap_or(key);
+    if !literal.starts_with("ssh-") && !literal.starts_with("ecdsa-") {
+        return Ok((PathBuf::from(key), None));
+    }
+    let mut file = tempfile::Builder::new().suffix(".pub").tempfile()?;
+    writeln!(file, "{literal}")?;
+    file.flush()?;
+    Ok((file.path().to_path_buf(), Some(file)))
+}
+
+/// A detached, armored signature over `data`.
+pub fn sign(key: &SigningKey, data: &[u8]) -> anyhow::Result<String> {
+    match key.format {
+        SignatureFormat::Ssh => {
+            let (path, _temp) = ssh_key_file(&key.key)?;
+            run_with_stdin(
+                Command::new("ssh-keygen")
+                    .args(["-Y", "sign", "-n", SIGNATURE_NAMESPACE, "-f"])
+                    .arg(path),
+                data,
+            )
+        }
+        SignatureFormat::Gpg => {
+            let mut command = Command::new("gpg");
+            command.args(["--armor", "--detach-sign"]);
+            if !key.key.is_empty() {
+                command.args(["--local-user", &key.key]);
+            }
+            run_with_stdin(&mut command, data)
+        }
+    }
+}
+
+/// The public half of an SSH signing key as git config names it: a literal
+/// key, a `.pub` file, or a private key file whose `.pub` sits beside it (or
+/// that `ssh-keygen -y` can read).
+fn ssh_public_key(key: &str) -> anyhow::Result<String> {
+    let literal = key.strip_prefix("key::").unwrap_or(key);
+    let line = if literal.starts_with("ssh-") || literal.starts_with("ecdsa-") {
+        literal.to_owned()
+    } else {
+        let path = PathBuf::from(key);
+        let public = if path.extension().is_some_and(|ext| ext == "pub") {
+            path
+        } else {
+            PathBuf::from(format!("{key}.pub"))
+        };
+        match std::fs::read_to_string(&public) {
+            Ok(content) => content,
+            Err(_) => run_with_stdin(Command::new("ssh-keygen").arg("-y").arg("-f").arg(key), b"")
+                .with_context(|| format!("Failed to read the public key of {key}"))?,
+        }
+    };
+    let fields: Vec<&str> = line.split_whitespace().take(2).collect();
+    match fields.as_slice() {
+        [kind, blob] => Ok(format!("{kind} {blob}")),
+        _ => anyhow::bail!("Not an SSH public key: {key}"),
+    }
+}
+
+/// The fingerprint of the key that made the GPG `signature` over `data`,
+/// from the `VALIDSIG` line of `gpg --status-fd`. Fails unless the signature
+/// verifies.
+fn gpg_signer(signature: &str, data: &[u8]) -> anyhow::Result<String> {
+    let mut sig_file = tempfile::NamedTempFile::new()?;
+    sig_file.write_all(signature.as_bytes())?;
+    sig_file.flush()?;
+    let status = run_with_stdin(
+        Command::new("gpg")
+            .args(["--status-fd", "1", "--verify"])
+            .arg(sig_file.path())
+            .arg("-"),
+        data,
+    )?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("[GNUPG:] VALIDSIG "))
+        .and_then(|fields| fields.split_whitespace().next())
+        .map(str::to_owned)
+        .context("gpg reported no valid signature")
+}
+
+/// Check `signature` over `data`, made with `key` (as recorded on the
+/// attestation). SSH signatures are checked with `ssh-keygen -Y verify`
+/// against an `allowed_signers` file holding only `key`, and GPG ones must
+/// have been made by the key whose fingerprint is `key`, so a signature by
+/// any other key fails; this proves the data is unchanged since `key`
+/// signed it, not that the key is trusted.
+pub fn verify_signature(
+    format: SignatureFormat,
+    key: &str,
+    signature: &str,
+    data: &[u8],
+) -> anyhow::Result<()> {
+    if format == SignatureFormat::Gpg {
+        let signer = gpg_signer(signature, data)?;
+        anyhow::ensure!(
+            signer.eq_ignore_ascii_case(key),
+            "Signed by {signer}, not the recorded key {key:?}"
+        );
+        return Ok(());
+    }
+    let mut sig_file = tempfile::NamedTempFile::new()?;
+    sig_file.write_all(signature.as_bytes())?;
+    sig_file.flush()?;
+    let mut allowed_signers = tempfile::NamedTempFile::new()?;
+    writeln!(
+        allowed_signers,
+        "{SSH_PRINCIPAL} namespaces=\"{SIGNATURE_NAMESPACE}\" {}",
+        ssh_public_key(key)?
+    )?;
+    allowed_signers.flush()?;
+    run_with_stdin(
+        Command::new("ssh-keygen")
+            .args([
+                "-Y",
+                "verify",
+                "-I",
+                SSH_PRINCIPAL,
+                "-n",
+                SIGNATURE_NAMESPACE,
+            ])
+            .arg("-f")
+            .arg(allowed_signers.path())
+            .arg("-s")
+            .arg(sig_file.path()),
+        data,
+    )
+    .map(|_| ())
+}
+
+/// Sign `ref_name`'s completed review and store the attestation on it,
+/// replacing any earlier one.
+pub fn attest(repo_path: &Path, ref_name: &str, key: &SigningKey) -> anyhow::Result<Attestation> {
+    let review = attested_review(repo_path, ref_name)?;
+    let bytes = review.canonical_bytes();
+    let signature = sign(key, &bytes)?;
+    // A GPG key ID or user ID can match several keys; the fingerprint of the
+    // one that signed is what a later check holds the signature to.
+    let recorded_key = match key.format {
+        SignatureFormat::Ssh => key.key.clone(),
+        SignatureFormat::Gpg => gpg_signer(&signature, &bytes)?,
+    };
+    let attestation = Attestation {
+        digest: hex::encode(Sha256::digest(&bytes)),
+        format: key.format,
+        key: recorded_key,
+        signature,
+        signed_at: now_iso8601(),
+        review,
+    };
+    for attempt in 0..MAX_SAVE_RETRIES {
+        let mut state = storage::load_review_state(repo_path, ref_name)?;
+        state.attestation = Some(attestation.clone());
+        state.prepare_for_save();
+        match storage::save_review_state(repo_path, &state) {
+            Ok(()) => return Ok(attestation),
+            Err(StorageError::VersionConflict { .. }) if attempt + 1 < MAX_SAVE_RETRIES => {}
+            Err(e) => return Err(e).context("Failed to save review"),
+        }
+    }
+    anyhow::bail!("Failed to save review after repeated version conflicts")
+}
+
+/// How `current` differs from what was signed, ignoring who is looking.
+fn review_changes(signed: &AttestedReview, current: &AttestedReview) -> Vec<String> {
+    let mut changes = Vec::new();
+    if (&signed.base, &signed.head) != (&current.base, &current.head) {
+        changes.push(format!(
+            "comparison moved from {}..{} to {}..{}",
+            signed.base, signed.head, current.base, current.head
+        ));
+    }
+    if signed.diff_sha256 != current.diff_sha256 {
+        changes.push("the diff changed".to_owned());
+    }
+    if signed.decisions != current.decisions {
+        let differing = signed
+            .decisions
+            .iter()
+            .filter(|(id, decision)| current.decisions.get(*id) != Some(decision))
+            .count()
+            + current
+                .decisions
+                .keys()
+                .filter(|id| !signed.decisions.contains_key(*id))
+                .count();
+        changes.push(format!("{differing} hunk decision(s) changed"));
+    }
+    changes
+}
+
+/// Check `ref_name`'s attestation: its signature, and whether the review
+/// still matches what was signed.
+pub fn verify(repo_path: &Path, ref_name: &str) -> anyhow::Result<AttestationCheck> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let attestation = state
+        .attestation
+        .context("This review has no attestation; sign it with `review attest`")?;
+    let bytes = attestation.review.canonical_bytes();
+    let signature_valid = hex::encode(Sha256::digest(&bytes)) == attestation.digest
+        && verify_signature(
+            attestation.format,
+            &attestation.key,
+            &attestation.signature,
+            &bytes,
+        )
+        .map_err(|e| log::debug!("[attestation] {ref_name}: {e:#}"))
+        .is_ok();
+    let changes = match attested_review(repo_path, ref_name) {
+        Ok(current) => review_changes(&attestation.review, &current),
+        Err(e) => vec![format!("the review no longer reads as complete: {e:#}")],
+    };
+    Ok(AttestationCheck {
+        attestation,
+        signature_valid,
+        changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::parse_diff;
+    use crate::review::state::{Attributed, HunkState, Source};
+
+    const DIFF: &str = "@@ -1,2 +1,2 @@\n a\n-b\n+c\n@@ -10,1 +10,1 @@\n-x\n+y\n";
+
+    #[test]
+    fn only_complete_reviews_have_decisions() {
+        let hunks = parse_diff(DIFF, "f.txt");
+        let mut state = ReviewState::new("feature", None);
+        state.hunks.insert(
+            hunks[0].id.clone(),
+            HunkState {
+                status: Some(Attributed::new(HunkStatus::Approved, Source::Ui)),
+                ..HunkState::default()
+            },
+        );
+        let err = hunk_decisions(&state, &hunks).unwrap_err();
+        assert!(err.to_string().contains("1 hunk(s) still undecided"));
+
+        state.hunks.insert(
+            hunks[1].id.clone(),
+            HunkState {
+                status: Some(Attributed::new(HunkStatus::Rejected, Source::Cli)),
+                ..HunkState::default()
+            },
+        );
+        let decisions = hunk_decisions(&state, &hunks).unwrap();
+        assert_eq!(decisions[&hunks[1].id], "rejected");
+
+        let mut reversed = hunks.clone();
+        reversed.reverse();
+        assert_eq!(diff_digest(&hunks), diff_digest(&reversed));
+        assert_ne!(diff_digest(&hunks), diff_digest(&hunks[..1]));
+    }
+
+    #[test]
+    fn ssh_signatures_verify_only_the_signed_bytes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let key_path = dir.path().join("id_ed25519");
+        let generated = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key_path)
+            .status();
+        if !generated.is_ok_and(|status| status.success()) {
+            return; // No ssh-keygen here.
+        }
+        let key = SigningKey {
+            format: SignatureFormat::Ssh,
+            key: key_path.display().to_string(),
+        };
+        let signature = sign(&key, b"reviewed").unwrap();
+        assert!(signature.contains("BEGIN SSH SIGNATURE"));
+        verify_signature(SignatureFormat::Ssh, &key.key, &signature, b"reviewed").unwrap();
+        assert!(verify_signature(SignatureFormat::Ssh, &key.key, &signature, b"tampered").is_err());
+
+        // Valid, but not by the key the attestation records.
+        let other_path = dir.path().join("id_other");
+        assert!(Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&other_path)
+            .status()
+            .unwrap()
+            .success());
+        let other = other_path.display().to_string();
+        assert!(verify_signature(SignatureFormat::Ssh, &other, &signature, b"reviewed").is_err());
+        let literal = std::fs::read_to_string(other_path.with_extension("pub")).unwrap();
+        assert!(verify_signature(SignatureFormat::Ssh, &literal, &signature, b"reviewed").is_err());
+    }
+
+    #[test]
+    fn gpg_signatures_verify_only_by_the_recorded_key() {
+        use crate::review::central::tests::ENV_LOCK;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let home = tempfile::TempDir::new().unwrap();
+        let previous = std::env::var_os("GNUPGHOME");
+        std::env::set_var("GNUPGHOME", home.path());
+        let generate = |user: &str| {
+            Command::new("gpg")
+                .args(["--batch", "--passphrase", "", "--quick-gen-key", user])
+                .args(["ed25519", "sign", "never"])
+                .output()
+                .is_ok_and(|output| output.status.success())
+        };
+        // No gpg here.
+        if generate("Signer <signer@example.com>") && generate("Other <other@example.com>") {
+            let key = SigningKey {
+                format: SignatureFormat::Gpg,
+                key: "signer@example.com".to_owned(),
+            };
+            let signature = sign(&key, b"reviewed").unwrap();
+            let fingerprint = gpg_signer(&signature, b"reviewed").unwrap();
+            verify_signature(SignatureFormat::Gpg, &fingerprint, &signature, b"reviewed").unwrap();
+            assert!(
+                verify_signature(SignatureFormat::Gpg, &fingerprint, &signature, b"tampered")
+                    .is_err()
+            );
+
+            // Valid, but not by the key the attestation records.
+            let other = sign(
+                &SigningKey {
+                    format: SignatureFormat::Gpg,
+                    key: "other@example.com".to_owned(),
+                },
+                b"reviewed",
+            )
+            .unwrap();
+            let err = verify_signature(SignatureFormat::Gpg, &fingerprint, &other, b"reviewed")
+                .unwrap_err();
+            assert!(err.to_string().starts_with("Signed by "), "{err}");
+            assert!(verify_signature(SignatureFormat::Gpg, "", &signature, b"reviewed").is_err());
+        }
+
+        let _ = Command::new("gpgconf")
+            .args(["--kill", "gpg-agent"])
+            .status();
+        match previous {
+            Some(value) => std::env::set_var("GNUPGHOME", value),
+            None => std::env::remove_var("GNUPGHOME"),
+        }
+    }
+}
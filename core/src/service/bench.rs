@@ -0,0 +1,242 @@
+//! Benchmark a review's base and head and flag the benchmarks it slowed.
+//!
+//! [`run_benchmarks`] runs the repo's `[bench]` command (see
+//! [`crate::review::repo_config`]) at the merge base and the head, each in
+//! its own kept worktree like [`super::build_size`], parses the timings it
+//! prints, and marks every benchmark that got slower by more than the
+//! threshold as a `performance:regression`. Timings are cached per commit.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use anyhow::{bail, Context};
+use log::info;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::review::central;
+use crate::review::repo_config::{BenchConfig, RepoConfig};
+use crate::review::state::{now_iso8601, BenchDelta, BenchReport};
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::Comparison;
+
+use super::build_size::{base_and_head, worktree_at};
+
+/// Flag on benchmarks that got slower than the threshold allows.
+pub const PERFORMANCE_REGRESSION_LABEL: &str = "performance:regression";
+
+/// How a benchmark command prints its results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BenchFormat {
+    /// `test name ... bench: 1,234 ns/iter (+/- 56)`: libtest's `cargo
+    /// bench`, and Criterion with `--output-format bencher`.
+    Bencher,
+    /// `BenchmarkName-8  1000  1234 ns/op`: `go test -bench`.
+    Go,
+    /// A JSON object of benchmark name to nanoseconds.
+    Json,
+}
+
+static BENCHER_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^test (\S+)\s+\.\.\. bench:\s+([\d,.]+) ns/iter").expect("valid regex")
+});
+static GO_LINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(Benchmark\S+)\s+\d+\s+([\d.]+) ns/op").expect("valid regex"));
+
+/// Parse benchmark timings (nanoseconds per iteration) from `output`.
+pub fn parse_timings(format: BenchFormat, output: &str) -> anyhow::Result<BTreeMap<String, f64>> {
+    let line_regex = match format {
+        BenchFormat::Json => {
+            let json = output.find('{').map_or(output, |start| &output[start..]);
+            return serde_json::from_str(json).context("Not a JSON object of benchmark timings");
+        }
+        BenchFormat::Bencher => &*BENCHER_LINE,
+        BenchFormat::Go => &*GO_LINE,
+    };
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let captures = line_regex.captures(line.trim())?;
+            let ns = captures[2].replace(',', "").parse().ok()?;
+            Some((captures[1].to_owned(), ns))
+        })
+        .collect())
+}
+
+/// The repo's `[bench]` table.
+pub fn bench_config(repo_path: &Path) -> anyhow::Result<BenchConfig> {
+    RepoConfig::load(repo_path)
+        .and_then(|c| c.bench)
+        .context("No benchmarks configured; add a [bench] table to .review.toml")
+}
+
+fn cache_path(repo_path: &Path, config: &BenchConfig, commit: &str) -> anyhow::Result<PathBuf> {
+    let mut hasher = Sha256::new();
+    for part in [commit, config.command.as_str()] {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\0");
+    }
+    let key = hex::encode(&hasher.finalize()[..16]);
+    Ok(central::get_repo_cache_dir(repo_path)?
+        .join("bench")
+        .join(format!("{key}.json")))
+}
+
+/// Benchmark `commit` in `side`'s worktree, or reuse an earlier run's timings.
+fn bench_commit(
+    repo_path: &Path,
+    source: &LocalGitSource,
+    config: &BenchConfig,
+    side: &str,
+    commit: &str,
+) -> anyhow::Result<BTreeMap<String, f64>> {
+    let cache = cache_path(repo_path, config, commit)?;
+    if let Some(timings) = fs::read_to_string(&cache)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+    {
+        info!("[bench] {side} {commit}: cached");
+        return Ok(timings);
+    }
+
+    let dir = worktree_at(repo_path, source, &format!("bench-{side}"), commit)?;
+    info!("[bench] running {side} {commit}: {}", config.command);
+    let output = Command::new("sh")
+        .args(["-c", &config.command])
+        .current_dir(&dir)
+        .output()
+        .with_context(|| format!("Failed to run {:?}", config.command))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(20).collect();
+        bail!(
+            "The benchmarks failed at the {side} ({commit}):\n{}",
+            tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+        );
+    }
+    let timings = parse_timings(config.format, &String::from_utf8_lossy(&output.stdout))?;
+    if timings.is_empty() {
+        bail!("The benchmarks at the {side} ({commit}) printed no timings");
+    }
+    if let Some(parent) = cache.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&cache, serde_json::to_vec(&timings)?)?;
+    Ok(timings)
+}
+
+/// Line up each side's timings by benchmark name and flag the ones more than
+/// `threshold_pct` slower at the head.
+pub fn compare_timings(
+    base: &BTreeMap<String, f64>,
+    head: &BTreeMap<String, f64>,
+    threshold_pct: f64,
+) -> Vec<BenchDelta> {
+    let mut names: Vec<&String> = base.keys().chain(head.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| {
+            let (base_ns, head_ns) = (base.get(name).copied(), head.get(name).copied());
+            let change_pct = match (base_ns, head_ns) {
+                (Some(b), Some(h)) if b > 0.0 => Some((h - b) / b * 100.0),
+                _ => None,
+            };
+            BenchDelta {
+                name: name.clone(),
+                base_ns,
+                head_ns,
+                change_pct,
+                regression: change_pct.is_some_and(|pct| pct > threshold_pct),
+            }
+        })
+        .collect()
+}
+
+/// Benchmark the merge base and head of `comparison` with `config`.
+pub fn run_benchmarks(
+    repo_path: &Path,
+    comparison: &Comparison,
+    config: &BenchConfig,
+) -> anyhow::Result<BenchReport> {
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let (base, head) = base_and_head(&source, comparison)?;
+    let base_timings = bench_commit(repo_path, &source, config, "base", &base)?;
+    let head_timings = bench_commit(repo_path, &source, config, "head", &head)?;
+    Ok(BenchReport {
+        command: config.command.clone(),
+        base,
+        head,
+        threshold_pct: config.threshold,
+        benchmarks: compare_timings(&base_timings, &head_timings, config.threshold),
+        measured_at: now_iso8601(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_each_format() {
+        let bencher = "running 2 tests\n\
+             test parse::small ... bench:       1,234 ns/iter (+/- 56)\n\
+             test parse::large ... bench:   98,765.50 ns/iter (+/- 1,000)\n\
+             test result: ok. 0 passed";
+        let expected = |pairs: &[(&str, f64)]| -> BTreeMap<String, f64> {
+            pairs
+                .iter()
+                .map(|&(name, ns)| (name.to_owned(), ns))
+                .collect()
+        };
+        assert_eq!(
+            parse_timings(BenchFormat::Bencher, bencher).unwrap(),
+            expected(&[("parse::small", 1234.0), ("parse::large", 98765.5)])
+        );
+
+        let go = "goos: linux\nBenchmarkParse-8   \t  500000\t      2456 ns/op\nPASS\n";
+        assert_eq!(
+            parse_timings(BenchFormat::Go, go).unwrap(),
+            expected(&[("BenchmarkParse-8", 2456.0)])
+        );
+
+        let json = "building...\n{\"parse\": 12.5}";
+        assert_eq!(
+            parse_timings(BenchFormat::Json, json).unwrap(),
+            expected(&[("parse", 12.5)])
+        );
+        assert!(parse_timings(BenchFormat::Json, "nope").is_err());
+    }
+
+    #[test]
+    fn flags_slowdowns_above_the_threshold() {
+        let base = BTreeMap::from([
+            ("a".to_owned(), 100.0),
+            ("b".to_owned(), 100.0),
+            ("gone".to_owned(), 1.0),
+        ]);
+        let head = BTreeMap::from([
+            ("a".to_owned(), 104.0),
+            ("b".to_owned(), 120.0),
+            ("new".to_owned(), 1.0),
+        ]);
+        let deltas = compare_timings(&base, &head, 5.0);
+        let flagged: Vec<&str> = deltas
+            .iter()
+            .filter(|d| d.regression)
+            .map(|d| d.name.as_str())
+            .collect();
+        assert_eq!(flagged, ["b"]);
+        assert_eq!(deltas[1].change_pct, Some(20.0));
+        assert_eq!(
+            deltas.iter().find(|d| d.name == "new").unwrap().change_pct,
+            None
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Find the commit in a review's range where a test command started failing.
+//!
+//! [`bisect_review`] checks out the review's head in a scratch worktree, so
+//! the user's checkout is never touched, confirms the command fails there and
+//! passes at the merge base, then lets `git bisect run` narrow it down. The
+//! culprit's diff is matched back onto the review's hunks so the UI can point
+//! at the changes that broke it.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use log::{info, warn};
+
+use crate::diff::parser::{parse_multi_file_diff, DiffHunk, LineType};
+use crate::review::state::{now_iso8601, BisectResult};
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::Endpoint;
+
+use super::targets::ResolvedReview;
+
+/// Removes the scratch worktree, ending any bisect left in it, however the
+/// run ends.
+struct ScratchWorktree<'a> {
+    source: &'a LocalGitSource,
+    path: PathBuf,
+}
+
+impl Drop for ScratchWorktree<'_> {
+    fn drop(&mut self) {
+        let _ = self.source.run_git_in(&self.path, &["bisect", "reset"]);
+        if let Err(e) = self
+            .source
+            .remove_review_worktree(&self.path.to_string_lossy())
+        {
+            warn!(
+                "[bisect] failed to remove worktree {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Run the test command in `dir`; `true` when it exits 0.
+fn command_passes(dir: &Path, command: &str) -> anyhow::Result<bool> {
+    let status = Command::new("sh")
+        .args(["-c", command])
+        .current_dir(dir)
+        .status()
+        .with_context(|| format!("Failed to run {command:?}"))?;
+    Ok(status.success())
+}
+
+/// The SHA from `git bisect`'s "<sha> is the first bad commit" line.
+fn first_bad_commit(output: &str) -> Option<&str> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_suffix(" is the first bad commit"))
+}
+
+/// Ids of the review's hunks that carry `culprit`'s changes: in a file the
+/// culprit touched, and adding or removing a line the culprit did.
+pub fn hunks_touched_by(hunks: &[DiffHunk], culprit: &[DiffHunk]) -> Vec<String> {
+    let changed = |hunk: &DiffHunk| {
+        hunk.lines
+            .iter()
+            .filter(|line| {
+                matches!(line.line_type, LineType::Added | LineType::Removed)
+                    && !line.content.trim().is_empty()
+            })
+            .map(|line| (line.line_type.clone(), line.content.trim().to_owned()))
+            .collect::<Vec<_>>()
+    };
+    hunks
+        .iter()
+        .filter(|hunk| {
+            let lines = changed(hunk);
+            culprit
+                .iter()
+                .filter(|c| c.file_path == hunk.file_path)
+                .any(|c| changed(c).iter().any(|line| lines.contains(line)))
+        })
+        .map(|hunk| hunk.id.clone())
+        .collect()
+}
+
+/// Bisect `review`'s commit range with `command`, a shell command that exits
+/// 0 when the code is good. `hunks` are the review's hunks, to map the
+/// culprit back onto.
+pub fn bisect_review(
+    repo_path: &Path,
+    review: &ResolvedReview,
+    hunks: &[DiffHunk],
+    command: &str,
+) -> anyhow::Result<BisectResult> {
+    let comparison = &review.comparison;
+    let (Endpoint::Ref(base), Endpoint::Ref(head)) = (
+        Endpoint::parse(&comparison.base),
+        Endpoint::parse(&comparison.head),
+    ) else {
+        bail!("{} has uncommitted changes to bisect", comparison.key);
+    };
+    if base.is_empty() {
+        bail!("{} has no base commit to bisect from", comparison.key);
+    }
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let bad = source
+        .resolve_ref(head)
+        .with_context(|| format!("Unknown ref {head}"))?;
+    let good = source.diff_base_ref(comparison);
+    if good == bad {
+        bail!("{} has no commits to bisect", comparison.key);
+    }
+
+    let worktree = source
+        .create_review_worktree(&format!("bisect-{}", review.ref_name), &bad)
+        .context("Failed to create a worktree to bisect in")?;
+    let worktree = ScratchWorktree {
+        source: &source,
+        path: PathBuf::from(worktree.path),
+    };
+    let dir = worktree.path.as_path();
+
+    info!("[bisect] checking {command:?} fails at {bad}");
+    if command_passes(dir, command)? {
+        bail!(
+            "{command:?} passes at the head of {}; nothing to bisect",
+            comparison.key
+        );
+    }
+    source.run_git_in(dir, &["checkout", "-q", "--detach", &good])?;
+    info!("[bisect] checking {command:?} passes at {good}");
+    if !command_passes(dir, command)? {
+        bail!(
+            "{command:?} already fails at the base of {}; the breakage predates the review",
+            comparison.key
+        );
+    }
+
+    source.run_git_in(dir, &["bisect", "start", &bad, &good])?;
+    let output = source.run_git_in(dir, &["bisect", "run", "sh", "-c", command])?;
+    let culprit = first_bad_commit(&output)
+        .with_context(|| format!("git bisect found no first bad commit:\n{output}"))?
+        .to_owned();
+    drop(worktree);
+
+    let detail = source.get_commit_detail(&culprit)?;
+    let culprit_hunks = parse_multi_file_diff(&detail.diff);
+    Ok(BisectResult {
+        command: command.to_owned(),
+        good,
+        bad,
+        short_hash: detail.short_hash,
+        subject: detail.message.lines().next().unwrap_or_default().to_owned(),
+        author: detail.author,
+        at: now_iso8601(),
+        hunks: hunks_touched_by(hunks, &culprit_hunks),
+        culprit,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::service::targets;
+    use crate::sources::traits::DiffSource;
+    use std::fs;
+
+    fn git(dir: &Path, args: &[&str]) -> anyhow::Result<()> {
+        let status = Command::new("git").args(args).current_dir(dir).status()?;
+        anyhow::ensure!(status.success(), "git {args:?} failed");
+        Ok(())
+    }
+
+    fn commit(dir: &Path, file: &str, content: &str, message: &str) {
+        fs::write(dir.join(file), content).unwrap();
+        for args in [
+            &["add", "."][..],
+            &[
+                "-c",
+                "user.name=t",
+                "-c",
+                "user.email=t@example.com",
+                "commit",
+                "-qm",
+                message,
+            ],
+        ] {
+            git(dir, args).unwrap();
+        }
+    }
+
+    #[test]
+    fn finds_the_commit_that_broke_the_command() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let root = repo.path();
+        git(root, &["init", "-q", "-b", "main"]).unwrap();
+        commit(root, "status", "ok\n", "init");
+        git(root, &["checkout", "-qb", "feature"]).unwrap();
+        commit(root, "notes", "one\n", "add notes");
+        commit(root, "status", "broken\n", "break status");
+        commit(root, "notes", "one\ntwo\n", "more notes");
+        git(root, &["checkout", "-q", "main"]).unwrap();
+
+        let review = targets::resolve(root, "feature", None).unwrap();
+        let source = LocalGitSource::new(root.to_path_buf()).unwrap();
+        let diff = source.get_diff(&review.comparison, None).unwrap();
+        let hunks = parse_multi_file_diff(&diff);
+
+        let result = bisect_review(root, &review, &hunks, "grep -qx ok status").unwrap();
+        assert_eq!(result.subject, "break status");
+        assert_eq!(result.hunks.len(), 1);
+        assert!(result.hunks[0].starts_with("status:"));
+        assert_eq!(source.list_worktrees().unwrap().len(), 1);
+
+        let err = bisect_review(root, &review, &hunks, "true").unwrap_err();
+        assert!(err.to_string().contains("nothing to bisect"));
+    }
+
+    #[test]
+    fn parses_first_bad_commit() {
+        let output = "Bisecting: 0 revisions left\nabc123 is the first bad commit\ncommit abc123\n";
+        assert_eq!(first_bad_commit(output), Some("abc123"));
+        assert_eq!(first_bad_commit("bisect found nothing"), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! The shape of a change before diving in: lines and hunks per language and
+//! per directory, and how much of it is generated code or tests.
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::classify::static_rules::is_test_path;
+use crate::diff::parser::DiffHunk;
+use crate::service::onboarding::{is_generated_path, language_for_path};
+
+/// Bucket for files in no known language.
+pub const OTHER_LANGUAGE: &str = "Other";
+
+/// Change totals for one language, one directory, or the whole comparison.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeBucket {
+    pub name: String,
+    pub files: usize,
+    pub hunks: usize,
+    pub lines_added: usize,
+    pub lines_removed: usize,
+    /// Changed lines in lockfiles and generated or vendored files.
+    pub generated_lines: usize,
+    /// Changed lines in test files.
+    pub test_lines: usize,
+    /// Share of changed lines that are generated, 0–100.
+    pub generated_pct: f64,
+    /// Share of changed lines that are in tests, 0–100.
+    pub test_pct: f64,
+    #[serde(skip)]
+    paths: HashSet<String>,
+}
+
+impl ChangeBucket {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn lines_changed(&self) -> usize {
+        self.lines_added + self.lines_removed
+    }
+
+    fn add(&mut self, hunk: &DiffHunk, added: usize, removed: usize) {
+        if self.paths.insert(hunk.file_path.clone()) {
+            self.files += 1;
+        }
+        self.hunks += 1;
+        self.lines_added += added;
+        self.lines_removed += removed;
+        if is_generated_path(&hunk.file_path) {
+            self.generated_lines += added + removed;
+        }
+        if is_test_path(&hunk.file_path) {
+            self.test_lines += added + removed;
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        let changed = self.lines_changed();
+        let pct = |n: usize| match changed {
+            0 => 0.0,
+            total => (n as f64 * 1000.0 / total as f64).round() / 10.0,
+        };
+        self.generated_pct = pct(self.generated_lines);
+        self.test_pct = pct(self.test_lines);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeBreakdown {
+    pub total: ChangeBucket,
+    /// Most changed lines first.
+    pub languages: Vec<ChangeBucket>,
+    /// Directories cut to the requested depth, most changed lines first.
+    pub directories: Vec<ChangeBucket>,
+}
+
+/// `src/diff/parser.rs` at depth 2 is `src/diff`; files at the root are `.`.
+pub fn directory_at_depth(path: &str, depth: usize) -> String {
+    let dirs: Vec<&str> = path.split('/').collect();
+    let dirs = &dirs[..dirs.len() - 1];
+    if dirs.is_empty() || depth == 0 {
+        ".".to_owned()
+    } else {
+        dirs[..depth.min(dirs.len())].join("/")
+    }
+}
+
+/// Break a comparison's hunks down by language and by directory, the
+/// latter grouped `depth` levels deep.
+pub fn change_breakdown(hunks: &[DiffHunk], depth: usize) -> ChangeBreakdown {
+    let mut total = ChangeBucket::new("total");
+    let mut languages: BTreeMap<&str, ChangeBucket> = BTreeMap::new();
+    let mut directories: BTreeMap<String, ChangeBucket> = BTreeMap::new();
+    for hunk in hunks {
+        let (added, removed) = hunk.line_counts();
+        total.add(hunk, added, removed);
+        let language = language_for_path(&hunk.file_path).unwrap_or(OTHER_LANGUAGE);
+        languages
+            .entry(language)
+            .or_insert_with(|| ChangeBucket::new(language))
+            .add(hunk, added, removed);
+        let directory = directory_at_depth(&hunk.file_path, depth);
+        directories
+            .entry(directory.clone())
+            .or_insert_with(|| ChangeBucket::new(&directory))
+            .add(hunk, added, removed);
+    }
+
+    let ranked = |buckets: Vec<ChangeBucket>| {
+        let mut buckets: Vec<ChangeBucket> =
+            buckets.into_iter().map(ChangeBucket::finish).collect();
+        buckets.sort_by_key(|b| std::cmp::Reverse(b.lines_changed()));
+        buckets
+    };
+    ChangeBreakdown {
+        total: total.finish(),
+        languages: ranked(languages.into_values().collect()),
+        directories: ranked(directories.into_values().collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::LineType;
+
+    fn hunk(path: &str, added: usize, removed: usize) -> DiffHunk {
+        let lines = std::iter::repeat_n((LineType::Added, "x"), added)
+            .chain(std::iter::repeat_n((LineType::Removed, "x"), removed))
+            .chain([(LineType::Context, "x")]);
+        DiffHunk::test(path, lines)
+    }
+
+    #[test]
+    fn breaks_down_by_language_and_directory() {
+        let hunks = [
+            hunk("core/src/lib.rs", 6, 2),
+            hunk("core/src/lib.rs", 2, 0),
+            hunk("core/tests/api_test.rs", 10, 0),
+            hunk("ui/app.ts", 3, 1),
+            hunk("Cargo.lock", 4, 4),
+            hunk("README", 1, 0),
+        ];
+        let breakdown = change_breakdown(&hunks, 2);
+
+        assert_eq!(breakdown.total.files, 5);
+        assert_eq!(breakdown.total.hunks, 6);
+        assert_eq!(breakdown.total.lines_changed(), 33);
+        assert_eq!(breakdown.total.test_lines, 10);
+        assert_eq!(breakdown.total.generated_lines, 8);
+        assert!((breakdown.total.generated_pct - 24.2).abs() < 1e-9);
+
+        let rust = &breakdown.languages[0];
+        assert_eq!((rust.name.as_str(), rust.files, rust.hunks), ("Rust", 2, 3));
+        assert!((rust.test_pct - 50.0).abs() < 1e-9);
+        assert_eq!(breakdown.languages[1].name, OTHER_LANGUAGE);
+
+        let dirs: Vec<(&str, usize)> = breakdown
+            .directories
+            .iter()
+            .map(|d| (d.name.as_str(), d.lines_changed()))
+            .collect();
+        assert_eq!(
+            dirs,
+            [("core/src", 10), ("core/tests", 10), (".", 9), ("ui", 4)]
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! Measure how a review changes the size of its build artifacts.
+//!
+//! [`measure_build_size`] builds the review's base and head commits with the
+//! repo's `[build]` command (see [`crate::review::repo_config`]) and sizes
+//! the artifacts its globs match on each side. Each side builds in its own
+//! review-managed worktree that is kept between runs and moved to the next
+//! commit, so the build tool's incremental caches survive; sizes are cached
+//! per commit, so a side whose commit hasn't moved isn't rebuilt at all.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use log::{info, warn};
+use sha2::{Digest, Sha256};
+
+use crate::review::central;
+use crate::review::repo_config::{BuildConfig, RepoConfig};
+use crate::review::state::{now_iso8601, ArtifactSize, BuildSizeReport};
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::{Comparison, Endpoint};
+
+/// The repo's `[build]` table.
+pub fn build_config(repo_path: &Path) -> anyhow::Result<BuildConfig> {
+    match RepoConfig::load(repo_path).and_then(|c| c.build) {
+        Some(config) if !config.artifacts.is_empty() => Ok(config),
+        Some(_) => bail!("[build] in .review.toml lists no artifacts to measure"),
+        None => bail!("No build configured; add a [build] table to .review.toml"),
+    }
+}
+
+/// The review-managed worktree `name`, moved to `commit`: reused when a
+/// previous run left it, else created.
+pub(super) fn worktree_at(
+    repo_path: &Path,
+    source: &LocalGitSource,
+    name: &str,
+    commit: &str,
+) -> anyhow::Result<PathBuf> {
+    let existing = central::get_worktree_base_dir(repo_path)?.join(name);
+    if existing.join(".git").exists() {
+        let moved = source
+            .run_git_in(
+                &existing,
+                &["checkout", "--quiet", "--force", "--detach", commit],
+            )
+            .and_then(|_| source.run_git_in(&existing, &["clean", "-fdq"]));
+        match moved {
+            Ok(_) => return Ok(existing),
+            Err(e) => {
+                warn!("[worktree_at] recreating {}: {e:#}", existing.display());
+                let _ = source.remove_review_worktree(&existing.to_string_lossy());
+            }
+        }
+    }
+    let worktree = source
+        .create_review_worktree(name, commit)
+        .context("Failed to create a worktree to build in")?;
+    Ok(PathBuf::from(worktree.path))
+}
+
+/// Sizes of the files under `dir` matching `globs`, keyed by relative path.
+fn artifact_sizes(dir: &Path, globs: &[String]) -> anyhow::Result<BTreeMap<String, u64>> {
+    let mut sizes = BTreeMap::new();
+    for pattern in globs {
+        let full = dir.join(pattern);
+        let paths = glob::glob(&full.to_string_lossy())
+            .with_context(|| format!("Invalid artifact glob {pattern:?}"))?;
+        for path in paths.flatten() {
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            if metadata.is_file() {
+                let relative = path.strip_prefix(dir).unwrap_or(&path);
+                sizes.insert(relative.to_string_lossy().into_owned(), metadata.len());
+            }
+        }
+    }
+    Ok(sizes)
+}
+
+fn cache_path(repo_path: &Path, config: &BuildConfig, commit: &str) -> anyhow::Result<PathBuf> {
+    let mut hasher = Sha256::new();
+    for part in [commit, config.command.as_str()]
+        .into_iter()
+        .chain(config.artifacts.iter().map(String::as_str))
+    {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\0");
+    }
+    let key = hex::encode(&hasher.finalize()[..16]);
+    Ok(central::get_repo_cache_dir(repo_path)?
+        .join("build-size")
+        .join(format!("{key}.json")))
+}
+
+/// Build `commit` in `side`'s worktree and size its artifacts, or reuse the
+/// sizes from an earlier build of the same commit.
+fn measure_commit(
+    repo_path: &Path,
+    source: &LocalGitSource,
+    config: &BuildConfig,
+    side: &str,
+    commit: &str,
+) -> anyhow::Result<BTreeMap<String, u64>> {
+    let cache = cache_path(repo_path, config, commit)?;
+    if let Some(sizes) = fs::read_to_string(&cache)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+    {
+        info!("[build_size] {side} {commit}: cached");
+        return Ok(sizes);
+    }
+
+    let dir = worktree_at(repo_path, source, &format!("build-size-{side}"), commit)?;
+    info!("[build_size] building {side} {commit}: {}", config.command);
+    let output = Command::new("sh")
+        .args(["-c", &config.command])
+        .current_dir(&dir)
+        .output()
+        .with_context(|| format!("Failed to run {:?}", config.command))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let tail: Vec<&str> = stderr.lines().rev().take(20).collect();
+        bail!(
+            "The build failed at the {side} ({commit}):\n{}",
+            tail.into_iter().rev().collect::<Vec<_>>().join("\n")
+        );
+    }
+    let sizes = artifact_sizes(&dir, &config.artifacts)?;
+    if let Some(parent) = cache.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&cache, serde_json::to_vec(&sizes)?)?;
+    Ok(sizes)
+}
+
+/// Line up each side's artifacts by path.
+pub fn compare_sizes(
+    base: &BTreeMap<String, u64>,
+    head: &BTreeMap<String, u64>,
+) -> Vec<ArtifactSize> {
+    let mut paths: Vec<&String> = base.keys().chain(head.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .map(|path| ArtifactSize {
+            path: path.clone(),
+            base_bytes: base.get(path).copied(),
+            head_bytes: head.get(path).copied(),
+        })
+        .collect()
+}
+
+/// The commits to build for `comparison`: its merge base and head. Fails
+/// for comparisons with uncommitted changes or no base.
+pub(super) fn base_and_head(
+    source: &LocalGitSource,
+    comparison: &Comparison,
+) -> anyhow::Result<(String, String)> {
+    let (Endpoint::Ref(base), Endpoint::Ref(head)) = (
+        Endpoint::parse(&comparison.base),
+        Endpoint::parse(&comparison.head),
+    ) else {
+        bail!(
+            "{} has uncommitted changes, which aren't built",
+            comparison.key
+        );
+    };
+    if base.is_empty() {
+        bail!("{} has no base commit to build", comparison.key);
+    }
+    let head = source
+        .resolve_ref(head)
+        .with_context(|| format!("Unknown ref {head}"))?;
+    Ok((source.diff_base_ref(comparison), head))
+}
+
+/// Build the base (merge base) and head commits of `comparison` with
+/// `config` and compare their artifacts' sizes.
+pub fn measure_build_size(
+    repo_path: &Path,
+    comparison: &Comparison,
+    config: &BuildConfig,
+) -> anyhow::Result<BuildSizeReport> {
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let (base, head) = base_and_head(&source, comparison)?;
+
+    let base_sizes = measure_commit(repo_path, &source, config, "base", &base)?;
+    let head_sizes = measure_commit(repo_path, &source, config, "head", &head)?;
+    if base_sizes.is_empty() && head_sizes.is_empty() {
+        bail!(
+            "The build produced nothing matching {}",
+            config.artifacts.join(", ")
+        );
+    }
+    Ok(BuildSizeReport {
+        command: config.command.clone(),
+        base,
+        head,
+        artifacts: compare_sizes(&base_sizes, &head_sizes),
+        measured_at: now_iso8601(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_artifacts_present_on_either_side() {
+        let base = BTreeMap::from([("app.js".to_owned(), 100), ("old.js".to_owned(), 40)]);
+        let head = BTreeMap::from([("app.js".to_owned(), 130), ("new.js".to_owned(), 5)]);
+        let artifacts = compare_sizes(&base, &head);
+        let deltas: Vec<(&str, i64)> = artifacts
+            .iter()
+            .map(|a| (a.path.as_str(), a.delta()))
+            .collect();
+        assert_eq!(deltas, [("app.js", 30), ("new.js", 5), ("old.js", -40)]);
+        let report = BuildSizeReport {
+            command: "make".to_owned(),
+            base: "a".to_owned(),
+            head: "b".to_owned(),
+            artifacts,
+            measured_at: String::new(),
+        };
+        assert_eq!(report.delta(), -5);
+    }
+
+    #[test]
+    fn sizes_matching_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("dist/sub")).unwrap();
+        fs::write(dir.path().join("dist/a.js"), "abc").unwrap();
+        fs::write(dir.path().join("dist/sub/b.js"), "abcdef").unwrap();
+        fs::write(dir.path().join("dist/c.css"), "x").unwrap();
+        let sizes = artifact_sizes(dir.path(), &["dist/**/*.js".to_owned()]).unwrap();
+        assert_eq!(
+            sizes,
+            BTreeMap::from([("dist/a.js".to_owned(), 3), ("dist/sub/b.js".to_owned(), 6)])
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! Review checklist reads and check/uncheck writes, shared by the desktop app
+//! and HTTP server. The template and sync rules live in
+//! [`crate::review::checklist`].
+
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::review::checklist::{
+    find_item, load_checklist_template, set_item_checked, sync_checklist,
+};
+use crate::review::state::{ChecklistItem, ReviewState, Source};
+use crate::review::storage;
+
+/// Load a review with its checklist synced against the repo's template. Not
+/// persisted — the synced list is written on the next save.
+fn load_synced(repo_path: &Path, ref_name: &str) -> anyhow::Result<ReviewState> {
+    let mut state = storage::load_review_state(repo_path, ref_name)?;
+    if let Some(template) = load_checklist_template(repo_path) {
+        sync_checklist(&mut state, &template);
+    }
+    Ok(state)
+}
+
+/// The review's checklist, synced against the repo's current template.
+pub fn get_checklist(repo_path: &Path, ref_name: &str) -> anyhow::Result<Vec<ChecklistItem>> {
+    Ok(load_synced(repo_path, ref_name)?.checklist)
+}
+
+/// Check or uncheck one item (by id or 1-based position) and persist the
+/// review. Returns the updated checklist.
+pub fn set_checklist_item(
+    repo_path: &Path,
+    ref_name: &str,
+    item: &str,
+    checked: bool,
+    source: Source,
+) -> anyhow::Result<Vec<ChecklistItem>> {
+    let mut state = load_synced(repo_path, ref_name)?;
+    let target = find_item(&mut state.checklist, item)
+        .with_context(|| format!("No checklist item '{item}'"))?;
+    set_item_checked(target, checked, source);
+    state.prepare_for_save();
+    storage::save_review_state(repo_path, &state)?;
+    Ok(state.checklist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::review::checklist::CHECKLIST_TEMPLATE_PATH;
+
+    #[test]
+    fn set_checklist_item_persists_check_state() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let template = repo.path().join(CHECKLIST_TEMPLATE_PATH);
+        std::fs::create_dir_all(template.parent().unwrap()).unwrap();
+        std::fs::write(&template, "- Migrations reviewed\n- Docs updated\n").unwrap();
+
+        let items = get_checklist(repo.path(), "feature").unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|i| !i.checked));
+
+        set_checklist_item(repo.path(), "feature", "docs-updated", true, Source::Ui).unwrap();
+        let items = get_checklist(repo.path(), "feature").unwrap();
+        assert!(!items[0].checked);
+        assert!(items[1].checked);
+        assert_eq!(items[1].checked_by, Some(Source::Ui));
+
+        assert!(set_checklist_item(repo.path(), "feature", "missing", true, Source::Ui).is_err());
+    }
+}
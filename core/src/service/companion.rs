@@ -0,0 +1,383 @@
+//! Read APIs for the companion (mobile) client: a review's symbol diffs, its
+//! guide's hunk groups, and the dependency graph between its changed files,
+//! trimmed to what a phone shows — no line ranges or reference positions,
+//! short descriptions, capped edge lists, single-file clusters dropped.
+//!
+//! Plus trust management: the taxonomy, and adding or removing a review's
+//! trust patterns. A pattern is only added when it can match something (see
+//! [`pattern_problems`]), since a typo'd pattern fails silently at review
+//! time and is hard to spot from a phone.
+//!
+//! Served token-protected under `/api/companion/*` (see
+//! [`crate::server::auth`]), addressed by repo and review ref rather than by
+//! comparison, since that's all the client knows.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::Context as _;
+use serde::Serialize;
+
+use crate::diff::parser::DiffHunk;
+use crate::review::state::ReviewState;
+use crate::review::storage::{self, StorageError};
+use crate::service::files::comparison_hunks;
+use crate::service::symbols::get_file_symbol_diffs;
+use crate::service::targets;
+use crate::sources::traits::Comparison;
+use crate::symbols::graph::{build_dependency_graph, DependencyGraph};
+use crate::symbols::{FileSymbolDiff, SymbolChangeType, SymbolDiff, SymbolKind};
+use crate::trust::lint::{lint_trust_list, Diagnostic};
+use crate::trust::patterns::{current_taxonomy_version, get_trust_taxonomy, TrustCategory};
+
+/// Group descriptions are cut to this many characters.
+pub const MAX_DESCRIPTION_CHARS: usize = 280;
+
+/// Symbols listed per dependency edge; the rest are counted.
+pub const MAX_EDGE_SYMBOLS: usize = 5;
+
+const MAX_SAVE_RETRIES: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionSymbol {
+    pub name: String,
+    pub kind: Option<SymbolKind>,
+    pub change_type: SymbolChangeType,
+    pub hunk_ids: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<CompanionSymbol>,
+}
+
+/// A file's changed symbols.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionFileSymbols {
+    pub file_path: String,
+    pub symbols: Vec<CompanionSymbol>,
+    /// Hunks outside any symbol.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub top_level_hunk_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionGroup {
+    pub title: String,
+    pub description: String,
+    pub hunk_ids: Vec<String>,
+}
+
+/// The review guide's groups.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionGroups {
+    pub groups: Vec<CompanionGroup>,
+    pub generated_at: String,
+    /// Grouped hunks no longer in the diff: the guide predates the latest
+    /// changes.
+    pub missing_hunks: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionEdge {
+    pub defines_file: String,
+    pub references_file: String,
+    pub symbols: Vec<String>,
+    /// Symbols left out past [`MAX_EDGE_SYMBOLS`].
+    pub more_symbols: usize,
+}
+
+/// Files linked through shared symbols.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionCluster {
+    pub files: Vec<String>,
+    pub edges: Vec<CompanionEdge>,
+}
+
+/// The taxonomy trust patterns are written against.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionTaxonomy {
+    pub version: String,
+    pub categories: Vec<TrustCategory>,
+}
+
+/// A review's trust list, with what's wrong with it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompanionTrust {
+    pub trust_list: Vec<String>,
+    /// Patterns already in the list that can't do anything (see
+    /// [`lint_trust_list`]).
+    pub diagnostics: Vec<Diagnostic>,
+    pub version: u64,
+}
+
+impl CompanionTrust {
+    fn of(state: &ReviewState) -> Self {
+        Self {
+            diagnostics: lint_trust_list(&get_trust_taxonomy(), &state.trust_list),
+            trust_list: state.trust_list.clone(),
+            version: state.version,
+        }
+    }
+}
+
+fn trim_symbol(symbol: SymbolDiff) -> CompanionSymbol {
+    CompanionSymbol {
+        name: symbol.name,
+        kind: symbol.kind,
+        change_type: symbol.change_type,
+        hunk_ids: symbol.hunk_ids,
+        children: symbol.children.into_iter().map(trim_symbol).collect(),
+    }
+}
+
+/// Symbol diffs without ranges or references. Files with no changed symbols
+/// (no grammar, or only top-level changes) are dropped: the hunk list
+/// already shows them.
+pub fn trim_symbol_diffs(diffs: Vec<FileSymbolDiff>) -> Vec<CompanionFileSymbols> {
+    diffs
+        .into_iter()
+        .filter(|diff| !diff.symbols.is_empty())
+        .map(|diff| CompanionFileSymbols {
+            file_path: diff.file_path,
+            symbols: diff.symbols.into_iter().map(trim_symbol).collect(),
+            top_level_hunk_ids: diff.top_level_hunk_ids,
+        })
+        .collect()
+}
+
+/// Multi-file clusters, with each edge's symbols capped.
+pub fn trim_graph(graph: DependencyGraph) -> Vec<CompanionCluster> {
+    graph
+        .clusters
+        .into_iter()
+        .filter(|cluster| cluster.files.len() > 1)
+        .map(|cluster| CompanionCluster {
+            files: cluster.files,
+            edges: cluster
+                .edges
+                .into_iter()
+                .map(|mut edge| {
+                    let more_symbols = edge.symbols.len().saturating_sub(MAX_EDGE_SYMBOLS);
+                    edge.symbols.truncate(MAX_EDGE_SYMBOLS);
+                    CompanionEdge {
+                        defines_file: edge.defines_file,
+                        references_file: edge.references_file,
+                        symbols: edge.symbols,
+                        more_symbols,
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// `text` cut to [`MAX_DESCRIPTION_CHARS`], with an ellipsis when cut.
+fn shorten(text: &str) -> String {
+    match text.char_indices().nth(MAX_DESCRIPTION_CHARS) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_owned(),
+    }
+}
+
+/// The review's comparison and current hunks.
+fn review_diff(repo_path: &Path, ref_name: &str) -> anyhow::Result<(Comparison, Vec<DiffHunk>)> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let review = targets::resolve(repo_path, ref_name, state.base_override.as_deref())?;
+    let hunks = comparison_hunks(repo_path, &review.comparison, state.github_pr.as_ref())?;
+    Ok((review.comparison, hunks))
+}
+
+fn changed_files(hunks: &[DiffHunk]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    hunks
+        .iter()
+        .filter(|hunk| seen.insert(hunk.file_path.as_str()))
+        .map(|hunk| hunk.file_path.clone())
+        .collect()
+}
+
+fn file_symbol_diffs(repo_path: &Path, ref_name: &str) -> anyhow::Result<Vec<FileSymbolDiff>> {
+    let (comparison, hunks) = review_diff(repo_path, ref_name)?;
+    get_file_symbol_diffs(repo_path, &changed_files(&hunks), &comparison)
+}
+
+/// The review's changed symbols, per file.
+pub fn symbols(repo_path: &Path, ref_name: &str) -> anyhow::Result<Vec<CompanionFileSymbols>> {
+    Ok(trim_symbol_diffs(file_symbol_diffs(repo_path, ref_name)?))
+}
+
+/// The review guide's groups, or `None` when no guide was written.
+pub fn groups(repo_path: &Path, ref_name: &str) -> anyhow::Result<Option<CompanionGroups>> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let Some(guide) = state.guide.and_then(|guide| guide.state) else {
+        return Ok(None);
+    };
+    let (_, hunks) = review_diff(repo_path, ref_name)?;
+    let current: HashSet<&str> = hunks.iter().map(|hunk| hunk.id.as_str()).collect();
+    let missing_hunks = guide
+        .groups
+        .iter()
+        .flat_map(|group| &group.hunk_ids)
+        .filter(|id| !current.contains(id.as_str()))
+        .count();
+    Ok(Some(CompanionGroups {
+        groups: guide
+            .groups
+            .into_iter()
+            .map(|group| CompanionGroup {
+                description: shorten(&group.description),
+                title: group.title,
+                hunk_ids: group.hunk_ids,
+            })
+            .collect(),
+        generated_at: guide.generated_at,
+        missing_hunks,
+    }))
+}
+
+/// The dependency graph between the review's changed files.
+pub fn graph(repo_path: &Path, ref_name: &str) -> anyhow::Result<Vec<CompanionCluster>> {
+    let diffs = file_symbol_diffs(repo_path, ref_name)?;
+    Ok(trim_graph(build_dependency_graph(&diffs)))
+}
+
+pub fn taxonomy() -> CompanionTaxonomy {
+    CompanionTaxonomy {
+        version: current_taxonomy_version().to_owned(),
+        categories: get_trust_taxonomy(),
+    }
+}
+
+/// Why `pattern` shouldn't be trusted: empty, never trusted, or matching no
+/// label in the taxonomy. Empty when it's fine to add.
+pub fn pattern_problems(pattern: &str) -> Vec<Diagnostic> {
+    lint_trust_list(&get_trust_taxonomy(), &[pattern.to_owned()])
+}
+
+/// The review's trust list.
+pub fn trust(repo_path: &Path, ref_name: &str) -> anyhow::Result<CompanionTrust> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    Ok(CompanionTrust::of(&state))
+}
+
+/// Load `ref_name`'s review, apply `update`, and save it if it changed,
+/// reapplying when another writer saved in between.
+fn update_trust_list(
+    repo_path: &Path,
+    ref_name: &str,
+    update: impl Fn(&mut Vec<String>) -> bool,
+) -> anyhow::Result<CompanionTrust> {
+    for attempt in 0..MAX_SAVE_RETRIES {
+        let mut state = storage::load_review_state(repo_path, ref_name)?;
+        if !update(&mut state.trust_list) {
+            return Ok(CompanionTrust::of(&state));
+        }
+        state.prepare_for_save();
+        match storage::save_review_state(repo_path, &state) {
+            Ok(()) => return Ok(CompanionTrust::of(&state)),
+            Err(StorageError::VersionConflict { .. }) if attempt + 1 < MAX_SAVE_RETRIES => {}
+            Err(e) => return Err(e).context("Failed to save review"),
+        }
+    }
+    anyhow::bail!("Failed to save review after repeated version conflicts")
+}
+
+/// Add `pattern` to the review's trust list (a no-op when it's listed).
+/// Callers check [`pattern_problems`] first.
+pub fn add_trust_pattern(
+    repo_path: &Path,
+    ref_name: &str,
+    pattern: &str,
+) -> anyhow::Result<CompanionTrust> {
+    update_trust_list(repo_path, ref_name, |list| {
+        if list.iter().any(|existing| existing == pattern) {
+            return false;
+        }
+        list.push(pattern.to_owned());
+        true
+    })
+}
+
+/// Remove `pattern` from the review's trust list (a no-op when it isn't
+/// listed).
+pub fn remove_trust_pattern(
+    repo_path: &Path,
+    ref_name: &str,
+    pattern: &str,
+) -> anyhow::Result<CompanionTrust> {
+    update_trust_list(repo_path, ref_name, |list| {
+        let before = list.len();
+        list.retain(|existing| existing != pattern);
+        list.len() != before
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::setup_test;
+    use crate::symbols::graph::{FileCluster, SymbolEdge};
+
+    #[test]
+    fn graph_drops_lone_files_and_caps_edges() {
+        let edge = SymbolEdge {
+            defines_file: "a.rs".to_owned(),
+            references_file: "b.rs".to_owned(),
+            symbols: (0..8).map(|i| format!("f{i}")).collect(),
+        };
+        let graph = DependencyGraph {
+            edges: vec![edge.clone()],
+            clusters: vec![
+                FileCluster {
+                    files: vec!["a.rs".to_owned(), "b.rs".to_owned()],
+                    edges: vec![edge],
+                },
+                FileCluster {
+                    files: vec!["c.rs".to_owned()],
+                    edges: Vec::new(),
+                },
+            ],
+        };
+        let clusters = trim_graph(graph);
+        assert_eq!(clusters.len(), 1);
+        let edge = &clusters[0].edges[0];
+        assert_eq!(edge.symbols.len(), MAX_EDGE_SYMBOLS);
+        assert_eq!(edge.more_symbols, 3);
+
+        assert_eq!(shorten("short"), "short");
+        let long = "word ".repeat(100);
+        assert_eq!(shorten(&long).chars().count(), MAX_DESCRIPTION_CHARS);
+    }
+
+    #[test]
+    fn edits_trust_list_and_rejects_dead_patterns() {
+        let (_guard, _home, repo) = setup_test();
+        let repo = repo.path();
+        storage::ensure_review_exists(repo, "feature", Some("main".to_owned()), None).unwrap();
+
+        assert!(pattern_problems("imports:*").is_empty());
+        assert!(!pattern_problems("").is_empty());
+        assert!(!pattern_problems("imports:typo").is_empty());
+        assert!(!pattern_problems("nosuchcategory:*").is_empty());
+
+        let before = trust(repo, "feature").unwrap();
+        let added = add_trust_pattern(repo, "feature", "imports:*").unwrap();
+        assert!(added.trust_list.contains(&"imports:*".to_owned()));
+        assert!(added.version > before.version);
+        let again = add_trust_pattern(repo, "feature", "imports:*").unwrap();
+        assert_eq!(again.version, added.version);
+
+        let removed = remove_trust_pattern(repo, "feature", "imports:*").unwrap();
+        assert!(!removed.trust_list.contains(&"imports:*".to_owned()));
+        assert_eq!(
+            trust(repo, "feature").unwrap().trust_list,
+            removed.trust_list
+        );
+    }
+}
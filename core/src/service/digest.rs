@@ -0,0 +1,637 @@
+//! The review digest — a summary of review work across every registered repo
+//! over a period, for a weekly team email: reviews completed, time spent
+//! (from [`crate::review::sessions`]), how much was approved versus trusted
+//! or rejected, and hunks carrying risk labels.
+//!
+//! Rendered as Markdown or HTML; [`send_digest`] mails both (as
+//! `multipart/alternative`) through the SMTP relay in the `digestSmtp`
+//! setting, using `curl` so no mail library is needed.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::classify::migrations::IRREVERSIBLE_LABEL;
+use crate::classify::sensitive::SENSITIVE_AREA_LABEL;
+use crate::classify::static_rules::{LICENSE_CHANGE_LABEL, TESTS_REMOVED_LABEL};
+use crate::review::central;
+use crate::review::sessions::load_sessions;
+use crate::review::state::{iso8601_from_system_time, HunkStatus, ReviewState, ReviewSummary};
+use crate::review::storage;
+use crate::secrets;
+use crate::service::semgrep::SEMGREP_FINDING_LABEL;
+use crate::service::settings::{read_settings, DigestSmtp};
+
+/// Labels worth calling out in a digest.
+const RISK_LABELS: &[&str] = &[
+    SENSITIVE_AREA_LABEL,
+    IRREVERSIBLE_LABEL,
+    TESTS_REMOVED_LABEL,
+    LICENSE_CHANGE_LABEL,
+    SEMGREP_FINDING_LABEL,
+    "ci:pull-request-target",
+    "infra:privileged-container",
+];
+
+/// Name of the SMTP password in secret storage.
+pub const SMTP_PASSWORD_SECRET: &str = "digest-smtp-password";
+
+/// Risk findings listed before the rest are summarized as a count.
+const MAX_LISTED_RISKS: usize = 25;
+
+/// Parse a period like `7d`, `2w`, `12h` (a bare number is days).
+pub fn parse_period(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => text.split_at(i),
+        None => (text, "d"),
+    };
+    let hours: u64 = match unit {
+        "h" => 1,
+        "d" => 24,
+        "w" => 24 * 7,
+        _ => return None,
+    };
+    let count: u64 = number.parse().ok().filter(|&n| n > 0)?;
+    Some(Duration::from_secs(count * hours * 3600))
+}
+
+/// A review completed in the period.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestReview {
+    pub repo_name: String,
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// `approved` or `changes_requested`.
+    pub state: String,
+    pub completed_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pr_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pr_number: Option<u32>,
+    pub total_hunks: usize,
+    pub approved_hunks: usize,
+    pub trusted_hunks: usize,
+    pub rejected_hunks: usize,
+    /// Time spent on it during the period.
+    pub active_secs: u64,
+}
+
+/// A hunk in a completed review with a risk label.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskFinding {
+    pub repo_name: String,
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub file_path: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<HunkStatus>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestTotals {
+    pub completed: usize,
+    pub approved: usize,
+    pub changes_requested: usize,
+    pub total_hunks: usize,
+    pub approved_hunks: usize,
+    pub trusted_hunks: usize,
+    pub rejected_hunks: usize,
+    /// Time spent reviewing in the period, including unfinished reviews.
+    pub active_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Digest {
+    pub since: String,
+    pub until: String,
+    pub totals: DigestTotals,
+    /// Most recently completed first.
+    pub reviews: Vec<DigestReview>,
+    pub risks: Vec<RiskFinding>,
+}
+
+fn epoch_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+fn risks_in(state: &ReviewState, repo_name: &str) -> Vec<RiskFinding> {
+    let mut risks: Vec<RiskFinding> = state
+        .hunks
+        .iter()
+        .flat_map(|(hunk_id, hunk)| {
+            hunk.labels()
+                .iter()
+                .filter(|label| RISK_LABELS.contains(&label.as_str()))
+                .map(move |label| RiskFinding {
+                    repo_name: repo_name.to_owned(),
+                    ref_name: state.ref_name.clone(),
+                    file_path: hunk_id
+                        .rsplit_once(':')
+                        .map_or(hunk_id.as_str(), |(path, _)| path)
+                        .to_owned(),
+                    label: label.clone(),
+                    status: hunk.status.as_ref().map(|s| s.value.clone()),
+                })
+        })
+        .collect();
+    risks.sort_by(|a, b| (&a.file_path, &a.label).cmp(&(&b.file_path, &b.label)));
+    risks
+}
+
+/// Gather the digest for the `period` up to now.
+pub fn build_digest(period: Duration) -> anyhow::Result<Digest> {
+    let until = SystemTime::now();
+    let since = until.checked_sub(period).unwrap_or(UNIX_EPOCH);
+    let since_iso = iso8601_from_system_time(since);
+    let since_ms = epoch_ms(since);
+
+    let mut totals = DigestTotals::default();
+    let mut reviews = Vec::new();
+    let mut risks = Vec::new();
+    for entry in central::list_registered_repos()? {
+        let repo_path = PathBuf::from(&entry.path);
+        if !repo_path.exists() {
+            continue;
+        }
+
+        let mut time_by_ref: HashMap<String, u64> = HashMap::new();
+        for session in load_sessions(&repo_path)? {
+            if session.end < since_ms {
+                continue;
+            }
+            let ms = session.end - session.start.max(since_ms);
+            *time_by_ref.entry(session.ref_name).or_default() += ms;
+        }
+        totals.active_secs += time_by_ref.values().sum::<u64>() / 1000;
+
+        let live = storage::list_saved_reviews(&repo_path)?
+            .into_iter()
+            .map(|s| (s, false));
+        let archived = storage::list_archived_reviews(&repo_path)?
+            .into_iter()
+            .map(|s| (s, true));
+        for (summary, is_archived) in live.chain(archived) {
+            let Some(state) = summary.state.clone() else {
+                continue;
+            };
+            if summary.updated_at < since_iso {
+                continue;
+            }
+            let loaded = if is_archived {
+                storage::load_archived_review(&repo_path, &summary.ref_name)
+            } else {
+                storage::load_review_state(&repo_path, &summary.ref_name)
+            };
+            match loaded {
+                Ok(full) => risks.extend(risks_in(&full, &entry.name)),
+                Err(e) => log::warn!(
+                    "[digest] Failed to load {} in {}: {e}",
+                    summary.ref_name,
+                    entry.path
+                ),
+            }
+            reviews.push(digest_review(
+                &summary,
+                state,
+                &entry.name,
+                time_by_ref.get(&summary.ref_name).copied().unwrap_or(0) / 1000,
+            ));
+        }
+    }
+
+    reviews.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+    for review in &reviews {
+        totals.completed += 1;
+        match review.state.as_str() {
+            "approved" => totals.approved += 1,
+            "changes_requested" => totals.changes_requested += 1,
+            _ => {}
+        }
+        totals.total_hunks += review.total_hunks;
+        totals.approved_hunks += review.approved_hunks;
+        totals.trusted_hunks += review.trusted_hunks;
+        totals.rejected_hunks += review.rejected_hunks;
+    }
+
+    Ok(Digest {
+        since: since_iso,
+        until: iso8601_from_system_time(until),
+        totals,
+        reviews,
+        risks,
+    })
+}
+
+fn digest_review(
+    summary: &ReviewSummary,
+    state: String,
+    repo_name: &str,
+    active_secs: u64,
+) -> DigestReview {
+    DigestReview {
+        repo_name: repo_name.to_owned(),
+        ref_name: summary.ref_name.clone(),
+        state,
+        completed_at: summary.updated_at.clone(),
+        pr_title: summary.github_pr.as_ref().map(|pr| pr.title.clone()),
+        pr_number: summary.github_pr.as_ref().map(|pr| pr.number),
+        total_hunks: summary.total_hunks,
+        approved_hunks: summary.approved_hunks,
+        trusted_hunks: summary.trusted_hunks,
+        rejected_hunks: summary.rejected_hunks,
+        active_secs,
+    }
+}
+
+/// `1h 05m`, `12m`, `<1m`.
+fn format_duration(secs: u64) -> String {
+    match (secs / 3600, secs % 3600 / 60) {
+        (0, 0) => "<1m".to_owned(),
+        (0, m) => format!("{m}m"),
+        (h, m) => format!("{h}h {m:02}m"),
+    }
+}
+
+fn percent(part: usize, whole: usize) -> String {
+    if whole == 0 {
+        return "–".to_owned();
+    }
+    format!("{}%", part * 100 / whole)
+}
+
+fn day(iso: &str) -> &str {
+    iso.get(..10).unwrap_or(iso)
+}
+
+fn review_title(review: &DigestReview) -> String {
+    match (&review.pr_number, &review.pr_title) {
+        (Some(number), Some(title)) => format!("#{number} {title}"),
+        _ => review.ref_name.clone(),
+    }
+}
+
+fn state_text(state: &str) -> &str {
+    match state {
+        "approved" => "approved",
+        "changes_requested" => "changes requested",
+        other => other,
+    }
+}
+
+fn status_text(status: Option<&HunkStatus>) -> &'static str {
+    match status {
+        Some(HunkStatus::Approved) => "approved",
+        Some(HunkStatus::Rejected) => "rejected",
+        Some(HunkStatus::SavedForLater) => "saved for later",
+        None => "not decided",
+    }
+}
+
+/// One-line summary, used as the email subject.
+pub fn digest_subject(digest: &Digest) -> String {
+    format!(
+        "Review digest {} – {}: {} review(s) completed, {} spent",
+        day(&digest.since),
+        day(&digest.until),
+        digest.totals.completed,
+        format_duration(digest.totals.active_secs)
+    )
+}
+
+/// Render as Markdown.
+pub fn render_markdown(digest: &Digest) -> String {
+    let t = &digest.totals;
+    let mut out = format!(
+        "# Review digest\n\n{} – {}\n\n",
+        day(&digest.since),
+        day(&digest.until)
+    );
+    let _ = writeln!(
+        out,
+        "- **{}** review(s) completed: {} approved, {} with changes requested",
+        t.completed, t.approved, t.changes_requested
+    );
+    let _ = writeln!(
+        out,
+        "- **{}** spent reviewing",
+        format_duration(t.active_secs)
+    );
+    let _ = writeln!(
+        out,
+        "- Of {} hunks: {} approved, {} trusted, {} rejected",
+        t.total_hunks,
+        percent(t.approved_hunks, t.total_hunks),
+        percent(t.trusted_hunks, t.total_hunks),
+        percent(t.rejected_hunks, t.total_hunks)
+    );
+
+    if !digest.reviews.is_empty() {
+        out.push_str(
+            "\n## Completed reviews\n\n| Review | Repo | Outcome | Hunks | Approved | Trusted | Time |\n\
+             |---|---|---|---:|---:|---:|---:|\n",
+        );
+        for r in &digest.reviews {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} | {} |",
+                review_title(r).replace('|', "\\|"),
+                r.repo_name,
+                state_text(&r.state),
+                r.total_hunks,
+                percent(r.approved_hunks, r.total_hunks),
+                percent(r.trusted_hunks, r.total_hunks),
+                format_duration(r.active_secs)
+            );
+        }
+    }
+
+    if !digest.risks.is_empty() {
+        out.push_str("\n## Risk findings\n\n");
+        for risk in digest.risks.iter().take(MAX_LISTED_RISKS) {
+            let _ = writeln!(
+                out,
+                "- `{}` in `{}` ({} / {}) — {}",
+                risk.label,
+                risk.file_path,
+                risk.repo_name,
+                risk.ref_name,
+                status_text(risk.status.as_ref())
+            );
+        }
+        if digest.risks.len() > MAX_LISTED_RISKS {
+            let _ = writeln!(out, "- …and {} more", digest.risks.len() - MAX_LISTED_RISKS);
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Render as a self-contained HTML page (inline styles, for email clients).
+pub fn render_html(digest: &Digest) -> String {
+    let t = &digest.totals;
+    let cell = "padding:4px 8px;border-bottom:1px solid #ddd;text-align:left";
+    let mut out = format!(
+        "<!doctype html>\n<html><body style=\"font-family:system-ui,sans-serif;font-size:14px;color:#222\">\n\
+         <h1 style=\"font-size:20px\">Review digest</h1>\n<p style=\"color:#666\">{} – {}</p>\n<ul>\n",
+        day(&digest.since),
+        day(&digest.until)
+    );
+    let _ = writeln!(
+        out,
+        "<li><strong>{}</strong> review(s) completed: {} approved, {} with changes requested</li>",
+        t.completed, t.approved, t.changes_requested
+    );
+    let _ = writeln!(
+        out,
+        "<li><strong>{}</strong> spent reviewing</li>",
+        format_duration(t.active_secs)
+    );
+    let _ = writeln!(
+        out,
+        "<li>Of {} hunks: {} approved, {} trusted, {} rejected</li>\n</ul>",
+        t.total_hunks,
+        percent(t.approved_hunks, t.total_hunks),
+        percent(t.trusted_hunks, t.total_hunks),
+        percent(t.rejected_hunks, t.total_hunks)
+    );
+
+    if !digest.reviews.is_empty() {
+        out.push_str("<h2 style=\"font-size:16px\">Completed reviews</h2>\n<table style=\"border-collapse:collapse\">\n<tr>");
+        for heading in [
+            "Review", "Repo", "Outcome", "Hunks", "Approved", "Trusted", "Time",
+        ] {
+            let _ = write!(out, "<th style=\"{cell}\">{heading}</th>");
+        }
+        out.push_str("</tr>\n");
+        for r in &digest.reviews {
+            out.push_str("<tr>");
+            for value in [
+                escape_html(&review_title(r)),
+                escape_html(&r.repo_name),
+                state_text(&r.state).to_owned(),
+                r.total_hunks.to_string(),
+                percent(r.approved_hunks, r.total_hunks),
+                percent(r.trusted_hunks, r.total_hunks),
+                format_duration(r.active_secs),
+            ] {
+                let _ = write!(out, "<td style=\"{cell}\">{value}</td>");
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+
+    if !digest.risks.is_empty() {
+        out.push_str("<h2 style=\"font-size:16px\">Risk findings</h2>\n<ul>\n");
+        for risk in digest.risks.iter().take(MAX_LISTED_RISKS) {
+            let _ = writeln!(
+                out,
+                "<li><code>{}</code> in <code>{}</code> ({} / {}) — {}</li>",
+                escape_html(&risk.label),
+                escape_html(&risk.file_path),
+                escape_html(&risk.repo_name),
+                escape_html(&risk.ref_name),
+                status_text(risk.status.as_ref())
+            );
+        }
+        if digest.risks.len() > MAX_LISTED_RISKS {
+            let _ = writeln!(
+                out,
+                "<li>…and {} more</li>",
+                digest.risks.len() - MAX_LISTED_RISKS
+            );
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+/// An RFC 5322 message with the Markdown and HTML renderings as
+/// `multipart/alternative` parts.
+fn mime_message(digest: &Digest, smtp: &DigestSmtp) -> String {
+    let boundary = format!("review-digest-{}", epoch_ms(SystemTime::now()));
+    let base64 = |text: &str| {
+        use base64::Engine as _;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+        encoded
+            .as_bytes()
+            .chunks(76)
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect::<Vec<_>>()
+            .join("\r\n")
+    };
+    let subject = {
+        use base64::Engine as _;
+        format!(
+            "=?UTF-8?B?{}?=",
+            base64::engine::general_purpose::STANDARD.encode(digest_subject(digest))
+        )
+    };
+    format!(
+        "From: {from}\r\nTo: {to}\r\nSubject: {subject}\r\nMIME-Version: 1.0\r\n\
+         Content-Type: multipart/alternative; boundary=\"{boundary}\"\r\n\r\n\
+         --{boundary}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{text}\r\n\
+         --{boundary}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{html}\r\n\
+         --{boundary}--\r\n",
+        from = smtp.from,
+        to = smtp.to.join(", "),
+        text = base64(&render_markdown(digest)),
+        html = base64(&render_html(digest)),
+    )
+}
+
+/// Mail the digest through the configured SMTP relay.
+pub fn send_digest(digest: &Digest) -> anyhow::Result<DigestSmtp> {
+    let smtp = read_settings()?
+        .settings
+        .digest_smtp
+        .context("No SMTP relay configured; set `digestSmtp` in settings.json")?;
+    if smtp.to.is_empty() {
+        anyhow::bail!("`digestSmtp.to` lists no recipients");
+    }
+    crate::offline::ensure_online("Mailing the digest")?;
+
+    let mut message = tempfile::NamedTempFile::new()?;
+    message.write_all(mime_message(digest, &smtp).as_bytes())?;
+
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--ssl", "--url", &smtp.url])
+        .args(["--mail-from", &smtp.from]);
+    for recipient in &smtp.to {
+        command.args(["--mail-rcpt", recipient]);
+    }
+    command.arg("--upload-file").arg(message.path());
+    // Credentials go in on stdin as a curl config, not in the argument list
+    // where other processes could see them.
+    let credentials = match &smtp.username {
+        Some(user) => {
+            let password = secrets::get_secret(SMTP_PASSWORD_SECRET)?.unwrap_or_default();
+            let quote = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+            command.args(["--config", "-"]);
+            Some(format!("user = \"{}:{}\"\n", quote(user), quote(&password)))
+        }
+        None => None,
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run curl")?;
+    if let (Some(credentials), Some(mut stdin)) = (credentials, child.stdin.take()) {
+        stdin.write_all(credentials.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Sending via {} failed: {}",
+            smtp.url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(smtp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::state::{Attributed, HunkState, Source};
+
+    #[test]
+    fn parses_periods() {
+        let hours = |text| parse_period(text).map(|d| d.as_secs() / 3600);
+        assert_eq!(hours("7d"), Some(7 * 24));
+        assert_eq!(hours("2w"), Some(14 * 24));
+        assert_eq!(hours("12h"), Some(12));
+        assert_eq!(hours("3"), Some(3 * 24));
+        assert_eq!(parse_period("0d"), None);
+        assert_eq!(parse_period("7y"), None);
+        assert_eq!(parse_period("d"), None);
+    }
+
+    #[test]
+    fn renders_reviews_and_risks() {
+        let mut state = ReviewState::new("feature", None);
+        state.hunks.insert(
+            "migrations/002.sql:abc".to_owned(),
+            HunkState {
+                classification: Some(Attributed::new(
+                    vec![IRREVERSIBLE_LABEL.to_owned(), "sql:ddl".to_owned()],
+                    Source::Static,
+                )),
+                status: Some(Attributed::new(HunkStatus::Approved, Source::Ui)),
+                ..Default::default()
+            },
+        );
+        let risks = risks_in(&state, "api");
+        assert_eq!(risks.len(), 1);
+        assert_eq!(risks[0].file_path, "migrations/002.sql");
+        assert_eq!(risks[0].label, IRREVERSIBLE_LABEL);
+
+        let digest = Digest {
+            since: "2026-01-01T00:00:00.000Z".to_owned(),
+            until: "2026-01-08T00:00:00.000Z".to_owned(),
+            totals: DigestTotals {
+                completed: 1,
+                approved: 1,
+                total_hunks: 4,
+                approved_hunks: 3,
+                trusted_hunks: 1,
+                active_secs: 3900,
+                ..Default::default()
+            },
+            reviews: vec![DigestReview {
+                repo_name: "api".to_owned(),
+                ref_name: "feature".to_owned(),
+                state: "approved".to_owned(),
+                completed_at: "2026-01-05T10:00:00.000Z".to_owned(),
+                pr_title: Some("Add <b>things</b>".to_owned()),
+                pr_number: Some(12),
+                total_hunks: 4,
+                approved_hunks: 3,
+                trusted_hunks: 1,
+                rejected_hunks: 0,
+                active_secs: 3900,
+            }],
+            risks,
+        };
+
+        let markdown = render_markdown(&digest);
+        assert!(markdown.contains("2026-01-01 – 2026-01-08"));
+        assert!(markdown.contains("**1h 05m** spent reviewing"));
+        assert!(markdown
+            .contains("| #12 Add <b>things</b> | api | approved | 4 | 75% | 25% | 1h 05m |"));
+        assert!(markdown
+            .contains("`sql:irreversible` in `migrations/002.sql` (api / feature) — approved"));
+
+        let html = render_html(&digest);
+        assert!(html.contains("#12 Add &lt;b&gt;things&lt;/b&gt;"));
+        assert!(!html.contains("<b>things"));
+        assert_eq!(
+            digest_subject(&digest),
+            "Review digest 2026-01-01 – 2026-01-08: 1 review(s) completed, 1h 05m spent"
+        );
+    }
+}
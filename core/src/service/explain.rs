@@ -0,0 +1,151 @@
+//! Explain one hunk's labels with the code around it: gather the symbols
+//! that enclose the hunk on each side of the diff and ask the model to
+//! re-evaluate the labels in their light (see [`crate::ai::explain`]).
+
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::ai::explain::{explain_label_with_model, ContextSnippet, LabelExplanation, Side};
+use crate::diff::parser::DiffHunk;
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::Comparison;
+use crate::symbols::{extractor::extract_symbols, Symbol};
+
+/// Longest snippet sent per side; a larger enclosing symbol is cut to a
+/// window centred on the hunk.
+const MAX_CONTEXT_LINES: u32 = 200;
+/// Lines either side of the hunk when no symbol encloses it.
+const FALLBACK_CONTEXT_LINES: u32 = 20;
+
+/// The innermost symbol spanning lines `start..=end`.
+fn enclosing_symbol(symbols: &[Symbol], start: u32, end: u32) -> Option<&Symbol> {
+    symbols
+        .iter()
+        .find(|s| s.start_line <= start && end <= s.end_line)
+        .map(|s| enclosing_symbol(&s.children, start, end).unwrap_or(s))
+}
+
+/// The code around lines `start..=end` of `content`: the innermost
+/// enclosing symbol, or a window of lines when there is none.
+pub fn context_snippet(
+    content: &str,
+    file_path: &str,
+    side: Side,
+    start: u32,
+    end: u32,
+) -> ContextSnippet {
+    let symbols = extract_symbols(content, file_path).unwrap_or_default();
+    let symbol = enclosing_symbol(&symbols, start, end);
+    let (mut first, mut last) = symbol.map_or(
+        (
+            start.saturating_sub(FALLBACK_CONTEXT_LINES).max(1),
+            end + FALLBACK_CONTEXT_LINES,
+        ),
+        |s| (s.start_line, s.end_line),
+    );
+    if last - first >= MAX_CONTEXT_LINES {
+        let centre = start + (end - start) / 2;
+        first = centre.saturating_sub(MAX_CONTEXT_LINES / 2).max(first);
+        last = first + MAX_CONTEXT_LINES - 1;
+    }
+    ContextSnippet {
+        side,
+        symbol: symbol.map(|s| s.name.clone()),
+        start_line: first,
+        lines: content
+            .lines()
+            .skip(first as usize - 1)
+            .take((last - first + 1) as usize)
+            .map(str::to_owned)
+            .collect(),
+    }
+}
+
+/// Context for each side of `hunk` that has lines in the comparison.
+pub fn hunk_context(
+    repo_path: &Path,
+    source: &LocalGitSource,
+    comparison: &Comparison,
+    hunk: &DiffHunk,
+) -> Vec<ContextSnippet> {
+    let sides = [
+        (
+            Side::Old,
+            source.diff_base_ref(comparison),
+            hunk.old_start,
+            hunk.old_count,
+        ),
+        (
+            Side::New,
+            comparison.head.clone(),
+            hunk.new_start,
+            hunk.new_count,
+        ),
+    ];
+    sides
+        .into_iter()
+        .filter(|(_, _, _, count)| *count > 0)
+        .filter_map(|(side, git_ref, start, count)| {
+            let content = if side == Side::New && source.include_working_tree(comparison) {
+                std::fs::read_to_string(repo_path.join(&hunk.file_path)).ok()
+            } else {
+                source
+                    .get_file_bytes(&hunk.file_path, &git_ref)
+                    .ok()
+                    .and_then(|bytes| String::from_utf8(bytes).ok())
+            }?;
+            let end = start + count - 1;
+            Some(context_snippet(&content, &hunk.file_path, side, start, end))
+        })
+        .collect()
+}
+
+/// Re-evaluate `hunk`'s `labels` (and their stored `reasoning`) with `model`,
+/// given the symbols around it in `comparison`.
+pub fn explain_hunk_label(
+    repo_path: &Path,
+    comparison: &Comparison,
+    hunk: &DiffHunk,
+    labels: &[String],
+    reasoning: Option<&str>,
+    model: &str,
+) -> anyhow::Result<LabelExplanation> {
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let context = hunk_context(repo_path, &source, comparison, hunk);
+    explain_label_with_model(hunk, labels, reasoning, &context, model, repo_path)
+        .context("AI explanation failed")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str =
+        "struct A;\n\nimpl A {\n    fn one() {\n        1;\n    }\n\n    fn two() {}\n}\n";
+
+    #[test]
+    fn snippet_without_a_symbol_is_a_window_of_lines() {
+        let snippet = context_snippet(CONTENT, "a.txt", Side::Old, 2, 2);
+        assert_eq!(snippet.symbol, None);
+        assert_eq!(snippet.start_line, 1);
+        assert_eq!(snippet.lines.len(), 9);
+
+        let long = (1..=500)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let snippet = context_snippet(&long, "a.txt", Side::New, 300, 300);
+        assert_eq!(snippet.start_line, 280);
+        assert_eq!(snippet.lines.len(), 41);
+    }
+
+    #[cfg(feature = "symbols-rust-lang")]
+    #[test]
+    fn snippet_is_the_innermost_enclosing_symbol() {
+        let snippet = context_snippet(CONTENT, "a.rs", Side::New, 5, 5);
+        assert_eq!(snippet.symbol.as_deref(), Some("one"));
+        assert_eq!(snippet.start_line, 4);
+        assert_eq!(snippet.lines, ["    fn one() {", "        1;", "    }"]);
+    }
+}
@@ -0,0 +1,379 @@
+//! Hunk-level export of review data for analysis in other tools: one record
+//! per hunk with its repo, comparison, labels, decision, who made it, when,
+//! its size, and its language and whether it's generated or a test.
+//!
+//! CSV is written directly. Parquet is converted from the CSV by the `duckdb`
+//! CLI when it's installed, rather than pulling a Parquet implementation into
+//! the build for one command.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::classify::static_rules::is_test_path;
+use crate::review::central;
+use crate::review::state::{HunkState, ReviewState, Source};
+use crate::review::storage;
+use crate::service::files::comparison_hunks;
+use crate::service::onboarding::{is_generated_path, language_for_path};
+use crate::service::targets;
+use crate::sources::local_git::LocalGitSource;
+
+/// One hunk of one review.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HunkRecord {
+    pub repo: String,
+    pub repo_path: String,
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// `base..head` when the review still resolves, else empty.
+    pub comparison: String,
+    pub pr_number: Option<u32>,
+    pub hunk_id: String,
+    pub file_path: String,
+    /// `;`-separated.
+    pub labels: String,
+    pub label_source: String,
+    pub labeled_at: String,
+    /// `approved`, `rejected`, `saved_for_later`, or empty.
+    pub decision: String,
+    pub decision_source: String,
+    /// The repo's `git config user.name` for decisions made in the app or
+    /// CLI on this machine.
+    pub reviewer: String,
+    pub decided_at: String,
+    /// Unknown for hunks no longer in the diff.
+    pub lines_added: Option<usize>,
+    pub lines_removed: Option<usize>,
+    /// By extension; empty when unknown.
+    pub language: String,
+    /// Lockfile, or vendored or generated code.
+    pub generated: bool,
+    /// In a test file.
+    pub test: bool,
+    /// Who signed the review's attestation (`review attest`), if anyone.
+    pub attested_by: String,
+    /// SHA-256 of what the attestation signed.
+    pub attestation_digest: String,
+    pub review_created_at: String,
+    pub review_updated_at: String,
+    pub archived: bool,
+}
+
+/// Column names, in [`HunkRecord::fields`] order.
+const COLUMNS: &[&str] = &[
+    "repo",
+    "repo_path",
+    "ref",
+    "comparison",
+    "pr_number",
+    "hunk_id",
+    "file_path",
+    "labels",
+    "label_source",
+    "labeled_at",
+    "decision",
+    "decision_source",
+    "reviewer",
+    "decided_at",
+    "lines_added",
+    "lines_removed",
+    "language",
+    "generated",
+    "test",
+    "attested_by",
+    "attestation_digest",
+    "review_created_at",
+    "review_updated_at",
+    "archived",
+];
+
+impl HunkRecord {
+    fn fields(&self) -> [String; 24] {
+        let optional = |n: Option<usize>| n.map(|n| n.to_string()).unwrap_or_default();
+        [
+            self.repo.clone(),
+            self.repo_path.clone(),
+            self.ref_name.clone(),
+            self.comparison.clone(),
+            self.pr_number.map(|n| n.to_string()).unwrap_or_default(),
+            self.hunk_id.clone(),
+            self.file_path.clone(),
+            self.labels.clone(),
+            self.label_source.clone(),
+            self.labeled_at.clone(),
+            self.decision.clone(),
+            self.decision_source.clone(),
+            self.reviewer.clone(),
+            self.decided_at.clone(),
+            optional(self.lines_added),
+            optional(self.lines_removed),
+            self.language.clone(),
+            self.generated.to_string(),
+            self.test.to_string(),
+            self.attested_by.clone(),
+            self.attestation_digest.clone(),
+            self.review_created_at.clone(),
+            self.review_updated_at.clone(),
+            self.archived.to_string(),
+        ]
+    }
+}
+
+fn file_path_of(hunk_id: &str) -> &str {
+    hunk_id.rsplit_once(':').map_or(hunk_id, |(path, _)| path)
+}
+
+fn hunk_record(
+    base: &HunkRecord,
+    hunk_id: &str,
+    hunk: Option<&HunkState>,
+    reviewer: Option<&str>,
+) -> HunkRecord {
+    let file_path = file_path_of(hunk_id);
+    let mut record = HunkRecord {
+        hunk_id: hunk_id.to_owned(),
+        file_path: file_path.to_owned(),
+        language: language_for_path(file_path).unwrap_or_default().to_owned(),
+        generated: is_generated_path(file_path),
+        test: is_test_path(file_path),
+        ..base.clone()
+    };
+    if let Some(classification) = hunk.and_then(|h| h.classification.as_ref()) {
+        record.labels = classification.value.join(";");
+        classification
+            .source
+            .as_str()
+            .clone_into(&mut record.label_source);
+        record.labeled_at = classification.at.clone().unwrap_or_default();
+    }
+    if let Some(status) = hunk.and_then(|h| h.status.as_ref()) {
+        record.decision = serde_json::to_value(&status.value)
+            .ok()
+            .and_then(|v| v.as_str().map(str::to_owned))
+            .unwrap_or_default();
+        status
+            .source
+            .as_str()
+            .clone_into(&mut record.decision_source);
+        record.decided_at = status.at.clone().unwrap_or_default();
+        if matches!(status.source, Source::Ui | Source::Cli) {
+            reviewer
+                .unwrap_or_default()
+                .clone_into(&mut record.reviewer);
+        }
+    }
+    record
+}
+
+/// Records for one review: every hunk in its current diff, then stored
+/// hunks that are no longer in it.
+fn review_records(
+    repo_path: &Path,
+    repo_name: &str,
+    state: &ReviewState,
+    archived: bool,
+    reviewer: Option<&str>,
+) -> Vec<HunkRecord> {
+    let mut base = HunkRecord {
+        repo: repo_name.to_owned(),
+        repo_path: repo_path.display().to_string(),
+        ref_name: state.ref_name.clone(),
+        pr_number: state.github_pr.as_ref().map(|pr| pr.number),
+        review_created_at: state.created_at.clone(),
+        review_updated_at: state.updated_at.clone(),
+        archived,
+        ..Default::default()
+    };
+    if let Some(attestation) = &state.attestation {
+        attestation
+            .review
+            .reviewer
+            .clone_into(&mut base.attested_by);
+        attestation.digest.clone_into(&mut base.attestation_digest);
+    }
+
+    // Archived reviews' branches are often gone; don't try to diff them.
+    let diff = if archived {
+        None
+    } else {
+        targets::resolve(repo_path, &state.ref_name, state.base_override.as_deref())
+            .and_then(|review| {
+                let hunks =
+                    comparison_hunks(repo_path, &review.comparison, state.github_pr.as_ref())?;
+                Ok((review.comparison.key, hunks))
+            })
+            .map_err(|e| log::debug!("[export] No diff for {}: {e:#}", state.ref_name))
+            .ok()
+    };
+
+    let mut records = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    if let Some((comparison, hunks)) = diff {
+        base.comparison = comparison;
+        for hunk in &hunks {
+            let mut record = hunk_record(&base, &hunk.id, state.hunks.get(&hunk.id), reviewer);
+            let (added, removed) = hunk.line_counts();
+            record.lines_added = Some(added);
+            record.lines_removed = Some(removed);
+            records.push(record);
+            seen.insert(hunk.id.clone());
+        }
+    }
+    let mut stale: Vec<_> = state
+        .hunks
+        .iter()
+        .filter(|(id, _)| !seen.contains(id.as_str()))
+        .collect();
+    stale.sort_by_key(|(id, _)| id.as_str());
+    for (id, hunk) in stale {
+        records.push(hunk_record(&base, id, Some(hunk), reviewer));
+    }
+    records
+}
+
+/// Hunk records for every review (live and archived) in `repo_path`, or in
+/// every registered repo when it's `None`.
+pub fn collect_hunk_records(repo_path: Option<&Path>) -> anyhow::Result<Vec<HunkRecord>> {
+    let repos: Vec<(PathBuf, String)> = match repo_path {
+        Some(path) => {
+            let name = central::repo_root(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            vec![(path.to_path_buf(), name)]
+        }
+        None => central::list_registered_repos()?
+            .into_iter()
+            .map(|entry| (PathBuf::from(entry.path), entry.name))
+            .filter(|(path, _)| path.exists())
+            .collect(),
+    };
+
+    let mut records = Vec::new();
+    for (path, name) in repos {
+        let reviewer = LocalGitSource::new(path.clone())
+            .ok()
+            .and_then(|source| source.get_user_name());
+        let live = storage::list_saved_reviews(&path)?
+            .into_iter()
+            .map(|summary| (storage::load_review_state(&path, &summary.ref_name), false));
+        let archived = storage::list_archived_reviews(&path)?
+            .into_iter()
+            .map(|summary| {
+                (
+                    storage::load_archived_review(&path, &summary.ref_name),
+                    true,
+                )
+            });
+        for (state, is_archived) in live.chain(archived) {
+            match state {
+                Ok(state) => records.extend(review_records(
+                    &path,
+                    &name,
+                    &state,
+                    is_archived,
+                    reviewer.as_deref(),
+                )),
+                Err(e) => log::warn!("[export] Skipping unreadable review in {name}: {e}"),
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Quote a CSV field when it needs it (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Write records as CSV with a header row.
+pub fn write_csv(records: &[HunkRecord], out: &mut impl Write) -> std::io::Result<()> {
+    writeln!(out, "{}", COLUMNS.join(","))?;
+    for record in records {
+        let fields: Vec<String> = record.fields().iter().map(|f| csv_field(f)).collect();
+        writeln!(out, "{}", fields.join(","))?;
+    }
+    Ok(())
+}
+
+/// Write records as Parquet at `path`, via the `duckdb` CLI.
+pub fn write_parquet(records: &[HunkRecord], path: &Path) -> anyhow::Result<()> {
+    let mut csv = tempfile::Builder::new().suffix(".csv").tempfile()?;
+    write_csv(records, &mut csv)?;
+    csv.flush()?;
+
+    let quote = |p: &Path| format!("'{}'", p.display().to_string().replace('\'', "''"));
+    let sql = format!(
+        "COPY (SELECT * FROM read_csv({}, header = true)) TO {} (FORMAT parquet)",
+        quote(csv.path()),
+        quote(path)
+    );
+    let output = Command::new("duckdb")
+        .args([":memory:", "-c", &sql])
+        .output()
+        .context("Parquet export needs the duckdb CLI (https://duckdb.org); export CSV instead")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "duckdb failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::state::{Attributed, HunkStatus};
+
+    #[test]
+    fn csv_rows_carry_labels_and_decisions() {
+        let mut state = ReviewState::new("feature", None);
+        state.hunks.insert(
+            "src/a, b.rs:abc".to_owned(),
+            HunkState {
+                classification: Some(Attributed {
+                    at: Some("2026-01-02T00:00:00.000Z".to_owned()),
+                    ..Attributed::new(
+                        vec!["imports:added".to_owned(), "formatting:style".to_owned()],
+                        Source::Ai,
+                    )
+                }),
+                status: Some(Attributed::new(HunkStatus::SavedForLater, Source::Ui)),
+                ..Default::default()
+            },
+        );
+        state.hunks.insert(
+            "README.md:def".to_owned(),
+            HunkState {
+                status: Some(Attributed::new(HunkStatus::Approved, Source::Github)),
+                ..Default::default()
+            },
+        );
+
+        let records = review_records(Path::new("/repo"), "repo", &state, true, Some("Ana"));
+        assert_eq!(records.len(), 2);
+        let mut csv = Vec::new();
+        write_csv(&records, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], COLUMNS.join(","));
+        assert!(lines[1]
+            .starts_with("repo,/repo,feature,,,README.md:def,README.md,,,,approved,github,,"));
+        assert!(lines[2].contains(
+            ",\"src/a, b.rs:abc\",\"src/a, b.rs\",imports:added;formatting:style,ai,2026-01-02T00:00:00.000Z,saved_for_later,ui,Ana,"
+        ));
+        assert!(lines[2].contains(",,,Rust,false,false,"));
+        assert!(lines[2].ends_with(",true"));
+    }
+}
@@ -5,16 +5,22 @@
 //! `Result<T, String>`.
 
 use anyhow::{bail, Context};
-use log::{debug, info};
-use std::collections::HashSet;
+use ignore::gitignore::Gitignore;
+use log::{debug, info, warn};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::time::Instant;
 
+use crate::diff::encoding::{decode, TextEncoding};
 use crate::diff::parser::{
-    compute_content_hash, create_binary_hunk, create_untracked_hunk, parse_diff,
-    parse_multi_file_diff, DiffHunk,
+    compute_content_hash, create_binary_hunk, create_oversized_hunk, create_untracked_hunk,
+    parse_diff, parse_multi_file_diff, parse_wide_diff, DiffHunk,
 };
-use crate::sources::github::{GhCliProvider, GitHubPrRef, GitHubProvider};
+use crate::diff::translations::{create_translation_rollup_hunk, supports_rollup};
+use crate::limits::{Degradation, LimitExceeded, LimitKind, ReviewLimits};
+use crate::review::repo_config::{self, RepoConfig};
+use crate::sources::azure::{AzureDevOpsProvider, AzurePrRef};
+use crate::sources::github::{GitHubClient, GitHubPrRef, GitHubProvider};
 use crate::sources::local_git::{LocalGitSource, SearchMatch, VerifiedStatus};
 use crate::sources::traits::{Comparison, DiffSource, FileEntry};
 
@@ -23,13 +29,15 @@ use super::util::{
     get_image_mime_type,
 };
 use super::ExpandedContextResult;
-use super::FileContent;
+use super::LimitedHunks;
+use super::{ContentSide, ContentWindow, DeferredContent, FileContent, FileContentWindow};
 
 /// List files with changes in the comparison.
 pub fn list_files(
     repo_path: &Path,
     comparison: &Comparison,
     github_pr: Option<&GitHubPrRef>,
+    azure_pr: Option<&AzurePrRef>,
 ) -> anyhow::Result<Vec<FileEntry>> {
     let t0 = Instant::now();
     debug!(
@@ -39,11 +47,14 @@ pub fn list_files(
 
     // PR routing: use gh CLI to get file list
     if let Some(pr) = github_pr {
-        let provider = GhCliProvider::new(repo_path.to_path_buf());
+        let provider = GitHubClient::for_repo(repo_path.to_path_buf());
         let files = provider
             .get_pull_request_files(pr.number)
             .context("Failed to list PR files")?;
-        let result = crate::sources::github::pr_files_to_file_entries(files);
+        let result = drop_ignored_files(
+            repo_path,
+            crate::sources::github::pr_files_to_file_entries(files),
+        );
         info!(
             "[list_files] SUCCESS (PR #{}): {} entries in {:?}",
             pr.number,
@@ -53,10 +64,25 @@ pub fn list_files(
         return Ok(result);
     }
 
+    // Azure DevOps PR routing: fetch the PR's refs and diff them locally
+    let fetched;
+    let comparison = match azure_pr {
+        Some(pr) => {
+            fetched = AzureDevOpsProvider::new(repo_path.to_path_buf())
+                .fetch_comparison(pr)
+                .context("Failed to fetch PR")?;
+            &fetched
+        }
+        None => comparison,
+    };
+
     let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
-    let result = source
-        .list_files(comparison)
-        .context("Failed to list files")?;
+    let result = drop_ignored_files(
+        repo_path,
+        source
+            .list_files(comparison)
+            .context("Failed to list files")?,
+    );
     info!(
         "[list_files] SUCCESS: {} entries in {:?}",
         result.len(),
@@ -65,6 +91,32 @@ pub fn list_files(
     Ok(result)
 }
 
+/// Leave out files matching the `ignore` globs in the repo's `.review.toml`,
+/// and directories that end up empty.
+fn drop_ignored_files(repo_path: &Path, entries: Vec<FileEntry>) -> Vec<FileEntry> {
+    fn retain(entries: Vec<FileEntry>, matcher: &Gitignore) -> Vec<FileEntry> {
+        entries
+            .into_iter()
+            .filter_map(|mut entry| {
+                if entry.is_directory {
+                    let children = retain(entry.children.take().unwrap_or_default(), matcher);
+                    (!children.is_empty()).then_some(FileEntry {
+                        children: Some(children),
+                        ..entry
+                    })
+                } else {
+                    (!repo_config::is_ignored(matcher, &entry.path)).then_some(entry)
+                }
+            })
+            .collect()
+    }
+
+    match RepoConfig::load(repo_path) {
+        Some(config) if !config.ignore.is_empty() => retain(entries, &config.ignore_matcher()),
+        _ => entries,
+    }
+}
+
 /// List all files in the repository (changed + unchanged, for file finder).
 pub fn list_all_files(repo_path: &Path, comparison: &Comparison) -> anyhow::Result<Vec<FileEntry>> {
     let t0 = Instant::now();
@@ -117,12 +169,83 @@ pub fn list_directory_contents(repo_path: &Path, dir_path: &str) -> anyhow::Resu
     Ok(result)
 }
 
-/// Get file content and diff hunks.
+/// Get file content and diff hunks. A file over the per-file budget
+/// (`REVIEW_MAX_FILE_BYTES`) comes back without its content, with its sizes
+/// in `deferred`; read it in windows with [`get_file_content_window`].
 pub fn get_file_content(
     repo_path: &Path,
     file_path: &str,
     comparison: &Comparison,
     github_pr: Option<&GitHubPrRef>,
+    azure_pr: Option<&AzurePrRef>,
+    function_context: bool,
+) -> anyhow::Result<FileContent> {
+    let mut content = load_file_content(
+        repo_path,
+        file_path,
+        comparison,
+        github_pr,
+        azure_pr,
+        function_context,
+    )?;
+    defer_large_content(
+        &mut content,
+        file_path,
+        ReviewLimits::from_env().max_file_bytes,
+    );
+    Ok(content)
+}
+
+/// Leave the content of a file over `limit` bytes out of `file`, recording
+/// its sizes instead. A diff over the limit is replaced by the placeholder
+/// hunk [`get_all_hunks_limited`] uses for it.
+fn defer_large_content(file: &mut FileContent, file_path: &str, limit: usize) {
+    let size = file.content.len();
+    let old_size = file.old_content.as_ref().map(String::len);
+    if size.max(old_size.unwrap_or(0)) > limit {
+        info!("[get_file_content] deferring {file_path}: {size} bytes (limit {limit})");
+        file.content = String::new();
+        file.old_content = None;
+        file.deferred = Some(DeferredContent {
+            size,
+            old_size,
+            limit,
+        });
+    }
+    if file.diff_patch.len() > limit {
+        file.hunks = vec![create_oversized_hunk(file_path, file.diff_patch.len())];
+        file.diff_patch = String::new();
+    }
+}
+
+/// A working-tree file's content as git stores it: for a symlink, the link
+/// target rather than what it points at.
+fn read_working_tree_file(path: &Path) -> std::io::Result<Vec<u8>> {
+    if path.symlink_metadata()?.file_type().is_symlink() {
+        let target = std::fs::read_link(path)?;
+        return Ok(target.to_string_lossy().into_owned().into_bytes());
+    }
+    std::fs::read(path)
+}
+
+/// Decode one version of a file (see [`decode`]), keeping in `encoding`
+/// the first encoding other than UTF-8 one was decoded from. `None` for
+/// binary content.
+fn decode_version(bytes: Vec<u8>, encoding: &mut Option<TextEncoding>) -> Option<String> {
+    let (text, detected) = decode(bytes)?;
+    if encoding.is_none() {
+        *encoding = detected;
+    }
+    Some(text)
+}
+
+fn load_file_content(
+    repo_path: &Path,
+    file_path: &str,
+    comparison: &Comparison,
+    github_pr: Option<&GitHubPrRef>,
+    azure_pr: Option<&AzurePrRef>,
+    function_context: bool,
 ) -> anyhow::Result<FileContent> {
     let t0 = Instant::now();
     debug!(
@@ -135,6 +258,28 @@ pub fn get_file_content(
         return get_file_content_for_pr(repo_path, file_path, pr);
     }
 
+    // Azure DevOps PR routing: the PR's refs, as fetched when listing files
+    let fetched;
+    let comparison = match azure_pr {
+        Some(pr) => {
+            fetched = AzureDevOpsProvider::new(repo_path.to_path_buf())
+                .comparison(pr)
+                .context("Failed to fetch PR")?;
+            &fetched
+        }
+        None => comparison,
+    };
+
+    if function_context {
+        let mut content = load_file_content(repo_path, file_path, comparison, None, None, false)?;
+        let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
+        if let Some((diff, hunks)) = function_context_hunks(&source, comparison, file_path)? {
+            content.diff_patch = diff;
+            content.hunks = hunks;
+        }
+        return Ok(content);
+    }
+
     // Validate the logical path doesn't escape the repo.
     if file_path.contains("..") || file_path.starts_with('/') || file_path.starts_with('\\') {
         bail!("Path traversal detected: file path escapes repository");
@@ -157,24 +302,27 @@ pub fn get_file_content(
 
     if !file_exists {
         debug!("[get_file_content] handling file not on disk");
-        let diff_output = source
-            .get_diff(comparison, Some(file_path))
+        let decoded = source
+            .get_decoded_diff(comparison, Some(file_path))
             .context("Failed to get diff")?;
 
-        let hunks = if diff_output.is_empty() {
+        let mut hunks = if decoded.text.is_empty() {
             vec![]
         } else {
-            parse_diff(&diff_output, file_path)
+            parse_diff(&decoded.text, file_path)
         };
+        decoded.tag(&mut hunks);
 
+        let mut encoding = None;
         let old_ref = source.diff_base_ref(comparison);
         let old_content = match source.get_file_bytes(file_path, &old_ref) {
-            Ok(bytes) => String::from_utf8(bytes).ok(),
+            Ok(bytes) => decode_version(bytes, &mut encoding),
             Err(_) => None,
         };
 
         // For committed comparisons, the file may exist on the head ref even
         // though it's not on disk.
+        let mut not_materialized = false;
         let content = if !source.include_working_tree(comparison) {
             match source.get_file_bytes(file_path, &comparison.head) {
                 Ok(bytes) => {
@@ -183,7 +331,7 @@ pub fn get_file_content(
                         comparison.head,
                         bytes.len()
                     );
-                    String::from_utf8(bytes).unwrap_or_default()
+                    decode_version(bytes, &mut encoding).unwrap_or_default()
                 }
                 Err(e) => {
                     debug!(
@@ -193,6 +341,11 @@ pub fn get_file_content(
                     String::new()
                 }
             }
+        } else if let Some(bytes) = source.unmaterialized_content(comparison, file_path) {
+            // Outside the sparse-checkout cone, not deleted.
+            debug!("[get_file_content] not materialized, read from the index");
+            not_materialized = true;
+            decode_version(bytes, &mut encoding).unwrap_or_default()
         } else {
             String::new()
         };
@@ -200,47 +353,30 @@ pub fn get_file_content(
         return Ok(FileContent {
             content,
             old_content,
-            diff_patch: diff_output,
+            diff_patch: decoded.text,
             hunks,
             content_type: "text".to_owned(),
             image_data_url: None,
             old_image_data_url: None,
+            deferred: None,
+            encoding,
+            not_materialized,
         });
     }
 
-    // Symlink directories: return the link target as content
-    if full_path.is_dir() {
-        let is_symlink = full_path
-            .symlink_metadata()
-            .is_ok_and(|m| m.file_type().is_symlink());
-
-        if !is_symlink {
-            bail!("Path is a directory: {file_path}");
-        }
-
-        let target = std::fs::read_link(&full_path)
-            .map(|p| p.to_string_lossy().to_string())
-            .unwrap_or_default();
-        let content = format!("{target}\n");
-        let content_hash = compute_content_hash(content.as_bytes());
-        let hunks = vec![create_untracked_hunk(
-            file_path,
-            &content_hash,
-            Some(&content),
-        )];
-
-        return Ok(FileContent {
-            content,
-            old_content: None,
-            diff_patch: String::new(),
-            hunks,
-            content_type: "text".to_owned(),
-            image_data_url: None,
-            old_image_data_url: None,
-        });
+    // Symlinks (to files or directories) are read as text: their link target.
+    let is_symlink = full_path
+        .symlink_metadata()
+        .is_ok_and(|m| m.file_type().is_symlink());
+    if full_path.is_dir() && !is_symlink {
+        bail!("Path is a directory: {file_path}");
     }
 
-    let content_type = get_content_type(file_path);
+    let content_type = if is_symlink {
+        "text".to_owned()
+    } else {
+        get_content_type(file_path)
+    };
     let ext = file_path.rsplit('.').next().unwrap_or("");
     let mime_type = get_image_mime_type(ext);
 
@@ -299,19 +435,27 @@ pub fn get_file_content(
             content_type,
             image_data_url,
             old_image_data_url,
+            deferred: None,
+            encoding: None,
+            not_materialized: false,
         });
     }
 
-    let content = std::fs::read_to_string(&full_path)
+    let bytes = read_working_tree_file(&full_path)
         .with_context(|| format!("{}: failed to read", full_path.display()))?;
+    let mut encoding = None;
+    let Some(content) = decode_version(bytes, &mut encoding) else {
+        bail!("{}: binary content", full_path.display());
+    };
     debug!(
         "[get_file_content] file content length: {} bytes",
         content.len()
     );
 
-    let diff_output = source
-        .get_diff(comparison, Some(file_path))
+    let decoded = source
+        .get_decoded_diff(comparison, Some(file_path))
         .context("Failed to get diff")?;
+    let diff_output = &decoded.text;
     debug!(
         "[get_file_content] diff output length: {} bytes",
         diff_output.len()
@@ -325,16 +469,15 @@ pub fn get_file_content(
         } else {
             debug!("[get_file_content] no diff, file is untracked (new)");
             let content_hash = compute_content_hash(content.as_bytes());
-            vec![create_untracked_hunk(
-                file_path,
-                &content_hash,
-                Some(&content),
-            )]
+            let mut hunk = create_untracked_hunk(file_path, &content_hash, Some(&content));
+            hunk.encoding.clone_from(&encoding);
+            vec![hunk]
         }
     } else {
         debug!("[get_file_content] parsing diff...");
-        let parsed = parse_diff(&diff_output, file_path);
+        let mut parsed = parse_diff(diff_output, file_path);
         debug!("[get_file_content] parsed {} hunks", parsed.len());
+        decoded.tag(&mut parsed);
         parsed
     };
 
@@ -348,7 +491,7 @@ pub fn get_file_content(
                     "[get_file_content] got old content from {old_ref}: {} bytes",
                     bytes.len()
                 );
-                String::from_utf8(bytes).ok()
+                decode_version(bytes, &mut encoding)
             }
             Err(e) => {
                 debug!("[get_file_content] no old version available from {old_ref}: {e}");
@@ -364,7 +507,7 @@ pub fn get_file_content(
                     "[get_file_content] got old content from {old_ref}: {} bytes",
                     bytes.len()
                 );
-                String::from_utf8(bytes).ok()
+                decode_version(bytes, &mut encoding)
             }
             Err(e) => {
                 debug!("[get_file_content] no old version at {old_ref}: {e}");
@@ -378,7 +521,7 @@ pub fn get_file_content(
                     comparison.head,
                     bytes.len()
                 );
-                String::from_utf8(bytes).ok()
+                decode_version(bytes, &mut encoding)
             }
             Err(e) => {
                 debug!(
@@ -394,11 +537,14 @@ pub fn get_file_content(
     let result = FileContent {
         content: final_content,
         old_content,
-        diff_patch: diff_output,
+        diff_patch: decoded.text,
         hunks,
         content_type,
         image_data_url: None,
         old_image_data_url: None,
+        deferred: None,
+        encoding,
+        not_materialized: false,
     };
     let payload_estimate = result.content.len()
         + result.old_content.as_ref().map_or(0, |s| s.len())
@@ -418,7 +564,7 @@ pub fn get_file_content_for_pr(
     file_path: &str,
     pr: &GitHubPrRef,
 ) -> anyhow::Result<FileContent> {
-    let provider = GhCliProvider::new(repo_path.to_path_buf());
+    let provider = GitHubClient::for_repo(repo_path.to_path_buf());
 
     // Get the full PR diff and extract this file's portion
     let full_diff = provider
@@ -446,37 +592,47 @@ pub fn get_file_content_for_pr(
             content_type,
             image_data_url: None,
             old_image_data_url: None,
+            deferred: None,
+            encoding: None,
+            not_materialized: false,
         });
     }
 
     // Try to get old/new content from local git refs
     let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
 
+    let mut encoding = None;
     let old_content = source
         .get_file_bytes(file_path, &pr.base_ref_name)
         .ok()
-        .and_then(|bytes| String::from_utf8(bytes).ok());
+        .and_then(|bytes| decode_version(bytes, &mut encoding));
 
     // Try the head ref first; if not available locally, try fetching
     let new_content = source
         .get_file_bytes(file_path, &pr.head_ref_name)
         .ok()
-        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .and_then(|bytes| decode_version(bytes, &mut encoding))
         .or_else(|| {
-            // Try fetching the PR head ref
-            let fetch_ref = format!("pull/{}/head:refs/pr/{}", pr.number, pr.number);
-            let _ = std::process::Command::new("git")
-                .args(["fetch", "origin", &fetch_ref])
-                .current_dir(repo_path)
-                .stdout(std::process::Stdio::null())
-                .stderr(std::process::Stdio::null())
-                .status();
+            // Try fetching the PR head ref, unless offline; one fetched
+            // earlier may still be there.
+            if !crate::offline::is_offline() {
+                let fetch_ref = format!("pull/{}/head:refs/pr/{}", pr.number, pr.number);
+                let remote = source
+                    .review_remote()
+                    .unwrap_or_else(|| "origin".to_owned());
+                let _ = std::process::Command::new("git")
+                    .args(["fetch", &remote, &fetch_ref])
+                    .current_dir(repo_path)
+                    .stdout(std::process::Stdio::null())
+                    .stderr(std::process::Stdio::null())
+                    .status();
+            }
 
             let pr_ref = format!("refs/pr/{}", pr.number);
             source
                 .get_file_bytes(file_path, &pr_ref)
                 .ok()
-                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .and_then(|bytes| decode_version(bytes, &mut encoding))
         });
 
     let content = new_content.unwrap_or_default();
@@ -489,6 +645,9 @@ pub fn get_file_content_for_pr(
         content_type,
         image_data_url: None,
         old_image_data_url: None,
+        deferred: None,
+        encoding,
+        not_materialized: false,
     })
 }
 
@@ -501,7 +660,7 @@ pub fn comparison_hunks(
     comparison: &Comparison,
     github_pr: Option<&GitHubPrRef>,
 ) -> anyhow::Result<Vec<DiffHunk>> {
-    let files = list_files(repo_path, comparison, github_pr)?;
+    let files = list_files(repo_path, comparison, github_pr, None)?;
     let mut paths = Vec::new();
     collect_file_paths(&files, &mut paths);
     get_all_hunks(repo_path, comparison, &paths)
@@ -520,12 +679,32 @@ fn collect_file_paths(entries: &[FileEntry], out: &mut Vec<String>) {
     }
 }
 
-/// Batch-load all hunks for multiple files in a single call.
+/// Batch-load all hunks for multiple files in a single call, within the
+/// configured [`ReviewLimits`]. Anything the budget left out is logged; use
+/// [`get_all_hunks_limited`] to get it back as structured data.
 pub fn get_all_hunks(
     repo_path: &Path,
     comparison: &Comparison,
     file_paths: &[String],
 ) -> anyhow::Result<Vec<DiffHunk>> {
+    let limited =
+        get_all_hunks_limited(repo_path, comparison, file_paths, &ReviewLimits::from_env())?;
+    for exceeded in &limited.limits_exceeded {
+        warn!("[get_all_hunks] {}", exceeded.describe());
+    }
+    Ok(limited.hunks)
+}
+
+/// Batch-load all hunks for multiple files, degrading gracefully when the
+/// comparison exceeds `limits`: files with an oversized diff (or untracked
+/// content) and files past the overall diff budget become placeholder hunks,
+/// and the hunk list is truncated to `max_hunks`.
+pub fn get_all_hunks_limited(
+    repo_path: &Path,
+    comparison: &Comparison,
+    file_paths: &[String],
+    limits: &ReviewLimits,
+) -> anyhow::Result<LimitedHunks> {
     let t0 = Instant::now();
     debug!(
         "[get_all_hunks] repo_path={}, {} files",
@@ -540,20 +719,26 @@ pub fn get_all_hunks(
         .working_tree_dir(comparison)
         .unwrap_or_else(|| repo_path.to_path_buf());
 
-    // Single git diff call for all files at once
+    // Single git diff call for all files at once, keeping only what fits
     let diff_start = Instant::now();
-    let full_diff = source
-        .get_diff(comparison, None)
+    let budgeted = source
+        .get_decoded_diff_within(comparison, limits.max_file_bytes, limits.max_diff_bytes)
         .context("Failed to get diff")?;
+    let mut decoded = budgeted.diff;
+    let full_diff = std::mem::take(&mut decoded.text);
     debug!(
-        "[get_all_hunks] git diff: {}KB in {:?}",
+        "[get_all_hunks] git diff: {}KB ({}KB kept) in {:?}",
+        budgeted.total_bytes / 1024,
         full_diff.len() / 1024,
         diff_start.elapsed()
     );
 
-    // Try hunk cache before parsing
-    let diff_hash = crate::diff::cache::compute_hash(&full_diff);
-    let mut all_hunks =
+    let mut limits_exceeded = Vec::new();
+    let within_limits = budgeted.oversized.is_empty() && budgeted.over_budget.is_empty();
+
+    let mut all_hunks = if within_limits {
+        // Try hunk cache before parsing
+        let diff_hash = crate::diff::cache::compute_hash(&full_diff);
         if let Ok(Some(cached)) = crate::diff::cache::load(repo_path, comparison, &diff_hash) {
             debug!("[get_all_hunks] hunk cache HIT");
             cached
@@ -568,7 +753,22 @@ pub fn get_all_hunks(
             // Save to cache (best-effort)
             let _ = crate::diff::cache::save(repo_path, comparison, &diff_hash, &parsed);
             parsed
-        };
+        }
+    } else {
+        // Over budget: the skipped sections become placeholders, and the cache
+        // is skipped — it only ever holds complete parses.
+        let mut hunks = parse_multi_file_diff(&full_diff);
+        hunks.extend(skipped_section_hunks(
+            budgeted.oversized,
+            budgeted.over_budget,
+            budgeted.total_bytes,
+            limits,
+            &mut limits_exceeded,
+        ));
+        hunks.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        hunks
+    };
+    decoded.tag(&mut all_hunks);
     drop(full_diff);
 
     // Build a set of file paths that got hunks from the diff
@@ -576,38 +776,229 @@ pub fn get_all_hunks(
 
     // For requested files that have no diff hunks, check if they're
     // untracked (new) and create untracked hunks for them
+    let mut oversized_untracked: Vec<(String, usize)> = Vec::new();
     for fp in file_paths {
         if !files_with_hunks.contains(fp.as_str()) {
             let is_tracked = source.is_file_tracked(fp).unwrap_or(false);
             if !is_tracked {
                 let full_path = content_root.join(fp);
-                let (content_hash, text_content) = std::fs::read(&full_path)
-                    .map(|bytes| {
-                        let hash = compute_content_hash(&bytes);
-                        let text = String::from_utf8(bytes).ok();
-                        (hash, text)
-                    })
-                    .unwrap_or_else(|_| ("00000000".to_owned(), None));
-                all_hunks.push(create_untracked_hunk(
-                    fp,
-                    &content_hash,
-                    text_content.as_deref(),
-                ));
+                let size = std::fs::symlink_metadata(&full_path).map_or(0, |m| m.len() as usize);
+                if size > limits.max_file_bytes {
+                    all_hunks.push(create_oversized_hunk(fp, size));
+                    oversized_untracked.push((fp.clone(), size));
+                    continue;
+                }
+                let (content_hash, text_content, encoding) =
+                    match read_working_tree_file(&full_path) {
+                        Ok(bytes) => {
+                            let hash = compute_content_hash(&bytes);
+                            match decode(bytes) {
+                                // Hashed as decoded, as `get_file_content` does.
+                                Some((text, Some(encoding))) => (
+                                    compute_content_hash(text.as_bytes()),
+                                    Some(text),
+                                    Some(encoding),
+                                ),
+                                Some((text, None)) => (hash, Some(text), None),
+                                None => (hash, None, None),
+                            }
+                        }
+                        Err(_) => ("00000000".to_owned(), None, None),
+                    };
+                let mut hunk = create_untracked_hunk(fp, &content_hash, text_content.as_deref());
+                hunk.encoding = encoding;
+                all_hunks.push(hunk);
             }
         }
     }
+    if !oversized_untracked.is_empty() {
+        limits_exceeded.push(LimitExceeded {
+            kind: LimitKind::FileBytes,
+            limit: limits.max_file_bytes,
+            actual: oversized_untracked
+                .iter()
+                .map(|(_, size)| *size)
+                .max()
+                .unwrap_or(0),
+            degradation: Degradation::Skipped,
+            files: oversized_untracked
+                .into_iter()
+                .map(|(path, _)| path)
+                .collect(),
+        });
+    }
 
     // Filter to only include hunks for the requested files
     let requested: HashSet<&str> = file_paths.iter().map(|s| s.as_str()).collect();
     all_hunks.retain(|h| requested.contains(h.file_path.as_str()));
 
+    if all_hunks.len() > limits.max_hunks {
+        limits_exceeded.push(LimitExceeded {
+            kind: LimitKind::Hunks,
+            limit: limits.max_hunks,
+            actual: all_hunks.len(),
+            degradation: Degradation::Truncated,
+            files: Vec::new(),
+        });
+        all_hunks.truncate(limits.max_hunks);
+    }
+
     info!(
         "[get_all_hunks] SUCCESS: {} hunks from {} files in {:?}",
         all_hunks.len(),
         file_paths.len(),
         t0.elapsed()
     );
-    Ok(all_hunks)
+    Ok(LimitedHunks {
+        hunks: all_hunks,
+        limits_exceeded,
+    })
+}
+
+/// Placeholder hunks for the diff sections skipped under `limits`: those over
+/// the per-file budget, and those past the overall budget of `total_bytes`.
+/// Each group is reported in `exceeded`.
+fn skipped_section_hunks(
+    oversized: Vec<(String, usize)>,
+    over_budget: Vec<(String, usize)>,
+    total_bytes: usize,
+    limits: &ReviewLimits,
+    exceeded: &mut Vec<LimitExceeded>,
+) -> Vec<DiffHunk> {
+    let hunks = oversized
+        .iter()
+        .chain(&over_budget)
+        .map(|(path, bytes)| create_oversized_hunk(path, *bytes))
+        .collect();
+
+    if let Some(largest) = oversized.iter().map(|(_, bytes)| *bytes).max() {
+        exceeded.push(LimitExceeded {
+            kind: LimitKind::FileBytes,
+            limit: limits.max_file_bytes,
+            actual: largest,
+            degradation: Degradation::Skipped,
+            files: oversized.into_iter().map(|(path, _)| path).collect(),
+        });
+    }
+    if !over_budget.is_empty() {
+        exceeded.push(LimitExceeded {
+            kind: LimitKind::DiffBytes,
+            limit: limits.max_diff_bytes,
+            actual: total_bytes,
+            degradation: Degradation::Skipped,
+            files: over_budget.into_iter().map(|(path, _)| path).collect(),
+        });
+    }
+    hunks
+}
+
+/// Replace the hunks of each translation catalog with one synthetic rollup
+/// hunk listing the keys added, removed, and changed (see
+/// [`crate::diff::translations`]). The rollup takes the place of the file's
+/// first hunk. Catalogs whose content can't be loaded or parsed keep their
+/// real hunks.
+pub fn collapse_translation_hunks(
+    repo_path: &Path,
+    comparison: &Comparison,
+    hunks: Vec<DiffHunk>,
+) -> Vec<DiffHunk> {
+    let mut rollups: HashMap<String, Option<DiffHunk>> = HashMap::new();
+    for hunk in &hunks {
+        if rollups.contains_key(&hunk.file_path) || !supports_rollup(&hunk.file_path) {
+            continue;
+        }
+        let rollup =
+            match get_file_content(repo_path, &hunk.file_path, comparison, None, None, false) {
+                Ok(file) if file.deferred.is_some() => None,
+                Ok(file) => create_translation_rollup_hunk(
+                    &hunk.file_path,
+                    file.old_content.as_deref().unwrap_or_default(),
+                    &file.content,
+                ),
+                Err(e) => {
+                    warn!(
+                        "[collapse_translation_hunks] Failed to load {}: {e}",
+                        hunk.file_path
+                    );
+                    None
+                }
+            };
+        rollups.insert(hunk.file_path.clone(), rollup);
+    }
+
+    let mut emitted = HashSet::new();
+    hunks
+        .into_iter()
+        .filter_map(|hunk| match rollups.get(&hunk.file_path) {
+            Some(Some(rollup)) => emitted
+                .insert(hunk.file_path.clone())
+                .then(|| rollup.clone()),
+            _ => Some(hunk),
+        })
+        .collect()
+}
+
+/// `file_path`'s diff regenerated with function context, and its hunks —
+/// each carrying its whole enclosing function and the IDs of the plain
+/// hunks it covers. `None` when there's no such diff (e.g. untracked files).
+fn function_context_hunks(
+    source: &LocalGitSource,
+    comparison: &Comparison,
+    file_path: &str,
+) -> anyhow::Result<Option<(String, Vec<DiffHunk>)>> {
+    let diff = source
+        .get_function_context_diff(comparison, file_path)
+        .context("Failed to get function-context diff")?;
+    if diff.is_empty() {
+        return Ok(None);
+    }
+    let hunks = parse_wide_diff(&diff, file_path);
+    Ok(Some((diff, hunks)))
+}
+
+/// Replace the hunks of each of `files` with function-context hunks (see
+/// [`LocalGitSource::get_function_context_diff`]), in place of the file's
+/// first hunk. Files whose diff can't be regenerated keep their hunks.
+pub fn widen_to_functions(
+    repo_path: &Path,
+    comparison: &Comparison,
+    hunks: Vec<DiffHunk>,
+    files: &[String],
+) -> Vec<DiffHunk> {
+    if files.is_empty() {
+        return hunks;
+    }
+    let source = match LocalGitSource::new(repo_path.to_path_buf()) {
+        Ok(source) => source,
+        Err(e) => {
+            warn!("[widen_to_functions] Failed to open repo: {e}");
+            return hunks;
+        }
+    };
+    let mut wide: HashMap<&str, Vec<DiffHunk>> = HashMap::new();
+    for file in files {
+        match function_context_hunks(&source, comparison, file) {
+            Ok(Some((_, file_hunks))) => {
+                wide.insert(file.as_str(), file_hunks);
+            }
+            Ok(None) => {}
+            Err(e) => warn!("[widen_to_functions] {file}: {e:#}"),
+        }
+    }
+
+    let mut emitted = HashSet::new();
+    let mut result = Vec::with_capacity(hunks.len());
+    for hunk in hunks {
+        match wide.get(hunk.file_path.as_str()) {
+            Some(file_hunks) => {
+                if emitted.insert(hunk.file_path.clone()) {
+                    result.extend(file_hunks.iter().cloned());
+                }
+            }
+            None => result.push(hunk),
+        }
+    }
+    result
 }
 
 /// Get file content for working tree diff (staged or unstaged).
@@ -627,23 +1018,24 @@ pub fn get_working_tree_file_content(
         parse_diff(&raw_diff, file_path)
     };
 
+    let mut encoding = None;
     let old_content = if cached {
         // Staged diff: old side is HEAD
         source
             .get_file_bytes(file_path, "HEAD")
             .ok()
-            .and_then(|b| String::from_utf8(b).ok())
+            .and_then(|b| decode_version(b, &mut encoding))
     } else {
         // Unstaged diff: old side is the index, falling back to HEAD
         source
             .get_file_bytes(file_path, ":0")
             .ok()
-            .and_then(|b| String::from_utf8(b).ok())
+            .and_then(|b| decode_version(b, &mut encoding))
             .or_else(|| {
                 source
                     .get_file_bytes(file_path, "HEAD")
                     .ok()
-                    .and_then(|b| String::from_utf8(b).ok())
+                    .and_then(|b| decode_version(b, &mut encoding))
             })
     };
 
@@ -652,12 +1044,15 @@ pub fn get_working_tree_file_content(
         source
             .get_file_bytes(file_path, ":0")
             .ok()
-            .and_then(|b| String::from_utf8(b).ok())
+            .and_then(|b| decode_version(b, &mut encoding))
             .unwrap_or_default()
     } else {
         // Unstaged diff: new side is the working tree
         let full_path = repo_path.join(file_path);
-        std::fs::read_to_string(&full_path).unwrap_or_default()
+        std::fs::read(&full_path)
+            .ok()
+            .and_then(|b| decode_version(b, &mut encoding))
+            .unwrap_or_default()
     };
 
     let content_type = get_content_type(file_path);
@@ -670,6 +1065,9 @@ pub fn get_working_tree_file_content(
         content_type,
         image_data_url: None,
         old_image_data_url: None,
+        deferred: None,
+        encoding,
+        not_materialized: false,
     })
 }
 
@@ -710,6 +1108,95 @@ pub fn get_expanded_context(
     })
 }
 
+/// Read one window of a file's old or new side — how the content of a file
+/// [`get_file_content`] deferred is loaded. Sides are read as
+/// `get_file_content` reads them: the new side from disk when the comparison
+/// includes the working tree.
+pub fn get_file_content_window(
+    repo_path: &Path,
+    file_path: &str,
+    comparison: &Comparison,
+    side: ContentSide,
+    window: ContentWindow,
+    github_pr: Option<&GitHubPrRef>,
+) -> anyhow::Result<FileContentWindow> {
+    let t0 = Instant::now();
+    if file_path.contains("..") || file_path.starts_with('/') || file_path.starts_with('\\') {
+        bail!("Path traversal detected: file path escapes repository");
+    }
+    let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
+
+    let bytes = match (side, github_pr) {
+        (ContentSide::Old, Some(pr)) => source.get_file_bytes(file_path, &pr.base_ref_name)?,
+        (ContentSide::New, Some(pr)) => source.get_file_bytes(file_path, &pr.head_ref_name)?,
+        (ContentSide::Old, None) => {
+            source.get_file_bytes(file_path, &source.diff_base_ref(comparison))?
+        }
+        (ContentSide::New, None) if source.include_working_tree(comparison) => {
+            let content_root = source
+                .working_tree_dir(comparison)
+                .unwrap_or_else(|| repo_path.to_path_buf());
+            let full_path = content_root.join(file_path);
+            match std::fs::read(&full_path) {
+                Ok(bytes) => bytes,
+                Err(e) => source
+                    .unmaterialized_content(comparison, file_path)
+                    .ok_or(e)
+                    .with_context(|| format!("{}: failed to read", full_path.display()))?,
+            }
+        }
+        (ContentSide::New, None) => source.get_file_bytes(file_path, &comparison.head)?,
+    };
+
+    let result = window_content(&bytes, window);
+    info!(
+        "[get_file_content_window] SUCCESS file={file_path} side={side:?} {}..{} of {} in {:?}",
+        result.start,
+        result.end,
+        result.total,
+        t0.elapsed()
+    );
+    Ok(result)
+}
+
+/// Cut `window` out of `bytes`.
+fn window_content(bytes: &[u8], window: ContentWindow) -> FileContentWindow {
+    match window {
+        ContentWindow::Lines { start, count } => {
+            let text = String::from_utf8_lossy(bytes);
+            let lines: Vec<&str> = text.split_inclusive('\n').collect();
+            let start = start.min(lines.len());
+            let end = start.saturating_add(count).min(lines.len());
+            FileContentWindow {
+                content: lines[start..end].concat(),
+                start,
+                end,
+                total: lines.len(),
+                size: bytes.len(),
+            }
+        }
+        ContentWindow::Bytes { offset, length } => {
+            // UTF-8 continuation bytes are 0b10xx_xxxx.
+            let is_boundary = |i: usize| i >= bytes.len() || bytes[i] & 0xC0 != 0x80;
+            let mut start = offset.min(bytes.len());
+            while !is_boundary(start) {
+                start += 1;
+            }
+            let mut end = offset.saturating_add(length).min(bytes.len()).max(start);
+            while end > start && !is_boundary(end) {
+                end -= 1;
+            }
+            FileContentWindow {
+                content: String::from_utf8_lossy(&bytes[start..end]).into_owned(),
+                start,
+                end,
+                total: bytes.len(),
+                size: bytes.len(),
+            }
+        }
+    }
+}
+
 /// Read a raw file from disk (no git needed, for standalone file viewing).
 pub fn read_raw_file(path: &Path) -> anyhow::Result<FileContent> {
     let t0 = Instant::now();
@@ -797,6 +1284,8 @@ pub fn list_directory_plain(dir_path: &Path) -> anyhow::Result<Vec<FileEntry>> {
                 .and_then(|m| m.modified().ok())
                 .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
                 .map(|d| d.as_secs()),
+            is_lfs_pointer: false,
+            not_materialized: false,
         });
     }
 
@@ -947,6 +1436,60 @@ fn verify_matches(
 mod tests {
     use super::*;
 
+    #[test]
+    fn large_content_is_deferred_and_read_in_windows() {
+        let mut file = FileContent {
+            content: "a\nb\nc\n".repeat(10),
+            old_content: Some("a\n".to_owned()),
+            diff_patch: "+x\n".to_owned(),
+            hunks: Vec::new(),
+            content_type: "text".to_owned(),
+            image_data_url: None,
+            old_image_data_url: None,
+            deferred: None,
+            encoding: None,
+            not_materialized: false,
+        };
+        defer_large_content(&mut file, "gen.txt", 16);
+        assert!(file.content.is_empty() && file.old_content.is_none());
+        assert_eq!(
+            file.deferred,
+            Some(DeferredContent {
+                size: 60,
+                old_size: Some(2),
+                limit: 16,
+            })
+        );
+        assert_eq!(file.diff_patch, "+x\n");
+
+        let lines = window_content(
+            b"one\ntwo\nthree",
+            ContentWindow::Lines { start: 1, count: 5 },
+        );
+        assert_eq!(lines.content, "two\nthree");
+        assert_eq!((lines.start, lines.end, lines.total), (1, 3, 3));
+
+        // "é" is two bytes; a window starting or ending inside it is narrowed.
+        let text = "aé b".as_bytes();
+        let bytes = window_content(
+            text,
+            ContentWindow::Bytes {
+                offset: 2,
+                length: 2,
+            },
+        );
+        assert_eq!(bytes.content, " ");
+        assert_eq!((bytes.start, bytes.end, bytes.total), (3, 4, 5));
+        let past_end = window_content(
+            text,
+            ContentWindow::Bytes {
+                offset: 9,
+                length: 4,
+            },
+        );
+        assert!(past_end.content.is_empty());
+    }
+
     #[test]
     fn is_identifier_query_accepts_typical_identifiers() {
         assert!(is_identifier_query("verified"));
@@ -1048,7 +1591,7 @@ mod tests {
         git(p, &["checkout", "-q", "feat"]);
 
         let comparison = Comparison::new(&default_branch, "feat");
-        let fc = get_file_content(p, "shared.txt", &comparison, None).unwrap();
+        let fc = get_file_content(p, "shared.txt", &comparison, None, None, false).unwrap();
 
         // Old side is the merge-base version, so the rendered diff shows only
         // feat's line2 change — not the default branch's line1 change.
@@ -1058,4 +1601,267 @@ mod tests {
             "old content should come from the merge-base, not the default branch tip"
         );
     }
+
+    #[test]
+    fn get_all_hunks_limited_skips_oversized_files() {
+        use crate::review::central::tests::{setup_test, ENV_LOCK};
+        use crate::sources::traits::Comparison;
+        use std::process::Command as Cmd;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let p = repo.path();
+        let git = |args: &[&str]| {
+            assert!(Cmd::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(p)
+                .status()
+                .unwrap()
+                .success());
+        };
+        git(&["init", "-q"]);
+        std::fs::write(p.join("small.txt"), "a\n").unwrap();
+        std::fs::write(p.join("big.txt"), "a\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "init"]);
+        std::fs::write(p.join("small.txt"), "b\n").unwrap();
+        std::fs::write(p.join("big.txt"), "x\n".repeat(1000)).unwrap();
+        std::fs::write(p.join("new.txt"), "y\n".repeat(1000)).unwrap();
+
+        let branch = LocalGitSource::new(p.to_path_buf())
+            .unwrap()
+            .get_current_branch()
+            .unwrap();
+        let comparison = Comparison::new("HEAD", branch);
+        let paths: Vec<String> = ["big.txt", "new.txt", "small.txt"]
+            .map(String::from)
+            .to_vec();
+        let limits = ReviewLimits {
+            max_file_bytes: 1000,
+            ..ReviewLimits::default()
+        };
+        let limited = get_all_hunks_limited(p, &comparison, &paths, &limits).unwrap();
+
+        let small: Vec<_> = limited
+            .hunks
+            .iter()
+            .filter(|h| h.file_path == "small.txt")
+            .collect();
+        assert_eq!(small.len(), 1);
+        assert!(!small[0].lines.is_empty());
+        let skipped: Vec<&str> = limited
+            .limits_exceeded
+            .iter()
+            .filter(|e| e.kind == LimitKind::FileBytes)
+            .flat_map(|e| e.files.iter().map(String::as_str))
+            .collect();
+        assert_eq!(skipped, ["big.txt", "new.txt"]);
+
+        // big.txt no longer fits in the overall budget, but small.txt after
+        // it still does.
+        let limits = ReviewLimits {
+            max_diff_bytes: 3000,
+            ..ReviewLimits::unlimited()
+        };
+        let budgeted = get_all_hunks_limited(p, &comparison, &paths, &limits).unwrap();
+        let [exceeded] = &budgeted.limits_exceeded[..] else {
+            panic!("expected one limit: {:?}", budgeted.limits_exceeded);
+        };
+        assert_eq!(exceeded.kind, LimitKind::DiffBytes);
+        assert_eq!(exceeded.files, ["big.txt"]);
+        assert!(exceeded.actual > 3000);
+        let big: Vec<_> = budgeted
+            .hunks
+            .iter()
+            .filter(|h| h.file_path == "big.txt")
+            .collect();
+        assert_eq!(big.len(), 1);
+        assert_eq!(big[0].lines.len(), 1);
+        assert!(budgeted
+            .hunks
+            .iter()
+            .any(|h| h.file_path == "small.txt" && !h.lines.is_empty()));
+
+        let full =
+            get_all_hunks_limited(p, &comparison, &paths, &ReviewLimits::unlimited()).unwrap();
+        assert!(full.limits_exceeded.is_empty());
+        assert!(full
+            .hunks
+            .iter()
+            .any(|h| h.file_path == "big.txt" && h.lines.len() > 100));
+    }
+
+    #[test]
+    fn collapse_translation_hunks_rolls_up_catalogs() {
+        use crate::review::central::tests::{setup_test, ENV_LOCK};
+        use crate::sources::traits::Comparison;
+        use std::process::Command as Cmd;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let p = repo.path();
+        let git = |args: &[&str]| {
+            assert!(Cmd::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(p)
+                .status()
+                .unwrap()
+                .success());
+        };
+        git(&["init", "-q"]);
+        std::fs::create_dir(p.join("locales")).unwrap();
+        let keys = |n: usize, value: &str| {
+            let body: Vec<String> = (0..n)
+                .map(|i| format!("  \"k{i}\": \"{value}{i}\""))
+                .collect();
+            format!("{{\n{}\n}}\n", body.join(",\n"))
+        };
+        std::fs::write(p.join("locales/en.json"), keys(40, "v")).unwrap();
+        std::fs::write(p.join("app.txt"), "a\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "init"]);
+        // Rewrite every other key so the diff has several hunks.
+        let mut en = keys(40, "v");
+        for i in (0..40).step_by(10) {
+            en = en.replace(&format!("\"v{i}\""), &format!("\"w{i}\""));
+        }
+        std::fs::write(p.join("locales/en.json"), en).unwrap();
+        std::fs::write(p.join("app.txt"), "b\n").unwrap();
+
+        let branch = LocalGitSource::new(p.to_path_buf())
+            .unwrap()
+            .get_current_branch()
+            .unwrap();
+        let comparison = Comparison::new("HEAD", branch);
+        let paths: Vec<String> = ["app.txt", "locales/en.json"].map(String::from).to_vec();
+        let hunks = get_all_hunks(p, &comparison, &paths).unwrap();
+        assert!(
+            hunks
+                .iter()
+                .filter(|h| h.file_path == "locales/en.json")
+                .count()
+                > 1
+        );
+
+        let collapsed = collapse_translation_hunks(p, &comparison, hunks);
+        let files: Vec<&str> = collapsed.iter().map(|h| h.file_path.as_str()).collect();
+        assert_eq!(files, ["app.txt", "locales/en.json"]);
+        let rollup = &collapsed[1];
+        assert_eq!(
+            rollup.lines[0].content,
+            "(translations (en): 0 added, 0 removed, 4 changed)"
+        );
+        assert_eq!(rollup.lines[1].content, "k0 = v0");
+        assert_eq!(rollup.lines[2].content, "k0 = w0");
+    }
+
+    #[test]
+    fn file_outside_sparse_cone_is_read_from_the_index() {
+        use crate::review::central::tests::{setup_test, ENV_LOCK};
+        use crate::sources::traits::Comparison;
+        use std::process::Command as Cmd;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let p = repo.path();
+        let git = |args: &[&str]| {
+            assert!(Cmd::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(p)
+                .status()
+                .unwrap()
+                .success());
+        };
+        git(&["init", "-q"]);
+        std::fs::create_dir_all(p.join("app")).unwrap();
+        std::fs::create_dir_all(p.join("docs")).unwrap();
+        std::fs::write(p.join("app/main.txt"), "a\n").unwrap();
+        std::fs::write(p.join("docs/guide.txt"), "old\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "init"]);
+        std::fs::write(p.join("docs/guide.txt"), "new\n").unwrap();
+        git(&["commit", "-qam", "edit guide"]);
+        git(&["sparse-checkout", "set", "app"]);
+        assert!(!p.join("docs/guide.txt").exists());
+
+        let branch = LocalGitSource::new(p.to_path_buf())
+            .unwrap()
+            .get_current_branch()
+            .unwrap();
+        let comparison = Comparison::new("HEAD~1", branch);
+        let fc = get_file_content(p, "docs/guide.txt", &comparison, None, None, false).unwrap();
+        assert!(fc.not_materialized);
+        assert_eq!(fc.content, "new\n");
+        assert_eq!(fc.old_content.as_deref(), Some("old\n"));
+
+        let on_disk = get_file_content(p, "app/main.txt", &comparison, None, None, false).unwrap();
+        assert!(!on_disk.not_materialized);
+    }
+
+    #[test]
+    fn listing_flags_unresolved_files_and_symlinks_read_as_their_target() {
+        use crate::review::central::tests::{setup_test, ENV_LOCK};
+        use crate::sources::traits::Comparison;
+        use std::process::Command as Cmd;
+
+        fn find<'a>(entries: &'a [FileEntry], path: &str) -> Option<&'a FileEntry> {
+            entries.iter().find_map(|e| {
+                if e.path == path {
+                    Some(e)
+                } else {
+                    find(e.children.as_deref()?, path)
+                }
+            })
+        }
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let p = repo.path();
+        let git = |args: &[&str]| {
+            assert!(Cmd::new("git")
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .current_dir(p)
+                .status()
+                .unwrap()
+                .success());
+        };
+        git(&["init", "-q"]);
+        std::fs::create_dir_all(p.join("app")).unwrap();
+        std::fs::create_dir_all(p.join("docs")).unwrap();
+        std::fs::write(p.join(".gitattributes"), "*.bin filter=lfs -text\n").unwrap();
+        std::fs::write(
+            p.join("big.bin"),
+            "version https://git-lfs.github.com/spec/v1\noid sha256:abc\nsize 12345\n",
+        )
+        .unwrap();
+        std::fs::write(p.join("app/main.txt"), "a\n").unwrap();
+        std::fs::write(p.join("docs/guide.txt"), "g\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-qm", "init"]);
+        git(&["sparse-checkout", "set", "app"]);
+        std::os::unix::fs::symlink("app/main.txt", p.join("link.txt")).unwrap();
+
+        let branch = LocalGitSource::new(p.to_path_buf())
+            .unwrap()
+            .get_current_branch()
+            .unwrap();
+        let comparison = Comparison::new("HEAD", branch);
+        let files = list_files(p, &comparison, None, None).unwrap();
+        let lfs = find(&files, "big.bin").unwrap();
+        assert!(lfs.is_lfs_pointer);
+        assert_eq!(lfs.size, Some(12345));
+        assert!(find(&files, "docs/guide.txt").unwrap().not_materialized);
+        assert!(find(&files, "link.txt").unwrap().is_symlink);
+        let plain = find(&files, "app/main.txt").unwrap();
+        assert!(!plain.is_lfs_pointer && !plain.not_materialized);
+
+        let hunks = get_all_hunks(p, &comparison, &["link.txt".to_owned()]).unwrap();
+        let fc = get_file_content(p, "link.txt", &comparison, None, None, false).unwrap();
+        assert_eq!(fc.content, "app/main.txt");
+        assert_eq!(hunks[0].id, fc.hunks[0].id);
+    }
 }
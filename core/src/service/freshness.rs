@@ -1,13 +1,17 @@
 //! Review freshness checking — determines whether a review's diff is still active.
 
 use log::error;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+use crate::review::storage;
 use crate::service::targets::resolve_review;
-use crate::sources::github::GhCliProvider;
+use crate::sources::github::{GitHubClient, PrStatus};
 use crate::sources::local_git::{DiffShortStat, LocalGitSource};
 use crate::sources::traits::Comparison;
 
+use super::jobs::{self, JobKind, JobPriority};
+use super::shortstats::cached_diff_shortstat;
 use super::{ReviewFreshnessInput, ReviewFreshnessResult};
 
 /// A diff is considered active when it has any changed files, additions, or deletions.
@@ -35,12 +39,26 @@ pub fn missing_refs_from_resolved(
 
 /// Check freshness for a single review.
 pub fn check_single_review_freshness(input: ReviewFreshnessInput) -> ReviewFreshnessResult {
+    check_review_freshness(input, None)
+}
+
+/// Check freshness for a single review, using `pr_status` when its PR's
+/// status was already fetched.
+fn check_review_freshness(
+    input: ReviewFreshnessInput,
+    pr_status: Option<PrStatus>,
+) -> ReviewFreshnessResult {
     let key = format!("{}:{}", input.repo_path, input.ref_name);
 
-    // PR comparisons: check state via gh CLI
+    // PR comparisons: check state via GitHub
     if let Some(ref pr) = input.github_pr {
-        let provider = GhCliProvider::new(PathBuf::from(&input.repo_path));
-        match provider.get_pr_status(pr.number) {
+        let status = match pr_status {
+            Some(status) => Ok(status),
+            None => {
+                GitHubClient::for_repo(PathBuf::from(&input.repo_path)).get_pr_status(pr.number)
+            }
+        };
+        match status {
             Ok(status) => {
                 let is_merged_or_closed = status.state == "MERGED" || status.state == "CLOSED";
                 if is_merged_or_closed {
@@ -140,8 +158,8 @@ pub fn check_single_review_freshness(input: ReviewFreshnessInput) -> ReviewFresh
         }
     };
 
-    // Working tree comparisons always need re-check
-    if source.include_working_tree(&comparison) {
+    // Working tree and index comparisons always need re-check
+    if source.diffs_uncommitted(&comparison) {
         let stats = source.get_diff_shortstat(&comparison).ok();
         return ReviewFreshnessResult {
             key,
@@ -189,8 +207,15 @@ pub fn check_single_review_freshness(input: ReviewFreshnessInput) -> ReviewFresh
         };
     }
 
-    // SHAs changed — re-check diff stats
-    let stats = source.get_diff_shortstat(&comparison).ok();
+    // SHAs changed — re-check diff stats (cached per SHA pair)
+    let stats = cached_diff_shortstat(
+        Path::new(&input.repo_path),
+        &source,
+        &comparison,
+        &resolved_old,
+        &resolved_new,
+    )
+    .ok();
     ReviewFreshnessResult {
         key,
         is_active: is_diff_active(&stats),
@@ -201,19 +226,75 @@ pub fn check_single_review_freshness(input: ReviewFreshnessInput) -> ReviewFresh
     }
 }
 
-/// Batch-check freshness for multiple reviews in parallel.
+/// PR statuses for the reviews' repos that talk to the GitHub API, which
+/// answers for many PRs per request. Keyed by repo path, then PR number.
+fn prefetch_pr_statuses(prs: Vec<(String, u32)>) -> HashMap<String, HashMap<u32, PrStatus>> {
+    let mut numbers: HashMap<String, Vec<u32>> = HashMap::new();
+    for (repo_path, number) in prs {
+        numbers.entry(repo_path).or_default().push(number);
+    }
+    numbers
+        .into_iter()
+        .filter_map(|(repo_path, numbers)| {
+            let GitHubClient::Api(api) = GitHubClient::for_repo(PathBuf::from(&repo_path)) else {
+                return None;
+            };
+            match api.get_pr_statuses(&numbers) {
+                Ok(statuses) => Some((repo_path, statuses)),
+                Err(e) => {
+                    error!("[check_reviews_freshness] batch PR status failed for {repo_path}: {e}");
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Batch-check freshness for multiple reviews in parallel, as background jobs.
 pub async fn check_reviews_freshness(
     reviews: Vec<ReviewFreshnessInput>,
 ) -> Vec<ReviewFreshnessResult> {
-    static FRESHNESS_SEMAPHORE: tokio::sync::Semaphore = tokio::sync::Semaphore::const_new(6);
+    // Archived reviews are parked on purpose — never spend git work on them.
+    let reviews: Vec<_> = reviews
+        .into_iter()
+        .filter(|input| {
+            !storage::is_review_archived(Path::new(&input.repo_path), &input.ref_name)
+                .unwrap_or(false)
+        })
+        .collect();
+
+    let prs: Vec<_> = reviews
+        .iter()
+        .filter_map(|input| Some((input.repo_path.clone(), input.github_pr.as_ref()?.number)))
+        .collect();
+    let mut prefetched = if prs.is_empty() {
+        HashMap::new()
+    } else {
+        jobs::run_blocking(
+            JobKind::Freshness,
+            JobPriority::Background,
+            "Check pull request statuses".to_owned(),
+            move || prefetch_pr_statuses(prs),
+        )
+        .await
+        .unwrap_or_default()
+    };
 
     let handles: Vec<_> = reviews
         .into_iter()
         .map(|input| {
-            tokio::spawn(async move {
-                let _permit = FRESHNESS_SEMAPHORE.acquire().await.unwrap();
-                tokio::task::spawn_blocking(move || check_single_review_freshness(input)).await
-            })
+            let pr_status = input.github_pr.as_ref().and_then(|pr| {
+                prefetched
+                    .get_mut(&input.repo_path)
+                    .and_then(|statuses| statuses.remove(&pr.number))
+            });
+            let label = format!("Check freshness of {}", input.ref_name);
+            tokio::spawn(jobs::run_blocking(
+                JobKind::Freshness,
+                JobPriority::Background,
+                label,
+                move || check_review_freshness(input, pr_status),
+            ))
         })
         .collect();
 
@@ -0,0 +1,605 @@
+//! Ingest static analyzer reports as findings on a review's hunks.
+//!
+//! Each supported format (SARIF, `cargo clippy --message-format=json`,
+//! `eslint -f json`, `semgrep --json`) is parsed into a [`Report`]; its
+//! findings that land on a changed line of the diff become [`Finding`]s on
+//! that hunk. The rest are about code the review didn't touch and are
+//! dropped. A finding's ID fingerprints the tool, rule, file, message and
+//! flagged line's text, so re-ingesting a run updates findings in place, and
+//! a tool's findings missing from its latest run are dropped as fixed.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::diff::parser::{DiffHunk, LineType};
+use crate::review::state::{Finding, FindingSeverity, ReviewState};
+
+/// One finding as an analyzer reported it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportedFinding {
+    pub tool: String,
+    pub rule: Option<String>,
+    pub severity: FindingSeverity,
+    pub message: String,
+    /// As reported: absolute, repo-relative, or relative to where the tool ran.
+    pub path: String,
+    pub line: u32,
+    pub end_line: Option<u32>,
+}
+
+/// A parsed report: the tools that ran, even those that found nothing, and
+/// their findings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub tools: BTreeSet<String>,
+    pub findings: Vec<ReportedFinding>,
+}
+
+fn parse_json<'a, T: Deserialize<'a>>(input: &'a str, format: &str) -> anyhow::Result<T> {
+    serde_json::from_str(input).with_context(|| format!("Not a valid {format} report"))
+}
+
+#[derive(Deserialize)]
+struct SarifLog {
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Deserialize)]
+struct SarifRun {
+    tool: SarifTool,
+    #[serde(default)]
+    results: Vec<SarifResult>,
+}
+
+#[derive(Deserialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Deserialize)]
+struct SarifDriver {
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: Option<String>,
+    level: Option<String>,
+    message: SarifMessage,
+    #[serde(default)]
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Deserialize)]
+struct SarifMessage {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: Option<SarifPhysicalLocation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: Option<SarifRegion>,
+}
+
+#[derive(Deserialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+}
+
+/// Parse a SARIF 2.1 log. Results without a line-level location are skipped.
+pub fn parse_sarif(input: &str) -> anyhow::Result<Report> {
+    let log: SarifLog = parse_json(input, "SARIF")?;
+    let mut report = Report::default();
+    for run in log.runs {
+        let tool = run.tool.driver.name;
+        report.tools.insert(tool.clone());
+        for result in run.results {
+            let Some(location) = result
+                .locations
+                .into_iter()
+                .find_map(|l| l.physical_location)
+            else {
+                continue;
+            };
+            let Some(line) = location.region.as_ref().and_then(|r| r.start_line) else {
+                continue;
+            };
+            let severity = match result.level.as_deref() {
+                Some("error") => FindingSeverity::Error,
+                Some("note" | "none") => FindingSeverity::Info,
+                _ => FindingSeverity::Warning,
+            };
+            report.findings.push(ReportedFinding {
+                tool: tool.clone(),
+                rule: result.rule_id,
+                severity,
+                message: result.message.text,
+                path: location.artifact_location.uri,
+                line,
+                end_line: location.region.and_then(|r| r.end_line),
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RustcDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    level: String,
+    code: Option<RustcCode>,
+    #[serde(default)]
+    spans: Vec<RustcSpan>,
+}
+
+#[derive(Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: u32,
+    line_end: u32,
+    is_primary: bool,
+}
+
+/// Parse `cargo clippy --message-format=json` output (one JSON message per
+/// line). Only diagnostics with a primary span count; build progress, notes
+/// and the closing "N warnings emitted" summary are skipped.
+pub fn parse_clippy(input: &str) -> anyhow::Result<Report> {
+    let mut report = Report {
+        tools: BTreeSet::from(["clippy".to_owned()]),
+        findings: Vec::new(),
+    };
+    for line in input.lines().filter(|l| l.trim_start().starts_with('{')) {
+        let message: CargoMessage = parse_json(line, "clippy")?;
+        let Some(diagnostic) = message
+            .message
+            .filter(|_| message.reason == "compiler-message")
+        else {
+            continue;
+        };
+        let severity = match diagnostic.level.as_str() {
+            "error" => FindingSeverity::Error,
+            "warning" => FindingSeverity::Warning,
+            _ => continue,
+        };
+        let Some(span) = diagnostic.spans.iter().find(|s| s.is_primary) else {
+            continue;
+        };
+        report.findings.push(ReportedFinding {
+            tool: "clippy".to_owned(),
+            rule: diagnostic.code.map(|c| c.code),
+            severity,
+            message: diagnostic.message,
+            path: span.file_name.clone(),
+            line: span.line_start,
+            end_line: Some(span.line_end).filter(|&end| end != span.line_start),
+        });
+    }
+    Ok(report)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintFile {
+    file_path: String,
+    #[serde(default)]
+    messages: Vec<EslintMessage>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EslintMessage {
+    rule_id: Option<String>,
+    severity: u8,
+    message: String,
+    line: Option<u32>,
+    end_line: Option<u32>,
+}
+
+/// Parse `eslint -f json` output.
+pub fn parse_eslint(input: &str) -> anyhow::Result<Report> {
+    let files: Vec<EslintFile> = parse_json(input, "ESLint")?;
+    let mut report = Report {
+        tools: BTreeSet::from(["eslint".to_owned()]),
+        findings: Vec::new(),
+    };
+    for file in files {
+        for message in file.messages {
+            let Some(line) = message.line else {
+                continue;
+            };
+            report.findings.push(ReportedFinding {
+                tool: "eslint".to_owned(),
+                rule: message.rule_id,
+                severity: if message.severity >= 2 {
+                    FindingSeverity::Error
+                } else {
+                    FindingSeverity::Warning
+                },
+                message: message.message,
+                path: file.file_path.clone(),
+                line,
+                end_line: message.end_line.filter(|&end| end != line),
+            });
+        }
+    }
+    Ok(report)
+}
+
+#[derive(Deserialize)]
+struct SemgrepOutput {
+    #[serde(default)]
+    results: Vec<SemgrepResult>,
+}
+
+#[derive(Deserialize)]
+struct SemgrepResult {
+    check_id: String,
+    path: String,
+    start: SemgrepPosition,
+    end: SemgrepPosition,
+    extra: SemgrepExtra,
+}
+
+#[derive(Deserialize)]
+struct SemgrepPosition {
+    line: u32,
+}
+
+#[derive(Deserialize)]
+struct SemgrepExtra {
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    severity: String,
+}
+
+/// Parse `semgrep --json` output.
+pub fn parse_semgrep(input: &str) -> anyhow::Result<Report> {
+    let output: SemgrepOutput = parse_json(input, "Semgrep")?;
+    let findings = output
+        .results
+        .into_iter()
+        .map(|result| ReportedFinding {
+            tool: "semgrep".to_owned(),
+            rule: Some(result.check_id),
+            severity: match result.extra.severity.to_ascii_uppercase().as_str() {
+                "ERROR" => FindingSeverity::Error,
+                "INFO" => FindingSeverity::Info,
+                _ => FindingSeverity::Warning,
+            },
+            message: result.extra.message,
+            path: result.path,
+            line: result.start.line,
+            end_line: Some(result.end.line).filter(|&end| end != result.start.line),
+        })
+        .collect();
+    Ok(Report {
+        tools: BTreeSet::from(["semgrep".to_owned()]),
+        findings,
+    })
+}
+
+/// A report format [`ReportFormat::parse`] understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Sarif,
+    Clippy,
+    Eslint,
+    Semgrep,
+}
+
+impl ReportFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportFormat::Sarif => "sarif",
+            ReportFormat::Clippy => "clippy",
+            ReportFormat::Eslint => "eslint",
+            ReportFormat::Semgrep => "semgrep",
+        }
+    }
+
+    /// Parse a report in this format.
+    pub fn parse(self, input: &str) -> anyhow::Result<Report> {
+        match self {
+            ReportFormat::Sarif => parse_sarif(input),
+            ReportFormat::Clippy => parse_clippy(input),
+            ReportFormat::Eslint => parse_eslint(input),
+            ReportFormat::Semgrep => parse_semgrep(input),
+        }
+    }
+}
+
+/// Map a reported path onto a file in the diff: strip a `file://` scheme,
+/// the repo's own path and a leading `./`, then take the exact match, or else
+/// the one diff file the path is a suffix of (tools run from a
+/// subdirectory report paths relative to it).
+fn diff_file_for<'a>(path: &str, repo_path: &Path, files: &'a BTreeSet<&str>) -> Option<&'a str> {
+    let path = path.strip_prefix("file://").unwrap_or(path);
+    let path = Path::new(path)
+        .strip_prefix(repo_path)
+        .map_or(path.to_owned(), |p| p.to_string_lossy().into_owned());
+    let path = path.strip_prefix("./").unwrap_or(&path);
+    if let Some(file) = files.get(path) {
+        return Some(file);
+    }
+    let suffix = format!("/{path}");
+    let mut candidates = files.iter().filter(|f| f.ends_with(&suffix));
+    match (candidates.next(), candidates.next()) {
+        (Some(file), None) => Some(file),
+        _ => None,
+    }
+}
+
+fn fingerprint(finding: &ReportedFinding, file_path: &str, line_text: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        finding.tool.as_str(),
+        finding.rule.as_deref().unwrap_or(""),
+        file_path,
+        finding.message.as_str(),
+        line_text.trim(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\0");
+    }
+    hex::encode(&hasher.finalize()[..8])
+}
+
+/// Turn `report`'s findings into [`Finding`]s on the hunks whose added lines
+/// they flag. Returns them, deduplicated, with the number that fell outside
+/// the diff.
+pub fn attach_findings(
+    report: &Report,
+    format: &str,
+    hunks: &[DiffHunk],
+    repo_path: &Path,
+    now: &str,
+) -> (Vec<Finding>, usize) {
+    let files: BTreeSet<&str> = hunks.iter().map(|h| h.file_path.as_str()).collect();
+    let mut seen = HashSet::new();
+    let mut findings = Vec::new();
+    let mut outside_diff = 0;
+    for reported in &report.findings {
+        let end = reported
+            .end_line
+            .unwrap_or(reported.line)
+            .max(reported.line);
+        let flagged = diff_file_for(&reported.path, repo_path, &files).and_then(|file| {
+            hunks
+                .iter()
+                .filter(|h| h.file_path == file)
+                .find_map(|hunk| {
+                    hunk.lines
+                        .iter()
+                        .filter(|l| l.line_type == LineType::Added)
+                        .find(|l| {
+                            l.new_line_number
+                                .is_some_and(|n| (reported.line..=end).contains(&n))
+                        })
+                        .map(|line| (hunk, line))
+                })
+        });
+        let Some((hunk, line)) = flagged else {
+            outside_diff += 1;
+            continue;
+        };
+        let id = fingerprint(reported, &hunk.file_path, &line.content);
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        findings.push(Finding {
+            id,
+            tool: reported.tool.clone(),
+            format: format.to_owned(),
+            rule: reported.rule.clone(),
+            severity: reported.severity,
+            message: reported.message.clone(),
+            file_path: hunk.file_path.clone(),
+            line: reported.line,
+            end_line: reported.end_line,
+            hunk_id: hunk.id.clone(),
+            ingested_at: now.to_owned(),
+        });
+    }
+    (findings, outside_diff)
+}
+
+/// What merging a run's findings changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IngestOutcome {
+    /// Findings not seen before.
+    pub added: usize,
+    /// Findings already recorded, refreshed from this run.
+    pub updated: usize,
+    /// Findings from the run's tools that this run no longer reports.
+    pub fixed: usize,
+}
+
+/// Merge `findings` from a run of `tools` into `state`: this run replaces
+/// whatever those tools reported before; other tools' findings are kept.
+pub fn merge_findings(
+    state: &mut ReviewState,
+    tools: &BTreeSet<String>,
+    findings: &[Finding],
+) -> IngestOutcome {
+    let previous: HashSet<&str> = state
+        .findings
+        .iter()
+        .filter(|f| tools.contains(&f.tool))
+        .map(|f| f.id.as_str())
+        .collect();
+    let current: HashSet<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+    let outcome = IngestOutcome {
+        added: current.difference(&previous).count(),
+        updated: current.intersection(&previous).count(),
+        fixed: previous.difference(&current).count(),
+    };
+    state.findings.retain(|f| !tools.contains(&f.tool));
+    state.findings.extend_from_slice(findings);
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::parse_diff;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,3 @@
+ fn a() {}
++fn b() { let x = 1; }
+ fn c() {}
+";
+
+    fn hunks() -> Vec<DiffHunk> {
+        parse_diff(DIFF, "src/lib.rs")
+    }
+
+    #[test]
+    fn parses_each_format() {
+        let sarif = r#"{"runs": [{"tool": {"driver": {"name": "CodeQL"}}, "results": [
+            {"ruleId": "js/xss", "level": "error", "message": {"text": "XSS"},
+             "locations": [{"physicalLocation": {"artifactLocation": {"uri": "src/a.js"},
+             "region": {"startLine": 3}}}]},
+            {"ruleId": "no-location", "message": {"text": "skipped"}}]},
+            {"tool": {"driver": {"name": "clean"}}}]}"#;
+        let report = parse_sarif(sarif).unwrap();
+        assert_eq!(
+            report.tools,
+            BTreeSet::from(["CodeQL".to_owned(), "clean".to_owned()])
+        );
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].severity, FindingSeverity::Error);
+
+        let clippy = concat!(
+            r#"{"reason": "compiler-artifact"}"#,
+            "\n",
+            r#"{"reason": "compiler-message", "message": {"message": "unused variable", "level": "warning", "code": {"code": "unused_variables"}, "spans": [{"file_name": "src/lib.rs", "line_start": 2, "line_end": 2, "is_primary": true}]}}"#,
+            "\n",
+            r#"{"reason": "compiler-message", "message": {"message": "1 warning emitted", "level": "warning", "code": null, "spans": []}}"#,
+        );
+        let report = parse_clippy(clippy).unwrap();
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].rule.as_deref(), Some("unused_variables"));
+
+        let eslint = r#"[{"filePath": "/repo/src/a.js", "messages": [
+            {"ruleId": "no-undef", "severity": 2, "message": "x is not defined", "line": 4, "endLine": 4}]}]"#;
+        let report = parse_eslint(eslint).unwrap();
+        assert_eq!(report.findings[0].severity, FindingSeverity::Error);
+        assert_eq!(report.findings[0].end_line, None);
+
+        let semgrep = r#"{"results": [{"check_id": "python.eval", "path": "a.py",
+            "start": {"line": 1}, "end": {"line": 2}, "extra": {"message": "eval", "severity": "INFO"}}]}"#;
+        let report = parse_semgrep(semgrep).unwrap();
+        assert_eq!(report.findings[0].severity, FindingSeverity::Info);
+        assert_eq!(report.findings[0].end_line, Some(2));
+
+        assert!(parse_eslint("not json").is_err());
+    }
+
+    #[test]
+    fn attaches_findings_on_added_lines_only() {
+        let finding = |path: &str, line: u32| ReportedFinding {
+            tool: "clippy".to_owned(),
+            rule: Some("unused_variables".to_owned()),
+            severity: FindingSeverity::Warning,
+            message: "unused variable: `x`".to_owned(),
+            path: path.to_owned(),
+            line,
+            end_line: None,
+        };
+        let report = Report {
+            tools: BTreeSet::from(["clippy".to_owned()]),
+            findings: vec![
+                finding("/repo/src/lib.rs", 2),
+                finding("./src/lib.rs", 2),
+                finding("src/lib.rs", 1),
+                finding("lib.rs", 2),
+                finding("src/other.rs", 2),
+            ],
+        };
+        let hunks = hunks();
+        let (findings, outside) =
+            attach_findings(&report, "clippy", &hunks, Path::new("/repo"), "now");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].hunk_id, hunks[0].id);
+        assert_eq!(findings[0].file_path, "src/lib.rs");
+        // Line 1 is context, and src/other.rs isn't in the diff.
+        assert_eq!(outside, 2);
+    }
+
+    #[test]
+    fn merging_a_run_replaces_the_tools_previous_findings() {
+        let hunks = hunks();
+        let reported = |message: &str| ReportedFinding {
+            tool: "semgrep".to_owned(),
+            rule: None,
+            severity: FindingSeverity::Warning,
+            message: message.to_owned(),
+            path: "src/lib.rs".to_owned(),
+            line: 2,
+            end_line: None,
+        };
+        let run = |messages: &[&str]| {
+            let report = Report {
+                tools: BTreeSet::from(["semgrep".to_owned()]),
+                findings: messages.iter().map(|m| reported(m)).collect(),
+            };
+            attach_findings(&report, "semgrep", &hunks, Path::new("/repo"), "now").0
+        };
+        let tools = BTreeSet::from(["semgrep".to_owned()]);
+        let mut state = ReviewState::new("feature", None);
+        let mut other = run(&["from another tool"]).remove(0);
+        other.tool = "eslint".to_owned();
+        state.findings.push(other);
+
+        let outcome = merge_findings(&mut state, &tools, &run(&["one", "two"]));
+        assert_eq!(outcome.added, 2);
+        let outcome = merge_findings(&mut state, &tools, &run(&["two", "three"]));
+        assert_eq!(
+            outcome,
+            IngestOutcome {
+                added: 1,
+                updated: 1,
+                fixed: 1
+            }
+        );
+        assert_eq!(state.findings.len(), 3);
+        assert!(state.findings.iter().any(|f| f.tool == "eslint"));
+    }
+}
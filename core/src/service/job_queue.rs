@@ -0,0 +1,289 @@
+//! Remotely triggered jobs that survive a restart.
+//!
+//! [`submit`] records a [`JobRequest`] in `~/.review/job-queue.json` before
+//! queueing it with [`jobs::submit`], and drops the record once the job
+//! finishes, whether it succeeded or failed. [`resume`], called when the
+//! server starts, queues again whatever an earlier process left there — jobs
+//! it was still waiting on or running when it stopped.
+//!
+//! A request equal to one already pending is not queued twice: [`submit`]
+//! returns the pending job's id instead. Each start of a job is counted in
+//! its record, so one that keeps taking the process down is given up after
+//! [`MAX_ATTEMPTS`] rather than resumed forever.
+
+use anyhow::Context;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use super::jobs::{self, JobKind};
+use super::prewarm::{prewarm_review, reclassify_review, ClassifyScope};
+use crate::review::central;
+use crate::review::state::now_iso8601;
+
+/// Filename of the queue in the central root.
+pub const JOB_QUEUE_FILE: &str = "job-queue.json";
+
+/// Starts after which an unfinished job is dropped instead of resumed.
+pub const MAX_ATTEMPTS: u32 = 3;
+
+/// Work that can be started remotely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobOperation {
+    /// Static rules, then `model` (if given) for the hunks they leave
+    /// unlabeled; labels are saved to the review.
+    Classify,
+    /// `model` (default sonnet) on just the hunks `scope` selects, replacing
+    /// their labels and keeping everyone else's.
+    Reclassify,
+}
+
+impl JobOperation {
+    /// The scheduler kind its jobs run as.
+    pub fn kind(self) -> JobKind {
+        match self {
+            JobOperation::Classify | JobOperation::Reclassify => JobKind::Classification,
+        }
+    }
+}
+
+/// A job as triggered, with everything needed to run it again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobRequest {
+    pub repo_path: String,
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub operation: JobOperation,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub scope: ClassifyScope,
+}
+
+impl JobRequest {
+    fn label(&self) -> String {
+        match self.operation {
+            JobOperation::Classify => format!("Classify {}", self.ref_name),
+            JobOperation::Reclassify => format!("Re-classify {}", self.ref_name),
+        }
+    }
+
+    fn run(&self) -> anyhow::Result<Value> {
+        let repo_path = Path::new(&self.repo_path);
+        let result = match self.operation {
+            JobOperation::Classify => serde_json::to_value(prewarm_review(
+                repo_path,
+                &self.ref_name,
+                self.model.as_deref(),
+            )?)?,
+            JobOperation::Reclassify => serde_json::to_value(reclassify_review(
+                repo_path,
+                &self.ref_name,
+                self.model.as_deref().unwrap_or("sonnet"),
+                &self.scope,
+            )?)?,
+        };
+        Ok(result)
+    }
+}
+
+/// A request recorded in the queue file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingJob {
+    pub request: JobRequest,
+    pub queued_at: String,
+    /// How many times a process has started it.
+    #[serde(default)]
+    pub attempts: u32,
+}
+
+/// Requests queued by this process, with their job ids. Held while the file
+/// is read and written, so records and ids change together.
+static QUEUED: LazyLock<Mutex<Vec<(u64, JobRequest)>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+fn queue_path() -> anyhow::Result<PathBuf> {
+    Ok(central::get_central_root()?.join(JOB_QUEUE_FILE))
+}
+
+/// Load the queue; missing or unreadable means nothing pending.
+pub fn load_pending() -> anyhow::Result<Vec<PendingJob>> {
+    let path = queue_path()?;
+    let Ok(json) = fs::read_to_string(&path) else {
+        return Ok(Vec::new());
+    };
+    Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!("[job_queue] ignoring corrupt {}: {e}", path.display());
+        Vec::new()
+    }))
+}
+
+fn save_pending(pending: &[PendingJob]) -> anyhow::Result<()> {
+    let path = queue_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(pending)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Apply `update` to the queue file, logging rather than failing: the job
+/// runs either way, it just may not survive a restart.
+fn update_pending(update: impl FnOnce(&mut Vec<PendingJob>)) {
+    let result = load_pending().and_then(|mut pending| {
+        update(&mut pending);
+        save_pending(&pending)
+    });
+    if let Err(e) = result {
+        warn!("[job_queue] failed to update the job queue: {e:#}");
+    }
+}
+
+/// Queue `request`, recorded so it survives a restart, and return its job
+/// id — or the id of an equal request that is already pending.
+pub fn submit(request: JobRequest) -> u64 {
+    let mut queued = QUEUED.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some((id, _)) = queued.iter().find(|(_, r)| *r == request) {
+        return *id;
+    }
+    update_pending(|pending| {
+        if !pending.iter().any(|job| job.request == request) {
+            pending.push(PendingJob {
+                request: request.clone(),
+                queued_at: now_iso8601(),
+                attempts: 0,
+            });
+        }
+    });
+    let id = queue(request.clone());
+    queued.push((id, request));
+    id
+}
+
+/// Hand `request` to the scheduler. Its record is updated as it starts and
+/// removed when it finishes.
+fn queue(request: JobRequest) -> u64 {
+    jobs::submit(request.operation.kind(), request.label(), move || {
+        {
+            let _queued = QUEUED.lock().unwrap_or_else(PoisonError::into_inner);
+            update_pending(|pending| {
+                if let Some(job) = pending.iter_mut().find(|job| job.request == request) {
+                    job.attempts += 1;
+                }
+            });
+        }
+        let outcome = request.run();
+        let mut queued = QUEUED.lock().unwrap_or_else(PoisonError::into_inner);
+        queued.retain(|(_, r)| *r != request);
+        update_pending(|pending| pending.retain(|job| job.request != request));
+        outcome
+    })
+}
+
+/// Split the recorded jobs into those to resume and those out of attempts,
+/// dropping repeats and the exhausted ones from the file.
+fn take_resumable() -> Vec<JobRequest> {
+    let mut resumable: Vec<JobRequest> = Vec::new();
+    update_pending(|pending| {
+        pending.retain(|job| {
+            if resumable.contains(&job.request) {
+                return false;
+            }
+            if job.attempts >= MAX_ATTEMPTS {
+                warn!(
+                    "[job_queue] giving up on \"{}\" after {} attempts",
+                    job.request.label(),
+                    job.attempts
+                );
+                return false;
+            }
+            resumable.push(job.request.clone());
+            true
+        });
+    });
+    resumable
+}
+
+/// Queue every job a previous process left unfinished. Returns their ids.
+pub fn resume() -> Vec<u64> {
+    let mut queued = QUEUED.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut ids = Vec::new();
+    for request in take_resumable() {
+        if queued.iter().any(|(_, r)| *r == request) {
+            continue;
+        }
+        let id = queue(request.clone());
+        queued.push((id, request));
+        ids.push(id);
+    }
+    if !ids.is_empty() {
+        info!("[job_queue] resumed {} unfinished job(s)", ids.len());
+    }
+    ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+
+    fn request(ref_name: &str) -> JobRequest {
+        JobRequest {
+            repo_path: "/nonexistent".to_owned(),
+            ref_name: ref_name.to_owned(),
+            operation: JobOperation::Classify,
+            model: None,
+            scope: ClassifyScope::default(),
+        }
+    }
+
+    fn pending(ref_name: &str, attempts: u32) -> PendingJob {
+        PendingJob {
+            request: request(ref_name),
+            queued_at: String::new(),
+            attempts,
+        }
+    }
+
+    #[test]
+    fn resumes_unfinished_jobs_once_and_drops_exhausted_ones() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, _repo) = setup_test();
+        save_pending(&[
+            pending("a", 1),
+            pending("a", 0),
+            pending("b", MAX_ATTEMPTS),
+            pending("c", 0),
+        ])
+        .unwrap();
+
+        assert_eq!(take_resumable(), [request("a"), request("c")]);
+        let left: Vec<String> = load_pending()
+            .unwrap()
+            .into_iter()
+            .map(|job| job.request.ref_name)
+            .collect();
+        assert_eq!(left, ["a", "c"]);
+    }
+
+    #[test]
+    fn request_round_trips_in_the_trigger_shape() {
+        let json = serde_json::json!({
+            "repoPath": "/repo",
+            "ref": "feature",
+            "operation": "reclassify",
+            "scope": { "labels": ["imports:*"] },
+        });
+        let parsed: JobRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.operation, JobOperation::Reclassify);
+        assert_eq!(parsed.scope.labels, ["imports:*"]);
+        let again: JobRequest =
+            serde_json::from_value(serde_json::to_value(&parsed).unwrap()).unwrap();
+        assert_eq!(again, parsed);
+    }
+}
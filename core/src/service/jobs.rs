@@ -0,0 +1,483 @@
+//! Central job scheduler for background and interactive work.
+//!
+//! Expensive operations — classification, symbol extraction, freshness checks
+//! — run through [`run`], which admits at most [`max_parallel`] jobs at once
+//! and, when slots are contended, starts interactive jobs (the user is waiting
+//! on them) before background ones. Queued and running jobs are visible via
+//! [`snapshot`] for the "background tasks" panel.
+//!
+//! Work triggered remotely goes through [`submit`] instead, which queues the
+//! job and returns its id at once; [`triggered_job`] reports its status and,
+//! once finished, its result or error. The server's remote triggers go
+//! through [`crate::service::job_queue`], which records them so they resume
+//! after a restart.
+//!
+//! Fan-out *within* a job goes through [`parallel_map`], which bounds its
+//! worker threads by the same limit instead of spawning one thread per item.
+//!
+//! The limit defaults to the machine's available parallelism, can be set with
+//! `REVIEW_MAX_JOBS`, and can be changed at runtime with [`set_max_parallel`].
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::sync::{Condvar, LazyLock, Mutex, PoisonError};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::review::state::now_iso8601;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobPriority {
+    /// Nobody is waiting on it (watcher-driven refreshes, sidebar freshness).
+    Background,
+    /// Backs something the user is looking at right now.
+    Interactive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobKind {
+    Classification,
+    Symbols,
+    Freshness,
+}
+
+/// A queued or running job, as shown in the background tasks panel.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobInfo {
+    pub id: u64,
+    pub kind: JobKind,
+    pub priority: JobPriority,
+    pub label: String,
+    pub queued_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+}
+
+/// Point-in-time view of the scheduler. `queued` is in start order.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobQueueSnapshot {
+    pub max_parallel: usize,
+    pub running: Vec<JobInfo>,
+    pub queued: Vec<JobInfo>,
+}
+
+/// Where a [`submit`]ted job is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed)
+    }
+}
+
+/// A job started with [`submit`], kept after it finishes so its result can
+/// be fetched.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggeredJob {
+    pub id: u64,
+    pub kind: JobKind,
+    pub label: String,
+    pub status: JobStatus,
+    pub queued_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Finished triggered jobs kept for status lookups; older ones are dropped.
+const KEEP_TRIGGERED: usize = 100;
+
+static TRIGGERED: LazyLock<Mutex<VecDeque<TriggeredJob>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::new()));
+
+struct SchedulerState {
+    max_parallel: usize,
+    next_id: u64,
+    running: Vec<JobInfo>,
+    queued: Vec<JobInfo>,
+}
+
+impl SchedulerState {
+    /// The queued job that should start next: highest priority, then FIFO.
+    fn next_up(&self) -> Option<u64> {
+        self.queued
+            .iter()
+            .max_by(|a, b| a.priority.cmp(&b.priority).then(b.id.cmp(&a.id)))
+            .map(|job| job.id)
+    }
+
+    fn sorted_queue(&self) -> Vec<JobInfo> {
+        let mut queued = self.queued.clone();
+        queued.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.id.cmp(&b.id)));
+        queued
+    }
+}
+
+struct Scheduler {
+    state: Mutex<SchedulerState>,
+    changed: Condvar,
+}
+
+static SCHEDULER: LazyLock<Scheduler> = LazyLock::new(|| Scheduler {
+    state: Mutex::new(SchedulerState {
+        max_parallel: default_max_parallel(),
+        next_id: 1,
+        running: Vec::new(),
+        queued: Vec::new(),
+    }),
+    changed: Condvar::new(),
+});
+
+thread_local! {
+    /// Set while this thread is running a job, so a job that calls into
+    /// another job-wrapped function runs it inline instead of queueing behind
+    /// itself.
+    static IN_JOB: Cell<bool> = const { Cell::new(false) };
+}
+
+fn default_max_parallel() -> usize {
+    std::env::var("REVIEW_MAX_JOBS")
+        .ok()
+        .and_then(|v| v.trim().parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(4, usize::from))
+}
+
+fn lock_state() -> std::sync::MutexGuard<'static, SchedulerState> {
+    SCHEDULER
+        .state
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Current parallelism limit.
+pub fn max_parallel() -> usize {
+    lock_state().max_parallel
+}
+
+/// Change the parallelism limit (clamped to at least 1). Raising it starts
+/// queued jobs immediately; lowering it lets running jobs finish.
+pub fn set_max_parallel(n: usize) -> usize {
+    let n = n.max(1);
+    lock_state().max_parallel = n;
+    SCHEDULER.changed.notify_all();
+    n
+}
+
+/// Queued and running jobs.
+pub fn snapshot() -> JobQueueSnapshot {
+    let state = lock_state();
+    JobQueueSnapshot {
+        max_parallel: state.max_parallel,
+        running: state.running.clone(),
+        queued: state.sorted_queue(),
+    }
+}
+
+/// Removes a finished (or panicked) job from the running set.
+struct RunningGuard(u64);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        IN_JOB.with(|flag| flag.set(false));
+        lock_state().running.retain(|job| job.id != self.0);
+        SCHEDULER.changed.notify_all();
+    }
+}
+
+/// Add a job to the queue. Returns its id.
+fn enqueue(kind: JobKind, priority: JobPriority, label: String) -> u64 {
+    let mut state = lock_state();
+    let id = state.next_id;
+    state.next_id += 1;
+    state.queued.push(JobInfo {
+        id,
+        kind,
+        priority,
+        label,
+        queued_at: now_iso8601(),
+        started_at: None,
+    });
+    id
+}
+
+/// Block until queued job `id` may start, then move it to the running set.
+/// The guard removes it again when dropped.
+fn start_when_admitted(id: u64) -> RunningGuard {
+    let mut state = lock_state();
+    while state.running.len() >= state.max_parallel || state.next_up() != Some(id) {
+        state = SCHEDULER
+            .changed
+            .wait(state)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+    let pos = state
+        .queued
+        .iter()
+        .position(|job| job.id == id)
+        .expect("queued job vanished");
+    let mut job = state.queued.remove(pos);
+    job.started_at = Some(now_iso8601());
+    state.running.push(job);
+    drop(state);
+    // Another queued job may be admissible too (limit > running).
+    SCHEDULER.changed.notify_all();
+
+    IN_JOB.with(|flag| flag.set(true));
+    RunningGuard(id)
+}
+
+/// Run `f` as a scheduled job, blocking until a slot is free. Nested calls
+/// from inside a running job execute inline.
+pub fn run<T>(
+    kind: JobKind,
+    priority: JobPriority,
+    label: impl Into<String>,
+    f: impl FnOnce() -> T,
+) -> T {
+    if IN_JOB.with(Cell::get) {
+        return f();
+    }
+
+    let id = enqueue(kind, priority, label.into());
+    let _guard = start_when_admitted(id);
+    f()
+}
+
+fn lock_triggered() -> std::sync::MutexGuard<'static, VecDeque<TriggeredJob>> {
+    TRIGGERED.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn update_triggered(id: u64, update: impl FnOnce(&mut TriggeredJob)) {
+    if let Some(job) = lock_triggered().iter_mut().find(|job| job.id == id) {
+        update(job);
+    }
+}
+
+/// Queue `f` as an interactive job on its own thread and return its id
+/// without waiting. Its outcome is kept for [`triggered_job`].
+pub fn submit<T, F>(kind: JobKind, label: impl Into<String>, f: F) -> u64
+where
+    T: Serialize,
+    F: FnOnce() -> anyhow::Result<T> + Send + 'static,
+{
+    let label = label.into();
+    let id = enqueue(kind, JobPriority::Interactive, label.clone());
+    {
+        let mut triggered = lock_triggered();
+        triggered.push_back(TriggeredJob {
+            id,
+            kind,
+            label,
+            status: JobStatus::Queued,
+            queued_at: now_iso8601(),
+            finished_at: None,
+            result: None,
+            error: None,
+        });
+        // Drop the oldest finished jobs; unfinished ones are always kept.
+        while triggered.len() > KEEP_TRIGGERED {
+            match triggered.iter().position(|job| job.status.is_finished()) {
+                Some(pos) => triggered.remove(pos),
+                None => break,
+            };
+        }
+    }
+
+    std::thread::spawn(move || {
+        let outcome = {
+            let _guard = start_when_admitted(id);
+            update_triggered(id, |job| job.status = JobStatus::Running);
+            f().and_then(|value| Ok(serde_json::to_value(value)?))
+        };
+        update_triggered(id, |job| {
+            job.finished_at = Some(now_iso8601());
+            match outcome {
+                Ok(value) => {
+                    job.status = JobStatus::Succeeded;
+                    job.result = Some(value);
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(format!("{e:#}"));
+                }
+            }
+        });
+    });
+    id
+}
+
+/// A [`submit`]ted job's current status, if it is still known.
+pub fn triggered_job(id: u64) -> Option<TriggeredJob> {
+    lock_triggered().iter().find(|job| job.id == id).cloned()
+}
+
+/// Async wrapper: run `f` as a scheduled job on the blocking thread pool.
+pub async fn run_blocking<T, F>(
+    kind: JobKind,
+    priority: JobPriority,
+    label: impl Into<String>,
+    f: F,
+) -> Result<T, tokio::task::JoinError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let label = label.into();
+    tokio::task::spawn_blocking(move || run(kind, priority, label, f)).await
+}
+
+/// Map `f` over `items` on at most [`max_parallel`] worker threads, keeping
+/// input order. Items whose worker panics are dropped. Workers count as part
+/// of the caller's job, if it's in one, so their nested jobs run inline.
+pub fn parallel_map<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let workers = max_parallel().min(items.len());
+    if workers <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+    let in_job = IN_JOB.with(Cell::get);
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let mut results: Vec<(usize, R)> = std::thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                s.spawn(|| {
+                    IN_JOB.with(|flag| flag.set(in_job));
+                    let mut out = Vec::new();
+                    loop {
+                        let next = queue.lock().unwrap_or_else(PoisonError::into_inner).next();
+                        let Some((index, item)) = next else {
+                            break;
+                        };
+                        out.push((index, f(item)));
+                    }
+                    out
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|h| h.join().ok())
+            .flatten()
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn parallel_map_keeps_order_and_bounds_workers() {
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let out = parallel_map((0..64).collect(), |n: u32| {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            active.fetch_sub(1, Ordering::SeqCst);
+            n * 2
+        });
+        assert_eq!(out, (0..64).map(|n| n * 2).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= max_parallel());
+    }
+
+    #[test]
+    fn parallel_map_workers_inherit_the_callers_job() {
+        let in_job = |()| IN_JOB.with(Cell::get);
+        assert!(parallel_map(vec![(); 8], in_job).iter().all(|&v| !v));
+        let nested = run(
+            JobKind::Freshness,
+            JobPriority::Interactive,
+            "outer",
+            || parallel_map(vec![(); 8], in_job),
+        );
+        assert!(nested.iter().all(|&v| v));
+    }
+
+    #[test]
+    fn queue_orders_interactive_before_background() {
+        let state = SchedulerState {
+            max_parallel: 1,
+            next_id: 4,
+            running: Vec::new(),
+            queued: [
+                (1, JobPriority::Background),
+                (2, JobPriority::Interactive),
+                (3, JobPriority::Interactive),
+            ]
+            .into_iter()
+            .map(|(id, priority)| JobInfo {
+                id,
+                kind: JobKind::Freshness,
+                priority,
+                label: String::new(),
+                queued_at: String::new(),
+                started_at: None,
+            })
+            .collect(),
+        };
+        assert_eq!(state.next_up(), Some(2));
+        let order: Vec<u64> = state.sorted_queue().iter().map(|job| job.id).collect();
+        assert_eq!(order, [2, 3, 1]);
+    }
+
+    #[test]
+    fn nested_run_executes_inline() {
+        let value = run(JobKind::Symbols, JobPriority::Interactive, "outer", || {
+            run(JobKind::Symbols, JobPriority::Interactive, "inner", || 7)
+        });
+        assert_eq!(value, 7);
+        assert!(snapshot().running.iter().all(|job| job.label != "outer"));
+    }
+
+    #[test]
+    fn submitted_jobs_report_their_outcome() {
+        let wait_for = |id: u64| loop {
+            let job = triggered_job(id).unwrap();
+            if job.status.is_finished() {
+                return job;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        };
+
+        let ok = submit(JobKind::Classification, "ok", || Ok(vec![1, 2]));
+        let failed = submit(
+            JobKind::Classification,
+            "failed",
+            || -> anyhow::Result<()> { anyhow::bail!("no diff") },
+        );
+        assert_ne!(ok, failed);
+
+        let ok = wait_for(ok);
+        assert_eq!(ok.status, JobStatus::Succeeded);
+        assert_eq!(ok.result, Some(serde_json::json!([1, 2])));
+        let failed = wait_for(failed);
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(failed.error.as_deref(), Some("no diff"));
+        assert!(triggered_job(u64::MAX).is_none());
+    }
+}
@@ -0,0 +1,117 @@
+//! Run the repo's configured linters (`[[lint]]` in `.review.toml`) and keep
+//! only what they flag on the review's changed lines.
+//!
+//! Linters run in the checkout of the review's head — where the project's
+//! dependencies and build cache live — so a review of a branch that isn't
+//! checked out can't be linted. Their reports are parsed and mapped onto
+//! hunks by [`super::ingest`], which drops diagnostics on lines the review
+//! didn't add or modify: pre-existing warnings never reach the review.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use log::info;
+
+use crate::review::repo_config::{LinterConfig, RepoConfig};
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::Comparison;
+
+use super::ingest::Report;
+
+/// The repo's configured linters; with `only`, just those named.
+pub fn configured_linters(repo_path: &Path, only: &[String]) -> anyhow::Result<Vec<LinterConfig>> {
+    let linters = RepoConfig::load(repo_path)
+        .map(|c| c.lint)
+        .unwrap_or_default();
+    if linters.is_empty() {
+        bail!("No linters configured; add a [[lint]] table to .review.toml");
+    }
+    if let Some(unknown) = only.iter().find(|n| !linters.iter().any(|l| &l.name == *n)) {
+        bail!("No linter named {unknown:?} in .review.toml");
+    }
+    Ok(linters
+        .into_iter()
+        .filter(|l| only.is_empty() || only.contains(&l.name))
+        .collect())
+}
+
+/// Where to run linters for `comparison`: the checkout of its head.
+pub fn lint_dir(repo_path: &Path, comparison: &Comparison) -> anyhow::Result<PathBuf> {
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    match source.working_tree_dir(comparison) {
+        Some(dir) => Ok(dir),
+        None => bail!(
+            "{} isn't checked out; linters run in the checkout of the review's head",
+            comparison.head
+        ),
+    }
+}
+
+/// Run `linter` in `dir` and parse its report. Linters exit non-zero when
+/// they find problems, so the exit status only matters when nothing was
+/// printed. Findings are recorded under the linter's configured name.
+pub fn run_linter(dir: &Path, linter: &LinterConfig) -> anyhow::Result<Report> {
+    info!("[run_linter] {}: {}", linter.name, linter.command);
+    let output = Command::new("sh")
+        .args(["-c", &linter.command])
+        .current_dir(dir)
+        .output()
+        .with_context(|| format!("Failed to run {:?}", linter.command))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if stdout.trim().is_empty() && !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "{} {} without a report{}",
+            linter.name,
+            output.status,
+            if stderr.trim().is_empty() {
+                String::new()
+            } else {
+                format!(": {}", stderr.trim())
+            }
+        );
+    }
+    let mut report = linter
+        .format
+        .parse(&stdout)
+        .with_context(|| format!("{} printed an unreadable report", linter.name))?;
+    report.tools = BTreeSet::from([linter.name.clone()]);
+    for finding in &mut report.findings {
+        finding.tool.clone_from(&linter.name);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::ingest::ReportFormat;
+
+    fn linter(command: &str) -> LinterConfig {
+        LinterConfig {
+            name: "custom-eslint".to_owned(),
+            command: command.to_owned(),
+            format: ReportFormat::Eslint,
+        }
+    }
+
+    #[test]
+    fn records_findings_under_the_linter_name_despite_a_failing_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = run_linter(
+            dir.path(),
+            &linter(
+                r#"echo '[{"filePath": "a.js", "messages": [{"ruleId": "semi", "severity": 1, "message": "Missing semicolon", "line": 2}]}]'; exit 1"#,
+            ),
+        )
+        .unwrap();
+        assert_eq!(report.tools, BTreeSet::from(["custom-eslint".to_owned()]));
+        assert_eq!(report.findings[0].tool, "custom-eslint");
+
+        let err = run_linter(dir.path(), &linter("echo broken >&2; exit 2")).unwrap_err();
+        assert!(err.to_string().contains("broken"));
+        assert!(run_linter(dir.path(), &linter("echo not-json")).is_err());
+    }
+}
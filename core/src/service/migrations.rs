@@ -0,0 +1,58 @@
+//! Pairing and data-loss checks for changed database migrations.
+
+use anyhow::Context;
+use log::info;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::classify::migrations::{check_migration, migration_format, MigrationCheck};
+use crate::diff::parser::parse_diff;
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::{Comparison, DiffSource};
+
+/// Check the migrations among `file_paths`. A `*.down.sql` counterpart counts
+/// as present if it's also changed or exists on the comparison's head side.
+/// Deleted migrations are skipped.
+pub fn get_migration_checks(
+    repo_path: &Path,
+    file_paths: &[String],
+    comparison: &Comparison,
+) -> anyhow::Result<Vec<MigrationCheck>> {
+    let t0 = Instant::now();
+    let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
+    let content_root = source.working_tree_dir(comparison);
+    let read_head = |file_path: &str| match &content_root {
+        Some(dir) => std::fs::read_to_string(dir.join(file_path)).ok(),
+        None => source
+            .get_file_bytes(file_path, &comparison.head)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok()),
+    };
+    let exists = |path: &str| {
+        file_paths.iter().any(|p| p == path)
+            || match &content_root {
+                Some(dir) => dir.join(path).exists(),
+                None => source.get_file_bytes(path, &comparison.head).is_ok(),
+            }
+    };
+
+    let mut checks = Vec::new();
+    for file_path in file_paths.iter().filter(|p| migration_format(p).is_some()) {
+        let Some(content) = read_head(file_path) else {
+            continue;
+        };
+        let diff = source
+            .get_diff(comparison, Some(file_path))
+            .context("Failed to get diff")?;
+        let hunks = parse_diff(&diff, file_path);
+        checks.extend(check_migration(file_path, &content, &hunks, exists));
+    }
+
+    info!(
+        "[get_migration_checks] {} migrations among {} files in {:?}",
+        checks.len(),
+        file_paths.len(),
+        t0.elapsed()
+    );
+    Ok(checks)
+}
@@ -6,16 +6,50 @@
 
 pub mod activity;
 pub mod activity_cache;
+pub mod attestation;
+pub mod bench;
+pub mod bisect;
+pub mod breakdown;
+pub mod build_size;
+pub mod checklist;
 pub mod commit;
+pub mod companion;
+pub mod digest;
+pub mod explain;
+pub mod export;
 pub mod files;
 pub mod freshness;
+pub mod ingest;
+pub mod job_queue;
+pub mod jobs;
+pub mod linters;
+pub mod migrations;
+pub mod onboarding;
+pub mod pairing;
+pub mod pr_review;
+pub mod pr_status;
+pub mod pr_sync;
+pub mod prewarm;
 pub mod review_io;
+pub mod schemas;
+pub mod semgrep;
+pub mod server_port;
+pub mod settings;
+pub mod shortstats;
+pub mod split;
+pub mod staging;
+pub mod submodules;
+pub mod support_bundle;
 pub mod symbols;
+pub mod tailscale;
 pub mod targets;
+pub mod timeline;
+pub mod two_person;
 pub mod util;
 pub mod vscode;
 pub mod watcher_events;
 
+use crate::diff::encoding::TextEncoding;
 use crate::diff::parser::{DiffHunk, MovePair};
 use crate::symbols::Symbol;
 use serde::{Deserialize, Serialize};
@@ -33,6 +67,72 @@ pub struct FileContent {
     pub content_type: String,
     pub image_data_url: Option<String>,
     pub old_image_data_url: Option<String>,
+    /// Set when the file is over the per-file budget: `content` and
+    /// `oldContent` are left empty, to be read in windows with
+    /// [`files::get_file_content_window`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deferred: Option<DeferredContent>,
+    /// Set when a version of the file wasn't UTF-8: the encoding its content
+    /// was decoded from (see [`crate::diff::encoding`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<TextEncoding>,
+    /// The file is outside a sparse checkout's cone: not on disk, so
+    /// `content` was read from the index rather than the working tree.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub not_materialized: bool,
+}
+
+/// Sizes of a file whose content [`files::get_file_content`] left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeferredContent {
+    /// Bytes of the new side.
+    pub size: usize,
+    /// Bytes of the old side, when there is one.
+    pub old_size: Option<usize>,
+    /// The per-file budget they exceeded (`REVIEW_MAX_FILE_BYTES`).
+    pub limit: usize,
+}
+
+/// Which version of a file a content window reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentSide {
+    Old,
+    New,
+}
+
+/// A slice of a file: `count` lines from 0-based line `start`, or `length`
+/// bytes from `offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "unit")]
+pub enum ContentWindow {
+    Lines { start: usize, count: usize },
+    Bytes { offset: usize, length: usize },
+}
+
+/// One window of a file's content. `start`..`end` is the range actually
+/// returned, in the window's unit (byte windows are narrowed to UTF-8
+/// character boundaries), and `total` the file's length in that unit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileContentWindow {
+    pub content: String,
+    pub start: usize,
+    pub end: usize,
+    pub total: usize,
+    /// Bytes in the whole file.
+    pub size: usize,
+}
+
+/// Hunks loaded under a [`crate::limits::ReviewLimits`] budget, plus every
+/// limit that forced a degradation.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LimitedHunks {
+    pub hunks: Vec<DiffHunk>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub limits_exceeded: Vec<crate::limits::LimitExceeded>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -0,0 +1,457 @@
+//! Repository onboarding: scan a repo's tracked files for its languages,
+//! lockfiles, generated code, and which trust categories fit it, and turn
+//! that into a starter `.review.toml` (see [`crate::review::repo_config`]).
+//!
+//! Lockfiles aren't suggested for `ignore`: dependency bumps are worth
+//! seeing, and the `generated:lockfile` trust pattern already waves through
+//! the noise. Opt-in categories that fit the repo are reported, never added to
+//! the suggested trust list.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use anyhow::Context;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use serde::Serialize;
+
+use crate::classify::static_rules::LOCKFILE_NAMES;
+use crate::filters::should_skip_file;
+use crate::review::repo_config::REPO_CONFIG_PATH;
+use crate::symbols::extractor::get_language_for_file;
+use crate::trust::patterns::get_trust_taxonomy;
+
+/// Languages worth reporting: name, extensions, and the cargo feature that
+/// compiles in its symbol grammar (`None`: there is no grammar).
+const LANGUAGES: &[(&str, &[&str], Option<&str>)] = &[
+    ("Rust", &["rs"], Some("symbols-rust-lang")),
+    ("TypeScript", &["ts", "tsx"], Some("symbols-typescript")),
+    (
+        "JavaScript",
+        &["js", "jsx", "mjs", "cjs"],
+        Some("symbols-typescript"),
+    ),
+    ("Python", &["py", "pyi"], Some("symbols-python")),
+    ("Go", &["go"], Some("symbols-go")),
+    ("Ruby", &["rb"], Some("symbols-ruby")),
+    ("Java", &["java"], Some("symbols-java")),
+    ("C", &["c", "h"], Some("symbols-c")),
+    (
+        "C++",
+        &["cpp", "cc", "cxx", "hpp", "hxx", "hh"],
+        Some("symbols-cpp"),
+    ),
+    ("C#", &["cs"], Some("symbols-csharp")),
+    ("PHP", &["php"], Some("symbols-php")),
+    ("CSS", &["css"], Some("symbols-css")),
+    ("HTML", &["html", "htm"], Some("symbols-html")),
+    (
+        "Markdown",
+        &["md", "markdown", "mdx"],
+        Some("symbols-markdown"),
+    ),
+    ("Kotlin", &["kt", "kts"], None),
+    ("Swift", &["swift"], None),
+    ("Scala", &["scala"], None),
+    ("Shell", &["sh", "bash", "zsh"], None),
+    ("SQL", &["sql"], None),
+    ("Terraform", &["tf"], None),
+    ("Vue", &["vue"], None),
+    ("Svelte", &["svelte"], None),
+];
+
+/// Globs for vendored or generated code, suggested for `ignore` when tracked
+/// files match them.
+const GENERATED_GLOBS: &[&str] = &[
+    "vendor/",
+    "third_party/",
+    "dist/",
+    "build/",
+    "generated/",
+    "__generated__/",
+    "*.min.js",
+    "*.min.css",
+    "*.js.map",
+    "*.pb.go",
+    "*_pb2.py",
+    "*_pb2_grpc.py",
+    "*.g.dart",
+    "__snapshots__/",
+];
+
+/// Files that make an opt-in (or file-type specific) taxonomy category fit.
+const CATEGORY_SIGNALS: &[(&str, &[&str])] = &[
+    ("type-annotations", &["*.py", "*.pyi", "*.ts", "*.tsx"]),
+    ("generated", LOCKFILE_NAMES),
+    (
+        "ci",
+        &[
+            ".github/workflows/",
+            ".gitlab-ci.yml",
+            ".circleci/",
+            "Jenkinsfile",
+        ],
+    ),
+    (
+        "infra",
+        &[
+            "Dockerfile",
+            "Containerfile",
+            "*.tf",
+            "Chart.yaml",
+            "kustomization.yaml",
+        ],
+    ),
+    (
+        "i18n",
+        &["locales/", "i18n/", "translations/", "*.po", "*.xliff"],
+    ),
+    (
+        "api",
+        &["*.proto", "*.graphql", "openapi.yaml", "openapi.json"],
+    ),
+    ("sql", &["*.sql", "migrations/"]),
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageUsage {
+    pub language: String,
+    pub files: usize,
+    /// Cargo feature with the symbol grammar, if there is one.
+    pub symbols_feature: Option<String>,
+    /// Whether that grammar is compiled into this build.
+    pub symbols_enabled: bool,
+    /// Known language server for it, if any.
+    pub language_server: Option<String>,
+    pub language_server_installed: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedMatch {
+    pub glob: String,
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryFit {
+    pub category: String,
+    pub name: String,
+    pub applies: bool,
+    /// Whether new reviews leave it untrusted until opted into.
+    pub opt_in: bool,
+    /// The first tracked file that made it fit.
+    pub evidence: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoScan {
+    pub files: usize,
+    /// Most files first.
+    pub languages: Vec<LanguageUsage>,
+    pub lockfiles: Vec<String>,
+    pub generated: Vec<GeneratedMatch>,
+    pub categories: Vec<CategoryFit>,
+    pub suggested_ignore: Vec<String>,
+    pub suggested_trust: Vec<String>,
+}
+
+fn tracked_files(repo_path: &Path) -> anyhow::Result<Vec<String>> {
+    let output = Command::new("git")
+        .args(["ls-files", "-z"])
+        .current_dir(repo_path)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git ls-files failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
+
+fn matcher(globs: &[&str]) -> Gitignore {
+    let mut builder = GitignoreBuilder::new("");
+    for glob in globs {
+        let _ = builder.add_line(None, glob);
+    }
+    builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+fn matches(matcher: &Gitignore, path: &str) -> bool {
+    matcher.matched_path_or_any_parents(path, false).is_ignore()
+}
+
+fn extension(path: &str) -> Option<String> {
+    let name = path.rsplit('/').next()?;
+    let (stem, ext) = name.rsplit_once('.')?;
+    (!stem.is_empty()).then(|| ext.to_lowercase())
+}
+
+/// The language a file is written in, by extension.
+pub(crate) fn language_for_path(path: &str) -> Option<&'static str> {
+    let ext = extension(path)?;
+    LANGUAGES
+        .iter()
+        .find(|(_, exts, _)| exts.contains(&ext.as_str()))
+        .map(|(name, _, _)| *name)
+}
+
+/// Whether a file is a lockfile or looks vendored or generated.
+pub(crate) fn is_generated_path(path: &str) -> bool {
+    static GENERATED: LazyLock<Gitignore> = LazyLock::new(|| matcher(GENERATED_GLOBS));
+    let name = path.rsplit('/').next().unwrap_or(path);
+    LOCKFILE_NAMES.contains(&name) || matches(&GENERATED, path)
+}
+
+#[cfg(feature = "lsp")]
+fn language_server(ext: &str) -> Option<(String, bool)> {
+    use crate::lsp::registry::{command_exists, server_for_extension};
+    server_for_extension(ext).map(|server| (server.name.to_owned(), command_exists(server.command)))
+}
+
+#[cfg(not(feature = "lsp"))]
+fn language_server(_ext: &str) -> Option<(String, bool)> {
+    None
+}
+
+fn scan_languages(files: &[String]) -> Vec<LanguageUsage> {
+    let mut counts: BTreeMap<usize, usize> = BTreeMap::new();
+    for path in files {
+        let Some(ext) = extension(path) else { continue };
+        if let Some(index) = LANGUAGES
+            .iter()
+            .position(|(_, exts, _)| exts.contains(&ext.as_str()))
+        {
+            *counts.entry(index).or_default() += 1;
+        }
+    }
+
+    let mut languages: Vec<LanguageUsage> = counts
+        .into_iter()
+        .map(|(index, files)| {
+            let (name, exts, feature) = LANGUAGES[index];
+            let server = language_server(exts[0]);
+            LanguageUsage {
+                language: name.to_owned(),
+                files,
+                symbols_feature: feature.map(str::to_owned),
+                symbols_enabled: get_language_for_file(&format!("x.{}", exts[0])).is_some(),
+                language_server_installed: server.as_ref().is_some_and(|(_, found)| *found),
+                language_server: server.map(|(name, _)| name),
+            }
+        })
+        .collect();
+    languages.sort_by_key(|lang| std::cmp::Reverse(lang.files));
+    languages
+}
+
+/// Scan the repo at `repo_path` and suggest a `.review.toml` for it.
+pub fn scan_repo(repo_path: &Path) -> anyhow::Result<RepoScan> {
+    let files = tracked_files(repo_path)?;
+    let file_name = |path: &str| path.rsplit('/').next().unwrap_or(path).to_owned();
+
+    let mut lockfiles: Vec<String> = files
+        .iter()
+        .filter(|path| LOCKFILE_NAMES.contains(&file_name(path).as_str()))
+        .cloned()
+        .collect();
+    lockfiles.sort();
+
+    // Already skipped by the built-in filters: nothing to suggest.
+    let generated: Vec<GeneratedMatch> = GENERATED_GLOBS
+        .iter()
+        .filter_map(|glob| {
+            let matcher = matcher(&[glob]);
+            let count = files
+                .iter()
+                .filter(|path| matches(&matcher, path) && !should_skip_file(path))
+                .count();
+            (count > 0).then(|| GeneratedMatch {
+                glob: (*glob).to_owned(),
+                files: count,
+            })
+        })
+        .collect();
+
+    let signals: BTreeMap<&str, Gitignore> = CATEGORY_SIGNALS
+        .iter()
+        .map(|(category, globs)| (*category, matcher(globs)))
+        .collect();
+    let has_code = files.iter().any(|path| {
+        extension(path)
+            .is_some_and(|ext| LANGUAGES.iter().any(|(_, e, _)| e.contains(&ext.as_str())))
+    });
+    let taxonomy = get_trust_taxonomy();
+    let categories: Vec<CategoryFit> = taxonomy
+        .iter()
+        .map(|category| {
+            let evidence = match signals.get(category.id.as_str()) {
+                Some(signal) => files.iter().find(|path| matches(signal, path)).cloned(),
+                // Imports, comments, formatting, ...: any code has them.
+                None => has_code.then(String::new),
+            };
+            CategoryFit {
+                category: category.id.clone(),
+                name: category.name.clone(),
+                applies: evidence.is_some(),
+                opt_in: category.patterns.iter().all(|p| p.opt_in),
+                evidence: evidence.filter(|e| !e.is_empty()),
+            }
+        })
+        .collect();
+
+    let suggested_trust = taxonomy
+        .iter()
+        .zip(&categories)
+        .filter(|(_, fit)| fit.applies)
+        .flat_map(|(category, _)| {
+            let trusted: Vec<String> = category
+                .patterns
+                .iter()
+                .filter(|p| !p.opt_in)
+                .map(|p| p.id.clone())
+                .collect();
+            if !trusted.is_empty() && trusted.len() == category.patterns.len() {
+                vec![format!("{}:*", category.id)]
+            } else {
+                trusted
+            }
+        })
+        .collect();
+
+    Ok(RepoScan {
+        files: files.len(),
+        languages: scan_languages(&files),
+        lockfiles,
+        suggested_ignore: generated.iter().map(|g| g.glob.clone()).collect(),
+        generated,
+        categories,
+        suggested_trust,
+    })
+}
+
+fn toml_array(values: &[String]) -> String {
+    if values.is_empty() {
+        return "[]".to_owned();
+    }
+    let items: Vec<String> = values
+        .iter()
+        .map(|v| format!("  {},\n", toml::Value::String(v.clone())))
+        .collect();
+    format!("[\n{}]", items.concat())
+}
+
+/// A starter `.review.toml` from a scan.
+pub fn render_repo_config(scan: &RepoScan) -> String {
+    let opt_in: Vec<&str> = scan
+        .categories
+        .iter()
+        .filter(|fit| fit.applies && fit.opt_in)
+        .map(|fit| fit.category.as_str())
+        .collect();
+    let opt_in_note = if opt_in.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "# Opt-in categories that fit this repo, left untrusted: {}.\n",
+            opt_in.join(", ")
+        )
+    };
+    format!(
+        "# Review settings for this repository. Generated by `review init`; edit freely.\n\n\
+         # Changed files matching these gitignore-style globs are left out of reviews.\n\
+         ignore = {}\n\n\
+         # Trust patterns new reviews start with, instead of the built-in defaults.\n\
+         {opt_in_note}\
+         trust = {}\n",
+        toml_array(&scan.suggested_ignore),
+        toml_array(&scan.suggested_trust)
+    )
+}
+
+/// Write `.review.toml` into the repo root. Refuses to replace an existing
+/// one unless `force`.
+pub fn write_repo_config(
+    repo_path: &Path,
+    scan: &RepoScan,
+    force: bool,
+) -> anyhow::Result<PathBuf> {
+    let path = repo_path.join(REPO_CONFIG_PATH);
+    if path.exists() && !force {
+        anyhow::bail!(
+            "{} already exists; pass --force to replace it",
+            path.display()
+        );
+    }
+    std::fs::write(&path, render_repo_config(scan))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::repo_config::RepoConfig;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn scan_suggests_ignore_globs_and_trust_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        git(repo, &["init", "-q"]);
+        for (path, content) in [
+            ("src/main.rs", "fn main() {}\n"),
+            ("src/lib.rs", "\n"),
+            ("app/index.ts", "export {};\n"),
+            ("Cargo.lock", "\n"),
+            ("vendor/dep/lib.go", "package dep\n"),
+            ("web/app.min.js", "x\n"),
+            ("Dockerfile", "FROM scratch\n"),
+            ("target/debug/out", "\n"),
+        ] {
+            let file = repo.join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+        }
+        git(repo, &["add", "-A"]);
+
+        let scan = scan_repo(repo).unwrap();
+        assert_eq!(scan.languages[0].language, "Rust");
+        assert_eq!(scan.languages[0].files, 2);
+        assert_eq!(scan.lockfiles, ["Cargo.lock"]);
+        // `target/` is already skipped by the built-in filters.
+        assert_eq!(scan.suggested_ignore, ["vendor/", "*.min.js"]);
+
+        let fit = |id: &str| scan.categories.iter().find(|c| c.category == id).unwrap();
+        assert!(fit("imports").applies);
+        assert!(fit("type-annotations").applies);
+        assert_eq!(fit("infra").evidence.as_deref(), Some("Dockerfile"));
+        assert!(!fit("sql").applies);
+        assert!(scan.suggested_trust.contains(&"imports:*".to_owned()));
+        assert!(!scan.suggested_trust.iter().any(|p| p.starts_with("infra:")));
+
+        let path = write_repo_config(repo, &scan, false).unwrap();
+        let config = RepoConfig::parse(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(config.ignore, scan.suggested_ignore);
+        assert_eq!(config.trust, Some(scan.suggested_trust.clone()));
+        assert!(write_repo_config(repo, &scan, false).is_err());
+    }
+}
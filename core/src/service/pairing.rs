@@ -0,0 +1,260 @@
+//! Which URLs a phone can reach the server at, for pairing.
+//!
+//! The machine's interface addresses come from `ip -o addr` (Linux) or
+//! `ifconfig` (macOS and the BSDs); neither being available just means no
+//! candidates. From those, [`candidates`] keeps the addresses a phone on the
+//! same network could plausibly use — no loopback, no container bridges, no
+//! link-local unless asked for — best first: private IPv4, then the tailnet,
+//! then other IPv4, then IPv6. All of them go in the pairing payload, and the
+//! client tries each in turn.
+
+use std::net::{IpAddr, Ipv4Addr};
+use std::process::Command;
+
+use serde::Serialize;
+
+/// Set to `1` to offer link-local addresses too.
+pub const LINK_LOCAL_ENV: &str = "REVIEW_PAIR_LINK_LOCAL";
+
+/// Version of the [`PairingPayload`] format.
+pub const PAIRING_VERSION: u32 = 1;
+
+/// Interfaces of virtual networks a phone can't be on.
+const VIRTUAL_INTERFACE_PREFIXES: &[&str] = &["docker", "veth", "br-", "virbr", "cni", "flannel"];
+
+/// One address of one network interface.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceAddr {
+    pub interface: String,
+    pub ip: IpAddr,
+}
+
+/// What the pairing QR code encodes.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingPayload {
+    pub version: u32,
+    /// Best first.
+    pub urls: Vec<String>,
+    pub token: String,
+}
+
+/// Addresses from `ip -o addr show`, one per line:
+/// `2: eth0    inet 192.168.1.5/24 brd ... scope global eth0`.
+pub fn parse_ip_addr(output: &str) -> Vec<InterfaceAddr> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let interface = fields.next()?.trim_end_matches(':');
+            let family = fields.next()?;
+            if family != "inet" && family != "inet6" {
+                return None;
+            }
+            let ip = fields.next()?.split('/').next()?.parse().ok()?;
+            Some(InterfaceAddr {
+                interface: interface.to_owned(),
+                ip,
+            })
+        })
+        .collect()
+}
+
+/// Addresses from `ifconfig`: an unindented `en0: flags=...` line starts each
+/// interface, and indented `inet`/`inet6` lines list its addresses (IPv6
+/// ones may carry a `%en0` zone).
+pub fn parse_ifconfig(output: &str) -> Vec<InterfaceAddr> {
+    let mut interface = None;
+    let mut addrs = Vec::new();
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            interface = line.split(':').next().filter(|name| !name.is_empty());
+            continue;
+        }
+        let (Some(name), mut fields) = (interface, line.split_whitespace()) else {
+            continue;
+        };
+        if !matches!(fields.next(), Some("inet" | "inet6")) {
+            continue;
+        }
+        let ip = fields
+            .next()
+            .and_then(|ip| ip.split('%').next())
+            .and_then(|ip| ip.parse().ok());
+        if let Some(ip) = ip {
+            addrs.push(InterfaceAddr {
+                interface: name.to_owned(),
+                ip,
+            });
+        }
+    }
+    addrs
+}
+
+/// This machine's interface addresses.
+pub fn interface_addrs() -> Vec<InterfaceAddr> {
+    let run = |program: &str, args: &[&str]| {
+        Command::new(program)
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    if let Some(output) = run("ip", &["-o", "addr", "show"]) {
+        return parse_ip_addr(&output);
+    }
+    run("ifconfig", &[])
+        .map(|output| parse_ifconfig(&output))
+        .unwrap_or_default()
+}
+
+fn is_link_local(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_link_local(),
+        IpAddr::V6(ip) => ip.segments()[0] & 0xffc0 == 0xfe80,
+    }
+}
+
+/// Tailscale hands out addresses from the CGNAT range, 100.64.0.0/10.
+fn is_tailnet(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    a == 100 && b & 0xc0 == 64
+}
+
+/// Lower is better: private IPv4, tailnet, other IPv4, IPv6, link-local.
+fn rank(ip: IpAddr) -> u8 {
+    match ip {
+        _ if is_link_local(ip) => 4,
+        IpAddr::V4(ip) if ip.is_private() => 0,
+        IpAddr::V4(ip) if is_tailnet(ip) => 1,
+        IpAddr::V4(_) => 2,
+        IpAddr::V6(_) => 3,
+    }
+}
+
+/// The addresses worth offering a phone, best first, each once. Link-local
+/// ones only with `include_link_local`: they need the interface's zone, which
+/// many clients can't use.
+pub fn candidates(addrs: &[InterfaceAddr], include_link_local: bool) -> Vec<InterfaceAddr> {
+    let mut kept: Vec<InterfaceAddr> = Vec::new();
+    for addr in addrs {
+        let virtual_interface = VIRTUAL_INTERFACE_PREFIXES
+            .iter()
+            .any(|prefix| addr.interface.starts_with(prefix));
+        let unusable = addr.ip.is_loopback()
+            || addr.ip.is_unspecified()
+            || addr.ip.is_multicast()
+            || (!include_link_local && is_link_local(addr.ip));
+        if !virtual_interface && !unusable && !kept.iter().any(|k| k.ip == addr.ip) {
+            kept.push(addr.clone());
+        }
+    }
+    kept.sort_by_key(|addr| rank(addr.ip));
+    kept
+}
+
+/// Whether [`LINK_LOCAL_ENV`] asks for link-local addresses.
+pub fn link_local_requested() -> bool {
+    std::env::var(LINK_LOCAL_ENV).is_ok_and(|v| v == "1")
+}
+
+/// The URL of a server on `port` at `addr`. IPv6 addresses are bracketed,
+/// and link-local ones carry their interface as the (escaped) zone.
+pub fn server_url(addr: &InterfaceAddr, port: u16) -> String {
+    match addr.ip {
+        IpAddr::V4(ip) => format!("http://{ip}:{port}"),
+        IpAddr::V6(ip) if is_link_local(addr.ip) => {
+            format!("http://[{ip}%25{}]:{port}", addr.interface)
+        }
+        IpAddr::V6(ip) => format!("http://[{ip}]:{port}"),
+    }
+}
+
+/// URLs a server bound to `bind` on `port` is reachable at from another
+/// device, best first. A specific address is the only one; `0.0.0.0` offers
+/// every IPv4 candidate and `::` every candidate (dual stack). Nothing for
+/// loopback.
+pub fn server_urls(bind: IpAddr, port: u16, include_link_local: bool) -> Vec<String> {
+    if bind.is_loopback() {
+        return Vec::new();
+    }
+    if !bind.is_unspecified() {
+        let addr = InterfaceAddr {
+            interface: String::new(),
+            ip: bind,
+        };
+        return vec![server_url(&addr, port)];
+    }
+    let ipv4_only = bind == IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+    candidates(&interface_addrs(), include_link_local)
+        .iter()
+        .filter(|addr| !ipv4_only || addr.ip.is_ipv4())
+        .map(|addr| server_url(addr, port))
+        .collect()
+}
+
+/// The pairing payload for a server on `bind` and `port`.
+pub fn pairing_payload(
+    bind: IpAddr,
+    port: u16,
+    token: String,
+    include_link_local: bool,
+) -> PairingPayload {
+    PairingPayload {
+        version: PAIRING_VERSION,
+        urls: server_urls(bind, port, include_link_local),
+        token,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_interfaces_and_ranks_candidates() {
+        let ip = "1: lo    inet 127.0.0.1/8 scope host lo\\       valid_lft forever\n\
+                  3: docker0    inet 172.17.0.1/16 brd 172.17.255.255 scope global docker0\n\
+                  4: eth0    inet6 2001:db8::5/64 scope global \\       valid_lft forever\n\
+                  4: eth0    inet6 fe80::1/64 scope link \\       valid_lft forever\n\
+                  4: eth0    inet 192.168.1.5/24 brd 192.168.1.255 scope global eth0\n\
+                  5: tailscale0    inet 100.101.102.103/32 scope global tailscale0\n";
+        let addrs = parse_ip_addr(ip);
+        assert_eq!(addrs.len(), 6);
+        let picked: Vec<String> = candidates(&addrs, false)
+            .iter()
+            .map(|addr| server_url(addr, 3421))
+            .collect();
+        assert_eq!(
+            picked,
+            [
+                "http://192.168.1.5:3421",
+                "http://100.101.102.103:3421",
+                "http://[2001:db8::5]:3421",
+            ]
+        );
+        let with_link_local = candidates(&addrs, true);
+        assert_eq!(
+            server_url(with_link_local.last().unwrap(), 3421),
+            "http://[fe80::1%25eth0]:3421"
+        );
+
+        let ifconfig = "lo0: flags=8049<UP,LOOPBACK> mtu 16384\n\
+                        \tinet 127.0.0.1 netmask 0xff000000\n\
+                        en0: flags=8863<UP,BROADCAST> mtu 1500\n\
+                        \tinet6 fe80::1c2b:3c4d%en0 prefixlen 64 secured scopeid 0x6\n\
+                        \tinet 10.0.0.7 netmask 0xffffff00 broadcast 10.0.0.255\n";
+        let addrs = parse_ifconfig(ifconfig);
+        assert_eq!(addrs.len(), 3);
+        assert_eq!(addrs[1].interface, "en0");
+        assert_eq!(candidates(&addrs, false)[0].ip.to_string(), "10.0.0.7");
+
+        assert!(server_urls(IpAddr::V4(Ipv4Addr::LOCALHOST), 3421, false).is_empty());
+        assert_eq!(
+            server_urls("192.168.1.5".parse().unwrap(), 3421, false),
+            ["http://192.168.1.5:3421"]
+        );
+    }
+}
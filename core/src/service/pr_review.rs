@@ -0,0 +1,204 @@
+//! Submit a local review to its GitHub pull request as an approve,
+//! request-changes, or comment review. The review body summarizes how the
+//! hunks were reviewed — trusted by label, approved by hand, rejected — so
+//! the PR shows what a person actually looked at.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::review::state::{HunkStatus, ReviewState};
+use crate::review::storage;
+use crate::sources::github::{GitHubClient, PrReviewEvent};
+use crate::trust::matching::matches_pattern;
+
+/// A review ready to submit, or just submitted.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrReviewDraft {
+    /// The pull request, when the review knows it.
+    pub number: Option<u32>,
+    pub event: PrReviewEvent,
+    pub body: String,
+}
+
+/// The event a review's state suggests: request changes once a hunk is
+/// rejected, approve once every hunk is reviewed, else comment.
+pub fn suggested_event(state: &ReviewState) -> PrReviewEvent {
+    match state.to_summary().state.as_deref() {
+        Some("changes_requested") => PrReviewEvent::RequestChanges,
+        Some("approved") => PrReviewEvent::Approve,
+        _ => PrReviewEvent::Comment,
+    }
+}
+
+/// Markdown summary of how a review's hunks were reviewed: how many were
+/// trusted (by which trust-list label), approved by hand, rejected (in which
+/// files), saved for later, or not reviewed, followed by the review's notes.
+pub fn review_summary(state: &ReviewState) -> String {
+    let mut trusted_by_label: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut approved = 0;
+    let mut rejected_files = BTreeSet::new();
+    let mut rejected = 0;
+    let mut saved = 0;
+    for (id, hunk) in &state.hunks {
+        match hunk.status.as_ref().map(|s| &s.value) {
+            Some(HunkStatus::Approved) => approved += 1,
+            Some(HunkStatus::Rejected) => {
+                rejected += 1;
+                rejected_files.insert(id.rsplit_once(':').map_or(id.as_str(), |(path, _)| path));
+            }
+            Some(HunkStatus::SavedForLater) => saved += 1,
+            None if state.labels_trusted(hunk.labels()) => {
+                let label = hunk.labels().iter().find(|label| {
+                    state
+                        .trust_list
+                        .iter()
+                        .any(|pattern| matches_pattern(label, pattern))
+                });
+                if let Some(label) = label {
+                    *trusted_by_label.entry(label).or_default() += 1;
+                }
+            }
+            None => {}
+        }
+    }
+    let trusted: usize = trusted_by_label.values().sum();
+    let reviewed = trusted + approved + rejected;
+    let total = state.total_diff_hunks.max(reviewed + saved);
+
+    let mut body = format!("**Review summary**: {reviewed} of {total} hunks reviewed\n\n");
+    if trusted > 0 {
+        let labels = trusted_by_label
+            .iter()
+            .map(|(label, n)| format!("`{label}` ({n})"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(body, "- {trusted} trusted by label: {labels}");
+    }
+    let _ = writeln!(body, "- {approved} approved by hand");
+    if rejected > 0 {
+        let files = rejected_files
+            .iter()
+            .map(|file| format!("`{file}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let _ = writeln!(body, "- {rejected} rejected, in {files}");
+    }
+    if saved > 0 {
+        let _ = writeln!(body, "- {saved} saved for later");
+    }
+    let unreviewed = total - reviewed - saved;
+    if unreviewed > 0 {
+        let _ = writeln!(body, "- {unreviewed} not reviewed");
+    }
+    let notes = state.notes.trim();
+    if !notes.is_empty() {
+        let _ = write!(body, "\n{notes}\n");
+    }
+    body
+}
+
+/// The review `ref_name` would submit: `event` (or the suggested one), and
+/// `message` above the summary.
+pub fn draft_review(
+    repo_path: &Path,
+    ref_name: &str,
+    event: Option<PrReviewEvent>,
+    message: Option<&str>,
+) -> anyhow::Result<PrReviewDraft> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let summary = review_summary(&state);
+    let body = match message.map(str::trim).filter(|m| !m.is_empty()) {
+        Some(message) => format!("{message}\n\n{summary}"),
+        None => summary,
+    };
+    Ok(PrReviewDraft {
+        number: state.github_pr.as_ref().map(|pr| pr.number),
+        event: event.unwrap_or_else(|| suggested_event(&state)),
+        body,
+    })
+}
+
+/// Submit `ref_name`'s review to its pull request (or PR `number`, for a
+/// review that doesn't know it). Approving a review with rejected hunks is
+/// refused.
+pub fn submit_review(
+    repo_path: &Path,
+    ref_name: &str,
+    number: Option<u32>,
+    event: Option<PrReviewEvent>,
+    message: Option<&str>,
+) -> anyhow::Result<PrReviewDraft> {
+    let mut draft = draft_review(repo_path, ref_name, event, message)?;
+    let number = number
+        .or(draft.number)
+        .with_context(|| format!("{ref_name} isn't a pull request review; name the PR"))?;
+    if draft.event == PrReviewEvent::Approve {
+        let state = storage::load_review_state(repo_path, ref_name)?;
+        let rejected = state.to_summary().rejected_hunks;
+        anyhow::ensure!(
+            rejected == 0,
+            "{rejected} hunk(s) are rejected; request changes instead of approving"
+        );
+    }
+    GitHubClient::for_repo(repo_path.to_path_buf())
+        .submit_review(number, draft.event, &draft.body)
+        .with_context(|| format!("Failed to submit the review of #{number}"))?;
+    draft.number = Some(number);
+    Ok(draft)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::state::{Attributed, HunkState, Source};
+
+    fn hunk(labels: &[&str], status: Option<HunkStatus>) -> HunkState {
+        HunkState {
+            classification: Some(Attributed::new(
+                labels.iter().map(|l| (*l).to_owned()).collect(),
+                Source::Static,
+            )),
+            status: status.map(|s| Attributed::new(s, Source::Ui)),
+            ..HunkState::default()
+        }
+    }
+
+    #[test]
+    fn summarizes_trusted_approved_and_rejected_hunks() {
+        let mut state = ReviewState::new("feature", None);
+        state.trust_list = vec!["imports:*".to_owned()];
+        state.total_diff_hunks = 5;
+        state
+            .hunks
+            .insert("a.rs:1".to_owned(), hunk(&["imports:added"], None));
+        state
+            .hunks
+            .insert("b.rs:2".to_owned(), hunk(&["imports:removed"], None));
+        state
+            .hunks
+            .insert("c.rs:3".to_owned(), hunk(&[], Some(HunkStatus::Approved)));
+        state.hunks.insert(
+            "src/d.rs:4".to_owned(),
+            hunk(&[], Some(HunkStatus::Rejected)),
+        );
+        state.notes = "Check the retry loop.".to_owned();
+
+        let body = review_summary(&state);
+        assert!(body.starts_with("**Review summary**: 4 of 5 hunks reviewed\n"));
+        assert!(body.contains("- 2 trusted by label: `imports:added` (1), `imports:removed` (1)\n"));
+        assert!(body.contains("- 1 approved by hand\n"));
+        assert!(body.contains("- 1 rejected, in `src/d.rs`\n"));
+        assert!(body.contains("- 1 not reviewed\n"));
+        assert!(body.ends_with("\nCheck the retry loop.\n"));
+        assert_eq!(suggested_event(&state), PrReviewEvent::RequestChanges);
+
+        state.hunks.remove("src/d.rs:4");
+        state.total_diff_hunks = 3;
+        assert_eq!(suggested_event(&state), PrReviewEvent::Approve);
+    }
+}
@@ -0,0 +1,204 @@
+//! Publish a pull request review's progress as a commit status on the PR's
+//! head ("Review: 34/120 hunks approved"), so teammates see how far review
+//! has got from the PR page.
+//!
+//! With `publishReviewStatus` on, every save of a PR review
+//! ([`super::review_io::save_review`]) republishes in the background. A
+//! status, not a check run: check runs need a GitHub App, statuses only the
+//! user's token. The last status posted per review is kept in
+//! `pr-status.json` in the repo's cache dir, so saves that don't change the
+//! tally don't post again.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
+
+use anyhow::Context;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::review::central;
+use crate::review::state::ReviewSummary;
+use crate::review::storage;
+use crate::sources::github::{CommitStatus, GitHubClient};
+
+/// Status name the progress is posted under. Distinct from the `review`
+/// status `review protection --post` gates merges with.
+pub const PROGRESS_CONTEXT: &str = "review/progress";
+
+/// Filename of the last-published record in a repo's cache dir.
+pub const PR_STATUS_FILE: &str = "pr-status.json";
+
+/// A status as posted on a PR's head.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishedStatus {
+    pub pr: u32,
+    pub sha: String,
+    #[serde(flatten)]
+    pub status: CommitStatus,
+}
+
+/// The status last published for each review, by ref.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PublishedRecord {
+    reviews: BTreeMap<String, PublishedStatus>,
+}
+
+fn record_path(repo_path: &Path) -> anyhow::Result<PathBuf> {
+    Ok(central::get_repo_cache_dir(repo_path)?.join(PR_STATUS_FILE))
+}
+
+/// Missing or unreadable means nothing published yet.
+fn load_record(repo_path: &Path) -> anyhow::Result<PublishedRecord> {
+    let path = record_path(repo_path)?;
+    let Ok(json) = fs::read_to_string(&path) else {
+        return Ok(PublishedRecord::default());
+    };
+    Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!("[pr_status] ignoring corrupt {}: {e}", path.display());
+        PublishedRecord::default()
+    }))
+}
+
+fn save_record(repo_path: &Path, record: &PublishedRecord) -> anyhow::Result<()> {
+    let path = record_path(repo_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(record)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The progress status for a review: `failure` once a hunk is rejected,
+/// `success` once every hunk is approved or trusted, else `pending`.
+pub fn progress_status(summary: &ReviewSummary) -> CommitStatus {
+    let approved = summary.approved_hunks + summary.trusted_hunks;
+    let mut description = format!("Review: {approved}/{} hunks approved", summary.total_hunks);
+    if summary.rejected_hunks > 0 {
+        let _ = write!(description, ", {} rejected", summary.rejected_hunks);
+    }
+    let state = match summary.state.as_deref() {
+        Some("changes_requested") => "failure",
+        Some("approved") => "success",
+        _ => "pending",
+    };
+    CommitStatus {
+        state: state.to_owned(),
+        context: PROGRESS_CONTEXT.to_owned(),
+        description,
+    }
+}
+
+/// Post `ref_name`'s progress on its PR's head. `None` when the review isn't
+/// of a pull request, or the same status is already posted on that head.
+pub fn publish_progress(
+    repo_path: &Path,
+    ref_name: &str,
+) -> anyhow::Result<Option<PublishedStatus>> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let Some(pr) = state.github_pr.as_ref() else {
+        return Ok(None);
+    };
+    let provider = GitHubClient::for_repo(repo_path.to_path_buf());
+    let sha = provider
+        .get_pr_status(pr.number)
+        .with_context(|| format!("Failed to look up #{}'s head", pr.number))?
+        .head_ref_oid;
+    let published = PublishedStatus {
+        pr: pr.number,
+        sha,
+        status: progress_status(&state.to_summary()),
+    };
+    let mut record = load_record(repo_path)?;
+    if record.reviews.get(ref_name) == Some(&published) {
+        return Ok(None);
+    }
+    provider
+        .create_commit_status(&published.sha, &published.status)
+        .with_context(|| format!("Failed to post the review status on #{}", pr.number))?;
+    record
+        .reviews
+        .insert(ref_name.to_owned(), published.clone());
+    save_record(repo_path, &record)?;
+    Ok(Some(published))
+}
+
+/// Reviews with a publisher thread running, and whether another save landed
+/// while it ran.
+static PUBLISHING: LazyLock<Mutex<HashMap<(PathBuf, String), bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Publish `ref_name`'s progress on a background thread when
+/// `publishReviewStatus` is on. A burst of saves runs one publisher, which
+/// goes again once if saves landed while it posted.
+pub fn publish_progress_in_background(repo_path: &Path, ref_name: &str) {
+    let enabled =
+        super::settings::read_settings().is_ok_and(|file| file.settings.publish_review_status);
+    if !enabled || crate::offline::is_offline() {
+        return;
+    }
+    let key = (repo_path.to_path_buf(), ref_name.to_owned());
+    {
+        let mut publishing = PUBLISHING.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some(again) = publishing.get_mut(&key) {
+            *again = true;
+            return;
+        }
+        publishing.insert(key.clone(), false);
+    }
+    std::thread::spawn(move || loop {
+        let (repo_path, ref_name) = &key;
+        if let Err(e) = publish_progress(repo_path, ref_name) {
+            warn!("[pr_status] {ref_name}: {e:#}");
+        }
+        let mut publishing = PUBLISHING.lock().unwrap_or_else(PoisonError::into_inner);
+        if publishing.get(&key) == Some(&true) {
+            publishing.insert(key.clone(), false);
+        } else {
+            publishing.remove(&key);
+            break;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::state::ReviewState;
+
+    fn summary(approved: usize, trusted: usize, rejected: usize) -> ReviewSummary {
+        let mut summary = ReviewState::new("feature", None).to_summary();
+        summary.total_hunks = 120;
+        summary.approved_hunks = approved;
+        summary.trusted_hunks = trusted;
+        summary.rejected_hunks = rejected;
+        summary.reviewed_hunks = approved + trusted + rejected;
+        summary.state = crate::review::state::overall_review_state(
+            rejected,
+            summary.reviewed_hunks,
+            summary.total_hunks,
+        )
+        .map(ToOwned::to_owned);
+        summary
+    }
+
+    #[test]
+    fn progress_counts_trusted_hunks_as_approved() {
+        let status = progress_status(&summary(30, 4, 0));
+        assert_eq!(status.description, "Review: 34/120 hunks approved");
+        assert_eq!(status.state, "pending");
+        assert_eq!(status.context, PROGRESS_CONTEXT);
+
+        let rejected = progress_status(&summary(30, 4, 2));
+        assert_eq!(
+            rejected.description,
+            "Review: 34/120 hunks approved, 2 rejected"
+        );
+        assert_eq!(rejected.state, "failure");
+
+        assert_eq!(progress_status(&summary(100, 20, 0)).state, "success");
+    }
+}
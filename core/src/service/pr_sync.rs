@@ -0,0 +1,251 @@
+//! Review-request sync: pre-warm a review for every pull request where the
+//! user's review is requested, once per PR revision.
+//!
+//! Which PRs have been synced, and at which `updatedAt`, is kept in
+//! `pr-sync.json` in the repo's cache dir. A PR is synced again when GitHub
+//! reports it updated, and forgotten once it stops being requested. Losing the
+//! file only means the next sync redoes (and re-announces) every request.
+//!
+//! [`sync_inbox`] refreshes the reviewer inbox ([`crate::review::inbox`]) from
+//! the same GitHub state.
+
+use anyhow::Context;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::prewarm::{prewarm_pull_request, PrewarmResult};
+use crate::review::central;
+use crate::review::inbox::{self, Inbox, InboxDirection, InboxStatus};
+use crate::review::state::now_iso8601;
+use crate::sources::github::{GitHubClient, PullRequest};
+
+/// Filename of the sync record in a repo's cache dir.
+pub const PR_SYNC_FILE: &str = "pr-sync.json";
+
+/// The `updatedAt` each requested PR had when it was last synced.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncedPrs {
+    pub prs: BTreeMap<u32, String>,
+}
+
+/// What one sync pass did.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOutcome {
+    /// Review requests pre-warmed this pass (new, or updated since last time).
+    pub synced: Vec<PrewarmResult>,
+    /// Requests that failed to pre-warm, with the error; retried next pass.
+    pub failed: Vec<(u32, String)>,
+}
+
+fn sync_path(repo_path: &Path) -> anyhow::Result<PathBuf> {
+    Ok(central::get_repo_cache_dir(repo_path)?.join(PR_SYNC_FILE))
+}
+
+/// Load the sync record; missing or unreadable means nothing synced yet.
+pub fn load_synced(repo_path: &Path) -> anyhow::Result<SyncedPrs> {
+    let path = sync_path(repo_path)?;
+    let Ok(json) = fs::read_to_string(&path) else {
+        return Ok(SyncedPrs::default());
+    };
+    Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!("[load_synced] ignoring corrupt {}: {e}", path.display());
+        SyncedPrs::default()
+    }))
+}
+
+fn save_synced(repo_path: &Path, synced: &SyncedPrs) -> anyhow::Result<()> {
+    let path = sync_path(repo_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(synced)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// The requested PRs that are new or updated since they were last synced.
+pub fn pending_requests<'a>(
+    requested: &'a [PullRequest],
+    synced: &SyncedPrs,
+) -> Vec<&'a PullRequest> {
+    requested
+        .iter()
+        .filter(|pr| synced.prs.get(&pr.number) != Some(&pr.updated_at))
+        .collect()
+}
+
+/// One sync pass: list review requests, pre-warm the pending ones (AI
+/// classification with `model`, static only when `None`), and record them.
+pub fn sync_review_requests(repo_path: &Path, model: Option<&str>) -> anyhow::Result<SyncOutcome> {
+    let provider = GitHubClient::for_repo(repo_path.to_path_buf());
+    let requested = provider
+        .list_review_requests()
+        .context("Failed to list review requests")?;
+    let mut synced = load_synced(repo_path)?;
+    let mut outcome = SyncOutcome::default();
+
+    for pr in pending_requests(&requested, &synced) {
+        match prewarm_pull_request(repo_path, pr, model) {
+            Ok(result) => {
+                synced.prs.insert(pr.number, pr.updated_at.clone());
+                outcome.synced.push(result);
+            }
+            Err(e) => outcome.failed.push((pr.number, format!("{e:#}"))),
+        }
+    }
+    synced
+        .prs
+        .retain(|number, _| requested.iter().any(|pr| pr.number == *number));
+    save_synced(repo_path, &synced)?;
+
+    info!(
+        "[sync_review_requests] {} requested, {} synced, {} failed",
+        requested.len(),
+        outcome.synced.len(),
+        outcome.failed.len()
+    );
+    Ok(outcome)
+}
+
+/// An inbox entry whose status moved during a sync.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxChange {
+    pub number: u32,
+    pub title: String,
+    pub direction: InboxDirection,
+    pub from: InboxStatus,
+    pub to: InboxStatus,
+}
+
+/// The inbox after a sync, and what changed in it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InboxSync {
+    pub inbox: Inbox,
+    pub changes: Vec<InboxChange>,
+}
+
+/// Refresh the reviewer inbox from GitHub: open PRs requesting the user's
+/// review (incoming) and open PRs they authored (outgoing), plus a direct
+/// lookup for entries that dropped out of both lists — usually because the
+/// user reviewed it, or it was merged or closed.
+pub fn sync_inbox(repo_path: &Path) -> anyhow::Result<InboxSync> {
+    let provider = GitHubClient::for_repo(repo_path.to_path_buf());
+    let incoming = provider
+        .list_review_statuses("review-requested:@me")
+        .context("Failed to list review requests")?;
+    let outgoing = provider
+        .list_review_statuses("author:@me")
+        .context("Failed to list your pull requests")?;
+
+    let mut inbox = inbox::load_inbox(repo_path)?;
+    inbox.drop_settled();
+    let known: Vec<(u32, InboxDirection)> = inbox
+        .entries
+        .iter()
+        .map(|e| (e.number, e.direction))
+        .collect();
+    let at = now_iso8601();
+    let mut changes = Vec::new();
+    let mut record = |inbox: &mut Inbox, direction, pr: &_| {
+        if let Some(change) = inbox.apply(direction, pr, &at) {
+            changes.push(InboxChange {
+                number: pr.number,
+                title: pr.title.clone(),
+                direction,
+                from: change.from,
+                to: change.to,
+            });
+        }
+    };
+
+    for pr in &incoming {
+        record(&mut inbox, InboxDirection::Incoming, pr);
+    }
+    for pr in outgoing
+        .iter()
+        .filter(|pr| !incoming.iter().any(|i| i.number == pr.number))
+    {
+        record(&mut inbox, InboxDirection::Outgoing, pr);
+    }
+    for (number, direction) in known {
+        let listed = incoming
+            .iter()
+            .chain(&outgoing)
+            .any(|pr| pr.number == number);
+        if listed {
+            continue;
+        }
+        match provider.get_review_status(number) {
+            Ok(pr) => record(&mut inbox, direction, &pr),
+            Err(e) => warn!("[sync_inbox] failed to refresh #{number}: {e}"),
+        }
+    }
+
+    inbox::save_inbox(repo_path, &inbox)?;
+    info!(
+        "[sync_inbox] {} incoming, {} outgoing, {} changed",
+        incoming.len(),
+        outgoing.len(),
+        changes.len()
+    );
+    Ok(InboxSync { inbox, changes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::sources::github::PrAuthor;
+
+    fn pr(number: u32, updated_at: &str) -> PullRequest {
+        PullRequest {
+            number,
+            title: format!("PR {number}"),
+            head_ref_name: format!("feature-{number}"),
+            base_ref_name: "main".to_owned(),
+            url: String::new(),
+            author: PrAuthor {
+                login: "octocat".to_owned(),
+            },
+            state: "OPEN".to_owned(),
+            is_draft: false,
+            updated_at: updated_at.to_owned(),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn pending_requests_are_new_or_updated() {
+        let requested = [pr(1, "t1"), pr(2, "t2"), pr(3, "t3")];
+        let synced = SyncedPrs {
+            prs: [(1, "t1".to_owned()), (2, "t0".to_owned())]
+                .into_iter()
+                .collect(),
+        };
+        let pending: Vec<u32> = pending_requests(&requested, &synced)
+            .iter()
+            .map(|pr| pr.number)
+            .collect();
+        assert_eq!(pending, [2, 3]);
+    }
+
+    #[test]
+    fn sync_record_round_trips() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        assert_eq!(load_synced(repo.path()).unwrap(), SyncedPrs::default());
+
+        let synced = SyncedPrs {
+            prs: [(7, "2026-01-01T00:00:00Z".to_owned())]
+                .into_iter()
+                .collect(),
+        };
+        save_synced(repo.path(), &synced).unwrap();
+        assert_eq!(load_synced(repo.path()).unwrap(), synced);
+    }
+}
@@ -0,0 +1,550 @@
+//! Pre-warm reviews for pull requests: create each PR's review, classify its
+//! hunks (static rules, then AI for what the rules leave unlabeled), and save
+//! the labels, so opening the review later needs no classification wait.
+//! Labels are saved as each AI batch completes, not only at the end.
+//!
+//! [`prewarm_review`] does the same for an existing local review, e.g. when
+//! classification is triggered remotely through the server.
+//!
+//! [`reclassify_review`] re-runs the model on just the hunks a reviewer
+//! doubts — picked by ID, file glob or current label — replacing their labels
+//! and leaving every other hunk's alone.
+
+use anyhow::Context;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::ai::classify::classify_hunks_with_model;
+use crate::classify::precedence::{apply_rules, load_rules};
+use crate::classify::{classify_repo_hunks_static, ClassifyResponse};
+use crate::diff::parser::{parse_multi_file_diff, DiffHunk};
+use crate::review::embeddings;
+use crate::review::state::{Attributed, ReviewState, Source};
+use crate::review::storage::{self, StorageError};
+use crate::service::files::comparison_hunks;
+use crate::service::targets;
+use crate::sources::github::{GitHubClient, GitHubPrRef, GitHubProvider, PullRequest};
+use crate::trust::matching::matches_pattern;
+use crate::trust::patterns::current_taxonomy_version;
+
+/// Hunks sent to the model per request.
+const AI_BATCH_SIZE: usize = 20;
+/// Attempts at saving a review that keeps changing underneath us.
+const MAX_SAVE_RETRIES: usize = 5;
+
+/// What pre-warming one pull request stored.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrewarmResult {
+    pub number: u32,
+    pub title: String,
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub hunks: usize,
+    /// Hunks newly labeled by static rules.
+    pub static_labeled: usize,
+    /// Hunks newly labeled by the model.
+    pub ai_labeled: usize,
+}
+
+/// What pre-warming a local review stored.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewPrewarmResult {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub hunks: usize,
+    pub static_labeled: usize,
+    pub ai_labeled: usize,
+}
+
+/// Which of a review's hunks [`reclassify_review`] re-runs the model on. A
+/// hunk must match every non-empty field; an empty scope covers every hunk.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ClassifyScope {
+    pub hunk_ids: Vec<String>,
+    /// Gitignore-style globs on the hunk's file path.
+    pub files: Vec<String>,
+    /// Trust patterns (e.g. `imports:*`): only hunks currently labeled with
+    /// a matching label.
+    pub labels: Vec<String>,
+    /// Only hunks a classifier labeled under an older taxonomy.
+    pub stale: bool,
+}
+
+impl ClassifyScope {
+    fn file_matcher(&self) -> Gitignore {
+        let mut builder = GitignoreBuilder::new("");
+        for glob in &self.files {
+            if let Err(e) = builder.add_line(None, glob) {
+                log::warn!("[reclassify] Skipping invalid file glob {glob:?}: {e}");
+            }
+        }
+        builder.build().unwrap_or_else(|_| Gitignore::empty())
+    }
+
+    /// The hunks in scope, given the review's current labels.
+    pub fn select(&self, hunks: &[DiffHunk], state: &ReviewState) -> Vec<DiffHunk> {
+        let files = self.file_matcher();
+        hunks
+            .iter()
+            .filter(|hunk| self.hunk_ids.is_empty() || self.hunk_ids.contains(&hunk.id))
+            .filter(|hunk| {
+                self.files.is_empty()
+                    || files
+                        .matched_path_or_any_parents(&hunk.file_path, false)
+                        .is_ignore()
+            })
+            .filter(|hunk| {
+                self.labels.is_empty()
+                    || state
+                        .hunks
+                        .get(&hunk.id)
+                        .and_then(|s| s.classification.as_ref())
+                        .is_some_and(|c| {
+                            c.value
+                                .iter()
+                                .any(|label| self.labels.iter().any(|p| matches_pattern(label, p)))
+                        })
+            })
+            .filter(|hunk| {
+                !self.stale
+                    || state
+                        .hunks
+                        .get(&hunk.id)
+                        .is_some_and(|s| s.has_stale_labels(current_taxonomy_version()))
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// What re-classifying part of a review changed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReclassifyResult {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// Hunks in scope, all sent to the model.
+    pub hunks: usize,
+    /// Hunks whose labels the model changed.
+    pub relabeled: usize,
+}
+
+fn pr_ref(pr: &PullRequest) -> GitHubPrRef {
+    GitHubPrRef {
+        number: pr.number,
+        title: pr.title.clone(),
+        head_ref_name: pr.head_ref_name.clone(),
+        base_ref_name: pr.base_ref_name.clone(),
+        body: (!pr.body.is_empty()).then(|| pr.body.clone()),
+    }
+}
+
+/// Store `response`'s non-empty labels on hunks that have no classification
+/// yet, attributed to `source`. Returns how many hunks were labeled.
+fn store_classifications(
+    state: &mut ReviewState,
+    response: &ClassifyResponse,
+    source: Source,
+) -> usize {
+    let mut stored = 0;
+    for (hunk_id, result) in &response.classifications {
+        if result.label.is_empty() {
+            continue;
+        }
+        let entry = state.hunks.entry(hunk_id.clone()).or_default();
+        if entry.classification.is_some() {
+            continue;
+        }
+        let mut labels = Attributed::new(result.label.clone(), source);
+        labels.reasoning = (!result.reasoning.is_empty()).then(|| result.reasoning.clone());
+        entry.classification = Some(labels);
+        entry.raw_labels = response.raw_labels.get(hunk_id).cloned();
+        stored += 1;
+    }
+    stored
+}
+
+/// Replace the labels of the hunks in `response` with the model's, attributed
+/// to `source`; a hunk the model now leaves unlabeled loses its labels.
+/// Returns how many hunks' labels changed.
+fn replace_classifications(
+    state: &mut ReviewState,
+    response: &ClassifyResponse,
+    source: Source,
+) -> usize {
+    let mut changed = 0;
+    for (hunk_id, result) in &response.classifications {
+        let entry = state.hunks.entry(hunk_id.clone()).or_default();
+        let before = entry.classification.as_ref().map(|c| &c.value);
+        if before.map_or(result.label.is_empty(), |labels| *labels == result.label) {
+            continue;
+        }
+        entry.classification = (!result.label.is_empty()).then(|| {
+            let mut labels = Attributed::new(result.label.clone(), source);
+            labels.reasoning = (!result.reasoning.is_empty()).then(|| result.reasoning.clone());
+            labels
+        });
+        entry.raw_labels = response.raw_labels.get(hunk_id).cloned();
+        changed += 1;
+    }
+    changed
+}
+
+/// Load the review, apply `update`, and save it, reloading and reapplying
+/// when another writer (the desktop app, a CLI command) saved in between.
+/// Returns the saved state and `update`'s count.
+fn update_review(
+    repo_path: &Path,
+    ref_name: &str,
+    update: impl Fn(&mut ReviewState) -> usize,
+) -> anyhow::Result<(ReviewState, usize)> {
+    for attempt in 0..MAX_SAVE_RETRIES {
+        let mut state = storage::load_review_state(repo_path, ref_name)?;
+        let count = update(&mut state);
+        state.prepare_for_save();
+        match storage::save_review_state(repo_path, &state) {
+            Ok(()) => return Ok((state, count)),
+            Err(StorageError::VersionConflict { .. }) if attempt + 1 < MAX_SAVE_RETRIES => {}
+            Err(e) => return Err(e).context("Failed to save review"),
+        }
+    }
+    anyhow::bail!("Failed to save review after repeated version conflicts")
+}
+
+/// Create `pr`'s review if needed, classify its diff, and save the labels.
+/// With `model` unset only static rules run. Existing labels are kept.
+pub fn prewarm_pull_request(
+    repo_path: &Path,
+    pr: &PullRequest,
+    model: Option<&str>,
+) -> anyhow::Result<PrewarmResult> {
+    let ref_name = &pr.head_ref_name;
+    storage::ensure_review_exists(
+        repo_path,
+        ref_name,
+        Some(pr.base_ref_name.clone()),
+        Some(pr_ref(pr)),
+    )
+    .with_context(|| format!("Failed to create review for #{}", pr.number))?;
+
+    let provider = GitHubClient::for_repo(repo_path.to_path_buf());
+    let diff = provider
+        .get_pull_request_diff(pr.number)
+        .with_context(|| format!("Failed to fetch diff for #{}", pr.number))?;
+    let hunks = parse_multi_file_diff(&diff);
+
+    let (static_labeled, ai_labeled) = classify_and_store(repo_path, ref_name, &hunks, model)
+        .with_context(|| format!("Failed to classify #{}", pr.number))?;
+    // Earlier reviewers' threads are a nicety: failing to pull them mustn't
+    // fail the pre-warm.
+    let threads = provider
+        .list_review_threads(pr.number)
+        .map_err(storage::StorageError::from)
+        .and_then(|threads| storage::import_pr_threads(repo_path, ref_name, &threads, &hunks));
+    if let Err(e) = threads {
+        log::warn!(
+            "[prewarm] Failed to import #{}'s review threads: {e}",
+            pr.number
+        );
+    }
+
+    info!(
+        "[prewarm_pull_request] #{}: {} hunks, {static_labeled} static, {ai_labeled} AI",
+        pr.number,
+        hunks.len()
+    );
+    Ok(PrewarmResult {
+        number: pr.number,
+        title: pr.title.clone(),
+        ref_name: ref_name.clone(),
+        hunks: hunks.len(),
+        static_labeled,
+        ai_labeled,
+    })
+}
+
+/// Classify `hunks` with static rules, then `model` for what they leave
+/// unlabeled, storing the labels on `ref_name`'s review. Returns how many
+/// hunks each labeled.
+fn classify_and_store(
+    repo_path: &Path,
+    ref_name: &str,
+    hunks: &[DiffHunk],
+    model: Option<&str>,
+) -> anyhow::Result<(usize, usize)> {
+    // Saved before the AI runs, and again after each batch, so labels that
+    // are already paid for survive a failed batch or the app quitting.
+    let static_response = classify_repo_hunks_static(repo_path, hunks);
+    if let Err(e) = embeddings::index_review(repo_path, ref_name, hunks) {
+        log::warn!("[prewarm] Failed to index {ref_name}'s hunks: {e}");
+    }
+    let (state, static_labeled) = update_review(repo_path, ref_name, |state| {
+        state.set_diff_hunks(hunks);
+        if let Err(e) = embeddings::carry_identical_approvals(repo_path, ref_name, hunks, state) {
+            log::warn!("[prewarm] Failed to match {ref_name}'s hunks to other reviews: {e}");
+        }
+        store_classifications(state, &static_response, Source::Static)
+    })?;
+
+    let mut ai_labeled = 0;
+    if let Some(model) = model {
+        let unlabeled: Vec<_> = hunks
+            .iter()
+            .filter(|h| {
+                state
+                    .hunks
+                    .get(&h.id)
+                    .is_none_or(|s| s.classification.is_none())
+            })
+            .cloned()
+            .collect();
+        let rules = load_rules(repo_path);
+        for batch in unlabeled.chunks(AI_BATCH_SIZE) {
+            let mut response = classify_hunks_with_model(batch, model, repo_path)
+                .context("AI classification failed")?;
+            apply_rules(&rules, &mut response);
+            let (_, stored) = update_review(repo_path, ref_name, |state| {
+                store_classifications(state, &response, Source::Ai)
+            })?;
+            ai_labeled += stored;
+        }
+    }
+    Ok((static_labeled, ai_labeled))
+}
+
+/// Classify the hunks of `ref_name`'s review (against its stored base, if
+/// any) and save the labels. With `model` unset only static rules run.
+pub fn prewarm_review(
+    repo_path: &Path,
+    ref_name: &str,
+    model: Option<&str>,
+) -> anyhow::Result<ReviewPrewarmResult> {
+    let review = targets::resolve(repo_path, ref_name, None)?;
+    let hunks = comparison_hunks(repo_path, &review.comparison, None)?;
+    let (static_labeled, ai_labeled) = classify_and_store(repo_path, ref_name, &hunks, model)?;
+
+    info!(
+        "[prewarm_review] {ref_name}: {} hunks, {static_labeled} static, {ai_labeled} AI",
+        hunks.len()
+    );
+    Ok(ReviewPrewarmResult {
+        ref_name: ref_name.to_owned(),
+        hunks: hunks.len(),
+        static_labeled,
+        ai_labeled,
+    })
+}
+
+/// Re-run `model` on the hunks of `ref_name`'s review that `scope` selects,
+/// replacing their labels — including ones a person set. Hunks outside the
+/// scope keep theirs.
+pub fn reclassify_review(
+    repo_path: &Path,
+    ref_name: &str,
+    model: &str,
+    scope: &ClassifyScope,
+) -> anyhow::Result<ReclassifyResult> {
+    let review = targets::resolve(repo_path, ref_name, None)?;
+    let hunks = comparison_hunks(repo_path, &review.comparison, None)?;
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let selected = scope.select(&hunks, &state);
+
+    let rules = load_rules(repo_path);
+    let mut relabeled = 0;
+    for batch in selected.chunks(AI_BATCH_SIZE) {
+        let mut response = classify_hunks_with_model(batch, model, repo_path)
+            .context("AI classification failed")?;
+        apply_rules(&rules, &mut response);
+        let (_, changed) = update_review(repo_path, ref_name, |state| {
+            replace_classifications(state, &response, Source::Ai)
+        })?;
+        relabeled += changed;
+    }
+
+    info!(
+        "[reclassify_review] {ref_name}: {} of {} hunks in scope, {relabeled} relabeled",
+        selected.len(),
+        hunks.len()
+    );
+    Ok(ReclassifyResult {
+        ref_name: ref_name.to_owned(),
+        hunks: selected.len(),
+        relabeled,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::classify::ClassificationResult;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::review::state::HunkState;
+    use std::collections::HashMap;
+
+    fn result(labels: &[&str]) -> ClassificationResult {
+        ClassificationResult {
+            label: labels.iter().map(|l| (*l).to_owned()).collect(),
+            reasoning: "because".to_owned(),
+            evidence: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn stores_only_new_non_empty_labels() {
+        let mut state = ReviewState::new("feature", None);
+        state.hunks.insert(
+            "kept".to_owned(),
+            HunkState {
+                classification: Some(Attributed::new(
+                    vec!["comments:added".to_owned()],
+                    Source::Ui,
+                )),
+                ..Default::default()
+            },
+        );
+        let response = ClassifyResponse {
+            classifications: [
+                ("kept".to_owned(), result(&["imports:added"])),
+                ("empty".to_owned(), result(&[])),
+                ("new".to_owned(), result(&["imports:added"])),
+            ]
+            .into_iter()
+            .collect(),
+            limits_exceeded: Vec::new(),
+            raw_labels: HashMap::new(),
+        };
+
+        assert_eq!(store_classifications(&mut state, &response, Source::Ai), 1);
+        let kept = state.hunks["kept"].classification.as_ref().unwrap();
+        assert_eq!(kept.value, ["comments:added"]);
+        assert!(state
+            .hunks
+            .get("empty")
+            .is_none_or(|s| s.classification.is_none()));
+        let new = state.hunks["new"].classification.as_ref().unwrap();
+        assert_eq!(new.source, Source::Ai);
+        assert_eq!(new.reasoning.as_deref(), Some("because"));
+    }
+
+    #[test]
+    fn each_batch_is_saved_alongside_other_writers() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let status = std::process::Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(repo.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let batch = |id: &str| ClassifyResponse {
+            classifications: [(id.to_owned(), result(&["imports:added"]))]
+                .into_iter()
+                .collect(),
+            limits_exceeded: Vec::new(),
+            raw_labels: HashMap::new(),
+        };
+
+        let first = batch("a");
+        update_review(repo.path(), "feature", |state| {
+            store_classifications(state, &first, Source::Ai)
+        })
+        .unwrap();
+
+        // The desktop app saves between batches
+        let mut other = storage::load_review_state(repo.path(), "feature").unwrap();
+        other.notes = "mid-run".to_owned();
+        other.prepare_for_save();
+        storage::save_review_state(repo.path(), &other).unwrap();
+
+        let second = batch("b");
+        let (_, stored) = update_review(repo.path(), "feature", |state| {
+            store_classifications(state, &second, Source::Ai)
+        })
+        .unwrap();
+        assert_eq!(stored, 1);
+
+        let state = storage::load_review_state(repo.path(), "feature").unwrap();
+        assert!(state.hunks["a"].classification.is_some());
+        assert!(state.hunks["b"].classification.is_some());
+        assert_eq!(state.notes, "mid-run");
+    }
+
+    #[test]
+    fn reclassifies_only_the_scoped_hunks() {
+        let hunk =
+            |id: &str, path: &str| DiffHunk::test(path, []).with_id(id).with_range(1, 1, 1, 1);
+        let hunks = [
+            hunk("a", "src/lib.rs"),
+            hunk("b", "src/gen/api.rs"),
+            hunk("c", "src/gen/types.rs"),
+        ];
+        let mut state = ReviewState::new("feature", None);
+        for (id, label) in [
+            ("a", "imports:added"),
+            ("b", "imports:added"),
+            ("c", "comments:added"),
+        ] {
+            state.hunks.entry(id.to_owned()).or_default().classification =
+                Some(Attributed::new(vec![label.to_owned()], Source::Ui));
+        }
+        let ids = |scope: &ClassifyScope| {
+            scope
+                .select(&hunks, &state)
+                .into_iter()
+                .map(|h| h.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(&ClassifyScope::default()), ["a", "b", "c"]);
+        let scope = ClassifyScope {
+            files: vec!["src/gen/".to_owned()],
+            labels: vec!["imports:*".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(ids(&scope), ["b"]);
+        let scope = ClassifyScope {
+            hunk_ids: vec!["a".to_owned(), "c".to_owned()],
+            ..Default::default()
+        };
+        assert_eq!(ids(&scope), ["a", "c"]);
+        let stale = ClassifyScope {
+            stale: true,
+            ..Default::default()
+        };
+        assert!(
+            ids(&stale).is_empty(),
+            "labels set by a person never go stale"
+        );
+
+        let response = ClassifyResponse {
+            classifications: [
+                ("a".to_owned(), result(&["imports:added"])),
+                ("b".to_owned(), result(&[])),
+                ("c".to_owned(), result(&["comments:removed"])),
+            ]
+            .into_iter()
+            .collect(),
+            limits_exceeded: Vec::new(),
+            raw_labels: HashMap::new(),
+        };
+        assert_eq!(
+            replace_classifications(&mut state, &response, Source::Ai),
+            2
+        );
+        assert_eq!(
+            state.hunks["a"].classification.as_ref().unwrap().source,
+            Source::Ui
+        );
+        assert!(state.hunks["b"].classification.is_none());
+        let c = state.hunks["c"].classification.as_ref().unwrap();
+        assert_eq!(
+            (c.value.as_slice(), c.source),
+            (&["comments:removed".to_owned()][..], Source::Ai)
+        );
+    }
+}
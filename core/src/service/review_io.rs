@@ -9,12 +9,14 @@
 //! reconciles directly via [`crate::review::state::ReviewState::reconcile`] with
 //! hunks it loaded itself.)
 
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
 use crate::diff::parser::DiffHunk;
-use crate::review::state::ReviewState;
+use crate::review::embeddings;
+use crate::review::state::{FileProgress, HunkState, ReviewState, ReviewSummary};
 use crate::review::storage;
 
 /// A loaded review plus how many decisions reconciliation carried forward onto
@@ -26,6 +28,10 @@ use crate::review::storage;
 pub struct ReviewLoadResult {
     pub state: ReviewState,
     pub carried_forward: usize,
+    /// Hunks suggested for approval because another review approved the
+    /// identical change (see [`embeddings::carry_identical_approvals`]).
+    #[serde(default)]
+    pub carried_from_reviews: usize,
 }
 
 /// Carry a loaded review's decisions forward onto the live diff, returning the
@@ -43,11 +49,31 @@ pub fn reconcile_review(mut state: ReviewState, live_hunks: &[DiffHunk]) -> Revi
     ReviewLoadResult {
         state,
         carried_forward,
+        carried_from_reviews: 0,
     }
 }
 
-/// Reconcile against the live hunks (when supplied), then persist; returns the
-/// new version. `live_hunks` is `None` only for callers with no diff in hand —
+/// [`reconcile_review`], then suggest approving the live hunks another review
+/// already approved unchanged. Best-effort: a failure is logged and the
+/// reconciled review returned. Doesn't touch the index; saving does.
+pub fn reconcile_review_in_repo(
+    repo: &Path,
+    state: ReviewState,
+    live_hunks: &[DiffHunk],
+) -> ReviewLoadResult {
+    let mut result = reconcile_review(state, live_hunks);
+    let ref_name = result.state.ref_name.clone();
+    match embeddings::carry_identical_approvals(repo, &ref_name, live_hunks, &mut result.state) {
+        Ok(carried) => result.carried_from_reviews = carried,
+        Err(e) => {
+            log::warn!("[review_io] Failed to match {ref_name}'s hunks to other reviews: {e}");
+        }
+    }
+    result
+}
+
+/// Reconcile against the live hunks (when supplied), then persist and index
+/// them for matching against other reviews; returns the new version. `live_hunks` is `None` only for callers with no diff in hand —
 /// e.g. saving a worktree-path change — where there is nothing to reconcile.
 pub fn save_review(
     repo: &Path,
@@ -61,9 +87,108 @@ pub fn save_review(
     }
     state.prepare_for_save();
     storage::save_review_state(repo, &state)?;
+    if let Some(hunks) = live_hunks {
+        if let Err(e) = embeddings::index_review(repo, &state.ref_name, hunks) {
+            log::warn!(
+                "[review_io] Failed to index {}'s hunks: {e}",
+                state.ref_name
+            );
+        }
+    }
+    if state.github_pr.is_some() {
+        super::pr_status::publish_progress_in_background(repo, &state.ref_name);
+    }
     Ok(state.version)
 }
 
+/// A review's progress as of its last save: the overall summary and the
+/// per-file rollups.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewProgress {
+    #[serde(flatten)]
+    pub summary: ReviewSummary,
+    pub files: BTreeMap<String, FileProgress>,
+}
+
+/// Read a review's progress from storage without diffing or walking its
+/// hunks — the rollups were computed when it was saved. Reviews saved before
+/// rollups existed get them computed from their stored hunks.
+pub fn get_review_progress(repo: &Path, ref_name: &str) -> anyhow::Result<ReviewProgress> {
+    let mut state = storage::load_review_state(repo, ref_name)?;
+    if state.file_progress.is_empty() && !state.hunks.is_empty() {
+        state.roll_up_files();
+    }
+    Ok(ReviewProgress {
+        summary: state.to_summary(),
+        files: state.file_progress,
+    })
+}
+
+/// What changed in a review between two versions, for clients that already
+/// hold the older one: hunk entries added or changed, hunk IDs removed, and —
+/// only if something outside `hunks` changed — the rest of the state, with
+/// `hunks` left empty.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewDelta {
+    pub since_version: u64,
+    pub version: u64,
+    pub hunks: BTreeMap<String, HunkState>,
+    pub removed_hunks: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<ReviewState>,
+}
+
+/// `state` without its hunks or the fields every save bumps, for comparing
+/// everything else.
+fn state_without_hunks(state: &ReviewState) -> serde_json::Value {
+    let mut rest = serde_json::to_value(ReviewState {
+        hunks: HashMap::new(),
+        ..state.clone()
+    })
+    .unwrap_or_default();
+    if let Some(object) = rest.as_object_mut() {
+        object.remove("version");
+        object.remove("updatedAt");
+    }
+    rest
+}
+
+/// The changes from `old` to `new`.
+pub fn review_delta(old: &ReviewState, new: &ReviewState) -> ReviewDelta {
+    let as_value = |hunk: &HunkState| serde_json::to_value(hunk).unwrap_or_default();
+    let hunks = new
+        .hunks
+        .iter()
+        .filter(|(id, hunk)| {
+            old.hunks
+                .get(*id)
+                .is_none_or(|previous| as_value(previous) != as_value(hunk))
+        })
+        .map(|(id, hunk)| (id.clone(), hunk.clone()))
+        .collect();
+    let mut removed_hunks: Vec<String> = old
+        .hunks
+        .keys()
+        .filter(|id| !new.hunks.contains_key(*id))
+        .cloned()
+        .collect();
+    removed_hunks.sort();
+    let state = (state_without_hunks(old) != state_without_hunks(new)).then(|| ReviewState {
+        hunks: HashMap::new(),
+        ..new.clone()
+    });
+
+    ReviewDelta {
+        since_version: old.version,
+        version: new.version,
+        hunks,
+        removed_hunks,
+        state,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,13 +204,6 @@ mod tests {
     const DIFF_A: &str = "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n@@ -1,3 +1,4 @@\n alpha\n beta\n+NEW\n gamma\n";
     const DIFF_B: &str = "diff --git a/f.txt b/f.txt\n--- a/f.txt\n+++ b/f.txt\n@@ -10,3 +10,4 @@\n delta\n epsilon\n+NEW\n zeta\n";
 
-    fn hunk(diff: &str) -> DiffHunk {
-        crate::diff::parser::parse_multi_file_diff(diff)
-            .into_iter()
-            .next()
-            .expect("one hunk")
-    }
-
     fn approved_with_key(key: Option<String>) -> HunkState {
         HunkState {
             status: Some(Attributed::new(HunkStatus::Approved, Source::Ui)),
@@ -96,8 +214,8 @@ mod tests {
 
     #[test]
     fn reconcile_review_carries_drifted_decision_forward() {
-        let a = hunk(DIFF_A);
-        let b = hunk(DIFF_B);
+        let a = DiffHunk::test_from_diff(DIFF_A);
+        let b = DiffHunk::test_from_diff(DIFF_B);
         assert_ne!(a.id, b.id, "context drift changes the id");
 
         let mut state = ReviewState::new(TEST_REF, None);
@@ -118,7 +236,7 @@ mod tests {
     #[test]
     fn reconcile_review_no_decisions_is_a_noop() {
         let state = ReviewState::new(TEST_REF, None);
-        let result = reconcile_review(state, &[hunk(DIFF_A)]);
+        let result = reconcile_review(state, &[DiffHunk::test_from_diff(DIFF_A)]);
         assert_eq!(result.carried_forward, 0);
         assert!(result.state.hunks.is_empty());
     }
@@ -128,7 +246,7 @@ mod tests {
         let _lock = ENV_LOCK.lock().unwrap();
         let (_env, _home, repo) = setup_test();
         let p = repo.path();
-        let a = hunk(DIFF_A);
+        let a = DiffHunk::test_from_diff(DIFF_A);
 
         let mut state = ReviewState::new(TEST_REF, None);
         // Decision with no stable key yet (as if just recorded in the UI).
@@ -157,4 +275,51 @@ mod tests {
         let loaded = storage::load_review_state(p, TEST_REF).unwrap();
         assert_eq!(loaded.version, 1);
     }
+
+    #[test]
+    fn get_review_progress_reads_saved_rollups() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let p = repo.path();
+        let a = DiffHunk::test_from_diff(DIFF_A);
+
+        let mut state = ReviewState::new(TEST_REF, None);
+        state.set_diff_hunks(std::slice::from_ref(&a));
+        state.hunks.insert(a.id.clone(), approved_with_key(None));
+        save_review(p, state, None).unwrap();
+
+        let progress = get_review_progress(p, TEST_REF).unwrap();
+        assert_eq!(progress.summary.total_hunks, 1);
+        assert_eq!(progress.summary.approved_hunks, 1);
+        let file = &progress.files["f.txt"];
+        assert_eq!((file.total, file.approved, file.unclassified), (1, 1, 1));
+    }
+
+    #[test]
+    fn review_delta_reports_only_what_changed() {
+        let mut old = ReviewState::new(TEST_REF, None);
+        old.hunks.insert("kept".to_owned(), HunkState::default());
+        old.hunks.insert("changed".to_owned(), HunkState::default());
+        old.hunks.insert("removed".to_owned(), HunkState::default());
+        old.version = 3;
+
+        let mut new = old.clone();
+        new.hunks.remove("removed");
+        new.hunks
+            .insert("changed".to_owned(), approved_with_key(None));
+        new.hunks.insert("added".to_owned(), HunkState::default());
+        new.version += 1;
+        new.updated_at = "2030-01-01T00:00:00.000Z".to_owned();
+
+        let delta = review_delta(&old, &new);
+        assert_eq!((delta.since_version, delta.version), (3, 4));
+        assert_eq!(delta.hunks.keys().collect::<Vec<_>>(), ["added", "changed"]);
+        assert_eq!(delta.removed_hunks, ["removed"]);
+        assert!(delta.state.is_none(), "only the version and time moved");
+
+        new.notes = "looked at the parser".to_owned();
+        let state = review_delta(&old, &new).state.expect("notes changed");
+        assert_eq!(state.notes, "looked at the parser");
+        assert!(state.hunks.is_empty());
+    }
 }
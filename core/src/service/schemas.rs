@@ -0,0 +1,63 @@
+//! Semantic deltas for changed API definitions (OpenAPI documents and
+//! protobuf files).
+
+use anyhow::Context;
+use log::{debug, info};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::diff::api_schema::{compute_schema_delta, may_be_api_schema, FileSchemaDelta};
+use crate::diff::parser::parse_diff;
+use crate::limits::ReviewLimits;
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::{Comparison, DiffSource};
+
+/// Compute API schema deltas for the files that are API definitions. Other
+/// files, and files too large to parse within the review's limits, are
+/// skipped.
+pub fn get_api_schema_deltas(
+    repo_path: &Path,
+    file_paths: &[String],
+    comparison: &Comparison,
+) -> anyhow::Result<Vec<FileSchemaDelta>> {
+    let t0 = Instant::now();
+    let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
+    let old_ref = source.diff_base_ref(comparison);
+    let content_root = source.working_tree_dir(comparison);
+    let limits = ReviewLimits::from_env();
+    let read_ref = |file_path: &str, git_ref: &str| {
+        source
+            .get_file_bytes(file_path, git_ref)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .unwrap_or_default()
+    };
+
+    let mut deltas = Vec::new();
+    for file_path in file_paths.iter().filter(|p| may_be_api_schema(p)) {
+        let old_content = read_ref(file_path, &old_ref);
+        let new_content = match &content_root {
+            Some(dir) => std::fs::read_to_string(dir.join(file_path)).unwrap_or_default(),
+            None => read_ref(file_path, &comparison.head),
+        };
+        if old_content.len().max(new_content.len()) > limits.max_file_bytes {
+            debug!("[get_api_schema_deltas] {file_path} is too large to parse, skipping");
+            continue;
+        }
+        let diff = source
+            .get_diff(comparison, Some(file_path))
+            .context("Failed to get diff")?;
+        let hunks = parse_diff(&diff, file_path);
+        if let Some(delta) = compute_schema_delta(file_path, &old_content, &new_content, &hunks) {
+            deltas.push(delta);
+        }
+    }
+
+    info!(
+        "[get_api_schema_deltas] {} API definitions among {} files in {:?}",
+        deltas.len(),
+        file_paths.len(),
+        t0.elapsed()
+    );
+    Ok(deltas)
+}
@@ -0,0 +1,405 @@
+//! Run Semgrep over a review's changed files.
+//!
+//! [`run_semgrep`] scans only the files the diff touches, as of the review's
+//! head, and caches each file's findings by its content hash and the rule
+//! packs, so a re-run only scans files that changed since. Findings go
+//! through the same pipeline as `review ingest` ([`super::ingest`]); hunks
+//! they flag also get the never-trusted [`SEMGREP_FINDING_LABEL`].
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::classify::{ClassificationResult, ClassifyResponse, Evidence, EvidenceLine};
+use crate::diff::parser::{compute_content_hash, DiffHunk};
+use crate::review::central;
+use crate::review::repo_config::RepoConfig;
+use crate::review::state::{Finding, ReviewState};
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::Comparison;
+
+use super::ingest::{parse_semgrep, Report, ReportedFinding};
+
+/// Label added to hunks with a Semgrep finding.
+pub const SEMGREP_FINDING_LABEL: &str = "security:semgrep-finding";
+
+/// The tool name Semgrep findings are recorded under.
+pub const SEMGREP_TOOL: &str = "semgrep";
+
+/// Bump when the cached shape or how findings are derived changes.
+const CACHE_VERSION: u32 = 1;
+
+/// Whether `config` names Semgrep registry rules (`auto`, a `p/` pack, a
+/// URL), which Semgrep downloads, rather than a local rule file or directory.
+fn is_registry_config(config: &str) -> bool {
+    config == "auto"
+        || config.contains("://")
+        || ["p/", "r/", "s/"]
+            .iter()
+            .any(|prefix| config.starts_with(prefix))
+}
+
+/// The rules to run: `configs` if given, else the repo's `[semgrep]` table.
+/// There's no default: Semgrep's own (`auto`) fetches rules from its registry
+/// and reports project metadata to pick them, so nothing runs until rules are
+/// configured. Local paths are made absolute — against the working directory
+/// for `configs`, the repo for the table — as Semgrep runs in a scratch
+/// directory.
+pub fn resolve_configs(repo_path: &Path, configs: Vec<String>) -> Vec<String> {
+    let (base, configs) = if configs.is_empty() {
+        let table = RepoConfig::load(repo_path).and_then(|c| c.semgrep);
+        (
+            repo_path.to_path_buf(),
+            table.map(|s| s.config).unwrap_or_default(),
+        )
+    } else {
+        (std::env::current_dir().unwrap_or_default(), configs)
+    };
+    configs
+        .into_iter()
+        .map(|config| {
+            if is_registry_config(&config) {
+                config
+            } else {
+                base.join(config).to_string_lossy().into_owned()
+            }
+        })
+        .collect()
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedScan {
+    version: u32,
+    findings: Vec<ReportedFinding>,
+}
+
+fn cache_path(
+    repo_path: &Path,
+    configs: &[String],
+    file_path: &str,
+    content_hash: &str,
+) -> anyhow::Result<PathBuf> {
+    let mut hasher = Sha256::new();
+    for part in configs
+        .iter()
+        .map(String::as_str)
+        .chain([file_path, content_hash])
+    {
+        hasher.update(part.as_bytes());
+        hasher.update(b"\0");
+    }
+    let key = hex::encode(&hasher.finalize()[..16]);
+    Ok(central::get_repo_cache_dir(repo_path)?
+        .join("semgrep")
+        .join(format!("{key}.json")))
+}
+
+fn load_cached(path: &Path) -> Option<Vec<ReportedFinding>> {
+    let cached: CachedScan = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    (cached.version == CACHE_VERSION).then_some(cached.findings)
+}
+
+fn save_cached(path: &Path, findings: Vec<ReportedFinding>) {
+    let cached = CachedScan {
+        version: CACHE_VERSION,
+        findings,
+    };
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(path, serde_json::to_vec(&cached).unwrap_or_default()));
+    if let Err(e) = result {
+        warn!("[semgrep] failed to cache {}: {e}", path.display());
+    }
+}
+
+/// The files a Semgrep run looked at.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScanStats {
+    /// Changed files with content on the head side.
+    pub files: usize,
+    /// Of those, files whose findings came from the cache.
+    pub cached: usize,
+}
+
+/// Scan `dir`'s `files` (relative paths) with `configs`.
+fn scan(dir: &Path, files: &[&str], configs: &[String]) -> anyhow::Result<Report> {
+    let mut command = Command::new("semgrep");
+    command.args([
+        "scan",
+        "--json",
+        "--quiet",
+        "--disable-version-check",
+        "--metrics",
+        "off",
+    ]);
+    for config in configs {
+        command.args(["--config", config]);
+    }
+    let output = command
+        .args(files)
+        .current_dir(dir)
+        .output()
+        .context("Semgrep isn't installed (https://semgrep.dev/docs/getting-started/)")?;
+    if !output.status.success() {
+        bail!(
+            "semgrep failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_semgrep(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Run Semgrep with `configs` over the changed files of `hunks`, as of the
+/// comparison's head. Files whose content was scanned before with the same
+/// rule packs are served from the cache; the rest are copied to a scratch
+/// directory and scanned in one run. Reported paths are repo-relative.
+///
+/// Refused when no rules are configured, and while offline when any of them
+/// come from the registry.
+pub fn run_semgrep(
+    repo_path: &Path,
+    comparison: &Comparison,
+    hunks: &[DiffHunk],
+    configs: &[String],
+) -> anyhow::Result<(Report, ScanStats)> {
+    if configs.is_empty() {
+        bail!(
+            "No Semgrep rules configured: pass `--config <rules file or directory>` or set `[semgrep] config` in .review.toml"
+        );
+    }
+    if configs.iter().any(|config| is_registry_config(config)) {
+        crate::offline::ensure_online("Semgrep registry rules")?;
+    }
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let working_tree = source.working_tree_dir(comparison);
+    let files: BTreeSet<&str> = hunks
+        .iter()
+        .filter(|h| h.new_count > 0)
+        .map(|h| h.file_path.as_str())
+        .collect();
+
+    let mut report = Report {
+        tools: BTreeSet::from([SEMGREP_TOOL.to_owned()]),
+        findings: Vec::new(),
+    };
+    let mut stats = ScanStats::default();
+    let mut pending: BTreeMap<&str, (Vec<u8>, PathBuf)> = BTreeMap::new();
+    for file in files {
+        let content = match &working_tree {
+            Some(dir) => fs::read(dir.join(file)).ok(),
+            None => source.get_file_bytes(file, &comparison.head).ok(),
+        };
+        let Some(content) = content else {
+            continue;
+        };
+        stats.files += 1;
+        let cache = cache_path(repo_path, configs, file, &compute_content_hash(&content))?;
+        match load_cached(&cache) {
+            Some(findings) => {
+                stats.cached += 1;
+                report.findings.extend(findings);
+            }
+            None => {
+                pending.insert(file, (content, cache));
+            }
+        }
+    }
+    if pending.is_empty() {
+        return Ok((report, stats));
+    }
+
+    let scratch = tempfile::tempdir()?;
+    for (file, (content, _)) in &pending {
+        let path = scratch.path().join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+    }
+    let paths: Vec<&str> = pending.keys().copied().collect();
+    info!(
+        "[run_semgrep] scanning {} of {} changed file(s) with {}",
+        paths.len(),
+        stats.files,
+        configs.join(", ")
+    );
+    let scanned = scan(scratch.path(), &paths, configs)?;
+
+    let mut by_file: BTreeMap<&str, Vec<ReportedFinding>> =
+        pending.keys().map(|file| (*file, Vec::new())).collect();
+    for mut finding in scanned.findings {
+        let path = finding.path.trim_start_matches("./").to_owned();
+        if let Some(findings) = by_file.get_mut(path.as_str()) {
+            finding.path = path;
+            findings.push(finding);
+        }
+    }
+    for (file, findings) in by_file {
+        save_cached(&pending[file].1, findings.clone());
+        report.findings.extend(findings);
+    }
+    Ok((report, stats))
+}
+
+/// A classification carrying [`SEMGREP_FINDING_LABEL`] for each hunk with a
+/// Semgrep finding, citing the rules and flagged lines as evidence.
+pub fn finding_labels(findings: &[Finding], hunks: &[DiffHunk]) -> ClassifyResponse {
+    let mut classifications: BTreeMap<&str, ClassificationResult> = BTreeMap::new();
+    for finding in findings.iter().filter(|f| f.tool == SEMGREP_TOOL) {
+        let result = classifications
+            .entry(finding.hunk_id.as_str())
+            .or_insert_with(|| ClassificationResult {
+                label: vec![SEMGREP_FINDING_LABEL.to_owned()],
+                reasoning: String::new(),
+                evidence: Vec::new(),
+            });
+        let rule = finding.rule.clone().unwrap_or_default();
+        if !result.reasoning.is_empty() {
+            result.reasoning.push_str("; ");
+        }
+        let _ = write!(result.reasoning, "Semgrep {rule}: {}", finding.message);
+        let lines = hunks
+            .iter()
+            .find(|h| h.id == finding.hunk_id)
+            .into_iter()
+            .flat_map(|h| &h.lines)
+            .filter(|l| l.new_line_number == Some(finding.line))
+            .map(EvidenceLine::from)
+            .collect();
+        result.evidence.push(Evidence {
+            rule: "semgrep".to_owned(),
+            lines,
+            matched: Some(rule),
+        });
+    }
+    ClassifyResponse {
+        classifications: classifications
+            .into_iter()
+            .map(|(id, result)| (id.to_owned(), result))
+            .collect(),
+        limits_exceeded: Vec::new(),
+        raw_labels: HashMap::new(),
+    }
+}
+
+/// Remove [`SEMGREP_FINDING_LABEL`] from hunks Semgrep no longer flags,
+/// leaving hunks it was the only label of unclassified. Returns how many
+/// hunks lost it.
+pub fn clear_fixed_labels(state: &mut ReviewState) -> usize {
+    let flagged: BTreeSet<String> = state
+        .findings
+        .iter()
+        .filter(|f| f.tool == SEMGREP_TOOL)
+        .map(|f| f.hunk_id.clone())
+        .collect();
+    let mut cleared = 0;
+    for (hunk_id, hunk) in &mut state.hunks {
+        let Some(classification) = &mut hunk.classification else {
+            continue;
+        };
+        if flagged.contains(hunk_id)
+            || !classification
+                .value
+                .iter()
+                .any(|l| l == SEMGREP_FINDING_LABEL)
+        {
+            continue;
+        }
+        classification.value.retain(|l| l != SEMGREP_FINDING_LABEL);
+        if classification.value.is_empty() {
+            // The label was all it had: back to unclassified.
+            hunk.classification = None;
+        }
+        cleared += 1;
+    }
+    cleared
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::state::{Attributed, FindingSeverity, HunkState, Source};
+
+    fn finding(hunk_id: &str, rule: &str) -> Finding {
+        Finding {
+            id: format!("{hunk_id}-{rule}"),
+            tool: SEMGREP_TOOL.to_owned(),
+            format: "semgrep".to_owned(),
+            rule: Some(rule.to_owned()),
+            severity: FindingSeverity::Error,
+            message: "Avoid eval".to_owned(),
+            file_path: "a.py".to_owned(),
+            line: 1,
+            end_line: None,
+            hunk_id: hunk_id.to_owned(),
+            ingested_at: "now".to_owned(),
+        }
+    }
+
+    #[test]
+    fn runs_only_configured_rules_and_no_registry_ones_offline() {
+        use crate::review::central::tests::{setup_test, ENV_LOCK};
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let comparison = Comparison::new("main", "feature");
+        assert!(resolve_configs(repo.path(), Vec::new()).is_empty());
+        let err = run_semgrep(repo.path(), &comparison, &[], &[]).unwrap_err();
+        assert!(err.to_string().starts_with("No Semgrep rules configured"));
+
+        fs::write(
+            repo.path().join(".review.toml"),
+            "[semgrep]\nconfig = [\"p/ci\", \".semgrep/\"]\n",
+        )
+        .unwrap();
+        let configs = resolve_configs(repo.path(), Vec::new());
+        let local = repo.path().join(".semgrep/").to_string_lossy().into_owned();
+        assert_eq!(configs, ["p/ci".to_owned(), local.clone()]);
+
+        std::env::set_var(crate::offline::OFFLINE_ENV, "1");
+        let err = run_semgrep(repo.path(), &comparison, &[], &configs).unwrap_err();
+        assert!(err.to_string().starts_with("Offline mode is on"), "{err}");
+        std::env::remove_var(crate::offline::OFFLINE_ENV);
+    }
+
+    #[test]
+    fn labels_flagged_hunks_and_clears_fixed_ones() {
+        let response = finding_labels(
+            &[
+                finding("a.py:1", "python.eval"),
+                finding("a.py:1", "python.exec"),
+            ],
+            &[],
+        );
+        let result = &response.classifications["a.py:1"];
+        assert_eq!(result.label, [SEMGREP_FINDING_LABEL]);
+        assert_eq!(result.evidence.len(), 2);
+        assert!(result.reasoning.contains("python.exec"));
+
+        let mut state = ReviewState::new("feature", None);
+        for id in ["a.py:1", "a.py:2"] {
+            state.hunks.insert(
+                id.to_owned(),
+                HunkState {
+                    classification: Some(Attributed::new(
+                        vec![SEMGREP_FINDING_LABEL.to_owned(), "imports:added".to_owned()],
+                        Source::Static,
+                    )),
+                    ..Default::default()
+                },
+            );
+        }
+        state.findings.push(finding("a.py:1", "python.eval"));
+        assert_eq!(clear_fixed_labels(&mut state), 1);
+        assert_eq!(state.hunks["a.py:2"].labels(), ["imports:added"]);
+        assert_eq!(state.hunks["a.py:1"].labels().len(), 2);
+    }
+}
@@ -0,0 +1,52 @@
+//! The API server's port, as recorded between starts.
+//!
+//! The server keeps the port it got in `~/.review/server.json` so the next
+//! start tries it first, and so clients that don't run the server — the
+//! `review tailscale` commands, `scripts/dev-web` — can find it. Kept out of
+//! the feature-gated `server` module so those clients build without it.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::review::central::get_central_root;
+
+/// The API server's port when nothing else is configured.
+pub const DEFAULT_PORT: u16 = 3421;
+
+/// What `~/.review/server.json` keeps between starts.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedServer {
+    port: Option<u16>,
+}
+
+fn persisted_server_path() -> Option<PathBuf> {
+    get_central_root().ok().map(|root| root.join("server.json"))
+}
+
+/// The port the API server last listened on, if recorded.
+pub fn persisted_port() -> Option<u16> {
+    let content = std::fs::read_to_string(persisted_server_path()?).ok()?;
+    serde_json::from_str::<PersistedServer>(&content).ok()?.port
+}
+
+/// The port the API server is most likely on: the one it last listened on,
+/// else [`DEFAULT_PORT`].
+pub fn server_port() -> u16 {
+    persisted_port().unwrap_or(DEFAULT_PORT)
+}
+
+/// Record `port` as the one the API server is listening on.
+pub fn persist_port(port: u16) {
+    let Some(path) = persisted_server_path() else {
+        return;
+    };
+    let record = PersistedServer { port: Some(port) };
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&path, serde_json::to_vec_pretty(&record)?));
+    if let Err(e) = written {
+        log::warn!("[server] failed to record port in {}: {e}", path.display());
+    }
+}
@@ -0,0 +1,703 @@
+//! Typed `~/.review/settings.json` — validation, defaults, and the schema the
+//! preferences UI renders from.
+//!
+//! The file is hand-editable, so reads are lenient: unknown keys and invalid
+//! values are reported (and fall back to their defaults) rather than failing
+//! the load. Writes are strict, and keep any unknown keys already in the file
+//! so a typo stays where the user can see and fix it.
+
+use anyhow::Context;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::review::central::get_central_root;
+
+pub const CODE_FONT_SIZE_MIN: u32 = 8;
+pub const CODE_FONT_SIZE_MAX: u32 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffLineDiffType {
+    Word,
+    WordAlt,
+    Char,
+    None,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOverflow {
+    Scroll,
+    Wrap,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    Tree,
+    Flat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffViewMode {
+    Unified,
+    Split,
+    Old,
+    /// Older versions wrote `"file"` for this mode.
+    #[serde(alias = "file")]
+    New,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSortOrder {
+    Updated,
+    Repo,
+    Size,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSortOrder {
+    Name,
+    Size,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentRepo {
+    pub path: String,
+    pub name: String,
+    /// ISO 8601 timestamp.
+    pub last_opened: String,
+}
+
+/// A VS Code-style theme definition registered as a UI theme.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomTheme {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub theme_type: String,
+    #[serde(default)]
+    pub colors: HashMap<String, String>,
+    #[serde(default)]
+    pub token_colors: Vec<Value>,
+}
+
+/// Where to send the review digest. The password, if the relay needs one, is
+/// kept in secret storage as `digest-smtp-password`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestSmtp {
+    /// `smtp://host:port` (upgraded with STARTTLS when offered) or
+    /// `smtps://host:port`.
+    pub url: String,
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+/// Every key `settings.json` understands. Defaults match the desktop UI's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "one field per settings.json key"
+)]
+pub struct Settings {
+    pub code_font_size: u32,
+    pub code_font_family: String,
+    pub code_theme: String,
+    pub ui_theme: String,
+    pub match_vscode_theme: bool,
+    pub custom_themes: Vec<CustomTheme>,
+    pub diff_line_diff_type: DiffLineDiffType,
+    pub diff_overflow: DiffOverflow,
+    pub diff_view_mode: DiffViewMode,
+    pub diff_view_mode_by_extension: HashMap<String, DiffViewMode>,
+    pub changes_display_mode: DisplayMode,
+    pub git_display_mode: DisplayMode,
+    pub file_sort_order: FileSortOrder,
+    pub review_sort_order: ReviewSortOrder,
+    pub show_outline: bool,
+    pub tab_rail_collapsed: bool,
+    pub files_panel_collapsed: bool,
+    pub guide_side_nav_collapsed: bool,
+    pub guide_side_nav_width: f64,
+    pub collapsed_orgs: HashMap<String, bool>,
+    pub collapsed_repos: HashMap<String, bool>,
+    pub collapsed_zones: HashMap<String, bool>,
+    pub recent_repositories: Vec<RecentRepo>,
+    pub working_on_pinned: Vec<String>,
+    pub working_on_dismissed: Vec<String>,
+    pub last_seen_diff_stats: HashMap<String, String>,
+    pub lsp_disabled_languages: Vec<String>,
+    pub sentry_enabled: bool,
+    pub sound_effects_enabled: bool,
+    /// Global shortcut per quick action (`approveHunk`, ...), overriding the
+    /// built-in ones; `""` turns one off.
+    pub quick_action_shortcuts: HashMap<String, String>,
+    /// SMTP relay `review digest --send` mails through.
+    pub digest_smtp: Option<DigestSmtp>,
+    /// Count feature usage locally; see [`crate::metrics`].
+    pub usage_metrics_enabled: bool,
+    /// Where `review stats --usage --submit` posts the counters; empty keeps
+    /// them local.
+    pub usage_metrics_endpoint: String,
+    /// Disable every network and AI call; see [`crate::offline`].
+    pub offline_mode: bool,
+    /// Suggest approving hunks whose exact change another review approved;
+    /// see [`crate::review::embeddings::carry_identical_approvals`].
+    pub carry_identical_approvals: bool,
+    /// GitHub API base per git host, for GitHub Enterprise Server instances
+    /// whose API isn't at `https://<host>/api/v3`; see
+    /// [`crate::sources::github_api::api_url`].
+    pub github_api_urls: HashMap<String, String>,
+    /// Post each PR review's progress as a commit status on the PR head as
+    /// it's saved; see [`crate::service::pr_status`].
+    pub publish_review_status: bool,
+    /// Deepen a shallow clone when a comparison reaches past its history;
+    /// see [`crate::sources::local_git::LocalGitSource::deepen`].
+    pub auto_deepen_shallow_clones: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            code_font_size: 11,
+            code_font_family: "ui-monospace, SFMono-Regular, 'SF Mono', Menlo, Consolas, monospace"
+                .to_owned(),
+            code_theme: "github-dark".to_owned(),
+            ui_theme: "review-dark".to_owned(),
+            match_vscode_theme: false,
+            custom_themes: Vec::new(),
+            diff_line_diff_type: DiffLineDiffType::Word,
+            diff_overflow: DiffOverflow::Scroll,
+            diff_view_mode: DiffViewMode::Split,
+            diff_view_mode_by_extension: HashMap::new(),
+            changes_display_mode: DisplayMode::Tree,
+            git_display_mode: DisplayMode::Tree,
+            file_sort_order: FileSortOrder::Name,
+            review_sort_order: ReviewSortOrder::Updated,
+            show_outline: false,
+            tab_rail_collapsed: false,
+            files_panel_collapsed: false,
+            guide_side_nav_collapsed: false,
+            guide_side_nav_width: 240.0,
+            collapsed_orgs: HashMap::new(),
+            collapsed_repos: HashMap::new(),
+            collapsed_zones: HashMap::new(),
+            recent_repositories: Vec::new(),
+            working_on_pinned: Vec::new(),
+            working_on_dismissed: Vec::new(),
+            last_seen_diff_stats: HashMap::new(),
+            lsp_disabled_languages: Vec::new(),
+            sentry_enabled: false,
+            sound_effects_enabled: true,
+            quick_action_shortcuts: HashMap::new(),
+            digest_smtp: None,
+            usage_metrics_enabled: false,
+            usage_metrics_endpoint: String::new(),
+            offline_mode: false,
+            carry_identical_approvals: false,
+            github_api_urls: HashMap::new(),
+            publish_review_status: false,
+            auto_deepen_shallow_clones: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidSetting {
+    pub key: String,
+    pub message: String,
+}
+
+/// Everything wrong with a settings object.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsErrors {
+    pub unknown_keys: Vec<String>,
+    pub invalid_values: Vec<InvalidSetting>,
+}
+
+impl SettingsErrors {
+    pub fn is_empty(&self) -> bool {
+        self.unknown_keys.is_empty() && self.invalid_values.is_empty()
+    }
+}
+
+impl std::fmt::Display for SettingsErrors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.unknown_keys.is_empty() {
+            parts.push(format!(
+                "unknown settings: {}",
+                self.unknown_keys.join(", ")
+            ));
+        }
+        for invalid in &self.invalid_values {
+            parts.push(format!("invalid {}: {}", invalid.key, invalid.message));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+impl std::error::Error for SettingsErrors {}
+
+/// The settings file as read: every key with its value or default, plus what
+/// was wrong with the file.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsFile {
+    pub settings: Settings,
+    pub errors: SettingsErrors,
+}
+
+/// How the preferences UI should render one setting.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingSchema {
+    pub key: &'static str,
+    /// `boolean`, `number`, `string`, `enum`, `array`, or `object`.
+    pub kind: &'static str,
+    pub description: &'static str,
+    pub default: Value,
+    /// Allowed values of an `enum` setting.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+}
+
+/// `(key, kind, options, description)` for each field of [`Settings`].
+const SCHEMA: &[(&str, &str, &[&str], &str)] = &[
+    (
+        "codeFontSize",
+        "number",
+        &[],
+        "Font size of code, in pixels",
+    ),
+    ("codeFontFamily", "string", &[], "CSS font stack for code"),
+    ("codeTheme", "string", &[], "Syntax highlighting theme"),
+    ("uiTheme", "string", &[], "UI theme ID, bundled or custom"),
+    (
+        "matchVscodeTheme",
+        "boolean",
+        &[],
+        "Follow the active VS Code theme",
+    ),
+    (
+        "customThemes",
+        "array",
+        &[],
+        "VS Code-style theme definitions to add as UI themes",
+    ),
+    (
+        "diffLineDiffType",
+        "enum",
+        &["word", "word-alt", "char", "none"],
+        "Granularity of intra-line diff highlights",
+    ),
+    (
+        "diffOverflow",
+        "enum",
+        &["scroll", "wrap"],
+        "How long diff lines are shown",
+    ),
+    (
+        "diffViewMode",
+        "enum",
+        &["unified", "split", "old", "new"],
+        "Default diff layout",
+    ),
+    (
+        "diffViewModeByExtension",
+        "object",
+        &[],
+        "Diff layout per file extension",
+    ),
+    (
+        "changesDisplayMode",
+        "enum",
+        &["tree", "flat"],
+        "Layout of the changed files list",
+    ),
+    (
+        "gitDisplayMode",
+        "enum",
+        &["tree", "flat"],
+        "Layout of the git status list",
+    ),
+    (
+        "fileSortOrder",
+        "enum",
+        &["name", "size", "modified"],
+        "Sort order of changed files",
+    ),
+    (
+        "reviewSortOrder",
+        "enum",
+        &["updated", "repo", "size"],
+        "Sort order of reviews in the sidebar",
+    ),
+    ("showOutline", "boolean", &[], "Show the symbol outline"),
+    ("tabRailCollapsed", "boolean", &[], "Collapse the tab rail"),
+    (
+        "filesPanelCollapsed",
+        "boolean",
+        &[],
+        "Collapse the files panel",
+    ),
+    (
+        "guideSideNavCollapsed",
+        "boolean",
+        &[],
+        "Collapse the guide navigation",
+    ),
+    (
+        "guideSideNavWidth",
+        "number",
+        &[],
+        "Width of the guide navigation, in pixels",
+    ),
+    (
+        "collapsedOrgs",
+        "object",
+        &[],
+        "Collapsed orgs in the sidebar",
+    ),
+    (
+        "collapsedRepos",
+        "object",
+        &[],
+        "Collapsed repos in the sidebar",
+    ),
+    (
+        "collapsedZones",
+        "object",
+        &[],
+        "Collapsed sidebar sections",
+    ),
+    (
+        "recentRepositories",
+        "array",
+        &[],
+        "Recently opened repositories",
+    ),
+    (
+        "workingOnPinned",
+        "array",
+        &[],
+        "Pinned \"working on\" items",
+    ),
+    (
+        "workingOnDismissed",
+        "array",
+        &[],
+        "Dismissed \"working on\" items",
+    ),
+    (
+        "lastSeenDiffStats",
+        "object",
+        &[],
+        "Last seen diff stats per branch",
+    ),
+    (
+        "lspDisabledLanguages",
+        "array",
+        &[],
+        "Languages whose language server is disabled",
+    ),
+    ("sentryEnabled", "boolean", &[], "Send crash reports"),
+    ("soundEffectsEnabled", "boolean", &[], "Play sound effects"),
+    (
+        "quickActionShortcuts",
+        "object",
+        &[],
+        "Global shortcuts for quick review actions (approveHunk, nextUnreviewed, toggleTrust); \"\" turns one off",
+    ),
+    (
+        "digestSmtp",
+        "object",
+        &[],
+        "SMTP relay for `review digest --send`: {url, from, to, username}",
+    ),
+    (
+        "usageMetricsEnabled",
+        "boolean",
+        &[],
+        "Count anonymous feature usage locally (separate from crash reports)",
+    ),
+    (
+        "usageMetricsEndpoint",
+        "string",
+        &[],
+        "URL usage counters are submitted to; empty keeps them local",
+    ),
+    (
+        "offlineMode",
+        "boolean",
+        &[],
+        "Disable everything that uses the network: AI, GitHub, Tailscale, digest mail, metrics submission, crash reports, update checks",
+    ),
+    (
+        "carryIdenticalApprovals",
+        "boolean",
+        &[],
+        "Suggest approving hunks identical to ones approved in another review (e.g. after a rebase or cherry-pick)",
+    ),
+    (
+        "githubApiUrls",
+        "object",
+        &[],
+        "GitHub API base URL per git host, for GitHub Enterprise Server (e.g. {\"ghe.example.com\": \"https://ghe.example.com/api/v3\"}); unlisted hosts use https://<host>/api/v3",
+    ),
+    (
+        "publishReviewStatus",
+        "boolean",
+        &[],
+        "Post each PR review's progress (\"Review: 34/120 hunks approved\") as a review/progress commit status on the PR head as you review",
+    ),
+    (
+        "autoDeepenShallowClones",
+        "boolean",
+        &[],
+        "Run `git fetch --deepen` when a comparison's base is older than a shallow clone's history, instead of asking first",
+    ),
+];
+
+/// The schema of every setting, with its default.
+pub fn settings_schema() -> Vec<SettingSchema> {
+    let defaults = serde_json::to_value(Settings::default()).unwrap_or_default();
+    SCHEMA
+        .iter()
+        .map(|&(key, kind, options, description)| {
+            let (min, max) = match key {
+                "codeFontSize" => (
+                    Some(f64::from(CODE_FONT_SIZE_MIN)),
+                    Some(f64::from(CODE_FONT_SIZE_MAX)),
+                ),
+                "guideSideNavWidth" => (Some(0.0), None),
+                _ => (None, None),
+            };
+            SettingSchema {
+                key,
+                kind,
+                description,
+                default: defaults.get(key).cloned().unwrap_or(Value::Null),
+                options: options.to_vec(),
+                min,
+                max,
+            }
+        })
+        .collect()
+}
+
+/// Range checks serde can't express.
+fn check_ranges(settings: &Settings) -> Vec<InvalidSetting> {
+    let mut invalid = Vec::new();
+    if !(CODE_FONT_SIZE_MIN..=CODE_FONT_SIZE_MAX).contains(&settings.code_font_size) {
+        invalid.push(InvalidSetting {
+            key: "codeFontSize".to_owned(),
+            message: format!("must be between {CODE_FONT_SIZE_MIN} and {CODE_FONT_SIZE_MAX}"),
+        });
+    }
+    if !settings.guide_side_nav_width.is_finite() || settings.guide_side_nav_width < 0.0 {
+        invalid.push(InvalidSetting {
+            key: "guideSideNavWidth".to_owned(),
+            message: "must be a non-negative number".to_owned(),
+        });
+    }
+    invalid
+}
+
+/// Validate a settings object. Always returns usable settings: unknown keys
+/// are ignored and invalid values replaced by their defaults, and both are
+/// listed in the errors.
+pub fn parse_settings(value: &Value) -> (Settings, SettingsErrors) {
+    let mut errors = SettingsErrors::default();
+    let Some(object) = value.as_object() else {
+        errors.invalid_values.push(InvalidSetting {
+            key: String::new(),
+            message: "settings must be a JSON object".to_owned(),
+        });
+        return (Settings::default(), errors);
+    };
+
+    let mut valid = Map::new();
+    for (key, value) in object {
+        if !SCHEMA.iter().any(|(k, ..)| k == key) {
+            errors.unknown_keys.push(key.clone());
+            continue;
+        }
+        let single = Value::Object(Map::from_iter([(key.clone(), value.clone())]));
+        match serde_json::from_value::<Settings>(single) {
+            Ok(_) => {
+                valid.insert(key.clone(), value.clone());
+            }
+            Err(e) => errors.invalid_values.push(InvalidSetting {
+                key: key.clone(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    let mut settings: Settings = serde_json::from_value(Value::Object(valid)).unwrap_or_default();
+    let out_of_range = check_ranges(&settings);
+    if !out_of_range.is_empty() {
+        let defaults = Settings::default();
+        for invalid in &out_of_range {
+            match invalid.key.as_str() {
+                "codeFontSize" => settings.code_font_size = defaults.code_font_size,
+                "guideSideNavWidth" => {
+                    settings.guide_side_nav_width = defaults.guide_side_nav_width;
+                }
+                _ => {}
+            }
+        }
+        errors.invalid_values.extend(out_of_range);
+    }
+    (settings, errors)
+}
+
+/// `~/.review/settings.json` (respects `$REVIEW_HOME`).
+pub fn settings_path() -> anyhow::Result<PathBuf> {
+    Ok(get_central_root()?.join("settings.json"))
+}
+
+fn read_raw() -> anyhow::Result<Option<Value>> {
+    let path = settings_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let value = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not valid JSON", path.display()))?;
+    Ok(Some(value))
+}
+
+/// Read the settings file, with defaults for anything missing or invalid.
+pub fn read_settings() -> anyhow::Result<SettingsFile> {
+    let (settings, errors) = match read_raw()? {
+        Some(value) => parse_settings(&value),
+        None => (Settings::default(), SettingsErrors::default()),
+    };
+    if !errors.is_empty() {
+        warn!("[settings] settings.json: {errors}");
+    }
+    Ok(SettingsFile { settings, errors })
+}
+
+/// Atomically write JSON to a file (write tmp + rename).
+fn atomic_write_json(path: &std::path::Path, value: &Value) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(value)?)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Validate and write the settings. Rejects unknown keys and invalid values
+/// (with a [`SettingsErrors`] error); keys in the file that aren't settings
+/// are left in place.
+pub fn write_settings(value: &Value) -> anyhow::Result<()> {
+    let (_, errors) = parse_settings(value);
+    if !errors.is_empty() {
+        return Err(errors.into());
+    }
+    let mut merged = match read_raw().ok().flatten() {
+        Some(Value::Object(existing)) => existing
+            .into_iter()
+            .filter(|(key, _)| !SCHEMA.iter().any(|(k, ..)| k == key))
+            .collect(),
+        _ => Map::new(),
+    };
+    if let Some(object) = value.as_object() {
+        merged.extend(object.clone());
+    }
+    atomic_write_json(&settings_path()?, &Value::Object(merged))
+}
+
+/// Create an empty settings file if there isn't one, returning its path.
+pub fn ensure_settings_file() -> anyhow::Result<PathBuf> {
+    let path = settings_path()?;
+    if !path.exists() {
+        atomic_write_json(&path, &serde_json::json!({}))?;
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use serde_json::json;
+
+    #[test]
+    fn schema_covers_every_setting() {
+        let defaults = serde_json::to_value(Settings::default()).unwrap();
+        let mut fields: Vec<_> = defaults.as_object().unwrap().keys().cloned().collect();
+        let mut keys: Vec<_> = settings_schema().iter().map(|s| s.key.to_owned()).collect();
+        fields.sort();
+        keys.sort();
+        assert_eq!(fields, keys);
+    }
+
+    #[test]
+    fn parse_reports_unknown_and_invalid_keys() {
+        let (settings, errors) = parse_settings(&json!({
+            "codeFontSise": 14,
+            "diffOverflow": "wrap",
+            "diffViewMode": "file",
+            "diffLineDiffType": "lines",
+            "codeFontSize": 64,
+        }));
+        assert_eq!(errors.unknown_keys, vec!["codeFontSise"]);
+        let invalid: Vec<_> = errors
+            .invalid_values
+            .iter()
+            .map(|i| i.key.as_str())
+            .collect();
+        assert_eq!(invalid, vec!["diffLineDiffType", "codeFontSize"]);
+
+        assert_eq!(settings.diff_overflow, DiffOverflow::Wrap);
+        assert_eq!(settings.diff_view_mode, DiffViewMode::New);
+        assert_eq!(settings.diff_line_diff_type, DiffLineDiffType::Word);
+        assert_eq!(settings.code_font_size, 11);
+    }
+
+    #[test]
+    fn write_rejects_invalid_and_keeps_unknown_keys_in_file() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, _repo) = setup_test();
+        let path = settings_path().unwrap();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, r#"{"codeFontSise": 14, "showOutline": true}"#).unwrap();
+
+        let file = read_settings().unwrap();
+        assert!(file.settings.show_outline);
+        assert_eq!(file.errors.unknown_keys, vec!["codeFontSise"]);
+
+        let err = write_settings(&json!({"diffOverflow": "fold"})).unwrap_err();
+        assert!(err.downcast_ref::<SettingsErrors>().is_some());
+
+        write_settings(&json!({"showOutline": false})).unwrap();
+        let written: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, json!({"codeFontSise": 14, "showOutline": false}));
+    }
+}
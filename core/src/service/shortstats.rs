@@ -0,0 +1,301 @@
+//! Diff shortstats for dashboards, batched and cached.
+//!
+//! A committed comparison's shortstat is fully determined by the SHAs its base
+//! and head resolve to — the same pair freshness checking fingerprints a review
+//! by — so it is cached under `<base-sha>..<head-sha>` in `shortstats.json` in
+//! the repo's cache dir and never goes stale. Comparisons of the working tree or
+//! index have no such fingerprint and are always recomputed.
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::freshness::missing_refs_from_resolved;
+use crate::review::central;
+use crate::review::state::now_iso8601;
+use crate::sources::local_git::{DiffShortStat, LocalGitError, LocalGitSource};
+use crate::sources::traits::Comparison;
+
+/// Filename of the shortstat cache in a repo's cache dir.
+pub const SHORTSTAT_CACHE_FILE: &str = "shortstats.json";
+
+/// Entries kept per repo; the oldest are evicted first.
+const MAX_CACHED: usize = 512;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedShortStat {
+    stats: DiffShortStat,
+    cached_at: String,
+}
+
+/// One repo's cache, loaded for the duration of a batch.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ShortStatCache {
+    entries: BTreeMap<String, CachedShortStat>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+fn cache_path(repo_path: &Path) -> Option<PathBuf> {
+    central::get_repo_cache_dir(repo_path)
+        .ok()
+        .map(|dir| dir.join(SHORTSTAT_CACHE_FILE))
+}
+
+impl ShortStatCache {
+    /// Load the repo's cache; missing or unreadable means empty.
+    fn load(repo_path: &Path) -> Self {
+        cache_path(repo_path)
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist new entries (atomic: write tmp + rename). Best-effort: a
+    /// failed write only costs a recompute next time.
+    fn save(mut self, repo_path: &Path) {
+        if !self.dirty {
+            return;
+        }
+        if self.entries.len() > MAX_CACHED {
+            let mut by_age: Vec<(String, String)> = self
+                .entries
+                .iter()
+                .map(|(key, entry)| (entry.cached_at.clone(), key.clone()))
+                .collect();
+            by_age.sort();
+            let excess = self.entries.len() - MAX_CACHED;
+            for (_, key) in by_age.into_iter().take(excess) {
+                self.entries.remove(&key);
+            }
+        }
+        let Some(path) = cache_path(repo_path) else {
+            return;
+        };
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| {
+                let tmp_path = path.with_extension("json.tmp");
+                fs::write(&tmp_path, serde_json::to_string(&self).unwrap_or_default())?;
+                fs::rename(&tmp_path, &path)
+            });
+        if let Err(e) = result {
+            warn!("[shortstats] failed to write {}: {e}", path.display());
+        }
+    }
+
+    /// The cached stats for `fingerprint`, computing and caching on a miss.
+    /// Without a fingerprint the stats are computed and not cached.
+    fn get_or_compute(
+        &mut self,
+        source: &LocalGitSource,
+        comparison: &Comparison,
+        fingerprint: Option<String>,
+    ) -> Result<DiffShortStat, LocalGitError> {
+        let Some(fingerprint) = fingerprint else {
+            return source.get_diff_shortstat(comparison);
+        };
+        if let Some(entry) = self.entries.get(&fingerprint) {
+            return Ok(entry.stats.clone());
+        }
+        let stats = source.get_diff_shortstat(comparison)?;
+        self.entries.insert(
+            fingerprint,
+            CachedShortStat {
+                stats: stats.clone(),
+                cached_at: now_iso8601(),
+            },
+        );
+        self.dirty = true;
+        Ok(stats)
+    }
+}
+
+/// The cache key for a committed comparison whose base and head resolved to
+/// `old_sha` and `new_sha`.
+fn sha_fingerprint(old_sha: &str, new_sha: &str) -> String {
+    format!("{old_sha}..{new_sha}")
+}
+
+/// The cache key for `comparison`, or `None` when it diffs uncommitted changes.
+/// Errors when a ref no longer exists, rather than diffing against the empty
+/// tree.
+fn comparison_fingerprint(
+    source: &LocalGitSource,
+    comparison: &Comparison,
+) -> Result<Option<String>, String> {
+    if source.diffs_uncommitted(comparison) {
+        return Ok(None);
+    }
+    let resolved_old = source.resolve_ref_or_empty_tree(&comparison.base);
+    let resolved_new = source.resolve_ref_or_empty_tree(&comparison.head);
+    let missing = missing_refs_from_resolved(comparison, &resolved_old, &resolved_new);
+    if !missing.is_empty() {
+        return Err(format!("Missing ref(s): {}", missing.join(", ")));
+    }
+    Ok(Some(sha_fingerprint(&resolved_old, &resolved_new)))
+}
+
+/// Shortstat of a committed comparison already resolved to `old_sha` and
+/// `new_sha` (as freshness checking does), through the cache.
+pub fn cached_diff_shortstat(
+    repo_path: &Path,
+    source: &LocalGitSource,
+    comparison: &Comparison,
+    old_sha: &str,
+    new_sha: &str,
+) -> Result<DiffShortStat, LocalGitError> {
+    let mut cache = ShortStatCache::load(repo_path);
+    let stats = cache.get_or_compute(source, comparison, Some(sha_fingerprint(old_sha, new_sha)));
+    cache.save(repo_path);
+    stats
+}
+
+/// One comparison to compute a shortstat for.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortStatRequest {
+    pub repo_path: String,
+    pub comparison: Comparison,
+}
+
+/// A comparison's shortstat, or why it couldn't be computed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortStatResult {
+    pub repo_path: String,
+    /// The comparison's key (`base..head`).
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stats: Option<DiffShortStat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Shortstats for many comparisons, possibly across repos, in request order.
+/// Each repo's cache is read and written once per batch.
+pub fn get_diff_shortstats(requests: &[ShortStatRequest]) -> Vec<ShortStatResult> {
+    let t0 = Instant::now();
+    let mut by_repo: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, request) in requests.iter().enumerate() {
+        by_repo
+            .entry(request.repo_path.clone())
+            .or_default()
+            .push(i);
+    }
+
+    let mut results: Vec<Option<ShortStatResult>> = vec![None; requests.len()];
+    let mut hits = 0;
+    for (repo_path, indices) in by_repo {
+        let repo = PathBuf::from(&repo_path);
+        let source = LocalGitSource::new(repo.clone());
+        let mut cache = ShortStatCache::load(&repo);
+        let cached_before = cache.entries.len();
+        for i in indices {
+            let comparison = &requests[i].comparison;
+            let stats = source
+                .as_ref()
+                .map_err(ToString::to_string)
+                .and_then(|source| {
+                    let fingerprint = comparison_fingerprint(source, comparison)?;
+                    hits += usize::from(
+                        fingerprint
+                            .as_ref()
+                            .is_some_and(|f| cache.entries.contains_key(f)),
+                    );
+                    cache
+                        .get_or_compute(source, comparison, fingerprint)
+                        .map_err(|e| e.to_string())
+                });
+            let (stats, error) = match stats {
+                Ok(stats) => (Some(stats), None),
+                Err(e) => (None, Some(e)),
+            };
+            results[i] = Some(ShortStatResult {
+                repo_path: repo_path.clone(),
+                key: comparison.key.clone(),
+                stats,
+                error,
+            });
+        }
+        if cache.entries.len() != cached_before {
+            info!(
+                "[get_diff_shortstats] {repo_path}: cached {} new",
+                cache.entries.len() - cached_before
+            );
+        }
+        cache.save(&repo);
+    }
+
+    info!(
+        "[get_diff_shortstats] {} comparisons, {hits} cached, in {:?}",
+        requests.len(),
+        t0.elapsed()
+    );
+    results.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn batch_caches_committed_comparisons() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let root = repo.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        fs::write(root.join("a.txt"), "one\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "init"]);
+        git(root, &["checkout", "-qb", "feature"]);
+        fs::write(root.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        git(root, &["commit", "-qam", "more"]);
+        // With `feature` checked out its comparison would be a working-tree one.
+        git(root, &["checkout", "-q", "main"]);
+
+        let request = |base: &str, head: &str| ShortStatRequest {
+            repo_path: root.to_string_lossy().into_owned(),
+            comparison: Comparison::new(base, head),
+        };
+        let requests = vec![request("main", "feature"), request("main", "nope")];
+        let expected = DiffShortStat {
+            file_count: 1,
+            additions: 2,
+            deletions: 0,
+        };
+
+        let results = get_diff_shortstats(&requests);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].key, "main..feature");
+        assert_eq!(results[0].stats, Some(expected));
+        assert_eq!(results[1].stats, None);
+        assert_eq!(results[1].error.as_deref(), Some("Missing ref(s): nope"));
+        let mut cache = ShortStatCache::load(root);
+        assert_eq!(cache.entries.len(), 1);
+
+        // A cached entry is served without re-running git.
+        cache.entries.values_mut().next().unwrap().stats.additions = 99;
+        cache.dirty = true;
+        cache.save(root);
+        let results = get_diff_shortstats(&requests);
+        assert_eq!(results[0].stats.as_ref().unwrap().additions, 99);
+    }
+}
@@ -0,0 +1,360 @@
+//! Split a long commit range into several logical reviews.
+//!
+//! When a mega-branch lands, one review of all of it is hard to divide up.
+//! [`propose_split`] cuts the review's commits (first-parent, oldest first)
+//! into runs of consecutive commits — by Claude when a model is given, else
+//! by clustering commits that touch the same directories. Because the runs
+//! are consecutive, each part is itself a comparison: its last commit,
+//! reviewed against the commit before its first.
+//!
+//! [`create_split_reviews`] saves one review per part, linked both ways: each
+//! part's `splitFrom` names the parent review, and the parent's `splitInto`
+//! lists the parts in order.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::ai::split::{split_commits_with_model, CommitSegment};
+use crate::review::state::{Source, SplitLink};
+use crate::review::storage;
+use crate::sources::local_git::{LocalGitSource, RangeCommit};
+use crate::sources::traits::Comparison;
+
+use super::targets;
+
+/// Default upper bound on the number of reviews a range is split into.
+pub const DEFAULT_MAX_REVIEWS: usize = 6;
+
+/// Areas named in a static part's title.
+const TITLE_AREAS: usize = 2;
+
+/// One proposed review: a run of consecutive commits.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProposedReview {
+    pub title: String,
+    pub reason: String,
+    /// The part's review ref: its last commit's SHA.
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// The part's base override: the commit before its first.
+    pub base: String,
+    /// Full SHAs, oldest first.
+    pub commits: Vec<String>,
+    /// Files the part's commits touched, sorted.
+    pub files: Vec<String>,
+}
+
+/// A proposed split of one review's commit range.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitPlan {
+    /// The review being split.
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub comparison: Comparison,
+    /// Who proposed the cut: `ai` or `static`.
+    pub source: Source,
+    pub reviews: Vec<ProposedReview>,
+    /// Why the AI proposal was abandoned for the static one, if it was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ai_error: Option<String>,
+}
+
+/// A file's area: the first two directories of its path, or `.` for files at
+/// the repo root.
+fn area(file: &str) -> &str {
+    let Some(dir_end) = file.rfind('/') else {
+        return ".";
+    };
+    let dir = &file[..dir_end];
+    match dir.match_indices('/').nth(1) {
+        Some((second_slash, _)) => &dir[..second_slash],
+        None => dir,
+    }
+}
+
+/// Title for a run of commits: the one commit's subject, else its busiest
+/// areas.
+fn static_title(commits: &[RangeCommit]) -> String {
+    if let [commit] = commits {
+        return commit.subject.clone();
+    }
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for file in commits.iter().flat_map(|c| &c.files) {
+        *counts.entry(area(file)).or_default() += 1;
+    }
+    let mut areas: Vec<(&str, usize)> = counts.into_iter().collect();
+    areas.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    let names: Vec<&str> = areas
+        .iter()
+        .take(TITLE_AREAS)
+        .map(|(name, _)| *name)
+        .collect();
+    if names.is_empty() {
+        format!("{} commits", commits.len())
+    } else {
+        names.join(", ")
+    }
+}
+
+/// Cluster `commits` without a model: a commit joins the current run when it
+/// shares an area with it, else starts a new one. While there are more than
+/// `max_reviews` runs, the adjacent pair with the fewest commits is merged.
+pub fn cluster_commits(commits: &[RangeCommit], max_reviews: usize) -> Vec<CommitSegment> {
+    let mut runs: Vec<(usize, usize, BTreeSet<&str>)> = Vec::new();
+    for (i, commit) in commits.iter().enumerate() {
+        let areas: BTreeSet<&str> = commit.files.iter().map(|f| area(f)).collect();
+        match runs.last_mut() {
+            Some((_, last, run_areas)) if areas.is_empty() || !run_areas.is_disjoint(&areas) => {
+                *last = i;
+                run_areas.extend(areas);
+            }
+            _ => runs.push((i, i, areas)),
+        }
+    }
+
+    while runs.len() > max_reviews.max(1) {
+        let Some(merge_at) = (0..runs.len() - 1).min_by_key(|&i| runs[i + 1].1 - runs[i].0) else {
+            break;
+        };
+        let (_, last, areas) = runs.remove(merge_at + 1);
+        runs[merge_at].1 = last;
+        runs[merge_at].2.extend(areas);
+    }
+
+    runs.into_iter()
+        .map(|(first, last, areas)| {
+            let run = &commits[first..=last];
+            CommitSegment {
+                first,
+                last,
+                title: static_title(run),
+                reason: format!(
+                    "{} commit{} touching {}",
+                    run.len(),
+                    if run.len() == 1 { "" } else { "s" },
+                    areas.into_iter().collect::<Vec<_>>().join(", ")
+                ),
+            }
+        })
+        .collect()
+}
+
+fn proposed_reviews(
+    commits: &[RangeCommit],
+    segments: Vec<CommitSegment>,
+    range_base: &str,
+) -> Vec<ProposedReview> {
+    segments
+        .into_iter()
+        .map(|segment| {
+            let run = &commits[segment.first..=segment.last];
+            let files: BTreeSet<&String> = run.iter().flat_map(|c| &c.files).collect();
+            ProposedReview {
+                title: segment.title,
+                reason: segment.reason,
+                ref_name: commits[segment.last].hash.clone(),
+                base: match segment.first {
+                    0 => range_base.to_owned(),
+                    first => commits[first - 1].hash.clone(),
+                },
+                commits: run.iter().map(|c| c.hash.clone()).collect(),
+                files: files.into_iter().cloned().collect(),
+            }
+        })
+        .collect()
+}
+
+/// Propose splitting the review of `ref_name` into at most `max_reviews`
+/// parts. With a `model`, Claude proposes the cut and the static clustering
+/// is the fallback if it fails; without one, only the static clustering runs.
+pub fn propose_split(
+    repo_path: &Path,
+    ref_name: &str,
+    base_override: Option<&str>,
+    model: Option<&str>,
+    max_reviews: usize,
+) -> anyhow::Result<SplitPlan> {
+    let review = targets::resolve(repo_path, ref_name, base_override)?;
+    let comparison = review.comparison;
+    if comparison.has_uncommitted_endpoint() {
+        bail!("{} has no commits to split", comparison.key);
+    }
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let range_base = source.diff_base_ref(&comparison);
+    let commits = source
+        .list_range_commits(&range_base, &comparison.head)
+        .with_context(|| format!("Failed to list the commits of {}", comparison.key))?;
+    if commits.len() < 2 {
+        bail!(
+            "{} has {} commit(s); nothing to split",
+            comparison.key,
+            commits.len()
+        );
+    }
+
+    let mut ai_error = None;
+    let ai_segments = model.and_then(|model| {
+        split_commits_with_model(&commits, max_reviews, model, repo_path)
+            .inspect_err(|e| {
+                warn!("[propose_split] falling back to static clustering: {e}");
+                ai_error = Some(e.to_string());
+            })
+            .ok()
+    });
+    let (source, segments) = match ai_segments {
+        Some(segments) => (Source::Ai, segments),
+        None => (Source::Static, cluster_commits(&commits, max_reviews)),
+    };
+
+    info!(
+        "[propose_split] {}: {} commits into {} reviews ({})",
+        comparison.key,
+        commits.len(),
+        segments.len(),
+        source.as_str()
+    );
+    Ok(SplitPlan {
+        ref_name: review.ref_name,
+        reviews: proposed_reviews(&commits, segments, &range_base),
+        comparison,
+        source,
+        ai_error,
+    })
+}
+
+/// Save a review for each part of `plan` and link them to the parent review.
+/// Returns the parts' refs. A part whose review already exists is reused,
+/// unless it is pinned to a different base.
+pub fn create_split_reviews(repo_path: &Path, plan: &SplitPlan) -> anyhow::Result<Vec<String>> {
+    let parts = plan.reviews.len();
+    for (i, part) in plan.reviews.iter().enumerate() {
+        storage::ensure_review_exists(repo_path, &part.ref_name, Some(part.base.clone()), None)?;
+        let mut state = storage::load_review_state(repo_path, &part.ref_name)?;
+        if state.base_override.as_deref() != Some(part.base.as_str()) {
+            bail!(
+                "A review of {} already exists against a different base",
+                part.ref_name
+            );
+        }
+        state.split_from = Some(SplitLink {
+            parent: plan.ref_name.clone(),
+            part: i + 1,
+            parts,
+            title: part.title.clone(),
+        });
+        storage::save_review_state(repo_path, &state)?;
+    }
+
+    storage::ensure_review_exists(repo_path, &plan.ref_name, None, None)?;
+    let mut parent = storage::load_review_state(repo_path, &plan.ref_name)?;
+    parent.split_into = plan.reviews.iter().map(|p| p.ref_name.clone()).collect();
+    storage::save_review_state(repo_path, &parent)?;
+    Ok(parent.split_into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::sources::traits::DiffSource;
+    use std::fs;
+    use std::process::Command;
+
+    fn commit(hash: &str, files: &[&str]) -> RangeCommit {
+        RangeCommit {
+            hash: hash.to_owned(),
+            subject: format!("commit {hash}"),
+            files: files.iter().map(|f| (*f).to_owned()).collect(),
+        }
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn clusters_consecutive_commits_by_area() {
+        let commits = [
+            commit("a", &["src/db/schema.rs"]),
+            commit("b", &["src/db/migrate.rs", "README.md"]),
+            commit("c", &["src/api/routes.rs"]),
+            commit("d", &["src/api/handlers.rs"]),
+            commit("e", &["ui/app.tsx"]),
+        ];
+        let spans = |segments: &[CommitSegment]| {
+            segments
+                .iter()
+                .map(|s| (s.first, s.last))
+                .collect::<Vec<_>>()
+        };
+
+        let segments = cluster_commits(&commits, 6);
+        assert_eq!(spans(&segments), [(0, 1), (2, 3), (4, 4)]);
+        assert_eq!(segments[1].title, "src/api");
+        assert_eq!(segments[2].title, "commit e");
+
+        // Over the limit, the smallest neighbouring runs merge first.
+        assert_eq!(spans(&cluster_commits(&commits, 2)), [(0, 1), (2, 4)]);
+    }
+
+    #[test]
+    fn creates_linked_reviews() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let root = repo.path();
+        git(root, &["init", "-q", "-b", "main"]);
+        fs::write(root.join("README.md"), "hi\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "init"]);
+        git(root, &["checkout", "-qb", "feature"]);
+        for (dir, name) in [("db", "a"), ("db", "b"), ("api", "c")] {
+            fs::create_dir_all(root.join("src").join(dir)).unwrap();
+            fs::write(root.join("src").join(dir).join(name), name).unwrap();
+            git(root, &["add", "."]);
+            git(root, &["commit", "-qm", name]);
+        }
+        git(root, &["checkout", "-q", "main"]);
+
+        let plan = propose_split(root, "feature", None, None, 6).unwrap();
+        assert_eq!(plan.source, Source::Static);
+        assert_eq!(plan.reviews.len(), 2);
+        assert_eq!(plan.reviews[0].files, ["src/db/a", "src/db/b"]);
+        assert_eq!(plan.reviews[1].base, plan.reviews[0].ref_name);
+
+        let refs = create_split_reviews(root, &plan).unwrap();
+        assert_eq!(
+            refs,
+            [
+                plan.reviews[0].ref_name.clone(),
+                plan.reviews[1].ref_name.clone()
+            ]
+        );
+        let parent = storage::load_review_state(root, "feature").unwrap();
+        assert_eq!(parent.split_into, refs);
+        let second = storage::load_review_state(root, &refs[1]).unwrap();
+        assert_eq!(second.base_override.as_deref(), Some(refs[0].as_str()));
+        let link = second.split_from.unwrap();
+        assert_eq!(
+            (link.parent.as_str(), link.part, link.parts),
+            ("feature", 2, 2)
+        );
+
+        // The part reviews only the commits in it.
+        let part = targets::resolve(root, &refs[1], None).unwrap();
+        let source = LocalGitSource::new(root.to_path_buf()).unwrap();
+        let diff = source.get_diff(&part.comparison, None).unwrap();
+        assert!(diff.contains("src/api/c") && !diff.contains("src/db/"));
+    }
+}
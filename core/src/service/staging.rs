@@ -0,0 +1,36 @@
+//! Stage a review guide group — one logical change — in a single step.
+
+use std::path::Path;
+
+use anyhow::{bail, Context};
+
+use crate::review::storage;
+use crate::sources::local_git::LocalGitSource;
+
+/// Stage every hunk of the guide group at `group_index` in review `ref_name`,
+/// across all its files, all or nothing. Returns the staged hunk IDs.
+pub fn stage_hunk_group(
+    repo_path: &Path,
+    ref_name: &str,
+    group_index: usize,
+) -> anyhow::Result<Vec<String>> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let groups = state
+        .guide
+        .as_ref()
+        .and_then(|g| g.state.as_ref())
+        .map(|generated| generated.groups.as_slice())
+        .unwrap_or_default();
+    let Some(group) = groups.get(group_index) else {
+        bail!(
+            "{ref_name} has no guide group {} ({} groups)",
+            group_index + 1,
+            groups.len()
+        );
+    };
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    source
+        .stage_hunk_ids(&group.hunk_ids)
+        .with_context(|| format!("Failed to stage \"{}\"", group.title))?;
+    Ok(group.hunk_ids.clone())
+}
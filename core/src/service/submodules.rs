@@ -0,0 +1,233 @@
+//! Multi-root review for repos with submodules. A superproject diff only shows
+//! a submodule as a moved gitlink ("Subproject commit abc → def"); the actual
+//! change lives in the submodule's own history. This module expands each
+//! changed submodule into a nested review scope so one review covers the whole
+//! delivered change.
+//!
+//! Each scope is a review in its own right: its comparison is resolved inside
+//! the submodule checkout (recorded old commit → new commit, or the checkout's
+//! working tree when the parent review includes one), and its decisions live in
+//! the submodule's own store — the same store `review -r <submodule>` uses.
+//!
+//! A single pointer hunk (see [`SubmoduleHunk`]) can also be expanded in place
+//! into the submodule's diff between its two commits, without a scope.
+
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::classify::{classify_repo_hunks_static, ClassifyResponse};
+use crate::diff::parser::{DiffHunk, SubmoduleHunk};
+use crate::review::state::ReviewSummary;
+use crate::review::storage;
+use crate::sources::local_git::{LocalGitSource, SubmoduleChange};
+use crate::sources::traits::Comparison;
+
+use super::files::comparison_hunks;
+use super::review_io::reconcile_review;
+
+/// One changed submodule, expanded into a nested review scope.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleScope {
+    /// Submodule path, relative to the superproject root.
+    pub path: String,
+    /// Absolute path of the submodule checkout — the repo its review lives in.
+    pub repo_path: String,
+    /// Review identity inside the submodule (the new commit, or its checked-out
+    /// branch when the parent review includes the working tree).
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    /// The submodule-side comparison; `base` is the commit the parent recorded.
+    pub comparison: Comparison,
+    /// False when the submodule isn't checked out (or was removed), so its
+    /// changes can't be diffed — `hunks` is then empty.
+    pub checked_out: bool,
+    /// Hunks in the submodule, with paths relative to the submodule root.
+    pub hunks: Vec<DiffHunk>,
+    /// Static (rule-based) classification of `hunks`.
+    pub classification: ClassifyResponse,
+    /// Progress of the submodule's review, reconciled against `hunks`.
+    pub summary: ReviewSummary,
+}
+
+/// Expand every submodule changed by the parent review into a nested scope.
+pub fn submodule_scopes(repo_path: &Path, ref_name: &str) -> anyhow::Result<Vec<SubmoduleScope>> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let resolved = super::targets::resolve(repo_path, ref_name, state.base_override.as_deref())?;
+    scopes_for_comparison(repo_path, &resolved.comparison)
+}
+
+/// Expand the submodules changed by `comparison` into nested scopes.
+pub fn scopes_for_comparison(
+    repo_path: &Path,
+    comparison: &Comparison,
+) -> anyhow::Result<Vec<SubmoduleScope>> {
+    let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
+    let working_tree = source.include_working_tree(comparison);
+    source
+        .changed_submodules(comparison)?
+        .into_iter()
+        .map(|change| build_scope(repo_path, change, working_tree))
+        .collect()
+}
+
+fn build_scope(
+    repo_path: &Path,
+    change: SubmoduleChange,
+    working_tree: bool,
+) -> anyhow::Result<SubmoduleScope> {
+    let SubmoduleChange {
+        path,
+        old_sha,
+        new_sha,
+    } = change;
+    let sub_path = repo_path.join(&path);
+    let base = old_sha.unwrap_or_else(|| LocalGitSource::EMPTY_TREE.to_owned());
+    let sub_source = new_sha
+        .as_ref()
+        .and_then(|_| LocalGitSource::new(sub_path.clone()).ok());
+
+    // In working-tree mode the scope follows whatever is checked out in the
+    // submodule (a branch, or "HEAD" when detached) so its uncommitted edits
+    // are part of the review; otherwise it's pinned to the recorded commit.
+    let head = match (&sub_source, working_tree) {
+        (Some(sub), true) => sub.get_current_branch().ok(),
+        _ => None,
+    }
+    .or_else(|| new_sha.clone())
+    .unwrap_or_default();
+    let comparison = Comparison::new(base.clone(), head.clone());
+
+    let hunks = match &sub_source {
+        Some(_) => comparison_hunks(&sub_path, &comparison, None)
+            .with_context(|| format!("Failed to diff submodule {path}"))?,
+        None => Vec::new(),
+    };
+
+    let mut state = storage::load_review_state(&sub_path, &head)?;
+    state.base_override.get_or_insert(base);
+    let mut state = reconcile_review(state, &hunks).state;
+    state.set_diff_hunks(&hunks);
+
+    Ok(SubmoduleScope {
+        repo_path: sub_path.to_string_lossy().into_owned(),
+        path,
+        ref_name: head,
+        classification: classify_repo_hunks_static(&sub_path, &hunks),
+        summary: state.to_summary(),
+        checked_out: sub_source.is_some(),
+        comparison,
+        hunks,
+    })
+}
+
+/// The submodule diff behind a pointer hunk: its old commit to its new one,
+/// with paths relative to the submodule root. `None` when `hunk` isn't a
+/// pointer change, or the submodule isn't checked out or lacks either commit
+/// (`git submodule update` fetches them); an added or removed submodule
+/// diffs against the empty tree.
+pub fn expand_submodule_hunk(
+    repo_path: &Path,
+    hunk: &DiffHunk,
+) -> anyhow::Result<Option<Vec<DiffHunk>>> {
+    let Some(SubmoduleHunk {
+        old_sha, new_sha, ..
+    }) = &hunk.submodule
+    else {
+        return Ok(None);
+    };
+    let sub_path = repo_path.join(&hunk.file_path);
+    if !sub_path.join(".git").exists() {
+        return Ok(None);
+    }
+    let sub = LocalGitSource::new(sub_path.clone()).context("Failed to open submodule")?;
+    let side = |sha: &Option<String>| match sha {
+        Some(sha) => sub.ref_exists(sha).then(|| sha.clone()),
+        None => Some(LocalGitSource::EMPTY_TREE.to_owned()),
+    };
+    let (Some(base), Some(head)) = (side(old_sha), side(new_sha)) else {
+        return Ok(None);
+    };
+    let hunks = comparison_hunks(&sub_path, &Comparison::new(base, head), None)
+        .with_context(|| format!("Failed to diff submodule {}", hunk.file_path))?;
+    Ok(Some(hunks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-c", "protocol.file.allow=always"])
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8(output.stdout).unwrap().trim().to_owned()
+    }
+
+    #[test]
+    fn submodule_changes_become_nested_scopes() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, root) = setup_test();
+        let lib = root.path().join("lib-src");
+        let parent = root.path().join("app");
+        for dir in [&lib, &parent] {
+            std::fs::create_dir_all(dir).unwrap();
+            git(dir, &["init", "-q"]);
+        }
+        std::fs::write(lib.join("lib.rs"), "fn a() {}\n").unwrap();
+        git(&lib, &["add", "."]);
+        git(&lib, &["commit", "-qm", "lib"]);
+        std::fs::write(parent.join("main.rs"), "fn main() {}\n").unwrap();
+        git(&parent, &["add", "."]);
+        git(&parent, &["commit", "-qm", "app"]);
+        git(&parent, &["submodule", "add", "-q", "../lib-src", "lib"]);
+        git(&parent, &["commit", "-qm", "add lib"]);
+        let base = git(&parent, &["rev-parse", "HEAD"]);
+        let old_sha = git(&parent.join("lib"), &["rev-parse", "HEAD"]);
+
+        // Bump the submodule with a real change and record it in the parent.
+        let sub = parent.join("lib");
+        std::fs::write(sub.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        git(&sub, &["commit", "-qam", "add b"]);
+        let new_sha = git(&sub, &["rev-parse", "HEAD"]);
+        git(&parent, &["commit", "-qam", "bump lib"]);
+        let head = git(&parent, &["rev-parse", "HEAD"]);
+
+        let comparison = Comparison::new(base, head);
+        let scopes = scopes_for_comparison(&parent, &comparison).unwrap();
+        assert_eq!(scopes.len(), 1);
+        let scope = &scopes[0];
+        assert_eq!(scope.path, "lib");
+        assert!(scope.checked_out);
+        assert_eq!(scope.comparison.base, old_sha);
+        assert_eq!(scope.ref_name, new_sha);
+        assert_eq!(scope.hunks.len(), 1);
+        assert_eq!(scope.hunks[0].file_path, "lib.rs");
+        assert_eq!(scope.summary.total_hunks, 1);
+        assert_eq!(scope.summary.reviewed_hunks, 0);
+
+        // The parent's own hunk for the bump is a pointer change, and expands
+        // into the same diff.
+        let parent_hunks = comparison_hunks(&parent, &comparison, None).unwrap();
+        let pointer = parent_hunks
+            .iter()
+            .find(|hunk| hunk.file_path == "lib")
+            .unwrap();
+        assert_eq!(
+            pointer.submodule.as_ref().unwrap().new_sha.as_deref(),
+            Some(new_sha.as_str())
+        );
+        let expanded = expand_submodule_hunk(&parent, pointer).unwrap().unwrap();
+        assert_eq!(expanded.len(), 1);
+        assert_eq!(expanded[0].file_path, "lib.rs");
+    }
+}
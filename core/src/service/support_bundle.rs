@@ -0,0 +1,299 @@
+//! Support bundles: recent logs, the settings file, and environment
+//! diagnostics, zipped up to attach to a bug report.
+//!
+//! Everything is redacted on the way in: the home directory becomes `~`, and
+//! token-like settings are dropped. `secrets.json` is never included.
+
+use anyhow::Context;
+use log::info;
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+use zip::write::SimpleFileOptions;
+
+use crate::logs::logs_dir;
+use crate::review::central::{get_central_root, list_registered_repos};
+
+use super::settings::{parse_settings, settings_path, SettingsErrors};
+
+/// Logs modified within this many days are included.
+pub const RECENT_LOG_DAYS: u64 = 7;
+/// Only the tail of a larger log is included.
+const MAX_BYTES_PER_LOG: usize = 2_000_000;
+
+const REDACTED: &str = "[redacted]";
+
+/// The machine the bundle was made on.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    pub version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub git_version: Option<String>,
+    pub gh_version: Option<String>,
+    pub claude_available: bool,
+    /// Whether `$REVIEW_HOME` moves the storage root off `~/.review`.
+    pub review_home_override: bool,
+    pub registered_repos: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings_errors: Option<SettingsErrors>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportBundle {
+    pub path: PathBuf,
+    /// Entries in the zip.
+    pub files: Vec<String>,
+}
+
+/// First line of `<program> --version`, if it runs.
+fn tool_version(program: &str) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .map(|line| line.trim().to_owned())
+}
+
+fn home_prefix() -> Option<String> {
+    dirs::home_dir()
+        .map(|home| home.to_string_lossy().into_owned())
+        .filter(|home| home.len() > 1)
+}
+
+/// Replace the home directory with `~`.
+fn redact_text(text: &str, home: Option<&str>) -> String {
+    match home {
+        Some(home) => text.replace(home, "~"),
+        None => text.to_owned(),
+    }
+}
+
+/// Whether a settings key names a credential (`githubToken`, `apiKey`, ...).
+fn is_sensitive_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    [
+        "token",
+        "secret",
+        "password",
+        "apikey",
+        "api_key",
+        "credentials",
+    ]
+    .iter()
+    .any(|suffix| key.ends_with(suffix))
+}
+
+/// Redact a settings value: credentials are replaced, home paths shortened.
+fn redact_value(value: &Value, home: Option<&str>) -> Value {
+    match value {
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| {
+                    let value = if is_sensitive_key(key) {
+                        Value::String(REDACTED.to_owned())
+                    } else {
+                        redact_value(value, home)
+                    };
+                    (redact_text(key, home), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(|v| redact_value(v, home)).collect()),
+        Value::String(text) => Value::String(redact_text(text, home)),
+        other => other.clone(),
+    }
+}
+
+/// The last [`MAX_BYTES_PER_LOG`] bytes of a log.
+fn read_log_tail(path: &Path) -> std::io::Result<String> {
+    let bytes = fs::read(path)?;
+    let start = bytes.len().saturating_sub(MAX_BYTES_PER_LOG);
+    Ok(String::from_utf8_lossy(&bytes[start..]).into_owned())
+}
+
+/// Log files modified in the last [`RECENT_LOG_DAYS`] days, with their names
+/// in the bundle.
+fn recent_logs() -> anyhow::Result<Vec<(String, PathBuf)>> {
+    let cutoff = SystemTime::now() - Duration::from_secs(RECENT_LOG_DAYS * 24 * 60 * 60);
+    let is_recent = |path: &Path| {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .is_ok_and(|modified| modified >= cutoff)
+    };
+
+    let dir = logs_dir()?;
+    let mut logs: Vec<(String, PathBuf)> = walkdir::WalkDir::new(&dir)
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry.file_type().is_file())
+        .map(walkdir::DirEntry::into_path)
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log") && is_recent(path))
+        .filter_map(|path| {
+            let rel = path
+                .strip_prefix(&dir)
+                .ok()?
+                .to_string_lossy()
+                .replace('\\', "/");
+            Some((format!("logs/{rel}"), path))
+        })
+        .collect();
+    logs.sort();
+
+    // Dev builds before the logs directory wrote here
+    let legacy = get_central_root()?.join("app.log");
+    if is_recent(&legacy) {
+        logs.push(("logs/legacy-app.log".to_owned(), legacy));
+    }
+    Ok(logs)
+}
+
+/// Gather diagnostics, then write them with the redacted settings and recent
+/// logs to a zip at `output`.
+pub fn create_support_bundle(output: &Path) -> anyhow::Result<SupportBundle> {
+    let home = home_prefix();
+    let home = home.as_deref();
+
+    let settings = match fs::read_to_string(settings_path()?) {
+        Ok(content) => Some(
+            serde_json::from_str::<Value>(&content)
+                .map_err(|e| format!("settings.json is not valid JSON: {e}")),
+        ),
+        Err(_) => None,
+    };
+    let settings_errors = match &settings {
+        Some(Ok(value)) => Some(parse_settings(value).1).filter(|errors| !errors.is_empty()),
+        _ => None,
+    };
+
+    let diagnostics = Diagnostics {
+        version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        git_version: tool_version("git"),
+        gh_version: tool_version("gh"),
+        claude_available: crate::ai::check_claude_available(),
+        review_home_override: std::env::var_os("REVIEW_HOME").is_some(),
+        registered_repos: list_registered_repos().map_or(0, |r| r.len()),
+        settings_errors,
+    };
+
+    let file = fs::File::create(output)
+        .with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    let mut files = Vec::new();
+    let mut add = |name: String, contents: &str| -> anyhow::Result<()> {
+        zip.start_file(name.as_str(), options)?;
+        zip.write_all(contents.as_bytes())?;
+        files.push(name);
+        Ok(())
+    };
+
+    add(
+        "diagnostics.json".to_owned(),
+        &redact_text(&serde_json::to_string_pretty(&diagnostics)?, home),
+    )?;
+    match settings {
+        Some(Ok(value)) => add(
+            "settings.json".to_owned(),
+            &serde_json::to_string_pretty(&redact_value(&value, home))?,
+        )?,
+        // Unparseable settings can't be redacted key by key, so only the
+        // error goes in
+        Some(Err(error)) => add("settings-error.txt".to_owned(), &redact_text(&error, home))?,
+        None => {}
+    }
+    for (name, path) in recent_logs()? {
+        let text =
+            read_log_tail(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        add(name, &redact_text(&text, home))?;
+    }
+    zip.finish()?;
+
+    info!(
+        "[create_support_bundle] Wrote {} files to {}",
+        files.len(),
+        output.display()
+    );
+    Ok(SupportBundle {
+        path: output.to_path_buf(),
+        files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use serde_json::json;
+    use std::io::Read;
+
+    #[test]
+    fn redacts_credentials_and_home_paths() {
+        let settings = json!({
+            "githubToken": "ghp_secret",
+            "customThemes": [{"name": "x", "tokenColors": []}],
+            "recentRepositories": [{"path": "/home/ana/src/app"}],
+            "collapsedRepos": {"/home/ana/src/app": true},
+        });
+        assert_eq!(
+            redact_value(&settings, Some("/home/ana")),
+            json!({
+                "githubToken": "[redacted]",
+                "customThemes": [{"name": "x", "tokenColors": []}],
+                "recentRepositories": [{"path": "~/src/app"}],
+                "collapsedRepos": {"~/src/app": true},
+            })
+        );
+    }
+
+    #[test]
+    fn bundles_logs_settings_and_diagnostics() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, home, _repo) = setup_test();
+        crate::logs::append(&logs_dir().unwrap().join("cli.log"), "hello").unwrap();
+        fs::write(
+            settings_path().unwrap(),
+            r#"{"apiKey": "sk-1", "showOutline": 3}"#,
+        )
+        .unwrap();
+        fs::write(home.path().join("secrets.json"), "{}").unwrap();
+
+        let output = home.path().join("bundle.zip");
+        let bundle = create_support_bundle(&output).unwrap();
+        assert_eq!(
+            bundle.files,
+            vec!["diagnostics.json", "settings.json", "logs/cli.log"]
+        );
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&output).unwrap()).unwrap();
+        let mut read = |name: &str| {
+            let mut text = String::new();
+            archive
+                .by_name(name)
+                .unwrap()
+                .read_to_string(&mut text)
+                .unwrap();
+            text
+        };
+        assert!(read("settings.json").contains("[redacted]"));
+        assert!(read("logs/cli.log").contains("hello"));
+        let diagnostics: Value = serde_json::from_str(&read("diagnostics.json")).unwrap();
+        assert_eq!(diagnostics["reviewHomeOverride"], true);
+        assert_eq!(
+            diagnostics["settingsErrors"]["invalidValues"][0]["key"],
+            "showOutline"
+        );
+    }
+}
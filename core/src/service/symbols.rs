@@ -3,21 +3,37 @@
 use anyhow::Context;
 use log::{debug, info};
 use std::collections::{HashMap, HashSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex, PoisonError};
 use std::time::Instant;
 
 use crate::diff::parser::{parse_multi_file_diff, DiffHunk};
+use crate::limits::ReviewLimits;
 use crate::sources::local_git::LocalGitSource;
-use crate::sources::traits::{Comparison, DiffSource};
+use crate::sources::traits::{Comparison, DiffSource, Endpoint};
 use crate::symbols::{self, FileSymbolDiff, Symbol, SymbolDefinition};
 
+use super::jobs::{self, JobKind, JobPriority};
 use super::RepoFileSymbols;
 
-/// Compute symbol-level diffs for files.
+/// Compute symbol-level diffs for files, as an interactive job.
 pub fn get_file_symbol_diffs(
     repo_path: &Path,
     file_paths: &[String],
     comparison: &Comparison,
+) -> anyhow::Result<Vec<FileSymbolDiff>> {
+    jobs::run(
+        JobKind::Symbols,
+        JobPriority::Interactive,
+        format!("Symbol diffs for {}", comparison.key),
+        || compute_file_symbol_diffs(repo_path, file_paths, comparison),
+    )
+}
+
+fn compute_file_symbol_diffs(
+    repo_path: &Path,
+    file_paths: &[String],
+    comparison: &Comparison,
 ) -> anyhow::Result<Vec<FileSymbolDiff>> {
     let t0 = Instant::now();
     debug!(
@@ -29,7 +45,9 @@ pub fn get_file_symbol_diffs(
     let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
 
     // Determine the git refs for old and new sides
-    let old_ref = if source.include_working_tree(comparison) {
+    let old_ref = if comparison.base_endpoint() == Endpoint::Index {
+        Comparison::INDEX.to_owned()
+    } else if source.include_working_tree(comparison) {
         "HEAD".to_owned()
     } else {
         comparison.base.clone()
@@ -53,61 +71,65 @@ pub fn get_file_symbol_diffs(
     let rename_map = crate::diff::parser::extract_rename_map(&full_diff);
 
     // Pass 1: compute FileSymbolDiff per file (parallel), also return file contents for reuse
+    let limits = ReviewLimits::from_env();
+    let old_ref = old_ref.as_str();
     let pass1_results: Vec<(
         FileSymbolDiff,
         Option<String>,
         Option<String>,
         Vec<DiffHunk>,
-    )> = std::thread::scope(|s| {
-        let handles: Vec<_> = file_paths
+    )> = jobs::parallel_map(file_paths.iter().collect(), |file_path: &String| {
+        // Get old content (use old path for renamed files)
+        let old_path = rename_map
+            .get(file_path.as_str())
+            .map_or(file_path.as_str(), String::as_str);
+        let old_content = source
+            .get_file_bytes(old_path, old_ref)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok());
+
+        // Get new content
+        let new_content = if source.include_working_tree(comparison) {
+            let full_path = repo_path.join(file_path);
+            std::fs::read_to_string(&full_path).ok()
+        } else {
+            source
+                .get_file_bytes(file_path, &comparison.head)
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+        };
+
+        let file_hunks: Vec<_> = all_hunks
             .iter()
-            .map(|file_path| {
-                let source = &source;
-                let all_hunks = &all_hunks;
-                let old_ref = old_ref.as_str();
-                let comparison = comparison;
-                let repo_path = repo_path;
-                let rename_map = &rename_map;
-                s.spawn(move || {
-                    // Get old content (use old path for renamed files)
-                    let old_path = rename_map
-                        .get(file_path.as_str())
-                        .map(|s| s.as_str())
-                        .unwrap_or(file_path);
-                    let old_content = source
-                        .get_file_bytes(old_path, old_ref)
-                        .ok()
-                        .and_then(|bytes| String::from_utf8(bytes).ok());
-
-                    // Get new content
-                    let new_content = if source.include_working_tree(comparison) {
-                        let full_path = repo_path.join(file_path);
-                        std::fs::read_to_string(&full_path).ok()
-                    } else {
-                        source
-                            .get_file_bytes(file_path, &comparison.head)
-                            .ok()
-                            .and_then(|bytes| String::from_utf8(bytes).ok())
-                    };
+            .filter(|h| h.file_path == *file_path)
+            .cloned()
+            .collect();
 
-                    let file_hunks: Vec<_> = all_hunks
-                        .iter()
-                        .filter(|h| h.file_path == *file_path)
-                        .cloned()
-                        .collect();
+        // Too large to parse within budget: keep the hunks, skip tree-sitter.
+        let too_large = [&old_content, &new_content]
+            .into_iter()
+            .flatten()
+            .any(|content| content.len() > limits.max_file_bytes);
+        if too_large {
+            let diff = FileSymbolDiff {
+                file_path: file_path.clone(),
+                symbols: vec![],
+                top_level_hunk_ids: file_hunks.iter().map(|h| h.id.clone()).collect(),
+                has_grammar: false,
+                symbol_references: vec![],
+                limit_exceeded: true,
+            };
+            return (diff, None, None, file_hunks);
+        }
 
-                    let diff = symbols::extractor::compute_file_symbol_diff(
-                        old_content.as_deref(),
-                        new_content.as_deref(),
-                        file_path,
-                        &file_hunks,
-                    );
+        let diff = symbols::extractor::compute_file_symbol_diff(
+            old_content.as_deref(),
+            new_content.as_deref(),
+            file_path,
+            &file_hunks,
+        );
 
-                    (diff, old_content, new_content, file_hunks)
-                })
-            })
-            .collect();
-        handles.into_iter().filter_map(|h| h.join().ok()).collect()
+        (diff, old_content, new_content, file_hunks)
     });
 
     // Collect modified symbol names across all files (from SymbolDiff trees)
@@ -160,84 +182,73 @@ pub fn get_file_symbol_diffs(
         .collect();
 
     // Pass 2: find references to modified symbols in each file (parallel)
-    let results: Vec<FileSymbolDiff> = std::thread::scope(|s| {
-        let handles: Vec<_> = pass1_results
-            .into_iter()
-            .zip(import_maps)
-            .map(
-                |((mut diff, old_content, new_content, file_hunks), file_imports)| {
-                    let modified_symbols = &modified_symbols;
-                    let definition_ranges_by_file = &definition_ranges_by_file;
-                    s.spawn(move || {
-                        if diff.has_grammar {
-                            let file_path = &diff.file_path;
-                            let def_ranges = definition_ranges_by_file
-                                .get(file_path)
-                                .cloned()
-                                .unwrap_or_default();
-
-                            // Scope target symbols: intersect with file's imports
-                            let scoped_symbols: HashSet<String>;
-                            let target_symbols = match &file_imports {
-                                Some(imports) => {
-                                    let defined_in_file: HashSet<&String> =
-                                        def_ranges.keys().collect();
-                                    scoped_symbols = modified_symbols
-                                        .iter()
-                                        .filter(|sym| {
-                                            imports.contains(sym.as_str())
-                                                || defined_in_file.contains(sym)
-                                        })
-                                        .cloned()
-                                        .collect();
-                                    &scoped_symbols
-                                }
-                                None => modified_symbols,
-                            };
-
-                            // Find references in new content
-                            if let Some(ref content) = new_content {
-                                let mut refs = symbols::extractor::find_symbol_references(
-                                    content,
-                                    file_path,
-                                    &file_hunks,
-                                    target_symbols,
-                                    &def_ranges,
-                                    true,
-                                );
-                                diff.symbol_references.append(&mut refs);
-                            }
+    let modified_symbols = &modified_symbols;
+    let definition_ranges_by_file = &definition_ranges_by_file;
+    let results: Vec<FileSymbolDiff> = jobs::parallel_map(
+        pass1_results.into_iter().zip(import_maps).collect(),
+        |((mut diff, old_content, new_content, file_hunks), file_imports)| {
+            if diff.has_grammar {
+                let file_path = &diff.file_path;
+                let def_ranges = definition_ranges_by_file
+                    .get(file_path)
+                    .cloned()
+                    .unwrap_or_default();
+
+                // Scope target symbols: intersect with file's imports
+                let scoped_symbols: HashSet<String>;
+                let target_symbols = match &file_imports {
+                    Some(imports) => {
+                        let defined_in_file: HashSet<&String> = def_ranges.keys().collect();
+                        scoped_symbols = modified_symbols
+                            .iter()
+                            .filter(|sym| {
+                                imports.contains(sym.as_str()) || defined_in_file.contains(sym)
+                            })
+                            .cloned()
+                            .collect();
+                        &scoped_symbols
+                    }
+                    None => modified_symbols,
+                };
+
+                // Find references in new content
+                if let Some(ref content) = new_content {
+                    let mut refs = symbols::extractor::find_symbol_references(
+                        content,
+                        file_path,
+                        &file_hunks,
+                        target_symbols,
+                        &def_ranges,
+                        true,
+                    );
+                    diff.symbol_references.append(&mut refs);
+                }
 
-                            // Find references in old content (for deletion-only hunks)
-                            if let Some(ref content) = old_content {
-                                let mut refs = symbols::extractor::find_symbol_references(
-                                    content,
-                                    file_path,
-                                    &file_hunks,
-                                    target_symbols,
-                                    &def_ranges,
-                                    false,
-                                );
-                                // Deduplicate
-                                let existing: HashSet<(&str, &str)> = diff
-                                    .symbol_references
-                                    .iter()
-                                    .map(|r| (r.symbol_name.as_str(), r.hunk_id.as_str()))
-                                    .collect();
-                                refs.retain(|r| {
-                                    !existing
-                                        .contains(&(r.symbol_name.as_str(), r.hunk_id.as_str()))
-                                });
-                                diff.symbol_references.append(&mut refs);
-                            }
-                        }
-                        diff
-                    })
-                },
-            )
-            .collect();
-        handles.into_iter().filter_map(|h| h.join().ok()).collect()
-    });
+                // Find references in old content (for deletion-only hunks)
+                if let Some(ref content) = old_content {
+                    let mut refs = symbols::extractor::find_symbol_references(
+                        content,
+                        file_path,
+                        &file_hunks,
+                        target_symbols,
+                        &def_ranges,
+                        false,
+                    );
+                    // Deduplicate
+                    let existing: HashSet<(&str, &str)> = diff
+                        .symbol_references
+                        .iter()
+                        .map(|r| (r.symbol_name.as_str(), r.hunk_id.as_str()))
+                        .collect();
+                    refs.retain(|r| {
+                        !existing.contains(&(r.symbol_name.as_str(), r.hunk_id.as_str()))
+                    });
+                    diff.symbol_references.append(&mut refs);
+                }
+            }
+            diff
+        },
+    );
 
     // Save to disk cache for next time
     let _ = symbols::cache::save(repo_path, comparison, &diff_hash, &results);
@@ -250,45 +261,86 @@ pub fn get_file_symbol_diffs(
     Ok(results)
 }
 
-/// Extract symbols from all tracked files in the repo.
+/// Symbols for all tracked files in the repo, served from the persistent
+/// symbol index — only files whose content changed since the last call are
+/// re-parsed.
 pub fn get_repo_symbols(repo_path: &Path) -> anyhow::Result<Vec<RepoFileSymbols>> {
     let t0 = Instant::now();
     debug!("[get_repo_symbols] repo_path={}", repo_path.display());
 
-    let source = LocalGitSource::new(repo_path.to_path_buf()).context("Failed to open repo")?;
-    let tracked_files = source
-        .get_tracked_files()
-        .context("Failed to get tracked files")?;
-
-    let mut results = Vec::new();
-    for file_path in &tracked_files {
-        if symbols::extractor::get_language_for_file(file_path).is_none() {
-            continue;
-        }
-        let full_path = repo_path.join(file_path);
-        let syms = std::fs::read_to_string(&full_path)
-            .ok()
-            .and_then(|content| symbols::extractor::extract_symbols(&content, file_path))
-            .unwrap_or_default();
-        if syms.is_empty() {
-            continue;
-        }
-        results.push(RepoFileSymbols {
-            file_path: file_path.clone(),
-            symbols: syms,
-        });
-    }
+    let index = jobs::run(
+        JobKind::Symbols,
+        JobPriority::Interactive,
+        "Index repo symbols",
+        || symbols::index::refresh(repo_path),
+    )
+    .context("Failed to refresh symbol index")?;
+    let results: Vec<RepoFileSymbols> = index
+        .files()
+        .map(|(file_path, syms)| RepoFileSymbols {
+            file_path: file_path.to_owned(),
+            symbols: syms.to_vec(),
+        })
+        .collect();
 
-    results.sort_by(|a, b| a.file_path.cmp(&b.file_path));
     info!(
-        "[get_repo_symbols] SUCCESS: {} files with symbols (from {} tracked) in {:?}",
+        "[get_repo_symbols] SUCCESS: {} files with symbols (from {} indexed) in {:?}",
         results.len(),
-        tracked_files.len(),
+        index.len(),
         t0.elapsed()
     );
     Ok(results)
 }
 
+/// Changed paths per repo, waiting for a queued symbol index update.
+static PENDING_INDEX_UPDATES: LazyLock<Mutex<HashMap<PathBuf, HashSet<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Keep the persistent symbol index current after the watcher sees
+/// working-tree changes, as a background job; returns without waiting for it.
+/// Changes seen while an update is still queued join that update.
+/// Best-effort: failures are logged, and a repo whose index hasn't been built
+/// yet is left alone.
+pub fn update_repo_symbol_index(repo_path: &Path, changed_paths: &[String]) {
+    {
+        let mut pending = PENDING_INDEX_UPDATES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(paths) = pending.get_mut(repo_path) {
+            paths.extend(changed_paths.iter().cloned());
+            return;
+        }
+        pending.insert(
+            repo_path.to_path_buf(),
+            changed_paths.iter().cloned().collect(),
+        );
+    }
+
+    let repo_path = repo_path.to_path_buf();
+    std::thread::spawn(move || {
+        let result = jobs::run(
+            JobKind::Symbols,
+            JobPriority::Background,
+            "Update symbol index",
+            || {
+                let paths: Vec<String> = PENDING_INDEX_UPDATES
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .remove(&repo_path)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect();
+                symbols::index::update_paths(&repo_path, &paths)
+            },
+        );
+        match result {
+            Ok(0) => {}
+            Ok(n) => debug!("[update_repo_symbol_index] re-indexed {n} files"),
+            Err(e) => debug!("[update_repo_symbol_index] failed: {e}"),
+        }
+    });
+}
+
 /// Extract all symbols from a file using tree-sitter.
 pub fn get_file_symbols(
     repo_path: &Path,
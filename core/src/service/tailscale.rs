@@ -0,0 +1,313 @@
+//! Exposing the review server over Tailscale, so a phone can reach it off
+//! the LAN without port forwarding.
+//!
+//! Everything goes through the `tailscale` CLI:
+//! - `tailscale status --json` for this machine's tailnet IP and name, and
+//!   whether HTTPS certificates (needed by Serve) and Funnel are enabled.
+//! - `tailscale serve` proxies `https://<machine>.<tailnet>.ts.net` to the
+//!   local server, reachable from devices on the tailnet.
+//! - `tailscale funnel` does the same but from the public internet.
+//!
+//! Only the HTTPS port 443 mapping is managed; one that already proxies
+//! somewhere else is left alone. Requests arriving this way carry Tailscale's
+//! forwarding headers, so the server requires its token from them on every
+//! route even though they reach it over loopback.
+
+use anyhow::Context;
+use log::info;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// The HTTPS port exposures are mapped on.
+const HTTPS_PORT: u16 = 443;
+
+/// Where the macOS app keeps its CLI when it isn't on `PATH`.
+#[cfg(target_os = "macos")]
+const MACOS_APP_CLI: &str = "/Applications/Tailscale.app/Contents/MacOS/Tailscale";
+
+/// How an exposure is reachable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExposeMode {
+    /// From devices on the tailnet.
+    Serve,
+    /// From anywhere on the internet.
+    Funnel,
+}
+
+/// The local server as exposed through Tailscale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Exposure {
+    pub url: String,
+    pub mode: ExposeMode,
+    /// The local address requests are proxied to.
+    pub target: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "independent checks the UI shows one by one"
+)]
+pub struct TailscaleStatus {
+    pub installed: bool,
+    /// Logged in and connected.
+    pub running: bool,
+    /// This machine's tailnet IPv4 address.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    /// This machine's MagicDNS name, e.g. `laptop.tail1234.ts.net`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_name: Option<String>,
+    /// HTTPS certificates are enabled for the tailnet.
+    pub serve_available: bool,
+    /// The tailnet's policy grants this machine Funnel.
+    pub funnel_available: bool,
+    /// The server's current exposure, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exposure: Option<Exposure>,
+}
+
+/// The `tailscale` CLI, if installed.
+fn tailscale_cli() -> Option<PathBuf> {
+    let name = if cfg!(windows) {
+        "tailscale.exe"
+    } else {
+        "tailscale"
+    };
+    let on_path = std::env::var_os("PATH").and_then(|path| {
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(name))
+            .find(|candidate| candidate.is_file())
+    });
+    #[cfg(target_os = "macos")]
+    let on_path = on_path.or_else(|| {
+        let app = PathBuf::from(MACOS_APP_CLI);
+        app.is_file().then_some(app)
+    });
+    on_path
+}
+
+/// Run the CLI and return its stdout, failing with its stderr.
+fn run_cli(args: &[&str]) -> anyhow::Result<String> {
+    let cli = tailscale_cli().context("Tailscale is not installed")?;
+    let output = Command::new(&cli)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run {}", cli.display()))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "tailscale {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn run_cli_json(args: &[&str]) -> anyhow::Result<Value> {
+    let stdout = run_cli(args)?;
+    if stdout.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(&stdout)
+        .with_context(|| format!("Unexpected output from tailscale {}", args.join(" ")))
+}
+
+/// Fill in what `tailscale status --json` says about this machine.
+fn parse_status(json: &Value) -> TailscaleStatus {
+    let this = &json["Self"];
+    let has_cap = |cap: &str| {
+        this["CapMap"].as_object().is_some_and(|caps| {
+            caps.keys()
+                .any(|key| key == cap || key.ends_with(&format!("/cap/{cap}")))
+        }) || this["Capabilities"].as_array().is_some_and(|caps| {
+            caps.iter()
+                .filter_map(Value::as_str)
+                .any(|key| key == cap || key.ends_with(&format!("/cap/{cap}")))
+        })
+    };
+    let cert_domains = json["CertDomains"]
+        .as_array()
+        .is_some_and(|d| !d.is_empty());
+
+    TailscaleStatus {
+        installed: true,
+        running: json["BackendState"] == "Running",
+        ip: this["TailscaleIPs"].as_array().and_then(|ips| {
+            ips.iter()
+                .filter_map(Value::as_str)
+                .find(|ip| ip.parse::<std::net::Ipv4Addr>().is_ok())
+                .map(str::to_owned)
+        }),
+        dns_name: this["DNSName"]
+            .as_str()
+            .map(|name| name.trim_end_matches('.').to_owned())
+            .filter(|name| !name.is_empty()),
+        serve_available: cert_domains,
+        funnel_available: cert_domains && has_cap("funnel"),
+        exposure: None,
+    }
+}
+
+/// Where `tailscale serve status --json` says the HTTPS port proxies to,
+/// with whether Funnel is on for it.
+fn https_mapping(serve: &Value, dns_name: &str) -> Option<(String, bool)> {
+    let host_port = format!("{dns_name}:{HTTPS_PORT}");
+    let target = serve["Web"][&host_port]["Handlers"]["/"]["Proxy"].as_str()?;
+    let funnel = serve["AllowFunnel"][&host_port].as_bool().unwrap_or(false);
+    Some((target.to_owned(), funnel))
+}
+
+fn local_target(port: u16) -> String {
+    format!("http://127.0.0.1:{port}")
+}
+
+/// Whether `target` points at `port` on this machine.
+fn targets_port(target: &str, port: u16) -> bool {
+    [
+        local_target(port),
+        format!("http://localhost:{port}"),
+        port.to_string(),
+    ]
+    .iter()
+    .any(|t| target.trim_end_matches('/') == t)
+}
+
+fn exposure_for(dns_name: &str, target: String, funnel: bool) -> Exposure {
+    Exposure {
+        url: format!("https://{dns_name}"),
+        mode: if funnel {
+            ExposeMode::Funnel
+        } else {
+            ExposeMode::Serve
+        },
+        target,
+    }
+}
+
+/// Tailscale's state on this machine, and how the server on `port` is
+/// exposed through it.
+pub fn tailscale_status(port: u16) -> anyhow::Result<TailscaleStatus> {
+    if tailscale_cli().is_none() {
+        return Ok(TailscaleStatus::default());
+    }
+    let mut status = parse_status(&run_cli_json(&["status", "--json"])?);
+    if let (true, Some(dns_name)) = (status.running, status.dns_name.clone()) {
+        let serve = run_cli_json(&["serve", "status", "--json"])?;
+        status.exposure = https_mapping(&serve, &dns_name)
+            .filter(|(target, _)| targets_port(target, port))
+            .map(|(target, funnel)| exposure_for(&dns_name, target, funnel));
+    }
+    Ok(status)
+}
+
+/// This machine's tailnet IPv4 address, if Tailscale is connected.
+pub fn tailscale_ip() -> Option<String> {
+    let status = parse_status(&run_cli_json(&["status", "--json"]).ok()?);
+    status.ip.filter(|_| status.running)
+}
+
+/// Proxy `https://<machine>.<tailnet>.ts.net` to the server on `port`.
+pub fn expose(port: u16, mode: ExposeMode) -> anyhow::Result<Exposure> {
+    crate::offline::ensure_online("Exposing the server over Tailscale")?;
+    let status = tailscale_status(port)?;
+    if !status.installed {
+        anyhow::bail!("Tailscale is not installed");
+    }
+    if !status.running {
+        anyhow::bail!("Tailscale is not connected; run `tailscale up` first");
+    }
+    let dns_name = status
+        .dns_name
+        .context("This machine has no MagicDNS name; enable MagicDNS for the tailnet")?;
+    if !status.serve_available {
+        anyhow::bail!("HTTPS certificates are not enabled for this tailnet");
+    }
+    if mode == ExposeMode::Funnel && !status.funnel_available {
+        anyhow::bail!("The tailnet policy does not allow Funnel for this machine");
+    }
+    let serve = run_cli_json(&["serve", "status", "--json"])?;
+    if let Some((target, _)) = https_mapping(&serve, &dns_name) {
+        if !targets_port(&target, port) {
+            anyhow::bail!(
+                "Tailscale already serves {target} on https://{dns_name}; \
+                 remove that first (`tailscale serve --https={HTTPS_PORT} off`)"
+            );
+        }
+    }
+
+    let target = local_target(port);
+    let https = format!("--https={HTTPS_PORT}");
+    // Switching from Funnel back to Serve needs Funnel turned off first
+    if mode == ExposeMode::Serve
+        && status.exposure.as_ref().map(|e| e.mode) == Some(ExposeMode::Funnel)
+    {
+        run_cli(&["funnel", &https, "off"])?;
+    }
+    let command = match mode {
+        ExposeMode::Serve => "serve",
+        ExposeMode::Funnel => "funnel",
+    };
+    run_cli(&[command, "--bg", &https, &target])?;
+    info!("[tailscale] Exposed {target} on https://{dns_name} via {command}");
+    Ok(exposure_for(&dns_name, target, mode == ExposeMode::Funnel))
+}
+
+/// Stop exposing the server on `port`. Does nothing if it isn't exposed.
+pub fn unexpose(port: u16) -> anyhow::Result<bool> {
+    let Some(exposure) = tailscale_status(port)?.exposure else {
+        return Ok(false);
+    };
+    let https = format!("--https={HTTPS_PORT}");
+    if exposure.mode == ExposeMode::Funnel {
+        run_cli(&["funnel", &https, "off"])?;
+    } else {
+        run_cli(&["serve", &https, "off"])?;
+    }
+    info!("[tailscale] Stopped exposing {}", exposure.target);
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_status_and_serve_config() {
+        let status = parse_status(&json!({
+            "BackendState": "Running",
+            "CertDomains": ["laptop.tail1234.ts.net"],
+            "Self": {
+                "DNSName": "laptop.tail1234.ts.net.",
+                "TailscaleIPs": ["fd7a:115c:a1e0::1", "100.101.102.103"],
+                "CapMap": {"https://tailscale.com/cap/funnel": null, "https": null},
+            },
+        }));
+        assert!(status.running);
+        assert_eq!(status.ip.as_deref(), Some("100.101.102.103"));
+        assert_eq!(status.dns_name.as_deref(), Some("laptop.tail1234.ts.net"));
+        assert!(status.serve_available);
+        assert!(status.funnel_available);
+
+        let no_certs = parse_status(&json!({"BackendState": "Stopped", "Self": {}}));
+        assert!(!no_certs.running);
+        assert!(!no_certs.serve_available && !no_certs.funnel_available);
+
+        let serve = json!({
+            "TCP": {"443": {"HTTPS": true}},
+            "Web": {"laptop.tail1234.ts.net:443": {"Handlers": {"/": {"Proxy": "http://127.0.0.1:3421"}}}},
+            "AllowFunnel": {"laptop.tail1234.ts.net:443": true},
+        });
+        let (target, funnel) = https_mapping(&serve, "laptop.tail1234.ts.net").unwrap();
+        assert!(funnel);
+        assert!(targets_port(&target, 3421));
+        assert!(!targets_port(&target, 3422));
+        assert!(https_mapping(&Value::Null, "laptop.tail1234.ts.net").is_none());
+    }
+}
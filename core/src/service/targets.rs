@@ -1,7 +1,8 @@
 //! Resolve a review's identity — a ref plus an optional base override — into a
 //! concrete [`Comparison`] for the diff pipeline.
 //!
-//! A review is *of one thing*: a ref (branch, SHA, tag, or `stash@{n}`). The
+//! A review is *of one thing*: a ref (branch, SHA, tag, or `stash@{n}`), or the
+//! index or working tree (the `:index` / `:worktree` endpoint tokens). The
 //! base is derived at read time by the ladder in [`resolve_review`], so a branch
 //! review re-baselines naturally as the branch and its default branch move. An
 //! explicit `base_override` short-circuits the ladder for the cases a human
@@ -13,7 +14,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::review::storage;
 use crate::sources::local_git::LocalGitSource;
-use crate::sources::traits::Comparison;
+use crate::sources::traits::{Comparison, Endpoint};
 
 /// Which arm of the [`resolve_review`] ladder produced a review's base — the
 /// intent behind the bare `base..head`, so the UI can label the comparison
@@ -29,6 +30,10 @@ pub enum BaseReason {
     BranchVsDefault,
     /// Any other rev (SHA, tag, `stash@{n}`, detached HEAD) reviewed as one commit.
     SingleCommit,
+    /// The index vs `HEAD` — what the next commit would contain.
+    Staged,
+    /// The working tree vs the index — changes not yet staged.
+    Unstaged,
 }
 
 /// A resolved review: its identity (`ref` + optional `baseOverride`) alongside
@@ -88,12 +93,13 @@ pub fn set_base_override(
 /// identity into a diff:
 ///
 /// 1. `base_override` set → `base..ref` verbatim (`""` = empty tree, a snapshot).
-/// 2. `ref` is a branch → vs the default branch (or, *for* the default branch,
+/// 2. `ref` is `:index` → `HEAD..:index`; `:worktree` → `:index..:worktree`.
+/// 3. `ref` is a branch → vs the default branch (or, *for* the default branch,
 ///    vs `origin/<default>` else `HEAD`). Merge-base is applied later at diff
 ///    time by [`LocalGitSource::diff_base_ref`], so rebases re-baseline for free.
-/// 3. any other resolvable rev (SHA, tag, `stash@{n}`, detached HEAD) → reviewed
+/// 4. any other resolvable rev (SHA, tag, `stash@{n}`, detached HEAD) → reviewed
 ///    as a single commit: `{ref}^..{ref}`.
-/// 4. otherwise → error.
+/// 5. otherwise → error.
 pub fn resolve_review(
     source: &LocalGitSource,
     ref_name: &str,
@@ -104,7 +110,14 @@ pub fn resolve_review(
         return Ok((Comparison::new(base, ref_name), BaseReason::Override));
     }
 
-    // 2. A branch (checked specifically, so tags fall through to rule 3).
+    // 2. The index or working tree: each side's natural base is the one below it.
+    match Endpoint::parse(ref_name) {
+        Endpoint::Index => return Ok((Comparison::staged(), BaseReason::Staged)),
+        Endpoint::Worktree => return Ok((Comparison::unstaged(), BaseReason::Unstaged)),
+        Endpoint::Ref(_) => {}
+    }
+
+    // 3. A branch (checked specifically, so tags fall through to rule 4).
     if source.is_branch(ref_name) {
         let default_branch = source.get_default_branch()?;
         if ref_name == default_branch {
@@ -126,13 +139,13 @@ pub fn resolve_review(
         ));
     }
 
-    // 3. Any other resolvable rev: review the one commit.
+    // 4. Any other resolvable rev: review the one commit.
     if source.resolve_ref(ref_name).is_some() {
         let base = source.resolve_ref_or_empty_tree(&format!("{ref_name}^"));
         return Ok((Comparison::new(base, ref_name), BaseReason::SingleCommit));
     }
 
-    // 4. Nothing resolved.
+    // 5. Nothing resolved.
     anyhow::bail!("Could not resolve review ref '{ref_name}'")
 }
 
@@ -214,4 +227,44 @@ mod tests {
         let (_c, reason) = resolve_review(&source, &sha, None).unwrap();
         assert_eq!(reason, BaseReason::SingleCommit);
     }
+
+    #[test]
+    fn index_and_worktree_resolve_as_staged_and_unstaged() {
+        use crate::sources::traits::DiffSource;
+
+        let (dir, source) = repo();
+        let path = dir.path();
+        std::fs::write(path.join("a.txt"), "one\n").unwrap();
+        git(path, &["add", "a.txt"]);
+        git(path, &["commit", "-m", "a"]);
+        std::fs::write(path.join("a.txt"), "one\nstaged\n").unwrap();
+        git(path, &["add", "a.txt"]);
+        std::fs::write(path.join("a.txt"), "one\nstaged\nunstaged\n").unwrap();
+        std::fs::write(path.join("new.txt"), "untracked\n").unwrap();
+
+        let (staged, reason) = resolve_review(&source, Comparison::INDEX, None).unwrap();
+        assert_eq!(reason, BaseReason::Staged);
+        assert_eq!(staged.key, "HEAD..:index");
+        let diff = source.get_diff(&staged, None).unwrap();
+        assert!(diff.contains("+staged") && !diff.contains("+unstaged"));
+        let stats = source.get_diff_shortstat(&staged).unwrap();
+        assert_eq!((stats.file_count, stats.additions), (1, 1));
+        let old = source.get_file_bytes("a.txt", &source.diff_base_ref(&staged));
+        assert_eq!(old.unwrap(), b"one\n");
+        let new = source.get_file_bytes("a.txt", &staged.head).unwrap();
+        assert_eq!(new, b"one\nstaged\n");
+
+        let (unstaged, reason) = resolve_review(&source, Comparison::WORKTREE, None).unwrap();
+        assert_eq!(reason, BaseReason::Unstaged);
+        assert_eq!(unstaged.key, ":index..:worktree");
+        let diff = source.get_diff(&unstaged, None).unwrap();
+        assert!(diff.contains("+unstaged") && !diff.contains("+staged"));
+        // The untracked file counts as an unstaged change.
+        let stats = source.get_diff_shortstat(&unstaged).unwrap();
+        assert_eq!((stats.file_count, stats.additions), (2, 1));
+
+        assert!(source.diffs_uncommitted(&staged) && source.diffs_uncommitted(&unstaged));
+        let backwards = Comparison::new(Comparison::WORKTREE, "main");
+        assert!(source.get_diff(&backwards, None).is_err());
+    }
 }
@@ -0,0 +1,395 @@
+//! Review activity timeline — a single chronological stream per review that
+//! merges the comparison's commits with everything recorded in the review:
+//! classification runs, review decisions, comments, and guide authoring.
+//!
+//! Backs the "what happened while I was away" view (`since` filters to events
+//! after a point in time) and doubles as an exportable audit trail. Derived on
+//! demand from git and the saved review — nothing here is persisted.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::review::state::{iso8601_from_system_time, HunkStatus, ReviewState, Source};
+use crate::review::storage;
+use crate::sources::local_git::LocalGitSource;
+use crate::sources::traits::Comparison;
+
+/// Upper bound on commits pulled into a timeline; a review spanning more than
+/// this is better explored with `git log`.
+const MAX_TIMELINE_COMMITS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimelineEventKind {
+    /// The review was first saved.
+    ReviewCreated,
+    /// A commit landed in the comparison range.
+    Commit,
+    /// A batch of hunks was labelled in one save (one classification run).
+    Classified,
+    /// A batch of hunks was given a review status in one save.
+    StatusChanged,
+    /// A line comment was left.
+    Comment,
+    /// A line comment was resolved.
+    CommentResolved,
+    /// The review guide was (re)written.
+    GuideWritten,
+}
+
+/// One entry in a review's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    /// When it happened (ISO 8601, UTC).
+    pub at: String,
+    pub kind: TimelineEventKind,
+    /// Who or what did it, for review-side events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<Source>,
+    /// Display name of the person behind it — a commit author or comment author.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actor: Option<String>,
+    /// One-line human-readable description.
+    pub summary: String,
+    /// Hunks the event touched (classification and status batches).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hunk_ids: Vec<String>,
+    /// Full commit SHA, for `Commit` events.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// A review's timeline, oldest event first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewTimeline {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+    pub comparison: Comparison,
+    pub events: Vec<TimelineEvent>,
+}
+
+/// Build the timeline for the review of `ref_name`. When `since` is given (an
+/// ISO 8601 timestamp), only events strictly after it are returned.
+pub fn review_timeline(
+    repo_path: &Path,
+    ref_name: &str,
+    since: Option<&str>,
+) -> anyhow::Result<ReviewTimeline> {
+    let state = storage::load_review_state(repo_path, ref_name)?;
+    let resolved = super::targets::resolve(repo_path, ref_name, state.base_override.as_deref())?;
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+
+    let mut events = commit_events(&source, &resolved.comparison);
+    events.extend(state_events(&state));
+    if let Some(since) = since {
+        let since = normalize_timestamp(since);
+        events.retain(|e| e.at > since);
+    }
+    events.sort_by(|a, b| a.at.cmp(&b.at));
+
+    Ok(ReviewTimeline {
+        ref_name: resolved.ref_name,
+        comparison: resolved.comparison,
+        events,
+    })
+}
+
+/// Commits in `base..head`. A snapshot review (empty base) has no range of its
+/// own, and a staged or unstaged review has no commits, so neither contributes
+/// any; git failures degrade to an empty list.
+fn commit_events(source: &LocalGitSource, comparison: &Comparison) -> Vec<TimelineEvent> {
+    if comparison.base.is_empty() || comparison.has_uncommitted_endpoint() {
+        return Vec::new();
+    }
+    let range = format!("{}..{}", comparison.base, comparison.head);
+    let commits = match source.list_commits(MAX_TIMELINE_COMMITS, None, Some(&range)) {
+        Ok(commits) => commits,
+        Err(e) => {
+            warn!("[review_timeline] failed to list commits in {range}: {e}");
+            return Vec::new();
+        }
+    };
+    commits
+        .into_iter()
+        .map(|c| TimelineEvent {
+            at: normalize_timestamp(&c.date),
+            kind: TimelineEventKind::Commit,
+            source: None,
+            actor: Some(c.author),
+            summary: format!("{} {}", c.short_hash, c.message),
+            hunk_ids: Vec::new(),
+            commit: Some(c.hash),
+        })
+        .collect()
+}
+
+/// Events recorded in the saved review itself. Per-hunk decisions stamped by
+/// the same save with the same source are folded into one event, so a
+/// classification run or a bulk approve reads as a single entry. Undated
+/// decisions are left out.
+pub fn state_events(state: &ReviewState) -> Vec<TimelineEvent> {
+    let mut events = vec![TimelineEvent {
+        at: state.created_at.clone(),
+        kind: TimelineEventKind::ReviewCreated,
+        source: None,
+        actor: None,
+        summary: format!("Review of {} started", state.ref_name),
+        hunk_ids: Vec::new(),
+        commit: None,
+    }];
+
+    // (at, source, status) -> hunk ids; `None` status means a classification.
+    type BatchKey<'a> = (&'a str, &'static str, Option<&'static str>);
+    let mut batches: BTreeMap<BatchKey<'_>, (Source, Vec<String>)> = BTreeMap::new();
+    for (hunk_id, entry) in &state.hunks {
+        if let Some(c) = &entry.classification {
+            if let Some(at) = c.at.as_deref().filter(|at| !at.is_empty()) {
+                batches
+                    .entry((at, c.source.as_str(), None))
+                    .or_insert_with(|| (c.source, Vec::new()))
+                    .1
+                    .push(hunk_id.clone());
+            }
+        }
+        if let Some(s) = &entry.status {
+            if let Some(at) = s.at.as_deref().filter(|at| !at.is_empty()) {
+                batches
+                    .entry((at, s.source.as_str(), Some(status_verb(&s.value))))
+                    .or_insert_with(|| (s.source, Vec::new()))
+                    .1
+                    .push(hunk_id.clone());
+            }
+        }
+    }
+    for ((at, _, verb), (source, mut hunk_ids)) in batches {
+        hunk_ids.sort();
+        let count = hunk_ids.len();
+        let noun = if count == 1 { "hunk" } else { "hunks" };
+        let (kind, summary) = match verb {
+            None => (
+                TimelineEventKind::Classified,
+                format!("Classified {count} {noun}"),
+            ),
+            Some(verb) => (
+                TimelineEventKind::StatusChanged,
+                format!("{verb} {count} {noun}"),
+            ),
+        };
+        events.push(TimelineEvent {
+            at: at.to_owned(),
+            kind,
+            source: Some(source),
+            actor: None,
+            summary,
+            hunk_ids,
+            commit: None,
+        });
+    }
+
+    for annotation in &state.annotations {
+        let location = format!("{}:{}", annotation.file_path, annotation.line_number);
+        events.push(TimelineEvent {
+            at: annotation.created_at.clone(),
+            kind: TimelineEventKind::Comment,
+            source: annotation.source,
+            actor: annotation.author.clone(),
+            summary: format!("Commented on {location}"),
+            hunk_ids: Vec::new(),
+            commit: None,
+        });
+        if let Some(resolved_at) = &annotation.resolved_at {
+            events.push(TimelineEvent {
+                at: resolved_at.clone(),
+                kind: TimelineEventKind::CommentResolved,
+                source: None,
+                actor: annotation.resolved_by.clone(),
+                summary: format!("Resolved comment on {location}"),
+                hunk_ids: Vec::new(),
+                commit: None,
+            });
+        }
+    }
+
+    if let Some(generated) = state.guide.as_ref().and_then(|g| g.state.as_ref()) {
+        events.push(TimelineEvent {
+            at: generated.generated_at.clone(),
+            kind: TimelineEventKind::GuideWritten,
+            source: None,
+            actor: None,
+            summary: format!("Guide written with {} group(s)", generated.groups.len()),
+            hunk_ids: Vec::new(),
+            commit: None,
+        });
+    }
+
+    events
+}
+
+fn status_verb(status: &HunkStatus) -> &'static str {
+    match status {
+        HunkStatus::Approved => "Approved",
+        HunkStatus::Rejected => "Rejected",
+        HunkStatus::SavedForLater => "Saved for later",
+    }
+}
+
+/// Convert an ISO 8601 timestamp with a UTC offset (as git emits for `%aI`,
+/// e.g. `2024-05-01T10:00:00+02:00`) into the UTC `...Z` form review state
+/// uses, so the two sort together lexicographically. Milliseconds are kept.
+/// Input that doesn't parse is returned unchanged.
+fn normalize_timestamp(ts: &str) -> String {
+    parse_offset_timestamp(ts).map_or_else(
+        || ts.to_owned(),
+        |millis| iso8601_from_system_time(UNIX_EPOCH + Duration::from_millis(millis)),
+    )
+}
+
+/// Milliseconds since the Unix epoch for `YYYY-MM-DDTHH:MM:SS[.fff](Z|±HH:MM)`.
+/// Digits past milliseconds are dropped.
+fn parse_offset_timestamp(ts: &str) -> Option<u64> {
+    let (date, time) = ts.split_once('T')?;
+    let mut ymd = date.splitn(3, '-').map(str::parse::<i64>);
+    let (year, month, day) = (ymd.next()?.ok()?, ymd.next()?.ok()?, ymd.next()?.ok()?);
+
+    let (clock, offset_secs) = if let Some(clock) = time.strip_suffix('Z') {
+        (clock, 0)
+    } else {
+        let split = time.rfind(['+', '-'])?;
+        let (clock, offset) = time.split_at(split);
+        let sign = if offset.starts_with('-') { -1 } else { 1 };
+        let (oh, om) = offset[1..].split_once(':')?;
+        (
+            clock,
+            sign * (oh.parse::<i64>().ok()? * 3600 + om.parse::<i64>().ok()? * 60),
+        )
+    };
+    let (clock, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+    let millis = if fraction.is_empty() {
+        0
+    } else {
+        format!("{fraction:0<3}").get(..3)?.parse::<i64>().ok()?
+    };
+    let mut hms = clock.splitn(3, ':').map(str::parse::<i64>);
+    let (h, m, s) = (hms.next()?.ok()?, hms.next()?.ok()?, hms.next()?.ok()?);
+
+    // Days from civil (Howard Hinnant's algorithm).
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + h * 3600 + m * 60 + s - offset_secs;
+    u64::try_from(secs * 1000 + millis).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::state::{AnnotationSide, Attributed, HunkState, LineAnnotation};
+
+    fn stamped<T>(value: T, source: Source, at: &str) -> Attributed<T> {
+        let mut attributed = Attributed::new(value, source);
+        attributed.at = Some(at.to_owned());
+        attributed
+    }
+
+    #[test]
+    fn normalize_timestamp_converts_offsets_to_utc() {
+        assert_eq!(
+            normalize_timestamp("2024-05-01T10:00:00+02:00"),
+            "2024-05-01T08:00:00.000Z"
+        );
+        assert_eq!(
+            normalize_timestamp("2024-02-29T23:30:00-01:00"),
+            "2024-03-01T00:30:00.000Z"
+        );
+        assert_eq!(
+            normalize_timestamp("2024-05-01T08:00:00.123Z"),
+            "2024-05-01T08:00:00.123Z"
+        );
+        assert_eq!(
+            normalize_timestamp("2024-05-01T10:00:00.5+02:00"),
+            "2024-05-01T08:00:00.500Z"
+        );
+        assert_eq!(normalize_timestamp("garbage"), "garbage");
+    }
+
+    #[test]
+    fn state_events_batch_decisions_by_save_and_source() {
+        let mut state = ReviewState::new("feature", None);
+        state.created_at = "2024-01-01T00:00:00.000Z".to_owned();
+        let t1 = "2024-01-02T00:00:00.000Z";
+        let t2 = "2024-01-03T00:00:00.000Z";
+        for id in ["a:1", "a:2"] {
+            state.hunks.insert(
+                id.to_owned(),
+                HunkState {
+                    classification: Some(stamped(vec!["x:y".to_owned()], Source::Ai, t1)),
+                    status: Some(stamped(HunkStatus::Approved, Source::Ui, t2)),
+                    ..Default::default()
+                },
+            );
+        }
+        state.annotations.push(LineAnnotation {
+            id: "c1".to_owned(),
+            file_path: "a.rs".to_owned(),
+            line_number: 3,
+            end_line_number: None,
+            side: AnnotationSide::default(),
+            content: "why?".to_owned(),
+            created_at: t1.to_owned(),
+            author: Some("sam".to_owned()),
+            source: Some(Source::Ui),
+            updated_at: None,
+            resolved_at: Some(t2.to_owned()),
+            resolved_by: None,
+        });
+
+        let mut events = state_events(&state);
+        events.sort_by(|a, b| a.at.cmp(&b.at).then(a.summary.cmp(&b.summary)));
+        let kinds: Vec<_> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TimelineEventKind::ReviewCreated,
+                TimelineEventKind::Classified,
+                TimelineEventKind::Comment,
+                TimelineEventKind::StatusChanged,
+                TimelineEventKind::CommentResolved,
+            ]
+        );
+        assert_eq!(events[1].hunk_ids, vec!["a:1", "a:2"]);
+        assert_eq!(events[3].summary, "Approved 2 hunks");
+    }
+
+    #[test]
+    fn state_events_skip_unstamped_decisions() {
+        let mut state = ReviewState::new("feature", None);
+        state.hunks.insert(
+            "a:1".to_owned(),
+            HunkState {
+                status: Some(Attributed::new(HunkStatus::Rejected, Source::Cli)),
+                ..Default::default()
+            },
+        );
+        state.hunks.insert(
+            "b:2".to_owned(),
+            HunkState {
+                status: Some(stamped(HunkStatus::Approved, Source::Cli, "")),
+                ..Default::default()
+            },
+        );
+        let events = state_events(&state);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, TimelineEventKind::ReviewCreated);
+    }
+}
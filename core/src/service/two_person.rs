@@ -0,0 +1,222 @@
+//! Two-person review, for codebases where no change merges on one person's
+//! word.
+//!
+//! A review marked as requiring a second reviewer (by hand, or for every new
+//! review with `two_person = true` in `.review.toml`) goes through a hand-off:
+//! the first reviewer decides hunks as usual, then hands off, which moves each
+//! decision into the hunk's `recommendation`. The second reviewer — someone
+//! else — confirms or overrides every recommendation by deciding the hunk
+//! again, then signs off. `review check` only passes once both have.
+
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+
+use crate::review::state::{now_iso8601, ReviewState, SignOff, Source, TwoPersonReview};
+use crate::review::storage::{self, StorageError};
+
+/// Saves retried after a version conflict.
+const MAX_SAVE_RETRIES: usize = 3;
+
+/// How far a two-person review has got.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TwoPersonProgress {
+    #[serde(flatten)]
+    pub review: TwoPersonReview,
+    /// Hunks carrying the first reviewer's recommendation. Suggestions carried
+    /// over from other reviews ([`Source::Carried`]) don't count.
+    pub recommended: usize,
+    /// Recommendations the second reviewer decided the same way.
+    pub confirmed: usize,
+    /// Recommendations the second reviewer decided differently.
+    pub overridden: usize,
+    /// Recommendations the second reviewer hasn't decided yet.
+    pub pending: usize,
+}
+
+/// Where `state`'s two-person review stands, or `None` when it isn't one.
+pub fn progress(state: &ReviewState) -> Option<TwoPersonProgress> {
+    let review = state.two_person.clone()?;
+    let mut progress = TwoPersonProgress {
+        review,
+        ..TwoPersonProgress::default()
+    };
+    for hunk in state.hunks.values() {
+        let Some(recommendation) = &hunk.recommendation else {
+            continue;
+        };
+        if recommendation.source == Source::Carried {
+            continue;
+        }
+        progress.recommended += 1;
+        match &hunk.status {
+            Some(status) if status.value == recommendation.value => progress.confirmed += 1,
+            Some(_) => progress.overridden += 1,
+            None => progress.pending += 1,
+        }
+    }
+    Some(progress)
+}
+
+/// Why a two-person review doesn't pass `review check` yet, or `None` when
+/// it does (or isn't one).
+pub fn gate_failure(state: &ReviewState) -> Option<String> {
+    let review = state.two_person.as_ref()?;
+    match (&review.handoff, &review.signoff) {
+        (None, _) => Some("two-person review awaits the first reviewer's hand-off".to_owned()),
+        (Some(handoff), None) => Some(format!(
+            "two-person review awaits a second reviewer's sign-off (handed off by {})",
+            handoff.by
+        )),
+        (Some(_), Some(_)) => None,
+    }
+}
+
+/// Load `ref_name`'s review, apply `update`, and save it, reapplying when
+/// another writer saved in between.
+fn update_review<T>(
+    repo_path: &Path,
+    ref_name: &str,
+    update: impl Fn(&mut ReviewState) -> anyhow::Result<T>,
+) -> anyhow::Result<(ReviewState, T)> {
+    for attempt in 0..MAX_SAVE_RETRIES {
+        let mut state = storage::load_review_state(repo_path, ref_name)?;
+        let result = update(&mut state)?;
+        state.prepare_for_save();
+        match storage::save_review_state(repo_path, &state) {
+            Ok(()) => return Ok((state, result)),
+            Err(StorageError::VersionConflict { .. }) if attempt + 1 < MAX_SAVE_RETRIES => {}
+            Err(e) => return Err(e).context("Failed to save review"),
+        }
+    }
+    anyhow::bail!("Failed to save review after repeated version conflicts")
+}
+
+/// Mark `ref_name`'s review as needing a second reviewer. A review already
+/// marked is left as it is.
+pub fn require_second_reviewer(repo_path: &Path, ref_name: &str) -> anyhow::Result<ReviewState> {
+    update_review(repo_path, ref_name, |state| {
+        state
+            .two_person
+            .get_or_insert_with(TwoPersonReview::default);
+        Ok(())
+    })
+    .map(|(state, ())| state)
+}
+
+/// The first reviewer, `by`, hands the review off: every decision so far
+/// becomes a recommendation for the second reviewer. Returns how many.
+pub fn hand_off(repo_path: &Path, ref_name: &str, by: &str) -> anyhow::Result<usize> {
+    update_review(repo_path, ref_name, |state| {
+        let review = state
+            .two_person
+            .as_mut()
+            .context("This review doesn't require a second reviewer")?;
+        if let Some(handoff) = &review.handoff {
+            anyhow::bail!("Already handed off by {} at {}", handoff.by, handoff.at);
+        }
+        review.handoff = Some(SignOff {
+            by: by.to_owned(),
+            at: now_iso8601(),
+        });
+        let mut recommended = 0;
+        for hunk in state.hunks.values_mut() {
+            if let Some(status) = hunk.status.take() {
+                hunk.recommendation = Some(status);
+                recommended += 1;
+            }
+        }
+        Ok(recommended)
+    })
+    .map(|(_, recommended)| recommended)
+}
+
+/// The second reviewer, `by`, signs off. Refused for the reviewer who handed
+/// off, and while any recommendation is neither confirmed nor overridden.
+pub fn sign_off(repo_path: &Path, ref_name: &str, by: &str) -> anyhow::Result<TwoPersonProgress> {
+    let (state, ()) = update_review(repo_path, ref_name, |state| {
+        let pending = progress(state).map_or(0, |progress| progress.pending);
+        let review = state
+            .two_person
+            .as_mut()
+            .context("This review doesn't require a second reviewer")?;
+        let handoff = review
+            .handoff
+            .as_ref()
+            .context("The first reviewer hasn't handed off yet")?;
+        anyhow::ensure!(
+            handoff.by != by,
+            "{by} handed this review off; the sign-off must come from someone else"
+        );
+        anyhow::ensure!(
+            pending == 0,
+            "{pending} recommended hunk(s) not yet confirmed or overridden"
+        );
+        review.signoff = Some(SignOff {
+            by: by.to_owned(),
+            at: now_iso8601(),
+        });
+        Ok(())
+    })?;
+    progress(&state).context("Two-person review missing after sign-off")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use crate::review::state::{Attributed, HunkState, HunkStatus, Source};
+
+    fn decided(status: HunkStatus) -> HunkState {
+        HunkState {
+            status: Some(Attributed::new(status, Source::Ui)),
+            ..HunkState::default()
+        }
+    }
+
+    #[test]
+    fn hand_off_then_sign_off_by_someone_else() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_guard, _home, repo) = setup_test();
+        let repo = repo.path();
+
+        let mut state = ReviewState::new("feature", None);
+        state
+            .hunks
+            .insert("a.rs:1".to_owned(), decided(HunkStatus::Approved));
+        state
+            .hunks
+            .insert("b.rs:2".to_owned(), decided(HunkStatus::Rejected));
+        storage::save_review_state(repo, &state).unwrap();
+        let state = require_second_reviewer(repo, "feature").unwrap();
+        assert!(gate_failure(&state)
+            .unwrap()
+            .contains("first reviewer's hand-off"));
+
+        assert_eq!(hand_off(repo, "feature", "ana").unwrap(), 2);
+        assert!(hand_off(repo, "feature", "ana").is_err());
+        let state = storage::load_review_state(repo, "feature").unwrap();
+        assert!(state.hunks["a.rs:1"].status.is_none());
+        assert!(gate_failure(&state).unwrap().contains("handed off by ana"));
+
+        // Both recommendations still need deciding, and not by ana.
+        assert!(sign_off(repo, "feature", "ben").is_err());
+        let mut state = storage::load_review_state(repo, "feature").unwrap();
+        for (id, status) in [
+            ("a.rs:1", HunkStatus::Approved),
+            ("b.rs:2", HunkStatus::Approved),
+        ] {
+            state.hunks.get_mut(id).unwrap().status = Some(Attributed::new(status, Source::Ui));
+        }
+        state.prepare_for_save();
+        storage::save_review_state(repo, &state).unwrap();
+        assert!(sign_off(repo, "feature", "ana").is_err());
+
+        let progress = sign_off(repo, "feature", "ben").unwrap();
+        assert_eq!((progress.confirmed, progress.overridden), (1, 1));
+        let state = storage::load_review_state(repo, "feature").unwrap();
+        assert_eq!(gate_failure(&state), None);
+    }
+}
@@ -1,6 +1,6 @@
 //! Pure utility functions shared across the service layer.
 
-use anyhow::{bail, Context};
+use anyhow::bail;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use std::path::{Path, PathBuf};
 
@@ -14,11 +14,11 @@ pub fn repo_relative_path(path: &Path, repo_root: &Path) -> String {
         .unwrap_or_else(|_| path.to_string_lossy().into_owned())
 }
 
-/// Walk up from `start` to find a directory containing `.git/`.
+/// Walk up from `start` to find a directory containing `.git/` (or `.jj/`).
 pub fn find_repo_root(start: &Path) -> Option<PathBuf> {
     let mut current = start;
     loop {
-        if current.join(".git").exists() {
+        if crate::sources::jj::is_repo_root(current) {
             return Some(current.to_path_buf());
         }
         match current.parent() {
@@ -231,11 +231,15 @@ pub fn bytes_to_file_content(bytes: Vec<u8>, file_path: &str) -> anyhow::Result<
             content_type,
             image_data_url,
             old_image_data_url: None,
+            deferred: None,
+            encoding: None,
+            not_materialized: false,
         });
     }
 
-    let content = String::from_utf8(bytes)
-        .with_context(|| format!("File is not valid UTF-8: {file_path}"))?;
+    let Some((content, encoding)) = crate::diff::encoding::decode(bytes) else {
+        bail!("File is binary: {file_path}");
+    };
     Ok(FileContent {
         content,
         old_content: None,
@@ -244,6 +248,9 @@ pub fn bytes_to_file_content(bytes: Vec<u8>, file_path: &str) -> anyhow::Result<
         content_type,
         image_data_url: None,
         old_image_data_url: None,
+        deferred: None,
+        encoding,
+        not_materialized: false,
     })
 }
 
@@ -2,24 +2,117 @@
 
 use log::{debug, info};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use super::util::strip_jsonc_comments;
 use super::VscodeThemeDetection;
 
+/// A VS Code build: where its settings, user extensions, and built-in
+/// extensions live.
+struct Flavor {
+    /// Directory under the platform config dir (`~/Library/Application Support`,
+    /// `~/.config`, or `%APPDATA%`) holding `User/settings.json`.
+    config_dir: &'static str,
+    /// Directory under home holding user-installed extensions.
+    extensions_dir: &'static str,
+    /// App bundle name under `/Applications` (macOS).
+    mac_app: &'static str,
+    /// Install roots of the distro packages, tarballs, and snaps (Linux).
+    linux_installs: &'static [&'static str],
+    /// Install directory name under `Programs` or `Program Files` (Windows).
+    windows_app: &'static str,
+}
+
+const FLAVORS: [Flavor; 3] = [
+    Flavor {
+        config_dir: "Code",
+        extensions_dir: ".vscode",
+        mac_app: "Visual Studio Code",
+        linux_installs: &[
+            "/usr/share/code",
+            "/opt/visual-studio-code",
+            "/snap/code/current/usr/share/code",
+        ],
+        windows_app: "Microsoft VS Code",
+    },
+    Flavor {
+        config_dir: "Code - Insiders",
+        extensions_dir: ".vscode-insiders",
+        mac_app: "Visual Studio Code - Insiders",
+        linux_installs: &[
+            "/usr/share/code-insiders",
+            "/opt/visual-studio-code-insiders",
+            "/snap/code-insiders/current/usr/share/code-insiders",
+        ],
+        windows_app: "Microsoft VS Code Insiders",
+    },
+    Flavor {
+        config_dir: "VSCodium",
+        extensions_dir: ".vscode-oss",
+        mac_app: "VSCodium",
+        linux_installs: &[
+            "/usr/share/codium",
+            "/opt/vscodium-bin",
+            "/snap/codium/current/usr/share/codium",
+        ],
+        windows_app: "VSCodium",
+    },
+];
+
+impl Flavor {
+    fn settings_path(&self, config_root: &Path) -> PathBuf {
+        config_root
+            .join(self.config_dir)
+            .join("User")
+            .join("settings.json")
+    }
+
+    /// User-installed extensions first, then the built-in themes shipped
+    /// with the app.
+    fn extension_dirs(&self, home: &Path) -> Vec<PathBuf> {
+        let mut dirs = vec![home.join(self.extensions_dir).join("extensions")];
+        let builtin = Path::new("resources").join("app").join("extensions");
+        if cfg!(target_os = "macos") {
+            let bundle = format!("{}.app/Contents/Resources/app/extensions", self.mac_app);
+            dirs.push(Path::new("/Applications").join(&bundle));
+            dirs.push(home.join("Applications").join(&bundle));
+        } else if cfg!(windows) {
+            let roots = [
+                dirs::data_local_dir().map(|d| d.join("Programs")),
+                std::env::var_os("ProgramFiles").map(PathBuf::from),
+            ];
+            dirs.extend(
+                roots
+                    .into_iter()
+                    .flatten()
+                    .map(|root| root.join(self.windows_app).join(&builtin)),
+            );
+        } else {
+            dirs.extend(
+                self.linux_installs
+                    .iter()
+                    .map(|root| Path::new(root).join(&builtin)),
+            );
+        }
+        dirs
+    }
+}
+
 /// Detect the active VS Code theme by reading VS Code settings and extension files.
+///
+/// Checks VS Code, then Insiders, then VSCodium, using the first whose
+/// settings exist.
 pub fn detect_vscode_theme() -> anyhow::Result<VscodeThemeDetection> {
     let home =
         dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+    let config_root = dirs::config_dir()
+        .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
 
-    // Try VS Code, then VS Code Insiders
-    let settings_path = [
-        home.join("Library/Application Support/Code/User/settings.json"),
-        home.join("Library/Application Support/Code - Insiders/User/settings.json"),
-    ]
-    .into_iter()
-    .find(|p| p.exists())
-    .ok_or_else(|| anyhow::anyhow!("VS Code settings.json not found"))?;
+    let (flavor, settings_path) = FLAVORS
+        .iter()
+        .map(|flavor| (flavor, flavor.settings_path(&config_root)))
+        .find(|(_, p)| p.exists())
+        .ok_or_else(|| anyhow::anyhow!("VS Code settings.json not found"))?;
 
     let settings_str = std::fs::read_to_string(&settings_path)
         .map_err(|e| anyhow::anyhow!("Failed to read settings: {e}"))?;
@@ -35,22 +128,19 @@ pub fn detect_vscode_theme() -> anyhow::Result<VscodeThemeDetection> {
         .ok_or_else(|| anyhow::anyhow!("workbench.colorTheme not set in VS Code settings"))?
         .to_owned();
 
-    debug!("[detect_vscode_theme] Active theme: {theme_name}");
-
-    // Search user-installed extensions first, then built-in themes in the app bundle
-    let search_dirs = [
-        home.join(".vscode/extensions"),
-        home.join(".vscode-insiders/extensions"),
-        std::path::PathBuf::from(
-            "/Applications/Visual Studio Code.app/Contents/Resources/app/extensions",
-        ),
-        std::path::PathBuf::from(
-            "/Applications/Visual Studio Code - Insiders.app/Contents/Resources/app/extensions",
-        ),
-    ];
-
-    for dir in &search_dirs {
-        if let Some(detection) = search_extensions_for_theme(dir, &theme_name) {
+    debug!(
+        "[detect_vscode_theme] Active theme: {theme_name} (from {})",
+        settings_path.display()
+    );
+
+    // The matching build's extensions first, then the other builds', which
+    // may share a user-installed theme
+    let search_dirs = std::iter::once(flavor)
+        .chain(FLAVORS.iter().filter(|f| f.config_dir != flavor.config_dir))
+        .flat_map(|f| f.extension_dirs(&home));
+
+    for dir in search_dirs {
+        if let Some(detection) = search_extensions_for_theme(&dir, &theme_name) {
             return Ok(detection);
         }
     }
@@ -175,3 +265,24 @@ fn matches_localized_theme(ext_path: &Path, id: &str, theme_name: &str) -> bool
     let normalized = theme_name.to_lowercase().replace(' ', "-");
     ext_name.to_lowercase().contains(&normalized)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flavors_use_platform_locations() {
+        let codium = &FLAVORS[2];
+        assert_eq!(
+            codium.settings_path(Path::new("/cfg")),
+            Path::new("/cfg/VSCodium/User/settings.json")
+        );
+
+        let dirs = codium.extension_dirs(Path::new("/home/u"));
+        assert_eq!(dirs[0], Path::new("/home/u/.vscode-oss/extensions"));
+        assert!(dirs.len() > 1);
+        if cfg!(target_os = "linux") {
+            assert!(dirs.contains(&PathBuf::from("/usr/share/codium/resources/app/extensions")));
+        }
+    }
+}
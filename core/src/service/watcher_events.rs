@@ -5,6 +5,7 @@
 //! shape the `git-changed` payload.
 
 use serde::Serialize;
+use std::path::{Path, PathBuf};
 
 /// Payload for the `git-changed` event. Carries the set of working-tree paths
 /// that changed in the debounce window, so the frontend can refresh only those
@@ -49,6 +50,41 @@ pub fn is_git_state_path(path_str: &str) -> bool {
         || path_str.ends_with("\\.git\\index")
 }
 
+/// The git-internal locations of a linked worktree that a recursive watch of
+/// its working tree doesn't reach: its own `HEAD` and `index` live in the main
+/// repo's `.git/worktrees/<name>/`, and branches in the shared `refs/heads/`.
+pub struct LinkedWorktreeDirs {
+    /// Per-worktree admin dir (`<main>/.git/worktrees/<name>`).
+    pub git_dir: PathBuf,
+    /// The shared `refs/heads/` of the main repo.
+    pub refs_heads: PathBuf,
+}
+
+/// Resolve [`LinkedWorktreeDirs`] for `repo_path`, or `None` when it's a
+/// regular checkout whose `.git/` sits inside the watched tree.
+pub fn linked_worktree_dirs(repo_path: &Path) -> Option<LinkedWorktreeDirs> {
+    let (git_dir, common_dir) = crate::review::central::resolve_git_dirs(repo_path);
+    if git_dir == common_dir {
+        return None;
+    }
+    // notify reports canonical paths (e.g. `/private/var` on macOS).
+    let canonical = |p: PathBuf| p.canonicalize().unwrap_or(p);
+    Some(LinkedWorktreeDirs {
+        git_dir: canonical(git_dir),
+        refs_heads: canonical(common_dir.join("refs").join("heads")),
+    })
+}
+
+/// Returns true if `path` is the `HEAD` or `index` of the linked worktree
+/// whose admin dir is `git_dir`. Other worktrees' admin files stay ignored,
+/// so staging in one worktree doesn't refresh every window of the repo.
+pub fn is_worktree_state_path(path: &Path, git_dir: &Path) -> bool {
+    path.parent() == Some(git_dir)
+        && path
+            .file_name()
+            .is_some_and(|name| name == "HEAD" || name == "index")
+}
+
 /// Returns true if `.git`-internal noise (lock files, pack files, logs) or
 /// common build-output directories (`target/`, `node_modules/`, ...) should be
 /// dropped before further categorization.
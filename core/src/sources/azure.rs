@@ -0,0 +1,442 @@
+//! Azure DevOps pull request provider.
+//!
+//! Pull requests are listed with the `az repos` CLI, or through the REST API
+//! when `AZURE_DEVOPS_EXT_PAT` holds a personal access token (the variable
+//! `az` itself reads). Azure DevOps has no unified-diff endpoint, so a PR is
+//! diffed locally: [`AzureDevOpsProvider::fetch_comparison`] fetches the
+//! merge commit the server publishes at `refs/pull/<id>/merge` — or, when the
+//! PR doesn't merge cleanly, its source and target branches — into
+//! `refs/azure/pr/<id>/…`, giving an ordinary [`Comparison`].
+
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use super::local_git::LocalGitSource;
+use super::traits::Comparison;
+
+/// Environment variable with a personal access token for the REST API.
+pub const PAT_ENV: &str = "AZURE_DEVOPS_EXT_PAT";
+
+/// Local namespace PR refs are fetched into.
+pub const LOCAL_REF_PREFIX: &str = "refs/azure/pr";
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// Lightweight PR reference, passed alongside a comparison like
+/// [`super::github::GitHubPrRef`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzurePrRef {
+    pub id: u32,
+    pub title: String,
+    /// Full ref, e.g. `refs/heads/feature`.
+    pub source_ref_name: String,
+    pub target_ref_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+}
+
+/// A pull request as `az repos pr list` and the REST API return it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzurePullRequest {
+    #[serde(rename = "pullRequestId")]
+    pub id: u32,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub source_ref_name: String,
+    pub target_ref_name: String,
+    pub status: String,
+    #[serde(default)]
+    pub is_draft: bool,
+    pub creation_date: String,
+    pub created_by: AzureIdentity,
+}
+
+/// Author of a pull request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AzureIdentity {
+    pub display_name: String,
+    #[serde(default)]
+    pub unique_name: String,
+}
+
+impl AzurePullRequest {
+    pub fn to_ref(&self) -> AzurePrRef {
+        AzurePrRef {
+            id: self.id,
+            title: self.title.clone(),
+            source_ref_name: self.source_ref_name.clone(),
+            target_ref_name: self.target_ref_name.clone(),
+            body: (!self.description.is_empty()).then(|| self.description.clone()),
+        }
+    }
+}
+
+/// The organization, project and repository an Azure Repos remote names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzureRepo {
+    pub organization: String,
+    pub project: String,
+    pub repository: String,
+}
+
+impl AzureRepo {
+    /// The REST API root for the repository's git resources.
+    pub fn api_url(&self) -> String {
+        format!(
+            "https://dev.azure.com/{}/{}/_apis/git/repositories/{}",
+            self.organization, self.project, self.repository
+        )
+    }
+}
+
+/// Parse an Azure Repos remote URL, in any of its forms:
+/// `https://[user@]dev.azure.com/<org>/<project>/_git/<repo>`,
+/// `https://<org>.visualstudio.com/[DefaultCollection/]<project>/_git/<repo>`,
+/// and the SSH `…@ssh.dev.azure.com:v3/<org>/<project>/<repo>` (or
+/// `vs-ssh.visualstudio.com`).
+pub fn parse_remote_url(url: &str) -> Option<AzureRepo> {
+    let url = url.trim().trim_end_matches('/');
+    let repo = |organization: &str, project: &str, repository: &str| {
+        Some(AzureRepo {
+            organization: organization.to_owned(),
+            project: project.to_owned(),
+            repository: repository
+                .strip_suffix(".git")
+                .unwrap_or(repository)
+                .to_owned(),
+        })
+    };
+
+    if let Some((_, path)) = url
+        .split_once("ssh.dev.azure.com:v3/")
+        .or_else(|| url.split_once("vs-ssh.visualstudio.com:v3/"))
+    {
+        let mut parts = path.splitn(3, '/');
+        return repo(parts.next()?, parts.next()?, parts.next()?);
+    }
+
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let rest = rest.split_once('@').map_or(rest, |(_user, rest)| rest);
+    let (host, path) = rest.split_once('/')?;
+    let (before, repository) = path.split_once("/_git/")?;
+    if host == "dev.azure.com" {
+        let (organization, project) = before.split_once('/')?;
+        repo(organization, project, repository)
+    } else {
+        let organization = host.strip_suffix(".visualstudio.com")?;
+        let project = before.strip_prefix("DefaultCollection/").unwrap_or(before);
+        repo(organization, project, repository)
+    }
+}
+
+/// Pull requests from `az repos pr list` (a JSON array) or the REST API (the
+/// array wrapped in `value`).
+pub fn parse_pull_requests(json: &str) -> Result<Vec<AzurePullRequest>, AzureError> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Listing {
+        Cli(Vec<AzurePullRequest>),
+        Rest { value: Vec<AzurePullRequest> },
+    }
+
+    match serde_json::from_str(json).map_err(|e| AzureError::Parse(e.to_string()))? {
+        Listing::Cli(prs) | Listing::Rest { value: prs } => Ok(prs),
+    }
+}
+
+/// The short name of a local ref a PR's `kind` (`merge`, `source` or
+/// `target`) is fetched into. Git resolves it to `refs/azure/pr/<id>/<kind>`.
+pub fn local_ref(id: u32, kind: &str) -> String {
+    format!("azure/pr/{id}/{kind}")
+}
+
+// ---------------------------------------------------------------------------
+// Error type
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+pub enum AzureError {
+    Io(String),
+    Command(String),
+    Parse(String),
+    Offline(String),
+}
+
+impl std::fmt::Display for AzureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(msg) => write!(f, "Azure DevOps I/O error: {msg}"),
+            Self::Command(msg) => write!(f, "Azure DevOps command error: {msg}"),
+            Self::Parse(msg) => write!(f, "Azure DevOps parse error: {msg}"),
+            Self::Offline(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for AzureError {}
+
+// ---------------------------------------------------------------------------
+// AzureDevOpsProvider
+// ---------------------------------------------------------------------------
+
+/// Azure DevOps pull requests of the repo's review remote (`origin` unless
+/// configured otherwise).
+pub struct AzureDevOpsProvider {
+    repo_path: PathBuf,
+    remote: String,
+}
+
+impl AzureDevOpsProvider {
+    pub fn new(repo_path: PathBuf) -> Self {
+        let remote = LocalGitSource::new(repo_path.clone())
+            .ok()
+            .and_then(|source| source.review_remote())
+            .unwrap_or_else(|| "origin".to_owned());
+        Self { repo_path, remote }
+    }
+
+    fn ensure_online() -> Result<(), AzureError> {
+        crate::offline::ensure_online("Azure DevOps")
+            .map_err(|e| AzureError::Offline(e.to_string()))
+    }
+
+    fn pat() -> Option<String> {
+        std::env::var(PAT_ENV).ok().filter(|pat| !pat.is_empty())
+    }
+
+    fn run(command: &mut Command) -> Result<String, AzureError> {
+        let output = command
+            .output()
+            .map_err(|e| AzureError::Io(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AzureError::Command(stderr.trim().to_owned()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String, AzureError> {
+        Self::run(Command::new("git").args(args).current_dir(&self.repo_path))
+    }
+
+    /// The Azure Repos repository the remote points at.
+    pub fn repo(&self) -> Result<AzureRepo, AzureError> {
+        let url = self.git(&["remote", "get-url", &self.remote])?;
+        parse_remote_url(&url)
+            .ok_or_else(|| AzureError::Parse(format!("not an Azure Repos remote: {}", url.trim())))
+    }
+
+    /// Returns `true` when the remote is on Azure Repos and there's a way to
+    /// reach it: a token for the REST API, or a signed-in `az`.
+    pub fn is_available(&self) -> bool {
+        if crate::offline::is_offline() || self.repo().is_err() {
+            return false;
+        }
+        Self::pat().is_some()
+            || Command::new("az")
+                .args(["account", "show"])
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|s| s.success())
+    }
+
+    /// List active pull requests for the repository.
+    pub fn list_pull_requests(&self) -> Result<Vec<AzurePullRequest>, AzureError> {
+        Self::ensure_online()?;
+        let json = match Self::pat() {
+            Some(pat) => {
+                let url = format!(
+                    "{}/pullrequests?searchCriteria.status=active&api-version=7.1",
+                    self.repo()?.api_url()
+                );
+                Self::rest_get(&url, &pat)?
+            }
+            None => Self::run(
+                Command::new("az")
+                    .args(["repos", "pr", "list", "--status", "active"])
+                    .args(["--detect", "true", "--output", "json"])
+                    .current_dir(&self.repo_path),
+            )?,
+        };
+        parse_pull_requests(&json)
+    }
+
+    /// GET `url` with basic auth, the token passed on stdin rather than in
+    /// the process arguments.
+    fn rest_get(url: &str, pat: &str) -> Result<String, AzureError> {
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--max-time", "30"])
+            .args(["--config", "-", url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| AzureError::Io(e.to_string()))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            writeln!(stdin, "user = \":{pat}\"").map_err(|e| AzureError::Io(e.to_string()))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| AzureError::Io(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(AzureError::Command(stderr.trim().to_owned()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn fetch(&self, server_ref: &str, id: u32, kind: &str) -> Result<(), AzureError> {
+        let refspec = format!("+{server_ref}:{LOCAL_REF_PREFIX}/{id}/{kind}");
+        self.git(&["fetch", "--quiet", "--no-tags", &self.remote, &refspec])
+            .map(drop)
+    }
+
+    fn has_local_ref(&self, id: u32, kind: &str) -> bool {
+        let full = format!("{LOCAL_REF_PREFIX}/{id}/{kind}");
+        self.git(&["rev-parse", "--verify", "--quiet", &full])
+            .is_ok()
+    }
+
+    /// Fetch `pr` and return it as a comparison: the server's merge commit
+    /// against its first parent (the target), or — with no merge commit,
+    /// e.g. on conflicts — the source branch against the target.
+    pub fn fetch_comparison(&self, pr: &AzurePrRef) -> Result<Comparison, AzureError> {
+        Self::ensure_online()?;
+        if self
+            .fetch(&format!("refs/pull/{}/merge", pr.id), pr.id, "merge")
+            .is_ok()
+        {
+            return Ok(Self::merge_comparison(pr.id));
+        }
+        self.fetch(&pr.source_ref_name, pr.id, "source")?;
+        self.fetch(&pr.target_ref_name, pr.id, "target")?;
+        Ok(Self::branch_comparison(pr.id))
+    }
+
+    /// `pr` as a comparison, fetching only when it hasn't been fetched yet.
+    pub fn comparison(&self, pr: &AzurePrRef) -> Result<Comparison, AzureError> {
+        if self.has_local_ref(pr.id, "merge") {
+            Ok(Self::merge_comparison(pr.id))
+        } else if self.has_local_ref(pr.id, "source") && self.has_local_ref(pr.id, "target") {
+            Ok(Self::branch_comparison(pr.id))
+        } else {
+            self.fetch_comparison(pr)
+        }
+    }
+
+    fn merge_comparison(id: u32) -> Comparison {
+        let merge = local_ref(id, "merge");
+        Comparison::new(format!("{merge}^1"), merge)
+    }
+
+    fn branch_comparison(id: u32) -> Comparison {
+        Comparison::new(local_ref(id, "target"), local_ref(id, "source"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::ENV_LOCK;
+
+    fn git(dir: &std::path::Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?}: {output:?}");
+        String::from_utf8_lossy(&output.stdout).trim().to_owned()
+    }
+
+    #[test]
+    fn parses_remotes_and_listings() {
+        let expected = AzureRepo {
+            organization: "acme".to_owned(),
+            project: "Web%20Apps".to_owned(),
+            repository: "site".to_owned(),
+        };
+        for url in [
+            "https://acme@dev.azure.com/acme/Web%20Apps/_git/site",
+            "https://acme.visualstudio.com/DefaultCollection/Web%20Apps/_git/site",
+            "git@ssh.dev.azure.com:v3/acme/Web%20Apps/site",
+            "acme@vs-ssh.visualstudio.com:v3/acme/Web%20Apps/site.git\n",
+        ] {
+            assert_eq!(parse_remote_url(url).as_ref(), Some(&expected), "{url}");
+        }
+        assert_eq!(parse_remote_url("git@github.com:acme/site.git"), None);
+        assert!(expected
+            .api_url()
+            .ends_with("/acme/Web%20Apps/_apis/git/repositories/site"));
+
+        let pr = r#"{"pullRequestId": 7, "title": "Fix", "sourceRefName": "refs/heads/fix",
+            "targetRefName": "refs/heads/main", "status": "active",
+            "creationDate": "2026-01-01T00:00:00Z", "createdBy": {"displayName": "Ana"}}"#;
+        let cli = parse_pull_requests(&format!("[{pr}]")).unwrap();
+        let rest = parse_pull_requests(&format!("{{\"value\": [{pr}], \"count\": 1}}")).unwrap();
+        assert_eq!(cli[0].id, 7);
+        assert_eq!(rest[0].created_by.display_name, "Ana");
+        assert_eq!(rest[0].to_ref().body, None);
+    }
+
+    #[test]
+    fn fetches_merge_commit_or_branches() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        std::env::remove_var(crate::offline::OFFLINE_ENV);
+        let server = tempfile::TempDir::new().unwrap();
+        let server = server.path();
+        git(server, &["init", "-q", "-b", "main"]);
+        git(server, &["config", "user.email", "t@example.com"]);
+        git(server, &["config", "user.name", "T"]);
+        std::fs::write(server.join("a.txt"), "one\n").unwrap();
+        git(server, &["add", "."]);
+        git(server, &["commit", "-qm", "base"]);
+        git(server, &["checkout", "-q", "-b", "fix"]);
+        std::fs::write(server.join("a.txt"), "one\ntwo\n").unwrap();
+        git(server, &["commit", "-qam", "fix"]);
+        git(server, &["checkout", "-q", "main"]);
+        let clone = tempfile::TempDir::new().unwrap();
+        let clone = clone.path();
+        git(clone, &["init", "-q"]);
+        git(
+            clone,
+            &["remote", "add", "origin", server.to_str().unwrap()],
+        );
+
+        let provider = AzureDevOpsProvider::new(clone.to_path_buf());
+        let pr = |id| AzurePrRef {
+            id,
+            title: "Fix".to_owned(),
+            source_ref_name: "refs/heads/fix".to_owned(),
+            target_ref_name: "refs/heads/main".to_owned(),
+            body: None,
+        };
+
+        // No merge commit published: the branches are fetched.
+        let comparison = provider.fetch_comparison(&pr(1)).unwrap();
+        assert_eq!(comparison.key, "azure/pr/1/target..azure/pr/1/source");
+        assert!(git(clone, &["diff", &comparison.base, &comparison.head]).contains("+two"));
+
+        git(
+            server,
+            &["merge", "-q", "--no-ff", "-m", "Merge PR 2", "fix"],
+        );
+        git(server, &["update-ref", "refs/pull/2/merge", "HEAD"]);
+        let comparison = provider.fetch_comparison(&pr(2)).unwrap();
+        assert_eq!(comparison.key, "azure/pr/2/merge^1..azure/pr/2/merge");
+        assert!(git(clone, &["diff", &comparison.base, &comparison.head]).contains("+two"));
+        assert_eq!(provider.comparison(&pr(2)).unwrap().key, comparison.key);
+    }
+}
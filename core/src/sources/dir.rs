@@ -0,0 +1,248 @@
+//! Comparing two plain directories — exported trees, unpacked releases, build
+//! output — where there's no git history to diff.
+//!
+//! [`DirSource`] walks both trees, pairs files by relative path, and diffs
+//! each changed pair with `git diff --no-index`, which needs the git binary
+//! but no repository. The hunks come out exactly as
+//! [`LocalGitSource`](super::local_git::LocalGitSource)'s do (same parser,
+//! same splitting and merging), so classification and move detection work on
+//! them unchanged.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::Serialize;
+use thiserror::Error;
+use walkdir::WalkDir;
+
+use super::traits::FileStatus;
+use crate::diff::parser::{self, DiffHunk};
+
+/// Directories never compared: VCS metadata isn't part of the tree.
+const SKIPPED_DIRS: &[&str] = &[".git", ".jj", ".hg", ".svn"];
+
+#[derive(Error, Debug)]
+pub enum DirError {
+    #[error("Not a directory: {0}")]
+    NotADirectory(PathBuf),
+    #[error("Git error: {0}")]
+    Git(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A file that differs between the two directories.
+#[derive(Debug, Clone, Serialize)]
+pub struct DirChange {
+    /// Path relative to both roots, `/`-separated.
+    pub path: String,
+    /// `Added`, `Deleted`, or `Modified`.
+    pub status: FileStatus,
+}
+
+/// Two directory trees compared file by file: `old` as the base, `new` as the
+/// head.
+#[derive(Debug, Clone)]
+pub struct DirSource {
+    old: PathBuf,
+    new: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(old: impl Into<PathBuf>, new: impl Into<PathBuf>) -> Result<Self, DirError> {
+        let (old, new) = (old.into(), new.into());
+        for dir in [&old, &new] {
+            if !dir.is_dir() {
+                return Err(DirError::NotADirectory(dir.clone()));
+            }
+        }
+        Ok(Self { old, new })
+    }
+
+    pub fn old_root(&self) -> &Path {
+        &self.old
+    }
+
+    pub fn new_root(&self) -> &Path {
+        &self.new
+    }
+
+    /// Files that were added, deleted, or whose bytes differ, sorted by path.
+    pub fn changed_files(&self) -> Result<Vec<DirChange>, DirError> {
+        let old = list_files(&self.old)?;
+        let new = list_files(&self.new)?;
+        let mut changes = Vec::new();
+        for (path, old_path) in &old {
+            let status = match new.get(path) {
+                None => FileStatus::Deleted,
+                Some(new_path) if !same_contents(old_path, new_path)? => FileStatus::Modified,
+                Some(_) => continue,
+            };
+            changes.push(DirChange {
+                path: path.clone(),
+                status,
+            });
+        }
+        changes.extend(
+            new.keys()
+                .filter(|path| !old.contains_key(*path))
+                .map(|path| DirChange {
+                    path: path.clone(),
+                    status: FileStatus::Added,
+                }),
+        );
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(changes)
+    }
+
+    /// The hunks of one changed file. A binary file gets a single placeholder
+    /// hunk, as in a git review.
+    pub fn file_hunks(&self, change: &DirChange) -> Result<Vec<DiffHunk>, DirError> {
+        let side = |root: &Path, present: bool| {
+            if present {
+                root.join(&change.path)
+            } else {
+                PathBuf::from(NULL_DEVICE)
+            }
+        };
+        let old = side(&self.old, !matches!(change.status, FileStatus::Added));
+        let new = side(&self.new, !matches!(change.status, FileStatus::Deleted));
+        let diff = diff_no_index(&old, &new)?;
+        let hunks = parser::parse_diff(&diff, &change.path);
+        if hunks.is_empty() && diff.lines().any(|l| l.starts_with("Binary files ")) {
+            return Ok(vec![parser::create_binary_hunk(&change.path)]);
+        }
+        Ok(hunks)
+    }
+
+    /// Every hunk between the two trees, with moved code paired up.
+    pub fn all_hunks(&self) -> Result<Vec<DiffHunk>, DirError> {
+        let mut hunks = Vec::new();
+        for change in self.changed_files()? {
+            hunks.extend(self.file_hunks(&change)?);
+        }
+        parser::detect_move_pairs(&mut hunks);
+        Ok(hunks)
+    }
+}
+
+#[cfg(windows)]
+const NULL_DEVICE: &str = "NUL";
+#[cfg(not(windows))]
+const NULL_DEVICE: &str = "/dev/null";
+
+/// Files (and symlinks) under `root`, by `/`-separated relative path.
+fn list_files(root: &Path) -> Result<BTreeMap<String, PathBuf>, DirError> {
+    let mut files = BTreeMap::new();
+    let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+        !(entry.file_type().is_dir()
+            && entry.depth() > 0
+            && SKIPPED_DIRS.contains(&entry.file_name().to_string_lossy().as_ref()))
+    });
+    for entry in walker {
+        let entry = entry.map_err(|e| DirError::Io(e.into()))?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        files.insert(path, entry.into_path());
+    }
+    Ok(files)
+}
+
+/// Whether two files hold the same bytes (symlinks: the same target).
+fn same_contents(a: &Path, b: &Path) -> Result<bool, DirError> {
+    let (meta_a, meta_b) = (a.symlink_metadata()?, b.symlink_metadata()?);
+    if meta_a.file_type().is_symlink() || meta_b.file_type().is_symlink() {
+        return Ok(std::fs::read_link(a).ok() == std::fs::read_link(b).ok());
+    }
+    if meta_a.len() != meta_b.len() {
+        return Ok(false);
+    }
+    Ok(std::fs::read(a)? == std::fs::read(b)?)
+}
+
+/// `git diff --no-index` of two files, with the options a repo review's
+/// hunks are parsed with. Exit status 1 just means they differ.
+fn diff_no_index(old: &Path, new: &Path) -> Result<String, DirError> {
+    let mut command = Command::new("git");
+    command.args([
+        "diff",
+        "--no-index",
+        "--no-color",
+        "--no-ext-diff",
+        "--no-textconv",
+        "--histogram",
+    ]);
+    command.args(parser::inter_hunk_context_arg());
+    let output = command.arg("--").arg(old).arg(new).output()?;
+    match output.status.code() {
+        Some(0 | 1) => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        _ => Err(DirError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::LineType;
+    use std::fs;
+
+    #[test]
+    fn diffs_two_trees_without_a_repo() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (old, new) = (dir.path().join("old"), dir.path().join("new"));
+        for root in [&old, &new] {
+            fs::create_dir_all(root.join("src")).unwrap();
+            fs::create_dir_all(root.join(".git")).unwrap();
+            fs::write(root.join("same.txt"), "unchanged\n").unwrap();
+        }
+        fs::write(old.join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        fs::write(new.join("src/lib.rs"), "fn a() {}\nfn c() {}\n").unwrap();
+        fs::write(old.join("gone.txt"), "bye\n").unwrap();
+        fs::write(new.join("added.txt"), "hi\n").unwrap();
+        fs::write(new.join("logo.bin"), [0u8, 1, 2, 0]).unwrap();
+        fs::write(old.join(".git/HEAD"), "ref: a\n").unwrap();
+        fs::write(new.join(".git/HEAD"), "ref: b\n").unwrap();
+
+        let source = DirSource::new(&old, &new).unwrap();
+        let changes = source.changed_files().unwrap();
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|c| (c.path.as_str(), format!("{:?}", c.status)))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("added.txt", "Added".to_owned()),
+                ("gone.txt", "Deleted".to_owned()),
+                ("logo.bin", "Added".to_owned()),
+                ("src/lib.rs", "Modified".to_owned()),
+            ]
+        );
+
+        let hunks = source.all_hunks().unwrap();
+        let lib = hunks.iter().find(|h| h.file_path == "src/lib.rs").unwrap();
+        assert!(lib.id.starts_with("src/lib.rs:"));
+        let changed: Vec<_> = lib
+            .lines
+            .iter()
+            .filter(|l| l.line_type != LineType::Context)
+            .map(|l| l.content.as_str())
+            .collect();
+        assert_eq!(changed, ["fn b() {}", "fn c() {}"]);
+        let gone = hunks.iter().find(|h| h.file_path == "gone.txt").unwrap();
+        assert_eq!((gone.old_count, gone.new_count), (1, 0));
+        let logo = hunks.iter().find(|h| h.file_path == "logo.bin").unwrap();
+        assert_eq!(logo.content, "(binary file)");
+        assert!(DirSource::new(&old, dir.path().join("missing")).is_err());
+    }
+}
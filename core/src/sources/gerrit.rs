@@ -0,0 +1,393 @@
+//! Gerrit change source.
+//!
+//! A Gerrit change is a series of patch sets, each a commit the server
+//! publishes at `refs/changes/<NN>/<change>/<patch set>` (`NN` being the last
+//! two digits of the change number). [`GerritSource`] looks a change up — by
+//! number, or by Change-Id through the REST API when the repo's
+//! `.review.toml` has a `[gerrit] url` — and fetches patch sets into local
+//! `refs/gerrit/<change>/<patch set>` refs, so each is an ordinary
+//! [`Comparison`] (its parent..itself, or one patch set against another)
+//! that classification and trust patterns work on like any branch.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use super::traits::Comparison;
+use crate::review::repo_config::RepoConfig;
+
+/// Local namespace patch sets are fetched into.
+pub const LOCAL_REF_PREFIX: &str = "refs/gerrit";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchSet {
+    pub number: u32,
+    /// The patch set's commit.
+    pub revision: String,
+    /// Where the server publishes it, e.g. `refs/changes/45/12345/2`.
+    #[serde(rename = "ref")]
+    pub server_ref: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GerritChange {
+    pub number: u32,
+    /// Known when looked up through the REST API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Oldest first.
+    pub patch_sets: Vec<PatchSet>,
+}
+
+impl GerritChange {
+    /// Patch set `number`, or the latest with `None`.
+    pub fn patch_set(&self, number: Option<u32>) -> Option<&PatchSet> {
+        match number {
+            Some(n) => self.patch_sets.iter().find(|ps| ps.number == n),
+            None => self.patch_sets.last(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GerritError {
+    Io(String),
+    Command(String),
+    Parse(String),
+    NotFound(String),
+    Offline(String),
+}
+
+impl std::fmt::Display for GerritError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(msg) => write!(f, "Gerrit I/O error: {msg}"),
+            Self::Command(msg) => write!(f, "Gerrit command error: {msg}"),
+            Self::Parse(msg) => write!(f, "Gerrit parse error: {msg}"),
+            Self::NotFound(msg) => write!(f, "Gerrit change not found: {msg}"),
+            Self::Offline(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for GerritError {}
+
+/// Whether `id` looks like a Change-Id (`I` and 40 hex digits).
+pub fn is_change_id(id: &str) -> bool {
+    id.len() == 41 && id.starts_with('I') && id[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// The server ref of a change's patch set.
+pub fn server_ref(change: u32, patch_set: u32) -> String {
+    format!("refs/changes/{:02}/{change}/{patch_set}", change % 100)
+}
+
+/// The short name of the local ref a patch set is fetched into. Git resolves
+/// it to `refs/gerrit/<change>/<patch set>`.
+pub fn local_ref(change: u32, patch_set: u32) -> String {
+    format!("gerrit/{change}/{patch_set}")
+}
+
+/// Patch sets of `change` from `git ls-remote` output, oldest first. Other
+/// refs under the change (e.g. NoteDb's `meta`) are skipped.
+pub fn parse_ls_remote(change: u32, output: &str) -> Vec<PatchSet> {
+    let prefix = format!("refs/changes/{:02}/{change}/", change % 100);
+    let mut patch_sets: Vec<PatchSet> = output
+        .lines()
+        .filter_map(|line| {
+            let (revision, server_ref) = line.split_once('\t')?;
+            let number = server_ref.strip_prefix(&prefix)?.parse().ok()?;
+            Some(PatchSet {
+                number,
+                revision: revision.to_owned(),
+                server_ref: server_ref.to_owned(),
+            })
+        })
+        .collect();
+    patch_sets.sort_by_key(|ps| ps.number);
+    patch_sets
+}
+
+#[derive(Deserialize)]
+struct RestRevision {
+    #[serde(rename = "_number")]
+    number: u32,
+    #[serde(rename = "ref")]
+    server_ref: String,
+}
+
+#[derive(Deserialize)]
+struct RestChange {
+    #[serde(rename = "_number")]
+    number: u32,
+    change_id: String,
+    subject: String,
+    branch: String,
+    status: String,
+    #[serde(default)]
+    revisions: std::collections::HashMap<String, RestRevision>,
+}
+
+/// A change from a REST `GET /changes/<id>?o=ALL_REVISIONS` response, which
+/// Gerrit prefixes with `)]}'` against XSSI.
+pub fn parse_rest_change(body: &str) -> Result<GerritChange, GerritError> {
+    let json = body.trim_start().trim_start_matches(")]}'");
+    let change: RestChange =
+        serde_json::from_str(json).map_err(|e| GerritError::Parse(e.to_string()))?;
+    let mut patch_sets: Vec<PatchSet> = change
+        .revisions
+        .into_iter()
+        .map(|(revision, r)| PatchSet {
+            number: r.number,
+            revision,
+            server_ref: r.server_ref,
+        })
+        .collect();
+    patch_sets.sort_by_key(|ps| ps.number);
+    Ok(GerritChange {
+        number: change.number,
+        change_id: Some(change.change_id),
+        subject: Some(change.subject),
+        branch: Some(change.branch),
+        status: Some(change.status),
+        patch_sets,
+    })
+}
+
+/// A repo's Gerrit server: its git remote, and optionally its REST API.
+pub struct GerritSource {
+    repo_path: PathBuf,
+    remote: String,
+    url: Option<String>,
+}
+
+impl GerritSource {
+    pub fn new(repo_path: PathBuf, remote: String, url: Option<String>) -> Self {
+        Self {
+            repo_path,
+            remote,
+            url: url.map(|u| u.trim_end_matches('/').to_owned()),
+        }
+    }
+
+    /// The source the repo's `[gerrit]` table describes (remote `origin` and
+    /// no REST API by default).
+    pub fn from_repo_config(repo_path: PathBuf) -> Self {
+        let config = RepoConfig::load(&repo_path)
+            .and_then(|c| c.gerrit)
+            .unwrap_or_default();
+        Self::new(repo_path, config.remote, config.url)
+    }
+
+    fn ensure_online() -> Result<(), GerritError> {
+        crate::offline::ensure_online("Gerrit").map_err(|e| GerritError::Offline(e.to_string()))
+    }
+
+    fn git(&self, args: &[&str]) -> Result<String, GerritError> {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| GerritError::Io(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GerritError::Command(stderr.trim().to_owned()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Look up a change by number or Change-Id. A Change-Id needs the REST
+    /// API; a number is looked up there when configured, else on the remote.
+    pub fn change(&self, id: &str) -> Result<GerritChange, GerritError> {
+        Self::ensure_online()?;
+        if let Some(url) = &self.url {
+            return Self::rest_change(url, id);
+        }
+        if is_change_id(id) {
+            return Err(GerritError::NotFound(format!(
+                "looking up a Change-Id needs `[gerrit] url` in .review.toml ({id})"
+            )));
+        }
+        let number: u32 = id.parse().map_err(|_| {
+            GerritError::NotFound(format!("not a change number or Change-Id: {id}"))
+        })?;
+        let pattern = format!("refs/changes/{:02}/{number}/*", number % 100);
+        let output = self.git(&["ls-remote", &self.remote, &pattern])?;
+        let patch_sets = parse_ls_remote(number, &output);
+        if patch_sets.is_empty() {
+            return Err(GerritError::NotFound(format!(
+                "{number} on remote {}",
+                self.remote
+            )));
+        }
+        Ok(GerritChange {
+            number,
+            change_id: None,
+            subject: None,
+            branch: None,
+            status: None,
+            patch_sets,
+        })
+    }
+
+    fn rest_change(url: &str, id: &str) -> Result<GerritChange, GerritError> {
+        let endpoint = format!("{url}/changes/{id}?o=ALL_REVISIONS");
+        let output = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--netrc-optional"])
+            .args(["--max-time", "30", &endpoint])
+            .output()
+            .map_err(|e| GerritError::Io(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("404") {
+                return Err(GerritError::NotFound(id.to_owned()));
+            }
+            return Err(GerritError::Command(stderr.trim().to_owned()));
+        }
+        parse_rest_change(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Fetch `patch_set` of `change` into its local ref (see [`local_ref`]),
+    /// returning that ref.
+    pub fn fetch_patch_set(
+        &self,
+        change: u32,
+        patch_set: &PatchSet,
+    ) -> Result<String, GerritError> {
+        Self::ensure_online()?;
+        let refspec = format!(
+            "+{}:{LOCAL_REF_PREFIX}/{change}/{}",
+            patch_set.server_ref, patch_set.number
+        );
+        self.git(&["fetch", "--quiet", "--no-tags", &self.remote, &refspec])?;
+        Ok(local_ref(change, patch_set.number))
+    }
+
+    /// Fetch patch set `head` of `change` (the latest with `None`) and, with
+    /// `against`, that patch set too. The comparison is `head` against its
+    /// parent, or against patch set `against` — what changed between them,
+    /// rebases included.
+    pub fn fetch_comparison(
+        &self,
+        change: &GerritChange,
+        head: Option<u32>,
+        against: Option<u32>,
+    ) -> Result<Comparison, GerritError> {
+        let missing = |n: Option<u32>| {
+            GerritError::NotFound(match n {
+                Some(n) => format!("{} has no patch set {n}", change.number),
+                None => format!("{} has no patch sets", change.number),
+            })
+        };
+        let head_ps = change.patch_set(head).ok_or_else(|| missing(head))?;
+        let head_ref = self.fetch_patch_set(change.number, head_ps)?;
+        let base = match against {
+            Some(n) => {
+                let base_ps = change.patch_set(Some(n)).ok_or_else(|| missing(Some(n)))?;
+                self.fetch_patch_set(change.number, base_ps)?
+            }
+            None => format!("{head_ref}^"),
+        };
+        Ok(Comparison::new(base, head_ref))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::ENV_LOCK;
+
+    fn git(dir: &std::path::Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {args:?}: {output:?}");
+        String::from_utf8_lossy(&output.stdout).trim().to_owned()
+    }
+
+    #[test]
+    fn parses_rest_and_ls_remote_changes() {
+        assert!(is_change_id("I8473b95934b5732ac55d26311a706c9c2bde9940"));
+        assert!(!is_change_id("12345"));
+        assert_eq!(server_ref(12345, 2), "refs/changes/45/12345/2");
+        assert_eq!(server_ref(7, 1), "refs/changes/07/7/1");
+
+        let ls_remote = "bbb\trefs/changes/45/12345/2\n\
+                         ccc\trefs/changes/45/12345/meta\n\
+                         aaa\trefs/changes/45/12345/1\n\
+                         ddd\trefs/changes/45/112345/1\n";
+        let patch_sets = parse_ls_remote(12345, ls_remote);
+        assert_eq!(
+            patch_sets.iter().map(|ps| ps.number).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(patch_sets[1].revision, "bbb");
+
+        let body = ")]}'\n{\"_number\": 12345, \"change_id\": \"I8473b95934b5732ac55d26311a706c9c2bde9940\", \
+                    \"subject\": \"Fix it\", \"branch\": \"main\", \"status\": \"NEW\", \"revisions\": {\
+                    \"bbb\": {\"_number\": 2, \"ref\": \"refs/changes/45/12345/2\"},\
+                    \"aaa\": {\"_number\": 1, \"ref\": \"refs/changes/45/12345/1\"}}}";
+        let change = parse_rest_change(body).unwrap();
+        assert_eq!(change.number, 12345);
+        assert_eq!(change.subject.as_deref(), Some("Fix it"));
+        assert_eq!(change.patch_set(None).unwrap().revision, "bbb");
+        assert_eq!(change.patch_set(Some(1)).unwrap().revision, "aaa");
+    }
+
+    #[test]
+    fn fetches_patch_sets_as_comparisons() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        std::env::remove_var(crate::offline::OFFLINE_ENV);
+        let server = tempfile::TempDir::new().unwrap();
+        let server = server.path();
+        git(server, &["init", "-q", "-b", "main"]);
+        git(server, &["config", "user.email", "t@example.com"]);
+        git(server, &["config", "user.name", "T"]);
+        std::fs::write(server.join("a.txt"), "one\n").unwrap();
+        git(server, &["add", "."]);
+        git(server, &["commit", "-qm", "base"]);
+        for (patch_set, content) in [(1, "one\ntwo\n"), (2, "one\n2\n")] {
+            git(server, &["checkout", "-q", "main"]);
+            git(server, &["checkout", "-q", "--detach"]);
+            std::fs::write(server.join("a.txt"), content).unwrap();
+            git(server, &["commit", "-qam", "change"]);
+            git(server, &["update-ref", &server_ref(345, patch_set), "HEAD"]);
+        }
+        let clone = tempfile::TempDir::new().unwrap();
+        let clone = clone.path();
+        git(clone, &["init", "-q"]);
+        git(
+            clone,
+            &["remote", "add", "origin", server.to_str().unwrap()],
+        );
+
+        let source = GerritSource::new(clone.to_path_buf(), "origin".to_owned(), None);
+        assert!(matches!(
+            source.change("I8473b95934b5732ac55d26311a706c9c2bde9940"),
+            Err(GerritError::NotFound(_))
+        ));
+        let change = source.change("345").unwrap();
+        assert_eq!(change.patch_sets.len(), 2);
+
+        let latest = source.fetch_comparison(&change, None, None).unwrap();
+        assert_eq!(latest.key, "gerrit/345/2^..gerrit/345/2");
+        let diff = git(clone, &["diff", &latest.base, &latest.head]);
+        assert!(diff.contains("+2"));
+
+        let interdiff = source.fetch_comparison(&change, Some(2), Some(1)).unwrap();
+        assert_eq!(interdiff.key, "gerrit/345/1..gerrit/345/2");
+        let diff = git(clone, &["diff", &interdiff.base, &interdiff.head]);
+        assert!(diff.contains("-two") && diff.contains("+2"));
+    }
+}
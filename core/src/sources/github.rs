@@ -1,13 +1,16 @@
 //! GitHub provider abstraction.
 //!
-//! Defines a trait for interacting with GitHub pull requests and a concrete
-//! implementation backed by the `gh` CLI.
+//! Defines a trait for interacting with GitHub pull requests, a concrete
+//! implementation backed by the `gh` CLI, and [`GitHubClient`], which picks
+//! between it and [`GitHubApiProvider`].
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use super::github_api::GitHubApiProvider;
+use super::local_git::LocalGitSource;
 use super::traits::{FileEntry, FileStatus};
 
 // ---------------------------------------------------------------------------
@@ -62,8 +65,8 @@ pub struct PrFile {
 // Trait
 // ---------------------------------------------------------------------------
 
-/// Abstraction over GitHub operations so the `gh` CLI can be swapped for
-/// direct API calls later.
+/// Abstraction over GitHub operations, implemented by the `gh` CLI and by
+/// direct API calls.
 pub trait GitHubProvider {
     type Error: std::error::Error;
 
@@ -87,18 +90,50 @@ pub trait GitHubProvider {
 /// [`GitHubProvider`] backed by the `gh` CLI.
 pub struct GhCliProvider {
     repo_path: PathBuf,
+    /// `HOST/OWNER/REPO` of an explicitly configured review remote, passed
+    /// to `gh` as `GH_REPO`; otherwise `gh` picks the repo from the remotes
+    /// itself.
+    gh_repo: Option<String>,
 }
 
 impl GhCliProvider {
     pub fn new(repo_path: PathBuf) -> Self {
-        Self { repo_path }
+        let gh_repo = LocalGitSource::new(repo_path.clone())
+            .ok()
+            .filter(|source| source.configured_review_remote().is_some())
+            .and_then(|source| source.get_remote_info().ok().flatten())
+            .and_then(|remote| {
+                remote
+                    .browse_url
+                    .strip_prefix("https://")
+                    .map(str::to_owned)
+            });
+        Self { repo_path, gh_repo }
     }
+
+    /// A `gh` command against this repo.
+    fn gh(&self) -> Result<Command, GhError> {
+        let mut command = gh_command()?;
+        if let Some(repo) = &self.gh_repo {
+            command.env("GH_REPO", repo);
+        }
+        Ok(command)
+    }
+}
+
+/// A `gh` command, refused in offline mode.
+fn gh_command() -> Result<Command, GhError> {
+    crate::offline::ensure_online("GitHub (`gh`)").map_err(|e| GhError::Offline(e.to_string()))?;
+    Ok(Command::new("gh"))
 }
 
 impl GitHubProvider for GhCliProvider {
     type Error = GhError;
 
     fn is_available(&self) -> bool {
+        if crate::offline::is_offline() {
+            return false;
+        }
         Command::new("gh")
             .args(["auth", "status"])
             .current_dir(&self.repo_path)
@@ -110,7 +145,8 @@ impl GitHubProvider for GhCliProvider {
     }
 
     fn list_pull_requests(&self) -> Result<Vec<PullRequest>, GhError> {
-        let output = Command::new("gh")
+        let output = self
+            .gh()?
             .args([
                 "pr",
                 "list",
@@ -132,7 +168,8 @@ impl GitHubProvider for GhCliProvider {
     }
 
     fn get_pull_request_diff(&self, number: u32) -> Result<String, GhError> {
-        let output = Command::new("gh")
+        let output = self
+            .gh()?
             .args(["pr", "diff", &number.to_string()])
             .current_dir(&self.repo_path)
             .output()
@@ -147,7 +184,8 @@ impl GitHubProvider for GhCliProvider {
     }
 
     fn get_pull_request_files(&self, number: u32) -> Result<Vec<PrFile>, GhError> {
-        let output = Command::new("gh")
+        let output = self
+            .gh()?
             .args(["pr", "view", &number.to_string(), "--json", "files"])
             .current_dir(&self.repo_path)
             .output()
@@ -178,6 +216,7 @@ pub enum GhError {
     Io(String),
     Command(String),
     Parse(String),
+    Offline(String),
 }
 
 impl std::fmt::Display for GhError {
@@ -186,6 +225,7 @@ impl std::fmt::Display for GhError {
             Self::Io(msg) => write!(f, "gh I/O error: {msg}"),
             Self::Command(msg) => write!(f, "gh command error: {msg}"),
             Self::Parse(msg) => write!(f, "gh parse error: {msg}"),
+            Self::Offline(msg) => f.write_str(msg),
         }
     }
 }
@@ -204,10 +244,128 @@ pub struct PrStatus {
     pub head_ref_oid: String, // SHA of the PR head commit
 }
 
+/// A pull request's review standing: its state and GitHub's review decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrReviewStatus {
+    pub number: u32,
+    pub title: String,
+    pub url: String,
+    pub author: PrAuthor,
+    pub head_ref_name: String,
+    pub base_ref_name: String,
+    pub state: String, // OPEN, MERGED, CLOSED
+    /// APPROVED, CHANGES_REQUESTED, REVIEW_REQUIRED, or empty when the repo
+    /// requires no review.
+    #[serde(default)]
+    pub review_decision: String,
+    pub updated_at: String,
+}
+
+const REVIEW_STATUS_FIELDS: &str =
+    "number,title,url,author,headRefName,baseRefName,state,reviewDecision,updatedAt";
+
 impl GhCliProvider {
+    /// List the review standing of open pull requests matching a `gh pr list`
+    /// search, e.g. `review-requested:@me` or `author:@me`.
+    pub fn list_review_statuses(&self, search: &str) -> Result<Vec<PrReviewStatus>, GhError> {
+        let output = self
+            .gh()?
+            .args([
+                "pr",
+                "list",
+                "--search",
+                search,
+                "--json",
+                REVIEW_STATUS_FIELDS,
+            ])
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| GhError::Io(e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GhError::Command(stderr.into_owned()));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| GhError::Parse(e.to_string()))
+    }
+
+    /// Get one pull request's review standing, whatever its state.
+    pub fn get_review_status(&self, number: u32) -> Result<PrReviewStatus, GhError> {
+        let output = self
+            .gh()?
+            .args([
+                "pr",
+                "view",
+                &number.to_string(),
+                "--json",
+                REVIEW_STATUS_FIELDS,
+            ])
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| GhError::Io(e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GhError::Command(stderr.into_owned()));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| GhError::Parse(e.to_string()))
+    }
+
+    /// Get one pull request by number, whatever its state.
+    pub fn get_pull_request(&self, number: u32) -> Result<PullRequest, GhError> {
+        let output = self
+            .gh()?
+            .args([
+                "pr",
+                "view",
+                &number.to_string(),
+                "--json",
+                "number,title,headRefName,baseRefName,url,author,state,isDraft,updatedAt,body",
+            ])
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| GhError::Io(e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GhError::Command(stderr.into_owned()));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| GhError::Parse(e.to_string()))
+    }
+
+    /// List open pull requests where the authenticated user's review is
+    /// requested.
+    pub fn list_review_requests(&self) -> Result<Vec<PullRequest>, GhError> {
+        let output = self
+            .gh()?
+            .args([
+                "pr",
+                "list",
+                "--search",
+                "review-requested:@me",
+                "--json",
+                "number,title,headRefName,baseRefName,url,author,state,isDraft,updatedAt,body",
+            ])
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| GhError::Io(e.to_string()))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GhError::Command(stderr.into_owned()));
+        }
+
+        serde_json::from_slice(&output.stdout).map_err(|e| GhError::Parse(e.to_string()))
+    }
+
     /// Get the current status (state + head SHA) of a pull request.
     pub fn get_pr_status(&self, number: u32) -> Result<PrStatus, GhError> {
-        let output = Command::new("gh")
+        let output = self
+            .gh()?
             .args([
                 "pr",
                 "view",
@@ -230,6 +388,378 @@ impl GhCliProvider {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Review submission
+// ---------------------------------------------------------------------------
+
+/// The verdict of a pull request review, named as GitHub's API names it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PrReviewEvent {
+    Approve,
+    RequestChanges,
+    Comment,
+}
+
+impl PrReviewEvent {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Approve => "APPROVE",
+            Self::RequestChanges => "REQUEST_CHANGES",
+            Self::Comment => "COMMENT",
+        }
+    }
+}
+
+impl GhCliProvider {
+    /// Submit a review of pull request `number`.
+    pub fn submit_review(
+        &self,
+        number: u32,
+        event: PrReviewEvent,
+        body: &str,
+    ) -> Result<(), GhError> {
+        let flag = match event {
+            PrReviewEvent::Approve => "--approve",
+            PrReviewEvent::RequestChanges => "--request-changes",
+            PrReviewEvent::Comment => "--comment",
+        };
+        let output = self
+            .gh()?
+            .args(["pr", "review", &number.to_string(), flag, "--body", body])
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| GhError::Io(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GhError::Command(stderr.trim().to_owned()));
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Review threads
+// ---------------------------------------------------------------------------
+
+/// A review comment thread on a pull request's diff, as GraphQL returns it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrReviewThread {
+    pub path: String,
+    /// The thread's (last) line in the PR's current diff; `None` once the
+    /// line changed and the thread is outdated.
+    pub line: Option<u32>,
+    /// First line of a multi-line thread.
+    pub start_line: Option<u32>,
+    /// `LEFT` (the base side of the diff) or `RIGHT` (the head side).
+    pub diff_side: String,
+    pub is_resolved: bool,
+    pub is_outdated: bool,
+    pub resolved_by: Option<PrAuthor>,
+    #[serde(deserialize_with = "nodes")]
+    pub comments: Vec<PrReviewComment>,
+}
+
+/// One comment of a [`PrReviewThread`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrReviewComment {
+    /// GraphQL node id; stable across edits.
+    pub id: String,
+    pub author: PrAuthor,
+    pub body: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// A connection's `nodes`, without the wrapper.
+fn nodes<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    struct Nodes<T> {
+        nodes: Vec<T>,
+    }
+    Nodes::deserialize(deserializer).map(|connection| connection.nodes)
+}
+
+/// One page of a pull request's review threads, from `$after` on.
+pub(super) const REVIEW_THREADS_QUERY: &str =
+    "query($owner: String!, $name: String!, $number: Int!, $after: String) { \
+     repository(owner: $owner, name: $name) { pullRequest(number: $number) { \
+     reviewThreads(first: 100, after: $after) { nodes { path line startLine diffSide \
+     isResolved isOutdated resolvedBy { login } comments(first: 100) { nodes { id \
+     author { login } body createdAt updatedAt } } } pageInfo { hasNextPage endCursor } } } } }";
+
+/// The threads in a [`REVIEW_THREADS_QUERY`] response, and the cursor of the
+/// next page when there is one.
+pub(super) fn review_threads_page(
+    mut data: serde_json::Value,
+) -> Result<(Vec<PrReviewThread>, Option<String>), GhError> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Page {
+        nodes: Vec<PrReviewThread>,
+        page_info: PageInfo,
+    }
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct PageInfo {
+        has_next_page: bool,
+        end_cursor: Option<String>,
+    }
+    let page: Page =
+        serde_json::from_value(data["repository"]["pullRequest"]["reviewThreads"].take())
+            .map_err(|e| GhError::Parse(e.to_string()))?;
+    let next = page
+        .page_info
+        .end_cursor
+        .filter(|_| page.page_info.has_next_page);
+    Ok((page.nodes, next))
+}
+
+impl GhCliProvider {
+    /// The review threads on pull request `number`, resolved and outdated
+    /// ones included.
+    pub fn list_review_threads(&self, number: u32) -> Result<Vec<PrReviewThread>, GhError> {
+        let query = format!("query={REVIEW_THREADS_QUERY}");
+        let number = format!("number={number}");
+        let mut threads = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let after_field = after.as_ref().map(|cursor| format!("after={cursor}"));
+            let mut args = vec!["graphql", "-f", query.as_str(), "-F", number.as_str()];
+            args.extend(["-F", "owner={owner}", "-F", "name={repo}"]);
+            if let Some(field) = &after_field {
+                args.extend(["-f", field.as_str()]);
+            }
+            let data = super::github_api::graphql_data(&self.gh_api(&args)?)?;
+            let (page, next) = review_threads_page(data)?;
+            threads.extend(page);
+            match next {
+                Some(cursor) => after = Some(cursor),
+                None => return Ok(threads),
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Branch protection and commit statuses
+// ---------------------------------------------------------------------------
+
+/// The parts of a branch protection rule that decide what must pass before a
+/// merge.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BranchProtection {
+    #[serde(default)]
+    pub required_status_checks: Option<RequiredStatusChecks>,
+    #[serde(default)]
+    pub required_pull_request_reviews: Option<RequiredReviews>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequiredStatusChecks {
+    /// Branches must be up to date with the base before merging.
+    #[serde(default)]
+    pub strict: bool,
+    /// Names of the statuses and check runs that must pass.
+    #[serde(default)]
+    pub contexts: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RequiredReviews {
+    #[serde(default)]
+    pub required_approving_review_count: u32,
+}
+
+/// A commit status to post: `state` is `success`, `failure`, `pending`, or
+/// `error`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitStatus {
+    pub state: String,
+    pub context: String,
+    pub description: String,
+}
+
+impl GhCliProvider {
+    /// Run `gh api` against the repo; `{owner}/{repo}` in `endpoint` is
+    /// filled in from its remote.
+    fn gh_api(&self, args: &[&str]) -> Result<Vec<u8>, GhError> {
+        let output = self
+            .gh()?
+            .arg("api")
+            .args(args)
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| GhError::Io(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GhError::Command(stderr.trim().to_owned()));
+        }
+        Ok(output.stdout)
+    }
+
+    /// `branch`'s protection rule, or `None` when it has none. Reading it
+    /// needs admin rights on the repo.
+    pub fn get_branch_protection(&self, branch: &str) -> Result<Option<BranchProtection>, GhError> {
+        let endpoint = format!("repos/{{owner}}/{{repo}}/branches/{branch}/protection");
+        match self.gh_api(&[&endpoint]) {
+            Ok(body) => serde_json::from_slice(&body)
+                .map(Some)
+                .map_err(|e| GhError::Parse(e.to_string())),
+            Err(GhError::Command(message)) if message.contains("Branch not protected") => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Post a status on commit `sha`. Unlike check runs, which only a GitHub
+    /// App can create, a user's token can post statuses, and branch
+    /// protection requires them by `context` all the same.
+    pub fn create_commit_status(&self, sha: &str, status: &CommitStatus) -> Result<(), GhError> {
+        let endpoint = format!("repos/{{owner}}/{{repo}}/statuses/{sha}");
+        let fields = [
+            format!("state={}", status.state),
+            format!("context={}", status.context),
+            format!("description={}", status.description),
+        ];
+        let mut args = vec!["--method", "POST", endpoint.as_str()];
+        for field in &fields {
+            args.extend(["-f", field]);
+        }
+        self.gh_api(&args).map(|_| ())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// GitHubClient
+// ---------------------------------------------------------------------------
+
+/// The GitHub provider for a repo: the API when a token is set (see
+/// [`super::github_api`]), otherwise the `gh` CLI.
+pub enum GitHubClient {
+    Api(GitHubApiProvider),
+    Cli(GhCliProvider),
+}
+
+impl GitHubClient {
+    pub fn for_repo(repo_path: PathBuf) -> Self {
+        match GitHubApiProvider::for_repo(repo_path.clone()) {
+            Some(api) => Self::Api(api),
+            None => Self::Cli(GhCliProvider::new(repo_path)),
+        }
+    }
+
+    pub fn list_review_statuses(&self, search: &str) -> Result<Vec<PrReviewStatus>, GhError> {
+        match self {
+            Self::Api(api) => api.list_review_statuses(search),
+            Self::Cli(cli) => cli.list_review_statuses(search),
+        }
+    }
+
+    pub fn get_review_status(&self, number: u32) -> Result<PrReviewStatus, GhError> {
+        match self {
+            Self::Api(api) => api.get_review_status(number),
+            Self::Cli(cli) => cli.get_review_status(number),
+        }
+    }
+
+    pub fn get_pull_request(&self, number: u32) -> Result<PullRequest, GhError> {
+        match self {
+            Self::Api(api) => api.get_pull_request(number),
+            Self::Cli(cli) => cli.get_pull_request(number),
+        }
+    }
+
+    pub fn list_review_requests(&self) -> Result<Vec<PullRequest>, GhError> {
+        match self {
+            Self::Api(api) => api.list_review_requests(),
+            Self::Cli(cli) => cli.list_review_requests(),
+        }
+    }
+
+    pub fn get_pr_status(&self, number: u32) -> Result<PrStatus, GhError> {
+        match self {
+            Self::Api(api) => api.get_pr_status(number),
+            Self::Cli(cli) => cli.get_pr_status(number),
+        }
+    }
+
+    pub fn submit_review(
+        &self,
+        number: u32,
+        event: PrReviewEvent,
+        body: &str,
+    ) -> Result<(), GhError> {
+        match self {
+            Self::Api(api) => api.submit_review(number, event, body),
+            Self::Cli(cli) => cli.submit_review(number, event, body),
+        }
+    }
+
+    pub fn create_commit_status(&self, sha: &str, status: &CommitStatus) -> Result<(), GhError> {
+        match self {
+            Self::Api(api) => api.create_commit_status(sha, status),
+            Self::Cli(cli) => cli.create_commit_status(sha, status),
+        }
+    }
+
+    pub fn list_review_threads(&self, number: u32) -> Result<Vec<PrReviewThread>, GhError> {
+        match self {
+            Self::Api(api) => api.list_review_threads(number),
+            Self::Cli(cli) => cli.list_review_threads(number),
+        }
+    }
+
+    /// The statuses of many pull requests: batched through the API, one
+    /// `gh` call each otherwise. Numbers that fail to resolve are left out.
+    pub fn get_pr_statuses(&self, numbers: &[u32]) -> Result<HashMap<u32, PrStatus>, GhError> {
+        match self {
+            Self::Api(api) => api.get_pr_statuses(numbers),
+            Self::Cli(cli) => Ok(numbers
+                .iter()
+                .filter_map(|&n| cli.get_pr_status(n).ok().map(|status| (n, status)))
+                .collect()),
+        }
+    }
+}
+
+impl GitHubProvider for GitHubClient {
+    type Error = GhError;
+
+    fn is_available(&self) -> bool {
+        match self {
+            Self::Api(api) => api.is_available(),
+            Self::Cli(cli) => cli.is_available(),
+        }
+    }
+
+    fn list_pull_requests(&self) -> Result<Vec<PullRequest>, GhError> {
+        match self {
+            Self::Api(api) => api.list_pull_requests(),
+            Self::Cli(cli) => cli.list_pull_requests(),
+        }
+    }
+
+    fn get_pull_request_diff(&self, number: u32) -> Result<String, GhError> {
+        match self {
+            Self::Api(api) => api.get_pull_request_diff(number),
+            Self::Cli(cli) => cli.get_pull_request_diff(number),
+        }
+    }
+
+    fn get_pull_request_files(&self, number: u32) -> Result<Vec<PrFile>, GhError> {
+        match self {
+            Self::Api(api) => api.get_pull_request_files(number),
+            Self::Cli(cli) => cli.get_pull_request_files(number),
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -274,6 +804,8 @@ pub fn pr_files_to_file_entries(files: Vec<PrFile>) -> Vec<FileEntry> {
             renamed_from: None,
             size: None,
             modified_at: None,
+            is_lfs_pointer: false,
+            not_materialized: false,
         };
 
         if let Some(parent) = Path::new(&file.path).parent() {
@@ -338,6 +870,8 @@ pub fn pr_files_to_file_entries(files: Vec<PrFile>) -> Vec<FileEntry> {
                 renamed_from: None,
                 size: None,
                 modified_at: None,
+                is_lfs_pointer: false,
+                not_materialized: false,
             },
         );
     }
@@ -0,0 +1,448 @@
+//! [`GitHubProvider`] talking to the GitHub API directly, for machines
+//! without the `gh` CLI.
+//!
+//! Authenticates with a token from `GH_TOKEN` or `GITHUB_TOKEN` — for
+//! GitHub Enterprise Server hosts `GH_ENTERPRISE_TOKEN` or
+//! `GITHUB_ENTERPRISE_TOKEN`, the variables `gh` reads — and queries GraphQL, whose pull request fields
+//! are the ones `gh --json` returns — so responses deserialize into the same
+//! types. Diffs come from the REST API. Requests go through `curl`, the token
+//! passed on stdin rather than in the process arguments. Batch lookups
+//! ([`GitHubApiProvider::get_pr_statuses`]) fetch many PRs per request.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::hash::BuildHasher;
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::github::{
+    review_threads_page, CommitStatus, GhError, GitHubProvider, PrFile, PrReviewEvent,
+    PrReviewStatus, PrReviewThread, PrStatus, PullRequest, REVIEW_THREADS_QUERY,
+};
+use super::local_git::LocalGitSource;
+
+/// Environment variables holding a github.com token, in order of precedence.
+pub const TOKEN_ENVS: [&str; 2] = ["GH_TOKEN", "GITHUB_TOKEN"];
+
+/// Environment variables holding a GitHub Enterprise Server token. Like `gh`,
+/// other hosts never get the github.com token.
+pub const ENTERPRISE_TOKEN_ENVS: [&str; 2] = ["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"];
+
+/// Pull requests looked up per batch query.
+const BATCH_SIZE: usize = 50;
+
+const PR_FIELDS: &str =
+    "number title headRefName baseRefName url author { login } state isDraft updatedAt body";
+const REVIEW_STATUS_FIELDS: &str = "number title url author { login } headRefName baseRefName \
+                                    state reviewDecision updatedAt";
+
+/// The token for `host` from the environment, if any.
+pub fn token_from_env(host: &str) -> Option<String> {
+    let vars = if host == "github.com" {
+        TOKEN_ENVS
+    } else {
+        ENTERPRISE_TOKEN_ENVS
+    };
+    vars.iter()
+        .find_map(|var| std::env::var(var).ok().filter(|t| !t.trim().is_empty()))
+}
+
+/// The REST API base for a git host: `githubApiUrls`' entry for it in
+/// settings.json, else github.com's API host or GitHub Enterprise Server's
+/// `/api/v3`.
+pub fn api_url<S: BuildHasher>(host: &str, configured: &HashMap<String, String, S>) -> String {
+    if let Some(url) = configured.get(host).filter(|url| !url.trim().is_empty()) {
+        return url.trim().trim_end_matches('/').to_owned();
+    }
+    if host == "github.com" {
+        "https://api.github.com".to_owned()
+    } else {
+        format!("https://{host}/api/v3")
+    }
+}
+
+/// The GraphQL endpoint beside a REST API base: `/graphql` on github.com's
+/// API host, `/api/graphql` on Enterprise Server.
+fn graphql_url(api_url: &str) -> String {
+    format!("{}/graphql", api_url.strip_suffix("/v3").unwrap_or(api_url))
+}
+
+/// `s` as a quoted curl config value.
+fn config_quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Replace the nulls GraphQL returns where `gh` gives a value: a deleted
+/// user's `author`, and a `reviewDecision` the repo doesn't require.
+fn fill_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                match (key.as_str(), &field) {
+                    ("author", Value::Null) => *field = json!({ "login": "ghost" }),
+                    ("reviewDecision", Value::Null) => *field = json!(""),
+                    _ => fill_nulls(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(fill_nulls),
+        _ => {}
+    }
+}
+
+/// The `data` of a GraphQL response, or its first error.
+pub(super) fn graphql_data(body: &[u8]) -> Result<Value, GhError> {
+    let mut response: Value =
+        serde_json::from_slice(body).map_err(|e| GhError::Parse(e.to_string()))?;
+    if let Some(message) = response["errors"][0]["message"].as_str() {
+        return Err(GhError::Command(message.to_owned()));
+    }
+    let mut data = response["data"].take();
+    fill_nulls(&mut data);
+    Ok(data)
+}
+
+fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, GhError> {
+    serde_json::from_value(value).map_err(|e| GhError::Parse(e.to_string()))
+}
+
+/// The query looking up `numbers`' statuses, each aliased `pr<number>`.
+fn statuses_query(numbers: &[u32]) -> String {
+    let fields = numbers.iter().fold(String::new(), |mut fields, n| {
+        let _ = write!(
+            fields,
+            " pr{n}: pullRequest(number: {n}) {{ state headRefOid }}"
+        );
+        fields
+    });
+    format!(
+        "query($owner: String!, $name: String!) {{ repository(owner: $owner, name: $name) {{{fields} }} }}"
+    )
+}
+
+/// [`GitHubProvider`] backed by the GitHub API.
+pub struct GitHubApiProvider {
+    owner: String,
+    name: String,
+    rest_url: String,
+    graphql_url: String,
+    token: String,
+}
+
+impl GitHubApiProvider {
+    /// A provider for `owner/name` at the REST API base `api_url`.
+    pub fn new(owner: String, name: String, api_url: String, token: String) -> Self {
+        Self {
+            owner,
+            name,
+            graphql_url: graphql_url(&api_url),
+            rest_url: api_url,
+            token,
+        }
+    }
+
+    /// The provider for the repo's review remote (`origin` unless configured
+    /// otherwise), when the remote names an `owner/repo` and there's a token
+    /// for its host in the environment.
+    pub fn for_repo(repo_path: PathBuf) -> Option<Self> {
+        let remote = LocalGitSource::new(repo_path)
+            .ok()?
+            .get_remote_info()
+            .ok()??;
+        let (owner, name) = remote.name.split_once('/')?;
+        if name.contains('/') {
+            return None;
+        }
+        let host = remote
+            .browse_url
+            .strip_prefix("https://")?
+            .split('/')
+            .next()?;
+        let token = token_from_env(host)?;
+        let configured = crate::service::settings::read_settings()
+            .map(|file| file.settings.github_api_urls)
+            .unwrap_or_default();
+        Some(Self::new(
+            owner.to_owned(),
+            name.to_owned(),
+            api_url(host, &configured),
+            token,
+        ))
+    }
+
+    fn request(&self, url: &str, accept: &str, body: Option<&str>) -> Result<Vec<u8>, GhError> {
+        crate::offline::ensure_online("GitHub API").map_err(|e| GhError::Offline(e.to_string()))?;
+        let mut config = String::new();
+        for header in [
+            format!("Authorization: Bearer {}", self.token),
+            format!("Accept: {accept}"),
+            "X-GitHub-Api-Version: 2022-11-28".to_owned(),
+        ] {
+            let _ = writeln!(config, "header = {}", config_quote(&header));
+        }
+        if let Some(body) = body {
+            let _ = writeln!(config, "header = \"Content-Type: application/json\"");
+            let _ = writeln!(config, "data-binary = {}", config_quote(body));
+        }
+
+        let mut child = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--max-time", "60"])
+            .args(["--user-agent", "review", "--config", "-", url])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| GhError::Io(e.to_string()))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(config.as_bytes())
+                .map_err(|e| GhError::Io(e.to_string()))?;
+        }
+        let output = child
+            .wait_with_output()
+            .map_err(|e| GhError::Io(e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GhError::Command(stderr.trim().to_owned()));
+        }
+        Ok(output.stdout)
+    }
+
+    /// Run a GraphQL query with the repo's `owner` and `name` among its
+    /// variables.
+    fn graphql(&self, query: &str, mut variables: Value) -> Result<Value, GhError> {
+        variables["owner"] = json!(self.owner);
+        variables["name"] = json!(self.name);
+        let body = json!({ "query": query, "variables": variables }).to_string();
+        graphql_data(&self.request(&self.graphql_url, "application/json", Some(&body))?)
+    }
+
+    /// Open pull requests matching a search, e.g. `review-requested:@me`.
+    fn search<T: DeserializeOwned>(&self, search: &str, fields: &str) -> Result<Vec<T>, GhError> {
+        let query = format!(
+            "query($q: String!) {{ search(query: $q, type: ISSUE, first: 100) \
+             {{ nodes {{ ... on PullRequest {{ {fields} }} }} }} }}"
+        );
+        let q = format!("repo:{}/{} is:pr is:open {search}", self.owner, self.name);
+        let mut data = self.graphql(&query, json!({ "q": q }))?;
+        from_value(data["search"]["nodes"].take())
+    }
+
+    fn pull_request<T: DeserializeOwned>(&self, number: u32, fields: &str) -> Result<T, GhError> {
+        let query = format!(
+            "query($owner: String!, $name: String!, $number: Int!) {{ repository(owner: $owner, \
+             name: $name) {{ pullRequest(number: $number) {{ {fields} }} }} }}"
+        );
+        let mut data = self.graphql(&query, json!({ "number": number }))?;
+        from_value(data["repository"]["pullRequest"].take())
+    }
+
+    /// List the review standing of open pull requests matching a search,
+    /// e.g. `review-requested:@me` or `author:@me`.
+    pub fn list_review_statuses(&self, search: &str) -> Result<Vec<PrReviewStatus>, GhError> {
+        self.search(search, REVIEW_STATUS_FIELDS)
+    }
+
+    /// Get one pull request's review standing, whatever its state.
+    pub fn get_review_status(&self, number: u32) -> Result<PrReviewStatus, GhError> {
+        self.pull_request(number, REVIEW_STATUS_FIELDS)
+    }
+
+    /// Get one pull request by number, whatever its state.
+    pub fn get_pull_request(&self, number: u32) -> Result<PullRequest, GhError> {
+        self.pull_request(number, PR_FIELDS)
+    }
+
+    /// List open pull requests where the authenticated user's review is
+    /// requested.
+    pub fn list_review_requests(&self) -> Result<Vec<PullRequest>, GhError> {
+        self.search("review-requested:@me", PR_FIELDS)
+    }
+
+    /// Get the current status (state + head SHA) of a pull request.
+    pub fn get_pr_status(&self, number: u32) -> Result<PrStatus, GhError> {
+        self.pull_request(number, "state headRefOid")
+    }
+
+    /// Submit a review of pull request `number`.
+    pub fn submit_review(
+        &self,
+        number: u32,
+        event: PrReviewEvent,
+        body: &str,
+    ) -> Result<(), GhError> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{number}/reviews",
+            self.rest_url, self.owner, self.name
+        );
+        let review = json!({ "event": event.as_str(), "body": body }).to_string();
+        self.request(&url, "application/vnd.github+json", Some(&review))
+            .map(|_| ())
+    }
+
+    /// Post a status on commit `sha`.
+    pub fn create_commit_status(&self, sha: &str, status: &CommitStatus) -> Result<(), GhError> {
+        let url = format!(
+            "{}/repos/{}/{}/statuses/{sha}",
+            self.rest_url, self.owner, self.name
+        );
+        let body = serde_json::to_string(status).map_err(|e| GhError::Parse(e.to_string()))?;
+        self.request(&url, "application/vnd.github+json", Some(&body))
+            .map(|_| ())
+    }
+
+    /// The review threads on pull request `number`, resolved and outdated
+    /// ones included.
+    pub fn list_review_threads(&self, number: u32) -> Result<Vec<PrReviewThread>, GhError> {
+        let mut threads = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let variables = json!({ "number": number, "after": after });
+            let (page, next) = review_threads_page(self.graphql(REVIEW_THREADS_QUERY, variables)?)?;
+            threads.extend(page);
+            match next {
+                Some(cursor) => after = Some(cursor),
+                None => return Ok(threads),
+            }
+        }
+    }
+
+    /// The statuses of many pull requests, [`BATCH_SIZE`] per request.
+    /// Numbers that don't name a pull request are left out.
+    pub fn get_pr_statuses(&self, numbers: &[u32]) -> Result<HashMap<u32, PrStatus>, GhError> {
+        let mut statuses = HashMap::new();
+        for batch in numbers.chunks(BATCH_SIZE) {
+            let mut data = self.graphql(&statuses_query(batch), json!({}))?;
+            for &number in batch {
+                let status = data["repository"][format!("pr{number}")].take();
+                if !status.is_null() {
+                    statuses.insert(number, from_value(status)?);
+                }
+            }
+        }
+        Ok(statuses)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfo {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+impl GitHubProvider for GitHubApiProvider {
+    type Error = GhError;
+
+    fn is_available(&self) -> bool {
+        !crate::offline::is_offline()
+    }
+
+    fn list_pull_requests(&self) -> Result<Vec<PullRequest>, GhError> {
+        let query = format!(
+            "query($owner: String!, $name: String!) {{ repository(owner: $owner, name: $name) \
+             {{ pullRequests(states: OPEN, first: 100, orderBy: {{field: CREATED_AT, \
+             direction: DESC}}) {{ nodes {{ {PR_FIELDS} }} }} }} }}"
+        );
+        let mut data = self.graphql(&query, json!({}))?;
+        from_value(data["repository"]["pullRequests"]["nodes"].take())
+    }
+
+    fn get_pull_request_diff(&self, number: u32) -> Result<String, GhError> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{number}",
+            self.rest_url, self.owner, self.name
+        );
+        let diff = self.request(&url, "application/vnd.github.diff", None)?;
+        Ok(String::from_utf8_lossy(&diff).into_owned())
+    }
+
+    fn get_pull_request_files(&self, number: u32) -> Result<Vec<PrFile>, GhError> {
+        let query = "query($owner: String!, $name: String!, $number: Int!, $after: String) { \
+                     repository(owner: $owner, name: $name) { pullRequest(number: $number) { \
+                     files(first: 100, after: $after) { nodes { path additions deletions } \
+                     pageInfo { hasNextPage endCursor } } } } }";
+        let mut files = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let mut data = self.graphql(query, json!({ "number": number, "after": after }))?;
+            let mut page = data["repository"]["pullRequest"]["files"].take();
+            files.extend(from_value::<Vec<PrFile>>(page["nodes"].take())?);
+            let info: PageInfo = from_value(page["pageInfo"].take())?;
+            match info.end_cursor {
+                Some(cursor) if info.has_next_page => after = Some(cursor),
+                _ => return Ok(files),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_urls_follow_the_host() {
+        let none = HashMap::new();
+        assert_eq!(api_url("github.com", &none), "https://api.github.com");
+        assert_eq!(
+            graphql_url("https://api.github.com"),
+            "https://api.github.com/graphql"
+        );
+        let ghes = api_url("ghe.example.com", &none);
+        assert_eq!(ghes, "https://ghe.example.com/api/v3");
+        assert_eq!(graphql_url(&ghes), "https://ghe.example.com/api/graphql");
+
+        let configured = HashMap::from([(
+            "git.example.com".to_owned(),
+            "https://api.example.com/api/v3/".to_owned(),
+        )]);
+        assert_eq!(
+            api_url("git.example.com", &configured),
+            "https://api.example.com/api/v3"
+        );
+        assert_eq!(config_quote(r#"{"a":"b\n"}"#), r#""{\"a\":\"b\\n\"}""#);
+    }
+
+    #[test]
+    fn graphql_responses_read_like_gh_output() {
+        let body = br#"{"data": {"search": {"nodes": [{"number": 4, "title": "Fix",
+            "url": "https://github.com/o/r/pull/4", "author": null, "headRefName": "fix",
+            "baseRefName": "main", "state": "OPEN", "reviewDecision": null,
+            "updatedAt": "2026-01-01T00:00:00Z"}]}}}"#;
+        let mut data = graphql_data(body).unwrap();
+        let statuses: Vec<PrReviewStatus> = from_value(data["search"]["nodes"].take()).unwrap();
+        assert_eq!(statuses[0].author.login, "ghost");
+        assert_eq!(statuses[0].review_decision, "");
+
+        let error = br#"{"data": null, "errors": [{"message": "Bad credentials"}]}"#;
+        assert!(matches!(graphql_data(error), Err(GhError::Command(m)) if m == "Bad credentials"));
+    }
+
+    #[test]
+    fn review_threads_flatten_their_comments() {
+        let body = br#"{"data": {"repository": {"pullRequest": {"reviewThreads": {
+            "nodes": [{"path": "src/a.rs", "line": 12, "startLine": null, "diffSide": "RIGHT",
+              "isResolved": true, "isOutdated": false, "resolvedBy": {"login": "lee"},
+              "comments": {"nodes": [{"id": "C_1", "author": null, "body": "Why?",
+                "createdAt": "2026-01-01T00:00:00Z", "updatedAt": "2026-01-01T00:00:00Z"}]}}],
+            "pageInfo": {"hasNextPage": true, "endCursor": "Y3Vy"}}}}}}"#;
+        let (threads, next) = review_threads_page(graphql_data(body).unwrap()).unwrap();
+        assert_eq!(next.as_deref(), Some("Y3Vy"));
+        assert_eq!(threads[0].line, Some(12));
+        assert_eq!(threads[0].comments[0].author.login, "ghost");
+        assert_eq!(threads[0].resolved_by.as_ref().unwrap().login, "lee");
+    }
+
+    #[test]
+    fn batches_status_lookups_by_alias() {
+        let query = statuses_query(&[3, 12]);
+        assert!(query.contains("pr3: pullRequest(number: 3) { state headRefOid }"));
+        assert!(query.contains("pr12: pullRequest(number: 12)"));
+    }
+}
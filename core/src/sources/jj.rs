@@ -0,0 +1,191 @@
+//! Jujutsu (jj) repositories.
+//!
+//! jj stores its commits in a git repo: the workspace's own `.git` when the
+//! repo is colocated, or one inside `.jj/repo/store` when it's jj-native.
+//! [`LocalGitSource`](super::local_git::LocalGitSource) diffs either through
+//! git once it knows that repo ([`JjRepo::git_dir`]); what git can't name —
+//! change IDs, `@`, bookmarks jj hasn't exported — is resolved to commits by
+//! the `jj` CLI ([`JjRepo::resolve`]).
+//!
+//! jj has no index and no uncommitted changes: every jj command first
+//! snapshots the working copy into the `@` commit. So a jj repo's "current
+//! branch" is `@`, reviewed as a commit like any other.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use thiserror::Error;
+
+/// The working-copy revision — what a jj repo reviews by default.
+pub const WORKING_COPY: &str = "@";
+
+#[derive(Error, Debug)]
+pub enum JjError {
+    #[error("jj error: {0}")]
+    Jj(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Whether `dir` is the root of a git or jj working copy.
+pub fn is_repo_root(dir: &Path) -> bool {
+    dir.join(".git").exists() || dir.join(".jj").is_dir()
+}
+
+/// A jj workspace and the git repo backing it.
+#[derive(Debug, Clone)]
+pub struct JjRepo {
+    root: PathBuf,
+    git_dir: PathBuf,
+    colocated: bool,
+}
+
+impl JjRepo {
+    /// The jj workspace rooted at `root`, if any. `None` for plain git repos
+    /// and for jj repos with a non-git backend, which there's no diffing.
+    pub fn detect(root: &Path) -> Option<Self> {
+        let jj_dir = root.join(".jj");
+        if !jj_dir.is_dir() {
+            return None;
+        }
+        // Secondary workspaces have a `repo` file pointing at the main one's
+        // repo dir.
+        let repo_dir = match std::fs::read_to_string(jj_dir.join("repo")) {
+            Ok(pointer) => resolve_relative(&jj_dir, pointer.trim()),
+            Err(_) => jj_dir.join("repo"),
+        };
+        let store = repo_dir.join("store");
+        let target = std::fs::read_to_string(store.join("git_target")).ok()?;
+        let git_dir = resolve_relative(&store, target.trim());
+        let git_dir = git_dir.canonicalize().unwrap_or(git_dir);
+        let colocated = root
+            .join(".git")
+            .canonicalize()
+            .is_ok_and(|dot_git| dot_git == git_dir);
+        Some(Self {
+            root: root.to_path_buf(),
+            git_dir,
+            colocated,
+        })
+    }
+
+    /// The git repo jj stores commits in.
+    pub fn git_dir(&self) -> &Path {
+        &self.git_dir
+    }
+
+    /// Whether the git repo is the workspace's own `.git`, which git commands
+    /// find by themselves.
+    pub fn is_colocated(&self) -> bool {
+        self.colocated
+    }
+
+    fn jj(&self, args: &[&str]) -> Result<String, JjError> {
+        let output = Command::new("jj")
+            .args(["--no-pager", "--color=never", "-R"])
+            .arg(&self.root)
+            .args(args)
+            .output()?;
+        if !output.status.success() {
+            return Err(JjError::Jj(
+                String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// The commit `rev` names — a change ID, bookmark, `@`, or any revset
+    /// selecting exactly one commit. Git's `^` / `~n` suffixes are accepted.
+    /// `None` when it doesn't resolve (or `jj` isn't installed).
+    pub fn resolve(&self, rev: &str) -> Option<String> {
+        let output = self
+            .jj(&[
+                "log",
+                "--no-graph",
+                "-r",
+                &to_revset(rev),
+                "-T",
+                "commit_id ++ \"\\n\"",
+            ])
+            .ok()?;
+        let mut commits = output.lines().filter(|l| !l.is_empty());
+        let commit = commits.next()?;
+        commits.next().is_none().then(|| commit.to_owned())
+    }
+
+    /// Whether `name` is a local bookmark.
+    pub fn is_bookmark(&self, name: &str) -> bool {
+        let revset = format!("bookmarks(exact:{})", quote(name));
+        self.jj(&["log", "--no-graph", "-r", &revset, "-T", "commit_id"])
+            .is_ok_and(|out| !out.trim().is_empty())
+    }
+}
+
+fn resolve_relative(base: &Path, path: &str) -> PathBuf {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base.join(path)
+    }
+}
+
+/// `rev` as a jj revset: git's parent suffixes (`^`, `~n`) become jj's `-`.
+pub fn to_revset(rev: &str) -> String {
+    let mut base = rev;
+    let mut parents = 0;
+    loop {
+        if let Some(rest) = base.strip_suffix('^') {
+            base = rest;
+            parents += 1;
+        } else if let Some((rest, n)) = base.rsplit_once('~') {
+            match n.parse::<usize>() {
+                Ok(n) if !rest.is_empty() => {
+                    base = rest;
+                    parents += n;
+                }
+                _ => break,
+            }
+        } else {
+            break;
+        }
+    }
+    format!("{base}{}", "-".repeat(parents))
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_git_parent_suffixes() {
+        assert_eq!(to_revset("@"), "@");
+        assert_eq!(to_revset("@^"), "@-");
+        assert_eq!(to_revset("main~2^"), "main---");
+        assert_eq!(to_revset("kxqpzmlw"), "kxqpzmlw");
+        assert_eq!(quote("a\"b"), "\"a\\\"b\"");
+    }
+
+    #[test]
+    fn detects_native_and_colocated_stores() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        assert!(JjRepo::detect(root).is_none());
+
+        let store = root.join(".jj/repo/store");
+        std::fs::create_dir_all(store.join("git")).unwrap();
+        std::fs::write(store.join("git_target"), "git").unwrap();
+        let native = JjRepo::detect(root).unwrap();
+        assert!(!native.is_colocated());
+        assert_eq!(native.git_dir(), store.join("git").canonicalize().unwrap());
+        assert!(is_repo_root(root));
+
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(store.join("git_target"), "../../../.git").unwrap();
+        assert!(JjRepo::detect(root).unwrap().is_colocated());
+    }
+}
@@ -0,0 +1,72 @@
+//! Git LFS pointer files.
+//!
+//! A file tracked with `filter=lfs` is stored in git as a small text pointer
+//! naming the real object by hash and size. When LFS isn't installed, or the
+//! object was never pulled, that pointer is also what's on disk, and reading
+//! it as the file's content would show three lines of metadata in place of
+//! an image or archive. [`parse`] recognizes one so the file can be flagged
+//! and its real size shown instead.
+
+use serde::{Deserialize, Serialize};
+
+/// First line of every pointer written by a current LFS client.
+pub const POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// Pointers are never larger than this, per the spec.
+pub const MAX_POINTER_BYTES: u64 = 1024;
+
+/// The object an LFS pointer stands for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LfsPointer {
+    /// e.g. `sha256:4d7a…`
+    pub oid: String,
+    /// Size of the real content in bytes.
+    pub size: u64,
+}
+
+/// Parse `bytes` as an LFS pointer; `None` for anything else.
+pub fn parse(bytes: &[u8]) -> Option<LfsPointer> {
+    if bytes.len() as u64 > MAX_POINTER_BYTES {
+        return None;
+    }
+    let text = std::str::from_utf8(bytes).ok()?;
+    let mut lines = text.lines();
+    if lines.next()? != POINTER_VERSION {
+        return None;
+    }
+    let (mut oid, mut size) = (None, None);
+    for line in lines {
+        match line.split_once(' ') {
+            Some(("oid", value)) => oid = Some(value.to_owned()),
+            Some(("size", value)) => size = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Some(LfsPointer {
+        oid: oid?,
+        size: size?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pointers_and_rejects_other_content() {
+        let pointer = b"version https://git-lfs.github.com/spec/v1\n\
+            oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+            size 12345\n";
+        assert_eq!(
+            parse(pointer),
+            Some(LfsPointer {
+                oid: "sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393"
+                    .to_owned(),
+                size: 12345,
+            })
+        );
+        assert_eq!(parse(b"version https://git-lfs.github.com/spec/v1\n"), None);
+        assert_eq!(parse(b"fn main() {}\n"), None);
+    }
+}
@@ -1,13 +1,18 @@
+use super::jj::{self, JjRepo};
+use super::lfs::{self, LfsPointer};
 use super::traits::{
-    ChangeStatus, CommitEntry, Comparison, DiffSource, FileEntry, FileStatus, GitStatusSummary,
-    StatusEntry,
+    ChangeStatus, CommitEntry, Comparison, DiffSource, Endpoint, FileEntry, FileStatus,
+    GitStatusSummary, StatusEntry,
+};
+use crate::diff::encoding::{decode_diff, DecodedDiff};
+use crate::diff::parser::{
+    parse_diff, parse_raw_diff, section_file_path, split_hunk, split_threshold, DiffHunk, LineType,
 };
-use crate::diff::parser::{parse_diff, LineType};
 use crate::review::central;
 use log::info;
-use serde::Serialize;
-use std::collections::{HashMap, HashSet};
-use std::io::Write;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::time::Instant;
@@ -23,15 +28,50 @@ pub struct RemoteInfo {
     pub browse_url: String,
 }
 
-/// Lightweight diff statistics from `git diff --shortstat`
+/// A git remote of the repo, as `git remote -v` lists it.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
+pub struct GitRemote {
+    pub name: String,
+    /// Fetch URL.
+    pub url: String,
+    /// The remote reviews resolve their base branch and PR host from; see
+    /// [`LocalGitSource::review_remote`].
+    pub is_review_remote: bool,
+}
+
+/// Lightweight diff statistics from `git diff --shortstat`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct DiffShortStat {
     pub file_count: u32,
     pub additions: u32,
     pub deletions: u32,
 }
 
+/// A commit of a range with the files it changed; see
+/// [`LocalGitSource::list_range_commits`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RangeCommit {
+    pub hash: String,
+    pub subject: String,
+    pub files: Vec<String>,
+}
+
+/// A submodule whose recorded commit differs between the two sides of a
+/// comparison (or, in working-tree mode, whose checkout has moved or is dirty).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubmoduleChange {
+    /// Submodule path, relative to the superproject root.
+    pub path: String,
+    /// Commit recorded on the base side; `None` when the submodule was added.
+    pub old_sha: Option<String>,
+    /// Commit on the head side; `None` when the submodule was removed.
+    pub new_sha: Option<String>,
+}
+
 /// Verification status for a search hit.
 ///
 /// `Yes` and `No` only appear when tree-sitter actually ran on the file.
@@ -65,6 +105,19 @@ pub struct SearchMatch {
     pub verified: VerifiedStatus,
 }
 
+/// Repo-relative path of the conventional list of commits blame ignores.
+pub const BLAME_IGNORE_REVS_FILE: &str = ".git-blame-ignore-revs";
+
+/// Commits each `git fetch --deepen` of a shallow clone reaches further back.
+pub const DEEPEN_STEP: u32 = 256;
+
+/// Automatic deepens tried for one missing commit before giving up.
+const DEEPEN_ATTEMPTS: u32 = 4;
+
+/// Git config key naming the remote a clone is reviewed against — e.g.
+/// `upstream` in a fork whose `origin` is the contributor's copy.
+pub const REVIEW_REMOTE_CONFIG: &str = "review.remote";
+
 /// Maps the hunks of a comparison's net diff to the commit(s) in `base..head`
 /// that introduced their lines. Attribution is derived on demand — never
 /// persisted — so it always reflects the current diff.
@@ -127,6 +180,61 @@ pub struct WorktreeInfo {
     pub is_review_managed: bool,
 }
 
+/// Why one file of a patch doesn't apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PatchConflictReason {
+    /// A hunk's context or removed lines don't match the file.
+    ContextMismatch,
+    /// The patch creates a file that already exists.
+    AlreadyExists,
+    /// The patch changes or deletes a file that doesn't exist.
+    MissingFile,
+    /// Anything else git refused; see the message.
+    Other,
+}
+
+/// How a 3-way merge against the blobs a patch records (`index` lines) would
+/// apply a file that doesn't apply directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThreeWayOutcome {
+    /// Merges without conflicts.
+    Clean,
+    /// Merges, but leaves conflict markers.
+    Conflicted,
+    /// Can't merge, e.g. the pre-image blob isn't in the repo.
+    Failed,
+}
+
+/// One file of a patch that wouldn't apply, from [`LocalGitSource::apply_patch_preview`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchConflict {
+    pub file_path: String,
+    /// 1-based position of the failing hunk among the file's hunks in the
+    /// patch, when git names one.
+    pub hunk: Option<usize>,
+    /// The failing hunk's first line in the pre-image.
+    pub old_line: Option<u32>,
+    pub reason: PatchConflictReason,
+    /// Git's own explanation.
+    pub message: String,
+    /// How a 3-way merge would fare instead.
+    pub three_way: Option<ThreeWayOutcome>,
+}
+
+/// Whether a patch applies to the working tree, and what blocks it if not.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchPreview {
+    /// Applies cleanly as is.
+    pub applies: bool,
+    /// Every file the patch touches, in patch order.
+    pub files: Vec<String>,
+    pub conflicts: Vec<PatchConflict>,
+}
+
 /// Tracked + untracked files and change statuses for a comparison, gathered
 /// from the directory its head branch is checked out in. Shared by
 /// `list_files` and `list_all_files`.
@@ -137,6 +245,17 @@ struct WorkingTreeFiles {
     /// The directory the listing was taken from — a linked worktree when the
     /// comparison reviews one, otherwise the main repo.
     root: PathBuf,
+    unresolved: UnresolvedFiles,
+}
+
+/// Tracked files whose content isn't on disk as-is, flagged in file listings
+/// so their content isn't read from disk for them.
+#[derive(Default)]
+struct UnresolvedFiles {
+    /// Skip-worktree entries: outside the sparse-checkout cone.
+    not_materialized: HashSet<String>,
+    /// LFS pointers, with the objects they stand for.
+    lfs_pointers: HashMap<String, LfsPointer>,
 }
 
 #[derive(Error, Debug)]
@@ -145,10 +264,16 @@ pub enum LocalGitError {
     Git(String),
     #[error("WORKTREE_EXISTS:{0}")]
     WorktreeExists(String),
+    /// A comparison needs a commit beyond a shallow clone's history, and it
+    /// wasn't deepened to reach it; see [`LocalGitSource::deepen`].
+    #[error("SHALLOW_CLONE:{0}")]
+    ShallowClone(String),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Not a git repository")]
     NotARepo,
+    #[error(transparent)]
+    Offline(#[from] crate::offline::OfflineError),
 }
 
 #[derive(Debug)]
@@ -161,11 +286,14 @@ pub struct LocalGitSource {
     /// Configured `git config user.email`, read once per source instance.
     /// `None` means unset/blank; the outer `OnceLock` guards the read.
     user_email_cache: std::sync::OnceLock<Option<String>>,
+    /// Set for jj workspaces (see [`crate::sources::jj`]).
+    jj: Option<JjRepo>,
 }
 
 impl LocalGitSource {
     pub fn new(repo_path: PathBuf) -> Result<Self, LocalGitError> {
-        if !repo_path.join(".git").exists() {
+        let jj = JjRepo::detect(&repo_path);
+        if !repo_path.join(".git").exists() && jj.is_none() {
             return Err(LocalGitError::NotARepo);
         }
         Ok(Self {
@@ -175,21 +303,45 @@ impl LocalGitSource {
             working_tree_dir_cache: std::sync::Mutex::new(std::collections::HashMap::new()),
             default_branch_cache: std::sync::OnceLock::new(),
             user_email_cache: std::sync::OnceLock::new(),
+            jj,
         })
     }
 
+    /// Whether this is a jj workspace, colocated or not.
+    pub fn is_jj(&self) -> bool {
+        self.jj.is_some()
+    }
+
+    /// A `git` command aimed at this repo's git store — for a jj-native
+    /// workspace, the one inside `.jj`, which has no working tree.
+    fn git_command(&self) -> Command {
+        let mut cmd = Command::new("git");
+        if let Some(jj) = self.jj.as_ref().filter(|jj| !jj.is_colocated()) {
+            cmd.env("GIT_DIR", jj.git_dir());
+        }
+        cmd
+    }
+
     /// Check if the comparison head is checked out somewhere, meaning working
     /// tree changes (staged + unstaged + untracked) should be included in diffs.
     pub fn include_working_tree(&self, comparison: &Comparison) -> bool {
         self.working_tree_dir(comparison).is_some()
     }
 
+    /// Whether the comparison's diff can change without any ref moving: its
+    /// head is checked out, or a side is the index or working tree. Such
+    /// comparisons have no SHA fingerprint and are always recomputed.
+    pub fn diffs_uncommitted(&self, comparison: &Comparison) -> bool {
+        comparison.has_uncommitted_endpoint() || self.include_working_tree(comparison)
+    }
+
     /// Directory whose working tree should be diffed for this comparison.
     ///
     /// Returns `Some` when `comparison.head` is checked out somewhere — either
     /// the main repo's current branch or a linked worktree — so diffs include
-    /// staged + unstaged + untracked changes from that directory. Returns `None`
-    /// for committed-only comparisons (the head branch isn't checked out).
+    /// staged + unstaged + untracked changes from that directory, and for an
+    /// explicit `:worktree` head. Returns `None` for committed-only comparisons
+    /// (the head branch isn't checked out) and an `:index` head.
     ///
     /// A linked worktree's uncommitted changes live in the worktree directory,
     /// not the main repo, so git commands must run there to see them.
@@ -197,6 +349,11 @@ impl LocalGitSource {
     /// Result is cached per head ref — it's consulted several times while
     /// servicing one request and each miss shells out to git.
     pub fn working_tree_dir(&self, comparison: &Comparison) -> Option<PathBuf> {
+        match comparison.head_endpoint() {
+            Endpoint::Worktree => return Some(self.repo_path.clone()),
+            Endpoint::Index => return None,
+            Endpoint::Ref(_) => {}
+        }
         if let Some(cached) = self
             .working_tree_dir_cache
             .lock()
@@ -214,11 +371,14 @@ impl LocalGitSource {
     }
 
     fn compute_working_tree_dir(&self, comparison: &Comparison) -> Option<PathBuf> {
-        // Main repo working tree.
-        if let Ok(branch) = self.get_current_branch() {
-            if comparison.head == branch {
-                return Some(self.repo_path.clone());
-            }
+        // Main repo working tree. jj's lives in the `@` commit, which is diffed
+        // like any other.
+        if self.jj.is_none()
+            && self
+                .get_current_branch()
+                .is_ok_and(|branch| branch == comparison.head)
+        {
+            return Some(self.repo_path.clone());
         }
         // A linked worktree that has `comparison.head` checked out.
         let worktrees = self.list_worktrees().ok()?;
@@ -244,6 +404,9 @@ impl LocalGitSource {
 
     /// Get the current branch name
     pub fn get_current_branch(&self) -> Result<String, LocalGitError> {
+        if self.jj.is_some() {
+            return Ok(jj::WORKING_COPY.to_owned());
+        }
         if let Ok(output) = self.run_git(&["rev-parse", "--abbrev-ref", "HEAD"]) {
             return Ok(output.trim().to_owned());
         }
@@ -252,6 +415,15 @@ impl LocalGitSource {
         Ok(output.trim().to_owned())
     }
 
+    /// A git config value for the repo (e.g. `user.signingkey`). `None` when
+    /// unset or blank.
+    pub fn get_config(&self, key: &str) -> Option<String> {
+        self.run_git(&["config", "--get", key])
+            .ok()
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+    }
+
     /// Get the configured git user's display name (`git config user.name`).
     /// Returns `None` when unset or blank — the UI falls back to leaving the
     /// author empty rather than fabricating an identity.
@@ -291,18 +463,84 @@ impl LocalGitSource {
             .is_some_and(|user| user.eq_ignore_ascii_case(email))
     }
 
-    /// Get remote info (org/repo name and browse URL) from the origin remote.
-    /// Returns `Ok(None)` for local-only repos with no `origin` remote; genuine
-    /// git failures still propagate as errors.
+    /// Get remote info (org/repo name and browse URL) from the review remote
+    /// (see [`Self::review_remote`]). Returns `Ok(None)` for local-only repos
+    /// with no remote to review against; genuine git failures still propagate
+    /// as errors.
     pub fn get_remote_info(&self) -> Result<Option<RemoteInfo>, LocalGitError> {
-        let remotes = self.run_git(&["remote"])?;
-        if !remotes.lines().any(|r| r.trim() == "origin") {
+        let Some(remote) = self.review_remote() else {
             return Ok(None);
-        }
-        let url = self.run_git(&["remote", "get-url", "origin"])?;
+        };
+        let url = self.run_git(&["remote", "get-url", &remote])?;
         parse_remote_url(url.trim()).map(Some)
     }
 
+    /// The repo's remotes with their fetch URLs, in `git remote` order.
+    pub fn list_remotes(&self) -> Result<Vec<GitRemote>, LocalGitError> {
+        let output = self.run_git(&["remote", "-v"])?;
+        let review_remote = self.review_remote();
+        let mut remotes: Vec<GitRemote> = Vec::new();
+        for line in output.lines() {
+            let mut fields = line.split_whitespace();
+            let (Some(name), Some(url), Some("(fetch)")) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            remotes.push(GitRemote {
+                name: name.to_owned(),
+                url: url.to_owned(),
+                is_review_remote: review_remote.as_deref() == Some(name),
+            });
+        }
+        Ok(remotes)
+    }
+
+    /// The remote the default branch, remote-only branches and the PR host
+    /// are resolved from: the one set in [`REVIEW_REMOTE_CONFIG`] if it
+    /// exists, else `origin`, else the repo's only remote. `None` when none
+    /// of those applies, e.g. a local-only repo.
+    pub fn review_remote(&self) -> Option<String> {
+        let output = self.run_git(&["remote"]).ok()?;
+        let names: Vec<&str> = output.lines().map(str::trim).collect();
+        let configured = self.get_config(REVIEW_REMOTE_CONFIG);
+        if let Some(configured) = configured.filter(|c| names.contains(&c.as_str())) {
+            return Some(configured);
+        }
+        if names.contains(&"origin") {
+            return Some("origin".to_owned());
+        }
+        match names.as_slice() {
+            [only] => Some((*only).to_owned()),
+            _ => None,
+        }
+    }
+
+    /// The remote explicitly set in [`REVIEW_REMOTE_CONFIG`], if it still
+    /// exists.
+    pub fn configured_review_remote(&self) -> Option<String> {
+        let configured = self.get_config(REVIEW_REMOTE_CONFIG)?;
+        self.run_git(&["remote", "get-url", &configured])
+            .is_ok()
+            .then_some(configured)
+    }
+
+    /// Set the remote reviews resolve against (in the clone's local git
+    /// config), or go back to the default with `None`.
+    pub fn set_review_remote(&self, remote: Option<&str>) -> Result<(), LocalGitError> {
+        match remote {
+            Some(remote) => {
+                self.run_git(&["remote", "get-url", remote])?;
+                self.run_git(&["config", "--local", REVIEW_REMOTE_CONFIG, remote])?;
+            }
+            None => {
+                // Exits 5 when the key isn't set, which is already the goal.
+                let _ = self.run_git(&["config", "--local", "--unset", REVIEW_REMOTE_CONFIG]);
+            }
+        }
+        Ok(())
+    }
+
     /// The well-known SHA for git's empty tree object.
     /// This exists in every git repo and represents a tree with no files.
     pub const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
@@ -349,7 +587,7 @@ impl LocalGitSource {
                 &format!("{prefix}{git_ref}"),
             ])
             .is_ok()
-        })
+        }) || self.jj.as_ref().is_some_and(|jj| jj.is_bookmark(git_ref))
     }
 
     /// Count commits in `base..head` (e.g. how far a branch is ahead of its
@@ -361,28 +599,40 @@ impl LocalGitSource {
             .and_then(|s| s.trim().parse::<u32>().ok())
     }
 
-    /// Resolve a ref to a SHA, falling back to `origin/<ref>` for
-    /// remote-only branches. Results are cached per `LocalGitSource`.
+    /// Resolve a ref to a SHA, falling back to `<remote>/<ref>` on the review
+    /// remote (see [`Self::review_remote`]) for remote-only branches. Results
+    /// are cached per `LocalGitSource`.
     pub fn resolve_ref(&self, git_ref: &str) -> Option<String> {
-        if git_ref.is_empty() {
+        // `:index` / `:worktree` would otherwise be read as index paths.
+        if git_ref.is_empty() || !matches!(Endpoint::parse(git_ref), Endpoint::Ref(_)) {
             return None;
         }
         if let Some(cached) = self.resolve_ref_cache.lock().unwrap().get(git_ref) {
             return cached.clone();
         }
+        // Git reads `@` as `HEAD`, which in a colocated jj repo is `@-`.
+        let jj_first = git_ref.starts_with(jj::WORKING_COPY);
         let resolved = self
-            .run_git(&["rev-parse", "--verify", git_ref])
-            .ok()
-            .map(|o| o.trim().to_owned())
+            .jj
+            .as_ref()
+            .filter(|_| jj_first)
+            .and_then(|jj| jj.resolve(git_ref))
+            .or_else(|| {
+                self.run_git(&["rev-parse", "--verify", git_ref])
+                    .ok()
+                    .map(|o| o.trim().to_owned())
+            })
             .or_else(|| {
-                if git_ref.starts_with("origin/") {
+                let remote = self.review_remote().unwrap_or_else(|| "origin".to_owned());
+                if git_ref.starts_with(&format!("{remote}/")) {
                     return None;
                 }
-                let with_origin = format!("origin/{git_ref}");
-                self.run_git(&["rev-parse", "--verify", &with_origin])
+                let with_remote = format!("{remote}/{git_ref}");
+                self.run_git(&["rev-parse", "--verify", &with_remote])
                     .ok()
                     .map(|o| o.trim().to_owned())
-            });
+            })
+            .or_else(|| self.jj.as_ref()?.resolve(git_ref));
         self.resolve_ref_cache
             .lock()
             .unwrap()
@@ -431,9 +681,13 @@ impl LocalGitSource {
             .map(|d| d.as_secs() as i64)
     }
 
-    /// Run `git fetch --prune origin` to refresh remote-tracking refs.
+    /// Run `git fetch --prune` of the review remote (`origin` unless
+    /// configured otherwise) to refresh remote-tracking refs. Refused while
+    /// offline.
     pub fn fetch_origin(&self) -> Result<(), LocalGitError> {
-        self.run_git(&["fetch", "--prune", "origin"])?;
+        crate::offline::ensure_online("Fetching from the remote")?;
+        let remote = self.review_remote().unwrap_or_else(|| "origin".to_owned());
+        self.run_git(&["fetch", "--prune", &remote])?;
         // A fetch can change ref SHAs out from under any cached resolutions
         // earlier in this source's lifetime.
         self.resolve_ref_cache.lock().unwrap().clear();
@@ -441,6 +695,52 @@ impl LocalGitSource {
         Ok(())
     }
 
+    /// Whether the repo is a shallow clone, with history cut off at some depth.
+    pub fn is_shallow(&self) -> bool {
+        self.run_git(&["rev-parse", "--is-shallow-repository"])
+            .is_ok_and(|output| output.trim() == "true")
+    }
+
+    /// Run `git fetch --deepen=<depth>` of the review remote, extending a
+    /// shallow clone's history by `depth` commits. Refused while offline.
+    pub fn deepen(&self, depth: u32) -> Result<(), LocalGitError> {
+        crate::offline::ensure_online("Deepening a shallow clone")?;
+        let t0 = Instant::now();
+        let remote = self.review_remote().unwrap_or_else(|| "origin".to_owned());
+        self.run_git(&["fetch", &format!("--deepen={depth}"), &remote])?;
+        // Commits that were missing may now resolve, and merge-bases that
+        // fell back to the base may now exist.
+        self.resolve_ref_cache.lock().unwrap().clear();
+        self.merge_base_cache.lock().unwrap().clear();
+        info!("[deepen] SUCCESS by {depth} in {:?}", t0.elapsed());
+        Ok(())
+    }
+
+    /// Deepen a shallow clone [`DEEPEN_STEP`] commits at a time until
+    /// `reached` holds, if the `autoDeepenShallowClones` setting allows it
+    /// and offline mode is off. Fails with [`LocalGitError::ShallowClone`]
+    /// naming `missing` when it doesn't, or when the history runs out first.
+    fn deepen_until(
+        &self,
+        missing: &str,
+        reached: impl Fn(&Self) -> bool,
+    ) -> Result<(), LocalGitError> {
+        let enabled = crate::service::settings::read_settings()
+            .is_ok_and(|file| file.settings.auto_deepen_shallow_clones);
+        if enabled && !crate::offline::is_offline() {
+            for _ in 0..DEEPEN_ATTEMPTS {
+                if !self.is_shallow() {
+                    break;
+                }
+                self.deepen(DEEPEN_STEP)?;
+                if reached(self) {
+                    return Ok(());
+                }
+            }
+        }
+        Err(LocalGitError::ShallowClone(missing.to_owned()))
+    }
+
     /// Get the default branch name (main or master). The result is stable for a
     /// repo, so it's cached per source instance after the first resolution.
     pub fn get_default_branch(&self) -> Result<String, LocalGitError> {
@@ -451,11 +751,13 @@ impl LocalGitSource {
     }
 
     fn compute_default_branch(&self) -> String {
-        // Try to get from remote origin HEAD
-        if let Ok(output) = self.run_git(&["symbolic-ref", "refs/remotes/origin/HEAD"]) {
-            let trimmed = output.trim();
-            if let Some(branch) = trimmed.strip_prefix("refs/remotes/origin/") {
-                return branch.to_owned();
+        // Try to get from the review remote's HEAD
+        if let Some(remote) = self.review_remote() {
+            let prefix = format!("refs/remotes/{remote}/");
+            if let Ok(output) = self.run_git(&["symbolic-ref", &format!("{prefix}HEAD")]) {
+                if let Some(branch) = output.trim().strip_prefix(&prefix) {
+                    return branch.to_owned();
+                }
             }
         }
         // Fall back to checking if main or master exists
@@ -488,16 +790,10 @@ impl LocalGitSource {
     ) -> Result<DiffShortStat, LocalGitError> {
         let wt_dir = self.working_tree_dir(comparison);
 
-        let merge_base = self.diff_base_ref(comparison);
-        let output = if let Some(dir) = &wt_dir {
-            // Net diff: merge_base vs working tree (single diff captures everything)
-            self.run_git_in(dir, &["diff", "--shortstat", &merge_base])?
-        } else {
-            // Committed diff between base and head refs
-            let resolved_head = self.resolve_ref_or_empty_tree(&comparison.head);
-            let range = format!("{merge_base}..{resolved_head}");
-            self.run_git(&["diff", "--shortstat", &range])?
-        };
+        let (dir, range) = self.diff_range(comparison)?;
+        let mut args = vec!["diff", "--shortstat"];
+        args.extend(range.iter().map(String::as_str));
+        let output = self.run_git_in(&dir, &args)?;
 
         let (mut file_count, additions, deletions) = parse_shortstat(&output);
 
@@ -515,6 +811,54 @@ impl LocalGitSource {
         })
     }
 
+    /// Submodules changed by the comparison, from the gitlink (`160000`) entries
+    /// of `git diff --raw`. Mirrors the two modes of `get_diff()`; in
+    /// working-tree mode a checkout that has moved without being staged reports
+    /// its current `HEAD`, and a dirty checkout appears with equal SHAs.
+    pub fn changed_submodules(
+        &self,
+        comparison: &Comparison,
+    ) -> Result<Vec<SubmoduleChange>, LocalGitError> {
+        const GITLINK_MODE: &str = "160000";
+        let raw_args = [
+            "diff",
+            "--raw",
+            "--no-abbrev",
+            "--no-renames",
+            "--ignore-submodules=none",
+        ];
+
+        let (root, range) = self.diff_range(comparison)?;
+        let mut args = raw_args.to_vec();
+        args.extend(range.iter().map(String::as_str));
+        let output = self.run_git_in(&root, &args)?;
+
+        Ok(output
+            .lines()
+            .filter_map(|line| {
+                let (meta, path) = line.split_once('\t')?;
+                let mut fields = meta.trim_start_matches(':').split_whitespace();
+                let (old_mode, new_mode) = (fields.next()?, fields.next()?);
+                let (old_sha, new_sha) = (fields.next()?, fields.next()?);
+                if old_mode != GITLINK_MODE && new_mode != GITLINK_MODE {
+                    return None;
+                }
+                let new_sha = (new_mode == GITLINK_MODE).then(|| {
+                    if new_sha.bytes().all(|b| b == b'0') {
+                        self.resolve_head_in(&root.join(path))
+                    } else {
+                        new_sha.to_owned()
+                    }
+                });
+                Some(SubmoduleChange {
+                    path: path.to_owned(),
+                    old_sha: (old_mode == GITLINK_MODE).then(|| old_sha.to_owned()),
+                    new_sha,
+                })
+            })
+            .collect())
+    }
+
     /// List all local and remote branches, separated, plus stashes
     /// Branches are sorted by most recent commit date (newest first)
     pub fn list_branches(&self) -> Result<super::traits::BranchList, LocalGitError> {
@@ -1323,6 +1667,49 @@ impl LocalGitSource {
         Ok(commits)
     }
 
+    /// The commits in `base..head` along the first-parent chain, oldest first,
+    /// each with the files it changed (a merge: vs its first parent). Unlike
+    /// [`Self::list_commits`], consecutive entries are parent and child, so any
+    /// run of them is itself a `base..head` range.
+    pub fn list_range_commits(
+        &self,
+        base: &str,
+        head: &str,
+    ) -> Result<Vec<RangeCommit>, LocalGitError> {
+        let range = format!(
+            "{}..{}",
+            self.resolve_ref_or_self(base),
+            self.resolve_ref_or_self(head)
+        );
+        let output = self.run_git(&[
+            "log",
+            "--first-parent",
+            "--reverse",
+            "--no-renames",
+            "--diff-merges=first-parent",
+            "--format=%x1e%H%x1f%s",
+            "--name-only",
+            &range,
+        ])?;
+
+        Ok(output
+            .split('\x1e')
+            .filter_map(|record| {
+                let (header, files) = record.split_once('\n').unwrap_or((record, ""));
+                let (hash, subject) = header.split_once('\x1f')?;
+                Some(RangeCommit {
+                    hash: hash.to_owned(),
+                    subject: subject.to_owned(),
+                    files: files
+                        .lines()
+                        .filter(|l| !l.is_empty())
+                        .map(str::to_owned)
+                        .collect(),
+                })
+            })
+            .collect())
+    }
+
     /// Fetch commit message bodies (`%b`, trimmed, `None` when empty) for the
     /// same `<limit_str> <resolved_ref>` selection as `list_commits`, keyed by
     /// full hash. A dedicated `git log` call with NUL/SOH control-byte
@@ -1467,10 +1854,48 @@ impl LocalGitSource {
         })
     }
 
+    /// Commits `git blame` should look through — typically mass reformats —
+    /// from the file `blame.ignoreRevsFile` names, else the repo's
+    /// [`BLAME_IGNORE_REVS_FILE`].
+    pub fn blame_ignore_revs(&self) -> Vec<String> {
+        let configured = self
+            .run_git(&["config", "--get", "blame.ignoreRevsFile"])
+            .ok()
+            .map(|path| path.trim().to_owned())
+            .filter(|path| !path.is_empty());
+        let path = configured.as_deref().unwrap_or(BLAME_IGNORE_REVS_FILE);
+        std::fs::read_to_string(self.repo_path.join(path))
+            .map(|content| parse_ignore_revs(&content))
+            .unwrap_or_default()
+    }
+
+    /// The hunks `rev` made to `paths`, against its first parent, unsplit.
+    pub fn get_commit_hunks(
+        &self,
+        rev: &str,
+        paths: &[&str],
+    ) -> Result<Vec<crate::diff::parser::DiffHunk>, LocalGitError> {
+        let parent = format!("{rev}^");
+        let mut args = vec![
+            "diff",
+            "--no-renames",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+            &parent,
+            rev,
+            "--",
+        ];
+        args.extend_from_slice(paths);
+        let diff = self.run_git(&args)?;
+        Ok(crate::diff::parser::parse_raw_multi_file_diff(&diff))
+    }
+
     /// Attribute each hunk of a comparison's net diff to the commit(s) in
     /// `base..head` that introduced its lines, via `git blame`. Pure-deletion
     /// hunks (no added lines to blame) are attributed by matching their
     /// removed-line content against each commit's own diff for the file.
+    /// Commits in [`Self::blame_ignore_revs`] are looked through, and only
+    /// credited with a hunk nothing else explains.
     pub fn attribute_hunks_to_commits(
         &self,
         comparison: &super::traits::Comparison,
@@ -1510,10 +1935,17 @@ impl LocalGitSource {
         let wt_dir = self.working_tree_dir(comparison);
         let blame_dir = wt_dir.as_deref().unwrap_or(&self.repo_path);
         let blame_head = wt_dir.is_none().then_some(comparison.head.as_str());
+        let ignore_revs = self.blame_ignore_revs();
 
         for (file_path, file_hunks) in by_file {
             let blame = self
-                .blame_new_lines(blame_dir, file_path, &comparison.base, blame_head)
+                .blame_new_lines(
+                    blame_dir,
+                    file_path,
+                    &comparison.base,
+                    blame_head,
+                    &ignore_revs,
+                )
                 .unwrap_or_default();
 
             // Only computed if the file has a hunk with no added lines to blame.
@@ -1526,9 +1958,10 @@ impl LocalGitSource {
                 // would otherwise poison the hunk's attribution. They're only
                 // used as a last resort, for hunks that are purely whitespace
                 // (e.g. a formatting-only change) and would otherwise end up
-                // unattributed.
+                // unattributed. Lines blame still pins on an ignored rev (ones
+                // it added outright) are held back the same way.
                 let mut shas: Vec<String> = Vec::new();
-                let mut whitespace_shas: Vec<String> = Vec::new();
+                let mut fallback_shas: Vec<String> = Vec::new();
                 for line in &hunk.lines {
                     if line.line_type != LineType::Added {
                         continue;
@@ -1542,8 +1975,8 @@ impl LocalGitSource {
                     if !commit_order.contains_key(sha.as_str()) {
                         continue;
                     }
-                    let target = if line.content.trim().is_empty() {
-                        &mut whitespace_shas
+                    let target = if line.content.trim().is_empty() || ignore_revs.contains(sha) {
+                        &mut fallback_shas
                     } else {
                         &mut shas
                     };
@@ -1552,7 +1985,7 @@ impl LocalGitSource {
                     }
                 }
                 if shas.is_empty() {
-                    shas = whitespace_shas;
+                    shas = fallback_shas;
                 }
 
                 if shas.is_empty() {
@@ -1583,7 +2016,7 @@ impl LocalGitSource {
                             self.removed_lines_by_commit(file_path, &comparison.key)
                                 .unwrap_or_default()
                         });
-                        for commit in &commits {
+                        for commit in commits.iter().filter(|c| !ignore_revs.contains(&c.hash)) {
                             if let Some(removed_by_commit) = diffs.get(&commit.hash) {
                                 if removed.iter().any(|l| removed_by_commit.contains(*l)) {
                                     shas.push(commit.hash.clone());
@@ -1625,15 +2058,19 @@ impl LocalGitSource {
     /// still blames the committed `HEAD`, not uncommitted edits). Working-tree
     /// lines with no committed history come back tagged with git's all-zero
     /// "not committed yet" sha, which likewise isn't in the caller's commit
-    /// list and gets filtered out.
+    /// list and gets filtered out. Blame passes through `ignore_revs`.
     fn blame_new_lines(
         &self,
         dir: &std::path::Path,
         file_path: &str,
         base: &str,
         head: Option<&str>,
+        ignore_revs: &[String],
     ) -> Result<HashMap<u32, String>, LocalGitError> {
         let mut args = vec!["blame", "--porcelain"];
+        for rev in ignore_revs {
+            args.extend(["--ignore-rev", rev]);
+        }
         let range = head.map(|head| format!("{base}..{head}"));
         if let Some(range) = &range {
             args.push(range);
@@ -1702,19 +2139,30 @@ impl LocalGitSource {
     }
 
     fn run_git(&self, args: &[&str]) -> Result<String, LocalGitError> {
-        run_git_cmd(&self.repo_path, args)
+        self.run_git_in(&self.repo_path, args)
     }
 
-    /// Run a git command in a directory other than `self.repo_path`.
-    fn run_git_in(&self, dir: &std::path::Path, args: &[&str]) -> Result<String, LocalGitError> {
-        run_git_cmd(dir, args)
+    /// Run a git command in a directory other than `self.repo_path`, such as
+    /// one of its worktrees.
+    pub fn run_git_in(
+        &self,
+        dir: &std::path::Path,
+        args: &[&str],
+    ) -> Result<String, LocalGitError> {
+        git_stdout(&self.git_command().args(args).current_dir(dir).output()?)
     }
 
     fn run_git_bytes(&self, args: &[&str]) -> Result<Vec<u8>, LocalGitError> {
-        let output = Command::new("git")
-            .args(args)
-            .current_dir(&self.repo_path)
-            .output()?;
+        self.run_git_bytes_in(&self.repo_path, args)
+    }
+
+    /// [`Self::run_git_bytes`] in a directory other than `self.repo_path`.
+    fn run_git_bytes_in(
+        &self,
+        dir: &std::path::Path,
+        args: &[&str],
+    ) -> Result<Vec<u8>, LocalGitError> {
+        let output = self.git_command().args(args).current_dir(dir).output()?;
 
         if output.status.success() {
             Ok(output.stdout)
@@ -1727,7 +2175,13 @@ impl LocalGitSource {
 
     /// Get file content as bytes at the specified ref
     pub fn get_file_bytes(&self, file_path: &str, git_ref: &str) -> Result<Vec<u8>, LocalGitError> {
-        let ref_spec = format!("{}:{}", self.resolve_ref_or_self(git_ref), file_path);
+        let ref_spec = match Endpoint::parse(git_ref) {
+            Endpoint::Ref(git_ref) => {
+                format!("{}:{}", self.resolve_ref_or_self(git_ref), file_path)
+            }
+            Endpoint::Index => format!(":{file_path}"),
+            Endpoint::Worktree => return Ok(std::fs::read(self.repo_path.join(file_path))?),
+        };
         self.run_git_bytes(&["show", &ref_spec])
     }
 
@@ -1737,6 +2191,55 @@ impl LocalGitSource {
         Ok(output.lines().map(std::borrow::ToOwned::to_owned).collect())
     }
 
+    /// Blob hash of every tracked file's working-tree content, keyed by path:
+    /// the index's hash for files that match it, re-hashed with
+    /// `git hash-object` for files modified since they were staged. Tracked
+    /// files missing from disk and submodule entries are omitted.
+    pub fn tracked_blob_hashes(&self) -> Result<HashMap<String, String>, LocalGitError> {
+        let mut hashes = HashMap::new();
+        for entry in self.run_git(&["ls-files", "-s", "-z"])?.split('\0') {
+            let Some((meta, path)) = entry.split_once('\t') else {
+                continue;
+            };
+            let mut fields = meta.split_whitespace();
+            if let (Some(mode), Some(hash)) = (fields.next(), fields.next()) {
+                if mode != "160000" {
+                    hashes.insert(path.to_owned(), hash.to_owned());
+                }
+            }
+        }
+
+        let modified = self.run_git(&["diff", "--name-only", "-z"])?;
+        let mut present = Vec::new();
+        for path in modified.split('\0').filter(|p| !p.is_empty()) {
+            if self.repo_path.join(path).is_file() {
+                present.push(path);
+            } else {
+                hashes.remove(path);
+            }
+        }
+        if !present.is_empty() {
+            let input = present.join("\n");
+            let output =
+                self.run_git_with_stdin(&["hash-object", "--stdin-paths"], input.as_bytes())?;
+            for (path, hash) in present.iter().zip(output.lines()) {
+                hashes.insert((*path).to_owned(), hash.trim().to_owned());
+            }
+        }
+        Ok(hashes)
+    }
+
+    /// Blob hash of a working-tree file's current content, or `None` if it
+    /// doesn't exist on disk.
+    pub fn hash_file(&self, file_path: &str) -> Option<String> {
+        if !self.repo_path.join(file_path).is_file() {
+            return None;
+        }
+        self.run_git(&["hash-object", "--", file_path])
+            .ok()
+            .map(|s| s.trim().to_owned())
+    }
+
     /// Get the merge-base between two refs (cached per instance).
     fn get_merge_base(&self, ref1: &str, ref2: &str) -> Result<String, LocalGitError> {
         let key = (ref1.to_owned(), ref2.to_owned());
@@ -1745,7 +2248,19 @@ impl LocalGitSource {
         }
         let r1 = self.resolve_ref_or_self(ref1);
         let r2 = self.resolve_ref_or_self(ref2);
-        let output = self.run_git(&["merge-base", &r1, &r2])?;
+        let output = match self.run_git(&["merge-base", &r1, &r2]) {
+            Ok(output) => output,
+            // No common ancestor (a silent failure, unlike a tree `head`'s)
+            // may just mean the fork point is older than a shallow clone
+            // reaches.
+            Err(LocalGitError::Git(stderr)) if stderr.trim().is_empty() && self.is_shallow() => {
+                let merge_base = |source: &Self| source.run_git(&["merge-base", &r1, &r2]).ok();
+                self.deepen_until(ref1, |source| merge_base(source).is_some())
+                    .map_err(|_| LocalGitError::Git(stderr))?;
+                merge_base(self).unwrap_or_default()
+            }
+            Err(e) => return Err(e),
+        };
         let result = output.trim().to_owned();
         self.merge_base_cache
             .lock()
@@ -1774,15 +2289,78 @@ impl LocalGitSource {
     /// rather than `comparison.base` — otherwise a `head` that is behind `base`
     /// gets diffed against `base`'s newer tip and shows its unrelated changes as
     /// noise.
+    ///
+    /// An `:index` base is returned as is; [`get_file_bytes`](Self::get_file_bytes)
+    /// reads it.
     pub fn diff_base_ref(&self, comparison: &Comparison) -> String {
+        if comparison.base_endpoint() == Endpoint::Index {
+            return Comparison::INDEX.to_owned();
+        }
         if let Some(dir) = self.working_tree_dir(comparison) {
             let resolved_head = self.resolve_head_in(&dir);
             self.merge_base_or_base(&comparison.base, &resolved_head)
+        } else if comparison.head_endpoint() == Endpoint::Index {
+            let resolved_head = self.resolve_head_in(&self.repo_path);
+            self.merge_base_or_base(&comparison.base, &resolved_head)
         } else {
             self.merge_base_or_base(&comparison.base, &comparison.head)
         }
     }
 
+    /// Make sure a shallow clone has the comparison's base commit, deepening
+    /// it if allowed (see [`Self::deepen_until`]). Without this a base older
+    /// than the clone's history would diff as the empty tree.
+    fn reach_base(&self, comparison: &Comparison) -> Result<(), LocalGitError> {
+        let Endpoint::Ref(base) = comparison.base_endpoint() else {
+            return Ok(());
+        };
+        if base.is_empty() || !self.is_shallow() || self.has_object(base) {
+            return Ok(());
+        }
+        self.deepen_until(base, |source| source.has_object(base))
+    }
+
+    /// Whether `git_ref` names an object present in the repo. A full SHA
+    /// resolves even when its object is missing, so ask for the object itself.
+    fn has_object(&self, git_ref: &str) -> bool {
+        self.run_git(&["cat-file", "-e", &self.resolve_ref_or_self(git_ref)])
+            .is_ok()
+    }
+
+    /// Where a comparison's `git diff` runs, and the revision arguments that
+    /// select its two sides:
+    /// - head checked out, or `:worktree` → the diff base vs that working tree
+    /// - `:index` head → `--cached` against the diff base
+    /// - `:index..:worktree` → no revisions: git's own index-vs-working-tree diff
+    /// - otherwise → the committed range `merge-base..head`
+    ///
+    /// Errors for endpoint pairs with no meaning, like a `:worktree` base.
+    fn diff_range(&self, comparison: &Comparison) -> Result<(PathBuf, Vec<String>), LocalGitError> {
+        self.reach_base(comparison)?;
+        match (comparison.base_endpoint(), comparison.head_endpoint()) {
+            (Endpoint::Index, Endpoint::Worktree) => Ok((self.repo_path.clone(), Vec::new())),
+            (Endpoint::Ref(_), Endpoint::Index) => Ok((
+                self.repo_path.clone(),
+                vec!["--cached".to_owned(), self.diff_base_ref(comparison)],
+            )),
+            (Endpoint::Ref(_), Endpoint::Ref(_) | Endpoint::Worktree) => {
+                let merge_base = self.diff_base_ref(comparison);
+                if let Some(dir) = self.working_tree_dir(comparison) {
+                    return Ok((dir, vec![merge_base]));
+                }
+                let resolved_head = self.resolve_ref_or_empty_tree(&comparison.head);
+                Ok((
+                    self.repo_path.clone(),
+                    vec![format!("{merge_base}..{resolved_head}")],
+                ))
+            }
+            _ => Err(LocalGitError::Git(format!(
+                "Cannot diff {}: the working tree can only be a comparison's head",
+                comparison.key
+            ))),
+        }
+    }
+
     fn get_changed_files(
         &self,
         comparison: &Comparison,
@@ -1790,18 +2368,13 @@ impl LocalGitSource {
         let mut changes = HashMap::new();
         let mut rename_map = HashMap::new();
 
-        let merge_base = self.diff_base_ref(comparison);
-        if let Some(dir) = self.working_tree_dir(comparison) {
-            // Net change status: merge_base vs working tree (single diff captures everything)
-            let output = self.run_git_in(&dir, &["diff", "--name-status", &merge_base])?;
-            self.parse_name_status(&output, &mut changes, &mut rename_map);
-        } else {
-            // Committed diff between base and head refs
-            let resolved_head = self.resolve_ref_or_empty_tree(&comparison.head);
-            let range = format!("{merge_base}..{resolved_head}");
-            let output = self.run_git(&["diff", "--name-status", &range])?;
-            self.parse_name_status(&output, &mut changes, &mut rename_map);
-        }
+        // Against a working tree this is the net change status: a single diff
+        // captures everything.
+        let (dir, range) = self.diff_range(comparison)?;
+        let mut args = vec!["diff", "--name-status"];
+        args.extend(range.iter().map(String::as_str));
+        let output = self.run_git_in(&dir, &args)?;
+        self.parse_name_status(&output, &mut changes, &mut rename_map);
 
         Ok((changes, rename_map))
     }
@@ -1843,6 +2416,27 @@ impl LocalGitSource {
         Ok(output.lines().map(std::borrow::ToOwned::to_owned).collect())
     }
 
+    /// The content of a file outside a sparse checkout's cone, in the
+    /// directory the comparison's head is checked out in: tracked but marked
+    /// skip-worktree, so not on disk, its working-tree side is what the index
+    /// holds. `None` for any other file, or when the head isn't checked out.
+    pub fn unmaterialized_content(
+        &self,
+        comparison: &Comparison,
+        file_path: &str,
+    ) -> Option<Vec<u8>> {
+        let dir = self.working_tree_dir(comparison)?;
+        let listing = self
+            .run_git_in(&dir, &["ls-files", "-t", "--", file_path])
+            .ok()?;
+        // `-t` tags skip-worktree entries with `S`.
+        if !listing.lines().any(|line| line.starts_with("S ")) {
+            return None;
+        }
+        self.run_git_bytes_in(&dir, &["show", &format!(":{file_path}")])
+            .ok()
+    }
+
     /// Check if a file is tracked by git (in the index)
     pub fn is_file_tracked(&self, file_path: &str) -> Result<bool, LocalGitError> {
         let output = self.run_git(&["ls-files", file_path])?;
@@ -1868,11 +2462,12 @@ impl LocalGitSource {
             }
         }
 
-        let mut all_files: HashSet<String> = self
-            .run_git_in(&root, &["ls-files"])?
-            .lines()
-            .map(std::borrow::ToOwned::to_owned)
-            .collect();
+        let (tracked, not_materialized) = self.tracked_files(&root)?;
+        let unresolved = UnresolvedFiles {
+            lfs_pointers: self.lfs_pointers(&root, &tracked, &not_materialized),
+            not_materialized,
+        };
+        let mut all_files = tracked;
         for path in file_status.keys() {
             all_files.insert(path.clone());
         }
@@ -1882,9 +2477,74 @@ impl LocalGitSource {
             rename_map,
             all_files,
             root,
+            unresolved,
         })
     }
 
+    /// The files tracked in `root`'s index, and those of them outside the
+    /// sparse-checkout cone (which `ls-files -t` tags `S`, skip-worktree).
+    fn tracked_files(
+        &self,
+        root: &std::path::Path,
+    ) -> Result<(HashSet<String>, HashSet<String>), LocalGitError> {
+        let mut tracked = HashSet::new();
+        let mut not_materialized = HashSet::new();
+        for line in self.run_git_in(root, &["ls-files", "-t"])?.lines() {
+            let Some((tag, path)) = line.split_once(' ') else {
+                continue;
+            };
+            if tag == "S" {
+                not_materialized.insert(path.to_owned());
+            }
+            tracked.insert(path.to_owned());
+        }
+        Ok((tracked, not_materialized))
+    }
+
+    /// The files of `tracked` under `root` that are LFS pointers on disk: an
+    /// `lfs` filter attribute, and pointer content because the object wasn't
+    /// pulled. Skipped altogether in repos without a tracked
+    /// `.gitattributes`, where nothing can be an LFS file.
+    fn lfs_pointers(
+        &self,
+        root: &std::path::Path,
+        tracked: &HashSet<String>,
+        not_materialized: &HashSet<String>,
+    ) -> HashMap<String, LfsPointer> {
+        let has_attributes = tracked
+            .iter()
+            .any(|path| path.rsplit('/').next() == Some(".gitattributes"));
+        if !has_attributes {
+            return HashMap::new();
+        }
+        let mut input = Vec::new();
+        for path in tracked.difference(not_materialized) {
+            input.extend_from_slice(path.as_bytes());
+            input.push(0);
+        }
+        let Ok(output) =
+            self.run_git_with_stdin(&["check-attr", "--stdin", "-z", "filter"], &input)
+        else {
+            return HashMap::new();
+        };
+        // `-z` output is path, attribute, value, each NUL-terminated.
+        let fields: Vec<&str> = output.split('\0').collect();
+        fields
+            .chunks_exact(3)
+            .filter(|record| record[2] == "lfs")
+            .filter_map(|record| {
+                let path = record[0];
+                let full_path = root.join(path);
+                let len = std::fs::symlink_metadata(&full_path).ok()?.len();
+                if len > lfs::MAX_POINTER_BYTES {
+                    return None;
+                }
+                let pointer = lfs::parse(&std::fs::read(&full_path).ok()?)?;
+                Some((path.to_owned(), pointer))
+            })
+            .collect()
+    }
+
     /// Get all files including gitignored (for browsing, not review)
     /// Uses git ls-files with different flags to get everything
     pub fn list_all_files(&self, comparison: &Comparison) -> Result<Vec<FileEntry>, LocalGitError> {
@@ -1893,6 +2553,7 @@ impl LocalGitSource {
             rename_map,
             mut all_files,
             root,
+            unresolved,
         } = self.working_tree_files(comparison)?;
 
         // Get gitignored entries using --directory to collapse entire ignored
@@ -1937,6 +2598,7 @@ impl LocalGitSource {
             &gitignored_dirs,
             Some(&root),
             &rename_map,
+            &unresolved,
         ))
     }
 
@@ -1946,8 +2608,11 @@ impl LocalGitSource {
     /// suitable for "browse mode" where the user wants to see every file
     /// in the repo without a diff comparison.
     pub fn list_tracked_files(&self) -> Result<Vec<FileEntry>, LocalGitError> {
-        let tracked = self.run_git(&["ls-files"])?;
-        let all_files: HashSet<String> = tracked.lines().map(|l| l.to_owned()).collect();
+        let (all_files, not_materialized) = self.tracked_files(&self.repo_path)?;
+        let unresolved = UnresolvedFiles {
+            lfs_pointers: self.lfs_pointers(&self.repo_path, &all_files, &not_materialized),
+            not_materialized,
+        };
         let file_status: HashMap<String, FileStatus> = HashMap::new();
         let gitignored_dirs: HashSet<String> = HashSet::new();
         let rename_map: HashMap<String, String> = HashMap::new();
@@ -1958,6 +2623,7 @@ impl LocalGitSource {
             &gitignored_dirs,
             Some(&self.repo_path),
             &rename_map,
+            &unresolved,
         ))
     }
 
@@ -2040,6 +2706,8 @@ impl LocalGitSource {
                 renamed_from: None,
                 size: None,
                 modified_at: None,
+                is_lfs_pointer: false,
+                not_materialized: false,
             });
         }
 
@@ -2073,7 +2741,8 @@ impl LocalGitSource {
 
     /// Run a git command with data piped to stdin.
     fn run_git_with_stdin(&self, args: &[&str], input: &[u8]) -> Result<String, LocalGitError> {
-        let mut child = Command::new("git")
+        let mut child = self
+            .git_command()
             .args(args)
             .current_dir(&self.repo_path)
             .stdin(Stdio::piped())
@@ -2116,21 +2785,91 @@ impl LocalGitSource {
         Ok(tree.trim().to_owned())
     }
 
-    /// Run a git command against an alternate index file (`GIT_INDEX_FILE`),
-    /// optionally feeding `stdin`. Used to build trees without disturbing the
-    /// repo's real index or working tree.
-    fn git_with_index(
-        &self,
-        index_path: &std::ffi::OsStr,
-        args: &[&str],
-        stdin: Option<&[u8]>,
-    ) -> Result<String, LocalGitError> {
-        let mut cmd = Command::new("git");
-        cmd.args(args)
-            .current_dir(&self.repo_path)
-            .env("GIT_INDEX_FILE", index_path)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+    /// Dry-run applying a unified-diff `patch` to the working tree (`git apply
+    /// --check`) without changing anything, reporting each file that wouldn't
+    /// apply and why. Files that don't apply directly are also dry-run as a
+    /// 3-way merge against the index. Errors only when git can't read the
+    /// patch at all.
+    pub fn apply_patch_preview(&self, patch: &[u8]) -> Result<PatchPreview, LocalGitError> {
+        let (applies, stderr) = self.check_apply(patch, false)?;
+        let (files, mut conflicts, _) = parse_apply_check(&stderr);
+        if applies {
+            return Ok(PatchPreview {
+                applies,
+                files,
+                conflicts: Vec::new(),
+            });
+        }
+        if conflicts.is_empty() {
+            return Err(LocalGitError::Git(stderr));
+        }
+
+        let (_, stderr) = self.check_apply(patch, true)?;
+        let (_, three_way_failures, merged) = parse_apply_check(&stderr);
+        let sections =
+            crate::diff::parser::parse_raw_multi_file_diff(&String::from_utf8_lossy(patch));
+        for conflict in &mut conflicts {
+            conflict.hunk = conflict.old_line.and_then(|line| {
+                sections
+                    .iter()
+                    .filter(|h| h.file_path == conflict.file_path)
+                    .position(|h| h.old_start == line)
+                    .map(|i| i + 1)
+            });
+            conflict.three_way = merged.get(&conflict.file_path).copied().or_else(|| {
+                three_way_failures
+                    .iter()
+                    .any(|f| f.file_path == conflict.file_path)
+                    .then_some(ThreeWayOutcome::Failed)
+            });
+        }
+        Ok(PatchPreview {
+            applies,
+            files,
+            conflicts,
+        })
+    }
+
+    /// `git apply --check --verbose`, optionally `--3way`: whether it passed,
+    /// and its report.
+    fn check_apply(&self, patch: &[u8], three_way: bool) -> Result<(bool, String), LocalGitError> {
+        let mut args = vec!["apply", "--check", "--verbose"];
+        if three_way {
+            args.push("--3way");
+        }
+        let mut child = self
+            .git_command()
+            .args(&args)
+            .current_dir(&self.repo_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(patch)?;
+        }
+        let output = child.wait_with_output()?;
+        Ok((
+            output.status.success(),
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ))
+    }
+
+    /// Run a git command against an alternate index file (`GIT_INDEX_FILE`),
+    /// optionally feeding `stdin`. Used to build trees without disturbing the
+    /// repo's real index or working tree.
+    fn git_with_index(
+        &self,
+        index_path: &std::ffi::OsStr,
+        args: &[&str],
+        stdin: Option<&[u8]>,
+    ) -> Result<String, LocalGitError> {
+        let mut cmd = self.git_command();
+        cmd.args(args)
+            .current_dir(&self.repo_path)
+            .env("GIT_INDEX_FILE", index_path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
         if stdin.is_some() {
             cmd.stdin(Stdio::piped());
         }
@@ -2150,6 +2889,114 @@ impl LocalGitSource {
         }
     }
 
+    /// `file_path`'s diff with each hunk widened to its whole enclosing
+    /// function (`git diff --function-context`, functions found by the
+    /// file's diff driver).
+    pub fn get_function_context_diff(
+        &self,
+        comparison: &Comparison,
+        file_path: &str,
+    ) -> Result<String, LocalGitError> {
+        self.diff_with(comparison, Some(file_path), &["--function-context"])
+            .map(|diff| diff.text)
+    }
+
+    /// [`DiffSource::get_diff`] with the encoding of each file whose section
+    /// wasn't UTF-8, to tag its hunks with.
+    pub fn get_decoded_diff(
+        &self,
+        comparison: &Comparison,
+        file_path: Option<&str>,
+    ) -> Result<DecodedDiff, LocalGitError> {
+        self.diff_with(comparison, file_path, &[])
+    }
+
+    fn diff_with(
+        &self,
+        comparison: &Comparison,
+        file_path: Option<&str>,
+        extra: &[&str],
+    ) -> Result<DecodedDiff, LocalGitError> {
+        // Against a working tree this is the net diff: a single diff avoids
+        // phantom hunks when working tree changes revert committed changes.
+        let (dir, range) = self.diff_range(comparison)?;
+        let diff_args = hunk_diff_args();
+        let mut args: Vec<&str> = diff_args.iter().map(String::as_str).collect();
+        args.extend(extra);
+        args.extend(range.iter().map(String::as_str));
+        if let Some(path) = file_path {
+            args.push("--");
+            args.push(path);
+        }
+        let output = self.run_git_bytes_in(&dir, &args).unwrap_or_default();
+        Ok(decode_diff(output))
+    }
+
+    /// [`Self::get_decoded_diff`] under a byte budget, read from git one file
+    /// section at a time: sections over `max_file_bytes`, and those that don't
+    /// fit in what's left of `max_diff_bytes`, are measured but never held in
+    /// memory.
+    pub fn get_decoded_diff_within(
+        &self,
+        comparison: &Comparison,
+        max_file_bytes: usize,
+        max_diff_bytes: usize,
+    ) -> Result<BudgetedDiff, LocalGitError> {
+        let (dir, range) = self.diff_range(comparison)?;
+        let mut child = self
+            .git_command()
+            .args(hunk_diff_args())
+            .args(&range)
+            .current_dir(&dir)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let Some(stdout) = child.stdout.take() else {
+            return Err(LocalGitError::Git("git diff has no output".to_owned()));
+        };
+        let mut reader = BufReader::new(stdout);
+
+        let mut budgeted = BudgetedDiff::default();
+        let mut kept = Vec::new();
+        let mut section = DiffSection::default();
+        // Lines are read in bounded pieces, so one huge line isn't held whole.
+        let mut piece = Vec::new();
+        let mut at_line_start = true;
+        loop {
+            piece.clear();
+            if (&mut reader)
+                .take(64 * 1024)
+                .read_until(b'\n', &mut piece)?
+                == 0
+            {
+                break;
+            }
+            if at_line_start && piece.starts_with(b"diff --git ") {
+                budgeted.finish(&section, &mut kept, max_file_bytes, max_diff_bytes);
+                section = DiffSection {
+                    header: String::from_utf8_lossy(&piece).into_owned(),
+                    ..DiffSection::default()
+                };
+            }
+            section.len += piece.len();
+            let room = max_diff_bytes.saturating_sub(kept.len());
+            if section.len <= max_file_bytes.min(room) {
+                section.bytes.extend_from_slice(&piece);
+            } else {
+                section.bytes = Vec::new();
+            }
+            at_line_start = piece.ends_with(b"\n");
+        }
+        budgeted.finish(&section, &mut kept, max_file_bytes, max_diff_bytes);
+
+        // Like `diff_with`, a failed diff reads as an empty one.
+        if !child.wait()?.success() {
+            return Ok(BudgetedDiff::default());
+        }
+        budgeted.diff = decode_diff(kept);
+        Ok(budgeted)
+    }
+
     /// Get the raw diff for a single file.
     ///
     /// When `cached` is true, returns the staged diff (`git diff --cached`).
@@ -2159,13 +3006,8 @@ impl LocalGitSource {
         file_path: &str,
         cached: bool,
     ) -> Result<String, LocalGitError> {
-        let mut args = vec![
-            "diff",
-            "--histogram",
-            "--no-renames",
-            "--src-prefix=a/",
-            "--dst-prefix=b/",
-        ];
+        let diff_args = hunk_diff_args();
+        let mut args: Vec<&str> = diff_args.iter().map(String::as_str).collect();
         if cached {
             args.push("--cached");
         }
@@ -2200,7 +3042,42 @@ impl LocalGitSource {
                 "No unstaged changes for this file".to_owned(),
             ));
         }
-        let patch = build_selective_patch(raw_diff, file_path, content_hashes)?;
+        let patch = build_selective_patch(raw_diff, file_path, content_hashes, false)?;
+        self.run_git_with_stdin(&["apply", "--cached", "--allow-empty"], patch.as_bytes())?;
+        Ok(())
+    }
+
+    /// Stage unstaged hunks across files by hunk ID (`<file>:<content hash>`)
+    /// as a single `git apply --cached`, which checks every file's patch
+    /// before writing the index — so either all of them are staged or, on any
+    /// failure, the index is left as it was. Errors without staging anything
+    /// if any of the hunks is no longer in the unstaged diff.
+    pub fn stage_hunk_ids(&self, hunk_ids: &[String]) -> Result<(), LocalGitError> {
+        let mut by_file: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for id in hunk_ids {
+            let (file_path, hash) = id
+                .rsplit_once(':')
+                .ok_or_else(|| LocalGitError::Git(format!("Not a hunk ID: {id}")))?;
+            by_file.entry(file_path).or_default().push(hash.to_owned());
+        }
+        if by_file.is_empty() {
+            return Err(LocalGitError::Git("No hunks to stage".to_owned()));
+        }
+
+        let mut patch = String::new();
+        for (file_path, hashes) in &by_file {
+            let raw_diff = self.get_raw_file_diff(file_path, false)?;
+            let unstaged: HashSet<String> = parse_diff(&raw_diff, file_path)
+                .into_iter()
+                .map(|hunk| hunk.content_hash)
+                .collect();
+            if let Some(missing) = hashes.iter().find(|h| !unstaged.contains(*h)) {
+                return Err(LocalGitError::Git(format!(
+                    "{file_path}:{missing} is no longer an unstaged change"
+                )));
+            }
+            patch.push_str(&build_selective_patch(&raw_diff, file_path, hashes, false)?);
+        }
         self.run_git_with_stdin(&["apply", "--cached", "--allow-empty"], patch.as_bytes())?;
         Ok(())
     }
@@ -2231,7 +3108,7 @@ impl LocalGitSource {
                 "No staged changes for this file".to_owned(),
             ));
         }
-        let patch = build_selective_patch(raw_diff, file_path, content_hashes)?;
+        let patch = build_selective_patch(raw_diff, file_path, content_hashes, true)?;
         self.run_git_with_stdin(
             &["apply", "--cached", "--reverse", "--allow-empty"],
             patch.as_bytes(),
@@ -2244,7 +3121,7 @@ impl LocalGitSource {
     /// Unlike `run_git()`, this returns the child process immediately so the
     /// caller can stream output lines in real time (e.g. for pre-commit hooks).
     pub fn spawn_commit(&self, message: &str) -> Result<std::process::Child, LocalGitError> {
-        Command::new("git")
+        self.git_command()
             .args(["commit", "-m", message])
             .current_dir(&self.repo_path)
             .env("FORCE_COLOR", "1")
@@ -2263,25 +3140,15 @@ impl LocalGitSource {
 
     /// Get the full unstaged diff (`git diff` — worktree vs index).
     pub fn get_unstaged_diff(&self) -> Result<String, LocalGitError> {
-        self.run_git(&[
-            "diff",
-            "--histogram",
-            "--no-renames",
-            "--src-prefix=a/",
-            "--dst-prefix=b/",
-        ])
+        let args = hunk_diff_args();
+        self.run_git(&args.iter().map(String::as_str).collect::<Vec<_>>())
     }
 
     /// Get the full staged diff (git diff --cached).
     pub fn get_staged_diff(&self) -> Result<String, LocalGitError> {
-        self.run_git(&[
-            "diff",
-            "--cached",
-            "--histogram",
-            "--no-renames",
-            "--src-prefix=a/",
-            "--dst-prefix=b/",
-        ])
+        let mut args = hunk_diff_args();
+        args.push("--cached".to_owned());
+        self.run_git(&args.iter().map(String::as_str).collect::<Vec<_>>())
     }
 
     /// Get recent full commit messages (subject + body) for style context.
@@ -2331,7 +3198,8 @@ impl LocalGitSource {
         args.push(query);
 
         // Run git grep - note: returns exit code 1 if no matches, which is not an error
-        let output = Command::new("git")
+        let output = self
+            .git_command()
             .args(&args)
             .current_dir(&self.repo_path)
             .output()?;
@@ -2392,6 +3260,7 @@ struct SymlinkInfo {
 /// Build a file tree from file paths and statuses.
 /// Shared helper used by both `list_files()` and `list_all_files()`.
 /// When repo_path is provided, symlinks are detected and broken symlinks are filtered out.
+/// Files in `unresolved` are flagged, and not-materialized ones aren't looked up on disk.
 #[expect(
     clippy::needless_pass_by_value,
     reason = "takes ownership for consistency with callers that build and pass the set"
@@ -2402,6 +3271,7 @@ fn build_file_tree(
     gitignored_dirs: &HashSet<String>,
     repo_path: Option<&std::path::Path>,
     rename_map: &HashMap<String, String>,
+    unresolved: &UnresolvedFiles,
 ) -> Vec<FileEntry> {
     use std::fs;
 
@@ -2412,6 +3282,9 @@ fn build_file_tree(
         all_files
             .iter()
             .map(|path| {
+                if unresolved.not_materialized.contains(path) {
+                    return (path.clone(), SymlinkInfo::default());
+                }
                 let full_path = repo.join(path);
                 let info = match fs::symlink_metadata(&full_path) {
                     Ok(metadata) => {
@@ -2555,6 +3428,8 @@ fn build_file_tree(
                 renamed_from: None,
                 size: None,
                 modified_at: None,
+                is_lfs_pointer: false,
+                not_materialized: false,
             },
         );
     }
@@ -2568,6 +3443,7 @@ fn build_file_tree(
 
         let status = file_status.get(file_path).cloned();
         let symlink = symlink_info.get(file_path);
+        let lfs_pointer = unresolved.lfs_pointers.get(file_path);
 
         // If symlink points to a directory, treat it as a directory
         let is_dir_symlink = symlink.is_some_and(|s| s.is_symlink && s.target_is_dir);
@@ -2583,8 +3459,12 @@ fn build_file_tree(
                 is_symlink: symlink.is_some_and(|s| s.is_symlink),
                 symlink_target: symlink.and_then(|s| s.target.clone()),
                 renamed_from: rename_map.get(file_path).cloned(),
-                size: symlink.and_then(|s| s.size),
+                size: lfs_pointer
+                    .map(|pointer| pointer.size)
+                    .or_else(|| symlink.and_then(|s| s.size)),
                 modified_at: symlink.and_then(|s| s.modified_at),
+                is_lfs_pointer: lfs_pointer.is_some(),
+                not_materialized: unresolved.not_materialized.contains(file_path),
             },
         );
     }
@@ -2651,7 +3531,7 @@ impl DiffSource for LocalGitSource {
         end_line: u32,
     ) -> Result<Vec<String>, Self::Error> {
         // Get file content at the specified ref
-        let ref_spec = format!("{git_ref}:{file_path}");
+        let ref_spec = format!("{}:{file_path}", self.resolve_ref_or_self(git_ref));
         let output = self.run_git(&["show", &ref_spec])?;
 
         // Extract the requested lines (1-indexed)
@@ -2671,6 +3551,7 @@ impl DiffSource for LocalGitSource {
             rename_map,
             all_files,
             root,
+            unresolved,
         } = self.working_tree_files(comparison)?;
 
         Ok(build_file_tree(
@@ -2679,6 +3560,7 @@ impl DiffSource for LocalGitSource {
             &HashSet::new(),
             Some(&root),
             &rename_map,
+            &unresolved,
         ))
     }
 
@@ -2687,56 +3569,76 @@ impl DiffSource for LocalGitSource {
         comparison: &Comparison,
         file_path: Option<&str>,
     ) -> Result<String, Self::Error> {
-        let mut all_diffs = String::new();
-        let merge_base = self.diff_base_ref(comparison);
+        Ok(self.get_decoded_diff(comparison, file_path)?.text)
+    }
+}
 
-        if let Some(dir) = self.working_tree_dir(comparison) {
-            // Net diff: merge_base vs working tree (single diff avoids phantom hunks
-            // when working tree changes revert committed changes)
-            let mut args = vec![
-                "diff",
-                "--histogram",
-                "--no-renames",
-                "--src-prefix=a/",
-                "--dst-prefix=b/",
-                &merge_base,
-            ];
-            if let Some(path) = file_path {
-                args.push("--");
-                args.push(path);
-            }
-            if let Ok(output) = self.run_git_in(&dir, &args) {
-                all_diffs.push_str(&output);
-            }
-        } else {
-            // Committed diff between base and head refs
-            let resolved_head = self.resolve_ref_or_empty_tree(&comparison.head);
-            let range = format!("{merge_base}..{resolved_head}");
-            let mut args = vec![
-                "diff",
-                "--histogram",
-                "--no-renames",
-                "--src-prefix=a/",
-                "--dst-prefix=b/",
-                &range,
-            ];
-            if let Some(path) = file_path {
-                args.push("--");
-                args.push(path);
-            }
-            if let Ok(output) = self.run_git(&args) {
-                all_diffs.push_str(&output);
-            }
-        }
+/// `git diff` and its options for diffs parsed into hunks, which must agree
+/// wherever hunk IDs are compared (review, staging).
+fn hunk_diff_args() -> Vec<String> {
+    let mut args: Vec<String> = [
+        "diff",
+        "--histogram",
+        "--no-renames",
+        "--src-prefix=a/",
+        "--dst-prefix=b/",
+    ]
+    .map(str::to_owned)
+    .to_vec();
+    args.extend(crate::diff::parser::inter_hunk_context_arg());
+    args
+}
 
-        Ok(all_diffs)
-    }
+/// A `git diff` read under a byte budget by
+/// [`LocalGitSource::get_decoded_diff_within`].
+#[derive(Debug, Default)]
+pub struct BudgetedDiff {
+    /// The file sections that fit, decoded.
+    pub diff: DecodedDiff,
+    /// Sections over the per-file budget, as (path, bytes).
+    pub oversized: Vec<(String, usize)>,
+    /// Sections that fit on their own but not in what was left of the overall
+    /// budget, as (path, bytes).
+    pub over_budget: Vec<(String, usize)>,
+    /// Size of the whole diff in bytes, skipped sections included.
+    pub total_bytes: usize,
 }
 
-/// Run a git command in the given directory, returning stdout or a `LocalGitError`.
-fn run_git_cmd(dir: &std::path::Path, args: &[&str]) -> Result<String, LocalGitError> {
-    let output = Command::new("git").args(args).current_dir(dir).output()?;
+/// A file section of a diff being read, with its bytes while they're within
+/// budget.
+#[derive(Default)]
+struct DiffSection {
+    header: String,
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl BudgetedDiff {
+    /// Keep a fully read section's bytes if it fits, or record it as skipped.
+    fn finish(
+        &mut self,
+        section: &DiffSection,
+        kept: &mut Vec<u8>,
+        max_file_bytes: usize,
+        max_diff_bytes: usize,
+    ) {
+        if section.len == 0 {
+            return;
+        }
+        self.total_bytes += section.len;
+        let fits = section.len <= max_file_bytes && kept.len() + section.len <= max_diff_bytes;
+        match section_file_path(&section.header) {
+            _ if fits => kept.extend_from_slice(&section.bytes),
+            Some(path) if section.len > max_file_bytes => self.oversized.push((path, section.len)),
+            Some(path) => self.over_budget.push((path, section.len)),
+            // Anything ahead of the first file's header names no file.
+            None => {}
+        }
+    }
+}
 
+/// A finished git command's stdout, or its stderr as a `LocalGitError`.
+fn git_stdout(output: &std::process::Output) -> Result<String, LocalGitError> {
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
@@ -2746,6 +3648,94 @@ fn run_git_cmd(dir: &std::path::Path, args: &[&str]) -> Result<String, LocalGitE
     }
 }
 
+/// Parse `git apply --check --verbose [--3way]` output into the files it
+/// checked, a conflict per file it refused (hunk and 3-way outcome unset), and
+/// each file a 3-way merge would apply.
+fn parse_apply_check(
+    stderr: &str,
+) -> (
+    Vec<String>,
+    Vec<PatchConflict>,
+    HashMap<String, ThreeWayOutcome>,
+) {
+    let mut files: Vec<String> = Vec::new();
+    let mut conflicts: Vec<PatchConflict> = Vec::new();
+    let mut merged = HashMap::new();
+    let mut failed_line = None;
+    for line in stderr.lines() {
+        if let Some(file) = line
+            .strip_prefix("Checking patch ")
+            .and_then(|rest| rest.strip_suffix("..."))
+        {
+            // Renames read "old => new"; the new path is the one to report.
+            let file = file.rsplit(" => ").next().unwrap_or(file);
+            files.push(file.to_owned());
+            failed_line = None;
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix("Applied patch to '") {
+            if let Some((file, outcome)) = rest.split_once("' ") {
+                let outcome = if outcome.starts_with("cleanly") {
+                    ThreeWayOutcome::Clean
+                } else {
+                    ThreeWayOutcome::Conflicted
+                };
+                merged.insert(file.to_owned(), outcome);
+            }
+            continue;
+        }
+        let Some(error) = line.strip_prefix("error: ") else {
+            continue;
+        };
+        let Some(file) = files.last() else {
+            continue;
+        };
+        if let Some(location) = error.strip_prefix("patch failed: ") {
+            failed_line = location
+                .rsplit_once(':')
+                .and_then(|(_, line)| line.parse().ok());
+            continue;
+        }
+        let Some(message) = error.strip_prefix(&format!("{file}: ")) else {
+            continue;
+        };
+        if conflicts.last().is_some_and(|c| &c.file_path == file) {
+            continue;
+        }
+        let reason = match message {
+            "patch does not apply" => PatchConflictReason::ContextMismatch,
+            "already exists in working directory" | "already exists in index" => {
+                PatchConflictReason::AlreadyExists
+            }
+            "No such file or directory" | "does not exist in index" => {
+                PatchConflictReason::MissingFile
+            }
+            _ => PatchConflictReason::Other,
+        };
+        conflicts.push(PatchConflict {
+            file_path: file.clone(),
+            hunk: None,
+            old_line: failed_line.take(),
+            reason,
+            message: message.to_owned(),
+            three_way: None,
+        });
+    }
+    (files, conflicts, merged)
+}
+
+/// The commits an ignore-revs file lists: full SHAs, one per line, `#`
+/// comments allowed. Anything else is skipped — `git blame --ignore-revs-file`
+/// rejects the whole file over one abbreviated or mistyped entry.
+pub fn parse_ignore_revs(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|rev| matches!(rev.len(), 40 | 64) && rev.bytes().all(|b| b.is_ascii_hexdigit()))
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
+
 /// Parse a git remote URL into a `RemoteInfo` with org/repo name and browse URL.
 ///
 /// Supported formats:
@@ -2779,6 +3769,12 @@ fn parse_remote_url(url: &str) -> Result<RemoteInfo, LocalGitError> {
 
         // Split into host and path
         if let Some((host, path)) = without_user.split_once('/') {
+            // An SSH port (ssh://git@host:2222/...) isn't the web server's
+            let host = if url.starts_with("ssh://") {
+                host.split_once(':').map_or(host, |(host, _port)| host)
+            } else {
+                host
+            };
             let path = path.strip_suffix(".git").unwrap_or(path);
             // Ensure we have at least org/repo (two path segments)
             if path.contains('/') {
@@ -2870,17 +3866,20 @@ fn split_diff_into_sections(raw_diff: &str) -> (String, Vec<String>) {
 
 /// Build a selective patch containing only hunks that match the given content hashes.
 ///
-/// Uses the existing `parse_diff()` parser to compute content hashes for each
-/// hunk, then pairs them by order with the raw diff sections.
+/// Uses the existing `parse_raw_diff()` parser to compute content hashes for each
+/// hunk, then pairs them by order with the raw diff sections. A hash naming a
+/// piece of a split hunk (see [`split_hunk`]) selects just that piece's lines;
+/// `reverse` says the patch will be applied with `--reverse`.
 fn build_selective_patch(
     raw_diff: &str,
     file_path: &str,
     content_hashes: &[String],
+    reverse: bool,
 ) -> Result<String, LocalGitError> {
     let hash_set: HashSet<&str> = content_hashes.iter().map(|s| s.as_str()).collect();
 
     // Parse the diff to get content hashes per hunk
-    let parsed_hunks = parse_diff(raw_diff, file_path);
+    let parsed_hunks = parse_raw_diff(raw_diff, file_path);
 
     // Split the raw diff into header + raw sections
     let (header, raw_sections) = split_diff_into_sections(raw_diff);
@@ -2897,9 +3896,27 @@ fn build_selective_patch(
     let mut patch = header;
     let initial_len = patch.len();
 
+    let max_changed = split_threshold();
     for (parsed, raw_section) in parsed_hunks.iter().zip(&raw_sections) {
         if hash_set.contains(parsed.content_hash.as_str()) {
             patch.push_str(raw_section);
+            continue;
+        }
+        let pieces = split_hunk(parsed.clone(), max_changed);
+        if pieces.len() < 2 {
+            continue;
+        }
+        let mut selected = vec![false; parsed.lines.len()];
+        let mut from = 0;
+        for piece in &pieces {
+            let to = from + piece.lines.len();
+            if hash_set.contains(piece.content_hash.as_str()) {
+                selected[from..to].fill(true);
+            }
+            from = to;
+        }
+        if selected.contains(&true) {
+            patch.push_str(&partial_hunk_patch(parsed, &selected, reverse));
         }
     }
 
@@ -2912,9 +3929,75 @@ fn build_selective_patch(
     Ok(patch)
 }
 
+/// A hunk's patch with only its `selected` lines' changes: the rest of its
+/// changes are dropped as if never made — on the side the patch is applied
+/// to, which is the new one when `reverse`.
+fn partial_hunk_patch(hunk: &DiffHunk, selected: &[bool], reverse: bool) -> String {
+    let mut body = String::new();
+    let (mut old_count, mut new_count) = (0, 0);
+    for (line, &keep) in hunk.lines.iter().zip(selected) {
+        let marker = match (&line.line_type, keep, reverse) {
+            (LineType::Context, ..)
+            | (LineType::Removed, false, false)
+            | (LineType::Added, false, true) => ' ',
+            (LineType::Added, true, _) => '+',
+            (LineType::Removed, true, _) => '-',
+            (LineType::Added, false, false) | (LineType::Removed, false, true) => continue,
+        };
+        if marker != '+' {
+            old_count += 1;
+        }
+        if marker != '-' {
+            new_count += 1;
+        }
+        body.push(marker);
+        body.push_str(&line.content);
+        body.push('\n');
+    }
+    format!(
+        "@@ -{},{old_count} +{},{new_count} @@\n{body}",
+        hunk.old_start, hunk.new_start
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    /// Run a git command in the given directory, returning stdout or a `LocalGitError`.
+    /// Run git in `dir`. A repo it inits gets a local identity, so commits
+    /// don't depend on the machine's global git config.
+    fn run_git_cmd(dir: &std::path::Path, args: &[&str]) -> Result<String, LocalGitError> {
+        let output = git_stdout(&Command::new("git").args(args).current_dir(dir).output()?)?;
+        if args.first() == Some(&"init") {
+            for (key, value) in [("user.name", "t"), ("user.email", "t@example.com")] {
+                git_stdout(
+                    &Command::new("git")
+                        .args(["config", key, value])
+                        .current_dir(dir)
+                        .output()?,
+                )?;
+            }
+        }
+        Ok(output)
+    }
+
+    #[test]
+    fn parse_remote_url_finds_the_web_host() {
+        let browse = |url| parse_remote_url(url).unwrap().browse_url;
+        assert_eq!(
+            browse("git@ghe.example.com:org/repo.git"),
+            "https://ghe.example.com/org/repo"
+        );
+        assert_eq!(
+            browse("ssh://git@ghe.example.com:2222/org/repo.git"),
+            "https://ghe.example.com/org/repo"
+        );
+        assert_eq!(
+            browse("https://ghe.example.com:8443/org/repo"),
+            "https://ghe.example.com:8443/org/repo"
+        );
+    }
     use crate::review::central::tests::{setup_test, EnvGuard};
 
     /// Set up a temp git repo with REVIEW_HOME for worktree tests.
@@ -3312,6 +4395,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_ignore_revs_keeps_full_shas() {
+        let sha = "a".repeat(40);
+        let content = format!(
+            "# Reformat\n{sha} # rustfmt\n\nabc123\n{}\n",
+            "B".repeat(64)
+        );
+        assert_eq!(parse_ignore_revs(&content), vec![sha, "b".repeat(64)]);
+    }
+
+    /// A commit in `.git-blame-ignore-revs` is looked through: the lines it
+    /// only reformatted go to the commit that wrote them.
+    #[test]
+    fn test_attribution_looks_through_ignored_revs() {
+        use crate::review::central::tests::ENV_LOCK;
+        use crate::sources::traits::Comparison;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _review_home, repo_dir, _source, base_sha) = setup_worktree_test();
+        let repo_path = repo_dir.path();
+        let head = |message: &str| {
+            run_git_cmd(repo_path, &["add", "-A"]).unwrap();
+            run_git_cmd(repo_path, &["commit", "-m", message]).unwrap();
+            run_git_cmd(repo_path, &["rev-parse", "HEAD"])
+                .unwrap()
+                .trim()
+                .to_owned()
+        };
+
+        std::fs::write(repo_path.join("file.rs"), "fn a() {\n    let v=1;\n}\n").unwrap();
+        let feature_sha = head("feature");
+        std::fs::write(repo_path.join("file.rs"), "fn a() {\n    let v = 1;\n}\n").unwrap();
+        let format_sha = head("format");
+        std::fs::write(
+            repo_path.join(BLAME_IGNORE_REVS_FILE),
+            format!("{format_sha}\n"),
+        )
+        .unwrap();
+
+        let source = LocalGitSource::new(repo_path.to_path_buf()).unwrap();
+        assert_eq!(source.blame_ignore_revs(), vec![format_sha.clone()]);
+        let comparison = Comparison::new(&base_sha, &format_sha);
+        let attribution = source.attribute_hunks_to_commits(&comparison).unwrap();
+        let shas = attribution
+            .hunk_commits
+            .iter()
+            .find(|(id, _)| id.starts_with("file.rs:"))
+            .map(|(_, shas)| shas.clone())
+            .unwrap();
+        assert_eq!(shas, vec![feature_sha]);
+    }
+
     /// When the comparison head is checked out (so hunks are diffed against
     /// the working tree), blame must follow suit — an uncommitted edit that
     /// shifts line numbers must not misalign attribution against a
@@ -3375,4 +4510,383 @@ mod tests {
             "expected middle-line commit to be attributed despite the uncommitted line shift: {shas:?}"
         );
     }
+
+    /// A preview names the hunk that fails, why each file is refused, and
+    /// whether a 3-way merge would get it in — without touching the tree.
+    #[test]
+    fn apply_patch_preview_reports_conflicts_per_file() {
+        use crate::review::central::tests::ENV_LOCK;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _review_home, repo_dir, source, _head_sha) = setup_worktree_test();
+        let repo_path = repo_dir.path();
+        let original = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        std::fs::write(repo_path.join("x.txt"), original).unwrap();
+        run_git_cmd(repo_path, &["add", "."]).unwrap();
+        run_git_cmd(repo_path, &["commit", "-m", "x"]).unwrap();
+
+        std::fs::write(
+            repo_path.join("x.txt"),
+            original.replace("b\n", "B\n").replace("j\n", "J\n"),
+        )
+        .unwrap();
+        std::fs::write(repo_path.join("z.txt"), "new\n").unwrap();
+        run_git_cmd(repo_path, &["add", "-N", "z.txt"]).unwrap();
+        let patch = run_git_cmd(repo_path, &["diff"]).unwrap();
+        run_git_cmd(repo_path, &["checkout", "x.txt"]).unwrap();
+        run_git_cmd(repo_path, &["reset", "-q"]).unwrap();
+        std::fs::remove_file(repo_path.join("z.txt")).unwrap();
+        assert!(
+            source
+                .apply_patch_preview(patch.as_bytes())
+                .unwrap()
+                .applies
+        );
+
+        // The second hunk's context moves, and z.txt appears untracked.
+        std::fs::write(repo_path.join("x.txt"), original.replace("j\n", "JJ\n")).unwrap();
+        run_git_cmd(repo_path, &["commit", "-am", "conflict"]).unwrap();
+        std::fs::write(repo_path.join("z.txt"), "z\n").unwrap();
+        let preview = source.apply_patch_preview(patch.as_bytes()).unwrap();
+        assert!(!preview.applies);
+        assert_eq!(preview.files, ["x.txt", "z.txt"]);
+        assert_eq!(
+            preview.conflicts[0],
+            PatchConflict {
+                file_path: "x.txt".to_owned(),
+                hunk: Some(2),
+                old_line: Some(7),
+                reason: PatchConflictReason::ContextMismatch,
+                message: "patch does not apply".to_owned(),
+                three_way: Some(ThreeWayOutcome::Conflicted),
+            }
+        );
+        assert_eq!(
+            (preview.conflicts[1].reason, preview.conflicts[1].three_way),
+            (
+                PatchConflictReason::AlreadyExists,
+                Some(ThreeWayOutcome::Failed)
+            )
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo_path.join("x.txt")).unwrap(),
+            original.replace("j\n", "JJ\n")
+        );
+
+        assert!(source.apply_patch_preview(b"not a patch").is_err());
+    }
+
+    /// Staging hunks across files is all or nothing.
+    #[test]
+    fn stage_hunk_ids_stages_across_files_or_not_at_all() {
+        use crate::review::central::tests::ENV_LOCK;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _review_home, repo_dir, source, _head_sha) = setup_worktree_test();
+        let repo_path = repo_dir.path();
+        std::fs::write(repo_path.join("a.txt"), "a\n").unwrap();
+        std::fs::write(repo_path.join("b.txt"), "b\n").unwrap();
+        run_git_cmd(repo_path, &["add", "."]).unwrap();
+        run_git_cmd(repo_path, &["commit", "-m", "files"]).unwrap();
+        std::fs::write(repo_path.join("a.txt"), "A\n").unwrap();
+        std::fs::write(repo_path.join("b.txt"), "B\n").unwrap();
+
+        let hunk_id = |file: &str| {
+            let diff = source.get_raw_file_diff(file, false).unwrap();
+            parse_diff(&diff, file)[0].id.clone()
+        };
+        let ids = vec![hunk_id("a.txt"), hunk_id("b.txt")];
+        let staged = || run_git_cmd(repo_path, &["diff", "--cached", "--name-only"]).unwrap();
+
+        let stale = vec![ids[0].clone(), "b.txt:0000000000000000".to_owned()];
+        assert!(source.stage_hunk_ids(&stale).is_err());
+        assert_eq!(staged(), "");
+
+        source.stage_hunk_ids(&ids).unwrap();
+        assert_eq!(staged(), "a.txt\nb.txt\n");
+    }
+
+    /// A jj-native workspace (no `.git`) is diffed through the git store
+    /// inside `.jj`, and reviews `@` rather than a branch.
+    #[test]
+    fn jj_native_workspace_diffs_through_its_git_store() {
+        let scratch = tempfile::TempDir::new().unwrap();
+        let scratch = scratch.path();
+        run_git_cmd(scratch, &["init", "-q", "-b", "main"]).unwrap();
+        run_git_cmd(scratch, &["config", "user.name", "Me"]).unwrap();
+        run_git_cmd(scratch, &["config", "user.email", "me@example.com"]).unwrap();
+        std::fs::write(scratch.join("a.txt"), "one\n").unwrap();
+        run_git_cmd(scratch, &["add", "."]).unwrap();
+        run_git_cmd(scratch, &["commit", "-qm", "base"]).unwrap();
+        run_git_cmd(scratch, &["checkout", "-qb", "feat"]).unwrap();
+        std::fs::write(scratch.join("a.txt"), "one\ntwo\n").unwrap();
+        run_git_cmd(scratch, &["commit", "-qam", "feat"]).unwrap();
+
+        let workspace = tempfile::TempDir::new().unwrap();
+        let root = workspace.path();
+        let store = root.join(".jj/repo/store");
+        std::fs::create_dir_all(&store).unwrap();
+        std::fs::write(store.join("git_target"), "git").unwrap();
+        let bare = store.join("git");
+        run_git_cmd(
+            scratch,
+            &["clone", "-q", "--bare", ".", bare.to_str().unwrap()],
+        )
+        .unwrap();
+
+        let source = LocalGitSource::new(root.to_path_buf()).unwrap();
+        assert!(source.is_jj());
+        assert_eq!(source.get_current_branch().unwrap(), "@");
+        assert!(source.is_branch("feat"));
+        let comparison = Comparison::new("main", "feat");
+        assert!(!source.diffs_uncommitted(&comparison));
+        let diff = source.get_diff(&comparison, None).unwrap();
+        assert!(diff.contains("+two"));
+        let lines = source.get_file_lines("a.txt", "feat", 2, 2).unwrap();
+        assert_eq!(lines, ["two"]);
+    }
+
+    /// A function-context diff spans the whole function, and names the
+    /// plain hunk it widens.
+    #[test]
+    fn function_context_diff_spans_the_enclosing_function() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = dir.path();
+        run_git_cmd(repo, &["init", "-q", "-b", "main"]).unwrap();
+        run_git_cmd(repo, &["config", "user.name", "Me"]).unwrap();
+        run_git_cmd(repo, &["config", "user.email", "me@example.com"]).unwrap();
+        let body = (0..12).fold(String::new(), |body, i| {
+            body + &format!("    let x{i} = {i};\n")
+        });
+        let source_text = format!("fn first() {{\n{body}}}\n\nfn second() {{\n{body}}}\n");
+        std::fs::write(repo.join("lib.rs"), &source_text).unwrap();
+        run_git_cmd(repo, &["add", "."]).unwrap();
+        run_git_cmd(repo, &["commit", "-qm", "base"]).unwrap();
+        std::fs::write(
+            repo.join("lib.rs"),
+            source_text.replacen("x6 = 6", "x6 = 60", 1),
+        )
+        .unwrap();
+
+        let source = LocalGitSource::new(repo.to_path_buf()).unwrap();
+        let comparison = Comparison::new("HEAD", Comparison::WORKTREE);
+        let plain = parse_diff(&source.get_diff(&comparison, None).unwrap(), "lib.rs");
+        let wide = crate::diff::parser::parse_wide_diff(
+            &source
+                .get_function_context_diff(&comparison, "lib.rs")
+                .unwrap(),
+            "lib.rs",
+        );
+        assert_eq!((plain.len(), wide.len()), (1, 1));
+        assert_eq!(wide[0].lines[0].content, "fn first() {");
+        assert!(wide[0].lines.iter().all(|l| !l.content.contains("second")));
+        assert_eq!(wide[0].merged_ids, [plain[0].id.clone()]);
+    }
+
+    /// A piece of a split hunk stages and unstages on its own.
+    #[test]
+    fn stages_and_unstages_one_piece_of_a_split_hunk() {
+        use crate::review::central::tests::ENV_LOCK;
+        use std::fmt::Write as _;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _review_home, repo_dir, source, _head_sha) = setup_worktree_test();
+        let repo_path = repo_dir.path();
+        std::fs::write(repo_path.join("notes.txt"), "top\nbottom\n").unwrap();
+        run_git_cmd(repo_path, &["add", "."]).unwrap();
+        run_git_cmd(repo_path, &["commit", "-m", "notes"]).unwrap();
+        let paragraphs: Vec<String> = (0..3)
+            .map(|p| {
+                (0..150).fold(String::new(), |mut text, i| {
+                    let _ = writeln!(text, "p{p} line {i}");
+                    text
+                })
+            })
+            .collect();
+        let content = format!("top\n{}bottom\n", paragraphs.join("\n"));
+        std::fs::write(repo_path.join("notes.txt"), &content).unwrap();
+
+        let diff = source.get_raw_file_diff("notes.txt", false).unwrap();
+        let pieces = parse_diff(&diff, "notes.txt");
+        assert_eq!(pieces.len(), 3);
+        source
+            .stage_hunk_ids(std::slice::from_ref(&pieces[1].id))
+            .unwrap();
+        let staged = run_git_cmd(repo_path, &["diff", "--cached"]).unwrap();
+        assert!(staged.contains("+p1 line 0\n") && staged.contains("+p1 line 149\n"));
+        assert!(!staged.contains("p0 line") && !staged.contains("p2 line"));
+
+        let staged_hunks = parse_diff(&staged, "notes.txt");
+        source
+            .unstage_hunks(
+                "notes.txt",
+                &staged_hunks
+                    .iter()
+                    .map(|h| h.content_hash.clone())
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+        assert_eq!(run_git_cmd(repo_path, &["diff", "--cached"]).unwrap(), "");
+
+        // Unstaging a piece leaves the rest of the staged hunk in place.
+        run_git_cmd(repo_path, &["add", "notes.txt"]).unwrap();
+        let staged = source.get_raw_file_diff("notes.txt", true).unwrap();
+        let staged_pieces = parse_diff(&staged, "notes.txt");
+        source
+            .unstage_hunks(
+                "notes.txt",
+                std::slice::from_ref(&staged_pieces[2].content_hash),
+            )
+            .unwrap();
+        let staged = run_git_cmd(repo_path, &["diff", "--cached"]).unwrap();
+        assert!(staged.contains("+p0 line 0\n") && staged.contains("+p1 line 0\n"));
+        assert!(!staged.contains("p2 line"));
+    }
+
+    #[test]
+    fn shallow_clone_is_deepened_to_reach_an_older_base_when_allowed() {
+        use crate::review::central::tests::ENV_LOCK;
+        use crate::service::settings::write_settings;
+        use crate::sources::traits::Comparison;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _review_home, origin_dir) = setup_test();
+        let origin = origin_dir.path();
+        run_git_cmd(origin, &["init"]).unwrap();
+        std::fs::write(origin.join("a.txt"), "one\n").unwrap();
+        run_git_cmd(origin, &["add", "a.txt"]).unwrap();
+        run_git_cmd(origin, &["commit", "-m", "one"]).unwrap();
+        let base_sha = run_git_cmd(origin, &["rev-parse", "HEAD"])
+            .unwrap()
+            .trim()
+            .to_owned();
+        for line in ["two\n", "three\n"] {
+            std::fs::write(origin.join("a.txt"), line).unwrap();
+            run_git_cmd(origin, &["commit", "-am", line.trim()]).unwrap();
+        }
+
+        let clone_dir = tempfile::tempdir().unwrap();
+        let url = format!("file://{}", origin.display());
+        let clone_path = clone_dir.path().to_str().unwrap();
+        run_git_cmd(origin, &["clone", "--depth", "1", &url, clone_path]).unwrap();
+        let comparison = Comparison::new(&base_sha, "HEAD");
+
+        let source = LocalGitSource::new(clone_dir.path().to_path_buf()).unwrap();
+        assert!(source.is_shallow());
+        let err = source.get_diff(&comparison, None).unwrap_err();
+        assert_eq!(err.to_string(), format!("SHALLOW_CLONE:{base_sha}"));
+        std::env::set_var(crate::offline::OFFLINE_ENV, "1");
+        let err = source.deepen(DEEPEN_STEP).unwrap_err();
+        assert!(matches!(err, LocalGitError::Offline(_)), "{err}");
+        assert!(source.is_shallow());
+        std::env::remove_var(crate::offline::OFFLINE_ENV);
+
+        write_settings(&serde_json::json!({ "autoDeepenShallowClones": true })).unwrap();
+        let diff = source.get_diff(&comparison, None).unwrap();
+        assert!(
+            diff.contains("-one\n") && diff.contains("+three\n"),
+            "{diff}"
+        );
+        assert!(!source.is_shallow());
+    }
+
+    #[test]
+    fn fetch_origin_is_refused_offline() {
+        use crate::review::central::tests::ENV_LOCK;
+        use crate::sources::traits::Comparison;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _review_home, origin_dir) = setup_test();
+        let origin = origin_dir.path();
+        run_git_cmd(origin, &["init", "-b", "main"]).unwrap();
+        std::fs::write(origin.join("a.txt"), "one\n").unwrap();
+        run_git_cmd(origin, &["add", "a.txt"]).unwrap();
+        run_git_cmd(origin, &["commit", "-m", "one"]).unwrap();
+        let clone_dir = tempfile::tempdir().unwrap();
+        let clone_path = clone_dir.path().to_str().unwrap();
+        run_git_cmd(
+            origin,
+            &["clone", "-q", origin.to_str().unwrap(), clone_path],
+        )
+        .unwrap();
+        std::fs::write(origin.join("a.txt"), "two\n").unwrap();
+        run_git_cmd(origin, &["commit", "-am", "two"]).unwrap();
+
+        let source = LocalGitSource::new(clone_dir.path().to_path_buf()).unwrap();
+        let fetched = |source: &LocalGitSource| {
+            !source
+                .get_diff(&Comparison::new("main", "origin/main"), None)
+                .unwrap()
+                .is_empty()
+        };
+        std::env::set_var(crate::offline::OFFLINE_ENV, "1");
+        let err = source.fetch_origin().unwrap_err();
+        assert!(matches!(err, LocalGitError::Offline(_)), "{err}");
+        assert!(!fetched(&source));
+
+        std::env::remove_var(crate::offline::OFFLINE_ENV);
+        source.fetch_origin().unwrap();
+        assert!(fetched(
+            &LocalGitSource::new(clone_dir.path().to_path_buf()).unwrap()
+        ));
+    }
+
+    #[test]
+    fn fork_resolves_its_base_branch_and_host_from_the_configured_remote() {
+        use crate::review::central::tests::ENV_LOCK;
+
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _review_home, repo_dir) = setup_test();
+        let repo = repo_dir.path();
+        run_git_cmd(repo, &["init", "-b", "main"]).unwrap();
+        std::fs::write(repo.join("a.txt"), "one\n").unwrap();
+        run_git_cmd(repo, &["add", "a.txt"]).unwrap();
+        run_git_cmd(repo, &["commit", "-m", "one"]).unwrap();
+        for (remote, url) in [
+            ("origin", "git@github.com:me/repo.git"),
+            ("upstream", "https://github.com/org/repo.git"),
+        ] {
+            run_git_cmd(repo, &["remote", "add", remote, url]).unwrap();
+        }
+        run_git_cmd(
+            repo,
+            &["update-ref", "refs/remotes/upstream/develop", "HEAD"],
+        )
+        .unwrap();
+        run_git_cmd(
+            repo,
+            &[
+                "symbolic-ref",
+                "refs/remotes/upstream/HEAD",
+                "refs/remotes/upstream/develop",
+            ],
+        )
+        .unwrap();
+
+        let source = LocalGitSource::new(repo.to_path_buf()).unwrap();
+        assert_eq!(source.review_remote().as_deref(), Some("origin"));
+        assert_eq!(source.get_remote_info().unwrap().unwrap().name, "me/repo");
+        assert_eq!(source.get_default_branch().unwrap(), "main");
+        assert!(source.set_review_remote(Some("missing")).is_err());
+
+        source.set_review_remote(Some("upstream")).unwrap();
+        let source = LocalGitSource::new(repo.to_path_buf()).unwrap();
+        assert_eq!(source.review_remote().as_deref(), Some("upstream"));
+        assert_eq!(source.get_remote_info().unwrap().unwrap().name, "org/repo");
+        assert_eq!(source.get_default_branch().unwrap(), "develop");
+        assert!(source.resolve_ref("develop").is_some());
+        let flagged: Vec<(String, bool)> = source
+            .list_remotes()
+            .unwrap()
+            .into_iter()
+            .map(|r| (r.name, r.is_review_remote))
+            .collect();
+        assert_eq!(
+            flagged,
+            [("origin".to_owned(), false), ("upstream".to_owned(), true)]
+        );
+
+        source.set_review_remote(None).unwrap();
+        assert_eq!(source.review_remote().as_deref(), Some("origin"));
+    }
 }
@@ -1,3 +1,11 @@
+pub mod azure;
+pub mod dir;
+pub mod gerrit;
 pub mod github;
+pub mod github_api;
+pub mod jj;
+pub mod lfs;
 pub mod local_git;
+pub mod patch;
+pub mod remote;
 pub mod traits;
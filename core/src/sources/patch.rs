@@ -0,0 +1,158 @@
+//! Patch files — a `.patch` or `.diff` reviewed on its own, with neither side
+//! of the change needed locally.
+//!
+//! [`PatchSource`] reads `git diff` / `git format-patch` output as is, and
+//! plain unified diffs (`diff -u`, `svn diff`) by rewriting their `---`/`+++`
+//! headers into git's, with the leading path component stripped as
+//! `patch -p1` would. Either way the hunks come from the same parser as a
+//! repo review's, so classification, trust, and decisions work on them
+//! unchanged. Reviews of patches are stored under a `patch:<name>` ref.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use crate::diff::parser::{self, DiffHunk};
+
+/// A unified diff to review.
+#[derive(Debug, Clone)]
+pub struct PatchSource {
+    name: String,
+    diff: String,
+}
+
+impl PatchSource {
+    /// Prefix of the refs patch reviews are stored under.
+    pub const REF_PREFIX: &'static str = "patch:";
+
+    /// A patch named `name` (what its review is stored under).
+    pub fn new(name: impl Into<String>, diff: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            diff: diff.into(),
+        }
+    }
+
+    /// The patch at `path`, named after its file name.
+    pub fn from_path(path: &Path) -> std::io::Result<Self> {
+        let diff = std::fs::read_to_string(path)?;
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        Ok(Self::new(name, diff))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The ref the patch's review is stored under. Keyed by name rather than
+    /// content, so a revised patch reopens the same review and decisions on
+    /// unchanged hunks carry over.
+    pub fn ref_name(&self) -> String {
+        format!("{}{}", Self::REF_PREFIX, self.name)
+    }
+
+    /// The patch's hunks, with moved code paired up.
+    pub fn hunks(&self) -> Vec<DiffHunk> {
+        let mut hunks = if self.diff.lines().any(|l| l.starts_with("diff --git ")) {
+            parser::parse_multi_file_diff(&self.diff)
+        } else {
+            parser::parse_multi_file_diff(&to_git_headers(&self.diff))
+        };
+        parser::detect_move_pairs(&mut hunks);
+        hunks
+    }
+
+    /// Files the patch touches, in order.
+    pub fn files(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        self.hunks()
+            .into_iter()
+            .map(|hunk| hunk.file_path)
+            .filter(|path| seen.insert(path.clone()))
+            .collect()
+    }
+}
+
+/// A path from a plain diff's `---`/`+++` header: the timestamp after the tab
+/// dropped, and the first component stripped as `patch -p1` does. `None` for
+/// `/dev/null`.
+fn header_path(header: &str) -> Option<String> {
+    let path = header.split('\t').next().unwrap_or(header).trim();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path.split_once('/').map_or(path, |(_, rest)| rest);
+    Some(path.to_owned())
+}
+
+/// Rewrite a plain unified diff's file headers into git's (`diff --git`,
+/// `--- a/`, `+++ b/`) so [`parser::parse_multi_file_diff`] splits it per
+/// file. A `---` line only counts as a header when a `+++` line and a hunk
+/// header follow it.
+fn to_git_headers(diff: &str) -> String {
+    let lines: Vec<&str> = diff.lines().collect();
+    let mut out = String::with_capacity(diff.len());
+    let mut i = 0;
+    while i < lines.len() {
+        let header = lines[i]
+            .strip_prefix("--- ")
+            .zip(lines.get(i + 1).and_then(|next| next.strip_prefix("+++ ")));
+        let hunk_follows = lines.get(i + 2).is_some_and(|l| l.starts_with("@@"));
+        if let (Some((old, new)), true) = (header, hunk_follows) {
+            let (old, new) = (header_path(old), header_path(new));
+            if let Some(path) = new.as_ref().or(old.as_ref()) {
+                let _ = writeln!(out, "diff --git a/{path} b/{path}");
+                let _ = match &old {
+                    Some(old) => writeln!(out, "--- a/{old}"),
+                    None => writeln!(out, "--- /dev/null"),
+                };
+                let _ = match &new {
+                    Some(new) => writeln!(out, "+++ b/{new}"),
+                    None => writeln!(out, "+++ /dev/null"),
+                };
+                i += 2;
+                continue;
+            }
+        }
+        out.push_str(lines[i]);
+        out.push('\n');
+        i += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::LineType;
+
+    #[test]
+    fn reads_git_and_plain_unified_diffs() {
+        let git = "From abc Mon Sep 17 00:00:00 2001\nSubject: [PATCH] x\n\n---\n\
+            diff --git a/src/a.rs b/src/a.rs\nindex 1..2 100644\n--- a/src/a.rs\n+++ b/src/a.rs\n\
+            @@ -1,2 +1,2 @@\n fn a() {}\n-fn b() {}\n+fn c() {}\n";
+        let hunks = PatchSource::new("x.patch", git).hunks();
+        assert_eq!(hunks.len(), 1);
+        assert_eq!(hunks[0].file_path, "src/a.rs");
+
+        let plain =
+            "--- old/src/a.rs\t2024-01-01 00:00:00\n+++ new/src/a.rs\t2024-01-02 00:00:00\n\
+            @@ -1,2 +1,2 @@\n fn a() {}\n-- removed line\n++ added line\n\
+            --- /dev/null\n+++ new/notes.txt\n@@ -0,0 +1 @@\n+hello\n";
+        let source = PatchSource::new("changes.diff", plain);
+        assert_eq!(source.ref_name(), "patch:changes.diff");
+        assert_eq!(source.files(), ["src/a.rs", "notes.txt"]);
+        let hunks = source.hunks();
+        let changed: Vec<_> = hunks[0]
+            .lines
+            .iter()
+            .filter(|l| l.line_type != LineType::Context)
+            .map(|l| l.content.as_str())
+            .collect();
+        assert_eq!(changed, ["- removed line", "+ added line"]);
+        assert_eq!(hunks[1].new_count, 1);
+    }
+}
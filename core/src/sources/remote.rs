@@ -0,0 +1,232 @@
+//! Reviewing a repository that isn't checked out locally.
+//!
+//! [`RemoteGitSource`] keeps a clone of the remote under
+//! `~/.review/remotes/<url-id>/`, made on first use and fetched into after.
+//! The clone is partial (`--filter=blob:none`) and has no checkout: all
+//! commits and trees come down, but file contents are fetched only as a diff
+//! needs them, so even a large repo is quick to open. The refs asked for are
+//! fetched as local branches of the clone, which makes them an ordinary
+//! [`Comparison`] there — reviews are stored against the clone like any
+//! other repo's, and every command that takes `--repo` works on it.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use super::traits::Comparison;
+use crate::review::central::{self, CentralError};
+
+/// Directory under the central root clones are kept in.
+pub const REMOTES_DIR: &str = "remotes";
+
+#[derive(Error, Debug)]
+pub enum RemoteError {
+    #[error("Git error: {0}")]
+    Git(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Central(#[from] CentralError),
+    #[error("{0}")]
+    Offline(String),
+}
+
+/// 16-hex id of a remote URL, naming its clone's directory.
+pub fn url_id(url: &str) -> String {
+    let digest = Sha256::digest(url.trim_end_matches('/').as_bytes());
+    hex::encode(&digest[..8])
+}
+
+/// Whether `git_ref` is a full commit SHA (SHA-1 or SHA-256), fetched as is
+/// rather than into a branch.
+fn is_full_sha(git_ref: &str) -> bool {
+    matches!(git_ref.len(), 40 | 64) && git_ref.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Where a fetched ref is kept in the clone: full refs under their own name,
+/// anything else as a branch.
+fn local_ref(git_ref: &str) -> String {
+    if git_ref.starts_with("refs/") {
+        git_ref.to_owned()
+    } else {
+        format!("refs/heads/{git_ref}")
+    }
+}
+
+/// A remote repository, reviewed through a cached partial clone.
+pub struct RemoteGitSource {
+    url: String,
+    clone_dir: PathBuf,
+}
+
+impl RemoteGitSource {
+    /// The source for `url`, its clone kept under the central root (it isn't
+    /// made until [`Self::fetch_comparison`]).
+    pub fn new(url: &str) -> Result<Self, RemoteError> {
+        let clone_dir = central::get_central_root()?
+            .join(REMOTES_DIR)
+            .join(url_id(url));
+        Ok(Self {
+            url: url.to_owned(),
+            clone_dir,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The clone's directory: the repo path reviews of this remote use.
+    pub fn clone_dir(&self) -> &Path {
+        &self.clone_dir
+    }
+
+    /// Whether the clone has been made.
+    pub fn is_cloned(&self) -> bool {
+        self.clone_dir.join(".git").exists()
+    }
+
+    fn ensure_online() -> Result<(), RemoteError> {
+        crate::offline::ensure_online("Remote review")
+            .map_err(|e| RemoteError::Offline(e.to_string()))
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> Result<String, RemoteError> {
+        let output = Command::new("git").args(args).current_dir(dir).output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(RemoteError::Git(stderr.trim().to_owned()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// Make the clone if there isn't one yet.
+    fn ensure_clone(&self) -> Result<(), RemoteError> {
+        if self.is_cloned() {
+            return Ok(());
+        }
+        let parent = self.clone_dir.parent().unwrap_or(&self.clone_dir);
+        std::fs::create_dir_all(parent)?;
+        // A clone interrupted earlier leaves a directory git won't clone into.
+        if self.clone_dir.exists() {
+            std::fs::remove_dir_all(&self.clone_dir)?;
+        }
+        let dir = self.clone_dir.to_string_lossy();
+        Self::git(
+            parent,
+            &[
+                "clone",
+                "--quiet",
+                "--filter=blob:none",
+                "--no-checkout",
+                "--no-tags",
+                &self.url,
+                &dir,
+            ],
+        )?;
+        // With nothing checked out, a branch HEAD points at would be diffed
+        // against an empty working tree; detached, every branch is committed
+        // only. An empty remote has no HEAD to detach.
+        if let Ok(sha) = Self::git(&self.clone_dir, &["rev-parse", "--verify", "HEAD"]) {
+            Self::git(
+                &self.clone_dir,
+                &["update-ref", "--no-deref", "HEAD", sha.trim()],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Fetch `git_ref` into the clone, returning what to name it by there.
+    fn fetch_ref(&self, git_ref: &str) -> Result<String, RemoteError> {
+        let fetch = [
+            "fetch",
+            "--quiet",
+            "--no-tags",
+            "--filter=blob:none",
+            "--update-head-ok",
+            "origin",
+        ];
+        if is_full_sha(git_ref) {
+            let mut args = fetch.to_vec();
+            args.push(git_ref);
+            Self::git(&self.clone_dir, &args)?;
+            return Ok(git_ref.to_owned());
+        }
+        let refspec = format!("+{git_ref}:{}", local_ref(git_ref));
+        let mut args = fetch.to_vec();
+        args.push(&refspec);
+        Self::git(&self.clone_dir, &args)?;
+        Ok(git_ref
+            .strip_prefix("refs/heads/")
+            .unwrap_or(git_ref)
+            .to_owned())
+    }
+
+    /// Clone the remote if needed and fetch `base` and `head` (branch names,
+    /// full refs, or full SHAs), returning the comparison between them in the
+    /// clone. Refetching moves the branches to where the remote has them now.
+    pub fn fetch_comparison(&self, base: &str, head: &str) -> Result<Comparison, RemoteError> {
+        Self::ensure_online()?;
+        self.ensure_clone()?;
+        let base = self.fetch_ref(base)?;
+        let head = self.fetch_ref(head)?;
+        Ok(Comparison::new(base, head))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::ENV_LOCK;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        RemoteGitSource::git(dir, args).unwrap().trim().to_owned()
+    }
+
+    #[test]
+    fn clones_once_and_fetches_refs_as_a_comparison() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        std::env::remove_var(crate::offline::OFFLINE_ENV);
+        let home = tempfile::TempDir::new().unwrap();
+        std::env::set_var("REVIEW_HOME", home.path());
+        let server = tempfile::TempDir::new().unwrap();
+        let server = server.path();
+        git(server, &["init", "-q", "-b", "main"]);
+        git(server, &["config", "user.email", "t@example.com"]);
+        git(server, &["config", "user.name", "T"]);
+        std::fs::write(server.join("a.txt"), "one\n").unwrap();
+        git(server, &["add", "."]);
+        git(server, &["commit", "-qm", "base"]);
+        git(server, &["checkout", "-q", "-b", "feature"]);
+        std::fs::write(server.join("a.txt"), "one\ntwo\n").unwrap();
+        git(server, &["commit", "-qam", "two"]);
+
+        let url = format!("file://{}", server.display());
+        let source = RemoteGitSource::new(&url).unwrap();
+        assert!(source
+            .clone_dir()
+            .starts_with(home.path().join(REMOTES_DIR)));
+        assert!(!source.is_cloned());
+        let comparison = source.fetch_comparison("main", "feature").unwrap();
+        assert_eq!(comparison.key, "main..feature");
+        assert!(source.is_cloned());
+        // The server has `feature` checked out; the clone must not, or it'd be
+        // diffed against the clone's empty working tree.
+        assert!(RemoteGitSource::git(source.clone_dir(), &["symbolic-ref", "-q", "HEAD"]).is_err());
+        let diff = git(source.clone_dir(), &["diff", "main", "feature"]);
+        assert!(diff.contains("+two"));
+
+        // A later fetch updates the branch in the existing clone.
+        std::fs::write(server.join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        git(server, &["commit", "-qam", "three"]);
+        let sha = git(server, &["rev-parse", "HEAD"]);
+        source.fetch_comparison("main", "feature").unwrap();
+        assert_eq!(git(source.clone_dir(), &["rev-parse", "feature"]), sha);
+        let pinned = source.fetch_comparison("main", &sha).unwrap();
+        assert_eq!(pinned.head, sha);
+
+        std::env::remove_var("REVIEW_HOME");
+    }
+}
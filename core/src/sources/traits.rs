@@ -51,14 +51,45 @@ pub enum ChangeStatus {
 }
 
 /// A comparison specification: base..head diff.
+///
+/// Each side is an [`Endpoint`]: a ref, or one of the [`Comparison::INDEX`] /
+/// [`Comparison::WORKTREE`] tokens, which git forbids in ref names so they
+/// can't collide with a branch.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Comparison {
-    pub base: String, // Base ref (e.g., "main"), or "" for empty tree (snapshots)
-    pub head: String, // Head ref (e.g., "feature")
+    pub base: String, // Base ref (e.g., "main"), "" for empty tree (snapshots), or ":index"
+    pub head: String, // Head ref (e.g., "feature"), ":index", or ":worktree"
     pub key: String,  // Always "{base}..{head}"
 }
 
+/// One side of a [`Comparison`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint<'a> {
+    /// A ref or SHA (`""` is the empty tree). As a head, a branch that is
+    /// checked out is diffed with its uncommitted changes.
+    Ref(&'a str),
+    /// The index: what the next commit would contain.
+    Index,
+    /// The repo's working tree, untracked files included. Only valid as a head.
+    Worktree,
+}
+
+impl<'a> Endpoint<'a> {
+    pub fn parse(side: &'a str) -> Self {
+        match side {
+            Comparison::INDEX => Self::Index,
+            Comparison::WORKTREE => Self::Worktree,
+            git_ref => Self::Ref(git_ref),
+        }
+    }
+}
+
 impl Comparison {
+    /// Endpoint token for the index.
+    pub const INDEX: &'static str = ":index";
+    /// Endpoint token for the working tree.
+    pub const WORKTREE: &'static str = ":worktree";
+
     /// Create a new comparison, deriving the key from base and head.
     pub fn new(base: impl Into<String>, head: impl Into<String>) -> Self {
         let base = base.into();
@@ -66,10 +97,40 @@ impl Comparison {
         let key = format!("{base}..{head}");
         Self { base, head, key }
     }
+
+    /// Staged changes — what the next commit would contain (`HEAD..:index`).
+    pub fn staged() -> Self {
+        Self::new("HEAD", Self::INDEX)
+    }
+
+    /// Unstaged changes, untracked files included (`:index..:worktree`).
+    pub fn unstaged() -> Self {
+        Self::new(Self::INDEX, Self::WORKTREE)
+    }
+
+    pub fn base_endpoint(&self) -> Endpoint<'_> {
+        Endpoint::parse(&self.base)
+    }
+
+    pub fn head_endpoint(&self) -> Endpoint<'_> {
+        Endpoint::parse(&self.head)
+    }
+
+    /// Whether a side is the index or the working tree — the diff can change
+    /// without any ref moving.
+    pub fn has_uncommitted_endpoint(&self) -> bool {
+        [self.base_endpoint(), self.head_endpoint()]
+            .iter()
+            .any(|side| !matches!(side, Endpoint::Ref(_)))
+    }
 }
 
 /// A file entry in the tree
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(
+    clippy::struct_excessive_bools,
+    reason = "independent flags of the UI's file tree entries"
+)]
 pub struct FileEntry {
     pub name: String,
     pub path: String,
@@ -87,6 +148,20 @@ pub struct FileEntry {
     pub size: Option<u64>,
     #[serde(rename = "modifiedAt", skip_serializing_if = "Option::is_none")]
     pub modified_at: Option<u64>,
+    /// A Git LFS pointer whose object isn't on disk; `size` is the object's.
+    #[serde(
+        rename = "isLfsPointer",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub is_lfs_pointer: bool,
+    /// Outside the sparse-checkout cone: tracked, but not on disk.
+    #[serde(
+        rename = "notMaterialized",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub not_materialized: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +59,46 @@ pub fn extract_symbols(source: &str, file_path: &str) -> Option<Vec<Symbol>> {
     Some(extract_symbols_from_node(root, source, &ext))
 }
 
+/// 0-based rows of `source` on which a statement starts, each with how many
+/// statement lists enclose it (0 for a file's top-level items). A statement
+/// starts a row and is one of a run of siblings each on its own row and not
+/// separated by commas — a block's statements, not an argument list's
+/// arguments. `None` when there's no grammar for the file.
+pub fn statement_start_rows(source: &str, file_path: &str) -> Option<HashMap<usize, usize>> {
+    let language = get_language_for_file(file_path)?;
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(source, None)?;
+    let indents: Vec<usize> = source
+        .lines()
+        .map(|line| line.len() - line.trim_start().len())
+        .collect();
+
+    let mut rows: HashMap<usize, usize> = HashMap::new();
+    let mut stack = vec![(tree.root_node(), 0)];
+    while let Some((node, depth)) = stack.pop() {
+        let mut cursor = node.walk();
+        let children: Vec<Node> = node.children(&mut cursor).collect();
+        let named: Vec<&Node> = children.iter().filter(|c| c.is_named()).collect();
+        let is_statement_list = named.len() >= 2
+            && children.iter().all(|c| c.kind() != ",")
+            && named
+                .windows(2)
+                .all(|w| w[0].end_position().row < w[1].start_position().row);
+        for child in named {
+            let start = child.start_position();
+            if is_statement_list && indents.get(start.row) == Some(&start.column) {
+                let row_depth = rows.entry(start.row).or_insert(depth);
+                *row_depth = (*row_depth).min(depth);
+            }
+            if child.end_position().row > start.row {
+                stack.push((*child, depth + usize::from(is_statement_list)));
+            }
+        }
+    }
+    Some(rows)
+}
+
 /// Find all symbol definitions matching `symbol_name` in the given source file.
 /// Recursively walks the symbol tree to find matches at any depth.
 pub fn find_definitions(source: &str, file_path: &str, symbol_name: &str) -> Vec<SymbolDefinition> {
@@ -1481,6 +1521,7 @@ pub fn compute_file_symbol_diff(
             top_level_hunk_ids: file_hunks.iter().map(|h| h.id.clone()).collect(),
             has_grammar: false,
             symbol_references: vec![],
+            limit_exceeded: false,
         };
     }
 
@@ -1513,6 +1554,7 @@ pub fn compute_file_symbol_diff(
         top_level_hunk_ids,
         has_grammar: true,
         symbol_references: vec![],
+        limit_exceeded: false,
     }
 }
 
@@ -2168,7 +2210,6 @@ fn collect_rust_use_names(node: Node, source: &str, names: &mut HashSet<String>)
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::diff::parser::DiffLine;
 
     /// Map hunks to symbols based on line range overlap.
     ///
@@ -2417,30 +2458,12 @@ func (s *Server) Start() {
         ];
 
         let hunks = vec![
-            DiffHunk {
-                id: "test.rs:abc".to_string(),
-                file_path: "test.rs".to_string(),
-                old_start: 2,
-                old_count: 3,
-                new_start: 3,
-                new_count: 3,
-                content: String::new(),
-                lines: vec![],
-                content_hash: String::new(),
-                move_pair_id: None,
-            },
-            DiffHunk {
-                id: "test.rs:def".to_string(),
-                file_path: "test.rs".to_string(),
-                old_start: 20,
-                old_count: 2,
-                new_start: 20,
-                new_count: 2,
-                content: String::new(),
-                lines: vec![],
-                content_hash: String::new(),
-                move_pair_id: None,
-            },
+            DiffHunk::test("test.rs", [])
+                .with_id("test.rs:abc")
+                .with_range(2, 3, 3, 3),
+            DiffHunk::test("test.rs", [])
+                .with_id("test.rs:def")
+                .with_range(20, 2, 20, 2),
         ];
 
         let (hunk_syms, top_level) = map_hunks_to_symbols(&hunks, &symbols, "test.rs");
@@ -2483,35 +2506,12 @@ const add = function(a, b) {
         new_count: u32,
     ) -> DiffHunk {
         // Generate realistic line data so overlap checks work correctly.
-        let mut lines = Vec::new();
-        for i in 0..old_count {
-            lines.push(DiffLine {
-                line_type: LineType::Removed,
-                content: String::new(),
-                old_line_number: Some(old_start + i),
-                new_line_number: None,
-            });
-        }
-        for i in 0..new_count {
-            lines.push(DiffLine {
-                line_type: LineType::Added,
-                content: String::new(),
-                old_line_number: None,
-                new_line_number: Some(new_start + i),
-            });
-        }
-        DiffHunk {
-            id: id.to_string(),
-            file_path: file.to_string(),
-            old_start,
-            old_count,
-            new_start,
-            new_count,
-            content: String::new(),
-            lines,
-            content_hash: String::new(),
-            move_pair_id: None,
-        }
+        let lines = std::iter::repeat_n((LineType::Removed, ""), old_count as usize).chain(
+            std::iter::repeat_n((LineType::Added, ""), new_count as usize),
+        );
+        DiffHunk::test(file, lines)
+            .with_id(id)
+            .with_range(old_start, old_count, new_start, new_count)
     }
 
     #[cfg(feature = "symbols-rust-lang")]
@@ -3047,18 +3047,9 @@ function render() {
     console.log(total);
 }
 "#;
-        let hunks = vec![DiffHunk {
-            id: "caller.ts:abc123".to_owned(),
-            file_path: "caller.ts".to_owned(),
-            old_start: 3,
-            old_count: 3,
-            new_start: 3,
-            new_count: 4,
-            content: String::new(),
-            lines: vec![],
-            content_hash: String::new(),
-            move_pair_id: None,
-        }];
+        let hunks = vec![DiffHunk::test("caller.ts", [])
+            .with_id("caller.ts:abc123")
+            .with_range(3, 3, 3, 4)];
 
         let mut targets = HashSet::new();
         targets.insert("calculateTotal".to_owned());
@@ -3088,30 +3079,12 @@ function render() {
 }
 "#;
         let hunks = vec![
-            DiffHunk {
-                id: "math.ts:def".to_owned(),
-                file_path: "math.ts".to_owned(),
-                old_start: 1,
-                old_count: 3,
-                new_start: 1,
-                new_count: 3,
-                content: String::new(),
-                lines: vec![],
-                content_hash: String::new(),
-                move_pair_id: None,
-            },
-            DiffHunk {
-                id: "math.ts:call".to_owned(),
-                file_path: "math.ts".to_owned(),
-                old_start: 5,
-                old_count: 3,
-                new_start: 5,
-                new_count: 4,
-                content: String::new(),
-                lines: vec![],
-                content_hash: String::new(),
-                move_pair_id: None,
-            },
+            DiffHunk::test("math.ts", [])
+                .with_id("math.ts:def")
+                .with_range(1, 3, 1, 3),
+            DiffHunk::test("math.ts", [])
+                .with_id("math.ts:call")
+                .with_range(5, 3, 5, 4),
         ];
 
         let mut targets = HashSet::new();
@@ -3134,18 +3107,9 @@ function render() {
     const x = fn(1);
 }
 "#;
-        let hunks = vec![DiffHunk {
-            id: "test.ts:abc".to_owned(),
-            file_path: "test.ts".to_owned(),
-            old_start: 1,
-            old_count: 3,
-            new_start: 1,
-            new_count: 3,
-            content: String::new(),
-            lines: vec![],
-            content_hash: String::new(),
-            move_pair_id: None,
-        }];
+        let hunks = vec![DiffHunk::test("test.ts", [])
+            .with_id("test.ts:abc")
+            .with_range(1, 3, 1, 3)];
 
         let mut targets = HashSet::new();
         targets.insert("x".to_owned());
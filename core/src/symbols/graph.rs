@@ -201,6 +201,7 @@ mod tests {
             top_level_hunk_ids: vec![],
             has_grammar: true,
             symbol_references: references,
+            limit_exceeded: false,
         }
     }
 
@@ -0,0 +1,256 @@
+//! Persistent repo-wide symbol index.
+//!
+//! Extracting symbols from every tracked file takes tens of seconds on large
+//! repos, so the results are kept on disk (in the disposable cache tier,
+//! `symbol-index.json`) keyed by git blob hash. A refresh only asks git for the
+//! current blob hash of each tracked file — cheap, no file reads — and parses
+//! just the content it hasn't seen before. Identical content under several
+//! paths is parsed once.
+//!
+//! The file watcher keeps the index warm between queries via
+//! [`update_paths`]; branch switches and other bulk changes are picked up by
+//! the next [`refresh`].
+
+use super::Symbol;
+use crate::review::central::{self, CentralError};
+use crate::service::jobs;
+use crate::sources::local_git::{LocalGitError, LocalGitSource};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum IndexError {
+    #[error("Central storage error: {0}")]
+    Central(#[from] CentralError),
+    #[error("Git error: {0}")]
+    Git(#[from] LocalGitError),
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+type Result<T> = std::result::Result<T, IndexError>;
+
+/// Bump this when symbol extraction changes to auto-invalidate stale indexes.
+const INDEX_VERSION: u32 = 1;
+
+/// Serializes load-modify-save cycles so the watcher and a query running at
+/// the same time can't clobber each other's writes.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SymbolIndex {
+    #[serde(default)]
+    version: u32,
+    /// Repo-relative path → blob hash of the content last indexed.
+    files: BTreeMap<String, String>,
+    /// Blob hash → symbols extracted from that content.
+    blobs: HashMap<String, Vec<Symbol>>,
+}
+
+impl SymbolIndex {
+    /// Files with at least one symbol, sorted by path.
+    pub fn files(&self) -> impl Iterator<Item = (&str, &[Symbol])> {
+        self.files.iter().filter_map(|(path, hash)| {
+            let symbols = self.blobs.get(hash)?;
+            (!symbols.is_empty()).then_some((path.as_str(), symbols.as_slice()))
+        })
+    }
+
+    /// Number of indexed files, including those without symbols.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Point `path` at `hash`, parsing the content if the blob is new. Returns
+    /// whether the index changed.
+    fn upsert(&mut self, repo_path: &Path, path: &str, hash: &str) -> bool {
+        if self.files.get(path).map(String::as_str) == Some(hash) {
+            return false;
+        }
+        if !self.blobs.contains_key(hash) {
+            let symbols = extract_from_disk(repo_path, path);
+            self.blobs.insert(hash.to_owned(), symbols);
+        }
+        self.files.insert(path.to_owned(), hash.to_owned());
+        true
+    }
+
+    /// Drop blobs no longer referenced by any path.
+    fn prune_blobs(&mut self) {
+        let live: HashSet<&String> = self.files.values().collect();
+        self.blobs.retain(|hash, _| live.contains(hash));
+    }
+}
+
+fn index_path(repo_path: &Path) -> Result<PathBuf> {
+    Ok(central::get_repo_cache_dir(repo_path)?.join("symbol-index.json"))
+}
+
+fn extract_from_disk(repo_path: &Path, path: &str) -> Vec<Symbol> {
+    fs::read_to_string(repo_path.join(path))
+        .ok()
+        .and_then(|content| super::extractor::extract_symbols(&content, path))
+        .unwrap_or_default()
+}
+
+/// Load the stored index. A missing, unreadable, or outdated index loads as
+/// empty — it's rebuilt on the next refresh.
+pub fn load(repo_path: &Path) -> SymbolIndex {
+    index_path(repo_path)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str::<SymbolIndex>(&content).ok())
+        .filter(|index| index.version == INDEX_VERSION)
+        .unwrap_or_default()
+}
+
+/// Save the index (atomic: write tmp + rename).
+fn save(repo_path: &Path, index: &mut SymbolIndex) -> Result<()> {
+    index.version = INDEX_VERSION;
+    index.prune_blobs();
+    let path = index_path(repo_path)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_string(index)?)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+/// Bring the index in line with the working tree: parse files whose blob hash
+/// is new (in parallel), drop files that are no longer tracked, and persist.
+pub fn refresh(repo_path: &Path) -> Result<SymbolIndex> {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let current: HashMap<String, String> = source
+        .tracked_blob_hashes()?
+        .into_iter()
+        .filter(|(path, _)| super::extractor::get_language_for_file(path).is_some())
+        .collect();
+
+    let mut index = load(repo_path);
+    let before = index.files.len();
+    index.files.retain(|path, _| current.contains_key(path));
+    let mut changed = index.files.len() != before;
+
+    // Parse unseen blobs across threads; one representative path per blob.
+    let mut unseen: HashMap<&str, &str> = HashMap::new();
+    for (path, hash) in &current {
+        if !index.blobs.contains_key(hash) {
+            unseen.entry(hash.as_str()).or_insert(path.as_str());
+        }
+    }
+    let parsed = jobs::parallel_map(unseen.into_iter().collect(), |(hash, path)| {
+        (hash.to_owned(), extract_from_disk(repo_path, path))
+    });
+    index.blobs.extend(parsed);
+
+    for (path, hash) in &current {
+        changed |= index.upsert(repo_path, path, hash);
+    }
+    if changed {
+        save(repo_path, &mut index)?;
+    }
+    Ok(index)
+}
+
+/// Re-index specific paths after a working-tree change. Only touches an index
+/// that already exists and paths it already tracks — new files and bulk
+/// changes are left to the next [`refresh`]. Returns how many paths changed.
+pub fn update_paths(repo_path: &Path, paths: &[String]) -> Result<usize> {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut index = load(repo_path);
+    let tracked: Vec<&String> = paths
+        .iter()
+        .filter(|path| index.files.contains_key(path.as_str()))
+        .collect();
+    if tracked.is_empty() {
+        return Ok(0);
+    }
+
+    let source = LocalGitSource::new(repo_path.to_path_buf())?;
+    let mut updated = 0;
+    for path in tracked {
+        let changed = match source.hash_file(path) {
+            Some(hash) => index.upsert(repo_path, path, &hash),
+            None => index.files.remove(path.as_str()).is_some(),
+        };
+        updated += usize::from(changed);
+    }
+    if updated > 0 {
+        save(repo_path, &mut index)?;
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::review::central::tests::{setup_test, ENV_LOCK};
+    use std::process::Command;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn symbol_names(index: &SymbolIndex, path: &str) -> Vec<String> {
+        index
+            .files()
+            .find(|(p, _)| *p == path)
+            .map(|(_, symbols)| symbols.iter().map(|s| s.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "symbols-rust-lang")]
+    #[test]
+    fn refresh_persists_and_updates_incrementally() {
+        let _lock = ENV_LOCK.lock().unwrap();
+        let (_env, _home, repo) = setup_test();
+        let root = repo.path();
+        git(root, &["init", "-q"]);
+        fs::write(root.join("a.rs"), "fn alpha() {}\n").unwrap();
+        fs::write(root.join("b.rs"), "fn alpha() {}\n").unwrap();
+        fs::write(root.join("notes.txt"), "not code\n").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-qm", "init"]);
+
+        let index = refresh(root).unwrap();
+        assert_eq!(index.len(), 2);
+        // Identical content is parsed once and shared.
+        assert_eq!(index.blobs.len(), 1);
+        assert_eq!(symbol_names(&index, "a.rs"), ["alpha"]);
+        assert_eq!(load(root).len(), 2);
+
+        // An unstaged edit is picked up by the watcher path.
+        fs::write(root.join("b.rs"), "fn beta() {}\n").unwrap();
+        assert_eq!(update_paths(root, &["b.rs".to_owned()]).unwrap(), 1);
+        let index = load(root);
+        assert_eq!(symbol_names(&index, "b.rs"), ["beta"]);
+        assert_eq!(index.blobs.len(), 2);
+
+        // Refresh agrees with the incremental update and drops deleted files.
+        fs::remove_file(root.join("a.rs")).unwrap();
+        let index = refresh(root).unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(symbol_names(&index, "b.rs"), ["beta"]);
+        assert_eq!(index.blobs.len(), 1);
+    }
+}
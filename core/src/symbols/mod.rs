@@ -6,6 +6,7 @@
 pub mod cache;
 pub mod extractor;
 pub mod graph;
+pub mod index;
 
 use serde::{Deserialize, Serialize};
 
@@ -26,7 +27,7 @@ pub enum SymbolKind {
 }
 
 /// A symbol definition extracted from a source file.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     pub name: String,
     pub kind: SymbolKind,
@@ -36,7 +37,7 @@ pub struct Symbol {
     pub end_line: u32,
     pub children: Vec<Symbol>,
     /// Heading depth for markdown symbols (1–6), None for code symbols.
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub depth: Option<u32>,
 }
 
@@ -123,4 +124,12 @@ pub struct FileSymbolDiff {
     /// References to modified symbols found in hunks of this file.
     #[serde(rename = "symbolReferences")]
     pub symbol_references: Vec<SymbolReference>,
+    /// Set when the file was too large to parse within the review's limits;
+    /// all its hunks are then reported as top-level.
+    #[serde(
+        rename = "limitExceeded",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub limit_exceeded: bool,
 }
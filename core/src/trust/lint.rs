@@ -0,0 +1,253 @@
+//! Lint a taxonomy and the trust lists written against it.
+//!
+//! A custom taxonomy that defines a label twice or files it under the wrong
+//! category, or a trust list naming a label that doesn't exist, fails quietly
+//! at review time: the pattern just never matches. [`lint_taxonomy`] and
+//! [`lint_trust_list`] surface those mistakes as [`Diagnostic`]s instead.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use super::matches_pattern;
+use super::patterns::{is_never_trusted, TrustCategory};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintCode {
+    /// Two categories share an id.
+    DuplicateCategory,
+    /// Two patterns share a label.
+    DuplicateLabel,
+    /// A category with no patterns.
+    EmptyCategory,
+    /// A label that isn't `<category>:<name>` of the category it's in, so
+    /// `<category>:*` won't match it.
+    MisfiledLabel,
+    /// A trust pattern without wildcards naming a label the taxonomy lacks.
+    UnknownLabel,
+    /// A trust pattern naming a label that is never trusted.
+    NeverTrusted,
+    /// A trust pattern that matches no label in the taxonomy.
+    UnmatchablePattern,
+    /// A trust pattern listed more than once.
+    DuplicatePattern,
+}
+
+/// One problem found by the linter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: LintCode,
+    /// The category, label, or trust pattern at fault.
+    pub subject: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, code: LintCode, subject: &str, message: String) -> Self {
+        Self {
+            severity,
+            code,
+            subject: subject.to_owned(),
+            message,
+        }
+    }
+}
+
+/// Whether any diagnostic is an error.
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+/// Check a taxonomy for duplicate categories and labels, empty categories, and
+/// labels filed under the wrong category.
+pub fn lint_taxonomy(categories: &[TrustCategory]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut category_ids = HashSet::new();
+    let mut labels = HashSet::new();
+
+    for category in categories {
+        if !category_ids.insert(category.id.as_str()) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                LintCode::DuplicateCategory,
+                &category.id,
+                format!("Category '{}' is defined more than once", category.id),
+            ));
+        }
+        if category.patterns.is_empty() {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                LintCode::EmptyCategory,
+                &category.id,
+                format!("Category '{}' has no patterns", category.id),
+            ));
+        }
+        for pattern in &category.patterns {
+            if !labels.insert(pattern.id.as_str()) {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    LintCode::DuplicateLabel,
+                    &pattern.id,
+                    format!("Label '{}' is defined more than once", pattern.id),
+                ));
+            }
+            let filed = pattern
+                .id
+                .strip_prefix(category.id.as_str())
+                .and_then(|rest| rest.strip_prefix(':'))
+                .is_some_and(|name| !name.is_empty());
+            if !filed {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Error,
+                    LintCode::MisfiledLabel,
+                    &pattern.id,
+                    format!(
+                        "Label '{}' should be '{}:<name>' to belong to category '{}'",
+                        pattern.id, category.id, category.id
+                    ),
+                ));
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Check a trust list against a taxonomy for patterns that can't do anything:
+/// unknown or never-trusted labels, wildcards matching nothing, and repeats.
+pub fn lint_trust_list(categories: &[TrustCategory], trust_list: &[String]) -> Vec<Diagnostic> {
+    let labels: Vec<&str> = categories
+        .iter()
+        .flat_map(|c| c.patterns.iter().map(|p| p.id.as_str()))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut diagnostics = Vec::new();
+
+    for pattern in trust_list {
+        if !seen.insert(pattern.as_str()) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                LintCode::DuplicatePattern,
+                pattern,
+                format!("Trust pattern '{pattern}' is listed more than once"),
+            ));
+            continue;
+        }
+        if pattern.trim().is_empty() {
+            diagnostics.push(Diagnostic::new(
+                Severity::Error,
+                LintCode::UnmatchablePattern,
+                pattern,
+                "Empty trust pattern matches nothing".to_owned(),
+            ));
+        } else if is_never_trusted(pattern) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                LintCode::NeverTrusted,
+                pattern,
+                format!("'{pattern}' is never trusted; listing it has no effect"),
+            ));
+        } else if !pattern.contains('*') {
+            if !labels.contains(&pattern.as_str()) {
+                diagnostics.push(Diagnostic::new(
+                    Severity::Warning,
+                    LintCode::UnknownLabel,
+                    pattern,
+                    format!("'{pattern}' is not a label in the taxonomy"),
+                ));
+            }
+        } else if !labels.iter().any(|label| matches_pattern(label, pattern)) {
+            diagnostics.push(Diagnostic::new(
+                Severity::Warning,
+                LintCode::UnmatchablePattern,
+                pattern,
+                format!("'{pattern}' matches no label in the taxonomy"),
+            ));
+        }
+    }
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trust::patterns::{get_default_pattern_ids, get_trust_taxonomy, parse_taxonomy};
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<(LintCode, &str)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.code, d.subject.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn bundled_taxonomy_and_default_trust_list_are_clean() {
+        let taxonomy = get_trust_taxonomy();
+        assert_eq!(lint_taxonomy(&taxonomy), []);
+        assert_eq!(lint_trust_list(&taxonomy, &get_default_pattern_ids()), []);
+    }
+
+    #[test]
+    fn lints_custom_taxonomy() {
+        let taxonomy = parse_taxonomy(
+            r#"{"categories": [
+                {"id": "docs", "name": "Docs", "description": "", "patterns": [
+                    {"id": "docs:readme", "name": "README", "description": ""},
+                    {"id": "docs:readme", "name": "README", "description": ""},
+                    {"id": "readme:typo", "name": "Typo", "description": ""}
+                ]},
+                {"id": "docs", "name": "Docs", "description": "", "patterns": []}
+            ]}"#,
+        )
+        .unwrap();
+        let diagnostics = lint_taxonomy(&taxonomy);
+        assert_eq!(
+            codes(&diagnostics),
+            [
+                (LintCode::DuplicateLabel, "docs:readme"),
+                (LintCode::MisfiledLabel, "readme:typo"),
+                (LintCode::DuplicateCategory, "docs"),
+                (LintCode::EmptyCategory, "docs"),
+            ]
+        );
+        assert!(has_errors(&diagnostics));
+    }
+
+    #[test]
+    fn lints_trust_list() {
+        let taxonomy = get_trust_taxonomy();
+        let trust_list: Vec<String> = [
+            "imports:*",
+            "imports:added",
+            "imports:added",
+            "imports:renamed",
+            "security:sensitive-area",
+            "*:rewritten",
+            "",
+        ]
+        .iter()
+        .map(|p| (*p).to_owned())
+        .collect();
+        let diagnostics = lint_trust_list(&taxonomy, &trust_list);
+        assert_eq!(
+            codes(&diagnostics),
+            [
+                (LintCode::DuplicatePattern, "imports:added"),
+                (LintCode::UnknownLabel, "imports:renamed"),
+                (LintCode::NeverTrusted, "security:sensitive-area"),
+                (LintCode::UnmatchablePattern, "*:rewritten"),
+                (LintCode::UnmatchablePattern, ""),
+            ]
+        );
+        assert!(has_errors(&diagnostics));
+    }
+}
@@ -1,3 +1,4 @@
+pub mod lint;
 pub mod matching;
 pub mod patterns;
 
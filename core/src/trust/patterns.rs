@@ -1,4 +1,36 @@
+use std::sync::LazyLock;
+
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::classify::migrations::IRREVERSIBLE_LABEL;
+use crate::classify::sensitive::SENSITIVE_AREA_LABEL;
+use crate::classify::static_rules::{
+    LICENSE_CHANGE_LABEL, PRIVILEGED_CONTAINER_LABEL, PULL_REQUEST_TARGET_LABEL,
+    TESTS_REMOVED_LABEL,
+};
+use crate::diff::api_schema::API_BREAKING_LABEL;
+use crate::service::semgrep::SEMGREP_FINDING_LABEL;
+
+/// Labels that always need a human: a hunk carrying one is never trusted,
+/// whatever its other labels and whatever the trust list says — a wildcard
+/// like `infra:*` doesn't reach them. The risky ones the taxonomy lists, so
+/// they can be recognized and filtered on, trusting them just has no effect.
+pub const NEVER_TRUSTED_LABELS: &[&str] = &[
+    SENSITIVE_AREA_LABEL,
+    TESTS_REMOVED_LABEL,
+    LICENSE_CHANGE_LABEL,
+    SEMGREP_FINDING_LABEL,
+    PRIVILEGED_CONTAINER_LABEL,
+    API_BREAKING_LABEL,
+    IRREVERSIBLE_LABEL,
+    PULL_REQUEST_TARGET_LABEL,
+];
+
+/// Whether `label` is one of [`NEVER_TRUSTED_LABELS`].
+pub fn is_never_trusted(label: &str) -> bool {
+    NEVER_TRUSTED_LABELS.contains(&label)
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrustPattern {
@@ -7,6 +39,10 @@ pub struct TrustPattern {
     pub category: String,
     pub name: String,
     pub description: String,
+    /// Left off the trust list of new reviews; the user has to trust it
+    /// explicitly (infrastructure changes, risky edits).
+    #[serde(default, rename = "optIn", skip_serializing_if = "std::ops::Not::not")]
+    pub opt_in: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,12 +73,19 @@ fn fill_pattern_categories(categories: Vec<TrustCategory>) -> Vec<TrustCategory>
         .collect()
 }
 
+/// Parse a taxonomy in the `taxonomy.json` format (`{"categories": [...]}`),
+/// filling in each pattern's category from its parent.
+pub fn parse_taxonomy(json: &str) -> Result<Vec<TrustCategory>, serde_json::Error> {
+    let taxonomy: TaxonomyFile = serde_json::from_str(json)?;
+    Ok(fill_pattern_categories(taxonomy.categories))
+}
+
 /// Load the trust taxonomy from JSON.
 /// First tries to load from bundled resources, then falls back to hardcoded.
 fn load_taxonomy_from_json() -> Vec<TrustCategory> {
     let json_str = include_str!("../../resources/taxonomy.json");
-    match serde_json::from_str::<TaxonomyFile>(json_str) {
-        Ok(taxonomy) => fill_pattern_categories(taxonomy.categories),
+    match parse_taxonomy(json_str) {
+        Ok(categories) => categories,
         Err(e) => {
             log::warn!("[load_taxonomy_from_json] Failed to parse bundled taxonomy: {e}");
             get_default_taxonomy()
@@ -55,6 +98,27 @@ pub fn get_trust_taxonomy() -> Vec<TrustCategory> {
     load_taxonomy_from_json()
 }
 
+/// A short fingerprint of a taxonomy's vocabulary: its pattern IDs and the
+/// descriptions classifiers label by. Changes whenever either does.
+pub fn taxonomy_version(taxonomy: &[TrustCategory]) -> String {
+    let mut hasher = Sha256::new();
+    for pattern in taxonomy.iter().flat_map(|c| &c.patterns) {
+        hasher.update(pattern.id.as_bytes());
+        hasher.update(b"\t");
+        hasher.update(pattern.description.as_bytes());
+        hasher.update(b"\n");
+    }
+    hex::encode(&hasher.finalize()[..6])
+}
+
+static CURRENT_TAXONOMY_VERSION: LazyLock<String> =
+    LazyLock::new(|| taxonomy_version(&get_trust_taxonomy()));
+
+/// The version of the taxonomy classifications are made with now.
+pub fn current_taxonomy_version() -> &'static str {
+    &CURRENT_TAXONOMY_VERSION
+}
+
 /// Return all pattern IDs from the taxonomy (e.g. "imports:added", "formatting:whitespace", etc.)
 pub fn get_all_pattern_ids() -> Vec<String> {
     get_trust_taxonomy()
@@ -63,6 +127,16 @@ pub fn get_all_pattern_ids() -> Vec<String> {
         .collect()
 }
 
+/// Pattern IDs trusted by a new review: every pattern except opt-in ones.
+pub fn get_default_pattern_ids() -> Vec<String> {
+    get_trust_taxonomy()
+        .into_iter()
+        .flat_map(|cat| cat.patterns.into_iter())
+        .filter(|p| !p.opt_in)
+        .map(|p| p.id)
+        .collect()
+}
+
 /// Fallback hardcoded taxonomy in case JSON loading fails
 fn get_default_taxonomy() -> Vec<TrustCategory> {
     vec![
@@ -76,18 +150,21 @@ fn get_default_taxonomy() -> Vec<TrustCategory> {
                     category: "imports".to_owned(),
                     name: "Added".to_owned(),
                     description: "New import statements added".to_owned(),
+                    opt_in: false,
                 },
                 TrustPattern {
                     id: "imports:removed".to_owned(),
                     category: "imports".to_owned(),
                     name: "Removed".to_owned(),
                     description: "Import statements removed".to_owned(),
+                    opt_in: false,
                 },
                 TrustPattern {
                     id: "imports:reordered".to_owned(),
                     category: "imports".to_owned(),
                     name: "Reordered".to_owned(),
                     description: "Import statements reordered".to_owned(),
+                    opt_in: false,
                 },
             ],
         },
@@ -101,18 +178,21 @@ fn get_default_taxonomy() -> Vec<TrustCategory> {
                     category: "formatting".to_owned(),
                     name: "Whitespace".to_owned(),
                     description: "Whitespace-only changes (spaces, tabs, blank lines)".to_owned(),
+                    opt_in: false,
                 },
                 TrustPattern {
                     id: "formatting:line-length".to_owned(),
                     category: "formatting".to_owned(),
                     name: "Line length".to_owned(),
                     description: "Line wrapping for length limits".to_owned(),
+                    opt_in: false,
                 },
                 TrustPattern {
                     id: "formatting:style".to_owned(),
                     category: "formatting".to_owned(),
                     name: "Style".to_owned(),
                     description: "Code style changes (semicolons, quotes, etc.)".to_owned(),
+                    opt_in: false,
                 },
             ],
         },
@@ -126,18 +206,21 @@ fn get_default_taxonomy() -> Vec<TrustCategory> {
                     category: "comments".to_owned(),
                     name: "Added".to_owned(),
                     description: "New comments added".to_owned(),
+                    opt_in: false,
                 },
                 TrustPattern {
                     id: "comments:removed".to_owned(),
                     category: "comments".to_owned(),
                     name: "Removed".to_owned(),
                     description: "Comments removed".to_owned(),
+                    opt_in: false,
                 },
                 TrustPattern {
                     id: "comments:modified".to_owned(),
                     category: "comments".to_owned(),
                     name: "Modified".to_owned(),
                     description: "Comments updated or corrected".to_owned(),
+                    opt_in: false,
                 },
             ],
         },
@@ -181,4 +264,24 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_default_pattern_ids_exclude_opt_in() {
+        let defaults = get_default_pattern_ids();
+        assert!(defaults.contains(&"imports:added".to_owned()));
+        assert!(!defaults.contains(&"infra:dockerfile".to_owned()));
+        assert!(!defaults.contains(&"ci:pull-request-target".to_owned()));
+        assert!(get_all_pattern_ids().contains(&"ci:pull-request-target".to_owned()));
+    }
+
+    #[test]
+    fn taxonomy_version_tracks_the_vocabulary() {
+        let mut taxonomy = load_taxonomy_from_json();
+        let version = taxonomy_version(&taxonomy);
+        assert_eq!(version.len(), 12);
+        assert_eq!(version, current_taxonomy_version());
+
+        taxonomy[0].patterns[0].description.push_str(" (reworded)");
+        assert_ne!(taxonomy_version(&taxonomy), version);
+    }
 }
@@ -0,0 +1,368 @@
+//! Installing the bundled `review-cli` sidecar as a `review` command on PATH.
+//!
+//! - macOS: a symlink at `/usr/local/bin/review`, created with administrator
+//!   privileges via osascript.
+//! - Linux: a symlink in the user's bin dir (`$XDG_BIN_HOME`, else
+//!   `~/.local/bin`), no sudo. An AppImage mounts at a new path on every
+//!   launch, so there the binary is copied instead. A `review` that isn't
+//!   ours — not a symlink to the sidecar or a copy of it — is left alone.
+//! - Windows: a copy at `%LOCALAPPDATA%\Review\bin\review.exe`, with that
+//!   directory added to the user's `Path`.
+//!
+//! A copy is recorded by the hash in a marker file beside it, and refreshed on
+//! launch so it follows app updates — unless something else has replaced it.
+
+use std::path::{Path, PathBuf};
+
+use log::info;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct CliInstallStatus {
+    pub installed: bool,
+    /// What the installed command runs: the symlink's target, or the sidecar
+    /// a copy was made from.
+    pub symlink_target: Option<String>,
+    /// Where the command is (or would be) installed.
+    pub install_path: String,
+    /// Whether the install directory is on `PATH`. A fresh `~/.local/bin` or
+    /// Windows `Path` entry may need a new terminal to take effect.
+    pub on_path: bool,
+}
+
+/// The sidecar binary, which Tauri places next to the main executable
+/// (`Review.app/Contents/MacOS/review-cli` in the macOS bundle).
+fn sidecar_path() -> Result<PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Could not locate the app: {e}"))?;
+    let name = if cfg!(windows) {
+        "review-cli.exe"
+    } else {
+        "review-cli"
+    };
+    let sidecar = exe
+        .parent()
+        .ok_or("Could not determine the app's directory")?
+        .join(name);
+    if sidecar.exists() {
+        Ok(sidecar)
+    } else {
+        Err(format!("Sidecar binary not found at {}", sidecar.display()))
+    }
+}
+
+/// Whether `dir` is one of the entries of this process's `PATH`.
+fn dir_on_path(dir: &Path) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| std::env::split_paths(&path).any(|p| p == dir))
+}
+
+/// Whether this platform installs the CLI as a copy rather than a symlink.
+fn installs_copies() -> bool {
+    cfg!(windows) || std::env::var_os("APPIMAGE").is_some()
+}
+
+/// The marker beside a copied install, holding the hash of what was copied.
+fn copy_marker(path: &Path) -> PathBuf {
+    path.with_extension("sidecar-hash")
+}
+
+fn file_hash(path: &Path) -> Option<String> {
+    std::fs::read(path)
+        .ok()
+        .map(|bytes| review::diff::parser::compute_content_hash(&bytes))
+}
+
+/// Copy the sidecar to `path` and record the copy as ours.
+fn install_copy(sidecar: &Path, path: &Path) -> Result<(), String> {
+    std::fs::copy(sidecar, path).map_err(|e| format!("Failed to copy the CLI: {e}"))?;
+    let hash = file_hash(path).ok_or("Failed to read back the copied CLI")?;
+    std::fs::write(copy_marker(path), hash)
+        .map_err(|e| format!("Failed to record the copied CLI: {e}"))
+}
+
+/// Whether `path` is a copy this app made that nothing has replaced since.
+fn is_our_copy(path: &Path) -> bool {
+    matches!(
+        (std::fs::read_to_string(copy_marker(path)), file_hash(path)),
+        (Ok(recorded), Some(current)) if recorded.trim() == current
+    )
+}
+
+/// Re-copy the sidecar over our copy at `path` if they differ. Returns
+/// whether it did; anything but our own copy is left alone.
+fn refresh_copy(path: &Path, sidecar: &Path) -> Result<bool, String> {
+    if path.is_symlink() || !is_our_copy(path) || file_hash(path) == file_hash(sidecar) {
+        return Ok(false);
+    }
+    install_copy(sidecar, path)?;
+    Ok(true)
+}
+
+pub fn status() -> Result<CliInstallStatus, String> {
+    let path = install_path()?;
+    let on_path = path.parent().is_some_and(dir_on_path);
+    let installed = path.symlink_metadata().is_ok();
+    let symlink_target = match std::fs::read_link(&path) {
+        Ok(target) => Some(target.to_string_lossy().to_string()),
+        Err(_) if installed => sidecar_path().ok().map(|p| p.to_string_lossy().to_string()),
+        Err(_) => None,
+    };
+    Ok(CliInstallStatus {
+        installed,
+        symlink_target,
+        install_path: path.to_string_lossy().to_string(),
+        on_path,
+    })
+}
+
+/// Re-copy an installed copy that no longer matches the app's sidecar (after
+/// an update). Symlinked installs follow the app on their own.
+pub fn refresh_installed_copy() {
+    if !installs_copies() {
+        return;
+    }
+    let (Ok(path), Ok(sidecar)) = (install_path(), sidecar_path()) else {
+        return;
+    };
+    match refresh_copy(&path, &sidecar) {
+        Ok(true) => info!("[cli_install] Refreshed {}", path.display()),
+        Ok(false) => {}
+        Err(e) => log::warn!("[cli_install] Failed to refresh {}: {e}", path.display()),
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{Path, PathBuf};
+
+    /// Well-known install location for the `review` CLI symlink.
+    const CLI_SYMLINK_PATH: &str = "/usr/local/bin/review";
+
+    pub fn install_path() -> Result<PathBuf, String> {
+        Ok(PathBuf::from(CLI_SYMLINK_PATH))
+    }
+
+    /// Run a shell command with administrator privileges via osascript.
+    /// Returns an error if the user cancels or the command fails.
+    fn run_admin_shell_command(shell_command: &str, cancel_message: &str) -> Result<(), String> {
+        let script = format!(
+            "do shell script \"{}\" with administrator privileges",
+            shell_command
+        );
+
+        let output = std::process::Command::new("osascript")
+            .args(["-e", &script])
+            .output()
+            .map_err(|e| format!("Failed to run osascript: {e}"))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if stderr.contains("User canceled") || stderr.contains("(-128)") {
+                return Err(cancel_message.to_string());
+            }
+            return Err(stderr.trim().to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn install(sidecar: &Path) -> Result<(), String> {
+        let shell_command = format!("ln -sf '{}' '{}'", sidecar.display(), CLI_SYMLINK_PATH);
+        run_admin_shell_command(&shell_command, "Installation cancelled")
+            .map_err(|e| format!("Failed to create symlink: {e}"))
+    }
+
+    pub fn uninstall(path: &Path) -> Result<(), String> {
+        let shell_command = format!("rm '{}'", path.display());
+        run_admin_shell_command(&shell_command, "Uninstall cancelled")
+            .map_err(|e| format!("Failed to remove symlink: {e}"))
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::{
+        copy_marker, install_copy, installs_copies, is_our_copy, sidecar_path, Path, PathBuf,
+    };
+
+    pub fn install_path() -> Result<PathBuf, String> {
+        let dir = match dirs::executable_dir() {
+            Some(dir) => dir,
+            None => dirs::home_dir()
+                .ok_or("Could not determine the home directory")?
+                .join(".local")
+                .join("bin"),
+        };
+        Ok(dir.join("review"))
+    }
+
+    /// Whether the `review` at `path` is one we installed: a symlink to a
+    /// `review-cli` sidecar (this app's, or an earlier install's), or a copy
+    /// we made.
+    fn is_ours(path: &Path, sidecar: &Path) -> bool {
+        match std::fs::read_link(path) {
+            Ok(target) => target == sidecar || target.file_name() == sidecar.file_name(),
+            Err(_) => is_our_copy(path),
+        }
+    }
+
+    /// Refuse to touch a `review` at `path` that isn't ours.
+    fn ensure_ours(path: &Path, sidecar: &Path) -> Result<(), String> {
+        if is_ours(path, sidecar) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} already exists and isn't this app's CLI; move or remove it first",
+                path.display()
+            ))
+        }
+    }
+
+    pub fn install(sidecar: &Path) -> Result<(), String> {
+        let path = install_path()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        }
+        if path.symlink_metadata().is_ok() {
+            ensure_ours(&path, sidecar)?;
+            std::fs::remove_file(&path)
+                .map_err(|e| format!("Failed to replace {}: {e}", path.display()))?;
+        }
+        // An AppImage's sidecar lives in a mount that moves every launch.
+        if installs_copies() {
+            install_copy(sidecar, &path)
+        } else {
+            std::os::unix::fs::symlink(sidecar, &path)
+                .map_err(|e| format!("Failed to create symlink: {e}"))
+        }
+    }
+
+    pub fn uninstall(path: &Path) -> Result<(), String> {
+        ensure_ours(path, &sidecar_path()?)?;
+        std::fs::remove_file(path)
+            .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+        let _ = std::fs::remove_file(copy_marker(path));
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::{copy_marker, install_copy, Path, PathBuf};
+
+    pub fn install_path() -> Result<PathBuf, String> {
+        Ok(dirs::data_local_dir()
+            .ok_or("Could not determine the local app data directory")?
+            .join("Review")
+            .join("bin")
+            .join("review.exe"))
+    }
+
+    /// Run a PowerShell snippet that edits the user's `Path`, passing the
+    /// directory through the environment rather than splicing it into the
+    /// script.
+    fn edit_user_path(dir: &Path, script: &str) -> Result<(), String> {
+        let output = std::process::Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", script])
+            .env("REVIEW_CLI_DIR", dir)
+            .output()
+            .map_err(|e| format!("Failed to run PowerShell: {e}"))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+        }
+    }
+
+    pub fn install(sidecar: &Path) -> Result<(), String> {
+        let path = install_path()?;
+        let dir = path.parent().ok_or("Could not determine the install dir")?;
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        install_copy(sidecar, &path)?;
+        edit_user_path(
+            dir,
+            "$p = [Environment]::GetEnvironmentVariable('Path', 'User'); \
+             $entries = @($p -split ';' | Where-Object { $_ }); \
+             if ($entries -notcontains $env:REVIEW_CLI_DIR) { \
+               [Environment]::SetEnvironmentVariable('Path', (($entries + $env:REVIEW_CLI_DIR) -join ';'), 'User') \
+             }",
+        )
+        .map_err(|e| format!("Installed, but failed to add it to Path: {e}"))
+    }
+
+    pub fn uninstall(path: &Path) -> Result<(), String> {
+        std::fs::remove_file(path)
+            .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+        let _ = std::fs::remove_file(copy_marker(path));
+        let Some(dir) = path.parent() else {
+            return Ok(());
+        };
+        edit_user_path(
+            dir,
+            "$p = [Environment]::GetEnvironmentVariable('Path', 'User'); \
+             $entries = @($p -split ';' | Where-Object { $_ -and $_ -ne $env:REVIEW_CLI_DIR }); \
+             [Environment]::SetEnvironmentVariable('Path', ($entries -join ';'), 'User')",
+        )
+        .map_err(|e| format!("Removed, but failed to update Path: {e}"))
+    }
+}
+
+pub use platform::install_path;
+
+/// Install the sidecar as `review`. Returns the sidecar's path.
+pub fn install() -> Result<String, String> {
+    let sidecar = sidecar_path()?;
+    platform::install(&sidecar)?;
+    info!(
+        "[install_cli] Installed {} -> {}",
+        install_path()?.display(),
+        sidecar.display()
+    );
+    Ok(sidecar.to_string_lossy().to_string())
+}
+
+pub fn uninstall() -> Result<(), String> {
+    let path = install_path()?;
+    if path.symlink_metadata().is_ok() {
+        platform::uninstall(&path)?;
+        info!("[uninstall_cli] Removed {}", path.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cli-install-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn refresh_updates_our_copy_and_leaves_a_foreign_file_alone() {
+        let dir = scratch_dir("refresh");
+        let sidecar = dir.join("review-cli");
+        let path = dir.join("review");
+
+        std::fs::write(&sidecar, "v1").unwrap();
+        install_copy(&sidecar, &path).unwrap();
+        std::fs::write(&sidecar, "v2").unwrap();
+        assert_eq!(refresh_copy(&path, &sidecar), Ok(true));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "v2");
+        assert_eq!(refresh_copy(&path, &sidecar), Ok(false));
+
+        // Someone else's `review`, with or without our old marker beside it.
+        std::fs::write(&path, "their own review").unwrap();
+        std::fs::write(&sidecar, "v3").unwrap();
+        assert_eq!(refresh_copy(&path, &sidecar), Ok(false));
+        std::fs::remove_file(copy_marker(&path)).unwrap();
+        assert_eq!(refresh_copy(&path, &sidecar), Ok(false));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "their own review");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
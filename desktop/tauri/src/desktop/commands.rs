@@ -10,21 +10,31 @@
 )]
 
 use log::{debug, error, info};
+use review::classify::migrations::MigrationCheck;
 use review::classify::{self, ClassifyResponse};
+use review::diff::api_schema::FileSchemaDelta;
 use review::diff::parser::{detect_move_pairs, DiffHunk};
+use review::limits::ReviewLimits;
 use review::lsp::client::LspClient;
 use review::lsp::registry;
 use review::review::state::{ReviewState, ReviewSummary};
-use review::review::storage::{self, GlobalReviewSummary};
+use review::review::storage::{self, GlobalReviewSummary, ThreadSync};
+use review::service::jobs::{self, JobKind, JobPriority};
+use review::service::pr_review::PrReviewDraft;
+use review::service::shortstats::{self, ShortStatRequest, ShortStatResult};
+use review::service::two_person::{self, TwoPersonProgress};
 use review::service::{
-    CommitOutputLine, CommitResult, DetectMovePairsResponse, ExpandedContextResult, FileContent,
-    RepoFileSymbols, RepoLocalActivity, ReviewFreshnessInput, ReviewFreshnessResult,
-    VscodeThemeDetection,
+    CommitOutputLine, CommitResult, ContentSide, ContentWindow, DetectMovePairsResponse,
+    ExpandedContextResult, FileContent, FileContentWindow, LimitedHunks, RepoFileSymbols,
+    RepoLocalActivity, ReviewFreshnessInput, ReviewFreshnessResult, VscodeThemeDetection,
+};
+use review::sources::azure::{AzureDevOpsProvider, AzurePrRef, AzurePullRequest};
+use review::sources::github::{
+    GitHubClient, GitHubPrRef, GitHubProvider, PrReviewEvent, PullRequest,
 };
-use review::sources::github::{GhCliProvider, GitHubPrRef, GitHubProvider, PullRequest};
 use review::sources::local_git::{
-    DiffShortStat, HunkAttribution, LocalBranchInfo, LocalGitSource, RemoteInfo, SearchMatch,
-    WorktreeInfo,
+    DiffShortStat, GitRemote, HunkAttribution, LocalBranchInfo, LocalGitSource, RemoteInfo,
+    SearchMatch, WorktreeInfo, DEEPEN_STEP,
 };
 use review::sources::traits::{
     BranchList, CommitDetail, CommitEntry, Comparison, DiffSource, FileEntry, GitStatusSummary,
@@ -98,7 +108,7 @@ pub fn get_current_repo() -> Result<String, String> {
     if args.len() > 1 {
         let repo_path = &args[1];
         let path = PathBuf::from(repo_path);
-        if path.join(".git").exists() {
+        if review::sources::jj::is_repo_root(&path) {
             return Ok(repo_path.clone());
         }
     }
@@ -108,7 +118,7 @@ pub fn get_current_repo() -> Result<String, String> {
 
     let mut current = cwd.as_path();
     loop {
-        if current.join(".git").exists() {
+        if review::sources::jj::is_repo_root(current) {
             let repo_path = current.to_string_lossy().to_string();
             return Ok(repo_path);
         }
@@ -123,23 +133,125 @@ pub fn get_current_repo() -> Result<String, String> {
 
 #[tauri::command]
 pub fn check_github_available(repo_path: String) -> bool {
-    let provider = GhCliProvider::new(PathBuf::from(&repo_path));
+    let provider = GitHubClient::for_repo(PathBuf::from(&repo_path));
     provider.is_available()
 }
 
 #[tauri::command]
 pub fn list_pull_requests(repo_path: String) -> Result<Vec<PullRequest>, String> {
-    let provider = GhCliProvider::new(PathBuf::from(&repo_path));
+    let provider = GitHubClient::for_repo(PathBuf::from(&repo_path));
     provider.list_pull_requests().map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub fn check_azure_available(repo_path: String) -> bool {
+    let provider = AzureDevOpsProvider::new(PathBuf::from(&repo_path));
+    provider.is_available()
+}
+
+#[tauri::command]
+pub fn list_azure_pull_requests(repo_path: String) -> Result<Vec<AzurePullRequest>, String> {
+    let provider = AzureDevOpsProvider::new(PathBuf::from(&repo_path));
+    provider.list_pull_requests().map_err(|e| e.to_string())
+}
+
+/// The review `submit_pr_review` would post to the PR: its suggested (or the
+/// given) event and the summary body.
+#[tauri::command]
+pub fn draft_pr_review(
+    repo_path: String,
+    r#ref: String,
+    pr: Option<u32>,
+    event: Option<PrReviewEvent>,
+    message: Option<String>,
+) -> Result<PrReviewDraft, String> {
+    let draft = review::service::pr_review::draft_review(
+        &PathBuf::from(&repo_path),
+        &r#ref,
+        event,
+        message.as_deref(),
+    )
+    .map_err(|e| format!("{e:#}"))?;
+    Ok(PrReviewDraft {
+        number: pr.or(draft.number),
+        ..draft
+    })
+}
+
+/// Submit the review to its GitHub PR (or `pr`) as an approve,
+/// request-changes, or comment review.
+#[tauri::command]
+pub fn submit_pr_review(
+    repo_path: String,
+    r#ref: String,
+    pr: Option<u32>,
+    event: Option<PrReviewEvent>,
+    message: Option<String>,
+) -> Result<PrReviewDraft, String> {
+    let t0 = Instant::now();
+    let submitted = review::service::pr_review::submit_review(
+        &PathBuf::from(&repo_path),
+        &r#ref,
+        pr,
+        event,
+        message.as_deref(),
+    )
+    .map_err(|e| format!("{e:#}"))?;
+    info!(
+        "submit_pr_review {} {} in {:?}",
+        r#ref,
+        submitted.event.as_str(),
+        t0.elapsed()
+    );
+    Ok(submitted)
+}
+
+/// Import the review threads of the review's PR (or `pr`) as comments on the
+/// hunks they were left on.
+#[tauri::command]
+pub fn sync_pr_threads(
+    repo_path: String,
+    r#ref: String,
+    pr: Option<u32>,
+) -> Result<ThreadSync, String> {
+    let t0 = Instant::now();
+    let sync = storage::sync_pr_threads(&PathBuf::from(&repo_path), &r#ref, pr)
+        .map_err(|e| e.to_string())?;
+    info!(
+        "sync_pr_threads {}: {} added, {} updated, {} removed in {:?}",
+        r#ref,
+        sync.added,
+        sync.updated,
+        sync.removed,
+        t0.elapsed()
+    );
+    Ok(sync)
+}
+
+/// Sync the reviewer inbox — PRs awaiting the user's review and their own —
+/// from GitHub, returning it with the status changes since the last sync.
+#[tauri::command]
+pub fn sync_review_inbox(repo_path: String) -> Result<review::service::pr_sync::InboxSync, String> {
+    let t0 = Instant::now();
+    let sync = review::service::pr_sync::sync_inbox(&PathBuf::from(&repo_path))
+        .map_err(|e| format!("{e:#}"))?;
+    info!(
+        "sync_review_inbox entries={} changes={} in {:?}",
+        sync.inbox.entries.len(),
+        sync.changes.len(),
+        t0.elapsed()
+    );
+    Ok(sync)
+}
+
 #[tauri::command]
 pub async fn list_files(
     repo_path: String,
     comparison: Comparison,
     github_pr: Option<GitHubPrRef>,
+    azure_pr: Option<AzurePrRef>,
 ) -> Result<Vec<FileEntry>, String> {
-    tokio::task::spawn_blocking(move || list_files_sync(repo_path, comparison, github_pr))
+    tokio::task::spawn_blocking(move || list_files_sync(repo_path, comparison, github_pr, azure_pr))
         .await
         .map_err(|e| e.to_string())?
 }
@@ -149,9 +261,15 @@ pub fn list_files_sync(
     repo_path: String,
     comparison: Comparison,
     github_pr: Option<GitHubPrRef>,
+    azure_pr: Option<AzurePrRef>,
 ) -> Result<Vec<FileEntry>, String> {
-    review::service::files::list_files(&PathBuf::from(&repo_path), &comparison, github_pr.as_ref())
-        .map_err(|e| e.to_string())
+    review::service::files::list_files(
+        &PathBuf::from(&repo_path),
+        &comparison,
+        github_pr.as_ref(),
+        azure_pr.as_ref(),
+    )
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -210,6 +328,8 @@ pub async fn get_file_content(
     file_path: String,
     comparison: Comparison,
     github_pr: Option<GitHubPrRef>,
+    azure_pr: Option<AzurePrRef>,
+    function_context: Option<bool>,
 ) -> Result<FileContent, String> {
     tokio::task::spawn_blocking(move || {
         review::service::files::get_file_content(
@@ -217,6 +337,8 @@ pub async fn get_file_content(
             &file_path,
             &comparison,
             github_pr.as_ref(),
+            azure_pr.as_ref(),
+            function_context.unwrap_or(false),
         )
         .map_err(|e| e.to_string())
     })
@@ -236,6 +358,48 @@ pub async fn get_all_hunks(
         .map_err(|e| e.to_string())?
 }
 
+/// Like `get_all_hunks`, but reports which review limits were exceeded (and
+/// how the result was degraded). `unlimited` is the explicit opt-in to load
+/// everything; `collapse_translations` replaces each translation catalog's
+/// hunks with one key-level rollup hunk; the hunks of `function_context_files`
+/// each carry their whole enclosing function.
+#[tauri::command]
+pub async fn get_all_hunks_limited(
+    repo_path: String,
+    comparison: Comparison,
+    file_paths: Vec<String>,
+    unlimited: Option<bool>,
+    collapse_translations: Option<bool>,
+    function_context_files: Option<Vec<String>>,
+) -> Result<LimitedHunks, String> {
+    tokio::task::spawn_blocking(move || {
+        let repo_path = PathBuf::from(&repo_path);
+        let mut limited = review::service::files::get_all_hunks_limited(
+            &repo_path,
+            &comparison,
+            &file_paths,
+            &ReviewLimits::resolve(unlimited.unwrap_or(false)),
+        )
+        .map_err(|e| e.to_string())?;
+        limited.hunks = review::service::files::widen_to_functions(
+            &repo_path,
+            &comparison,
+            limited.hunks,
+            &function_context_files.unwrap_or_default(),
+        );
+        if collapse_translations.unwrap_or(false) {
+            limited.hunks = review::service::files::collapse_translation_hunks(
+                &repo_path,
+                &comparison,
+                limited.hunks,
+            );
+        }
+        Ok(limited)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 /// Synchronous implementation of `get_all_hunks`, callable from blocking contexts.
 pub fn get_all_hunks_sync(
     repo_path: String,
@@ -251,10 +415,11 @@ pub fn get_diff(
     repo_path: String,
     comparison: Comparison,
     github_pr: Option<GitHubPrRef>,
+    azure_pr: Option<AzurePrRef>,
 ) -> Result<String, String> {
     // PR routing: use gh CLI to get diff
     if let Some(ref pr) = github_pr {
-        let provider = GhCliProvider::new(PathBuf::from(&repo_path));
+        let provider = GitHubClient::for_repo(PathBuf::from(&repo_path));
         return provider
             .get_pull_request_diff(pr.number)
             .map_err(|e| e.to_string());
@@ -262,6 +427,15 @@ pub fn get_diff(
 
     let source = LocalGitSource::new(PathBuf::from(&repo_path)).map_err(|e| e.to_string())?;
 
+    // Azure DevOps PRs are diffed locally from their fetched refs
+    if let Some(ref pr) = azure_pr {
+        let provider = AzureDevOpsProvider::new(PathBuf::from(&repo_path));
+        let comparison = provider.comparison(pr).map_err(|e| e.to_string())?;
+        return source
+            .get_diff(&comparison, None)
+            .map_err(|e| e.to_string());
+    }
+
     source
         .get_diff(&comparison, None)
         .map_err(|e| e.to_string())
@@ -278,6 +452,16 @@ pub fn get_diff_shortstat(
         .map_err(|e| e.to_string())
 }
 
+/// Shortstats for many comparisons at once, cached by base/head SHA.
+#[tauri::command]
+pub async fn get_diff_shortstats(
+    comparisons: Vec<ShortStatRequest>,
+) -> Result<Vec<ShortStatResult>, String> {
+    tokio::task::spawn_blocking(move || shortstats::get_diff_shortstats(&comparisons))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Resolve a review's `ref` (+ optional base override) into a `ResolvedReview`
 /// (identity + concrete `Comparison`) the normal review flow can open.
 #[tauri::command]
@@ -309,18 +493,28 @@ pub fn load_review_state(repo_path: String, r#ref: String) -> Result<ReviewState
 /// Carry persisted decisions forward onto the live diff the UI just loaded, so a
 /// review reflects prior work even after edits shifted hunk IDs. Reconciles
 /// in-memory against the supplied hunks (no `git diff`); persistence happens on
-/// the next save.
+/// the next save. With `repo_path`, hunks that other reviews approved
+/// unchanged are approved too.
 #[tauri::command]
 pub fn reconcile_review_state(
     state: ReviewState,
     hunks: Vec<DiffHunk>,
+    repo_path: Option<String>,
 ) -> Result<review::service::review_io::ReviewLoadResult, String> {
     let t0 = Instant::now();
     let key = state.ref_name.clone();
-    let result = review::service::review_io::reconcile_review(state, &hunks);
+    let result = match repo_path {
+        Some(repo_path) => review::service::review_io::reconcile_review_in_repo(
+            std::path::Path::new(&repo_path),
+            state,
+            &hunks,
+        ),
+        None => review::service::review_io::reconcile_review(state, &hunks),
+    };
     info!(
-        "reconcile_review_state {key} carried={} in {:?}",
+        "reconcile_review_state {key} carried={} from_reviews={} in {:?}",
         result.carried_forward,
+        result.carried_from_reviews,
         t0.elapsed()
     );
     Ok(result)
@@ -364,11 +558,228 @@ pub fn set_base_override(
         .map_err(|e| e.to_string())
 }
 
+/// A review's progress as of its last save — overall and per file — read
+/// from storage without recomputing the diff.
+#[tauri::command]
+pub fn get_review_progress(
+    repo_path: String,
+    r#ref: String,
+) -> Result<review::service::review_io::ReviewProgress, String> {
+    review::service::review_io::get_review_progress(&PathBuf::from(&repo_path), &r#ref)
+        .map_err(|e| e.to_string())
+}
+
+/// Chronological activity for a review — commits, classification runs, review
+/// decisions, comments — optionally limited to events after `since`.
+#[tauri::command]
+pub fn get_review_timeline(
+    repo_path: String,
+    r#ref: String,
+    since: Option<String>,
+) -> Result<review::service::timeline::ReviewTimeline, String> {
+    let t0 = Instant::now();
+    let timeline = review::service::timeline::review_timeline(
+        &PathBuf::from(&repo_path),
+        &r#ref,
+        since.as_deref(),
+    )
+    .map_err(|e| e.to_string())?;
+    info!(
+        "get_review_timeline {} events={} in {:?}",
+        r#ref,
+        timeline.events.len(),
+        t0.elapsed()
+    );
+    Ok(timeline)
+}
+
+/// Propose splitting a review's commit range into at most `max_reviews`
+/// smaller reviews (AI with `model`, else directory clustering), saving them
+/// as linked reviews when `create` is set.
+#[tauri::command]
+pub async fn split_review(
+    repo_path: String,
+    r#ref: String,
+    base_override: Option<String>,
+    model: Option<String>,
+    max_reviews: usize,
+    create: bool,
+) -> Result<review::service::split::SplitPlan, String> {
+    tokio::task::spawn_blocking(move || {
+        let repo = PathBuf::from(&repo_path);
+        let plan = review::service::split::propose_split(
+            &repo,
+            &r#ref,
+            base_override.as_deref(),
+            model.as_deref(),
+            max_reviews,
+        )
+        .map_err(|e| format!("{e:#}"))?;
+        if create {
+            review::service::split::create_split_reviews(&repo, &plan)
+                .map_err(|e| format!("{e:#}"))?;
+        }
+        info!(
+            "split_review {} into {} reviews (create={create})",
+            r#ref,
+            plan.reviews.len()
+        );
+        Ok(plan)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Changed submodules of a review, each expanded into a nested review scope
+/// with its own comparison, hunks, static classification, and progress.
+#[tauri::command]
+pub fn get_submodule_scopes(
+    repo_path: String,
+    r#ref: String,
+) -> Result<Vec<review::service::submodules::SubmoduleScope>, String> {
+    let t0 = Instant::now();
+    let scopes = review::service::submodules::submodule_scopes(&PathBuf::from(&repo_path), &r#ref)
+        .map_err(|e| e.to_string())?;
+    info!(
+        "get_submodule_scopes {} scopes={} in {:?}",
+        r#ref,
+        scopes.len(),
+        t0.elapsed()
+    );
+    Ok(scopes)
+}
+
+/// The submodule diff behind a pointer hunk, or `None` when the submodule
+/// isn't checked out or lacks the commits.
+#[tauri::command]
+pub fn expand_submodule_hunk(
+    repo_path: String,
+    hunk: DiffHunk,
+) -> Result<Option<Vec<DiffHunk>>, String> {
+    review::service::submodules::expand_submodule_hunk(&PathBuf::from(&repo_path), &hunk)
+        .map_err(|e| e.to_string())
+}
+
+/// The review's checklist, synced against the repo's `.review/checklist.md`.
+#[tauri::command]
+pub fn get_review_checklist(
+    repo_path: String,
+    r#ref: String,
+) -> Result<Vec<review::review::state::ChecklistItem>, String> {
+    review::service::checklist::get_checklist(&PathBuf::from(&repo_path), &r#ref)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_review_checklist_item(
+    repo_path: String,
+    r#ref: String,
+    item: String,
+    checked: bool,
+) -> Result<Vec<review::review::state::ChecklistItem>, String> {
+    let t0 = Instant::now();
+    let items = review::service::checklist::set_checklist_item(
+        &PathBuf::from(&repo_path),
+        &r#ref,
+        &item,
+        checked,
+        review::review::state::Source::Ui,
+    )
+    .map_err(|e| e.to_string())?;
+    info!(
+        "set_review_checklist_item {} {item}={checked} in {:?}",
+        r#ref,
+        t0.elapsed()
+    );
+    Ok(items)
+}
+
+/// Where the review's two-person review stands; `None` when it isn't one.
+#[tauri::command]
+pub fn get_two_person_progress(
+    repo_path: String,
+    r#ref: String,
+) -> Result<Option<TwoPersonProgress>, String> {
+    let state = storage::load_review_state(&PathBuf::from(&repo_path), &r#ref)
+        .map_err(|e| e.to_string())?;
+    Ok(two_person::progress(&state))
+}
+
+/// Require a second reviewer's sign-off on the review.
+#[tauri::command]
+pub fn require_second_reviewer(repo_path: String, r#ref: String) -> Result<(), String> {
+    two_person::require_second_reviewer(&PathBuf::from(&repo_path), &r#ref)
+        .map(|_| ())
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Hand the review off as its first reviewer, `by`. Returns how many
+/// decisions became recommendations.
+#[tauri::command]
+pub fn hand_off_review(repo_path: String, r#ref: String, by: String) -> Result<usize, String> {
+    let recommended = two_person::hand_off(&PathBuf::from(&repo_path), &r#ref, &by)
+        .map_err(|e| format!("{e:#}"))?;
+    info!(
+        "hand_off_review {} by {by}: {recommended} recommendations",
+        r#ref
+    );
+    Ok(recommended)
+}
+
+/// Sign the review off as its second reviewer, `by`.
+#[tauri::command]
+pub fn sign_off_review(
+    repo_path: String,
+    r#ref: String,
+    by: String,
+) -> Result<TwoPersonProgress, String> {
+    let progress = two_person::sign_off(&PathBuf::from(&repo_path), &r#ref, &by)
+        .map_err(|e| format!("{e:#}"))?;
+    info!("sign_off_review {} by {by}", r#ref);
+    Ok(progress)
+}
+
+/// Record review activity (or a pause) for session time tracking.
+#[tauri::command]
+pub fn record_review_activity(
+    repo_path: String,
+    r#ref: String,
+    event: review::review::sessions::SessionEvent,
+) -> Result<(), String> {
+    review::review::sessions::record_activity(&PathBuf::from(&repo_path), &r#ref, event)
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn delete_review(repo_path: String, r#ref: String) -> Result<(), String> {
     storage::delete_review(&PathBuf::from(&repo_path), &r#ref).map_err(|e| e.to_string())
 }
 
+/// Archive a review — compressed, hidden from listings and freshness checks,
+/// restorable with `unarchive_review`.
+#[tauri::command]
+pub fn archive_review(repo_path: String, r#ref: String) -> Result<ReviewState, String> {
+    let t0 = Instant::now();
+    let state =
+        storage::archive_review(&PathBuf::from(&repo_path), &r#ref).map_err(|e| e.to_string())?;
+    info!("archive_review {} in {:?}", r#ref, t0.elapsed());
+    Ok(state)
+}
+
+#[tauri::command]
+pub fn unarchive_review(repo_path: String, r#ref: String) -> Result<ReviewState, String> {
+    let t0 = Instant::now();
+    let state =
+        storage::unarchive_review(&PathBuf::from(&repo_path), &r#ref).map_err(|e| e.to_string())?;
+    info!("unarchive_review {} in {:?}", r#ref, t0.elapsed());
+    Ok(state)
+}
+
+#[tauri::command]
+pub fn list_archived_reviews(repo_path: String) -> Result<Vec<ReviewSummary>, String> {
+    storage::list_archived_reviews(&PathBuf::from(&repo_path)).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn review_exists(repo_path: String, r#ref: String) -> Result<bool, String> {
     storage::review_exists(&PathBuf::from(&repo_path), &r#ref).map_err(|e| e.to_string())
@@ -431,6 +842,30 @@ pub fn fetch_origin(repo_path: String) -> Result<(), String> {
     Ok(())
 }
 
+/// Extend a shallow clone's history, after a `SHALLOW_CLONE:` error.
+#[tauri::command]
+pub fn deepen_clone(repo_path: String) -> Result<(), String> {
+    let source = LocalGitSource::new(PathBuf::from(&repo_path)).map_err(|e| e.to_string())?;
+    source.deepen(DEEPEN_STEP).map_err(|e| e.to_string())
+}
+
+/// The repo's remotes, flagging the one reviews resolve against.
+#[tauri::command]
+pub fn list_remotes(repo_path: String) -> Result<Vec<GitRemote>, String> {
+    let source = LocalGitSource::new(PathBuf::from(&repo_path)).map_err(|e| e.to_string())?;
+    source.list_remotes().map_err(|e| e.to_string())
+}
+
+/// Pick the remote reviews resolve their base branch and PR host from;
+/// `None` goes back to the default.
+#[tauri::command]
+pub fn set_review_remote(repo_path: String, remote: Option<String>) -> Result<(), String> {
+    let source = LocalGitSource::new(PathBuf::from(&repo_path)).map_err(|e| e.to_string())?;
+    source
+        .set_review_remote(remote.as_deref())
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub fn get_default_branch(repo_path: String) -> Result<String, String> {
     let source = LocalGitSource::new(PathBuf::from(&repo_path)).map_err(|e| e.to_string())?;
@@ -598,6 +1033,17 @@ pub fn stage_hunks(
         .map_err(|e| e.to_string())
 }
 
+/// Stage all hunks of a review guide group at once; returns the staged hunk IDs.
+#[tauri::command]
+pub fn stage_hunk_group(
+    repo_path: String,
+    r#ref: String,
+    group_index: usize,
+) -> Result<Vec<String>, String> {
+    review::service::staging::stage_hunk_group(&PathBuf::from(&repo_path), &r#ref, group_index)
+        .map_err(|e| format!("{e:#}"))
+}
+
 #[tauri::command]
 pub fn unstage_hunks(
     repo_path: String,
@@ -727,20 +1173,71 @@ pub fn check_claude_available() -> bool {
 }
 
 #[tauri::command]
-pub fn classify_hunks_static(hunks: Vec<DiffHunk>) -> ClassifyResponse {
+pub fn is_offline() -> bool {
+    review::offline::is_offline()
+}
+
+#[tauri::command]
+pub async fn classify_hunks_static(
+    hunks: Vec<DiffHunk>,
+    repo_path: Option<String>,
+) -> Result<ClassifyResponse, String> {
     let t0 = Instant::now();
     debug!(
         "[classify_hunks_static] Classifying {} hunks with static rules",
         hunks.len()
     );
-    let result = classify::classify_hunks_static(&hunks);
+    let label = format!("Classify {} hunks", hunks.len());
+    let (result, total) = jobs::run_blocking(
+        JobKind::Classification,
+        JobPriority::Interactive,
+        label,
+        move || {
+            let limits = ReviewLimits::from_env();
+            let mut response = classify::classify_hunks_static_limited(&hunks, &limits);
+            if let Some(repo_path) = &repo_path {
+                classify::apply_sensitive_paths(
+                    std::path::Path::new(repo_path),
+                    &hunks,
+                    &mut response,
+                );
+            }
+            // Hunks the limits skipped stay unclassified.
+            let fitting: Vec<DiffHunk> = hunks
+                .iter()
+                .filter(|h| h.content.len() <= limits.max_file_bytes)
+                .cloned()
+                .collect();
+            classify::apply_heuristic_labels(&fitting, &mut response);
+            if let Some(repo_path) = &repo_path {
+                classify::apply_label_precedence(std::path::Path::new(repo_path), &mut response);
+            }
+            (response, hunks.len())
+        },
+    )
+    .await
+    .map_err(|e| e.to_string())?;
     info!(
         "[classify_hunks_static] Classified {} of {} hunks in {:?}",
         result.classifications.len(),
-        hunks.len(),
+        total,
         t0.elapsed()
     );
-    result
+    Ok(result)
+}
+
+/// Queued and running background jobs, for the background tasks panel.
+#[tauri::command]
+pub fn get_job_queue() -> jobs::JobQueueSnapshot {
+    jobs::snapshot()
+}
+
+/// Change how many scheduled jobs may run at once.
+#[tauri::command]
+pub fn set_job_parallelism(max_parallel: usize) -> jobs::JobQueueSnapshot {
+    let applied = jobs::set_max_parallel(max_parallel);
+    info!("[set_job_parallelism] max_parallel={applied}");
+    jobs::snapshot()
 }
 
 #[tauri::command]
@@ -763,6 +1260,12 @@ pub fn detect_hunks_move_pairs(mut hunks: Vec<DiffHunk>) -> DetectMovePairsRespo
     review::service::DetectMovePairsResponse { pairs, hunks }
 }
 
+/// Diff two in-memory texts, e.g. a file and a suggested edit to it, without git.
+#[tauri::command]
+pub fn diff_contents(old_text: String, new_text: String, path_hint: String) -> Vec<DiffHunk> {
+    review::diff::contents::diff_contents(&old_text, &new_text, &path_hint)
+}
+
 /// Validate that a path is within .git/review/ or ~/.review/ for security
 fn validate_review_path(path: &str) -> Result<PathBuf, String> {
     review::service::util::validate_review_path(path).map_err(|e| e.to_string())
@@ -817,6 +1320,31 @@ pub fn get_expanded_context(
     .map_err(|e| e.to_string())
 }
 
+/// Read one window of a file whose content `get_file_content` deferred.
+#[tauri::command]
+pub async fn get_file_content_window(
+    repo_path: String,
+    file_path: String,
+    comparison: Comparison,
+    side: ContentSide,
+    window: ContentWindow,
+    github_pr: Option<GitHubPrRef>,
+) -> Result<FileContentWindow, String> {
+    tokio::task::spawn_blocking(move || {
+        review::service::files::get_file_content_window(
+            &PathBuf::from(&repo_path),
+            &file_path,
+            &comparison,
+            side,
+            window,
+            github_pr.as_ref(),
+        )
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 pub fn match_trust_pattern(label: String, pattern: String) -> bool {
     review::trust::matches_pattern(&label, &pattern)
@@ -843,15 +1371,24 @@ pub fn stop_file_watcher(app: tauri::AppHandle, repo_path: String) {
     super::watchers::stop_watching(&repo_path, app);
 }
 
-/// Consume a pending CLI open request (signal file written by the `review` CLI).
-/// Returns `Some(CliOpenRequest)` on cold start when the CLI launched the app,
-/// or `None` if there is no pending request.
-#[tauri::command]
-pub fn consume_cli_request() -> Option<CliOpenRequest> {
-    let req = super::read_open_request()?;
+/// Consume a pending open request: a deep link or file open that arrived
+/// before the window, else the signal file written by the `review` CLI.
+/// Returns `Some(CliOpenRequest)` on such a cold start, or `None` if there is
+/// no pending request.
+#[tauri::command]
+pub fn consume_cli_request(
+    pending: tauri::State<'_, super::PendingOpen>,
+) -> Option<CliOpenRequest> {
+    let req = pending
+        .0
+        .lock()
+        .unwrap()
+        .take()
+        .or_else(super::read_open_request)?;
     Some(CliOpenRequest {
         repo_path: req.repo_path,
         ref_name: req.ref_name,
+        base_override: req.base_override,
         focused_file: req.focused_file,
         focused_hunk_hash: req.focused_hunk_hash,
     })
@@ -863,6 +1400,8 @@ pub struct CliOpenRequest {
     pub repo_path: String,
     #[serde(rename = "ref")]
     pub ref_name: Option<String>,
+    #[serde(rename = "baseOverride")]
+    pub base_override: Option<String>,
     #[serde(rename = "focusedFile")]
     pub focused_file: Option<String>,
     #[serde(rename = "focusedHunkHash")]
@@ -870,6 +1409,15 @@ pub struct CliOpenRequest {
 }
 
 // Multi-window support
+
+/// The label of a repo's window. Hashed on the repo path only — one window
+/// per repo.
+pub fn repo_window_label(repo_path: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    repo_path.hash(&mut hasher);
+    format!("repo-{:x}", hasher.finish())
+}
+
 #[tauri::command]
 pub async fn open_repo_window(
     app: tauri::AppHandle,
@@ -918,10 +1466,7 @@ pub async fn open_repo_window(
         return Ok(());
     }
 
-    // Hash window label on repo_path only — one window per repo
-    let mut hasher = DefaultHasher::new();
-    repo_path.hash(&mut hasher);
-    let label = format!("repo-{:x}", hasher.finish());
+    let label = repo_window_label(&repo_path);
 
     // If a window already exists for this repo, reuse it
     if let Some(existing) = app.get_webview_window(&label) {
@@ -997,6 +1542,42 @@ pub async fn get_file_symbol_diffs(
     .map_err(|e| e.to_string())?
 }
 
+#[tauri::command]
+pub async fn get_api_schema_deltas(
+    repo_path: String,
+    file_paths: Vec<String>,
+    comparison: Comparison,
+) -> Result<Vec<FileSchemaDelta>, String> {
+    tokio::task::spawn_blocking(move || {
+        review::service::schemas::get_api_schema_deltas(
+            &PathBuf::from(&repo_path),
+            &file_paths,
+            &comparison,
+        )
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn get_migration_checks(
+    repo_path: String,
+    file_paths: Vec<String>,
+    comparison: Comparison,
+) -> Result<Vec<MigrationCheck>, String> {
+    tokio::task::spawn_blocking(move || {
+        review::service::migrations::get_migration_checks(
+            &PathBuf::from(&repo_path),
+            &file_paths,
+            &comparison,
+        )
+        .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 #[tauri::command]
 pub async fn get_dependency_graph(
     repo_path: String,
@@ -1133,102 +1714,23 @@ pub async fn get_file_raw_content(
 
 // --- CLI sidecar install ---
 
-/// Well-known install location for the `review` CLI symlink.
-const CLI_SYMLINK_PATH: &str = "/usr/local/bin/review";
-
-#[derive(Debug, Serialize)]
-pub struct CliInstallStatus {
-    pub installed: bool,
-    pub symlink_target: Option<String>,
-}
+pub use super::cli_install::CliInstallStatus;
 
 #[tauri::command]
-pub fn get_cli_install_status() -> CliInstallStatus {
-    let path = std::path::Path::new(CLI_SYMLINK_PATH);
-    match std::fs::read_link(path) {
-        Ok(target) => CliInstallStatus {
-            installed: true,
-            symlink_target: Some(target.to_string_lossy().to_string()),
-        },
-        Err(_) => CliInstallStatus {
-            installed: false,
-            symlink_target: None,
-        },
-    }
-}
-
-/// Run a shell command with administrator privileges via osascript.
-/// Returns an error if the user cancels or the command fails.
-fn run_admin_shell_command(shell_command: &str, cancel_message: &str) -> Result<(), String> {
-    let script = format!(
-        "do shell script \"{}\" with administrator privileges",
-        shell_command
-    );
-
-    let output = std::process::Command::new("osascript")
-        .args(["-e", &script])
-        .output()
-        .map_err(|e| format!("Failed to run osascript: {e}"))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if stderr.contains("User canceled") || stderr.contains("(-128)") {
-            return Err(cancel_message.to_string());
-        }
-        return Err(stderr.trim().to_string());
-    }
-
-    Ok(())
+pub fn get_cli_install_status() -> Result<CliInstallStatus, String> {
+    super::cli_install::status()
 }
 
+/// Put the bundled CLI on PATH as `review`; see [`super::cli_install`] for
+/// where each platform installs it. Returns the sidecar's path.
 #[tauri::command]
-pub fn install_cli(app: tauri::AppHandle) -> Result<String, String> {
-    use tauri::Manager;
-
-    // The sidecar binary lives next to the main binary inside the app bundle:
-    //   Review.app/Contents/MacOS/review-cli
-    let resource_dir = app
-        .path()
-        .resource_dir()
-        .map_err(|e| format!("Could not determine resource dir: {e}"))?;
-
-    // resource_dir points to Contents/Resources; the binary is in Contents/MacOS
-    let sidecar_path = resource_dir
-        .parent()
-        .ok_or("Could not determine app bundle path")?
-        .join("MacOS")
-        .join("review-cli");
-
-    if !sidecar_path.exists() {
-        return Err(format!(
-            "Sidecar binary not found at {}",
-            sidecar_path.display()
-        ));
-    }
-
-    let shell_command = format!("ln -sf '{}' '{}'", sidecar_path.display(), CLI_SYMLINK_PATH);
-    run_admin_shell_command(&shell_command, "Installation cancelled")
-        .map_err(|e| format!("Failed to create symlink: {e}"))?;
-
-    info!(
-        "[install_cli] Symlinked {} -> {}",
-        CLI_SYMLINK_PATH,
-        sidecar_path.display()
-    );
-    Ok(sidecar_path.to_string_lossy().to_string())
+pub fn install_cli() -> Result<String, String> {
+    super::cli_install::install()
 }
 
 #[tauri::command]
 pub fn uninstall_cli() -> Result<(), String> {
-    let symlink_path = std::path::Path::new(CLI_SYMLINK_PATH);
-    if symlink_path.symlink_metadata().is_ok() {
-        let shell_command = format!("rm '{}'", CLI_SYMLINK_PATH);
-        run_admin_shell_command(&shell_command, "Uninstall cancelled")
-            .map_err(|e| format!("Failed to remove symlink: {e}"))?;
-
-        info!("[uninstall_cli] Removed {CLI_SYMLINK_PATH}");
-    }
-    Ok(())
+    super::cli_install::uninstall()
 }
 
 // --- Menu state ---
@@ -1345,60 +1847,89 @@ pub async fn generate_commit_message(
 
 // --- Settings file I/O ---
 
-/// Return the path to `~/.review/settings.json` (respects `$REVIEW_HOME`).
-fn settings_path() -> Result<PathBuf, String> {
-    let root = review::review::central::get_central_root().map_err(|e| e.to_string())?;
-    Ok(root.join("settings.json"))
+/// Read `~/.review/settings.json` with defaults filled in, plus any unknown
+/// keys or invalid values it contains.
+#[tauri::command]
+pub fn read_settings() -> Result<review::service::settings::SettingsFile, String> {
+    review::service::settings::read_settings().map_err(|e| e.to_string())
 }
 
-/// Read a single key from `settings.json`. Returns `None` if the file or key is missing.
-pub fn read_setting(key: &str) -> Option<serde_json::Value> {
-    let path = settings_path().ok()?;
-    let content = std::fs::read_to_string(&path).ok()?;
-    let obj: serde_json::Value = serde_json::from_str(&content).ok()?;
-    obj.get(key).cloned()
+/// Validate the settings and atomically write them to `settings.json`.
+#[tauri::command]
+pub fn write_settings(settings: serde_json::Value) -> Result<(), String> {
+    review::service::settings::write_settings(&settings).map_err(|e| e.to_string())
 }
 
-/// Atomically write JSON to a file (write tmp + rename).
-fn atomic_write_json(path: &std::path::Path, value: &serde_json::Value) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
-    let tmp = path.with_extension("json.tmp");
-    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
-    std::fs::write(&tmp, json.as_bytes()).map_err(|e| e.to_string())?;
-    std::fs::rename(&tmp, path).map_err(|e| e.to_string())?;
-    Ok(())
+/// Every setting's type, allowed values, and default, for the preferences UI.
+#[tauri::command]
+pub fn get_settings_schema() -> Vec<review::service::settings::SettingSchema> {
+    review::service::settings::settings_schema()
 }
 
-/// Read the entire `settings.json` file. Returns `null` if the file doesn't exist.
+/// This machine's tailnet IPv4 address, if Tailscale is connected.
 #[tauri::command]
-pub fn read_settings() -> Result<Option<serde_json::Value>, String> {
-    let path = settings_path()?;
-    if !path.exists() {
-        return Ok(None);
-    }
-    let content = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
-    let value: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-    Ok(Some(value))
+pub async fn get_tailscale_ip() -> Result<Option<String>, String> {
+    tokio::task::spawn_blocking(review::service::tailscale::tailscale_ip)
+        .await
+        .map_err(|e| e.to_string())
 }
 
-/// Atomically write the full settings JSON to `settings.json`.
+/// Tailscale's state, and how the review server on `port` is exposed through it.
 #[tauri::command]
-pub fn write_settings(settings: serde_json::Value) -> Result<(), String> {
-    let path = settings_path()?;
-    atomic_write_json(&path, &settings)
+pub async fn get_tailscale_status(
+    port: u16,
+) -> Result<review::service::tailscale::TailscaleStatus, String> {
+    tokio::task::spawn_blocking(move || review::service::tailscale::tailscale_status(port))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Expose the review server on `port` through Tailscale Serve, or Funnel
+/// with `funnel`.
+#[tauri::command]
+pub async fn tailscale_expose(
+    port: u16,
+    funnel: bool,
+) -> Result<review::service::tailscale::Exposure, String> {
+    use review::service::tailscale::{expose, ExposeMode};
+    let mode = if funnel {
+        ExposeMode::Funnel
+    } else {
+        ExposeMode::Serve
+    };
+    tokio::task::spawn_blocking(move || expose(port, mode))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Stop exposing the review server on `port`. Returns whether it was exposed.
+#[tauri::command]
+pub async fn tailscale_unexpose(port: u16) -> Result<bool, String> {
+    tokio::task::spawn_blocking(move || review::service::tailscale::unexpose(port))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{e:#}"))
+}
+
+/// Re-register the global quick-action shortcuts after the frontend saves
+/// `quickActionShortcuts`. Returns each action's binding and whether the OS
+/// accepted it.
+#[tauri::command]
+pub fn apply_quick_action_shortcuts(
+    app: tauri::AppHandle,
+    overrides: HashMap<String, String>,
+) -> Vec<super::shortcuts::QuickActionBinding> {
+    super::shortcuts::apply(&app, &overrides)
 }
 
 /// Create the settings file if it doesn't exist, then open it with the system editor.
 #[tauri::command]
 pub fn open_settings_file(app: tauri::AppHandle) -> Result<(), String> {
     use tauri_plugin_opener::OpenerExt;
-    let path = settings_path()?;
-    if !path.exists() {
-        // Create with empty object so the user has a valid JSON file to edit
-        atomic_write_json(&path, &serde_json::json!({}))?;
-    }
+    // Create with empty object so the user has a valid JSON file to edit
+    let path = review::service::settings::ensure_settings_file().map_err(|e| e.to_string())?;
     app.opener()
         .open_path(path.to_string_lossy().as_ref(), None::<&str>)
         .map_err(|e| e.to_string())
@@ -3,8 +3,12 @@
 //! This module contains all Tauri-specific code including:
 //! - Command handlers (commands.rs)
 //! - File system watchers (watchers.rs)
+//! - CLI install per platform (cli_install.rs)
+//! - Global quick-action shortcuts (shortcuts.rs)
 
+pub mod cli_install;
 pub mod commands;
+pub mod shortcuts;
 pub mod watchers;
 
 // Re-export commands for convenient access
@@ -47,16 +51,40 @@ fn open_request_path() -> std::path::PathBuf {
     std::path::PathBuf::from(tmp).join("review-open-request")
 }
 
-/// Parsed signal-file payload. The 5th `focused_hunk_hash` line is optional;
-/// older callers (the CLI) only write 4 lines and leave it implicitly absent.
+/// What to open: a signal-file payload, a deep link, or forwarded CLI args.
+/// The signal file's 5th `focused_hunk_hash` line is optional; older CLIs only
+/// write 4 lines and leave it implicitly absent. Only deep links pin a base.
 #[cfg(desktop)]
+#[derive(Debug, Default)]
 pub struct OpenRequest {
     pub repo_path: String,
     pub ref_name: Option<String>,
+    pub base_override: Option<String>,
     pub focused_file: Option<String>,
     pub focused_hunk_hash: Option<String>,
 }
 
+/// An open request that arrived before any window could take it (a cold-start
+/// deep link or file open), held in memory until the frontend asks for it via
+/// `consume_cli_request`. Only the CLI, a separate process, still needs the
+/// signal file.
+#[cfg(desktop)]
+#[derive(Default)]
+pub struct PendingOpen(pub std::sync::Mutex<Option<OpenRequest>>);
+
+/// Navigate an existing window to `req`, or hold it for the frontend's
+/// startup when no window exists yet.
+#[cfg(desktop)]
+fn open_or_queue(app: &tauri::AppHandle, req: OpenRequest) {
+    if app.webview_windows().is_empty() {
+        if let Some(pending) = app.try_state::<PendingOpen>() {
+            *pending.0.lock().unwrap() = Some(req);
+        }
+    } else {
+        emit_cli_open_review(app, &req);
+    }
+}
+
 /// Read and delete the signal file. Returns the parsed payload if the file
 /// exists and was written recently (within 30 seconds).
 #[cfg(desktop)]
@@ -93,6 +121,7 @@ fn read_open_request() -> Option<OpenRequest> {
         Some(OpenRequest {
             repo_path,
             ref_name,
+            base_override: None,
             focused_file,
             focused_hunk_hash,
         })
@@ -101,22 +130,21 @@ fn read_open_request() -> Option<OpenRequest> {
 
 /// Emit a `cli:open-review` event to an existing window so the frontend
 /// navigates to the requested review instead of opening a new window/tab.
+/// The repo's own window takes it when one is open.
 #[cfg(desktop)]
-fn emit_cli_open_review(
-    app: &tauri::AppHandle,
-    repo_path: &str,
-    ref_name: Option<&str>,
-    focused_file: Option<&str>,
-    focused_hunk_hash: Option<&str>,
-) {
-    if let Some((_, window)) = app.webview_windows().into_iter().next() {
+fn emit_cli_open_review(app: &tauri::AppHandle, req: &OpenRequest) {
+    let window = app
+        .get_webview_window(&commands::repo_window_label(&req.repo_path))
+        .or_else(|| app.webview_windows().into_values().next());
+    if let Some(window) = window {
         let _ = window.emit(
             "cli:open-review",
             serde_json::json!({
-                "repoPath": repo_path,
-                "ref": ref_name,
-                "focusedFile": focused_file,
-                "focusedHunkHash": focused_hunk_hash,
+                "repoPath": req.repo_path,
+                "ref": req.ref_name,
+                "baseOverride": req.base_override,
+                "focusedFile": req.focused_file,
+                "focusedHunkHash": req.focused_hunk_hash,
             }),
         );
         let _ = window.show();
@@ -133,6 +161,8 @@ fn emit_cli_open_review(
 /// a broadcast if none reports focus.
 #[cfg(desktop)]
 fn emit_menu_event<P: serde::Serialize + Clone>(app: &tauri::AppHandle, event: &str, payload: P) {
+    let feature = format!("menu.{}", event.trim_start_matches("menu:"));
+    std::thread::spawn(move || review::metrics::record(&feature));
     // `Manager::get_focused_window` would be cleaner but is gated behind Tauri's
     // `unstable` feature, so find the focused window via the stable API instead.
     let focused = app
@@ -147,93 +177,32 @@ fn emit_menu_event<P: serde::Serialize + Clone>(app: &tauri::AppHandle, event: &
     }
 }
 
-/// Parse a `review://open?repo=&ref=&file=&hunk=` URL into the parts
-/// `emit_cli_open_review` needs. Returns `None` for unrecognized URLs
-/// (wrong scheme, missing or unknown repo id, etc.).
+/// Parse a `review://` link (the `review://<repo-id>/<ref>/<hunk-id>`
+/// permalink or the `review://open?repo=&ref=&file=&hunk=` form) into an
+/// open request. Returns `None` for unrecognized URLs (wrong scheme, missing
+/// or unknown repo id, etc.).
 #[cfg(desktop)]
-fn parse_review_url(raw: &str) -> Option<(String, Option<String>, Option<String>, Option<String>)> {
-    let url = url::Url::parse(raw).ok()?;
-    if url.scheme() != "review" {
-        return None;
-    }
-
-    let mut repo_id: Option<String> = None;
-    let mut review_ref: Option<String> = None;
-    let mut file: Option<String> = None;
-    let mut hunk: Option<String> = None;
-
-    for (key, value) in url.query_pairs() {
-        match key.as_ref() {
-            "repo" => repo_id = Some(value.into_owned()),
-            "ref" => review_ref = Some(value.into_owned()),
-            "file" => file = Some(value.into_owned()),
-            "hunk" => hunk = Some(value.into_owned()),
-            _ => {}
-        }
-    }
-
-    let repo_id = repo_id?;
-    let entry = review::review::central::get_registered_repo(&repo_id)
-        .ok()
-        .flatten()?;
-
-    Some((entry.path, review_ref, file, hunk))
+fn parse_review_url(raw: &str) -> Option<OpenRequest> {
+    let link = review::review::permalink::ReviewLink::parse(raw)?;
+    Some(OpenRequest {
+        repo_path: link.repo_path()?,
+        ref_name: link.review_ref,
+        base_override: link.base_override,
+        focused_file: link.file,
+        focused_hunk_hash: link.hunk_hash,
+    })
 }
 
-/// Handle a `review://` URL: parse it, then either navigate the running
-/// window or write a signal file for the next launch. `app_handle` is used
-/// to emit into an existing window; the signal-file fallback is for the
-/// cold-start case where no webview exists yet.
+/// Handle a `review://` URL: parse it, then navigate the repo's window, or
+/// hold it for the frontend when the app is still starting.
 #[cfg(desktop)]
 fn handle_deep_link(app: &tauri::AppHandle, raw: &str) {
-    let Some((repo_path, review_ref, file, hunk)) = parse_review_url(raw) else {
+    let Some(req) = parse_review_url(raw) else {
         log::warn!("Ignoring unrecognized deep link: {}", raw);
         return;
     };
     log::info!("Deep link opened: {}", raw);
-
-    if app.webview_windows().is_empty() {
-        // Cold start — frontend not ready yet. Drop a signal file the
-        // startup code reads (matches the CLI's existing channel).
-        write_open_request(
-            &repo_path,
-            review_ref.as_deref(),
-            file.as_deref(),
-            hunk.as_deref(),
-        );
-        return;
-    }
-
-    emit_cli_open_review(
-        app,
-        &repo_path,
-        review_ref.as_deref(),
-        file.as_deref(),
-        hunk.as_deref(),
-    );
-}
-
-/// Write the signal file used by `read_open_request`. Always writes 5
-/// lines (timestamp + 4 fields, blank for missing) for forward
-/// compatibility with older readers that only consumed 4.
-#[cfg(desktop)]
-fn write_open_request(
-    repo_path: &str,
-    ref_name: Option<&str>,
-    focused_file: Option<&str>,
-    focused_hunk_hash: Option<&str>,
-) {
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-    let content = format!(
-        "{now}\n{repo_path}\n{}\n{}\n{}",
-        ref_name.unwrap_or(""),
-        focused_file.unwrap_or(""),
-        focused_hunk_hash.unwrap_or(""),
-    );
-    let _ = std::fs::write(open_request_path(), content);
+    open_or_queue(app, req);
 }
 
 /// Run the Tauri desktop application.
@@ -261,7 +230,7 @@ pub fn run() {
             release: sentry::release_name!(),
             environment: Some("production".into()),
             before_send: Some(Arc::new(move |mut event| {
-                if !consent_for_hook.load(Ordering::Relaxed) {
+                if !consent_for_hook.load(Ordering::Relaxed) || review::offline::is_offline() {
                     return None;
                 }
                 // Strip PII fields
@@ -303,14 +272,14 @@ pub fn run() {
                     .cloned()
                     .collect();
                 if let Some(repo) = non_flag_args.first().cloned() {
-                    let ref_name = non_flag_args.get(1).cloned();
-                    let focused_file = non_flag_args.get(2).cloned();
                     emit_cli_open_review(
                         app,
-                        &repo,
-                        ref_name.as_deref(),
-                        focused_file.as_deref(),
-                        None,
+                        &OpenRequest {
+                            repo_path: repo,
+                            ref_name: non_flag_args.get(1).cloned(),
+                            focused_file: non_flag_args.get(2).cloned(),
+                            ..OpenRequest::default()
+                        },
                     );
                 }
             },
@@ -326,22 +295,22 @@ pub fn run() {
                 .level_for("notify", log::LevelFilter::Warn)
                 .level_for("notify_debouncer_mini", log::LevelFilter::Warn);
 
-            // In dev mode, also write logs to ~/.review/app.log so we can
-            // read traces for debugging (same file the frontend logger uses).
-            if cfg!(debug_assertions) {
-                if let Some(home) = dirs::home_dir() {
-                    let review_dir = home.join(".review");
-                    let _ = std::fs::create_dir_all(&review_dir);
-                    builder = builder
-                        .target(tauri_plugin_log::Target::new(
-                            tauri_plugin_log::TargetKind::Folder {
-                                path: review_dir,
-                                file_name: Some("app.log".into()),
-                            },
-                        ))
-                        .max_file_size(10_000_000) // 10 MB
-                        .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepOne);
-                }
+            // Also write logs to ~/.review/logs/app.log, where
+            // `review support-bundle` collects them (and, in dev, the
+            // frontend logger writes too).
+            if let Ok(logs_dir) = review::logs::logs_dir() {
+                let _ = std::fs::create_dir_all(&logs_dir);
+                builder = builder
+                    .target(tauri_plugin_log::Target::new(
+                        tauri_plugin_log::TargetKind::Folder {
+                            path: logs_dir,
+                            file_name: Some("app".into()),
+                        },
+                    ))
+                    .max_file_size(review::logs::MAX_LOG_BYTES.into())
+                    .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepSome(
+                        review::logs::KEEP_ROTATED,
+                    ));
             }
 
             builder.build()
@@ -357,12 +326,42 @@ pub fn run() {
     let builder = builder
         .plugin(tauri_plugin_window_state::Builder::new().build())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .manage(PendingOpen::default())
         .setup(move |app| {
+            // On Linux and Windows a cold-start deep link arrives as the first
+            // instance's own argument; hold it for the frontend.
+            if let Some(raw) = std::env::args()
+                .skip(1)
+                .find(|a| a.starts_with("review://"))
+            {
+                match parse_review_url(&raw) {
+                    Some(req) => *app.state::<PendingOpen>().0.lock().unwrap() = Some(req),
+                    None => log::warn!("Ignoring unrecognized deep link: {}", raw),
+                }
+            }
+
             // Restore Sentry consent from persisted settings
-            if let Some(serde_json::Value::Bool(true)) = commands::read_setting("sentryEnabled") {
+            if review::service::settings::read_settings().is_ok_and(|f| f.settings.sentry_enabled) {
                 consent.store(true, Ordering::Relaxed);
             }
 
+            // A CLI installed as a copy (Windows, AppImage) doesn't follow app
+            // updates the way a symlink does.
+            #[cfg(not(debug_assertions))]
+            std::thread::spawn(cli_install::refresh_installed_copy);
+
+            // Secrets stored while no keychain was available move into it
+            // once there is one.
+            std::thread::spawn(|| {
+                if let Err(e) = review::secrets::migrate_plaintext_secrets() {
+                    log::warn!("Failed to migrate secrets to the keychain: {e}");
+                }
+            });
+
+            shortcuts::apply_from_settings(app.handle());
+
+            std::thread::spawn(review::metrics::submit_if_due);
+
             let close = MenuItemBuilder::new("Close")
                 .id("close")
                 .accelerator("CmdOrCtrl+W")
@@ -586,7 +585,7 @@ pub fn run() {
                 "restart_lsp" => emit_menu_event(app, "menu:restart-lsp", ()),
                 "settings" => emit_menu_event(app, "menu:open-settings", ()),
                 "check_for_updates" => emit_menu_event(app, "menu:check-for-updates", ()),
-                "install_cli" => match commands::install_cli(app.clone()) {
+                "install_cli" => match commands::install_cli() {
                     Ok(_) => emit_menu_event(app, "cli:installed", ()),
                     Err(e) => emit_menu_event(app, "cli:install-error", e),
                 },
@@ -623,10 +622,19 @@ pub fn run() {
             commands::get_current_repo,
             commands::check_github_available,
             commands::list_pull_requests,
+            commands::check_azure_available,
+            commands::list_azure_pull_requests,
+            commands::draft_pr_review,
+            commands::submit_pr_review,
+            commands::sync_pr_threads,
+            commands::sync_review_inbox,
             commands::get_current_branch,
             commands::get_git_user,
             commands::get_remote_info,
             commands::fetch_origin,
+            commands::deepen_clone,
+            commands::list_remotes,
+            commands::set_review_remote,
             commands::get_default_branch,
             commands::list_branches,
             commands::list_local_branches,
@@ -645,6 +653,7 @@ pub fn run() {
             commands::unstage_file,
             commands::unstage_all,
             commands::stage_hunks,
+            commands::stage_hunk_group,
             commands::unstage_hunks,
             commands::git_commit,
             commands::get_working_tree_file_content,
@@ -657,16 +666,34 @@ pub fn run() {
             commands::list_directory_contents,
             commands::get_file_content,
             commands::get_all_hunks,
+            commands::get_all_hunks_limited,
             commands::get_diff,
             commands::get_diff_shortstat,
+            commands::get_diff_shortstats,
             commands::get_expanded_context,
+            commands::get_file_content_window,
             commands::resolve_review,
             commands::load_review_state,
             commands::reconcile_review_state,
             commands::save_review_state,
             commands::list_saved_reviews,
             commands::set_base_override,
+            commands::get_review_progress,
+            commands::get_review_timeline,
+            commands::split_review,
+            commands::get_submodule_scopes,
+            commands::expand_submodule_hunk,
+            commands::get_review_checklist,
+            commands::set_review_checklist_item,
+            commands::get_two_person_progress,
+            commands::require_second_reviewer,
+            commands::hand_off_review,
+            commands::sign_off_review,
+            commands::record_review_activity,
             commands::delete_review,
+            commands::archive_review,
+            commands::unarchive_review,
+            commands::list_archived_reviews,
             commands::review_exists,
             commands::ensure_review_exists,
             commands::list_all_reviews_global,
@@ -675,8 +702,12 @@ pub fn run() {
             commands::consume_cli_request,
             commands::open_repo_window,
             commands::check_claude_available,
+            commands::is_offline,
             commands::classify_hunks_static,
+            commands::get_job_queue,
+            commands::set_job_parallelism,
             commands::detect_hunks_move_pairs,
+            commands::diff_contents,
             commands::write_text_file,
             commands::append_to_file,
             commands::start_file_watcher,
@@ -686,6 +717,8 @@ pub fn run() {
             commands::should_skip_file,
             commands::search_file_contents,
             commands::get_file_symbol_diffs,
+            commands::get_api_schema_deltas,
+            commands::get_migration_checks,
             commands::get_dependency_graph,
             commands::get_file_symbols,
             commands::get_repo_symbols,
@@ -703,7 +736,13 @@ pub fn run() {
             commands::set_window_background_color,
             commands::read_settings,
             commands::write_settings,
+            commands::get_settings_schema,
             commands::open_settings_file,
+            commands::get_tailscale_ip,
+            commands::get_tailscale_status,
+            commands::tailscale_expose,
+            commands::tailscale_unexpose,
+            commands::apply_quick_action_shortcuts,
             commands::path_is_file,
             commands::read_raw_file,
             commands::get_file_raw_content,
@@ -728,15 +767,12 @@ pub fn run() {
                     let _ = window.show();
                     let _ = window.set_focus();
                 }
-                // Check for a pending open request from the CLI or a cold-start deep link
-                if let Some(req) = read_open_request() {
-                    emit_cli_open_review(
-                        app_handle,
-                        &req.repo_path,
-                        req.ref_name.as_deref(),
-                        req.focused_file.as_deref(),
-                        req.focused_hunk_hash.as_deref(),
-                    );
+                // Check for a pending open request from a cold-start deep link or the CLI
+                let pending = app_handle
+                    .try_state::<PendingOpen>()
+                    .and_then(|pending| pending.0.lock().unwrap().take());
+                if let Some(req) = pending.or_else(read_open_request) {
+                    emit_cli_open_review(app_handle, &req);
                 }
             }
             #[cfg(target_os = "macos")]
@@ -755,22 +791,14 @@ pub fn run() {
                                     repo_path,
                                     focused_file
                                 );
-                                if app_handle.webview_windows().is_empty() {
-                                    write_open_request(
-                                        &repo_path,
-                                        None,
-                                        focused_file.as_deref(),
-                                        None,
-                                    );
-                                } else {
-                                    emit_cli_open_review(
-                                        app_handle,
-                                        &repo_path,
-                                        None,
-                                        focused_file.as_deref(),
-                                        None,
-                                    );
-                                }
+                                open_or_queue(
+                                    app_handle,
+                                    OpenRequest {
+                                        repo_path,
+                                        focused_file,
+                                        ..OpenRequest::default()
+                                    },
+                                );
                             }
                         }
                         "review" => {
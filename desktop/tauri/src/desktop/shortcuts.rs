@@ -0,0 +1,148 @@
+//! Global shortcuts for quick review actions, usable while another app (an
+//! editor, a terminal) has focus.
+//!
+//! Each [`QuickAction`] is bound to an accelerator: a built-in default,
+//! overridden per action by the `quickActionShortcuts` setting. A press emits
+//! the action's `shortcut:*` event to the review window, where the frontend
+//! maps it to a store action — it knows which hunk is current, so nothing
+//! review-specific happens here.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuickAction {
+    /// Approve the focused hunk.
+    ApproveHunk,
+    /// Move to the next hunk that still needs review.
+    NextUnreviewed,
+    /// Add or remove the focused hunk's label on the trust list.
+    ToggleTrust,
+}
+
+impl QuickAction {
+    pub const ALL: [Self; 3] = [Self::ApproveHunk, Self::NextUnreviewed, Self::ToggleTrust];
+
+    /// Key in the `quickActionShortcuts` setting.
+    fn key(self) -> &'static str {
+        match self {
+            Self::ApproveHunk => "approveHunk",
+            Self::NextUnreviewed => "nextUnreviewed",
+            Self::ToggleTrust => "toggleTrust",
+        }
+    }
+
+    /// Event emitted to the frontend.
+    fn event(self) -> &'static str {
+        match self {
+            Self::ApproveHunk => "shortcut:approve-hunk",
+            Self::NextUnreviewed => "shortcut:next-unreviewed",
+            Self::ToggleTrust => "shortcut:toggle-trust",
+        }
+    }
+
+    fn default_shortcut(self) -> &'static str {
+        match self {
+            Self::ApproveHunk => "CommandOrControl+Alt+A",
+            Self::NextUnreviewed => "CommandOrControl+Alt+N",
+            Self::ToggleTrust => "CommandOrControl+Alt+T",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickActionBinding {
+    pub action: QuickAction,
+    /// Empty when the action has no shortcut.
+    pub shortcut: String,
+    /// Whether the OS accepted the shortcut (another app may hold it).
+    pub registered: bool,
+}
+
+/// Accelerators currently registered by [`apply`].
+static REGISTERED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// The shortcut for each action: `overrides` (keyed by action, as in the
+/// setting) where given, else the default.
+fn resolve(overrides: &HashMap<String, String>) -> Vec<(QuickAction, String)> {
+    QuickAction::ALL
+        .into_iter()
+        .map(|action| {
+            let shortcut = overrides
+                .get(action.key())
+                .map_or(action.default_shortcut(), String::as_str);
+            (action, shortcut.trim().to_owned())
+        })
+        .collect()
+}
+
+/// The window a press should act on: the focused one, else the main window.
+fn target_window(app: &tauri::AppHandle) -> Option<tauri::WebviewWindow> {
+    let windows = app.webview_windows();
+    windows
+        .values()
+        .find(|w| w.is_focused().unwrap_or(false))
+        .or_else(|| windows.get("main"))
+        .or_else(|| windows.values().next())
+        .cloned()
+}
+
+/// Replace the registered quick-action shortcuts with those resolved from
+/// `overrides`.
+pub fn apply(
+    app: &tauri::AppHandle,
+    overrides: &HashMap<String, String>,
+) -> Vec<QuickActionBinding> {
+    let global_shortcut = app.global_shortcut();
+    let mut registered = REGISTERED.lock().unwrap_or_else(PoisonError::into_inner);
+    for shortcut in registered.drain(..) {
+        if let Err(e) = global_shortcut.unregister(shortcut.as_str()) {
+            warn!("[shortcuts] Failed to unregister {shortcut}: {e}");
+        }
+    }
+
+    resolve(overrides)
+        .into_iter()
+        .map(|(action, shortcut)| {
+            let ok = !shortcut.is_empty()
+                && global_shortcut
+                    .on_shortcut(shortcut.as_str(), move |app, _, event| {
+                        if event.state == ShortcutState::Pressed {
+                            if let Some(window) = target_window(app) {
+                                let _ = window.emit(action.event(), ());
+                            }
+                            std::thread::spawn(move || {
+                                review::metrics::record(&format!("shortcut.{}", action.key()));
+                            });
+                        }
+                    })
+                    .map_err(|e| warn!("[shortcuts] Failed to register {shortcut}: {e}"))
+                    .is_ok();
+            if ok {
+                registered.push(shortcut.clone());
+            }
+            QuickActionBinding {
+                action,
+                shortcut,
+                registered: ok,
+            }
+        })
+        .collect()
+}
+
+/// Register the shortcuts from settings.json.
+pub fn apply_from_settings(app: &tauri::AppHandle) {
+    let overrides = review::service::settings::read_settings()
+        .map(|file| file.settings.quick_action_shortcuts)
+        .unwrap_or_default();
+    let bindings = apply(app, &overrides);
+    let active = bindings.iter().filter(|b| b.registered).count();
+    info!("[shortcuts] Registered {active} quick-action shortcuts");
+}
@@ -8,7 +8,8 @@ use notify::RecursiveMode;
 use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
 use review::service::activity_cache::RefreshTrigger;
 use review::service::watcher_events::{
-    categorize_change, is_git_state_path, ChangeKind, GitChangedPayload,
+    categorize_change, is_git_state_path, is_worktree_state_path, linked_worktree_dirs, ChangeKind,
+    GitChangedPayload,
 };
 use review::service::EVENT_REPO_ACTIVITY_CHANGED;
 use std::collections::{BTreeSet, HashMap};
@@ -26,24 +27,10 @@ const WATCHER_DEBOUNCE_MS: u64 = 500;
 const EVENT_REVIEW_STATE_CHANGED: &str = "review-state-changed";
 const EVENT_GIT_CHANGED: &str = "git-changed";
 
-/// Log a message to the app.log file (for debugging watcher events, dev only)
+/// Log a message to the repo's log file (for debugging watcher events, dev only)
 #[cfg(debug_assertions)]
 fn log_to_file(repo_path: &Path, message: &str) {
-    use std::io::Write;
-
-    let log_path = if let Ok(dir) = review::review::central::get_repo_storage_dir(repo_path) {
-        dir.join("app.log")
-    } else {
-        return;
-    };
-    if let Ok(mut file) = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_path)
-    {
-        let timestamp = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ");
-        let _ = writeln!(file, "[{timestamp}] [WATCHER] {message}");
-    }
+    review::logs::append_repo_log(repo_path, &format!("[WATCHER] {message}"));
 }
 
 #[cfg(not(debug_assertions))]
@@ -129,6 +116,10 @@ pub fn start_watching(repo_path: &str, app: AppHandle) -> Result<(), String> {
     // Build gitignore matcher for this repo
     let gitignore = build_gitignore(&repo_path_buf).map(Arc::new);
 
+    // A linked worktree's HEAD, index and branches live outside its tree.
+    let worktree = linked_worktree_dirs(&repo_path_buf);
+    let worktree_git_dir = worktree.as_ref().map(|w| w.git_dir.clone());
+
     let app_clone = app.clone();
     let repo_for_closure = repo_path_str.clone();
     let repo_path_for_closure = repo_path_buf.clone();
@@ -160,6 +151,14 @@ pub fn start_watching(repo_path: &str, app: AppHandle) -> Result<(), String> {
                             continue;
                         }
 
+                        if worktree_git_dir
+                            .as_ref()
+                            .is_some_and(|dir| is_worktree_state_path(&event.path, dir))
+                        {
+                            git_state_changed = true;
+                            continue;
+                        }
+
                         // Skip gitignored paths (but not .git internal paths which we handle separately)
                         if !path_str.contains("/.git/")
                             && !path_str.contains("\\.git\\")
@@ -216,6 +215,14 @@ pub fn start_watching(repo_path: &str, app: AppHandle) -> Result<(), String> {
                         let _ = app_clone.emit(EVENT_REVIEW_STATE_CHANGED, &repo_for_closure);
                     }
 
+                    if !changed_paths.is_empty() {
+                        let paths: Vec<String> = changed_paths.iter().cloned().collect();
+                        review::service::symbols::update_repo_symbol_index(
+                            &repo_path_for_closure,
+                            &paths,
+                        );
+                    }
+
                     // Git state changes (index, HEAD, refs/heads) are a subset of
                     // working tree changes — emit git-changed for both.
                     if working_tree_changed || git_state_changed {
@@ -259,6 +266,17 @@ pub fn start_watching(repo_path: &str, app: AppHandle) -> Result<(), String> {
         .watch(&repo_path_buf, RecursiveMode::Recursive)
         .map_err(|e| format!("Failed to watch repository: {e}"))?;
 
+    if let Some(worktree) = &worktree {
+        debouncer
+            .watcher()
+            .watch(&worktree.git_dir, RecursiveMode::NonRecursive)
+            .ok();
+        debouncer
+            .watcher()
+            .watch(&worktree.refs_heads, RecursiveMode::Recursive)
+            .ok();
+    }
+
     // Also watch the repo's central storage dir for review state changes
     if let Ok(central_dir) = review::review::central::get_repo_storage_dir(&repo_path_buf) {
         if central_dir.exists() {
@@ -12,6 +12,9 @@ import type {
   Comparison,
   GitHubPrRef,
   PullRequest,
+  PrReviewDraft,
+  PrReviewOptions,
+  ThreadSync,
   CommitEntry,
   CommitDetail,
   HunkAttribution,
@@ -27,20 +30,29 @@ import type {
   TrustCategory,
   DiffHunk,
   DiffShortStat,
+  ShortStatRequest,
+  ShortStatResult,
   ClassifyResponse,
   DetectMovePairsResponse,
+  ContentSide,
+  ContentWindow,
   ExpandedContext,
+  FileContentWindow,
   SearchMatch,
   FileSymbol,
   RepoFileSymbols,
+  FileSchemaDelta,
+  MigrationCheck,
   FileSymbolDiff,
   SymbolDefinition,
   LspServerStatus,
   RemoteInfo,
+  GitRemote,
   RepoLocalActivity,
   ReviewFreshnessInput,
   ReviewFreshnessResult,
   WorktreeInfo,
+  SettingSchema,
 } from "../types";
 
 /**
@@ -83,9 +95,18 @@ export interface ApiClient {
   /** Get remote info (org/repo name and browse URL) */
   getRemoteInfo(repoPath: string): Promise<RemoteInfo | null>;
 
-  /** Run `git fetch --prune origin` for the repo. */
+  /** Run `git fetch --prune` of the review remote (`origin` by default). */
   fetchOrigin(repoPath: string): Promise<void>;
 
+  /** Run `git fetch --deepen` on a shallow clone, e.g. after a `SHALLOW_CLONE:` error. */
+  deepenClone(repoPath: string): Promise<void>;
+
+  /** List the repo's remotes, flagging the one reviews resolve against. */
+  listRemotes(repoPath: string): Promise<GitRemote[]>;
+
+  /** Set the remote the base branch and PR host are resolved from (null resets to the default). */
+  setReviewRemote(repoPath: string, remote: string | null): Promise<void>;
+
   /** Get the default branch (e.g., main or master) */
   getDefaultBranch(repoPath: string): Promise<string>;
 
@@ -114,6 +135,13 @@ export interface ApiClient {
     contentHashes: string[],
   ): Promise<void>;
 
+  /** Stage every hunk of a review guide group across files, all or nothing; resolves to the staged hunk IDs */
+  stageHunkGroup(
+    repoPath: string,
+    ref: string,
+    groupIndex: number,
+  ): Promise<string[]>;
+
   /** Unstage specific hunks in a file by content hash */
   unstageHunks(
     repoPath: string,
@@ -134,6 +162,9 @@ export interface ApiClient {
     comparison: Comparison,
   ): Promise<DiffShortStat>;
 
+  /** Diff statistics for many comparisons at once, cached by base/head SHA */
+  getDiffShortStats(comparisons: ShortStatRequest[]): Promise<ShortStatResult[]>;
+
   /** List recent commits */
   listCommits(
     repoPath: string,
@@ -160,6 +191,23 @@ export interface ApiClient {
   /** List open pull requests for the repository */
   listPullRequests(repoPath: string): Promise<PullRequest[]>;
 
+  /** The review `submitPrReview` would post: its event and summary body */
+  draftPrReview(
+    repoPath: string,
+    ref: string,
+    options?: PrReviewOptions,
+  ): Promise<PrReviewDraft>;
+
+  /** Post the review to its PR as an approve/request-changes/comment review */
+  submitPrReview(
+    repoPath: string,
+    ref: string,
+    options?: PrReviewOptions,
+  ): Promise<PrReviewDraft>;
+
+  /** Import the PR's review threads as comments on the review's hunks */
+  syncPrThreads(repoPath: string, ref: string, pr?: number): Promise<ThreadSync>;
+
   // ----- Worktree operations -----
 
   /** Create a review-managed worktree for the given git ref */
@@ -231,6 +279,23 @@ export interface ApiClient {
     githubPr?: GitHubPrRef,
   ): Promise<ExpandedContext>;
 
+  /** Read one window of a file whose content getFileContent deferred */
+  getFileContentWindow(
+    repoPath: string,
+    filePath: string,
+    comparison: Comparison,
+    side: ContentSide,
+    window: ContentWindow,
+    githubPr?: GitHubPrRef,
+  ): Promise<FileContentWindow>;
+
+  /** Diff two in-memory texts into hunks (e.g. to preview a suggestion), without git */
+  diffContents(
+    oldText: string,
+    newText: string,
+    pathHint: string,
+  ): Promise<DiffHunk[]>;
+
   /** Search file contents using git grep */
   searchFileContents(
     repoPath: string,
@@ -258,11 +323,13 @@ export interface ApiClient {
   /**
    * Carry persisted decisions forward onto the live diff `hunks` (already loaded
    * for display), returning the reconciled state and how many were carried.
-   * In-memory only — persisted on the next save.
+   * With `repoPath`, hunks another review in the repo approved unchanged are
+   * approved too. In-memory only — persisted on the next save.
    */
   reconcileReviewState(
     state: ReviewState,
     hunks: DiffHunk[],
+    repoPath?: string,
   ): Promise<ReviewLoadResult>;
 
   /**
@@ -292,6 +359,13 @@ export interface ApiClient {
   /** Delete a saved review */
   deleteReview(repoPath: string, ref: string): Promise<void>;
 
+  /** Record review activity, or a pause, for session time tracking */
+  recordReviewActivity(
+    repoPath: string,
+    ref: string,
+    event: "active" | "pause",
+  ): Promise<void>;
+
   /** Check whether a review file exists on disk */
   reviewExists(repoPath: string, ref: string): Promise<boolean>;
 
@@ -315,7 +389,11 @@ export interface ApiClient {
   // ----- Classification -----
 
   /** Classify hunks using static pattern matching (no AI) */
-  classifyHunksStatic(hunks: DiffHunk[]): Promise<ClassifyResponse>;
+  /** Static classification; with `repoPath`, also applies the repo's sensitive path policy. */
+  classifyHunksStatic(
+    hunks: DiffHunk[],
+    repoPath?: string,
+  ): Promise<ClassifyResponse>;
 
   /** Detect move pairs in hunks */
   detectMovePairs(hunks: DiffHunk[]): Promise<DetectMovePairsResponse>;
@@ -366,6 +444,20 @@ export interface ApiClient {
     comparison: Comparison,
   ): Promise<FileSymbolDiff[]>;
 
+  /** Compute semantic deltas for changed OpenAPI and protobuf definitions */
+  getApiSchemaDeltas(
+    repoPath: string,
+    filePaths: string[],
+    comparison: Comparison,
+  ): Promise<FileSchemaDelta[]>;
+
+  /** Check changed migrations for missing down halves and data loss */
+  getMigrationChecks(
+    repoPath: string,
+    filePaths: string[],
+    comparison: Comparison,
+  ): Promise<MigrationCheck[]>;
+
   /** Find symbol definitions by name across the repo */
   findSymbolDefinitions(
     repoPath: string,
@@ -421,6 +513,7 @@ export interface ApiClient {
   consumeCliRequest(): Promise<{
     repoPath: string;
     ref: string | null;
+    baseOverride?: string | null;
     focusedFile: string | null;
     focusedHunkHash: string | null;
   } | null>;
@@ -499,6 +592,11 @@ export interface ApiClient {
     tokenColors: unknown[];
   }>;
 
+  // ----- Settings -----
+
+  /** Type, allowed values, and default of every setting in settings.json */
+  getSettingsSchema(): Promise<SettingSchema[]>;
+
   /** Set the window background color (affects title bar on macOS) */
   setWindowBackgroundColor(r: number, g: number, b: number): Promise<void>;
 
@@ -22,16 +22,27 @@ import type {
   DetectMovePairsResponse,
   DiffHunk,
   DiffShortStat,
+  ShortStatRequest,
+  ShortStatResult,
+  ContentSide,
+  ContentWindow,
   ExpandedContext,
+  FileContentWindow,
   FileContent,
   FileEntry,
   FileSymbol,
+  FileSchemaDelta,
+  MigrationCheck,
   FileSymbolDiff,
   RepoFileSymbols,
   GitHubPrRef,
   GitStatusSummary,
   PullRequest,
+  PrReviewDraft,
+  PrReviewOptions,
+  ThreadSync,
   RemoteInfo,
+  GitRemote,
   RepoLocalActivity,
   ReviewFreshnessInput,
   ReviewFreshnessResult,
@@ -43,6 +54,7 @@ import type {
   SearchMatch,
   SymbolDefinition,
   LspServerStatus,
+  SettingSchema,
   TrustCategory,
   WorktreeInfo,
 } from "../types";
@@ -158,6 +170,18 @@ export class HttpClient implements ApiClient {
     await this.post<null>("/api/git/fetch-origin", { repoPath });
   }
 
+  async deepenClone(repoPath: string): Promise<void> {
+    await this.post<null>("/api/git/deepen", { repoPath });
+  }
+
+  async listRemotes(repoPath: string): Promise<GitRemote[]> {
+    return this.post("/api/git/remotes", { repoPath });
+  }
+
+  async setReviewRemote(repoPath: string, remote: string | null): Promise<void> {
+    await this.post<null>("/api/git/review-remote", { repoPath, remote });
+  }
+
   async getDefaultBranch(repoPath: string): Promise<string> {
     return this.post("/api/git/default-branch", { repoPath });
   }
@@ -198,6 +222,18 @@ export class HttpClient implements ApiClient {
     });
   }
 
+  async stageHunkGroup(
+    repoPath: string,
+    ref: string,
+    groupIndex: number,
+  ): Promise<string[]> {
+    return this.post("/api/git/stage-hunk-group", {
+      repoPath,
+      ref,
+      groupIndex,
+    });
+  }
+
   async unstageHunks(
     repoPath: string,
     filePath: string,
@@ -229,6 +265,12 @@ export class HttpClient implements ApiClient {
     return this.post("/api/git/diff-shortstat", { repoPath, comparison });
   }
 
+  async getDiffShortStats(
+    comparisons: ShortStatRequest[],
+  ): Promise<ShortStatResult[]> {
+    return this.post("/api/git/diff-shortstats", { comparisons });
+  }
+
   async listCommits(
     repoPath: string,
     limit?: number,
@@ -272,6 +314,34 @@ export class HttpClient implements ApiClient {
     return this.post("/api/github/pull-requests", { repoPath });
   }
 
+  async draftPrReview(
+    repoPath: string,
+    ref: string,
+    options: PrReviewOptions = {},
+  ): Promise<PrReviewDraft> {
+    return this.post("/api/github/review/draft", { repoPath, ref, ...options });
+  }
+
+  async submitPrReview(
+    repoPath: string,
+    ref: string,
+    options: PrReviewOptions = {},
+  ): Promise<PrReviewDraft> {
+    return this.post("/api/github/review/submit", {
+      repoPath,
+      ref,
+      ...options,
+    });
+  }
+
+  async syncPrThreads(
+    repoPath: string,
+    ref: string,
+    pr?: number,
+  ): Promise<ThreadSync> {
+    return this.post("/api/github/review/threads", { repoPath, ref, pr });
+  }
+
   // ----- Worktree operations -----
 
   async createReviewWorktree(
@@ -388,6 +458,36 @@ export class HttpClient implements ApiClient {
     });
   }
 
+  async getFileContentWindow(
+    repoPath: string,
+    filePath: string,
+    comparison: Comparison,
+    side: ContentSide,
+    window: ContentWindow,
+    githubPr?: GitHubPrRef,
+  ): Promise<FileContentWindow> {
+    return this.post("/api/files/content-window", {
+      repoPath,
+      filePath,
+      comparison,
+      side,
+      window,
+      githubPr: githubPr ?? null,
+    });
+  }
+
+  async diffContents(
+    oldText: string,
+    newText: string,
+    pathHint: string,
+  ): Promise<DiffHunk[]> {
+    return this.post("/api/files/diff-contents", {
+      oldText,
+      newText,
+      pathHint,
+    });
+  }
+
   async searchFileContents(
     repoPath: string,
     query: string,
@@ -423,8 +523,9 @@ export class HttpClient implements ApiClient {
   async reconcileReviewState(
     state: ReviewState,
     hunks: DiffHunk[],
+    repoPath?: string,
   ): Promise<ReviewLoadResult> {
-    return this.post("/api/review/reconcile", { state, hunks });
+    return this.post("/api/review/reconcile", { state, hunks, repoPath });
   }
 
   async saveReviewState(
@@ -455,6 +556,14 @@ export class HttpClient implements ApiClient {
     await this.post("/api/review/delete", { repoPath, ref });
   }
 
+  async recordReviewActivity(
+    repoPath: string,
+    ref: string,
+    event: "active" | "pause",
+  ): Promise<void> {
+    await this.post("/api/review/activity", { repoPath, ref, event });
+  }
+
   async reviewExists(repoPath: string, ref: string): Promise<boolean> {
     return this.post("/api/review/exists", { repoPath, ref });
   }
@@ -493,8 +602,11 @@ export class HttpClient implements ApiClient {
 
   // ----- Classification -----
 
-  async classifyHunksStatic(hunks: DiffHunk[]): Promise<ClassifyResponse> {
-    return this.post("/api/classify/static", { hunks });
+  async classifyHunksStatic(
+    hunks: DiffHunk[],
+    repoPath?: string,
+  ): Promise<ClassifyResponse> {
+    return this.post("/api/classify/static", { hunks, repoPath });
   }
 
   async detectMovePairs(hunks: DiffHunk[]): Promise<DetectMovePairsResponse> {
@@ -595,6 +707,30 @@ export class HttpClient implements ApiClient {
     });
   }
 
+  async getApiSchemaDeltas(
+    repoPath: string,
+    filePaths: string[],
+    comparison: Comparison,
+  ): Promise<FileSchemaDelta[]> {
+    return this.post("/api/schemas/deltas", {
+      repoPath,
+      filePaths,
+      comparison,
+    });
+  }
+
+  async getMigrationChecks(
+    repoPath: string,
+    filePaths: string[],
+    comparison: Comparison,
+  ): Promise<MigrationCheck[]> {
+    return this.post("/api/migrations/checks", {
+      repoPath,
+      filePaths,
+      comparison,
+    });
+  }
+
   async findSymbolDefinitions(
     repoPath: string,
     symbolName: string,
@@ -724,6 +860,7 @@ export class HttpClient implements ApiClient {
   async consumeCliRequest(): Promise<{
     repoPath: string;
     ref: string | null;
+    baseOverride?: string | null;
     focusedFile: string | null;
     focusedHunkHash: string | null;
   } | null> {
@@ -796,6 +933,12 @@ export class HttpClient implements ApiClient {
     return this.post("/api/misc/vscode-theme");
   }
 
+  // ----- Settings -----
+
+  async getSettingsSchema(): Promise<SettingSchema[]> {
+    return this.post("/api/misc/settings-schema");
+  }
+
   async setWindowBackgroundColor(
     _r: number,
     _g: number,
@@ -25,16 +25,27 @@ import type {
   DetectMovePairsResponse,
   DiffHunk,
   DiffShortStat,
+  ShortStatRequest,
+  ShortStatResult,
+  ContentSide,
+  ContentWindow,
   ExpandedContext,
+  FileContentWindow,
   FileContent,
   FileEntry,
   FileSymbol,
+  FileSchemaDelta,
+  MigrationCheck,
   FileSymbolDiff,
   RepoFileSymbols,
   GitHubPrRef,
   GitStatusSummary,
   PullRequest,
+  PrReviewDraft,
+  PrReviewOptions,
+  ThreadSync,
   RemoteInfo,
+  GitRemote,
   RepoLocalActivity,
   ReviewFreshnessInput,
   ReviewFreshnessResult,
@@ -46,6 +57,7 @@ import type {
   SearchMatch,
   SymbolDefinition,
   LspServerStatus,
+  SettingSchema,
   TrustCategory,
   WorktreeInfo,
 } from "../types";
@@ -82,6 +94,18 @@ export class TauriClient implements ApiClient {
     await invoke<void>("fetch_origin", { repoPath });
   }
 
+  async deepenClone(repoPath: string): Promise<void> {
+    await invoke<void>("deepen_clone", { repoPath });
+  }
+
+  async listRemotes(repoPath: string): Promise<GitRemote[]> {
+    return invoke<GitRemote[]>("list_remotes", { repoPath });
+  }
+
+  async setReviewRemote(repoPath: string, remote: string | null): Promise<void> {
+    await invoke<void>("set_review_remote", { repoPath, remote });
+  }
+
   async getDefaultBranch(repoPath: string): Promise<string> {
     return invoke<string>("get_default_branch", { repoPath });
   }
@@ -118,6 +142,18 @@ export class TauriClient implements ApiClient {
     await invoke("stage_hunks", { repoPath, filePath, contentHashes });
   }
 
+  async stageHunkGroup(
+    repoPath: string,
+    ref: string,
+    groupIndex: number,
+  ): Promise<string[]> {
+    return invoke<string[]>("stage_hunk_group", {
+      repoPath,
+      ref,
+      groupIndex,
+    });
+  }
+
   async unstageHunks(
     repoPath: string,
     filePath: string,
@@ -148,6 +184,12 @@ export class TauriClient implements ApiClient {
     });
   }
 
+  async getDiffShortStats(
+    comparisons: ShortStatRequest[],
+  ): Promise<ShortStatResult[]> {
+    return invoke<ShortStatResult[]>("get_diff_shortstats", { comparisons });
+  }
+
   async listCommits(
     repoPath: string,
     limit?: number,
@@ -191,6 +233,38 @@ export class TauriClient implements ApiClient {
     return invoke<PullRequest[]>("list_pull_requests", { repoPath });
   }
 
+  async draftPrReview(
+    repoPath: string,
+    ref: string,
+    options: PrReviewOptions = {},
+  ): Promise<PrReviewDraft> {
+    return invoke<PrReviewDraft>("draft_pr_review", {
+      repoPath,
+      ref,
+      ...options,
+    });
+  }
+
+  async submitPrReview(
+    repoPath: string,
+    ref: string,
+    options: PrReviewOptions = {},
+  ): Promise<PrReviewDraft> {
+    return invoke<PrReviewDraft>("submit_pr_review", {
+      repoPath,
+      ref,
+      ...options,
+    });
+  }
+
+  async syncPrThreads(
+    repoPath: string,
+    ref: string,
+    pr?: number,
+  ): Promise<ThreadSync> {
+    return invoke<ThreadSync>("sync_pr_threads", { repoPath, ref, pr });
+  }
+
   // ----- Worktree operations -----
 
   async createReviewWorktree(
@@ -314,6 +388,36 @@ export class TauriClient implements ApiClient {
     });
   }
 
+  async getFileContentWindow(
+    repoPath: string,
+    filePath: string,
+    comparison: Comparison,
+    side: ContentSide,
+    window: ContentWindow,
+    githubPr?: GitHubPrRef,
+  ): Promise<FileContentWindow> {
+    return invoke<FileContentWindow>("get_file_content_window", {
+      repoPath,
+      filePath,
+      comparison,
+      side,
+      window,
+      githubPr: githubPr ?? null,
+    });
+  }
+
+  async diffContents(
+    oldText: string,
+    newText: string,
+    pathHint: string,
+  ): Promise<DiffHunk[]> {
+    return invoke<DiffHunk[]>("diff_contents", {
+      oldText,
+      newText,
+      pathHint,
+    });
+  }
+
   async searchFileContents(
     repoPath: string,
     query: string,
@@ -352,8 +456,13 @@ export class TauriClient implements ApiClient {
   async reconcileReviewState(
     state: ReviewState,
     hunks: DiffHunk[],
+    repoPath?: string,
   ): Promise<ReviewLoadResult> {
-    return invoke<ReviewLoadResult>("reconcile_review_state", { state, hunks });
+    return invoke<ReviewLoadResult>("reconcile_review_state", {
+      state,
+      hunks,
+      repoPath,
+    });
   }
 
   async saveReviewState(
@@ -384,6 +493,14 @@ export class TauriClient implements ApiClient {
     await invoke("delete_review", { repoPath, ref });
   }
 
+  async recordReviewActivity(
+    repoPath: string,
+    ref: string,
+    event: "active" | "pause",
+  ): Promise<void> {
+    await invoke("record_review_activity", { repoPath, ref, event });
+  }
+
   async reviewExists(repoPath: string, ref: string): Promise<boolean> {
     return invoke<boolean>("review_exists", { repoPath, ref });
   }
@@ -416,8 +533,14 @@ export class TauriClient implements ApiClient {
 
   // ----- Classification -----
 
-  async classifyHunksStatic(hunks: DiffHunk[]): Promise<ClassifyResponse> {
-    return invoke<ClassifyResponse>("classify_hunks_static", { hunks });
+  async classifyHunksStatic(
+    hunks: DiffHunk[],
+    repoPath?: string,
+  ): Promise<ClassifyResponse> {
+    return invoke<ClassifyResponse>("classify_hunks_static", {
+      hunks,
+      repoPath,
+    });
   }
 
   async detectMovePairs(hunks: DiffHunk[]): Promise<DetectMovePairsResponse> {
@@ -499,6 +622,30 @@ export class TauriClient implements ApiClient {
     });
   }
 
+  async getApiSchemaDeltas(
+    repoPath: string,
+    filePaths: string[],
+    comparison: Comparison,
+  ): Promise<FileSchemaDelta[]> {
+    return invoke<FileSchemaDelta[]>("get_api_schema_deltas", {
+      repoPath,
+      filePaths,
+      comparison,
+    });
+  }
+
+  async getMigrationChecks(
+    repoPath: string,
+    filePaths: string[],
+    comparison: Comparison,
+  ): Promise<MigrationCheck[]> {
+    return invoke<MigrationCheck[]>("get_migration_checks", {
+      repoPath,
+      filePaths,
+      comparison,
+    });
+  }
+
   async getFileSymbols(
     repoPath: string,
     filePath: string,
@@ -594,6 +741,7 @@ export class TauriClient implements ApiClient {
   async consumeCliRequest(): Promise<{
     repoPath: string;
     ref: string | null;
+    baseOverride?: string | null;
     focusedFile: string | null;
     focusedHunkHash: string | null;
   } | null> {
@@ -707,6 +855,12 @@ export class TauriClient implements ApiClient {
     return invoke("detect_vscode_theme");
   }
 
+  // ----- Settings -----
+
+  async getSettingsSchema(): Promise<SettingSchema[]> {
+    return invoke("get_settings_schema");
+  }
+
   async setWindowBackgroundColor(
     r: number,
     g: number,
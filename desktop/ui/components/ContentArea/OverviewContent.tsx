@@ -52,12 +52,18 @@ function PrBodySection(): ReactNode {
   );
 }
 
-/** Heads-up that reconciliation carried decisions forward after the diff drifted. */
+/**
+ * Heads-up that reconciliation carried decisions forward after the diff
+ * drifted, or suggested approving hunks another review already approved
+ * unchanged.
+ */
 function CarryForwardBanner(): ReactNode {
   const carriedForward = useReviewStore((s) => s.carriedForward);
+  const carriedFromReviews = useReviewStore((s) => s.carriedFromReviews);
   const dismiss = useReviewStore((s) => s.dismissCarriedForward);
-  if (carriedForward <= 0) return null;
+  if (carriedForward <= 0 && carriedFromReviews <= 0) return null;
   const noun = carriedForward === 1 ? "decision" : "decisions";
+  const hunkNoun = carriedFromReviews === 1 ? "hunk" : "hunks";
   return (
     <div className="mb-3 flex items-center gap-2 rounded-lg border border-edge-strong/40 bg-surface-raised/50 px-3 py-2 text-sm text-fg-secondary">
       <svg
@@ -75,8 +81,19 @@ function CarryForwardBanner(): ReactNode {
         />
       </svg>
       <span>
-        <span className="font-medium">{carriedForward}</span> review {noun}{" "}
-        carried forward — the diff changed since you last reviewed.
+        {carriedForward > 0 && (
+          <>
+            <span className="font-medium">{carriedForward}</span> review{" "}
+            {noun} carried forward — the diff changed since you last reviewed.{" "}
+          </>
+        )}
+        {carriedFromReviews > 0 && (
+          <>
+            <span className="font-medium">{carriedFromReviews}</span>{" "}
+            {hunkNoun} suggested for approval — identical to changes already
+            approved in another review.
+          </>
+        )}
       </span>
       <button
         type="button"
@@ -60,10 +60,21 @@ function ComparisonLabel({
   // The trunk reviewed against itself: `main..main` is the working tree, which
   // the raw range doesn't convey.
   const isWorkingTree = baseReason === "trunkWorkingTree";
-
-  const canChangeBase = !!repoPath && !!reviewRef && comparison.base !== "";
-
-  const label = isWorkingTree ? (
+  // Staged and unstaged reviews diff the index, so their endpoint tokens
+  // (`HEAD..:index`, `:index..:worktree`) are spelled out instead.
+  const uncommittedLabel =
+    baseReason === "staged"
+      ? "staged changes"
+      : baseReason === "unstaged"
+        ? "unstaged changes"
+        : null;
+
+  const canChangeBase =
+    !!repoPath && !!reviewRef && comparison.base !== "" && !uncommittedLabel;
+
+  const label = uncommittedLabel ? (
+    <span className="font-medium">{uncommittedLabel}</span>
+  ) : isWorkingTree ? (
     <span className="font-medium">
       {comparison.head} <span className="text-fg-faint">·</span> uncommitted
     </span>
@@ -77,9 +88,10 @@ function ComparisonLabel({
     ? `${comparison.head} working tree — change base`
     : "Change base";
 
-  const labelClass = isWorkingTree
-    ? "shrink-0 text-xs text-fg-muted"
-    : "shrink-0 text-xs text-fg-muted font-mono";
+  const labelClass =
+    isWorkingTree || uncommittedLabel
+      ? "shrink-0 text-xs text-fg-muted"
+      : "shrink-0 text-xs text-fg-muted font-mono";
 
   if (!canChangeBase) {
     return <span className={labelClass}>{label}</span>;
@@ -19,6 +19,8 @@ import {
   useFileWatcher,
   useKeyboardNavigation,
   useMouseNavigation,
+  useQuickActions,
+  useReviewSessions,
   useReviewProgress,
   useCelebration,
   useLspClient,
@@ -230,6 +232,8 @@ export function ReviewView({
 
   useKeyboardNavigation();
   useMouseNavigation();
+  useQuickActions();
+  useReviewSessions();
   // Hold deep-link focus until the diff is real again — consuming it against the
   // all-deleted diff behind the notice would drop the requested hunk.
   useDeepLinkFocus(!compareRefMissing);
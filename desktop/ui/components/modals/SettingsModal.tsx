@@ -121,6 +121,8 @@ export function SettingsModal({
   const [devMode, setDevMode] = useState(false);
   const [cliInstalled, setCliInstalled] = useState(false);
   const [cliSymlinkTarget, setCliSymlinkTarget] = useState<string | null>(null);
+  const [cliInstallPath, setCliInstallPath] = useState("/usr/local/bin/review");
+  const [cliOnPath, setCliOnPath] = useState(true);
   const [cliError, setCliError] = useState<string | null>(null);
   const [cliLoading, setCliLoading] = useState(false);
 
@@ -133,9 +135,13 @@ export function SettingsModal({
       const status = await invoke<{
         installed: boolean;
         symlink_target: string | null;
+        install_path: string;
+        on_path: boolean;
       }>("get_cli_install_status");
       setCliInstalled(status.installed);
       setCliSymlinkTarget(status.symlink_target);
+      setCliInstallPath(status.install_path);
+      setCliOnPath(status.on_path);
     } catch {
       // Ignore errors checking status
     }
@@ -480,7 +486,7 @@ export function SettingsModal({
                       <span className="text-xs text-fg-secondary">
                         Installed at{" "}
                         <code className="text-xxs text-fg-muted">
-                          /usr/local/bin/review
+                          {cliInstallPath}
                         </code>
                       </span>
                     </div>
@@ -489,6 +495,12 @@ export function SettingsModal({
                         {cliSymlinkTarget}
                       </p>
                     )}
+                    {!cliOnPath && (
+                      <p className="mt-1 pl-3.5 text-xxs text-fg-faint">
+                        Its directory isn't on your PATH yet — open a new
+                        terminal, or add it to your shell profile.
+                      </p>
+                    )}
                   </div>
                   <button
                     onClick={() => handleCliAction("uninstall_cli")}
@@ -514,9 +526,9 @@ export function SettingsModal({
                     </button>
                   </div>
                   <p className="mt-2 text-xxs text-fg-faint leading-relaxed">
-                    Creates a symlink at{" "}
-                    <code className="text-fg-muted">/usr/local/bin/review</code>{" "}
-                    so you can run <code className="text-fg-muted">review</code>{" "}
+                    Installs it at{" "}
+                    <code className="text-fg-muted">{cliInstallPath}</code> so
+                    you can run <code className="text-fg-muted">review</code>{" "}
                     from any terminal.
                   </p>
                 </>
@@ -11,7 +11,9 @@ export {
 export { useComparisonLoader } from "./useComparisonLoader";
 export { useKeyboardNavigation } from "./useKeyboardNavigation";
 export { useMouseNavigation } from "./useMouseNavigation";
+export { useQuickActions } from "./useQuickActions";
 export { useReviewProgress } from "./useReviewProgress";
+export { useReviewSessions } from "./useReviewSessions";
 export { useHighlighter, getLanguageFromFilename } from "./useHighlighter";
 export { usePrefersReducedMotion } from "./usePrefersReducedMotion";
 export { useCelebration } from "./useCelebration";
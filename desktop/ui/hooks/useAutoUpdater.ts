@@ -1,4 +1,5 @@
 import { useState, useEffect, useCallback, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
 import { isTauriEnvironment } from "../api/client";
 import { getPlatformServices } from "../platform";
 
@@ -33,6 +34,17 @@ export function useAutoUpdater() {
       setState((s) => ({ ...s, checking: true, error: null }));
     }
 
+    if (await invoke<boolean>("is_offline")) {
+      if (!silent) {
+        setState((s) => ({
+          ...s,
+          checking: false,
+          error: "Offline mode is on; update checks are disabled.",
+        }));
+      }
+      return;
+    }
+
     try {
       const { check } = await import("@tauri-apps/plugin-updater");
       const update = await check();
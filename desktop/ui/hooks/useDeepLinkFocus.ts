@@ -8,7 +8,7 @@ import { useFileDiff } from "../stores/selectors/hunks";
  * and applies it as `selectedFile` + `focusedHunkId` + `scrollTarget`.
  *
  * Runs at the review-shell level so it works whether the deep link arrives
- * on cold start (signal file) or warm (cli:open-review event).
+ * on cold start (`consumeCliRequest`) or warm (cli:open-review event).
  *
  * Pass `enabled = false` to hold the pending target instead of consuming it
  * (e.g. while the compared branch is gone and the only loaded diff is the
@@ -0,0 +1,88 @@
+import { useEffect } from "react";
+import { getPlatformServices } from "../platform";
+import { useReviewStore } from "../stores";
+import { getAllHunksFromState } from "../stores/selectors/hunks";
+import { hunkLabels, isHunkReviewed } from "../types";
+
+type ReviewState = ReturnType<typeof useReviewStore.getState>;
+
+/** The focused hunk's ID, if it's still in the diff. */
+function focusedHunkId(state: ReviewState): string | null {
+  const id = state.focusedHunkId;
+  if (!id) return null;
+  return getAllHunksFromState(state).some((h) => h.id === id) ? id : null;
+}
+
+/**
+ * Focus the next unreviewed hunk after the focused one, wrapping around to
+ * the start of the file list. Does nothing when everything is reviewed.
+ */
+function focusNextUnreviewed(state: ReviewState) {
+  const { filesByPath, flatFileList, reviewState, stagedFilePaths } = state;
+  const trustList = reviewState?.trustList ?? [];
+  const autoApproveStaged = reviewState?.autoApproveStaged ?? false;
+  const ordered = flatFileList.flatMap((filePath) =>
+    (filesByPath[filePath]?.hunks ?? []).map((hunk) => ({ filePath, hunk })),
+  );
+  const start = ordered.findIndex(
+    ({ hunk }) => hunk.id === state.focusedHunkId,
+  );
+
+  for (let i = 1; i <= ordered.length; i++) {
+    const { filePath, hunk } = ordered[(start + i) % ordered.length];
+    const reviewed = isHunkReviewed(reviewState?.hunks[hunk.id], trustList, {
+      autoApproveStaged,
+      stagedFilePaths,
+      filePath,
+    });
+    if (!reviewed) {
+      // With a hunk given, navigateToBrowse leaves scrolling to the caller
+      state.navigateToBrowse(filePath, { hunkId: hunk.id });
+      useReviewStore.setState({
+        scrollTarget: { type: "hunk", hunkId: hunk.id },
+      });
+      return;
+    }
+  }
+}
+
+/** Trust the focused hunk's labels, or untrust them if all are trusted. */
+function toggleTrust(state: ReviewState) {
+  const id = focusedHunkId(state);
+  if (!id) return;
+  const labels = hunkLabels(state.reviewState?.hunks[id]);
+  if (labels.length === 0) return;
+  const trustList = state.reviewState?.trustList ?? [];
+  if (labels.every((label) => trustList.includes(label))) {
+    labels.forEach((label) => state.removeTrustPattern(label));
+  } else {
+    labels.forEach((label) => state.addTrustPattern(label));
+  }
+}
+
+/**
+ * Routes the desktop app's global quick-action shortcuts (see
+ * `quickActionShortcuts` in settings.json) to review actions on this
+ * window's review.
+ */
+export function useQuickActions() {
+  useEffect(() => {
+    const { menuEvents } = getPlatformServices();
+    const unlisteners = [
+      menuEvents.on("shortcut:approve-hunk", () => {
+        const state = useReviewStore.getState();
+        const id = focusedHunkId(state);
+        if (!id) return;
+        state.approveHunk(id);
+        focusNextUnreviewed(useReviewStore.getState());
+      }),
+      menuEvents.on("shortcut:next-unreviewed", () => {
+        focusNextUnreviewed(useReviewStore.getState());
+      }),
+      menuEvents.on("shortcut:toggle-trust", () => {
+        toggleTrust(useReviewStore.getState());
+      }),
+    ];
+    return () => unlisteners.forEach((unlisten) => unlisten());
+  }, []);
+}
@@ -323,10 +323,12 @@ export function useRepositoryInit(): UseRepositoryInitReturn {
           }
 
           if (cliRequest.ref) {
-            // review start <spec> — open the resolved review ref
+            // review start <spec> or a review:// link — open the resolved
+            // review ref, with the base a link pins.
             const resolved = await resolveTarget(
               cliRequest.repoPath,
               cliRequest.ref,
+              cliRequest.baseOverride ?? undefined,
             );
             if (cliRequest.focusedFile) {
               useReviewStore.getState().setPendingDeepLinkFocus({
@@ -466,6 +468,7 @@ export function useRepositoryInit(): UseRepositoryInitReturn {
         const data = payload as {
           repoPath?: string;
           ref?: string | null;
+          baseOverride?: string | null;
           focusedFile?: string | null;
           focusedHunkHash?: string | null;
         } | null;
@@ -497,7 +500,11 @@ export function useRepositoryInit(): UseRepositoryInitReturn {
           });
         }
 
-        const resolved = await resolveTarget(repoPath, ref);
+        const resolved = await resolveTarget(
+          repoPath,
+          ref,
+          data?.baseOverride ?? undefined,
+        );
 
         const state = useReviewStore.getState();
         const { routePrefix } = await resolveRepoIdentity(repoPath);
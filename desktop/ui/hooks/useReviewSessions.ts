@@ -0,0 +1,66 @@
+import { useEffect } from "react";
+import { getApiClient } from "../api";
+import { useReviewStore } from "../stores";
+
+/** At most one activity report per this long; well under the backend's
+ *  five-minute idle timeout. */
+const REPORT_INTERVAL_MS = 30_000;
+
+const ACTIVITY_EVENTS = ["keydown", "mousemove", "mousedown", "wheel"] as const;
+
+/**
+ * Reports activity on the active review for session time tracking: input
+ * while the window has focus counts as reviewing, and blurring or hiding the
+ * window pauses. Idle detection (no input for a while) happens in the
+ * backend from the gaps between reports.
+ */
+export function useReviewSessions() {
+  const repoPath = useReviewStore((s) => s.activeReviewKey?.repoPath);
+  const ref = useReviewStore((s) => s.activeReviewKey?.ref);
+
+  useEffect(() => {
+    if (!repoPath || !ref) return;
+    const client = getApiClient();
+    let lastReport = 0;
+
+    const report = (event: "active" | "pause") => {
+      client.recordReviewActivity(repoPath, ref, event).catch((err) => {
+        console.warn("[useReviewSessions] Failed to record activity:", err);
+      });
+    };
+    const onActivity = () => {
+      const now = Date.now();
+      if (!document.hasFocus() || now - lastReport < REPORT_INTERVAL_MS) {
+        return;
+      }
+      lastReport = now;
+      report("active");
+    };
+    const onPause = () => {
+      lastReport = 0;
+      report("pause");
+    };
+    const onVisibilityChange = () => {
+      if (document.visibilityState === "hidden") onPause();
+    };
+
+    if (document.hasFocus()) onActivity();
+    for (const event of ACTIVITY_EVENTS) {
+      window.addEventListener(event, onActivity, { passive: true });
+    }
+    window.addEventListener("focus", onActivity);
+    window.addEventListener("blur", onPause);
+    document.addEventListener("visibilitychange", onVisibilityChange);
+
+    return () => {
+      for (const event of ACTIVITY_EVENTS) {
+        window.removeEventListener(event, onActivity);
+      }
+      window.removeEventListener("focus", onActivity);
+      window.removeEventListener("blur", onPause);
+      document.removeEventListener("visibilitychange", onVisibilityChange);
+      // Switching reviews (or closing this one) ends its session
+      report("pause");
+    };
+  }, [repoPath, ref]);
+}
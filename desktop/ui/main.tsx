@@ -72,7 +72,7 @@ function PreferencesGate({ children }: { children: React.ReactNode }) {
 // Initialize Sentry early (events are dropped until user opts in)
 initSentry();
 
-// Initialize file logging (patches console.*, writes to ~/.review/app.log)
+// Initialize file logging (patches console.*, writes to ~/.review/logs/app.log)
 initializeLogger();
 initLogPath();
 
@@ -42,6 +42,7 @@ import type {
   MenuEventService,
   PlatformServices,
 } from "./types";
+import type { SettingsFile } from "../types";
 
 // ----- Clipboard -----
 
@@ -142,11 +143,10 @@ class JsonFileStorageService implements StorageService {
   private async ensureLoaded(): Promise<void> {
     if (this.loaded) return;
     try {
-      const data = await invoke<Record<string, unknown> | null>(
-        "read_settings",
-      );
-      if (data) {
-        this.cache = data;
+      const { settings, errors } = await invoke<SettingsFile>("read_settings");
+      this.cache = settings;
+      if (errors.unknownKeys.length || errors.invalidValues.length) {
+        console.warn("settings.json has problems:", errors);
       }
     } catch (err) {
       console.error("Failed to load settings:", err);
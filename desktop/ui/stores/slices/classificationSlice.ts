@@ -54,7 +54,10 @@ export const createClassificationSlice: SliceCreatorWithClient<
       get().repoPath !== repoPath || get().comparison?.key !== comparisonKey;
     startActivity("classify-static", "Classifying hunks", 50);
     try {
-      const staticResponse = await client.classifyHunksStatic(hunksToClassify);
+      const staticResponse = await client.classifyHunksStatic(
+        hunksToClassify,
+        repoPath ?? undefined,
+      );
       if (isStale()) return;
       const staticCount = Object.keys(staticResponse.classifications).length;
 
@@ -76,6 +79,7 @@ export const createClassificationSlice: SliceCreatorWithClient<
                 "static",
                 classification.reasoning || undefined,
               ),
+              rawLabels: staticResponse.rawLabels?.[hunkId],
             };
           }
 
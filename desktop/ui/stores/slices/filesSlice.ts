@@ -235,6 +235,7 @@ const diffDataResetState = {
   fileNavIndex: -1,
   // Review
   carriedForward: 0,
+  carriedFromReviews: 0,
   undoStack: [] as UndoEntry[],
   readOnlyPreview: false,
   // Other slices
@@ -2,6 +2,11 @@ import { invoke } from "@tauri-apps/api/core";
 import type { StorageService } from "../../platform";
 import type { SliceCreatorWithStorage } from "../types";
 import type { RecentRepo } from "../../utils/preferences";
+import type {
+  QuickAction,
+  QuickActionBinding,
+  SettingsFile,
+} from "../../types";
 import { setSentryConsent } from "../../utils/sentry";
 import { setSoundEnabled } from "../../utils/sounds";
 import {
@@ -148,6 +153,8 @@ const defaults = {
   matchVscodeTheme: false,
   showOutline: false,
   lspDisabledLanguages: [] as string[],
+  // Global shortcut per quick action, overriding the built-in defaults.
+  quickActionShortcuts: {} as Partial<Record<QuickAction, string>>,
 };
 
 export interface PreferencesSlice {
@@ -220,6 +227,10 @@ export interface PreferencesSlice {
   // LSP disabled languages
   lspDisabledLanguages: string[];
 
+  // Global quick-action shortcuts (approve, next unreviewed, toggle trust).
+  // Missing actions use the default; an empty string disables one.
+  quickActionShortcuts: Partial<Record<QuickAction, string>>;
+
   /** True once loadPreferences() has completed (theme, fonts, etc. are ready) */
   preferencesLoaded: boolean;
 
@@ -292,6 +303,11 @@ export interface PreferencesSlice {
 
   // LSP disabled languages actions
   setLspDisabledLanguages: (languages: string[]) => void;
+
+  // Quick-action shortcut actions
+  setQuickActionShortcuts: (
+    shortcuts: Partial<Record<QuickAction, string>>,
+  ) => Promise<QuickActionBinding[]>;
 }
 
 export const createPreferencesSlice: SliceCreatorWithStorage<
@@ -377,9 +393,7 @@ export const createPreferencesSlice: SliceCreatorWithStorage<
       // Read settings file for custom themes
       let settings: Record<string, unknown> | null = null;
       try {
-        settings = await invoke<Record<string, unknown> | null>(
-          "read_settings",
-        );
+        settings = (await invoke<SettingsFile>("read_settings")).settings;
       } catch {
         // read_settings failed — continue with defaults
       }
@@ -660,5 +674,14 @@ export const createPreferencesSlice: SliceCreatorWithStorage<
       set({ lspDisabledLanguages: languages });
       storage.set("lspDisabledLanguages", languages);
     },
+
+    setQuickActionShortcuts: async (shortcuts) => {
+      set({ quickActionShortcuts: shortcuts });
+      storage.set("quickActionShortcuts", shortcuts);
+      // The app registers them at startup; re-register now for the change.
+      return invoke<QuickActionBinding[]>("apply_quick_action_shortcuts", {
+        overrides: shortcuts,
+      }).catch(() => []);
+    },
   };
 };
@@ -1,7 +1,9 @@
 import type { ApiClient } from "../../api";
 import {
   attributed,
+  type DiffHunk,
   type FileDiff,
+  type FileProgress,
   type GlobalReviewSummary,
   type HunkState,
   type HunkStatusValue,
@@ -69,6 +71,9 @@ export interface ReviewSlice {
   // How many decisions the last load carried forward onto a drifted diff.
   // Transient — surfaced as a banner, cleared on dismiss or next clean load.
   carriedForward: number;
+  // How many hunks the last load approved because another review approved the
+  // identical change (a rebase or cherry-pick). Transient, like carriedForward.
+  carriedFromReviews: number;
 
   // Actions
   setReviewState: (state: ReviewState) => void;
@@ -76,8 +81,9 @@ export interface ReviewSlice {
 
   // Persistence
   loadReviewState: () => Promise<void>;
-  // Carry persisted decisions forward onto the loaded diff (call after the
-  // hunks are loaded); updates carriedForward for the banner.
+  // Carry persisted decisions forward onto the loaded diff, and approve hunks
+  // other reviews approved unchanged (call after the hunks are loaded);
+  // updates carriedForward and carriedFromReviews for the banner.
   reconcileReviewState: () => Promise<void>;
   saveReviewState: () => Promise<void>;
   loadSavedReviews: () => Promise<void>;
@@ -201,6 +207,45 @@ function patchGlobalReviewProgress(
   set({ globalReviews: patched });
 }
 
+const EMPTY_FILE_PROGRESS: FileProgress = {
+  total: 0,
+  approved: 0,
+  rejected: 0,
+  trusted: 0,
+  savedForLater: 0,
+  unclassified: 0,
+};
+
+/**
+ * The review state with its diff totals (overall and per file) taken from the
+ * loaded hunks, or the same object when they already match. Per-file counts
+ * are recomputed by the backend on save.
+ */
+function withDiffTotals(
+  reviewState: ReviewState,
+  hunks: DiffHunk[],
+): ReviewState {
+  const totals: Record<string, number> = {};
+  for (const hunk of hunks) {
+    totals[hunk.filePath] = (totals[hunk.filePath] ?? 0) + 1;
+  }
+  const current = reviewState.fileProgress ?? {};
+  const unchanged =
+    reviewState.totalDiffHunks === hunks.length &&
+    Object.entries(totals).every(
+      ([path, total]) => current[path]?.total === total,
+    );
+  if (unchanged) return reviewState;
+
+  const fileProgress = Object.fromEntries(
+    Object.entries(totals).map(([path, total]) => [
+      path,
+      { ...(current[path] ?? EMPTY_FILE_PROGRESS), total },
+    ]),
+  );
+  return { ...reviewState, totalDiffHunks: hunks.length, fileProgress };
+}
+
 interface HunkStatusGetter {
   reviewState: ReviewState | null;
   readOnlyPreview: boolean;
@@ -365,9 +410,11 @@ export const createReviewSlice: SliceCreatorWithClient<ReviewSlice> =
     savedReviews: [],
     savedReviewsLoading: false,
     carriedForward: 0,
+    carriedFromReviews: 0,
 
     setReviewState: (state) => set({ reviewState: state }),
-    dismissCarriedForward: () => set({ carriedForward: 0 }),
+    dismissCarriedForward: () =>
+      set({ carriedForward: 0, carriedFromReviews: 0 }),
 
     loadReviewState: async () => {
       const { repoPath, comparison, reviewRef, reviewBaseOverride } = get();
@@ -395,6 +442,7 @@ export const createReviewSlice: SliceCreatorWithClient<ReviewSlice> =
         console.error("Failed to load review state:", err);
         set({
           carriedForward: 0,
+          carriedFromReviews: 0,
           reviewState: {
             ref: reviewRef,
             baseOverride: reviewBaseOverride ?? undefined,
@@ -414,22 +462,25 @@ export const createReviewSlice: SliceCreatorWithClient<ReviewSlice> =
     reconcileReviewState: async () => {
       const { repoPath, comparison, reviewState } = get();
       if (!repoPath || !comparison || !reviewState) return;
-      // Nothing to carry forward without recorded decisions or a loaded diff.
-      if (Object.keys(reviewState.hunks).length === 0) return;
+      // Nothing to carry forward or match without a loaded diff. (A review
+      // with no decisions yet can still pick up approvals from other reviews.)
       const hunks = getAllHunksFromState(get());
       if (hunks.length === 0) return;
 
       const comparisonKey = comparison.key;
       try {
-        const { state, carriedForward } = await client.reconcileReviewState(
-          reviewState,
-          hunks,
-        );
+        const {
+          state,
+          carriedForward,
+          carriedFromReviews = 0,
+        } = await client.reconcileReviewState(reviewState, hunks, repoPath);
         // Discard if the comparison changed, or the user touched the review
         // while reconciliation was in flight (avoid clobbering newer edits).
         if (get().comparison?.key !== comparisonKey) return;
         if (get().reviewState?.updatedAt !== reviewState.updatedAt) return;
-        set({ reviewState: state, carriedForward });
+        set({ reviewState: state, carriedForward, carriedFromReviews });
+        // Approvals picked up from other reviews are new decisions: keep them.
+        if (carriedFromReviews > 0) await get().saveReviewState();
       } catch (err) {
         console.error("Failed to reconcile review state:", err);
       }
@@ -491,10 +542,13 @@ export const createReviewSlice: SliceCreatorWithClient<ReviewSlice> =
       // pristine reviews that returned above).
       const hunks = getAllHunksFromState(get());
 
-      // Ensure totalDiffHunks is set from the actual diff hunk count
-      if (hunks.length > 0 && reviewState.totalDiffHunks !== hunks.length) {
-        reviewState = { ...reviewState, totalDiffHunks: hunks.length };
-        set({ reviewState });
+      // Ensure the diff totals (overall and per file) match the actual hunks
+      if (hunks.length > 0) {
+        const synced = withDiffTotals(reviewState, hunks);
+        if (synced !== reviewState) {
+          reviewState = synced;
+          set({ reviewState });
+        }
       }
 
       const saveAndUpdateVersion = async (
@@ -791,22 +845,17 @@ export const createReviewSlice: SliceCreatorWithClient<ReviewSlice> =
     syncTotalDiffHunks: () => {
       const { reviewState, saveReviewState } = get();
       const hunks = getAllHunksFromState(get());
-      if (
-        !reviewState ||
-        hunks.length === 0 ||
-        reviewState.totalDiffHunks === hunks.length
-      ) {
-        return;
-      }
+      if (!reviewState || hunks.length === 0) return;
 
-      const updated = { ...reviewState, totalDiffHunks: hunks.length };
+      const updated = withDiffTotals(reviewState, hunks);
+      if (updated === reviewState) return;
       set({ reviewState: updated });
 
       // Immediately patch globalReviews so the sidebar shows correct progress
       // (otherwise it stays inflated until the next saveReviewState call)
       patchGlobalReviewProgress(get, set, updated);
 
-      // Persist the corrected totalDiffHunks to disk so it survives app restarts
+      // Persist the corrected totals to disk so they survive app restarts
       debouncedSave(saveReviewState);
     },
 
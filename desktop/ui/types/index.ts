@@ -172,14 +172,37 @@ export interface PullRequest {
   body: string;
 }
 
+// A review to post to a GitHub PR. Mirrors core's `service::pr_review::PrReviewDraft`.
+export type PrReviewEvent = "APPROVE" | "REQUEST_CHANGES" | "COMMENT";
+
+export interface PrReviewDraft {
+  number: number | null; // the PR, when the review knows it
+  event: PrReviewEvent;
+  body: string; // the message, then the summary of how hunks were reviewed
+}
+
+// What importing a PR's review threads did. Mirrors core's `review::storage::ThreadSync`.
+export interface ThreadSync {
+  added: number;
+  updated: number;
+  removed: number;
+  unanchored: number; // outdated, or on lines outside the diff
+}
+
+export interface PrReviewOptions {
+  pr?: number; // for a review that wasn't started from a PR
+  event?: PrReviewEvent; // defaults to what the review's state suggests
+  message?: string;
+}
+
 // Comparison - the resolved base..head pair the data endpoints diff. This is
 // *plumbing*, not identity: a review is identified by its `ref` (see
 // ResolvedReview / ReviewState), and the base is derived at read time. The
 // frontend obtains a Comparison from `resolveReview` and passes it to the data
 // endpoints (list_files, get_all_hunks, get_diff*, symbols, freshness, ...).
 export interface Comparison {
-  base: string; // Base ref (e.g., "main")
-  head: string; // Head ref (e.g., "feature")
+  base: string; // Base ref (e.g., "main"), or ":index"
+  head: string; // Head ref (e.g., "feature"), ":index", or ":worktree"
   key: string; // Always "{base}..{head}"
 }
 
@@ -190,7 +213,9 @@ export type BaseReason =
   | "override" // an explicit base override is pinned
   | "trunkWorkingTree" // the default branch vs itself (its uncommitted work)
   | "branchVsDefault" // a non-default branch vs the default branch
-  | "singleCommit"; // any other rev reviewed as one commit
+  | "singleCommit" // any other rev reviewed as one commit
+  | "staged" // the index vs HEAD (`HEAD..:index`)
+  | "unstaged"; // the working tree vs the index (`:index..:worktree`)
 
 // A resolved review: its identity (`ref` + optional `baseOverride`) alongside
 // the concrete Comparison the data endpoints diff. Returned by the identity
@@ -252,6 +277,10 @@ export interface FileEntry {
   // Symlink info
   isSymlink?: boolean;
   symlinkTarget?: string;
+  // Git LFS pointer whose object wasn't pulled (`size` is the object's)
+  isLfsPointer?: boolean;
+  // Outside the sparse-checkout cone: tracked, but not on disk
+  notMaterialized?: boolean;
   // Rename info (old path before rename)
   renamedFrom?: string;
   // File size in bytes (only for files, from local git)
@@ -275,6 +304,25 @@ export interface DiffHunk {
   contentHash: string;
   // ID of the paired hunk if this is part of a move
   movePairId?: string;
+  // IDs of the hunks git would have shown separately, if this merges several
+  mergedIds?: string[];
+  // Set when the hunk is a submodule's pointer moving rather than file lines
+  submodule?: SubmoduleHunk;
+  // Set when the file's diff wasn't UTF-8 and was decoded from this encoding
+  encoding?: TextEncoding;
+}
+
+// The encoding non-UTF-8 text was decoded from
+export interface TextEncoding {
+  name: string; // WHATWG name, e.g. "Shift_JIS" or "windows-1252"
+  lossy?: boolean; // some bytes didn't fit and were replaced with U+FFFD
+}
+
+// A submodule pointer change: the commits recorded before and after
+export interface SubmoduleHunk {
+  oldSha?: string; // absent when the submodule was added
+  newSha?: string; // absent when the submodule was removed
+  dirty?: boolean; // the checkout has uncommitted changes
 }
 
 /**
@@ -291,6 +339,17 @@ export interface MovePair {
   destHunkId: string;
   sourceFilePath: string;
   destFilePath: string;
+  // What changed besides the move; absent for a pure move
+  residual?: MoveEdit[];
+}
+
+// A differing line of a moved block: indices into each hunk's lines, and
+// [start, end) ranges in UTF-16 code units
+export interface MoveEdit {
+  sourceLine?: number;
+  destLine?: number;
+  sourceRanges: [number, number][];
+  destRanges: [number, number][];
 }
 
 /**
@@ -332,7 +391,8 @@ export type Source =
   | "cli" // a human via the review CLI
   | "agent" // an external agent (Claude/Codex) through the CLI
   | "github"
-  | "gitlab";
+  | "gitlab"
+  | "carried"; // suggested from an identical change another review approved
 
 // A value paired with its provenance and an optional rationale. Each axis of a
 // HunkState — classification, status — is an Attributed<T>.
@@ -340,6 +400,9 @@ export interface Attributed<T> {
   value: T;
   source: Source;
   reasoning?: string;
+  // When the value was set (ISO 8601); stamped by the backend on save. Empty
+  // when it was stored before times were recorded.
+  at?: string;
 }
 
 export type HunkStatusValue = "approved" | "rejected" | "saved_for_later";
@@ -350,6 +413,12 @@ export type HunkStatusValue = "approved" | "rejected" | "saved_for_later";
 export interface HunkState {
   classification?: Attributed<string[]>;
   status?: Attributed<HunkStatusValue>;
+  /** In a two-person review, the first reviewer's decision, moved here at hand-off. */
+  recommendation?: Attributed<HunkStatusValue>;
+  /** Labels as classified, when precedence rules dropped some of them. */
+  rawLabels?: string[];
+  /** Taxonomy version current when the labels were set. */
+  taxonomyVersion?: string;
 }
 
 // Construct an attributed value, omitting reasoning when not provided.
@@ -371,6 +440,19 @@ export function isHunkUnclassified(hunkState: HunkState | undefined): boolean {
   return !hunkState?.classification;
 }
 
+// Label the static classifier adds to hunks under the repo's sensitive paths
+// (`.review/sensitive-paths`).
+export const SENSITIVE_AREA_LABEL = "security:sensitive-area";
+
+// Labels that always need a human: a hunk carrying one is never trusted,
+// whatever else it's labeled. Mirrors the Rust NEVER_TRUSTED_LABELS.
+export const NEVER_TRUSTED_LABELS = [
+  SENSITIVE_AREA_LABEL,
+  "tests:removed",
+  "legal:license-change",
+  "security:semgrep-finding",
+];
+
 // Whether a hunk is auto-approved by the trust list — i.e. its label is
 // trust-listed. (An explicit approve/reject still wins — callers check
 // `status` before this.) This is the single chokepoint every "is it
@@ -381,6 +463,9 @@ export function isHunkTrusted(
 ): boolean {
   const labels = hunkState?.classification?.value;
   if (!labels || labels.length === 0) return false;
+  if (labels.some((label) => NEVER_TRUSTED_LABELS.includes(label))) {
+    return false;
+  }
   return anyLabelMatchesAnyPattern(labels, trustList);
 }
 
@@ -463,10 +548,49 @@ export interface RejectionFeedback {
 export interface ClassificationResult {
   label: string[];
   reasoning: string;
+  /** What each rule behind the labels matched. */
+  evidence?: Evidence[];
+}
+
+/** Why a rule labeled a hunk. */
+export interface Evidence {
+  /** The rule that matched, e.g. "whitespace" or "sensitive-path". */
+  rule: string;
+  /** The hunk lines the rule matched; absent for path-only rules. */
+  lines?: EvidenceLine[];
+  /** What matched outside the hunk's lines, e.g. a sensitive path glob. */
+  matched?: string;
+}
+
+export interface EvidenceLine {
+  oldLineNumber?: number;
+  newLineNumber?: number;
+  text: string;
+}
+
+export type LimitKind = "hunks" | "diffBytes" | "fileBytes";
+
+export type Degradation = "truncated" | "sampled" | "skipped";
+
+/** A review budget that was exceeded, and how the result was degraded. */
+export interface LimitExceeded {
+  kind: LimitKind;
+  limit: number;
+  actual: number;
+  degradation: Degradation;
+  files?: string[];
+}
+
+export interface LimitedHunks {
+  hunks: DiffHunk[];
+  limitsExceeded?: LimitExceeded[];
 }
 
 export interface ClassifyResponse {
   classifications: Record<string, ClassificationResult>;
+  limitsExceeded?: LimitExceeded[];
+  /** Labels as classified, for hunks whose labels precedence rules changed. */
+  rawLabels?: Record<string, string[]>;
 }
 
 export interface HunkGroup {
@@ -507,13 +631,141 @@ export interface ReviewState {
   totalDiffHunks: number; // Total diff hunks (including unclassified) for accurate progress
   githubPr?: GitHubPrRef; // Optional GitHub PR reference
   worktreePath?: string; // Path to review-managed worktree, if created
+  archivedAt?: string; // Set when the review is archived
+  checklist?: ChecklistItem[]; // Seeded from the repo's .review/checklist.md
+  fileProgress?: Record<string, FileProgress>; // Per-file rollups, recomputed on every save
+  splitFrom?: SplitLink; // Set on a review created by splitting another's commit range
+  splitInto?: string[]; // Refs of the reviews this one was split into, in commit order
+  findings?: Finding[]; // Static analyzer findings ingested with `review ingest`
+  buildSize?: BuildSizeReport; // Artifact sizes from the last `review size` run
+  bench?: BenchReport; // Benchmark timings from the last `review bench` run
+  twoPerson?: TwoPersonReview; // Set when the review needs a second reviewer's sign-off
+  attestation?: Attestation; // Signed record of the reviewed diff and decisions (`review attest`)
+}
+
+// A reviewer's signature over a completed review.
+export interface Attestation {
+  review: {
+    ref: string;
+    comparison: string;
+    base: string;
+    head: string;
+    diffSha256: string;
+    decisions: Record<string, "approved" | "rejected" | "trusted">;
+    reviewer: string;
+  };
+  digest: string;
+  format: "ssh" | "gpg";
+  key: string;
+  signature: string;
+  signedAt: string;
+}
+
+// Who signed a two-person review's hand-off or sign-off, and when.
+export interface SignOff {
+  by: string;
+  at: string;
+}
+
+export interface TwoPersonReview {
+  handoff?: SignOff;
+  signoff?: SignOff;
+}
+
+// How far a two-person review has got.
+export interface TwoPersonProgress extends TwoPersonReview {
+  recommended: number;
+  confirmed: number;
+  overridden: number;
+  pending: number;
+}
+
+// Build artifact sizes at a review's base and head commits.
+export interface BuildSizeReport {
+  command: string;
+  base: string;
+  head: string;
+  artifacts: ArtifactSize[];
+  measuredAt: string;
+}
+
+// One artifact's size on each side; null when that side didn't produce it.
+export interface ArtifactSize {
+  path: string;
+  baseBytes: number | null;
+  headBytes: number | null;
+}
+
+// Benchmark timings at a review's base and head commits.
+export interface BenchReport {
+  command: string;
+  base: string;
+  head: string;
+  thresholdPct: number; // Percent slowdown above which a benchmark regressed
+  benchmarks: BenchDelta[];
+  measuredAt: string;
+}
+
+// One benchmark's ns/iter on each side; null when it didn't run on that side.
+export interface BenchDelta {
+  name: string;
+  baseNs: number | null;
+  headNs: number | null;
+  changePct: number | null; // Positive is slower
+  regression: boolean; // Flagged performance:regression
+}
+
+// A static analyzer's finding on a changed line of a hunk.
+export interface Finding {
+  id: string; // Fingerprint; stable across runs that report the same finding
+  tool: string; // e.g. "clippy", or a SARIF driver name
+  format: "sarif" | "clippy" | "eslint" | "semgrep";
+  rule?: string;
+  severity: "info" | "warning" | "error";
+  message: string;
+  filePath: string;
+  line: number; // New side
+  endLine?: number;
+  hunkId: string;
+  ingestedAt: string;
+}
+
+// A split review's link back to the review it was split from.
+export interface SplitLink {
+  parent: string;
+  part: number; // 1-based, in commit order
+  parts: number;
+  title: string;
+}
+
+// Review progress for one file; `total` comes from the diff, the counts are
+// recomputed by the backend on every save.
+export interface FileProgress {
+  total: number;
+  approved: number;
+  rejected: number;
+  trusted: number;
+  savedForLater: number;
+  unclassified: number;
+}
+
+// One checklist item and its check state on a review.
+export interface ChecklistItem {
+  id: string;
+  text: string;
+  checked: boolean;
+  checkedAt?: string;
+  checkedBy?: Source;
 }
 
 // Result of loading a review: the state plus how many decisions reconciliation
-// carried forward onto the current diff (for surfacing "N carried forward").
+// carried forward onto the current diff (for surfacing "N carried forward"),
+// and how many hunks were approved because another review approved the
+// identical change.
 export interface ReviewLoadResult {
   state: ReviewState;
   carriedForward: number;
+  carriedFromReviews?: number;
 }
 
 // Summary of a saved review tagged with repo info (for cross-repo listing)
@@ -539,6 +791,18 @@ export interface ReviewSummary {
   updatedAt: string;
   githubPr?: GitHubPrRef; // Optional GitHub PR reference
   worktreePath?: string; // Path to review-managed worktree, if created
+  archivedAt?: string; // Set when the review is archived
+  /** Hunks a classifier labeled under an older taxonomy version. */
+  staleTaxonomyHunks?: number;
+  /** Bytes the build artifacts grew by (negative when they shrank). */
+  buildSizeDelta?: number;
+  /** Benchmarks flagged `performance:regression` by the last `review bench`. */
+  benchRegressions?: number;
+}
+
+// A review's progress as of its last save, from `get_review_progress`.
+export interface ReviewProgress extends ReviewSummary {
+  files: Record<string, FileProgress>;
 }
 
 // Information about a git worktree
@@ -557,6 +821,8 @@ export interface TrustPattern {
   category: string; // e.g., "imports"
   name: string; // e.g., "added"
   description: string;
+  /** Not trusted by new reviews until the user opts in. */
+  optIn?: boolean;
 }
 
 export interface TrustCategory {
@@ -632,6 +898,51 @@ export interface FileSymbolDiff {
   topLevelHunkIds: string[];
   hasGrammar: boolean;
   symbolReferences: SymbolReference[];
+  /** True when the file was too large to symbol-diff. */
+  limitExceeded?: boolean;
+}
+
+export type ApiSchemaKind = "openapi" | "protobuf";
+
+export interface SchemaChange {
+  /** e.g. `GET /users/{id}`, `property User.email`, `field User.email`, `rpc Users.Get` */
+  element: string;
+  changeType: SymbolChangeType;
+  /** True when existing clients break. */
+  breaking: boolean;
+  /** What changed about a modified element, e.g. `renumbered 3 → 4`. */
+  detail?: string;
+  hunkIds: string[];
+}
+
+export interface FileSchemaDelta {
+  filePath: string;
+  kind: ApiSchemaKind;
+  changes: SchemaChange[];
+}
+
+export type MigrationFormat =
+  | "sqlUp"
+  | "sqlDown"
+  | "sql"
+  | "alembic"
+  | "rails"
+  | "django";
+
+export type MigrationIssueKind = "missingDown" | "destructiveOperation";
+
+export interface MigrationIssue {
+  kind: MigrationIssueKind;
+  detail: string;
+  /** 1-based line in the new file, for destructive operations. */
+  line?: number;
+  hunkIds: string[];
+}
+
+export interface MigrationCheck {
+  filePath: string;
+  format: MigrationFormat;
+  issues: MigrationIssue[];
 }
 
 // Dependency graph types
@@ -681,6 +992,14 @@ export interface RemoteInfo {
   browseUrl: string;
 }
 
+export interface GitRemote {
+  name: string;
+  /** Fetch URL. */
+  url: string;
+  /** The remote the base branch and PR host are resolved from. */
+  isReviewRemote: boolean;
+}
+
 // Review freshness checking
 export interface ReviewFreshnessInput {
   repoPath: string;
@@ -710,6 +1029,20 @@ export interface DiffShortStat {
   deletions: number;
 }
 
+// One comparison for the batched shortstat call
+export interface ShortStatRequest {
+  repoPath: string;
+  comparison: Comparison;
+}
+
+// A comparison's shortstat (cached by base/head SHA), or why it failed
+export interface ShortStatResult {
+  repoPath: string;
+  key: string;
+  stats?: DiffShortStat;
+  error?: string;
+}
+
 // Commit streaming types
 export interface CommitOutputLine {
   text: string;
@@ -734,6 +1067,37 @@ export interface FileContent {
   contentType: ContentType;
   imageDataUrl?: string;
   oldImageDataUrl?: string;
+  /** Set when the file is over the per-file budget: content and oldContent
+   * are empty, to be read with getFileContentWindow. */
+  deferred?: DeferredContent;
+  /** Set when a version of the file wasn't UTF-8: the encoding its content
+   * was decoded from. */
+  encoding?: TextEncoding;
+  /** The file is outside the sparse-checkout cone: not on disk, so content
+   * was read from the index. */
+  notMaterialized?: boolean;
+}
+
+export interface DeferredContent {
+  size: number;
+  oldSize: number | null;
+  limit: number;
+}
+
+export type ContentSide = "old" | "new";
+
+/** `count` lines from 0-based line `start`, or `length` bytes from `offset`. */
+export type ContentWindow =
+  | { unit: "lines"; start: number; count: number }
+  | { unit: "bytes"; offset: number; length: number };
+
+/** `start`..`end` and `total` are in the window's unit. */
+export interface FileContentWindow {
+  content: string;
+  start: number;
+  end: number;
+  total: number;
+  size: number;
 }
 
 // Local activity types
@@ -781,3 +1145,39 @@ export interface LspServerStatus {
   language: string;
   state: LspServerState;
 }
+
+// --- Settings types ---
+
+/** How the preferences UI should render one `settings.json` key. */
+export interface SettingSchema {
+  key: string;
+  kind: "boolean" | "number" | "string" | "enum" | "array" | "object";
+  description: string;
+  default: unknown;
+  /** Allowed values of an `enum` setting. */
+  options?: string[];
+  min?: number;
+  max?: number;
+}
+
+/** Review actions bound to global shortcuts; see `quickActionShortcuts`. */
+export type QuickAction = "approveHunk" | "nextUnreviewed" | "toggleTrust";
+
+/** A quick action's shortcut, and whether the OS accepted it. */
+export interface QuickActionBinding {
+  action: QuickAction;
+  /** Empty when the action has no shortcut. */
+  shortcut: string;
+  registered: boolean;
+}
+
+export interface SettingsErrors {
+  unknownKeys: string[];
+  invalidValues: { key: string; message: string }[];
+}
+
+/** `settings.json` with defaults filled in, plus what was wrong with it. */
+export interface SettingsFile {
+  settings: Record<string, unknown>;
+  errors: SettingsErrors;
+}
@@ -11,7 +11,7 @@ export function initLogPath(): void {
     .getReviewRoot()
     .then((root) => {
       if (!root) return;
-      logFilePath = `${root}/app.log`;
+      logFilePath = `${root}/logs/app.log`;
     })
     .catch(() => {
       // Silently fall back -- no log file
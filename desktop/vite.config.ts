@@ -24,7 +24,7 @@ export default defineConfig({
     strictPort: true,
     proxy: {
       "/api": {
-        target: "http://127.0.0.1:3421",
+        target: `http://127.0.0.1:${process.env.REVIEW_PORT ?? "3421"}`,
       },
     },
     watch: {